@@ -0,0 +1,162 @@
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::retry;
+use aws_config::sts::AssumeRoleProvider;
+use aws_config::SdkConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::Future;
+
+/// An AWS account that a command should be executed against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountTarget {
+    pub account_id: String,
+    pub name: String,
+    pub role_arn: String,
+}
+
+/// Resolve the accounts a command should fan out to. Returns `None` when the
+/// command should only run against the base credentials.
+pub async fn resolve_targets(
+    base: &SdkConfig,
+    org: bool,
+    org_role: &str,
+    accounts_file: &Option<String>,
+) -> Result<Option<Vec<AccountTarget>>, AppError> {
+    if let Some(path) = accounts_file {
+        return load_account_targets(path).map(Some);
+    }
+
+    if org {
+        return discover_org_accounts(base, org_role).await.map(Some);
+    }
+
+    Ok(None)
+}
+
+/// Load explicit targets from a YAML file mapping account IDs to role ARNs,
+/// for running across accounts without Organizations access.
+pub fn load_account_targets(path: &str) -> Result<Vec<AccountTarget>, AppError> {
+    let content = fs::read_to_string(path)?;
+    let accounts: BTreeMap<String, String> = serde_yaml::from_str(&content).map_err(|e| {
+        AppError::ConfigurationError(format!("Invalid accounts file {}: {}", path, e))
+    })?;
+
+    if accounts.is_empty() {
+        return Err(AppError::ConfigurationError(format!(
+            "Accounts file {} does not list any accounts",
+            path
+        )));
+    }
+
+    let mut targets = Vec::new();
+    for (account_id, role_arn) in accounts {
+        if !role_arn.starts_with("arn:") {
+            return Err(AppError::ConfigurationError(format!(
+                "Account {} has an invalid role ARN: {}",
+                account_id, role_arn
+            )));
+        }
+
+        targets.push(AccountTarget {
+            name: account_id.clone(),
+            account_id,
+            role_arn,
+        });
+    }
+
+    eprintln!("📄 Loaded {} accounts from {}", targets.len(), path);
+    Ok(targets)
+}
+
+/// Enumerate the active member accounts of the AWS Organization the base
+/// credentials belong to, targeting `role_name` in each of them.
+pub async fn discover_org_accounts(
+    base: &SdkConfig,
+    role_name: &str,
+) -> Result<Vec<AccountTarget>, AppError> {
+    tracing::info!("🏢 Discovering AWS Organization member accounts...");
+
+    let client = clients::get::<aws_sdk_organizations::Client>(base).await;
+    let mut pages = client.list_accounts().into_paginator().send();
+
+    let mut targets = Vec::new();
+    loop {
+        executor::throttle("organizations").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("organizations:ListAccounts", e))?;
+
+        for account in page.accounts() {
+            if account.status() != Some(&aws_sdk_organizations::types::AccountStatus::Active) {
+                continue;
+            }
+
+            if let Some(account_id) = account.id() {
+                targets.push(AccountTarget {
+                    account_id: account_id.to_string(),
+                    name: account.name().unwrap_or(account_id).to_string(),
+                    role_arn: format!("arn:aws:iam::{}:role/{}", account_id, role_name),
+                });
+            }
+        }
+    }
+
+    eprintln!("   Found {} active accounts", targets.len());
+    Ok(targets)
+}
+
+/// Build an SDK config whose credentials come from assuming the target's role.
+pub async fn assume_role_config(base: &SdkConfig, target: &AccountTarget) -> SdkConfig {
+    let provider = AssumeRoleProvider::builder(&target.role_arn)
+        .session_name("actlog")
+        .configure(base)
+        .build()
+        .await;
+
+    let mut loader = retry::aws_loader().credentials_provider(provider);
+    if let Some(region) = base.region() {
+        loader = loader.region(region.clone());
+    }
+
+    loader.load().await
+}
+
+/// Run `op` against every target account with bounded parallelism. Accounts
+/// that fail are reported and skipped so one broken role doesn't abort the
+/// whole run.
+pub async fn fan_out<T, F, Fut>(
+    base: &SdkConfig,
+    targets: &[AccountTarget],
+    op: F,
+) -> Vec<(AccountTarget, T)>
+where
+    T: Send + 'static,
+    F: Fn(SdkConfig) -> Fut,
+    Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+{
+    tracing::info!("🔀 Running across {} accounts...", targets.len());
+
+    let mut jobs = Vec::new();
+    for target in targets {
+        let config = assume_role_config(base, target).await;
+        jobs.push((target.account_id.clone(), op(config)));
+    }
+
+    let mut results: Vec<(AccountTarget, T)> = executor::run_bounded(jobs, "account")
+        .await
+        .into_iter()
+        .filter_map(|(account_id, value)| {
+            targets
+                .iter()
+                .find(|t| t.account_id == account_id)
+                .map(|t| (t.clone(), value))
+        })
+        .collect();
+
+    results.sort_by(|a, b| a.0.account_id.cmp(&b.0.account_id));
+    results
+}
@@ -1,33 +1,304 @@
+use crate::audit;
+use crate::cache;
 use crate::cli::{Cli, Commands};
+use crate::clients;
 use crate::commands::{
-    authenticate, cleanup_resources, configure, list_resources, report_costs, scale_instances,
+    alerts, archive_snapshots, authenticate, check_quotas, cleanup_resources, configure, dashboard,
+    dns_check, doctor, export_data, export_graph, idle_report, list_regions, list_resources, lock,
+    metrics, optimize_ebs, overview, policy, query_resources, recommendations, reconcile,
+    report_carbon, report_costs, s3, scale_instances, schedule, self_update, snapshot,
+    spot_advisor, storage_analysis, tags, usage_report, who_created,
 };
 use crate::error::AppError;
+use crate::executor;
+use crate::guard;
+use crate::interrupt;
+use crate::inventory::{self, CacheMode};
+use crate::output;
+use crate::retry;
+use crate::session;
+use crate::settings;
+use crate::timeouts;
+use colored::*;
+use tracing_subscriber::EnvFilter;
 
 pub async fn run(cli: Cli) -> Result<(), AppError> {
-    match &cli.command {
+    // A replay runs the recorded command; only the log level carries over
+    let mut cli = match &cli.command {
+        Commands::Replay { file } => {
+            let mut replayed = session::replay(file)?;
+            replayed.verbose = replayed.verbose.max(cli.verbose);
+            replayed.quiet |= cli.quiet;
+            replayed
+        }
+        _ => cli,
+    };
+
+    // Progress lines would scribble over the dashboard's screen
+    let full_screen = matches!(cli.command, Commands::Dashboard { .. });
+    init_logging(cli.verbose, cli.quiet || full_screen);
+
+    let settings = settings::load_settings()?;
+    executor::configure(
+        cli.parallelism.or(settings.executor.parallelism),
+        &settings.executor.rate_limits,
+    );
+    executor::set_call_budget(cli.max_api_calls);
+    retry::configure(cli.max_attempts, &settings.retry);
+
+    // Every request of a recorded or replayed run has to go over the wire
+    let in_session = cli.record.is_some() || session::replaying();
+    inventory::set_cache_mode(if cli.no_cache || in_session {
+        CacheMode::Disabled
+    } else if cli.refresh {
+        CacheMode::Refresh
+    } else {
+        CacheMode::Use
+    });
+    cache::configure(cli.cache_responses && !in_session, &settings.cache);
+    timeouts::configure(cli.timeout, &settings.timeouts);
+    guard::configure(cli.read_only, cli.command.profile(), &settings.profiles);
+    clients::configure(cli.command.profile())?;
+    if let Some(path) = &cli.record {
+        session::record(path).await;
+    }
+
+    if let Some(path) = &cli.output {
+        output::configure(
+            path,
+            cli.overwrite || cli.command.output_force(),
+            cli.format_given,
+            cli.command.format_mut(),
+        )?;
+    }
+
+    interrupt::install();
+
+    let command = async {
+        tokio::select! {
+            result = dispatch(&cli.command) => result,
+            _ = executor::call_budget_exceeded() => {
+                Err(AppError::CallBudgetExceeded(cli.max_api_calls.unwrap_or_default()))
+            }
+        }
+    };
+    let result = match timeouts::command_timeout() {
+        Some(limit) => tokio::time::timeout(limit, command)
+            .await
+            .map_err(|_| AppError::Timeout(timeouts::stalled_message(limit)))
+            .and_then(|result| result),
+        None => command.await,
+    };
+    // Only a complete run replaces the --output file, though incomplete
+    // results are still worth keeping
+    let result = match result {
+        Ok(()) => output::finish(),
+        Err(AppError::PartialResults(message)) => {
+            output::finish().and(Err(AppError::PartialResults(message)))
+        }
+        Err(e) => Err(e),
+    };
+
+    if cli.verbose > 0 {
+        print_api_calls();
+    }
+
+    audit::flush();
+    // A failed run is often the one worth sending to support
+    if let Err(e) = session::finish() {
+        eprintln!(
+            "   ⚠️  Could not write the session: {}",
+            e.to_string().yellow()
+        );
+    }
+    result
+}
+
+/// Send progress messages and logs to stderr so stdout only carries results.
+/// Progress is shown unless `quiet`; `-v` adds actlog's and the AWS SDK's
+/// debug logs, such as retries, and `-vv` every library's. `RUST_LOG` still
+/// wins.
+fn init_logging(verbose: u8, quiet: bool) {
+    let default_filter = match (quiet, verbose) {
+        (true, _) => "warn",
+        (false, 0) => "actlog=info,warn",
+        (false, 1) => "actlog=debug,aws_smithy_runtime=debug,warn",
+        (false, _) => "actlog=trace,debug",
+    };
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+
+    // Progress reads as plain lines; levels and sources only help when debugging
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .with_level(verbose > 0)
+        .with_target(verbose > 0)
+        .init();
+}
+
+/// Requests this run made per service and what the billed ones cost.
+fn print_api_calls() {
+    let calls = executor::api_calls();
+    if calls.is_empty() {
+        return;
+    }
+
+    let total: u64 = calls.iter().map(|(_, count, _)| count).sum();
+    let cost: f64 = calls.iter().map(|(_, _, cost)| cost).sum();
+    eprintln!("\n📞 {} API calls (estimated cost ${:.4})", total, cost);
+    for (service, count, cost) in &calls {
+        if *cost > 0.0 {
+            eprintln!("   {:<14} {:>6}  ${:.4}", service, count, cost);
+        } else {
+            eprintln!("   {:<14} {:>6}", service, count);
+        }
+    }
+}
+
+async fn dispatch(command: &Commands) -> Result<(), AppError> {
+    match command {
         Commands::Authenticate { .. } => {
-            authenticate(&cli.command).await?;
+            authenticate(command).await?;
         }
 
         Commands::Config { .. } => {
-            configure(&cli.command).await?;
+            configure(command).await?;
+        }
+
+        Commands::Schedule { .. } => {
+            schedule(command).await?;
+        }
+
+        Commands::Lock { .. } => {
+            lock(command).await?;
         }
 
         Commands::ReportCosts { .. } => {
-            report_costs(&cli.command).await?;
+            report_costs(command).await?;
+        }
+
+        Commands::UsageReport { .. } => {
+            usage_report(command).await?;
+        }
+
+        Commands::ReportCarbon { .. } => {
+            report_carbon(command).await?;
         }
 
         Commands::ScaleInstances { .. } => {
-            scale_instances(&cli.command).await?;
+            scale_instances(command).await?;
         }
 
         Commands::Cleanup { .. } => {
-            cleanup_resources(&cli.command).await?;
+            cleanup_resources(command).await?;
         }
 
         Commands::List { .. } => {
-            list_resources(&cli.command).await?;
+            list_resources(command).await?;
+        }
+
+        Commands::IdleReport { .. } => {
+            idle_report(command).await?;
+        }
+
+        Commands::Recommendations { .. } => {
+            recommendations(command).await?;
+        }
+
+        Commands::Overview { .. } => {
+            overview(command).await?;
+        }
+
+        Commands::Quotas { .. } => {
+            check_quotas(command).await?;
+        }
+
+        Commands::SpotAdvisor { .. } => {
+            spot_advisor(command).await?;
+        }
+
+        Commands::StorageAnalysis { .. } => {
+            storage_analysis(command).await?;
+        }
+
+        Commands::EbsOptimize { .. } => {
+            optimize_ebs(command).await?;
+        }
+
+        Commands::SnapshotArchive { .. } => {
+            archive_snapshots(command).await?;
+        }
+
+        Commands::Reconcile { .. } => {
+            reconcile(command).await?;
+        }
+
+        Commands::Policy { .. } => {
+            policy(command).await?;
+        }
+
+        Commands::Query { .. } => {
+            query_resources(command).await?;
+        }
+
+        Commands::Export { .. } => {
+            export_data(command).await?;
+        }
+
+        Commands::Alerts { .. } => {
+            alerts(command).await?;
+        }
+
+        Commands::Snapshot { .. } => {
+            snapshot(command).await?;
+        }
+
+        Commands::Graph { .. } => {
+            export_graph(command).await?;
+        }
+
+        Commands::DnsCheck { .. } => {
+            dns_check(command).await?;
+        }
+
+        Commands::Metrics { .. } => {
+            metrics(command).await?;
+        }
+
+        Commands::Regions { .. } => {
+            list_regions(command).await?;
+        }
+
+        Commands::S3 { .. } => {
+            s3(command).await?;
+        }
+
+        Commands::Tags { .. } => {
+            tags(command).await?;
+        }
+
+        Commands::WhoCreated { .. } => {
+            who_created(command).await?;
+        }
+
+        Commands::SelfUpdate { .. } => {
+            self_update(command).await?;
+        }
+
+        Commands::Doctor { .. } => {
+            doctor(command).await?;
+        }
+
+        Commands::Dashboard { .. } => {
+            dashboard(command).await?;
+        }
+
+        Commands::Replay { .. } => {
+            return Err(AppError::InvalidParameters(
+                "A session can't replay another session".to_string(),
+            ));
         }
     }
 
@@ -0,0 +1,109 @@
+use crate::error::AppError;
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// A change actlog made or attempted, one JSON object per line in the audit log.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub command: String,
+    pub provider: String,
+    pub action: String,
+    pub resource_id: String,
+    pub succeeded: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The open log, or `None` once opening it has failed.
+static LOG: OnceLock<Mutex<Option<File>>> = OnceLock::new();
+
+pub fn audit_path() -> Result<PathBuf, AppError> {
+    let data_dir = dirs::data_local_dir().ok_or_else(|| {
+        AppError::ConfigurationError("Could not determine data directory".to_string())
+    })?;
+
+    Ok(data_dir.join("actlog").join("audit.log"))
+}
+
+fn open_log() -> Result<File, AppError> {
+    let path = audit_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(OpenOptions::new().create(true).append(true).open(path)?)
+}
+
+/// Append the outcome of one change. Audit problems are reported once and
+/// never fail the command.
+pub fn record(
+    command: &str,
+    provider: &str,
+    action: &str,
+    resource_id: &str,
+    result: &Result<(), AppError>,
+) {
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        command: command.to_string(),
+        provider: provider.to_string(),
+        action: action.to_string(),
+        resource_id: resource_id.to_string(),
+        succeeded: result.is_ok(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+
+    let log = LOG.get_or_init(|| {
+        Mutex::new(
+            open_log()
+                .map_err(|e| {
+                    tracing::warn!("⚠️  Audit log unavailable: {}", e.to_string().yellow())
+                })
+                .ok(),
+        )
+    });
+    let Ok(mut log) = log.lock() else {
+        return;
+    };
+    let Some(file) = log.as_mut() else {
+        return;
+    };
+
+    let written = serde_json::to_string(&entry)
+        .map_err(AppError::from)
+        .and_then(|line| Ok(writeln!(file, "{}", line)?));
+    if let Err(e) = written {
+        tracing::warn!("⚠️  Could not write audit log: {}", e.to_string().yellow());
+    }
+}
+
+/// Every entry in the audit log, oldest first. Lines that don't parse, such
+/// as one cut short by a crash, are skipped.
+pub fn read_entries() -> Result<Vec<AuditEntry>, AppError> {
+    let path = audit_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        if let Ok(entry) = serde_json::from_str(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Make everything recorded so far durable on disk.
+pub fn flush() {
+    if let Some(Ok(mut log)) = LOG.get().map(|l| l.lock()) {
+        if let Some(file) = log.as_mut() {
+            let _ = file.flush().and_then(|_| file.sync_all());
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::error::AppError;
+use crate::executor;
+use crate::retry;
+use crate::session;
+use crate::token_cache;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+use tokio::sync::OnceCell;
+
+pub const MANAGEMENT_API: &str = "https://management.azure.com";
+
+/// API version used for Microsoft.Network resources
+pub const NETWORK_API_VERSION: &str = "2023-09-01";
+
+/// API version used for Azure Monitor metrics
+pub const METRICS_API_VERSION: &str = "2018-01-01";
+
+/// API version used for Microsoft.Compute resources
+pub const COMPUTE_API_VERSION: &str = "2024-03-01";
+
+/// API version used for Microsoft.Resources subscriptions
+pub const SUBSCRIPTIONS_API_VERSION: &str = "2022-12-01";
+
+const LOGIN_API: &str = "https://login.microsoftonline.com";
+
+/// Cached tokens this close to expiring are replaced rather than reused
+const TOKEN_REFRESH_MARGIN_MINUTES: i64 = 5;
+
+/// The Azure CLI, which Windows installs as a batch script that `Command`
+/// only finds by its full name
+const AZ: &str = if cfg!(windows) { "az.cmd" } else { "az" };
+
+static TOKEN: OnceCell<String> = OnceCell::const_new();
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+pub fn subscription_id() -> Result<String, AppError> {
+    std::env::var("AZURE_SUBSCRIPTION_ID")
+        .map_err(|_| AppError::ConfigurationError("AZURE_SUBSCRIPTION_ID not set".to_string()))
+}
+
+/// ARM token: `AZURE_ACCESS_TOKEN` when set, then the service principal
+/// `authenticate azure` signs in, otherwise the Azure CLI login. Fetched
+/// once per command; service principal tokens are also cached between
+/// commands.
+async fn access_token() -> Result<String, AppError> {
+    TOKEN
+        .get_or_try_init(|| async {
+            if let Ok(token) = std::env::var("AZURE_ACCESS_TOKEN") {
+                return Ok(token);
+            }
+            if session::replaying() {
+                return Ok("REPLAY".to_string());
+            }
+
+            if let (Ok(tenant_id), Ok(client_id), Ok(client_secret)) = (
+                std::env::var("AZURE_TENANT_ID"),
+                std::env::var("AZURE_CLIENT_ID"),
+                std::env::var("AZURE_CLIENT_SECRET"),
+            ) {
+                return cached_service_principal_token(&tenant_id, &client_id, &client_secret)
+                    .await;
+            }
+
+            let output = tokio::process::Command::new(AZ)
+                .args([
+                    "account",
+                    "get-access-token",
+                    "--resource",
+                    "https://management.azure.com/",
+                    "--query",
+                    "accessToken",
+                    "--output",
+                    "tsv",
+                ])
+                .output()
+                .await
+                .map_err(|e| {
+                    AppError::AuthenticationError(format!("Could not run the Azure CLI: {}", e))
+                })?;
+            if !output.status.success() {
+                return Err(AppError::AuthenticationError(format!(
+                    "az could not provide an access token: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+        .await
+        .cloned()
+}
+
+/// A service principal's signed-in Azure Resource Manager token.
+#[derive(Serialize, Deserialize)]
+pub struct ArmToken {
+    pub access_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// ARM token for a service principal, reused from earlier commands until
+/// it is about to expire.
+async fn cached_service_principal_token(
+    tenant_id: &str,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<String, AppError> {
+    let key = format!("azure_{}_{}", tenant_id, client_id);
+    let reuse_until = Utc::now() + Duration::minutes(TOKEN_REFRESH_MARGIN_MINUTES);
+    if let Ok(Some(token)) = token_cache::load::<ArmToken>(&key) {
+        if token.expires_at > reuse_until {
+            return Ok(token.access_token);
+        }
+    }
+
+    let token = service_principal_token(tenant_id, client_id, client_secret).await?;
+    if let Err(e) = token_cache::store(&key, &token) {
+        eprintln!("   ⚠️  Could not cache the Azure token: {}", e);
+    }
+    Ok(token.access_token)
+}
+
+/// Sign the service principal in, cache the token for later commands and
+/// use it for the rest of this one.
+pub async fn service_principal_sign_in(
+    tenant_id: &str,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<ArmToken, AppError> {
+    let token = service_principal_token(tenant_id, client_id, client_secret).await?;
+    token_cache::store(&format!("azure_{}_{}", tenant_id, client_id), &token)?;
+    let _ = TOKEN.set(token.access_token.clone());
+    Ok(token)
+}
+
+/// Client-credentials token for a service principal from Microsoft Entra ID.
+async fn service_principal_token(
+    tenant_id: &str,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<ArmToken, AppError> {
+    let client = CLIENT.get_or_init(reqwest::Client::new);
+    let issued_at = Utc::now();
+    executor::throttle("oauth2").await;
+    let response = retry::send_http("oauth2.token", || {
+        client
+            .post(format!("{}/{}/oauth2/v2.0/token", LOGIN_API, tenant_id))
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+                ("scope", "https://management.azure.com/.default"),
+            ])
+    })
+    .await?;
+
+    let status = response.status();
+    let body: Value = response.json().await?;
+    if !status.is_success() {
+        return Err(AppError::AuthenticationError(format!(
+            "Service principal sign-in failed: {}",
+            body["error_description"]
+                .as_str()
+                .or(body["error"].as_str())
+                .unwrap_or("unknown error")
+        )));
+    }
+
+    let access_token = body["access_token"].as_str().ok_or_else(|| {
+        AppError::AuthenticationError("Token response has no access_token".to_string())
+    })?;
+    Ok(ArmToken {
+        access_token: access_token.to_string(),
+        expires_at: issued_at + Duration::seconds(body["expires_in"].as_i64().unwrap_or(3600)),
+    })
+}
+
+/// Call an ARM `url`, returning the JSON body (`null` when empty).
+/// Non-success statuses become provider errors carrying the ARM error code.
+async fn request(method: Method, operation: &str, url: &str) -> Result<Value, AppError> {
+    let token = access_token().await?;
+    let client = CLIENT.get_or_init(reqwest::Client::new);
+
+    executor::throttle("arm").await;
+    let response = retry::send_http(operation, || {
+        client.request(method.clone(), url).bearer_auth(&token)
+    })
+    .await?;
+
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(AppError::azure(operation, status, &body));
+    }
+
+    if body.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// GET a single ARM resource or response.
+pub async fn get(operation: &str, url: &str) -> Result<Value, AppError> {
+    request(Method::GET, operation, url).await
+}
+
+/// POST to an ARM action such as `.../stop`. Long-running actions may
+/// finish in the background; success means ARM accepted the request.
+pub async fn post(operation: &str, url: &str) -> Result<Value, AppError> {
+    request(Method::POST, operation, url).await
+}
+
+/// Every item of an ARM list call, following `nextLink`.
+pub async fn list(operation: &str, url: &str) -> Result<Vec<Value>, AppError> {
+    let mut items = Vec::new();
+    let mut next = Some(url.to_string());
+
+    while let Some(url) = next {
+        let page = request(Method::GET, operation, &url).await?;
+        if let Some(values) = page["value"].as_array() {
+            items.extend(values.iter().cloned());
+        }
+        next = page["nextLink"].as_str().map(str::to_string);
+    }
+
+    Ok(items)
+}
+
+/// Delete the resource with ARM id `resource_id`. Deletes may finish in the
+/// background; success means ARM accepted the request.
+pub async fn delete(operation: &str, resource_id: &str, api_version: &str) -> Result<(), AppError> {
+    let url = format!(
+        "{}{}?api-version={}",
+        MANAGEMENT_API, resource_id, api_version
+    );
+    request(Method::DELETE, operation, &url).await.map(|_| ())
+}
+
+/// Resource group named in an ARM resource id.
+pub fn resource_group_of(resource_id: &str) -> Option<&str> {
+    let mut segments = resource_id.split('/');
+    segments
+        .by_ref()
+        .find(|s| s.eq_ignore_ascii_case("resourceGroups"))?;
+    segments.next()
+}
+
+/// Virtual machines of the subscription, with their instance view so the
+/// power state is known.
+pub async fn virtual_machines() -> Result<Vec<Value>, AppError> {
+    let url = format!(
+        "{}/subscriptions/{}/providers/Microsoft.Compute/virtualMachines?api-version={}&statusOnly=true",
+        MANAGEMENT_API,
+        subscription_id()?,
+        COMPUTE_API_VERSION
+    );
+    list("Microsoft.Compute/virtualMachines/read", &url).await
+}
+
+/// Whether ARM resource `resource_id` is in `resource_group`, or any group
+/// when `None`. Group names are case-insensitive.
+pub fn in_resource_group(resource_id: &str, resource_group: Option<&str>) -> bool {
+    resource_group.map_or(true, |group| {
+        resource_group_of(resource_id).is_some_and(|g| g.eq_ignore_ascii_case(group))
+    })
+}
+
+/// Power state of a VM listed by [`virtual_machines`], e.g. `running` or
+/// `deallocated`.
+pub fn power_state(vm: &Value) -> String {
+    vm["properties"]["instanceView"]["statuses"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s["code"].as_str()?.strip_prefix("PowerState/"))
+        .next()
+        .unwrap_or("unknown")
+        .to_string()
+}
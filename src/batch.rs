@@ -0,0 +1,381 @@
+use crate::audit;
+use crate::cli::OutputFormat;
+use crate::error::AppError;
+use crate::guard;
+use crate::interrupt;
+use crate::locks;
+use crate::report;
+use colored::*;
+use serde::Serialize;
+use std::future::Future;
+
+/// One change in a batch run by cleanup, scale-instances, reconcile,
+/// ebs-optimize or tags propagate.
+pub trait BatchAction {
+    /// Short action name recorded in the audit log, e.g. "delete".
+    fn action(&self) -> &str;
+
+    /// The resource the action changes.
+    fn resource_id(&self) -> &str;
+
+    fn describe(&self) -> String;
+}
+
+/// What happened to one resource.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ResourceOutcome {
+    Succeeded,
+    Failed {
+        error: String,
+        /// Whether re-running the action later could succeed
+        retryable: bool,
+        /// The resource no longer existed when the action ran
+        missing: bool,
+    },
+    Skipped {
+        reason: String,
+    },
+}
+
+impl ResourceOutcome {
+    fn status(&self) -> &'static str {
+        match self {
+            ResourceOutcome::Succeeded => "succeeded",
+            ResourceOutcome::Failed { .. } => "failed",
+            ResourceOutcome::Skipped { .. } => "skipped",
+        }
+    }
+
+    fn detail(&self) -> &str {
+        match self {
+            ResourceOutcome::Succeeded => "",
+            ResourceOutcome::Failed { error, .. } => error,
+            ResourceOutcome::Skipped { reason } => reason,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItem {
+    pub resource_id: String,
+    pub action: String,
+    pub description: String,
+    #[serde(flatten)]
+    pub outcome: ResourceOutcome,
+}
+
+/// Where an applied batch diverged from its plan, by resource id.
+#[derive(Debug, Default, Serialize)]
+pub struct Discrepancies {
+    /// Resources that disappeared between plan and apply
+    pub missing: Vec<String>,
+    /// Actions that failed for any other reason
+    pub failed: Vec<String>,
+    /// Actions never attempted: locked, read-only or interrupted
+    pub not_attempted: Vec<String>,
+}
+
+impl Discrepancies {
+    fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.failed.is_empty() && self.not_attempted.is_empty()
+    }
+}
+
+/// Per-resource results of a batch of changes, in the order they were planned.
+#[derive(Debug, Serialize)]
+pub struct BatchOutcome {
+    pub command: String,
+    pub interrupted: bool,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub items: Vec<BatchItem>,
+    pub discrepancies: Discrepancies,
+}
+
+impl BatchOutcome {
+    fn new(command: &str, interrupted: bool, items: Vec<BatchItem>) -> Self {
+        let count = |status: &str| {
+            items
+                .iter()
+                .filter(|i| i.outcome.status() == status)
+                .count()
+        };
+        let mut discrepancies = Discrepancies::default();
+        for item in &items {
+            let id = item.resource_id.clone();
+            match item.outcome {
+                ResourceOutcome::Succeeded => {}
+                ResourceOutcome::Failed { missing: true, .. } => discrepancies.missing.push(id),
+                ResourceOutcome::Failed { .. } => discrepancies.failed.push(id),
+                ResourceOutcome::Skipped { .. } => discrepancies.not_attempted.push(id),
+            }
+        }
+
+        Self {
+            command: command.to_string(),
+            interrupted,
+            succeeded: count("succeeded"),
+            failed: count("failed"),
+            skipped: count("skipped"),
+            items,
+            discrepancies,
+        }
+    }
+
+    /// Failed actions that could succeed if run again.
+    pub fn retryable(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|i| {
+                matches!(
+                    i.outcome,
+                    ResourceOutcome::Failed {
+                        retryable: true,
+                        ..
+                    }
+                )
+            })
+            .count()
+    }
+
+    /// Whether the action at `index` of the planned batch succeeded.
+    pub fn succeeded_at(&self, index: usize) -> bool {
+        matches!(
+            self.items.get(index).map(|i| &i.outcome),
+            Some(ResourceOutcome::Succeeded)
+        )
+    }
+
+    pub fn render(&self, format: &OutputFormat) -> Result<(), AppError> {
+        match format {
+            OutputFormat::Table => {
+                if self.interrupted {
+                    println!(
+                        "\n🛑 Stopped after {} of {} actions.",
+                        self.succeeded + self.failed,
+                        self.items.len()
+                    );
+                }
+                println!(
+                    "\n📋 {} succeeded, {} failed, {} skipped",
+                    self.succeeded.to_string().green(),
+                    self.failed.to_string().red(),
+                    self.skipped.to_string().yellow()
+                );
+                println!(
+                    "{:<24} {:<16} {:<10} {}",
+                    "Resource", "Action", "Status", "Detail"
+                );
+                println!("{:-<85}", "");
+
+                for item in &self.items {
+                    let status = match item.outcome {
+                        ResourceOutcome::Succeeded => item.outcome.status().green(),
+                        ResourceOutcome::Failed { .. } => item.outcome.status().red(),
+                        ResourceOutcome::Skipped { .. } => item.outcome.status().yellow(),
+                    };
+                    println!(
+                        "{:<24} {:<16} {:<10} {}",
+                        item.resource_id,
+                        item.action,
+                        status,
+                        item.outcome.detail()
+                    );
+                }
+
+                let retryable = self.retryable();
+                if retryable > 0 {
+                    println!(
+                        "   {} of the failures look transient (throttling, timeouts or server errors); re-running may fix them.",
+                        retryable
+                    );
+                }
+
+                self.render_discrepancies();
+
+                if self.interrupted {
+                    if let Ok(path) = audit::audit_path() {
+                        println!("   Audit log: {}", path.display());
+                    }
+                }
+            }
+
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(self)?;
+                println!("{}", json);
+            }
+
+            OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+                println!("{}", report::document(format, self)?);
+            }
+
+            OutputFormat::Csv => {
+                println!("ResourceId,Action,Status,Retryable,Missing,Detail,Description");
+                for item in &self.items {
+                    let (retryable, missing) = match item.outcome {
+                        ResourceOutcome::Failed {
+                            retryable, missing, ..
+                        } => (retryable, missing),
+                        _ => (false, false),
+                    };
+                    println!(
+                        "{},{},{},{},{},{},{}",
+                        csv_field(&item.resource_id),
+                        csv_field(&item.action),
+                        item.outcome.status(),
+                        retryable,
+                        missing,
+                        csv_field(item.outcome.detail()),
+                        csv_field(&item.description)
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The plan-versus-outcome section of the table report. Silent when every
+    /// planned action was applied.
+    fn render_discrepancies(&self) {
+        let d = &self.discrepancies;
+        if d.is_empty() {
+            return;
+        }
+
+        println!(
+            "\n📐 Plan vs. outcome: {} planned, {} applied",
+            self.items.len(),
+            self.succeeded
+        );
+        if !d.missing.is_empty() {
+            println!(
+                "   Gone before apply ({}): {}",
+                d.missing.len(),
+                d.missing.join(", ").yellow()
+            );
+        }
+        if !d.failed.is_empty() {
+            println!(
+                "   Failed ({}): {}",
+                d.failed.len(),
+                d.failed.join(", ").red()
+            );
+        }
+        if !d.not_attempted.is_empty() {
+            println!(
+                "   Not attempted ({}): {}",
+                d.not_attempted.len(),
+                d.not_attempted.join(", ")
+            );
+        }
+    }
+
+    /// `Interrupted` if the batch was interrupted, `BatchFailed` if any
+    /// action failed, otherwise `Ok`.
+    pub fn into_result(self) -> Result<(), AppError> {
+        if self.interrupted {
+            Err(AppError::Interrupted)
+        } else if self.failed > 0 {
+            Err(AppError::BatchFailed(self.failed))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Run `execute` for every action in order, recording each attempt in the
+/// audit log. Failures don't stop the batch; locked resources are skipped,
+/// as is everything in read-only mode and after Ctrl-C.
+pub async fn run<'a, A, F, Fut>(
+    command: &str,
+    provider: &str,
+    actions: &'a [A],
+    mut execute: F,
+) -> BatchOutcome
+where
+    A: BatchAction,
+    F: FnMut(&'a A) -> Fut,
+    Fut: Future<Output = Result<(), AppError>>,
+{
+    let _batch = interrupt::batch();
+    let mut interrupted = false;
+    let mut items = Vec::with_capacity(actions.len());
+
+    // Commands refuse before prompting; this backs that up for every caller
+    let read_only = guard::ensure_writable(command).err().map(|e| e.to_string());
+
+    // Without the registry a lock can't be honoured, so nothing is changed
+    let locks = locks::active_locks().map_err(|e| {
+        tracing::warn!("⚠️  Lock registry unavailable: {}", e.to_string().yellow());
+        format!("lock registry unavailable: {}", e)
+    });
+
+    for action in actions {
+        interrupted = interrupted || interrupt::interrupted();
+
+        let locked = match &locks {
+            Ok(locks) => locks.get(action.resource_id()).map(|l| l.describe()),
+            Err(reason) => Some(reason.clone()),
+        };
+
+        let outcome = if interrupted {
+            ResourceOutcome::Skipped {
+                reason: "interrupted".to_string(),
+            }
+        } else if let Some(reason) = &read_only {
+            ResourceOutcome::Skipped {
+                reason: reason.clone(),
+            }
+        } else if let Some(reason) = locked {
+            tracing::warn!("🔒 {}: {}", action.describe(), reason.yellow());
+            ResourceOutcome::Skipped { reason }
+        } else {
+            let result = execute(action).await;
+            audit::record(
+                command,
+                provider,
+                action.action(),
+                action.resource_id(),
+                &result,
+            );
+            match result {
+                Ok(()) => {
+                    tracing::info!("   ✅ {}", action.describe());
+                    ResourceOutcome::Succeeded
+                }
+                Err(e) => {
+                    tracing::warn!("   ❌ {}: {}", action.describe(), e.to_string().red());
+                    ResourceOutcome::Failed {
+                        error: e.to_string(),
+                        retryable: e.is_retryable(),
+                        missing: e.is_not_found(),
+                    }
+                }
+            }
+        };
+
+        items.push(BatchItem {
+            resource_id: action.resource_id().to_string(),
+            action: action.action().to_string(),
+            description: action.describe(),
+            outcome,
+        });
+    }
+
+    if interrupted {
+        audit::flush();
+    }
+
+    BatchOutcome::new(command, interrupted, items)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,261 @@
+use crate::clients;
+use crate::commands::cost_report::CostAlert;
+use crate::error::AppError;
+use crate::executor;
+use aws_config::SdkConfig;
+use aws_sdk_costexplorer::types::{DateInterval, Granularity, Metric};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Month-to-date spend against a monthly budget and where it is heading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetBurn {
+    pub budget: f64,
+    pub month_to_date: f64,
+    /// Provider forecast for the rest of the month, when one is available
+    pub forecast: Option<f64>,
+    pub daily_burn: f64,
+    pub projected_total: f64,
+    /// First day the month's spend is expected to pass the budget
+    pub projected_overrun: Option<NaiveDate>,
+}
+
+impl BudgetBurn {
+    /// Early warning while the budget still holds but the month won't.
+    pub fn alert(&self) -> Option<CostAlert> {
+        let date = self.projected_overrun?;
+        Some(CostAlert {
+            message: format!(
+                "On pace to exceed budget by the {} (projected ${:.2} this month)",
+                ordinal(date.day()),
+                self.projected_total
+            ),
+            severity: "medium".to_string(),
+            threshold: self.budget,
+            actual_cost: self.month_to_date,
+        })
+    }
+}
+
+/// Project the month's spend from `month_to_date`, the cost of the days
+/// before `today`. The rest of the month follows `forecast` when given,
+/// otherwise the daily burn so far.
+pub fn project(
+    budget: f64,
+    month_to_date: f64,
+    forecast: Option<f64>,
+    today: NaiveDate,
+) -> BudgetBurn {
+    let elapsed = today.day0();
+    let remaining = days_in_month(today) - elapsed;
+
+    let daily_burn = if elapsed > 0 {
+        month_to_date / elapsed as f64
+    } else {
+        0.0
+    };
+    let remaining_burn = forecast.map_or(daily_burn, |f| f / remaining as f64);
+    let projected_total = month_to_date + remaining_burn * remaining as f64;
+
+    // Already over budget is reported by the threshold check itself
+    let projected_overrun =
+        if month_to_date < budget && projected_total > budget && remaining_burn > 0.0 {
+            let days = ((budget - month_to_date) / remaining_burn).floor() as i64;
+            Some(today + Duration::days(days.min(remaining as i64 - 1)))
+        } else {
+            None
+        };
+
+    BudgetBurn {
+        budget,
+        month_to_date,
+        forecast,
+        daily_burn,
+        projected_total,
+        projected_overrun,
+    }
+}
+
+/// Month-to-date spend for providers whose reports only cover a fixed
+/// period: assume this month ran at the period's daily average.
+pub fn month_to_date_from_average(total: f64, period_days: i64, today: NaiveDate) -> f64 {
+    total / period_days.max(1) as f64 * today.day0() as f64
+}
+
+/// Month-to-date unblended cost and Cost Explorer's forecast for the rest
+/// of the month.
+pub async fn aws_spend(
+    config: &SdkConfig,
+    today: NaiveDate,
+) -> Result<(f64, Option<f64>), AppError> {
+    let client = clients::get::<aws_sdk_costexplorer::Client>(config).await;
+    let month_start = today.with_day(1).unwrap_or(today);
+    let month_end = month_start + Duration::days(days_in_month(today) as i64);
+
+    let interval = |start: NaiveDate, end: NaiveDate| {
+        DateInterval::builder()
+            .start(start.format("%Y-%m-%d").to_string())
+            .end(end.format("%Y-%m-%d").to_string())
+            .build()
+            .map_err(|e| AppError::AwsError(e.to_string()))
+    };
+
+    // Cost Explorer rejects empty periods, and nothing is billed yet on the 1st
+    let mut month_to_date = 0.0;
+    if today > month_start {
+        executor::throttle("costexplorer").await;
+        let response = client
+            .get_cost_and_usage()
+            .time_period(interval(month_start, today)?)
+            .granularity(Granularity::Monthly)
+            .metrics("UnblendedCost")
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetCostAndUsage", e))?;
+        month_to_date = response
+            .results_by_time()
+            .iter()
+            .filter_map(|result| result.total()?.get("UnblendedCost")?.amount())
+            .filter_map(|amount| amount.parse::<f64>().ok())
+            .sum();
+    }
+
+    // Accounts without enough history have no forecast; fall back to the burn rate
+    executor::throttle("costexplorer").await;
+    let forecast = client
+        .get_cost_forecast()
+        .time_period(interval(today, month_end)?)
+        .metric(Metric::UnblendedCost)
+        .granularity(Granularity::Monthly)
+        .send()
+        .await
+        .ok()
+        .and_then(|response| response.total()?.amount()?.parse::<f64>().ok());
+
+    Ok((month_to_date, forecast))
+}
+
+/// Spend expected over the next `days` days and where the month ends up,
+/// from `report-costs --forecast`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostForecast {
+    pub days: u32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub forecast_cost: f64,
+    /// Month-to-date spend plus the forecast for the rest of the month
+    pub projected_month_end: f64,
+    /// "cost explorer", or "linear" when extrapolated from the report period
+    pub method: String,
+}
+
+impl CostForecast {
+    /// Warning for a budget the forecast says will be passed, whether or not
+    /// today's spend already has. Month-end overruns are flagged by
+    /// [`BudgetBurn::alert`].
+    pub fn alert(&self, threshold: f64) -> Option<CostAlert> {
+        (self.forecast_cost > threshold).then(|| CostAlert {
+            message: format!(
+                "Forecast spend for the next {} days exceeds budget threshold",
+                self.days
+            ),
+            severity: "medium".to_string(),
+            threshold,
+            actual_cost: self.forecast_cost,
+        })
+    }
+}
+
+/// Cost Explorer's forecast for the next `days` days and the month, falling
+/// back to [`linear_forecast`] when the account has too little history.
+pub async fn aws_forecast(
+    config: &SdkConfig,
+    days: u32,
+    period_total: f64,
+    period_days: i64,
+    today: NaiveDate,
+) -> Result<CostForecast, AppError> {
+    let client = clients::get::<aws_sdk_costexplorer::Client>(config).await;
+    let end_date = today + Duration::days(days as i64);
+    let interval = DateInterval::builder()
+        .start(today.format("%Y-%m-%d").to_string())
+        .end(end_date.format("%Y-%m-%d").to_string())
+        .build()
+        .map_err(|e| AppError::AwsError(e.to_string()))?;
+
+    executor::throttle("costexplorer").await;
+    let forecast_cost = match client
+        .get_cost_forecast()
+        .time_period(interval)
+        .metric(Metric::UnblendedCost)
+        .granularity(Granularity::Monthly)
+        .send()
+        .await
+    {
+        Ok(response) => response
+            .total()
+            .and_then(|total| total.amount())
+            .and_then(|amount| amount.parse::<f64>().ok()),
+        Err(e) => {
+            log::debug!("No Cost Explorer forecast, extrapolating: {}", e);
+            None
+        }
+    };
+
+    let (month_to_date, rest_of_month) = aws_spend(config, today).await?;
+    match (forecast_cost, rest_of_month) {
+        (Some(forecast_cost), Some(rest_of_month)) => Ok(CostForecast {
+            days,
+            start_date: today,
+            end_date,
+            forecast_cost,
+            projected_month_end: month_to_date + rest_of_month,
+            method: "cost explorer".to_string(),
+        }),
+        _ => Ok(linear_forecast(period_total, period_days, days, today)),
+    }
+}
+
+/// Forecast that carries on at the report period's daily average, for
+/// providers without a forecasting API.
+pub fn linear_forecast(
+    period_total: f64,
+    period_days: i64,
+    days: u32,
+    today: NaiveDate,
+) -> CostForecast {
+    let daily = period_total / period_days.max(1) as f64;
+    let remaining = days_in_month(today) - today.day0();
+    let month_to_date = month_to_date_from_average(period_total, period_days, today);
+
+    CostForecast {
+        days,
+        start_date: today,
+        end_date: today + Duration::days(days as i64),
+        forecast_cost: daily * days as f64,
+        projected_month_end: month_to_date + daily * remaining as f64,
+        method: "linear".to_string(),
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|next| next.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+fn ordinal(day: u32) -> String {
+    let suffix = match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", day, suffix)
+}
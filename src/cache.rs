@@ -0,0 +1,157 @@
+use crate::azure;
+use crate::cli::CloudProvider;
+use crate::clients;
+use crate::error::AppError;
+use crate::gcp;
+use crate::inventory::{self, CacheMode};
+use crate::settings::ResponseCacheSettings;
+use aws_config::SdkConfig;
+use aws_credential_types::provider::ProvideCredentials;
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// How long a cached response is served when no TTL is configured
+const DEFAULT_TTL_SECONDS: u64 = 300;
+
+struct ResponseCacheConfig {
+    enabled: bool,
+    ttl: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    fetched_at: DateTime<Utc>,
+    payload: serde_json::Value,
+}
+
+static CONFIG: OnceLock<ResponseCacheConfig> = OnceLock::new();
+static MEMORY: OnceLock<Mutex<HashMap<String, Entry>>> = OnceLock::new();
+
+/// Turn the response cache on when `actlog.toml` or `--cache-responses` asks
+/// for it. It stays off otherwise.
+pub fn configure(enable: bool, settings: &ResponseCacheSettings) {
+    let ttl = settings.ttl_seconds.unwrap_or(DEFAULT_TTL_SECONDS);
+    let _ = CONFIG.set(ResponseCacheConfig {
+        enabled: enable || settings.enabled,
+        ttl: Duration::seconds(ttl as i64),
+    });
+}
+
+fn config() -> Option<&'static ResponseCacheConfig> {
+    CONFIG
+        .get()
+        .filter(|c| c.enabled && inventory::cache_mode() != CacheMode::Disabled)
+}
+
+/// Cache key component identifying the region and credentials behind
+/// `config`, so responses from different accounts never mix.
+pub async fn aws_scope(config: &SdkConfig) -> String {
+    let region = config.region().map(|r| r.to_string()).unwrap_or_default();
+    let identity = match config.credentials_provider() {
+        Some(provider) => provider
+            .provide_credentials()
+            .await
+            .map(|c| c.access_key_id().to_string())
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+    format!("{}/{}", region, identity)
+}
+
+/// Cache key component identifying the account `provider` talks to: the AWS
+/// region and credentials, the GCP project or the Azure subscription.
+pub async fn provider_scope(provider: &CloudProvider) -> String {
+    match provider {
+        CloudProvider::Aws => aws_scope(&clients::aws_config().await).await,
+        CloudProvider::Gcp => gcp::project_id().unwrap_or_default(),
+        CloudProvider::Azure => azure::subscription_id().unwrap_or_default(),
+    }
+}
+
+/// Serve a read-only call from memory or disk while younger than the TTL,
+/// otherwise run `fetch` and remember its result. `--refresh` skips the
+/// lookup but still stores the new response.
+pub async fn cached_call<T, F, Fut>(key: &str, fetch: F) -> Result<T, AppError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    let Some(config) = config() else {
+        return fetch().await;
+    };
+
+    if inventory::cache_mode() == CacheMode::Use {
+        if let Some(value) = lookup(key, config.ttl) {
+            log::debug!("response cache hit for {}", key);
+            return Ok(value);
+        }
+    }
+
+    let value = fetch().await?;
+    if let Ok(payload) = serde_json::to_value(&value) {
+        store(key, payload);
+    }
+    Ok(value)
+}
+
+fn lookup<T: DeserializeOwned>(key: &str, ttl: Duration) -> Option<T> {
+    let fresh = |entry: &Entry| Utc::now() - entry.fetched_at < ttl;
+
+    let memory = MEMORY.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(entry) = memory.lock().ok()?.get(key).filter(|e| fresh(e)) {
+        return serde_json::from_value(entry.payload.clone()).ok();
+    }
+
+    let content = fs::read_to_string(entry_path(key)?).ok()?;
+    let entry: Entry = serde_json::from_str(&content).ok()?;
+    if !fresh(&entry) {
+        return None;
+    }
+
+    let value = serde_json::from_value(entry.payload.clone()).ok()?;
+    memory.lock().ok()?.insert(key.to_string(), entry);
+    Some(value)
+}
+
+fn store(key: &str, payload: serde_json::Value) {
+    let entry = Entry {
+        fetched_at: Utc::now(),
+        payload,
+    };
+
+    if let Some(path) = entry_path(key) {
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, serde_json::to_string(&entry).unwrap_or_default()));
+        if let Err(e) = written {
+            log::debug!("could not write response cache {}: {}", path.display(), e);
+        }
+    }
+
+    if let Ok(mut memory) = MEMORY.get_or_init(|| Mutex::new(HashMap::new())).lock() {
+        memory.insert(key.to_string(), entry);
+    }
+}
+
+/// File holding the response for `key`. Keys are hashed so credentials in
+/// the scope never reach the disk.
+fn entry_path(key: &str) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    Some(
+        dirs::cache_dir()?
+            .join("actlog")
+            .join("responses")
+            .join(format!("{:016x}.json", hasher.finish())),
+    )
+}
@@ -0,0 +1,393 @@
+use crate::azure;
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::regions;
+use aws_config::SdkConfig;
+use chrono::{Duration, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Days of traffic looked at when deciding whether an endpoint is idle
+pub const IDLE_WINDOW_DAYS: i64 = 30;
+
+pub const CLOUDFRONT_DISTRIBUTION: &str = "CloudFront Distribution";
+pub const CLOUD_CDN_BACKEND: &str = "Cloud CDN Backend";
+pub const AZURE_CDN_ENDPOINT: &str = "Azure CDN Endpoint";
+
+const AZURE_CDN_API_VERSION: &str = "2023-05-01";
+
+/// A CloudFront distribution, Cloud CDN-enabled backend or Azure CDN endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct CdnEndpoint {
+    pub id: String,
+    pub name: String,
+    pub resource_type: String,
+    pub region: String,
+    pub domain: String,
+    pub origins: Vec<String>,
+    pub state: String,
+    /// Backend services and buckets carry no labels, so GCP's are empty
+    pub tags: HashMap<String, String>,
+    /// Requests served over the last [`IDLE_WINDOW_DAYS`] days
+    pub requests: f64,
+}
+
+impl CdnEndpoint {
+    /// Serving no traffic at all makes the endpoint an idle candidate.
+    pub fn is_idle(&self) -> bool {
+        self.requests == 0.0
+    }
+}
+
+/// Every CloudFront distribution with its request count from CloudWatch.
+pub async fn cloudfront_distributions(config: &SdkConfig) -> Result<Vec<CdnEndpoint>, AppError> {
+    let client = clients::get::<aws_sdk_cloudfront::Client>(config).await;
+    // CloudFront publishes its metrics in us-east-1 only
+    let metrics_config = regions::regional_config(config, "us-east-1");
+    let cloudwatch = clients::get::<aws_sdk_cloudwatch::Client>(&metrics_config).await;
+
+    let mut endpoints = Vec::new();
+    let mut marker = None;
+    loop {
+        executor::throttle("cloudfront").await;
+        let response = client
+            .list_distributions()
+            .set_marker(marker)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("cloudfront:ListDistributions", e))?;
+        let Some(list) = response.distribution_list() else {
+            break;
+        };
+
+        for distribution in list.items() {
+            let id = distribution.id();
+            endpoints.push(CdnEndpoint {
+                id: id.to_string(),
+                name: distribution
+                    .aliases()
+                    .and_then(|a| a.items().first())
+                    .cloned()
+                    .unwrap_or_else(|| id.to_string()),
+                resource_type: CLOUDFRONT_DISTRIBUTION.to_string(),
+                region: "global".to_string(),
+                domain: distribution.domain_name().to_string(),
+                origins: distribution
+                    .origins()
+                    .map(|o| {
+                        o.items()
+                            .iter()
+                            .map(|origin| origin.domain_name().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                state: if distribution.enabled() {
+                    distribution.status().to_string()
+                } else {
+                    "Disabled".to_string()
+                },
+                tags: cloudfront_tags(&client, distribution.arn()).await?,
+                requests: cloudfront_requests(&cloudwatch, id).await?,
+            });
+        }
+
+        if !list.is_truncated() {
+            break;
+        }
+        marker = list.next_marker().map(str::to_string);
+    }
+
+    Ok(endpoints)
+}
+
+async fn cloudfront_tags(
+    client: &aws_sdk_cloudfront::Client,
+    arn: &str,
+) -> Result<HashMap<String, String>, AppError> {
+    executor::throttle("cloudfront").await;
+    let response = client
+        .list_tags_for_resource()
+        .resource(arn)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("cloudfront:ListTagsForResource", e).for_resource(arn))?;
+
+    Ok(response
+        .tags()
+        .map(|tags| {
+            tags.items()
+                .iter()
+                .map(|tag| {
+                    (
+                        tag.key().to_string(),
+                        tag.value().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+async fn cloudfront_requests(
+    cloudwatch: &aws_sdk_cloudwatch::Client,
+    distribution_id: &str,
+) -> Result<f64, AppError> {
+    let now = Utc::now();
+    executor::throttle("cloudwatch").await;
+    let response = cloudwatch
+        .get_metric_statistics()
+        .namespace("AWS/CloudFront")
+        .metric_name("Requests")
+        .dimensions(
+            aws_sdk_cloudwatch::types::Dimension::builder()
+                .name("DistributionId")
+                .value(distribution_id)
+                .build(),
+        )
+        .dimensions(
+            aws_sdk_cloudwatch::types::Dimension::builder()
+                .name("Region")
+                .value("Global")
+                .build(),
+        )
+        .start_time(aws_sdk_cloudwatch::primitives::DateTime::from_secs(
+            (now - Duration::days(IDLE_WINDOW_DAYS)).timestamp(),
+        ))
+        .end_time(aws_sdk_cloudwatch::primitives::DateTime::from_secs(
+            now.timestamp(),
+        ))
+        .period(86400)
+        .statistics(aws_sdk_cloudwatch::types::Statistic::Sum)
+        .send()
+        .await
+        .map_err(|e| {
+            AppError::aws("cloudwatch:GetMetricStatistics", e).for_resource(distribution_id)
+        })?;
+
+    // No datapoints means no requests
+    Ok(response.datapoints().iter().filter_map(|d| d.sum()).sum())
+}
+
+/// Backend services and buckets with Cloud CDN enabled, with request counts
+/// from Cloud Monitoring.
+pub async fn gcp_cdn_backends() -> Result<Vec<CdnEndpoint>, AppError> {
+    let project_id = gcp::project_id()?;
+    let mut endpoints = Vec::new();
+
+    let url = format!(
+        "{}/projects/{}/aggregated/backendServices",
+        gcp::COMPUTE_API,
+        project_id
+    );
+    let pages = gcp::list_pages("compute.backendServices.aggregatedList", &url).await?;
+    let services = pages
+        .iter()
+        .filter_map(|page| page["items"].as_object())
+        .flat_map(|scopes| scopes.values())
+        .filter_map(|scope| scope["backendServices"].as_array())
+        .flatten()
+        .filter(|service| service["enableCDN"].as_bool() == Some(true));
+    for service in services {
+        let name = service["name"].as_str().unwrap_or_default();
+        endpoints.push(CdnEndpoint {
+            id: service["id"].as_str().unwrap_or(name).to_string(),
+            name: name.to_string(),
+            resource_type: CLOUD_CDN_BACKEND.to_string(),
+            region: service["region"]
+                .as_str()
+                .map(gcp::short_name)
+                .unwrap_or("global")
+                .to_string(),
+            domain: String::new(),
+            origins: service["backends"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|b| b["group"].as_str())
+                .map(|group| gcp::short_name(group).to_string())
+                .collect(),
+            state: "enabled".to_string(),
+            tags: HashMap::new(),
+            requests: gcp_backend_requests(&project_id, name).await?,
+        });
+    }
+
+    let url = format!(
+        "{}/projects/{}/global/backendBuckets",
+        gcp::COMPUTE_API,
+        project_id
+    );
+    let pages = gcp::list_pages("compute.backendBuckets.list", &url).await?;
+    let buckets = pages
+        .iter()
+        .filter_map(|page| page["items"].as_array())
+        .flatten()
+        .filter(|bucket| bucket["enableCdn"].as_bool() == Some(true));
+    for bucket in buckets {
+        let name = bucket["name"].as_str().unwrap_or_default();
+        endpoints.push(CdnEndpoint {
+            id: bucket["id"].as_str().unwrap_or(name).to_string(),
+            name: name.to_string(),
+            resource_type: CLOUD_CDN_BACKEND.to_string(),
+            region: "global".to_string(),
+            domain: String::new(),
+            origins: bucket["bucketName"]
+                .as_str()
+                .map(|b| vec![format!("gs://{}", b)])
+                .unwrap_or_default(),
+            state: "enabled".to_string(),
+            tags: HashMap::new(),
+            requests: gcp_backend_requests(&project_id, name).await?,
+        });
+    }
+
+    Ok(endpoints)
+}
+
+/// Requests the HTTP(S) load balancer routed to `backend` over the window.
+async fn gcp_backend_requests(project_id: &str, backend: &str) -> Result<f64, AppError> {
+    let now = Utc::now();
+    let start = now - Duration::days(IDLE_WINDOW_DAYS);
+    let filter = format!(
+        "metric.type=\"loadbalancing.googleapis.com/https/request_count\" AND resource.labels.backend_target_name=\"{}\"",
+        backend
+    );
+    let start_time = start.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let end_time = now.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let alignment = format!("{}s", IDLE_WINDOW_DAYS * 86400);
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/projects/{}/timeSeries", gcp::MONITORING_API, project_id),
+        &[
+            ("filter", filter.as_str()),
+            ("interval.startTime", start_time.as_str()),
+            ("interval.endTime", end_time.as_str()),
+            ("aggregation.alignmentPeriod", alignment.as_str()),
+            ("aggregation.perSeriesAligner", "ALIGN_SUM"),
+            ("aggregation.crossSeriesReducer", "REDUCE_SUM"),
+        ],
+    )
+    .map_err(|e| AppError::InvalidParameters(e.to_string()))?;
+
+    let response = gcp::get("monitoring.timeSeries.list", url.as_str())
+        .await
+        .map_err(|e| e.for_resource(backend))?;
+
+    // int64 values arrive as strings
+    Ok(response["timeSeries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|series| series["points"].as_array())
+        .flatten()
+        .filter_map(|point| {
+            let value = &point["value"]["int64Value"];
+            value
+                .as_str()
+                .and_then(|v| v.parse::<f64>().ok())
+                .or(value.as_f64())
+        })
+        .sum())
+}
+
+/// Endpoints of every Azure CDN profile, with request counts from Azure Monitor.
+pub async fn azure_cdn_endpoints() -> Result<Vec<CdnEndpoint>, AppError> {
+    let subscription_id = azure::subscription_id()?;
+    let url = format!(
+        "{}/subscriptions/{}/providers/Microsoft.Cdn/profiles?api-version={}",
+        azure::MANAGEMENT_API,
+        subscription_id,
+        AZURE_CDN_API_VERSION
+    );
+    let profiles = azure::list("Microsoft.Cdn/profiles/read", &url).await?;
+
+    let mut endpoints = Vec::new();
+    for profile in &profiles {
+        let profile_id = profile["id"].as_str().unwrap_or_default();
+        let url = format!(
+            "{}{}/endpoints?api-version={}",
+            azure::MANAGEMENT_API,
+            profile_id,
+            AZURE_CDN_API_VERSION
+        );
+        for endpoint in azure::list("Microsoft.Cdn/profiles/endpoints/read", &url).await? {
+            let properties = &endpoint["properties"];
+            let host_name = properties["hostName"].as_str().unwrap_or_default();
+            endpoints.push(CdnEndpoint {
+                id: endpoint["id"].as_str().unwrap_or_default().to_string(),
+                name: endpoint["name"].as_str().unwrap_or_default().to_string(),
+                resource_type: AZURE_CDN_ENDPOINT.to_string(),
+                region: endpoint["location"]
+                    .as_str()
+                    .unwrap_or("global")
+                    .to_string(),
+                domain: host_name.to_string(),
+                origins: properties["origins"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|o| o["properties"]["hostName"].as_str())
+                    .map(str::to_string)
+                    .collect(),
+                state: properties["resourceState"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_string(),
+                tags: endpoint["tags"]
+                    .as_object()
+                    .map(|tags| {
+                        tags.iter()
+                            .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                requests: azure_endpoint_requests(profile_id, host_name).await?,
+            });
+        }
+    }
+
+    Ok(endpoints)
+}
+
+/// RequestCount is a profile metric; the Endpoint dimension narrows it down.
+async fn azure_endpoint_requests(profile_id: &str, host_name: &str) -> Result<f64, AppError> {
+    let now = Utc::now();
+    let start = now - Duration::days(IDLE_WINDOW_DAYS);
+    let timespan = format!(
+        "{}/{}",
+        start.to_rfc3339_opts(SecondsFormat::Secs, true),
+        now.to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+    let filter = format!("Endpoint eq '{}'", host_name);
+    let url = reqwest::Url::parse_with_params(
+        &format!(
+            "{}{}/providers/Microsoft.Insights/metrics",
+            azure::MANAGEMENT_API,
+            profile_id
+        ),
+        &[
+            ("api-version", azure::METRICS_API_VERSION),
+            ("metricnames", "RequestCount"),
+            ("aggregation", "Total"),
+            ("interval", "P1D"),
+            ("timespan", timespan.as_str()),
+            ("$filter", filter.as_str()),
+        ],
+    )
+    .map_err(|e| AppError::InvalidParameters(e.to_string()))?;
+
+    let metrics = azure::list("Microsoft.Insights/metrics/read", url.as_str())
+        .await
+        .map_err(|e| e.for_resource(host_name))?;
+
+    Ok(metrics
+        .iter()
+        .filter_map(|metric| metric["timeseries"].as_array())
+        .flatten()
+        .filter_map(|series| series["data"].as_array())
+        .flatten()
+        .filter_map(|point| point["total"].as_f64())
+        .sum())
+}
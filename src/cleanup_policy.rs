@@ -0,0 +1,146 @@
+use crate::commands::list::ResourceSummary;
+use crate::error::AppError;
+use crate::query::{self, Expr};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Cleanup rules from a YAML file passed to `cleanup --policy`, e.g.
+///
+/// ```yaml
+/// rules:
+///   - name: never-touch-prod
+///     match: tags.env=prod
+///     action: keep
+///   - name: stale-stopped-instances
+///     match: type='EC2 Instance' and state=stopped and age>14d
+///     unless: tags.owner=*
+///     action: delete
+///     reason: Stopped for over two weeks without an owner
+/// ```
+///
+/// The first rule a resource matches decides what happens to it; resources
+/// no rule matches are left alone.
+#[derive(Debug)]
+pub struct CleanupPolicy {
+    pub rules: Vec<CleanupRule>,
+}
+
+#[derive(Debug)]
+pub struct CleanupRule {
+    pub name: String,
+    pub action: RuleAction,
+    /// Shown as the cleanup reason; the rule's name when not set
+    pub reason: Option<String>,
+    selector: Expr,
+    unless: Option<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    /// Never clean up the resource, whatever later rules say
+    Keep,
+    Delete,
+    Stop,
+    Deactivate,
+    /// Whatever cleanup decides without a policy
+    Default,
+}
+
+impl RuleAction {
+    /// The cleanup action type the rule asks for, if it asks for one.
+    pub fn action_type(&self) -> Option<&'static str> {
+        match self {
+            RuleAction::Delete => Some("DELETE"),
+            RuleAction::Stop => Some("STOP"),
+            RuleAction::Deactivate => Some("DEACTIVATE"),
+            RuleAction::Keep | RuleAction::Default => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    name: String,
+    /// Query expression, as in `actlog query`
+    #[serde(rename = "match")]
+    selector: String,
+    unless: Option<String>,
+    action: RuleAction,
+    reason: Option<String>,
+}
+
+impl CleanupPolicy {
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            AppError::ConfigurationError(format!("Could not read policy {}: {}", path.display(), e))
+        })?;
+        Self::parse(&content).map_err(|e| match e {
+            AppError::ConfigurationError(message) => {
+                AppError::ConfigurationError(format!("{}: {}", path.display(), message))
+            }
+            other => other,
+        })
+    }
+
+    /// Parse and check a policy: every expression must parse and rule names
+    /// must be unique, so a mistake fails before anything is scanned.
+    pub fn parse(content: &str) -> Result<Self, AppError> {
+        let file: PolicyFile = serde_yaml::from_str(content)
+            .map_err(|e| AppError::ConfigurationError(format!("Invalid cleanup policy: {}", e)))?;
+        if file.rules.is_empty() {
+            return Err(AppError::ConfigurationError(
+                "The policy has no rules".to_string(),
+            ));
+        }
+
+        let mut names = HashSet::new();
+        let mut rules = Vec::with_capacity(file.rules.len());
+        for spec in file.rules {
+            if !names.insert(spec.name.clone()) {
+                return Err(AppError::ConfigurationError(format!(
+                    "Rule name '{}' is used twice",
+                    spec.name
+                )));
+            }
+            let parse = |field: &str, expression: &str| {
+                query::parse(expression).map_err(|e| {
+                    AppError::ConfigurationError(format!("Rule '{}' {}: {}", spec.name, field, e))
+                })
+            };
+            rules.push(CleanupRule {
+                selector: parse("match", &spec.selector)?,
+                unless: spec
+                    .unless
+                    .as_deref()
+                    .map(|unless| parse("unless", unless))
+                    .transpose()?,
+                name: spec.name,
+                action: spec.action,
+                reason: spec.reason,
+            });
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// The first rule `resource` matches and isn't exempt from.
+    pub fn evaluate(&self, provider: &str, resource: &ResourceSummary) -> Option<&CleanupRule> {
+        self.rules.iter().find(|rule| {
+            rule.selector.matches(provider, resource)
+                && !rule
+                    .unless
+                    .as_ref()
+                    .is_some_and(|unless| unless.matches(provider, resource))
+        })
+    }
+}
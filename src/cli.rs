@@ -1,4 +1,7 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use crate::settings::CredentialStorage;
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgGroup, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use std::ffi::OsString;
 use std::fmt;
 
 #[derive(Parser)]
@@ -12,11 +15,93 @@ pub struct Cli {
     /// The command to run
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Regions or accounts to scan concurrently (overrides actlog.toml)
+    #[arg(long, global = true)]
+    pub parallelism: Option<usize>,
+
+    /// Stop the command before it makes more than this many API requests
+    #[arg(long, global = true)]
+    pub max_api_calls: Option<u64>,
+
+    /// Attempts per provider request before giving up (overrides actlog.toml)
+    #[arg(long, global = true)]
+    pub max_attempts: Option<u32>,
+
+    /// Abort the command after this many seconds (overrides actlog.toml)
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+
+    /// Log retries and other diagnostics; -vv adds every library's debug logs
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Only print results, warnings and errors, without progress messages
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Bypass the local inventory cache entirely
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Refetch from the cloud APIs and update the local inventory cache
+    #[arg(long, global = true, conflicts_with = "no_cache")]
+    pub refresh: bool,
+
+    /// Reuse describe, list and metric responses younger than the cache TTL
+    #[arg(long, global = true, conflicts_with = "no_cache")]
+    pub cache_responses: bool,
+
+    /// Refuse every change to cloud resources; reports and dry runs still work
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
+    /// Print failures to stderr as a JSON object instead of text
+    #[arg(long, global = true)]
+    pub json_errors: bool,
+
+    /// Write the command's output to this file instead of stdout; a .json, .csv,
+    /// .yaml or .toml extension picks the format unless --format does
+    #[arg(long, global = true, value_name = "PATH")]
+    pub output: Option<String>,
+
+    /// Replace the --output file if it already exists, without the other
+    /// effects of a command's --force
+    #[arg(long, global = true, requires = "output")]
+    pub overwrite: bool,
+
+    /// Record every provider request and response of this run to a session file
+    #[arg(long, global = true, value_name = "FILE")]
+    pub record: Option<String>,
+
+    /// Whether the command's --format was given rather than left at its default
+    #[arg(skip)]
+    pub format_given: bool,
+}
+
+impl Cli {
+    /// Parse `args` as `try_parse_from` does, also noting whether `--format`
+    /// was given, so `--output` only infers a format from the extension
+    /// when it wasn't.
+    pub fn try_parse_args<I, T>(args: I) -> Result<Cli, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = Cli::command().try_get_matches_from(args)?;
+        let mut cli = Cli::from_arg_matches(&matches)?;
+        cli.format_given = matches.subcommand().is_some_and(|(_, command)| {
+            command.ids().any(|id| id == "format")
+                && command.value_source("format") == Some(ValueSource::CommandLine)
+        });
+        Ok(cli)
+    }
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Authenticate with cloud providers
+    #[command(group(ArgGroup::new("temporary_session").args(["role_arn", "mfa_serial"])))]
     Authenticate {
         /// Cloud provider to authenticate with
         #[arg(value_enum)]
@@ -29,6 +114,50 @@ pub enum Commands {
         /// Force re-authentication even if credentials exist
         #[arg(short, long)]
         force: bool,
+
+        /// Where to keep secrets; `credentials.storage` in actlog.toml, else the OS keyring
+        #[arg(long, value_enum)]
+        storage: Option<CredentialStorage>,
+
+        /// Assume this IAM role with STS and keep its temporary credentials (AWS)
+        #[arg(long)]
+        role_arn: Option<String>,
+
+        /// External ID the role's trust policy requires
+        #[arg(long, requires = "role_arn")]
+        external_id: Option<String>,
+
+        /// Session length in seconds: 900-43200 for a role, 900-129600 with --mfa-serial
+        #[arg(long, requires = "temporary_session")]
+        session_duration: Option<u64>,
+
+        /// ARN or serial number of your MFA device; trades the profile's keys for an MFA session (AWS)
+        #[arg(long, conflicts_with_all = ["role_arn", "sso"])]
+        mfa_serial: Option<String>,
+
+        /// One-time code from the MFA device; prompted for when unset
+        #[arg(long, requires = "mfa_serial")]
+        mfa_code: Option<String>,
+
+        /// Sign in through IAM Identity Center (AWS SSO) instead of access keys
+        #[arg(long, requires_all = ["sso_start_url", "sso_region"], conflicts_with = "role_arn")]
+        sso: bool,
+
+        /// AWS access portal URL, e.g. https://my-org.awsapps.com/start
+        #[arg(long, requires = "sso")]
+        sso_start_url: Option<String>,
+
+        /// Region of the IAM Identity Center instance
+        #[arg(long, requires = "sso")]
+        sso_region: Option<String>,
+
+        /// Account to sign in to; picked from the accounts you can access when unset
+        #[arg(long, requires = "sso")]
+        sso_account_id: Option<String>,
+
+        /// Permission set role to use in the account; picked when unset
+        #[arg(long, requires = "sso")]
+        sso_role_name: Option<String>,
     },
 
     /// Generate cost reports for cloud resources
@@ -56,6 +185,108 @@ pub enum Commands {
         /// Profile name to use for authentication
         #[arg(short, long, default_value = "default")]
         profile: String,
+
+        /// Run against every member account of the AWS Organization
+        #[arg(long)]
+        org: bool,
+
+        /// Role to assume in each member account when using --org
+        #[arg(long, default_value = "OrganizationAccountAccessRole")]
+        org_role: String,
+
+        /// YAML file mapping account IDs to role ARNs to run against
+        #[arg(long = "accounts", conflicts_with = "org")]
+        accounts_file: Option<String>,
+
+        /// Also write the report as a PDF statement to this file
+        #[arg(long, value_name = "FILE")]
+        pdf: Option<String>,
+
+        /// Render the report with this Handlebars template instead of --format
+        #[arg(long, value_name = "FILE")]
+        template: Option<String>,
+
+        /// Write amounts the way this locale does, e.g. de-DE for 1.234,56 €
+        #[arg(long)]
+        locale: Option<String>,
+
+        /// Also report Reserved Instance and Savings Plans coverage, unused commitments and purchase recommendations (AWS)
+        #[arg(long)]
+        commitments: bool,
+
+        /// Forecast spend for this many days ahead and the month's end, alerting when either passes the budget threshold
+        #[arg(long, value_name = "DAYS")]
+        forecast: Option<u32>,
+
+        /// Length of each period costs are broken down by; hourly needs hourly data enabled in Cost Explorer (AWS)
+        #[arg(long, value_enum, default_value_t = CostGranularity::Monthly)]
+        granularity: CostGranularity,
+
+        /// What each cost line is: service, region, account, tag:<key> or usage-type (AWS)
+        #[arg(long, default_value = "service", value_name = "GROUPING")]
+        group_by: String,
+
+        /// Also split Kubernetes node costs across namespaces by the resources their pods request, from the cluster kubectl points at (AWS)
+        #[arg(long)]
+        kubernetes: bool,
+
+        /// kubectl context of the cluster to attribute instead of the current one
+        #[arg(long, requires = "kubernetes")]
+        kube_context: Option<String>,
+
+        /// Split Kubernetes costs by this pod label instead of by namespace
+        #[arg(long, value_name = "KEY", requires = "kubernetes")]
+        kube_label: Option<String>,
+
+        /// Replace the --output file if it already exists
+        #[arg(long, requires = "output")]
+        force: bool,
+    },
+
+    /// Report EC2 instance-hours per instance family and environment (AWS)
+    UsageReport {
+        /// Start date for the usage period (YYYY-MM-DD)
+        #[arg(short, long)]
+        start_date: Option<String>,
+
+        /// End date for the usage period (YYYY-MM-DD)
+        #[arg(short, long)]
+        end_date: Option<String>,
+
+        /// Tag holding each instance's environment
+        #[arg(long, default_value = "Environment")]
+        tag_key: String,
+
+        /// Output format for the report
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Estimate compute CO2e per provider and region
+    ReportCarbon {
+        /// Only report this provider
+        #[arg(short, long, value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Start date for the period (YYYY-MM-DD)
+        #[arg(short, long)]
+        start_date: Option<String>,
+
+        /// End date for the period (YYYY-MM-DD)
+        #[arg(short, long)]
+        end_date: Option<String>,
+
+        /// Output format for the report
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(long, default_value = "default")]
+        profile: String,
     },
 
     /// Auto-scale cloud resources based on usage patterns
@@ -91,17 +322,49 @@ pub enum Commands {
         /// Dry run mode (show what would be done without executing)
         #[arg(short, long)]
         dry_run: bool,
+
+        /// Replay the policy against this much metric history (e.g. 14d) instead of scaling
+        #[arg(long)]
+        backtest: Option<String>,
+
+        /// Hourly price of one instance, to put a cost on backtested capacity
+        #[arg(long)]
+        instance_price: Option<f64>,
+
+        /// Seconds after scaling a group up before it is scaled again
+        #[arg(long, default_value_t = 300)]
+        scale_up_cooldown: i32,
+
+        /// Seconds after scaling a group before it is scaled down again
+        #[arg(long, default_value_t = 600)]
+        scale_down_cooldown: i32,
+
+        /// Keep polling metrics and scaling until interrupted
+        #[arg(long, conflicts_with = "backtest")]
+        watch: bool,
+
+        /// Seconds between polls with --watch
+        #[arg(long, default_value_t = 60, requires = "watch")]
+        interval: u64,
+
+        /// Output format for the per-resource results
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
     },
 
     /// Clean up unused or underutilized resources
+    #[command(args_conflicts_with_subcommands = true)]
     Cleanup {
+        #[command(subcommand)]
+        action: Option<CleanupCommand>,
+
         /// Cloud provider to clean up resources on
         #[arg(value_enum)]
-        provider: CloudProvider,
+        provider: Option<CloudProvider>,
 
         /// Type of resource to clean up
         #[arg(value_enum)]
-        resource_type: ResourceType,
+        resource_type: Option<ResourceType>,
 
         /// Age threshold for resources to be considered for cleanup (days)
         #[arg(short, long, default_value_t = 30)]
@@ -111,6 +374,10 @@ pub enum Commands {
         #[arg(short, long, default_value_t = 10.0)]
         utilization_threshold: f64,
 
+        /// Days of CloudWatch CPU, network and disk metrics that decide whether a running instance is idle (AWS)
+        #[arg(long, default_value_t = 14)]
+        lookback_days: u32,
+
         /// Profile name to use for authentication
         #[arg(short, long, default_value = "default")]
         profile: String,
@@ -119,16 +386,76 @@ pub enum Commands {
         #[arg(short, long)]
         dry_run: bool,
 
-        /// Force cleanup without confirmation prompts
+        /// Force cleanup without confirmation prompts, replacing the --output file if it exists
         #[arg(short, long)]
         force: bool,
+
+        /// Output format for the per-resource results
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Run against every member account of the AWS Organization
+        #[arg(long)]
+        org: bool,
+
+        /// Role to assume in each member account when using --org
+        #[arg(long, default_value = "OrganizationAccountAccessRole")]
+        org_role: String,
+
+        /// YAML file mapping account IDs to role ARNs to run against
+        #[arg(long = "accounts", conflicts_with = "org")]
+        accounts_file: Option<String>,
+
+        /// Also write the cleanup summary as a PDF to this file
+        #[arg(long, value_name = "FILE")]
+        pdf: Option<String>,
+
+        /// Apply a reviewed plan from `--dry-run --format json` instead of scanning
+        #[arg(long, value_name = "FILE")]
+        plan: Option<String>,
+
+        /// Decide what to clean up with the rules in this YAML policy file
+        #[arg(long, value_name = "FILE", conflicts_with = "plan")]
+        policy: Option<String>,
+
+        /// Stop, snapshot or archive resources and tag them for `cleanup purge` after this many days, instead of deleting them (AWS)
+        #[arg(long, value_name = "DAYS", conflicts_with_all = ["org", "accounts_file", "plan_out"])]
+        quarantine: Option<u32>,
+
+        /// Also delete S3 buckets that still hold objects, deleting every object version first
+        #[arg(long, conflicts_with = "quarantine")]
+        delete_objects: bool,
+
+        /// Write the proposed actions to a signed plan for `cleanup apply` instead of running them
+        #[arg(long, value_name = "FILE", conflicts_with = "plan")]
+        plan_out: Option<String>,
+
+        /// Show who created each resource, from the activity logs
+        #[arg(long)]
+        who_created: bool,
+
+        /// Only clean up resources in this resource group (Azure)
+        #[arg(long)]
+        resource_group: Option<String>,
+
+        /// Only clean up resources tagged key=value, or carrying key at all; repeat to require several
+        #[arg(long = "include-tag", value_name = "KEY[=VALUE]")]
+        include_tags: Vec<String>,
+
+        /// Never clean up resources tagged key=value, or carrying key at all, e.g. do-not-delete=true
+        #[arg(long = "exclude-tag", value_name = "KEY[=VALUE]")]
+        exclude_tags: Vec<String>,
+
+        /// Only clean up these resources, skipping the interactive picker; repeat for several, or `-` to read them from stdin
+        #[arg(long = "id", value_name = "ID")]
+        ids: Vec<String>,
     },
 
     /// List available resources and their current status
     List {
-        /// Cloud provider to list resources from
+        /// Cloud provider to list resources from, or all of them at once
         #[arg(value_enum)]
-        provider: CloudProvider,
+        provider: ProviderSelection,
 
         /// Type of resource to list
         #[arg(value_enum)]
@@ -141,73 +468,963 @@ pub enum Commands {
         /// Output format for the list
         #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
         format: OutputFormat,
+
+        /// Run against every member account of the AWS Organization
+        #[arg(long)]
+        org: bool,
+
+        /// Role to assume in each member account when using --org
+        #[arg(long, default_value = "OrganizationAccountAccessRole")]
+        org_role: String,
+
+        /// YAML file mapping account IDs to role ARNs to run against
+        #[arg(long = "accounts", conflicts_with = "org")]
+        accounts_file: Option<String>,
+
+        /// Render the listing with this Handlebars template instead of --format
+        #[arg(long, value_name = "FILE")]
+        template: Option<String>,
+
+        /// Only list resources in this resource group (Azure)
+        #[arg(long)]
+        resource_group: Option<String>,
+
+        /// Region to list instead of the profile's region (AWS); repeat for several
+        #[arg(long = "region", value_name = "REGION", conflicts_with_all = ["org", "accounts_file"])]
+        regions: Vec<String>,
+
+        /// List every region enabled for the account (AWS)
+        #[arg(long, conflicts_with_all = ["regions", "org", "accounts_file"])]
+        all_regions: bool,
+
+        /// Only show resources tagged key=value, or carrying key at all; repeat to require several
+        #[arg(long = "tag", value_name = "KEY[=VALUE]")]
+        tags: Vec<String>,
+
+        /// Replace the --output file if it already exists
+        #[arg(long, requires = "output")]
+        force: bool,
     },
 
-    /// Configure cloud provider settings and credentials
-    Config {
-        /// Cloud provider to configure
+    /// Report idle resources across all types and regions without deleting anything
+    IdleReport {
+        /// Cloud provider to scan
         #[arg(value_enum)]
         provider: CloudProvider,
 
-        /// Profile name for the configuration
+        /// Age threshold for resources to be considered idle (days)
+        #[arg(short, long, default_value_t = 30)]
+        age_threshold: u32,
+
+        /// Utilization threshold for resources to be considered idle (percentage)
+        #[arg(short, long, default_value_t = 10.0)]
+        utilization_threshold: f64,
+
+        /// Look up the creator of resources without an owner tag in the activity logs
+        #[arg(long)]
+        who_created: bool,
+
+        /// Output format for the report
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
         #[arg(short, long, default_value = "default")]
         profile: String,
+    },
 
-        /// Set API key or access key
+    /// Show a prioritized list of savings opportunities from every analysis
+    Recommendations {
+        /// Cloud provider to analyze
+        #[arg(value_enum)]
+        provider: CloudProvider,
+
+        /// Hide recommendations saving less than this per month (USD)
+        #[arg(long, default_value_t = 0.0)]
+        min_savings: f64,
+
+        /// Output format for the recommendations
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// One-screen summary: resources, spend, idle resources, expiring credentials and recent changes
+    Overview {
+        /// Cloud provider to summarize
+        #[arg(value_enum)]
+        provider: CloudProvider,
+
+        /// Monthly budget to compare month-to-date spend against
         #[arg(short, long)]
-        api_key: Option<String>,
+        budget: Option<f64>,
 
-        /// Set secret key
+        /// Age threshold for resources to be considered idle (days)
+        #[arg(short, long, default_value_t = 30)]
+        age_threshold: u32,
+
+        /// Utilization threshold for resources to be considered idle (percentage)
+        #[arg(short, long, default_value_t = 10.0)]
+        utilization_threshold: f64,
+
+        /// Output format for the overview
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Report usage against service quotas and warn when close to a limit
+    Quotas {
+        /// Cloud provider to check
+        #[arg(value_enum)]
+        provider: CloudProvider,
+
+        /// Warn when usage is at or above this percentage of a quota
+        #[arg(short, long, default_value_t = 80.0)]
+        warn_percent: f64,
+
+        /// Output format for the quota report
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Compare spot prices and interruption rates against on-demand (AWS)
+    SpotAdvisor {
+        /// EC2 instance type to price (e.g., m5.xlarge)
         #[arg(short, long)]
-        secret_key: Option<String>,
+        instance_type: String,
 
-        /// Set region
+        /// Region to check, or "all" for every enabled region
         #[arg(short, long)]
         region: Option<String>,
 
-        /// Set project ID (for GCP)
+        /// Output format for the price list
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Analyze bucket size, age and access to recommend lifecycle policies
+    StorageAnalysis {
+        /// Cloud provider to analyze (S3 only for now)
+        #[arg(value_enum)]
+        provider: CloudProvider,
+
+        /// Write a lifecycle configuration JSON per bucket into this directory
         #[arg(short, long)]
-        project_id: Option<String>,
+        generate_lifecycle: Option<String>,
 
-        /// Set subscription ID (for Azure)
+        /// Output format for the analysis
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Move gp2 and over-provisioned io1/io2 EBS volumes to gp3 (AWS)
+    EbsOptimize {
+        /// Modify the volumes instead of only reporting them
+        #[arg(long)]
+        apply: bool,
+
+        /// Apply without confirmation prompts
         #[arg(short, long)]
-        subscription_id: Option<String>,
+        force: bool,
+
+        /// Output format for the volume report
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
     },
-}
 
-#[derive(ValueEnum, Clone, Debug)]
-pub enum CloudProvider {
-    Aws,
-    Gcp,
-    Azure,
-}
+    /// Recommend moving old, rarely restored EBS snapshots to the archive tier (AWS)
+    SnapshotArchive {
+        /// Minimum snapshot age to consider (days)
+        #[arg(short, long, default_value_t = 90)]
+        age_threshold: u32,
 
-impl fmt::Display for CloudProvider {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            CloudProvider::Aws => write!(f, "AWS"),
-            CloudProvider::Gcp => write!(f, "GCP"),
-            CloudProvider::Azure => write!(f, "Azure"),
-        }
-    }
-}
+        /// Skip snapshots restored more often than this in the last 90 days
+        #[arg(long, default_value_t = 0)]
+        max_restores: usize,
 
-#[derive(ValueEnum, Clone, Debug)]
-pub enum ResourceType {
-    Ec2,
-    S3,
-    Rds,
-    Lambda,
-    LoadBalancer,
-    Vpc,
-    Subnet,
-    SecurityGroup,
-    All,
-}
+        /// Archive the snapshots instead of only reporting them
+        #[arg(long)]
+        apply: bool,
 
-#[derive(ValueEnum, Clone, Debug)]
-pub enum OutputFormat {
-    Table,
-    Json,
-    Csv,
+        /// Apply without confirmation prompts
+        #[arg(short, long)]
+        force: bool,
+
+        /// Output format for the snapshot report
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Plan and apply a declared state for ASG capacity, power schedules and tags (AWS)
+    Reconcile {
+        /// YAML file declaring the desired state
+        #[arg(short, long)]
+        desired: String,
+
+        /// Apply the plan instead of only printing it
+        #[arg(long)]
+        apply: bool,
+
+        /// Apply without confirmation prompts
+        #[arg(short, long)]
+        force: bool,
+
+        /// Output format for the per-resource results
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Evaluate governance policies against live inventory
+    Policy {
+        #[command(subcommand)]
+        action: PolicyAction,
+    },
+
+    /// Filter the merged inventory of all providers with a query expression
+    Query {
+        /// Filter such as 'type=instance and state=stopped and tags.env!=prod and age>30d'
+        expression: String,
+
+        /// Only query this provider
+        #[arg(short, long, value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Output format for matching resources
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Export inventory or cost data as analytics-ready files
+    Export {
+        /// Cloud provider to export from
+        #[arg(value_enum)]
+        provider: CloudProvider,
+
+        /// Dataset to export
+        #[arg(short, long, value_enum)]
+        what: ExportDataset,
+
+        /// File format to write
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Parquet)]
+        format: ExportFormat,
+
+        /// Directory to write the export into
+        #[arg(short, long, default_value = "./data")]
+        out: String,
+
+        /// Start date for cost exports (YYYY-MM-DD)
+        #[arg(short, long)]
+        start_date: Option<String>,
+
+        /// End date for cost exports (YYYY-MM-DD)
+        #[arg(short, long)]
+        end_date: Option<String>,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Evaluate the alert rules defined in actlog.toml
+    Alerts {
+        #[command(subcommand)]
+        action: AlertsAction,
+    },
+
+    /// Compare inventory snapshots to spot drift
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// Export the resource dependency graph for visualization
+    Graph {
+        /// Cloud provider to build the graph for
+        #[arg(value_enum)]
+        provider: CloudProvider,
+
+        /// Output format for the graph
+        #[arg(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Find DNS records pointing at addresses or load balancers that no longer exist
+    DnsCheck {
+        /// Cloud provider whose DNS zones to check
+        #[arg(value_enum)]
+        provider: CloudProvider,
+
+        /// Output format for the dangling records
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Chart CPU, network or disk metrics of one instance
+    Metrics {
+        /// Cloud provider; inferred from the ID when omitted
+        #[arg(long, value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Instance ID (AWS, GCP) or VM resource ID (Azure)
+        #[arg(long)]
+        id: String,
+
+        /// Metrics to chart, comma separated
+        #[arg(short, long, value_enum, value_delimiter = ',', default_value = "cpu")]
+        metric: Vec<MetricKind>,
+
+        /// How far back to look, e.g. 6h, 24h or 7d
+        #[arg(short, long, default_value = "24h")]
+        window: String,
+
+        /// Region of the instance (AWS); the profile's region by default
+        #[arg(short, long)]
+        region: Option<String>,
+
+        /// Output format for the series
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(long, default_value = "default")]
+        profile: String,
+    },
+
+    /// List enabled regions, the services actlog manages in each and their latency
+    Regions {
+        /// Cloud provider to list regions for
+        #[arg(value_enum)]
+        provider: CloudProvider,
+
+        /// Time a connection to each region's endpoint from this machine (AWS)
+        #[arg(long)]
+        latency: bool,
+
+        /// Output format for the list
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Bulk operations on the objects in an S3 bucket
+    S3 {
+        #[command(subcommand)]
+        action: S3Action,
+    },
+
+    /// Bulk tagging across AWS accounts and GCP projects
+    Tags {
+        #[command(subcommand)]
+        action: TagsAction,
+    },
+
+    /// Find who created a resource from the provider's activity logs
+    WhoCreated {
+        /// Cloud provider; inferred from the ID when omitted
+        #[arg(long, value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Resource ID (AWS), name (GCP) or resource ID (Azure)
+        #[arg(long)]
+        id: String,
+
+        /// Region whose CloudTrail history to search (AWS)
+        #[arg(short, long)]
+        region: Option<String>,
+
+        /// Output format for the result
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Replace this binary with the latest GitHub release
+    SelfUpdate {
+        /// Only report whether a newer release exists
+        #[arg(long)]
+        check: bool,
+
+        /// Skip confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Lock a resource so cleanup, scaling and power schedules leave it alone
+    #[command(args_conflicts_with_subcommands = true)]
+    Lock {
+        #[command(subcommand)]
+        action: Option<LockAction>,
+
+        /// Resource ID, instance or auto-scaling group name to lock
+        resource_id: Option<String>,
+
+        /// Last day the lock holds (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Why the resource is locked, shown when automation skips it
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Run actlog commands on cron schedules
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleAction,
+    },
+
+    /// Configure cloud provider settings and credentials
+    #[command(args_conflicts_with_subcommands = true)]
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+
+        /// Cloud provider to configure
+        #[arg(value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Profile name for the configuration
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+
+        /// Set API key or access key
+        #[arg(short, long)]
+        api_key: Option<String>,
+
+        /// Set secret key
+        #[arg(short, long)]
+        secret_key: Option<String>,
+
+        /// Set region
+        #[arg(short, long)]
+        region: Option<String>,
+
+        /// Set project ID (for GCP)
+        #[arg(short, long)]
+        project_id: Option<String>,
+
+        /// Set subscription ID (for Azure)
+        #[arg(short, long)]
+        subscription_id: Option<String>,
+    },
+
+    /// Check the credentials of every configured profile and suggest fixes
+    Doctor {
+        /// Only check this provider
+        #[arg(value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Only check this profile
+        #[arg(short, long)]
+        profile: Option<String>,
+
+        /// Skip the API and permission checks
+        #[arg(long)]
+        offline: bool,
+
+        /// Output format for the results
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Live terminal dashboard of resources and cost trends
+    Dashboard {
+        /// Provider shown first; switch with the arrow keys
+        #[arg(value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Credentials profile to use
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+
+        /// Seconds between resource refreshes (costs refresh at most hourly)
+        #[arg(long, value_name = "SECONDS", default_value_t = 60)]
+        interval: u64,
+    },
+
+    /// Re-run a session recorded with --record against its recorded responses, offline
+    Replay {
+        /// Session file written by --record
+        file: String,
+    },
+}
+
+impl Commands {
+    /// The credentials profile the command runs with, for commands that take one.
+    pub fn profile(&self) -> Option<&str> {
+        match self {
+            // Before the general arm, which would take the unused top-level profile
+            Commands::Cleanup {
+                action:
+                    Some(
+                        CleanupCommand::Apply { profile, .. }
+                        | CleanupCommand::Purge { profile, .. }
+                        | CleanupCommand::Restore { profile, .. },
+                    ),
+                ..
+            } => Some(profile),
+            Commands::Authenticate { profile, .. }
+            | Commands::ReportCosts { profile, .. }
+            | Commands::UsageReport { profile, .. }
+            | Commands::ReportCarbon { profile, .. }
+            | Commands::ScaleInstances { profile, .. }
+            | Commands::Cleanup { profile, .. }
+            | Commands::List { profile, .. }
+            | Commands::IdleReport { profile, .. }
+            | Commands::Recommendations { profile, .. }
+            | Commands::Overview { profile, .. }
+            | Commands::Quotas { profile, .. }
+            | Commands::SpotAdvisor { profile, .. }
+            | Commands::StorageAnalysis { profile, .. }
+            | Commands::EbsOptimize { profile, .. }
+            | Commands::SnapshotArchive { profile, .. }
+            | Commands::Reconcile { profile, .. }
+            | Commands::Export { profile, .. }
+            | Commands::Graph { profile, .. }
+            | Commands::DnsCheck { profile, .. }
+            | Commands::Metrics { profile, .. }
+            | Commands::Regions { profile, .. }
+            | Commands::Config { profile, .. }
+            | Commands::Dashboard { profile, .. }
+            | Commands::S3 {
+                action: S3Action::Purge { profile, .. },
+            }
+            | Commands::Tags {
+                action: TagsAction::Propagate { profile, .. },
+            } => Some(profile),
+            Commands::Doctor {
+                profile: Some(profile),
+                ..
+            } => Some(profile),
+            _ => None,
+        }
+    }
+
+    /// The output format of commands whose output `--output` can redirect.
+    /// Whether the command's `--force` lets `--output` replace an existing file.
+    pub fn output_force(&self) -> bool {
+        match self {
+            Commands::List { force, .. }
+            | Commands::ReportCosts { force, .. }
+            | Commands::Cleanup { force, .. } => *force,
+            _ => false,
+        }
+    }
+
+    pub fn format_mut(&mut self) -> Option<&mut OutputFormat> {
+        match self {
+            Commands::List { format, .. }
+            | Commands::ReportCosts { format, .. }
+            | Commands::Cleanup { format, .. } => Some(format),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Write an encrypted backup of all actlog configuration and credentials
+    Backup {
+        /// File to write the encrypted backup to
+        #[arg(short, long, default_value = "actlog-backup.age")]
+        out: String,
+
+        /// Read the passphrase from the first line of stdin instead of prompting
+        #[arg(long)]
+        passphrase_stdin: bool,
+    },
+
+    /// Restore configuration and credentials from an encrypted backup
+    Restore {
+        /// Encrypted backup file created by `config backup`
+        file: String,
+
+        /// Read the passphrase from the first line of stdin instead of prompting
+        #[arg(long)]
+        passphrase_stdin: bool,
+
+        /// Overwrite existing files without confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Move secrets saved in plaintext in credentials.json into the OS keyring
+    MigrateCredentials,
+}
+
+#[derive(Subcommand)]
+pub enum LockAction {
+    /// Show the locks in force
+    List {
+        /// Output format for the list
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Remove the lock on a resource
+    Release {
+        /// Resource ID the lock was taken on
+        resource_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CleanupCommand {
+    /// Carry out exactly the actions of a plan written by --plan-out
+    Apply {
+        /// Signed plan file
+        plan: String,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+
+        /// Apply without the confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+
+        /// Output format for the per-resource results
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Permanently delete quarantined resources past their quarantine window
+    Purge {
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+
+        /// Show what is quarantined and due without deleting anything
+        #[arg(short, long)]
+        dry_run: bool,
+
+        /// Purge without the confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+
+        /// Output format for the per-resource results
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Bring a quarantined resource back into service
+    Restore {
+        /// ID of the quarantined instance or bucket
+        resource_id: String,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ScheduleAction {
+    /// Schedule an actlog command, e.g. `add nightly --cron '0 3 * * *' -- cleanup aws ec2 --force`
+    Add {
+        /// Name to list, run and remove the job by
+        name: String,
+
+        /// Cron expression in local time: minute hour day-of-month month day-of-week
+        #[arg(long)]
+        cron: String,
+
+        /// actlog arguments to run, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Show the scheduled jobs and when they run next
+    List {
+        /// Output format for the list
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+
+    /// Remove a scheduled job
+    Remove {
+        /// Name of the job
+        name: String,
+    },
+
+    /// Run a scheduled job now
+    Run {
+        /// Name of the job
+        name: String,
+    },
+
+    /// Keep running and execute each job when its schedule comes due
+    Daemon {
+        /// SQS queue URL an EventBridge rule sends EC2 state changes and
+        /// volume detachments to; each affected resource is evaluated for cleanup
+        #[arg(long, value_name = "URL")]
+        events_queue: Option<String>,
+
+        /// Clean up resources events flag instead of only reporting them
+        #[arg(long, requires = "events_queue")]
+        events_force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum S3Action {
+    /// Permanently delete object versions and delete markers matching a key pattern
+    Purge {
+        /// Bucket to purge
+        bucket: String,
+
+        /// Key pattern to delete, e.g. 'logs/2023/*'; all keys when omitted
+        #[arg(long)]
+        include: Option<String>,
+
+        /// Only delete versions last modified longer ago than this, e.g. 365d
+        #[arg(long)]
+        older_than: Option<String>,
+
+        /// Show what would be deleted without deleting it
+        #[arg(short, long)]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagsAction {
+    /// Tag instances with their account's or project's metadata where missing
+    Propagate {
+        /// YAML file mapping AWS account IDs and GCP project IDs to tags
+        #[arg(short, long)]
+        mapping: String,
+
+        /// Role to assume in each mapped AWS account
+        #[arg(long, default_value = "OrganizationAccountAccessRole")]
+        org_role: String,
+
+        /// Add the tags instead of only printing the plan
+        #[arg(long)]
+        apply: bool,
+
+        /// Apply without confirmation prompts
+        #[arg(short, long)]
+        force: bool,
+
+        /// Output format for the per-resource results
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+
+        /// Profile name to use for authentication
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AlertsAction {
+    /// Show the configured alert rules
+    List,
+
+    /// Evaluate every rule and notify the configured sinks
+    Evaluate {
+        /// Evaluate rules without sending notifications
+        #[arg(short, long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Show resources added, removed or changed since an earlier snapshot
+    Diff {
+        /// Age of the snapshot to compare against, e.g. 24h or 7d
+        #[arg(short, long, default_value = "24h")]
+        since: String,
+
+        /// Only diff this provider
+        #[arg(short, long, value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Send any drift to the sinks configured in actlog.toml
+        #[arg(short, long)]
+        notify: bool,
+
+        /// Output format for the drift
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PolicyAction {
+    /// Check a cleanup policy file, or a directory of policies, without scanning anything
+    Validate {
+        /// Cleanup policy file for `cleanup --policy`, or a directory for `policy check`
+        path: String,
+    },
+
+    /// Check every policy and exit non-zero on violations
+    Check {
+        /// Directory of YAML policy files
+        #[arg(short = 'd', long, default_value = "./policies")]
+        policies: String,
+
+        /// Only check resources from this provider
+        #[arg(short, long, value_enum)]
+        provider: Option<CloudProvider>,
+
+        /// Output format for results
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum CloudProvider {
+    Aws,
+    Gcp,
+    Azure,
+}
+
+impl fmt::Display for CloudProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloudProvider::Aws => write!(f, "AWS"),
+            CloudProvider::Gcp => write!(f, "GCP"),
+            CloudProvider::Azure => write!(f, "Azure"),
+        }
+    }
+}
+
+/// One provider, or every provider queried concurrently.
+#[derive(ValueEnum, Clone, Debug)]
+pub enum ProviderSelection {
+    Aws,
+    Gcp,
+    Azure,
+    All,
+}
+
+impl ProviderSelection {
+    pub fn providers(&self) -> Vec<CloudProvider> {
+        match self {
+            ProviderSelection::Aws => vec![CloudProvider::Aws],
+            ProviderSelection::Gcp => vec![CloudProvider::Gcp],
+            ProviderSelection::Azure => vec![CloudProvider::Azure],
+            ProviderSelection::All => {
+                vec![CloudProvider::Aws, CloudProvider::Gcp, CloudProvider::Azure]
+            }
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum ResourceType {
+    Ec2,
+    S3,
+    Rds,
+    Lambda,
+    LoadBalancer,
+    Vpc,
+    Subnet,
+    SecurityGroup,
+    StaticIp,
+    Volume,
+    Snapshot,
+    NetworkInterface,
+    Iam,
+    Dns,
+    Cdn,
+    All,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+    Yaml,
+    Toml,
+    /// One JSON object per line, written as results arrive
+    Jsonl,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
+pub enum CostGranularity {
+    Hourly,
+    Daily,
+    Monthly,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum ExportDataset {
+    Inventory,
+    Costs,
+    /// Costs in the FinOps FOCUS 1.0 column layout
+    Focus,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum ExportFormat {
+    Parquet,
+    Csv,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum MetricKind {
+    Cpu,
+    Network,
+    Disk,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
 }
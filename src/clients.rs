@@ -0,0 +1,168 @@
+use crate::cache;
+use crate::cli::CloudProvider;
+use crate::commands::authenticate::session_credentials;
+use crate::commands::config::stored_config;
+use crate::error::AppError;
+use crate::retry;
+use crate::session;
+use aws_config::{ConfigLoader, Region, SdkConfig};
+use colored::*;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::OnceCell;
+
+/// An AWS SDK client the registry can build from a config.
+pub trait AwsClient: Clone + Send + Sync + 'static {
+    const SERVICE: &'static str;
+
+    fn from_config(config: &SdkConfig) -> Self;
+}
+
+macro_rules! aws_clients {
+    ($($client:ty => $service:literal),* $(,)?) => {
+        $(
+            impl AwsClient for $client {
+                const SERVICE: &'static str = $service;
+
+                fn from_config(config: &SdkConfig) -> Self {
+                    <$client>::new(config)
+                }
+            }
+        )*
+    };
+}
+
+aws_clients! {
+    aws_sdk_autoscaling::Client => "autoscaling",
+    aws_sdk_cloudfront::Client => "cloudfront",
+    aws_sdk_cloudtrail::Client => "cloudtrail",
+    aws_sdk_cloudwatch::Client => "cloudwatch",
+    aws_sdk_costexplorer::Client => "costexplorer",
+    aws_sdk_ec2::Client => "ec2",
+    aws_sdk_elasticloadbalancingv2::Client => "elbv2",
+    aws_sdk_iam::Client => "iam",
+    aws_sdk_lambda::Client => "lambda",
+    aws_sdk_organizations::Client => "organizations",
+    aws_sdk_pricing::Client => "pricing",
+    aws_sdk_rds::Client => "rds",
+    aws_sdk_route53::Client => "route53",
+    aws_sdk_s3::Client => "s3",
+    aws_sdk_servicequotas::Client => "servicequotas",
+    aws_sdk_sqs::Client => "sqs",
+    aws_sdk_ssm::Client => "ssm",
+    aws_sdk_sts::Client => "sts",
+}
+
+static CONFIG: OnceCell<SdkConfig> = OnceCell::const_new();
+
+/// Named AWS profile and region the command runs with, set once at startup.
+static PROFILE: OnceLock<AwsProfile> = OnceLock::new();
+
+#[derive(Debug)]
+struct AwsProfile {
+    /// The `--profile` the command runs with
+    name: String,
+    /// Region saved with `actlog config aws --profile <name>`
+    region: Option<String>,
+}
+
+impl Default for AwsProfile {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            region: None,
+        }
+    }
+}
+
+/// Clients built so far, keyed by service, region and credentials.
+static CLIENTS: OnceLock<Mutex<HashMap<String, Box<dyn Any + Send + Sync>>>> = OnceLock::new();
+
+/// Load AWS credentials from `profile` in the shared AWS config files, in
+/// the region saved for it by `actlog config`. The `default` profile keeps
+/// the SDK's usual lookup so `AWS_PROFILE` still applies.
+pub fn configure(profile: Option<&str>) -> Result<(), AppError> {
+    let profile = profile.unwrap_or("default");
+    let region = stored_config(&CloudProvider::Aws, profile)?.and_then(|c| c.region);
+
+    let _ = PROFILE.set(AwsProfile {
+        name: profile.to_string(),
+        region: region.filter(|r| !r.is_empty()),
+    });
+    Ok(())
+}
+
+/// The command's AWS config, loaded on first use and shared afterwards.
+pub async fn aws_config() -> SdkConfig {
+    CONFIG.get_or_init(load_aws_config).await.clone()
+}
+
+/// The profile's config. A profile authenticated with `--role-arn` or
+/// `--sso` uses its temporary credentials, refreshed first when they are
+/// about to expire.
+async fn load_aws_config() -> SdkConfig {
+    let profile = PROFILE.get_or_init(AwsProfile::default);
+    let base = profile_config().await;
+    if session::replaying() {
+        return base;
+    }
+
+    match session_credentials(&profile.name, &base).await {
+        Ok(Some(credentials)) => {
+            profile_loader(profile)
+                .credentials_provider(credentials)
+                .load()
+                .await
+        }
+        Ok(None) => base,
+        Err(e) => {
+            eprintln!(
+                "   ⚠️  Using the profile's own credentials: {}",
+                e.to_string().yellow()
+            );
+            base
+        }
+    }
+}
+
+/// The AWS config of the command's profile, ignoring any session from
+/// `authenticate --role-arn` or `--sso`.
+pub async fn profile_config() -> SdkConfig {
+    profile_loader(PROFILE.get_or_init(AwsProfile::default))
+        .load()
+        .await
+}
+
+fn profile_loader(profile: &AwsProfile) -> ConfigLoader {
+    let mut loader = retry::aws_loader();
+    if profile.name != "default" {
+        loader = loader.profile_name(&profile.name);
+    }
+    // AWS_REGION still overrides the saved region for a single run
+    let region_from_env = ["AWS_REGION", "AWS_DEFAULT_REGION"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some());
+    if let Some(region) = profile.region.clone().filter(|_| !region_from_env) {
+        loader = loader.region(Region::new(region));
+    }
+    loader
+}
+
+/// Client for `C`'s service built from `config`. Later calls for the same
+/// service, region and credentials get the same client and its connection
+/// pool instead of a new one.
+pub async fn get<C: AwsClient>(config: &SdkConfig) -> C {
+    let key = format!("{}:{}", C::SERVICE, cache::aws_scope(config).await);
+
+    let mut clients = CLIENTS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .expect("client registry lock poisoned");
+    clients
+        .entry(key)
+        .or_insert_with(|| Box::new(C::from_config(config)))
+        .downcast_ref::<C>()
+        .expect("client registered under another service")
+        .clone()
+}
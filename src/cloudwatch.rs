@@ -0,0 +1,156 @@
+use crate::commands::list::aws_datetime;
+use crate::error::AppError;
+use crate::executor;
+use aws_sdk_cloudwatch::types::{Dimension, Metric, MetricDataQuery, MetricStat, Statistic};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::BTreeMap;
+
+/// Most series a single GetMetricData call may ask for
+const MAX_QUERIES_PER_CALL: usize = 500;
+
+/// Datapoints of one series by timestamp, oldest first.
+pub type Series = BTreeMap<DateTime<Utc>, f64>;
+
+/// One metric series to read: a metric of one resource and the statistic
+/// taken each period.
+#[derive(Debug, Clone)]
+pub struct MetricQuery {
+    pub namespace: &'static str,
+    pub metric_name: String,
+    pub dimensions: Vec<(&'static str, String)>,
+    pub statistic: Statistic,
+}
+
+impl MetricQuery {
+    /// A metric of the resource identified by a single dimension, e.g.
+    /// `AWS/EC2` `CPUUtilization` for `InstanceId` `i-0abc`.
+    pub fn new(
+        namespace: &'static str,
+        metric_name: &str,
+        dimension: &'static str,
+        value: &str,
+        statistic: Statistic,
+    ) -> Self {
+        MetricQuery {
+            namespace,
+            metric_name: metric_name.to_string(),
+            dimensions: vec![(dimension, value.to_string())],
+            statistic,
+        }
+    }
+
+    fn to_data_query(&self, id: usize, period: i64) -> MetricDataQuery {
+        let metric = Metric::builder()
+            .namespace(self.namespace)
+            .metric_name(&self.metric_name)
+            .set_dimensions(Some(
+                self.dimensions
+                    .iter()
+                    .map(|(name, value)| Dimension::builder().name(*name).value(value).build())
+                    .collect(),
+            ))
+            .build();
+        MetricDataQuery::builder()
+            .id(format!("m{}", id))
+            .metric_stat(
+                MetricStat::builder()
+                    .metric(metric)
+                    .period(period as i32)
+                    .stat(self.statistic.as_str())
+                    .build(),
+            )
+            .return_data(true)
+            .build()
+    }
+}
+
+/// Datapoints of every query between `start` and `end`, one per `period`
+/// seconds, in the order of `queries`. Queries go out in GetMetricData calls
+/// of up to 500 series, and those calls run concurrently within the shared
+/// parallelism, so a scan makes one request per 500 resources instead of
+/// one per resource. CloudWatch still bills each series queried.
+pub async fn fetch(
+    client: &aws_sdk_cloudwatch::Client,
+    queries: &[MetricQuery],
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+    period: i64,
+) -> Result<Vec<Series>, AppError> {
+    let batches: Vec<Vec<Series>> = stream::iter(queries.chunks(MAX_QUERIES_PER_CALL))
+        .map(|batch| fetch_batch(client, batch, start, end, period))
+        .buffered(executor::parallelism())
+        .try_collect()
+        .await?;
+    Ok(batches.into_iter().flatten().collect())
+}
+
+/// Average of each series over all its datapoints, `None` where CloudWatch
+/// had no data, e.g. for a resource that didn't exist during the window.
+pub fn averages(series: &[Series]) -> Vec<Option<f64>> {
+    series
+        .iter()
+        .map(|points| {
+            (!points.is_empty()).then(|| points.values().sum::<f64>() / points.len() as f64)
+        })
+        .collect()
+}
+
+async fn fetch_batch(
+    client: &aws_sdk_cloudwatch::Client,
+    batch: &[MetricQuery],
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+    period: i64,
+) -> Result<Vec<Series>, AppError> {
+    let data_queries: Vec<MetricDataQuery> = batch
+        .iter()
+        .enumerate()
+        .map(|(id, query)| query.to_data_query(id, period))
+        .collect();
+    let mut series = vec![Series::new(); batch.len()];
+
+    // Large windows come back in pages of at most 100,800 datapoints
+    let mut next_token = None;
+    loop {
+        executor::throttle("cloudwatch").await;
+        // Billed per metric queried, not per call
+        executor::bill_units("cloudwatch", data_queries.len() as u64);
+        let response = client
+            .get_metric_data()
+            .set_metric_data_queries(Some(data_queries.clone()))
+            .start_time(aws_sdk_cloudwatch::primitives::DateTime::from_secs(
+                start.timestamp(),
+            ))
+            .end_time(aws_sdk_cloudwatch::primitives::DateTime::from_secs(
+                end.timestamp(),
+            ))
+            .set_next_token(next_token)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("cloudwatch:GetMetricData", e))?;
+
+        for result in response.metric_data_results() {
+            let Some(points) = result
+                .id()
+                .and_then(|id| id.strip_prefix('m'))
+                .and_then(|index| index.parse::<usize>().ok())
+                .and_then(|index| series.get_mut(index))
+            else {
+                continue;
+            };
+            for (timestamp, value) in result.timestamps().iter().zip(result.values()) {
+                if let Some(timestamp) = aws_datetime(Some(timestamp)) {
+                    points.insert(timestamp, *value);
+                }
+            }
+        }
+
+        next_token = response.next_token().map(str::to_string);
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(series)
+}
@@ -0,0 +1,307 @@
+use crate::budget;
+use crate::cli::{AlertsAction, CloudProvider, Commands, ResourceType};
+use crate::clients;
+use crate::commands::cleanup::{analyze_cleanup_actions, CleanupAction, TagSelection};
+use crate::commands::cost_report::{determine_date_range, CostReport};
+use crate::commands::scaling::ScalingPolicy;
+use crate::error::AppError;
+use crate::notify::{self, Notification};
+use crate::providers;
+use crate::settings::{self, AlertMetric, AlertRule, Settings, SinkConfig, SinkKind};
+use chrono::Utc;
+use clap::ValueEnum;
+use colored::*;
+use std::collections::HashMap;
+
+/// Data fetched for one provider, shared by every rule that needs it.
+#[derive(Default)]
+struct ProviderData {
+    cost_report: Option<CostReport>,
+    idle: Option<Vec<CleanupAction>>,
+    resource_counts: HashMap<String, usize>,
+    scaling_actions: Option<usize>,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn alerts(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Alerts { action } = cmd {
+        let settings = settings::load_settings()?;
+
+        match action {
+            AlertsAction::List => list_rules(&settings)?,
+            AlertsAction::Evaluate { dry_run } => evaluate_rules(&settings, *dry_run).await?,
+        }
+    }
+
+    Ok(())
+}
+
+fn list_rules(settings: &Settings) -> Result<(), AppError> {
+    if settings.alerts.is_empty() {
+        println!(
+            "ℹ️  No alert rules configured. Add [[alerts]] entries to {}",
+            settings::settings_path()?.display()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{:<25} {:<8} {:<16} {:<14} {:<10} {:<20}",
+        "Name", "Provider", "Metric", "Condition", "Severity", "Sinks"
+    );
+    println!("{:-<95}", "");
+    for rule in &settings.alerts {
+        println!(
+            "{:<25} {:<8} {:<16} {:<14} {:<10} {:<20}",
+            rule.name,
+            rule.provider,
+            format!("{:?}", rule.metric),
+            format!("{} {}", rule.operator.symbol(), rule.threshold),
+            rule.severity,
+            if rule.sinks.is_empty() {
+                "all".to_string()
+            } else {
+                rule.sinks.join(",")
+            }
+        );
+    }
+
+    Ok(())
+}
+
+async fn evaluate_rules(settings: &Settings, dry_run: bool) -> Result<(), AppError> {
+    if settings.alerts.is_empty() {
+        println!("ℹ️  No alert rules configured.");
+        return Ok(());
+    }
+
+    tracing::info!("🚨 Evaluating {} alert rules...", settings.alerts.len());
+
+    // Without configured sinks, alerts still reach the terminal
+    let sinks = if settings.sinks.is_empty() {
+        vec![SinkConfig {
+            name: "stdout".to_string(),
+            kind: SinkKind::Stdout,
+            url: None,
+        }]
+    } else {
+        settings.sinks.clone()
+    };
+
+    let mut data: HashMap<String, ProviderData> = HashMap::new();
+    let mut triggered = 0;
+
+    for rule in &settings.alerts {
+        let provider = CloudProvider::from_str(&rule.provider, true).map_err(|_| {
+            AppError::ConfigurationError(format!(
+                "Alert '{}' has unknown provider '{}'",
+                rule.name, rule.provider
+            ))
+        })?;
+        let provider_data = data.entry(rule.provider.to_lowercase()).or_default();
+
+        let value = match metric_value(rule, &provider, provider_data).await {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("⚠️  Could not evaluate '{}': {}", rule.name.yellow(), e);
+                continue;
+            }
+        };
+
+        if !rule.operator.matches(value, rule.threshold) {
+            println!(
+                "   ✅ {}: {:.2} (threshold {} {:.2})",
+                rule.name,
+                value,
+                rule.operator.symbol(),
+                rule.threshold
+            );
+            continue;
+        }
+
+        triggered += 1;
+        println!(
+            "   🔥 {}: {:.2} {} {:.2}",
+            rule.name.red(),
+            value,
+            rule.operator.symbol(),
+            rule.threshold
+        );
+
+        let notification = Notification {
+            title: format!("Alert '{}' triggered", rule.name),
+            message: format!(
+                "{} {:?} is {:.2} ({} {:.2})",
+                provider,
+                rule.metric,
+                value,
+                rule.operator.symbol(),
+                rule.threshold
+            ),
+            severity: rule.severity.clone(),
+            source: "alerts".to_string(),
+        };
+
+        if dry_run {
+            continue;
+        }
+
+        for sink in sinks
+            .iter()
+            .filter(|s| rule.sinks.is_empty() || rule.sinks.contains(&s.name))
+        {
+            if let Err(e) = notify::send(sink, &notification).await {
+                tracing::warn!("⚠️  Failed to notify sink '{}': {}", sink.name, e);
+            }
+        }
+    }
+
+    println!(
+        "\n📊 {} of {} rules triggered{}",
+        triggered,
+        settings.alerts.len(),
+        if dry_run {
+            " (dry run - nothing sent)"
+        } else {
+            ""
+        }
+    );
+
+    Ok(())
+}
+
+async fn metric_value(
+    rule: &AlertRule,
+    provider: &CloudProvider,
+    data: &mut ProviderData,
+) -> Result<f64, AppError> {
+    match rule.metric {
+        AlertMetric::TotalCost => Ok(cost_report(provider, data).await?.total_cost),
+
+        AlertMetric::ServiceCost => {
+            let service = rule.service.as_deref().ok_or_else(|| {
+                AppError::ConfigurationError("service_cost rules need a service".to_string())
+            })?;
+            Ok(cost_report(provider, data)
+                .await?
+                .services
+                .iter()
+                .filter(|s| s.service_name == service)
+                .map(|s| s.cost)
+                .sum())
+        }
+
+        AlertMetric::ResourceCount => {
+            let name = rule.resource_type.as_deref().ok_or_else(|| {
+                AppError::ConfigurationError(
+                    "resource_count rules need a resource_type".to_string(),
+                )
+            })?;
+            let resource_type = ResourceType::from_str(name, true).map_err(|_| {
+                AppError::ConfigurationError(format!("Unknown resource type '{}'", name))
+            })?;
+
+            if let Some(count) = data.resource_counts.get(name) {
+                return Ok(*count as f64);
+            }
+
+            let resources = providers::get(provider).list(&resource_type).await?;
+            data.resource_counts
+                .insert(name.to_string(), resources.len());
+            Ok(resources.len() as f64)
+        }
+
+        AlertMetric::IdleCount => Ok(idle_actions(provider, data).await?.len() as f64),
+
+        AlertMetric::IdleCost => Ok(idle_actions(provider, data)
+            .await?
+            .iter()
+            .map(|a| a.estimated_savings)
+            .sum()),
+
+        AlertMetric::ProjectedCost => {
+            let today = Utc::now().date_naive();
+            let (month_to_date, forecast) = match provider {
+                CloudProvider::Aws => {
+                    let config = clients::aws_config().await;
+                    budget::aws_spend(&config, today).await?
+                }
+                _ => {
+                    let (start, end) = determine_date_range(&None, &None)?;
+                    let total = cost_report(provider, data).await?.total_cost;
+                    let days = (end - start).num_days();
+                    (budget::month_to_date_from_average(total, days, today), None)
+                }
+            };
+            Ok(budget::project(rule.threshold, month_to_date, forecast, today).projected_total)
+        }
+
+        AlertMetric::ScalingActions => {
+            if let Some(count) = data.scaling_actions {
+                return Ok(count as f64);
+            }
+
+            // Evaluate against the CLI's default scaling policy
+            let policy = ScalingPolicy {
+                min_instances: 1,
+                max_instances: 10,
+                cpu_threshold: 70.0,
+                memory_threshold: 80.0,
+                scale_up_cooldown: 300,
+                scale_down_cooldown: 600,
+            };
+            let actions = providers::get(provider).scale(&policy, &None).await?;
+            data.scaling_actions = Some(actions.len());
+            Ok(actions.len() as f64)
+        }
+    }
+}
+
+async fn cost_report<'a>(
+    provider: &CloudProvider,
+    data: &'a mut ProviderData,
+) -> Result<&'a CostReport, AppError> {
+    if data.cost_report.is_none() {
+        let (start, end) = determine_date_range(&None, &None)?;
+        let report = providers::get(provider)
+            .cost_report(&start, &end, &None)
+            .await?;
+        data.cost_report = Some(report);
+    }
+
+    Ok(data
+        .cost_report
+        .as_ref()
+        .expect("cost report was just loaded"))
+}
+
+async fn idle_actions<'a>(
+    provider: &CloudProvider,
+    data: &'a mut ProviderData,
+) -> Result<&'a [CleanupAction], AppError> {
+    if data.idle.is_none() {
+        // Same defaults as `actlog cleanup`
+        let candidates = providers::get(provider);
+        let mut resources = candidates
+            .find_cleanup_candidates(&ResourceType::Ec2, 30, 10.0)
+            .await?;
+        if let CloudProvider::Aws = provider {
+            resources.extend(
+                candidates
+                    .find_cleanup_candidates(&ResourceType::S3, 30, 10.0)
+                    .await?,
+            );
+        }
+        data.idle = Some(analyze_cleanup_actions(
+            &resources,
+            provider,
+            &TagSelection::default(),
+            None,
+        )?);
+    }
+
+    Ok(data
+        .idle
+        .as_deref()
+        .expect("idle resources were just loaded"))
+}
@@ -1,10 +1,34 @@
+use crate::azure;
 use crate::cli::{CloudProvider, Commands};
+use crate::clients;
 use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::picker;
+use crate::secrets;
+use crate::settings::{self, CredentialStorage};
+use crate::sso::{self, SsoRole};
+use aws_config::sts::AssumeRoleProvider;
+use aws_config::SdkConfig;
+use aws_credential_types::provider::ProvideCredentials;
+use aws_credential_types::Credentials;
+use chrono::{DateTime, Utc};
 use colored::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// STS limits on a role session, in seconds
+const MIN_SESSION_SECONDS: u64 = 900;
+const MAX_SESSION_SECONDS: u64 = 43_200;
+
+/// GetSessionToken allows IAM users up to 36 hours
+const MAX_MFA_SESSION_SECONDS: u64 = 129_600;
+
+/// Sessions this close to expiring are renewed before a command uses them
+const REFRESH_MARGIN_MINUTES: i64 = 5;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CloudCredentials {
@@ -17,6 +41,81 @@ pub struct CloudCredentials {
     pub subscription_id: Option<String>,
     pub token: Option<String>,
     pub expires_at: Option<String>,
+    /// The access key, secret key and token are in the OS keyring rather
+    /// than in this file
+    #[serde(default)]
+    pub in_keyring: bool,
+    /// Role the credentials are a session of, so they can be renewed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assumed_role: Option<AssumedRole>,
+    /// IAM Identity Center role the credentials were issued for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sso: Option<SsoRole>,
+    /// MFA device the session was opened with; it ends at `expires_at`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mfa_serial: Option<String>,
+}
+
+/// An IAM role assumed by `authenticate --role-arn`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssumedRole {
+    pub role_arn: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    /// Requested session length; the role's default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_seconds: Option<u64>,
+}
+
+/// The parts of [`CloudCredentials`] kept in the OS keyring.
+#[derive(Serialize, Deserialize)]
+struct Secrets {
+    access_key: Option<String>,
+    secret_key: Option<String>,
+    token: Option<String>,
+}
+
+impl CloudCredentials {
+    fn has_secrets(&self) -> bool {
+        self.access_key.is_some() || self.secret_key.is_some() || self.token.is_some()
+    }
+
+    /// Move the secrets into the OS keyring under `key`, leaving only what
+    /// is safe to write to credentials.json.
+    fn seal(&mut self, key: &str) -> Result<(), AppError> {
+        let secrets = Secrets {
+            access_key: self.access_key.clone(),
+            secret_key: self.secret_key.clone(),
+            token: self.token.clone(),
+        };
+        secrets::store(key, &serde_json::to_string(&secrets)?)?;
+
+        self.access_key = None;
+        self.secret_key = None;
+        self.token = None;
+        self.in_keyring = true;
+        Ok(())
+    }
+
+    /// Bring back the secrets [`CloudCredentials::seal`] moved to the keyring.
+    fn unseal(&mut self, key: &str) -> Result<(), AppError> {
+        if !self.in_keyring {
+            return Ok(());
+        }
+
+        let stored = secrets::load(key)?.ok_or_else(|| {
+            AppError::AuthenticationError(format!(
+                "Credentials for {} are missing from the OS keyring; run `actlog authenticate` again",
+                key
+            ))
+        })?;
+        let secrets: Secrets = serde_json::from_str(&stored)?;
+        self.access_key = secrets.access_key;
+        self.secret_key = secrets.secret_key;
+        self.token = secrets.token;
+        self.in_keyring = false;
+        Ok(())
+    }
 }
 
 pub async fn authenticate(cmd: &Commands) -> Result<(), AppError> {
@@ -24,9 +123,25 @@ pub async fn authenticate(cmd: &Commands) -> Result<(), AppError> {
         provider,
         profile,
         force,
+        storage,
+        role_arn,
+        external_id,
+        session_duration,
+        sso,
+        sso_start_url,
+        sso_region,
+        sso_account_id,
+        sso_role_name,
+        mfa_serial,
+        mfa_code,
     } = cmd
     {
-        println!("🔐 Authenticating with {}...", provider.to_string().green());
+        tracing::info!("🔐 Authenticating with {}...", provider.to_string().green());
+        let storage = match storage {
+            Some(storage) => *storage,
+            None => settings::load_settings()?.credentials.storage,
+        };
+        let key = format!("{}_{}", provider.to_string(), profile);
 
         let config_dir = get_config_dir()?;
         let credentials_file = config_dir.join("credentials.json");
@@ -36,10 +151,11 @@ pub async fn authenticate(cmd: &Commands) -> Result<(), AppError> {
 
         // Check if credentials already exist and force is not set
         if !*force {
-            if let Some(existing) =
-                credentials.get(&format!("{}_{}", provider.to_string(), profile))
-            {
-                if existing.token.is_some() && !is_token_expired(existing) {
+            if let Some(existing) = credentials.get_mut(&key) {
+                if existing.unseal(&key).is_ok()
+                    && existing.token.is_some()
+                    && !is_token_expired(existing)
+                {
                     println!(
                         "✅ Already authenticated with {} (profile: {})",
                         provider.to_string().green(),
@@ -50,18 +166,61 @@ pub async fn authenticate(cmd: &Commands) -> Result<(), AppError> {
             }
         }
 
+        if role_arn.is_some() && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--role-arn only applies to AWS".to_string(),
+            ));
+        }
+        if *sso && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--sso only applies to AWS".to_string(),
+            ));
+        }
+        if mfa_serial.is_some() && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--mfa-serial only applies to AWS".to_string(),
+            ));
+        }
+
         // Perform authentication based on provider
-        let new_credentials = match provider {
-            CloudProvider::Aws => authenticate_aws(profile).await?,
+        let mut new_credentials = match provider {
+            CloudProvider::Aws if *sso => {
+                authenticate_aws_sso(
+                    profile,
+                    sso_start_url.as_deref().unwrap_or_default(),
+                    sso_region.as_deref().unwrap_or_default(),
+                    sso_account_id.as_deref(),
+                    sso_role_name.as_deref(),
+                )
+                .await?
+            }
+            CloudProvider::Aws if mfa_serial.is_some() => {
+                authenticate_aws_mfa(
+                    profile,
+                    mfa_serial.as_deref().unwrap_or_default(),
+                    mfa_code.clone(),
+                    *session_duration,
+                )
+                .await?
+            }
+            CloudProvider::Aws => match role_arn {
+                Some(role_arn) => {
+                    let role = AssumedRole {
+                        role_arn: role_arn.clone(),
+                        external_id: external_id.clone(),
+                        session_seconds: *session_duration,
+                    };
+                    authenticate_aws_role(profile, role).await?
+                }
+                None => authenticate_aws(profile).await?,
+            },
             CloudProvider::Gcp => authenticate_gcp(profile).await?,
             CloudProvider::Azure => authenticate_azure(profile).await?,
         };
 
         // Store credentials
-        credentials.insert(
-            format!("{}_{}", provider.to_string(), profile),
-            new_credentials,
-        );
+        place_secrets(&mut new_credentials, &key, storage);
+        credentials.insert(key, new_credentials);
         save_credentials(&credentials_file, &credentials)?;
 
         println!(
@@ -75,7 +234,7 @@ pub async fn authenticate(cmd: &Commands) -> Result<(), AppError> {
 }
 
 async fn authenticate_aws(profile: &str) -> Result<CloudCredentials, AppError> {
-    println!("🔑 Setting up AWS authentication...");
+    tracing::info!("🔑 Setting up AWS authentication...");
 
     // Check for AWS credentials in environment variables
     let access_key = std::env::var("AWS_ACCESS_KEY_ID").ok();
@@ -94,11 +253,10 @@ async fn authenticate_aws(profile: &str) -> Result<CloudCredentials, AppError> {
     }
 
     // Validate credentials by making a test API call
-    let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-        .load()
-        .await;
-    let ec2_client = aws_sdk_ec2::Client::new(&config);
+    let config = clients::aws_config().await;
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(&config).await;
 
+    executor::throttle("ec2").await;
     match ec2_client.describe_regions().send().await {
         Ok(_) => {
             println!("✅ AWS credentials validated successfully");
@@ -112,6 +270,10 @@ async fn authenticate_aws(profile: &str) -> Result<CloudCredentials, AppError> {
                 subscription_id: None,
                 token: Some("aws_credentials_valid".to_string()),
                 expires_at: None,
+                in_keyring: false,
+                assumed_role: None,
+                sso: None,
+                mfa_serial: None,
             })
         }
         Err(e) => {
@@ -124,28 +286,337 @@ async fn authenticate_aws(profile: &str) -> Result<CloudCredentials, AppError> {
     }
 }
 
-async fn authenticate_gcp(profile: &str) -> Result<CloudCredentials, AppError> {
-    println!("🔑 Setting up GCP authentication...");
+/// Assume `role` with the profile's own credentials and keep the session.
+async fn authenticate_aws_role(
+    profile: &str,
+    role: AssumedRole,
+) -> Result<CloudCredentials, AppError> {
+    tracing::info!("🔑 Assuming {}...", role.role_arn.green());
+
+    if let Some(seconds) = role.session_seconds {
+        if !(MIN_SESSION_SECONDS..=MAX_SESSION_SECONDS).contains(&seconds) {
+            return Err(AppError::InvalidParameters(format!(
+                "--session-duration must be between {} and {} seconds",
+                MIN_SESSION_SECONDS, MAX_SESSION_SECONDS
+            )));
+        }
+    }
 
-    // Check for GCP credentials in environment variables
-    let project_id = std::env::var("GOOGLE_CLOUD_PROJECT").ok();
-    let credentials_path = std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok();
+    let base = clients::profile_config().await;
+    let credentials = assume_role(&base, &role).await?;
+    let session = CloudCredentials {
+        assumed_role: Some(role),
+        ..temporary_credentials(profile, &base, &credentials)
+    };
+    if let Some(expires_at) = &session.expires_at {
+        println!("✅ Role session valid until {}", expires_at);
+    }
+    Ok(session)
+}
 
-    if project_id.is_none() {
-        println!("⚠️  GCP project ID not found in environment variables.");
-        println!("   Please set GOOGLE_CLOUD_PROJECT");
-        return Err(AppError::AuthenticationError(
-            "GCP project ID not found".to_string(),
+async fn assume_role(base: &SdkConfig, role: &AssumedRole) -> Result<Credentials, AppError> {
+    let mut builder = AssumeRoleProvider::builder(&role.role_arn)
+        .session_name("actlog")
+        .configure(base);
+    if let Some(external_id) = &role.external_id {
+        builder = builder.external_id(external_id);
+    }
+    if let Some(seconds) = role.session_seconds {
+        builder = builder.session_length(Duration::from_secs(seconds));
+    }
+
+    builder
+        .build()
+        .await
+        .provide_credentials()
+        .await
+        .map_err(|e| {
+            AppError::AuthenticationError(format!("Could not assume {}: {}", role.role_arn, e))
+        })
+}
+
+/// Trade the profile's long-lived keys for an MFA session with STS
+/// GetSessionToken. The session can't be renewed without a new code, so it
+/// is used until it expires.
+async fn authenticate_aws_mfa(
+    profile: &str,
+    serial: &str,
+    code: Option<String>,
+    session_seconds: Option<u64>,
+) -> Result<CloudCredentials, AppError> {
+    tracing::info!("🔑 Opening an MFA session with {}...", serial.green());
+
+    if let Some(seconds) = session_seconds {
+        if !(MIN_SESSION_SECONDS..=MAX_MFA_SESSION_SECONDS).contains(&seconds) {
+            return Err(AppError::InvalidParameters(format!(
+                "--session-duration must be between {} and {} seconds with --mfa-serial",
+                MIN_SESSION_SECONDS, MAX_MFA_SESSION_SECONDS
+            )));
+        }
+    }
+
+    let code = match code {
+        Some(code) => code,
+        None => rpassword::prompt_password(format!("MFA code for {}: ", serial))?,
+    };
+    let code = code.trim();
+    if code.len() != 6 || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::InvalidParameters(
+            "The MFA code must be the 6 digits your device shows".to_string(),
         ));
     }
 
-    if credentials_path.is_none() {
+    let base = clients::profile_config().await;
+    let sts_client = clients::get::<aws_sdk_sts::Client>(&base).await;
+    executor::throttle("sts").await;
+    let response = sts_client
+        .get_session_token()
+        .serial_number(serial)
+        .token_code(code)
+        .set_duration_seconds(session_seconds.map(|s| s as i32))
+        .send()
+        .await
+        .map_err(|e| AppError::aws("sts:GetSessionToken", e).for_resource(serial))?;
+
+    let session = response.credentials().ok_or_else(|| {
+        AppError::AuthenticationError("STS returned no session credentials".to_string())
+    })?;
+    let credentials = Credentials::new(
+        session.access_key_id(),
+        session.secret_access_key(),
+        Some(session.session_token().to_string()),
+        SystemTime::try_from(*session.expiration()).ok(),
+        "actlog-mfa",
+    );
+    let session = CloudCredentials {
+        mfa_serial: Some(serial.to_string()),
+        ..temporary_credentials(profile, &base, &credentials)
+    };
+    if let Some(expires_at) = &session.expires_at {
+        println!("✅ MFA session valid until {}", expires_at);
+    }
+    Ok(session)
+}
+
+/// Sign in to IAM Identity Center with the device flow and keep credentials
+/// for one account role. The account and role are picked from what the user
+/// can access unless given.
+async fn authenticate_aws_sso(
+    profile: &str,
+    start_url: &str,
+    region: &str,
+    account_id: Option<&str>,
+    role_name: Option<&str>,
+) -> Result<CloudCredentials, AppError> {
+    tracing::info!("🔑 Signing in to {}...", start_url.green());
+    let access_token = sso::access_token(start_url, region).await?;
+
+    let account_id = match account_id {
+        Some(account_id) => account_id.to_string(),
+        None => {
+            let accounts = sso::accounts(&access_token, region).await?;
+            let labels = accounts
+                .iter()
+                .map(|account| format!("{} ({})", account.name, account.id))
+                .collect();
+            let chosen = choose("account", "--sso-account-id", labels)?;
+            accounts[chosen].id.clone()
+        }
+    };
+    let role_name = match role_name {
+        Some(role_name) => role_name.to_string(),
+        None => {
+            let roles = sso::roles(&access_token, region, &account_id).await?;
+            let chosen = choose("role", "--sso-role-name", roles.clone())?;
+            roles[chosen].clone()
+        }
+    };
+
+    let role = SsoRole {
+        start_url: start_url.to_string(),
+        region: region.to_string(),
+        account_id,
+        role_name,
+    };
+    let credentials = sso::role_credentials(&access_token, &role).await?;
+    println!(
+        "✅ Signed in to account {} as {}",
+        role.account_id.green(),
+        role.role_name.green()
+    );
+
+    let base = clients::profile_config().await;
+    Ok(CloudCredentials {
+        sso: Some(role),
+        ..temporary_credentials(profile, &base, &credentials)
+    })
+}
+
+/// Index of the `what` to use among `labels`: the only one, or the one the
+/// operator picks. Without a terminal several choices need `flag`.
+fn choose(what: &str, flag: &str, labels: Vec<String>) -> Result<usize, AppError> {
+    match labels.len() {
+        0 => Err(AppError::AuthenticationError(format!(
+            "No {} is available to this IAM Identity Center user",
+            what
+        ))),
+        1 => Ok(0),
+        _ if picker::interactive() => picker::pick_one(&format!("Choose the {}:", what), labels)?
+            .ok_or(AppError::UserCancelled),
+        _ => Err(AppError::InvalidParameters(format!(
+            "Several {}s are available ({}); choose one with {}",
+            what,
+            labels.join(", "),
+            flag
+        ))),
+    }
+}
+
+/// Stored form of temporary `credentials`, not yet tied to how they were
+/// obtained.
+fn temporary_credentials(
+    profile: &str,
+    base: &SdkConfig,
+    credentials: &Credentials,
+) -> CloudCredentials {
+    CloudCredentials {
+        provider: "aws".to_string(),
+        profile: profile.to_string(),
+        access_key: Some(credentials.access_key_id().to_string()),
+        secret_key: Some(credentials.secret_access_key().to_string()),
+        region: base.region().map(|r| r.to_string()),
+        project_id: None,
+        subscription_id: None,
+        token: credentials.session_token().map(str::to_string),
+        expires_at: credentials
+            .expiry()
+            .map(|expiry| DateTime::<Utc>::from(expiry).to_rfc3339()),
+        in_keyring: false,
+        assumed_role: None,
+        sso: None,
+        mfa_serial: None,
+    }
+}
+
+/// Temporary credentials for `profile` when it was authenticated with
+/// `--role-arn`, `--sso` or `--mfa-serial`. A role or Identity Center
+/// session about to expire is renewed, from `base` or the cached Identity
+/// Center token, and saved back where it was kept. An MFA session is used
+/// until it expires and is an error after that.
+pub(crate) async fn session_credentials(
+    profile: &str,
+    base: &SdkConfig,
+) -> Result<Option<Credentials>, AppError> {
+    let credentials_file = settings::settings_path()?.with_file_name("credentials.json");
+    let mut credentials = load_credentials(&credentials_file)?;
+    let key = format!("{}_{}", CloudProvider::Aws, profile);
+
+    let Some(stored) = credentials.get_mut(&key) else {
+        return Ok(None);
+    };
+    let (assumed_role, sso_role) = (stored.assumed_role.clone(), stored.sso.clone());
+    let mfa_serial = stored.mfa_serial.clone();
+    if assumed_role.is_none() && sso_role.is_none() && mfa_serial.is_none() {
+        return Ok(None);
+    }
+    let storage = if stored.in_keyring {
+        CredentialStorage::Keyring
+    } else {
+        CredentialStorage::File
+    };
+
+    // Nothing renews an MFA session, so it is worth using to the last minute
+    let refresh_after = match mfa_serial {
+        Some(_) => Utc::now(),
+        None => Utc::now() + chrono::Duration::minutes(REFRESH_MARGIN_MINUTES),
+    };
+    if stored.unseal(&key).is_ok() {
+        let expires = stored
+            .expires_at
+            .as_deref()
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| at.with_timezone(&Utc));
+        if let (Some(access_key), Some(secret_key), Some(expires)) =
+            (&stored.access_key, &stored.secret_key, expires)
+        {
+            if expires > refresh_after {
+                return Ok(Some(Credentials::new(
+                    access_key,
+                    secret_key,
+                    stored.token.clone(),
+                    Some(SystemTime::from(expires)),
+                    "actlog",
+                )));
+            }
+        }
+    }
+
+    let renewed = if let Some(role) = &assumed_role {
+        tracing::info!("🔄 Renewing session for {}...", role.role_arn);
+        assume_role(base, role).await?
+    } else if let Some(role) = &sso_role {
+        tracing::info!(
+            "🔄 Renewing IAM Identity Center session for {}...",
+            role.role_name
+        );
+        sso::renew(role).await?
+    } else if let Some(serial) = &mfa_serial {
+        return Err(AppError::AuthenticationError(format!(
+            "The MFA session for profile {} has expired; run `actlog authenticate aws --profile {} --mfa-serial {}` again",
+            profile, profile, serial
+        )));
+    } else {
+        return Ok(None);
+    };
+    let mut session = CloudCredentials {
+        assumed_role,
+        sso: sso_role,
+        ..temporary_credentials(profile, base, &renewed)
+    };
+    place_secrets(&mut session, &key, storage);
+    credentials.insert(key, session);
+    save_credentials(&credentials_file, &credentials)?;
+    Ok(Some(renewed))
+}
+
+/// Load the service account key, mint an access token from it and check
+/// the token can read the project.
+async fn authenticate_gcp(profile: &str) -> Result<CloudCredentials, AppError> {
+    tracing::info!("🔑 Setting up GCP authentication...");
+
+    let Ok(credentials_path) = std::env::var("GOOGLE_APPLICATION_CREDENTIALS") else {
         println!("⚠️  GCP credentials file path not found in environment variables.");
         println!("   Please set GOOGLE_APPLICATION_CREDENTIALS to point to your service account key file");
         return Err(AppError::AuthenticationError(
             "GCP credentials file not found".to_string(),
         ));
+    };
+    let key = gcp::load_service_account(&credentials_path)?;
+
+    let Some(project_id) = std::env::var("GOOGLE_CLOUD_PROJECT")
+        .ok()
+        .or(key.project_id.clone())
+    else {
+        println!("⚠️  GCP project ID not found in environment variables or the key file.");
+        println!("   Please set GOOGLE_CLOUD_PROJECT");
+        return Err(AppError::AuthenticationError(
+            "GCP project ID not found".to_string(),
+        ));
+    };
+
+    println!("   Service account: {}", key.client_email.green());
+    let token = gcp::service_account_token(&key).await?;
+
+    // The cheapest call that proves both the token and the account's access
+    let url = format!("{}/projects/{}", gcp::RESOURCE_MANAGER_API, project_id);
+    if let Err(e) = gcp::get("cloudresourcemanager.projects.get", &url).await {
+        println!("❌ GCP credentials validation failed: {}", e);
+        return Err(AppError::AuthenticationError(format!(
+            "{} cannot read project {}; grant it a role such as roles/browser or roles/viewer on the project: {}",
+            key.client_email, project_id, e
+        )));
     }
+    println!("✅ GCP credentials validated successfully");
 
     Ok(CloudCredentials {
         provider: "gcp".to_string(),
@@ -153,15 +624,21 @@ async fn authenticate_gcp(profile: &str) -> Result<CloudCredentials, AppError> {
         access_key: None,
         secret_key: None,
         region: None,
-        project_id,
+        project_id: Some(project_id),
         subscription_id: None,
-        token: Some("gcp_credentials_valid".to_string()),
-        expires_at: None,
+        token: Some(token.token),
+        expires_at: Some(token.expires_at.to_rfc3339()),
+        in_keyring: false,
+        assumed_role: None,
+        sso: None,
+        mfa_serial: None,
     })
 }
 
+/// Sign the service principal in with the client-credentials flow and
+/// check the token can read the subscription.
 async fn authenticate_azure(profile: &str) -> Result<CloudCredentials, AppError> {
-    println!("🔑 Setting up Azure authentication...");
+    tracing::info!("🔑 Setting up Azure authentication...");
 
     // Check for Azure credentials in environment variables
     let subscription_id = std::env::var("AZURE_SUBSCRIPTION_ID").ok();
@@ -169,20 +646,47 @@ async fn authenticate_azure(profile: &str) -> Result<CloudCredentials, AppError>
     let client_id = std::env::var("AZURE_CLIENT_ID").ok();
     let client_secret = std::env::var("AZURE_CLIENT_SECRET").ok();
 
-    if subscription_id.is_none() {
+    let Some(subscription_id) = subscription_id else {
         println!("⚠️  Azure subscription ID not found in environment variables.");
         println!("   Please set AZURE_SUBSCRIPTION_ID");
         return Err(AppError::AuthenticationError(
             "Azure subscription ID not found".to_string(),
         ));
-    }
+    };
 
-    if tenant_id.is_none() || client_id.is_none() || client_secret.is_none() {
+    let (Some(tenant_id), Some(client_id), Some(client_secret)) =
+        (tenant_id, client_id, client_secret)
+    else {
         println!("⚠️  Azure service principal credentials not found in environment variables.");
         println!("   Please set AZURE_TENANT_ID, AZURE_CLIENT_ID, and AZURE_CLIENT_SECRET");
         return Err(AppError::AuthenticationError(
             "Azure service principal credentials not found".to_string(),
         ));
+    };
+
+    let token = azure::service_principal_sign_in(&tenant_id, &client_id, &client_secret).await?;
+
+    let url = format!(
+        "{}/subscriptions/{}?api-version={}",
+        azure::MANAGEMENT_API,
+        subscription_id,
+        azure::SUBSCRIPTIONS_API_VERSION
+    );
+    match azure::get("Microsoft.Resources/subscriptions/read", &url).await {
+        Ok(subscription) => println!(
+            "✅ Azure credentials validated for {}",
+            subscription["displayName"]
+                .as_str()
+                .unwrap_or(subscription_id.as_str())
+                .green()
+        ),
+        Err(e) => {
+            println!("❌ Azure credentials validation failed: {}", e);
+            return Err(AppError::AuthenticationError(format!(
+                "Service principal {} cannot read subscription {}; assign it a role such as Reader on the subscription: {}",
+                client_id, subscription_id, e
+            )));
+        }
     }
 
     Ok(CloudCredentials {
@@ -192,9 +696,13 @@ async fn authenticate_azure(profile: &str) -> Result<CloudCredentials, AppError>
         secret_key: None,
         region: None,
         project_id: None,
-        subscription_id,
-        token: Some("azure_credentials_valid".to_string()),
-        expires_at: None,
+        subscription_id: Some(subscription_id),
+        token: Some(token.access_token),
+        expires_at: Some(token.expires_at.to_rfc3339()),
+        in_keyring: false,
+        assumed_role: None,
+        sso: None,
+        mfa_serial: None,
     })
 }
 
@@ -212,6 +720,81 @@ fn get_config_dir() -> Result<std::path::PathBuf, AppError> {
     Ok(config_dir)
 }
 
+/// What `actlog authenticate` saved for `profile` of `provider`, if anything.
+pub(crate) fn stored_credentials(
+    provider: &CloudProvider,
+    profile: &str,
+) -> Result<Option<CloudCredentials>, AppError> {
+    let credentials_file = settings::settings_path()?.with_file_name("credentials.json");
+    Ok(load_credentials(&credentials_file)?.remove(&format!("{}_{}", provider, profile)))
+}
+
+/// Profiles of `provider` that `actlog authenticate` saved credentials for.
+pub(crate) fn authenticated_profiles(provider: &CloudProvider) -> Result<Vec<String>, AppError> {
+    let credentials_file = settings::settings_path()?.with_file_name("credentials.json");
+    let prefix = format!("{}_", provider);
+    Ok(load_credentials(&credentials_file)?
+        .into_keys()
+        .filter_map(|key| key.strip_prefix(&prefix).map(str::to_string))
+        .collect())
+}
+
+/// Keys of the profiles whose secrets are in the OS keyring, the names
+/// they are filed under there.
+pub(crate) fn keyring_profiles() -> Result<Vec<String>, AppError> {
+    let credentials_file = settings::settings_path()?.with_file_name("credentials.json");
+    Ok(load_credentials(&credentials_file)?
+        .into_iter()
+        .filter(|(_, entry)| entry.in_keyring)
+        .map(|(key, _)| key)
+        .collect())
+}
+
+/// Keep the secrets of `credentials` where `storage` says. Without a usable
+/// keyring they stay in credentials.json, with a warning.
+fn place_secrets(credentials: &mut CloudCredentials, key: &str, storage: CredentialStorage) {
+    match storage {
+        CredentialStorage::Keyring => {
+            if let Err(e) = credentials.seal(key) {
+                println!(
+                    "   ⚠️  {}; storing credentials in credentials.json instead",
+                    e.to_string().yellow()
+                );
+            }
+        }
+        CredentialStorage::File => {
+            // Don't leave an older copy behind in the keyring
+            let _ = secrets::delete(key);
+        }
+    }
+}
+
+/// Move the secrets of every profile still kept in plaintext into the OS
+/// keyring, returning how many profiles moved.
+pub(crate) fn migrate_credentials() -> Result<usize, AppError> {
+    let credentials_file = settings::settings_path()?.with_file_name("credentials.json");
+    let mut credentials = load_credentials(&credentials_file)?;
+
+    let mut migrated = 0;
+    let mut result = Ok(());
+    for (key, entry) in credentials.iter_mut() {
+        if entry.in_keyring || !entry.has_secrets() {
+            continue;
+        }
+        if let Err(e) = entry.seal(key) {
+            result = Err(e);
+            break;
+        }
+        migrated += 1;
+    }
+
+    // Whatever moved before a failure must not stay in the file as well
+    if migrated > 0 {
+        save_credentials(&credentials_file, &credentials)?;
+    }
+    result.map(|_| migrated)
+}
+
 fn load_credentials(
     credentials_file: &Path,
 ) -> Result<HashMap<String, CloudCredentials>, AppError> {
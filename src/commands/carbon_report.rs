@@ -0,0 +1,493 @@
+use crate::cli::{CloudProvider, Commands, OutputFormat, ResourceType};
+use crate::clients;
+use crate::commands::cost_report::determine_date_range;
+use crate::commands::list::{list_azure_resources, list_gcp_resources, ResourceSummary};
+use crate::commands::usage_report::{
+    dimension_filter, EC2_COMPUTE_SERVICE, RUNNING_HOURS_USAGE_GROUP,
+};
+use crate::error::AppError;
+use crate::executor;
+use crate::report;
+use aws_config::SdkConfig;
+use aws_sdk_costexplorer::types::{
+    DateInterval, Dimension, Expression, Granularity, GroupDefinition, GroupDefinitionType,
+};
+use aws_sdk_ec2::types::InstanceType;
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// CPU utilization assumed for every instance-hour
+const AVERAGE_UTILIZATION: f64 = 0.5;
+
+/// Assumed for instance types whose size can't be determined
+const DEFAULT_VCPUS: f64 = 2.0;
+
+/// World average grid intensity (gCO2e/kWh), used for unlisted regions
+const DEFAULT_INTENSITY: f64 = 475.0;
+
+/// Average grid carbon intensity per region in gCO2e/kWh, from the Cloud
+/// Carbon Footprint methodology.
+const AWS_INTENSITY: &[(&str, f64)] = &[
+    ("us-east-1", 379.1),
+    ("us-east-2", 410.6),
+    ("us-west-1", 240.7),
+    ("us-west-2", 135.6),
+    ("ca-central-1", 13.0),
+    ("sa-east-1", 61.7),
+    ("eu-west-1", 278.6),
+    ("eu-west-2", 225.0),
+    ("eu-west-3", 51.1),
+    ("eu-central-1", 311.0),
+    ("eu-north-1", 8.8),
+    ("ap-south-1", 708.0),
+    ("ap-northeast-1", 462.0),
+    ("ap-northeast-2", 415.6),
+    ("ap-southeast-1", 408.0),
+    ("ap-southeast-2", 760.0),
+];
+
+const GCP_INTENSITY: &[(&str, f64)] = &[
+    ("us-central1", 394.0),
+    ("us-east1", 434.0),
+    ("us-east4", 309.0),
+    ("us-west1", 60.0),
+    ("northamerica-northeast1", 28.0),
+    ("southamerica-east1", 129.0),
+    ("europe-west1", 110.0),
+    ("europe-west2", 172.0),
+    ("europe-west3", 269.0),
+    ("europe-west4", 283.0),
+    ("europe-north1", 59.0),
+    ("asia-east1", 453.0),
+    ("asia-northeast1", 453.0),
+    ("asia-south1", 670.0),
+    ("asia-southeast1", 372.0),
+    ("australia-southeast1", 598.0),
+];
+
+/// Keyed by ARM location name, e.g. "eastus"
+const AZURE_INTENSITY: &[(&str, f64)] = &[
+    ("eastus", 379.1),
+    ("eastus2", 379.1),
+    ("centralus", 454.0),
+    ("westus", 240.7),
+    ("westus2", 135.6),
+    ("canadacentral", 13.0),
+    ("brazilsouth", 61.7),
+    ("northeurope", 278.6),
+    ("westeurope", 328.0),
+    ("uksouth", 225.0),
+    ("francecentral", 51.1),
+    ("germanywestcentral", 311.0),
+    ("swedencentral", 8.8),
+    ("centralindia", 708.0),
+    ("japaneast", 462.0),
+    ("southeastasia", 408.0),
+    ("australiaeast", 760.0),
+];
+
+/// Per-vCPU power draw at idle and full load, and data-center PUE.
+struct PowerModel {
+    min_watts: f64,
+    max_watts: f64,
+    pue: f64,
+}
+
+impl PowerModel {
+    fn for_provider(provider: &CloudProvider) -> Self {
+        match provider {
+            CloudProvider::Aws => PowerModel {
+                min_watts: 0.74,
+                max_watts: 3.5,
+                pue: 1.135,
+            },
+            CloudProvider::Gcp => PowerModel {
+                min_watts: 0.71,
+                max_watts: 4.26,
+                pue: 1.1,
+            },
+            CloudProvider::Azure => PowerModel {
+                min_watts: 0.78,
+                max_watts: 3.76,
+                pue: 1.185,
+            },
+        }
+    }
+
+    fn kwh(&self, vcpu_hours: f64) -> f64 {
+        let watts = self.min_watts + AVERAGE_UTILIZATION * (self.max_watts - self.min_watts);
+        vcpu_hours * watts / 1000.0 * self.pue
+    }
+}
+
+/// Instance-hours and vCPU-hours a provider ran in one region.
+#[derive(Default)]
+struct RegionUsage {
+    instance_hours: f64,
+    vcpu_hours: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CarbonRow {
+    pub provider: String,
+    pub region: String,
+    pub instance_hours: f64,
+    pub vcpu_hours: f64,
+    pub energy_kwh: f64,
+    /// Grid carbon intensity used, in gCO2e/kWh
+    pub intensity: f64,
+    /// The region has no listed intensity and the world average was used
+    pub default_intensity: bool,
+    pub co2e_kg: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CarbonReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub total_energy_kwh: f64,
+    pub total_co2e_kg: f64,
+    pub rows: Vec<CarbonRow>,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn report_carbon(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::ReportCarbon {
+        provider,
+        start_date,
+        end_date,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!("🌱 Estimating compute carbon footprint...");
+
+        let (start, end) = determine_date_range(start_date, end_date)?;
+        let providers = match provider {
+            Some(provider) => vec![provider.clone()],
+            None => vec![CloudProvider::Aws, CloudProvider::Gcp, CloudProvider::Azure],
+        };
+
+        let usages = executor::for_each_provider(&providers, move |provider| async move {
+            provider_usage(&provider, &start, &end).await
+        })
+        .await;
+
+        let mut rows = Vec::new();
+        for (provider, usage) in usages {
+            let usage = match usage {
+                Ok(usage) => usage,
+                Err(e) => {
+                    tracing::warn!("⚠️  Skipping {}: {}", provider.to_string().yellow(), e);
+                    continue;
+                }
+            };
+
+            let model = PowerModel::for_provider(&provider);
+            for (region, usage) in usage {
+                let energy_kwh = model.kwh(usage.vcpu_hours);
+                let listed = intensity(&provider, &region);
+                let intensity = listed.unwrap_or(DEFAULT_INTENSITY);
+                rows.push(CarbonRow {
+                    provider: provider.to_string(),
+                    region,
+                    instance_hours: usage.instance_hours,
+                    vcpu_hours: usage.vcpu_hours,
+                    energy_kwh,
+                    intensity,
+                    default_intensity: listed.is_none(),
+                    co2e_kg: energy_kwh * intensity / 1000.0,
+                });
+            }
+        }
+        rows.sort_by(|a, b| b.co2e_kg.total_cmp(&a.co2e_kg));
+
+        let report = CarbonReport {
+            start_date: start.format("%Y-%m-%d").to_string(),
+            end_date: end.format("%Y-%m-%d").to_string(),
+            total_energy_kwh: rows.iter().map(|r| r.energy_kwh).sum(),
+            total_co2e_kg: rows.iter().map(|r| r.co2e_kg).sum(),
+            rows,
+        };
+
+        output_carbon_report(&report, format)?;
+    }
+
+    Ok(())
+}
+
+fn intensity(provider: &CloudProvider, region: &str) -> Option<f64> {
+    let table = match provider {
+        CloudProvider::Aws => AWS_INTENSITY,
+        CloudProvider::Gcp => GCP_INTENSITY,
+        CloudProvider::Azure => AZURE_INTENSITY,
+    };
+    table
+        .iter()
+        .find(|(name, _)| *name == region)
+        .map(|(_, intensity)| *intensity)
+}
+
+async fn provider_usage(
+    provider: &CloudProvider,
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+) -> Result<BTreeMap<String, RegionUsage>, AppError> {
+    let period_hours = (*end - *start).num_hours().max(1) as f64;
+
+    match provider {
+        CloudProvider::Aws => {
+            let config = clients::aws_config().await;
+            aws_usage(&config, start, end).await
+        }
+        CloudProvider::Gcp => Ok(running_usage(
+            &list_gcp_resources(&ResourceType::Ec2).await?,
+            "Machine Type",
+            period_hours,
+        )),
+        CloudProvider::Azure => Ok(running_usage(
+            &list_azure_resources(&ResourceType::Ec2, None).await?,
+            "Size",
+            period_hours,
+        )),
+    }
+}
+
+/// EC2 running hours per region and instance type from Cost Explorer.
+async fn aws_usage(
+    config: &SdkConfig,
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+) -> Result<BTreeMap<String, RegionUsage>, AppError> {
+    let cost_client = clients::get::<aws_sdk_costexplorer::Client>(config).await;
+
+    let time_period = DateInterval::builder()
+        .start(start.format("%Y-%m-%d").to_string())
+        .end(end.format("%Y-%m-%d").to_string())
+        .build()
+        .map_err(|e| AppError::AwsError(e.to_string()))?;
+
+    let filter = Expression::builder()
+        .and(dimension_filter(Dimension::Service, EC2_COMPUTE_SERVICE))
+        .and(dimension_filter(
+            Dimension::UsageTypeGroup,
+            RUNNING_HOURS_USAGE_GROUP,
+        ))
+        .build();
+
+    // (region, instance type) -> hours
+    let mut hours: BTreeMap<(String, String), f64> = BTreeMap::new();
+    let mut next_page_token = None;
+    loop {
+        executor::throttle("costexplorer").await;
+        let response = cost_client
+            .get_cost_and_usage()
+            .time_period(time_period.clone())
+            .granularity(Granularity::Monthly)
+            .metrics("UsageQuantity")
+            .filter(filter.clone())
+            .group_by(
+                GroupDefinition::builder()
+                    .r#type(GroupDefinitionType::Dimension)
+                    .key("REGION")
+                    .build(),
+            )
+            .group_by(
+                GroupDefinition::builder()
+                    .r#type(GroupDefinitionType::Dimension)
+                    .key("INSTANCE_TYPE")
+                    .build(),
+            )
+            .set_next_page_token(next_page_token)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetCostAndUsage", e))?;
+
+        for group in response
+            .results_by_time()
+            .iter()
+            .flat_map(|result| result.groups())
+        {
+            let keys = group.keys();
+            let (Some(region), Some(instance_type)) = (keys.first(), keys.get(1)) else {
+                continue;
+            };
+            let quantity = group
+                .metrics()
+                .and_then(|m| m.get("UsageQuantity"))
+                .and_then(|m| m.amount())
+                .and_then(|a| a.parse::<f64>().ok())
+                .unwrap_or(0.0);
+            *hours
+                .entry((region.clone(), instance_type.clone()))
+                .or_default() += quantity;
+        }
+
+        next_page_token = response.next_page_token().map(str::to_string);
+        if next_page_token.is_none() {
+            break;
+        }
+    }
+
+    let mut instance_types: Vec<String> = hours.keys().map(|(_, t)| t.clone()).collect();
+    instance_types.sort();
+    instance_types.dedup();
+    let vcpus = aws_vcpus(config, &instance_types).await?;
+
+    let mut usage: BTreeMap<String, RegionUsage> = BTreeMap::new();
+    for ((region, instance_type), hours) in hours {
+        let entry = usage.entry(region).or_default();
+        entry.instance_hours += hours;
+        entry.vcpu_hours += hours * vcpus.get(&instance_type).copied().unwrap_or(DEFAULT_VCPUS);
+    }
+    Ok(usage)
+}
+
+/// Default vCPU count of each instance type.
+async fn aws_vcpus(
+    config: &SdkConfig,
+    instance_types: &[String],
+) -> Result<HashMap<String, f64>, AppError> {
+    let ec2 = clients::get::<aws_sdk_ec2::Client>(config).await;
+    let mut vcpus = HashMap::new();
+
+    // Cost Explorer also reports placeholders such as "NoInstanceType",
+    // which DescribeInstanceTypes would reject
+    let known: Vec<&String> = instance_types.iter().filter(|t| t.contains('.')).collect();
+    for chunk in known.chunks(100) {
+        executor::throttle("ec2").await;
+        let response = ec2
+            .describe_instance_types()
+            .set_instance_types(Some(
+                chunk
+                    .iter()
+                    .map(|t| InstanceType::from(t.as_str()))
+                    .collect(),
+            ))
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ec2:DescribeInstanceTypes", e))?;
+
+        for info in response.instance_types() {
+            if let (Some(instance_type), Some(count)) = (
+                info.instance_type(),
+                info.v_cpu_info().and_then(|v| v.default_v_cpus()),
+            ) {
+                vcpus.insert(instance_type.as_str().to_string(), count as f64);
+            }
+        }
+    }
+
+    Ok(vcpus)
+}
+
+/// Running instances from an inventory listing, assumed to have run the
+/// whole period. `size_key` names the machine type in `additional_info`.
+fn running_usage(
+    instances: &[ResourceSummary],
+    size_key: &str,
+    period_hours: f64,
+) -> BTreeMap<String, RegionUsage> {
+    let mut usage: BTreeMap<String, RegionUsage> = BTreeMap::new();
+    for instance in instances
+        .iter()
+        .filter(|i| i.state.eq_ignore_ascii_case("running"))
+    {
+        let vcpus = instance
+            .additional_info
+            .get(size_key)
+            .and_then(|size| size_vcpus(size))
+            .unwrap_or(DEFAULT_VCPUS);
+        // Azure display names ("East US") become location names ("eastus")
+        let region = instance.region.replace(' ', "").to_lowercase();
+
+        let entry = usage.entry(region).or_default();
+        entry.instance_hours += period_hours;
+        entry.vcpu_hours += period_hours * vcpus;
+    }
+    usage
+}
+
+/// vCPUs encoded in a machine type name: the trailing number of
+/// `n1-standard-4`, or the first number of `Standard_D4s_v3`.
+fn size_vcpus(size: &str) -> Option<f64> {
+    let name = size.strip_prefix("Standard_").unwrap_or(size);
+    let digits: String = if size.starts_with("Standard_") {
+        name.chars()
+            .skip_while(|c| !c.is_ascii_digit())
+            .take_while(|c| c.is_ascii_digit())
+            .collect()
+    } else {
+        name.rsplit('-').next()?.to_string()
+    };
+    digits.parse::<f64>().ok().filter(|v| *v > 0.0)
+}
+
+fn output_carbon_report(report: &CarbonReport, format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n🌍 Estimated compute emissions from {} to {}",
+                report.start_date.green(),
+                report.end_date.green()
+            );
+            println!(
+                "{:<8} {:<24} {:>14} {:>14} {:>12} {:>10} {:>12}",
+                "Provider", "Region", "Inst. Hours", "vCPU Hours", "kWh", "gCO2e/kWh", "kg CO2e"
+            );
+            println!("{:-<100}", "");
+
+            for row in &report.rows {
+                println!(
+                    "{:<8} {:<24} {:>14.1} {:>14.1} {:>12.1} {:>9.0}{:1} {:>12.1}",
+                    row.provider,
+                    row.region,
+                    row.instance_hours,
+                    row.vcpu_hours,
+                    row.energy_kwh,
+                    row.intensity,
+                    if row.default_intensity { "*" } else { "" },
+                    row.co2e_kg
+                );
+            }
+
+            println!("{:-<100}", "");
+            println!(
+                "{:<63} {:>12.1} {:>11} {:>12.1}",
+                "Total", report.total_energy_kwh, "", report.total_co2e_kg
+            );
+            if report.rows.iter().any(|r| r.default_intensity) {
+                println!("* region not in the intensity tables; world average used");
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(report)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, report)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Provider,Region,InstanceHours,VcpuHours,EnergyKwh,IntensityGPerKwh,Co2eKg");
+            for row in &report.rows {
+                println!(
+                    "{},{},{:.2},{:.2},{:.3},{:.1},{:.3}",
+                    row.provider,
+                    row.region,
+                    row.instance_hours,
+                    row.vcpu_hours,
+                    row.energy_kwh,
+                    row.intensity,
+                    row.co2e_kg
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
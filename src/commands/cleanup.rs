@@ -1,9 +1,109 @@
-use crate::cli::{CloudProvider, Commands, ResourceType};
+use crate::accounts;
+use crate::azure;
+use crate::batch::{self, BatchAction};
+use crate::cdn;
+use crate::cleanup_policy::{CleanupPolicy, RuleAction};
+use crate::cli::{CleanupCommand, CloudProvider, Commands, OutputFormat, ResourceType};
+use crate::clients;
+use crate::cloudwatch::{self, MetricQuery};
+use crate::commands::list::{aws_datetime, bucket_tags, ResourceSummary};
+use crate::creators::{self, Creator};
+use crate::databases::{self, ManagedDatabase};
 use crate::error::AppError;
-use chrono::{DateTime, Duration, Utc};
+use crate::executor;
+use crate::gcp;
+use crate::graph::{self, ResourceGraph};
+use crate::guard;
+use crate::ids;
+use crate::locks;
+use crate::output::{out, outln};
+use crate::owners;
+use crate::picker;
+use crate::plans;
+use crate::pricing;
+use crate::providers;
+use crate::quarantine::{self, QuarantinedResource};
+use crate::query::TagFilter;
+use crate::regions;
+use crate::report::{self, HtmlReport};
+use aws_config::SdkConfig;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use clap::ValueEnum;
 use colored::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Resource type names of the GCP resources cleanup can delete
+const GCP_STATIC_IP: &str = "Static IP";
+const GCP_SNAPSHOT: &str = "Disk Snapshot";
+
+/// Resource type names of the IAM identities cleanup can retire
+const IAM_ACCESS_KEY: &str = "IAM Access Key";
+const IAM_ROLE: &str = "IAM Role";
+
+/// Resource type names of the EBS storage cleanup can delete
+const EBS_VOLUME: &str = "EBS Volume";
+const EBS_SNAPSHOT: &str = "EBS Snapshot";
+
+/// Volume ID AWS reports for snapshots copied from another snapshot
+const COPIED_SNAPSHOT_VOLUME: &str = "vol-ffffffff";
+
+/// EBS list prices (us-east-1) of standard snapshot storage
+const EBS_SNAPSHOT_PRICE_PER_GB: f64 = 0.05;
+
+/// State of an S3 bucket with no objects and no old versions left
+const BUCKET_EMPTY: &str = "empty";
+
+/// Objects counted per bucket before the count is reported as a lower bound
+const MAX_COUNTED_OBJECTS: usize = 100_000;
+
+/// Resource IDs from `--id`, so EC2 scans describe only those instances and
+/// volumes, as event-driven evaluation of one resource does
+static SCAN_IDS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// S3 Standard list price (us-east-1) per GiB-month
+const S3_STANDARD_PRICE_PER_GB: f64 = 0.023;
+
+/// Resource type names of the AWS network resources cleanup can delete
+const ELASTIC_IP: &str = "Elastic IP";
+const LOAD_BALANCER: &str = "Load Balancer";
+
+/// AWS list prices (us-east-1): a public IPv4 address, and the hourly charge
+/// of an application or network load balancer before capacity units
+const ELASTIC_IP_MONTHLY_COST: f64 = 3.65;
+const LOAD_BALANCER_MONTHLY_COST: f64 = 16.43;
+
+/// Days without requests after which a load balancer counts as unused
+const LOAD_BALANCER_IDLE_WINDOW_DAYS: i64 = 14;
+
+/// Most ARNs elbv2:DescribeTags accepts at once
+const ELB_TAGS_BATCH: usize = 20;
+
+/// Roles AWS services create and own; IAM refuses to delete them directly
+const SERVICE_LINKED_ROLE_PATH: &str = "/aws-service-role/";
+
+/// Resource type names of the Azure resources cleanup can delete
+const AZURE_VM: &str = "Virtual Machine";
+const AZURE_NIC: &str = "Network Interface";
+const AZURE_PUBLIC_IP: &str = "Public IP";
+
+/// GCP list prices: an unattached external IP and standard snapshot storage
+const GCP_STATIC_IP_MONTHLY_COST: f64 = 7.30;
+const GCP_SNAPSHOT_PRICE_PER_GB: f64 = 0.05;
+
+/// Azure list price of a static public IP; dynamic ones cost nothing while
+/// unattached
+const AZURE_STATIC_PUBLIC_IP_MONTHLY_COST: f64 = 3.65;
+
+/// Days of CloudWatch metrics that decide whether a running EC2 instance is
+/// underused, unless `--lookback-days` says otherwise
+pub(crate) const DEFAULT_LOOKBACK_DAYS: u32 = 14;
+
+/// Average daily network (in plus out) and EBS (read plus write) traffic
+/// below which a running instance with low CPU counts as idle
+const IDLE_NETWORK_BYTES_PER_DAY: f64 = 100.0 * 1024.0 * 1024.0;
+const IDLE_DISK_BYTES_PER_DAY: f64 = 100.0 * 1024.0 * 1024.0;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[allow(unused_variables, dead_code)]
@@ -18,9 +118,11 @@ pub struct ResourceInfo {
     pub utilization: f64,
     pub estimated_cost: f64,
     pub tags: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CleanupAction {
     pub action_type: String,
     pub resource: ResourceInfo,
@@ -28,123 +130,1175 @@ pub struct CleanupAction {
     pub estimated_savings: f64,
 }
 
+/// The actions a dry run proposes, in execution order. Written by
+/// `--dry-run --format json` and read back by `--plan`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CleanupPlan {
+    pub provider: String,
+    pub generated_at: DateTime<Utc>,
+    pub total_savings: f64,
+    pub actions: Vec<CleanupAction>,
+}
+
+/// A plan written by `--plan-out` for `cleanup apply`: the actions, plus
+/// the scan that found them so apply can check nothing has changed since.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedPlan {
+    #[serde(flatten)]
+    pub plan: CleanupPlan,
+    pub resource_type: String,
+    pub age_threshold: u32,
+    pub utilization_threshold: f64,
+    #[serde(default = "default_lookback_days")]
+    pub lookback_days: u32,
+    /// Whether non-empty buckets were planned for deletion with their objects
+    #[serde(default)]
+    pub delete_objects: bool,
+    /// Accounts planned across with `--org` or `--accounts`, and the roles
+    /// apply assumes in them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub targets: Option<Vec<accounts::AccountTarget>>,
+}
+
+/// Which resources cleanup may select by their tags, from `--include-tag`
+/// and `--exclude-tag`. The default selects everything.
+#[derive(Debug, Default)]
+pub struct TagSelection {
+    /// A resource must match all of these
+    pub include: Vec<TagFilter>,
+    /// A resource matching any of these is never selected
+    pub exclude: Vec<TagFilter>,
+}
+
+impl TagSelection {
+    pub fn selects(&self, tags: &HashMap<String, String>) -> bool {
+        self.include.iter().all(|f| f.matches(tags))
+            && !self.exclude.iter().any(|f| f.matches(tags))
+    }
+}
+
 #[allow(unused_variables, dead_code)]
 pub async fn cleanup_resources(cmd: &Commands) -> Result<(), AppError> {
     if let Commands::Cleanup {
+        action,
         provider,
         resource_type,
         age_threshold,
         utilization_threshold,
+        lookback_days,
         profile,
         dry_run,
         force,
+        format,
+        org,
+        org_role,
+        accounts_file,
+        pdf,
+        plan,
+        policy,
+        quarantine,
+        delete_objects,
+        plan_out,
+        who_created,
+        resource_group,
+        include_tags,
+        exclude_tags,
+        ids,
     } = cmd
     {
-        println!(
+        match action {
+            Some(CleanupCommand::Purge {
+                dry_run,
+                force,
+                format,
+                ..
+            }) => return purge_quarantined(*dry_run, *force, format).await,
+            Some(CleanupCommand::Restore { resource_id, .. }) => {
+                return restore_quarantined(resource_id).await
+            }
+            _ => {}
+        }
+
+        // `cleanup apply` runs the usual flow on exactly the saved actions
+        let saved = match action {
+            Some(CleanupCommand::Apply { plan, .. }) => {
+                Some(plans::read_signed::<SavedPlan>(std::path::Path::new(plan))?)
+            }
+            _ => None,
+        };
+        let (provider, resource_type) = match (&saved, provider, resource_type) {
+            (Some(saved), _, _) => saved_scan(saved)?,
+            (None, Some(provider), Some(resource_type)) => {
+                (provider.clone(), resource_type.clone())
+            }
+            _ => {
+                return Err(AppError::InvalidParameters(
+                    "Name a provider and resource type, e.g. `actlog cleanup aws ec2`, or apply a plan with `actlog cleanup apply <plan>`".to_string(),
+                ))
+            }
+        };
+        let (provider, resource_type) = (&provider, &resource_type);
+        let (force, format) = match action {
+            Some(CleanupCommand::Apply { force, format, .. }) => (force, format),
+            _ => (force, format),
+        };
+
+        let tag_selection = TagSelection {
+            include: TagFilter::parse_all(include_tags)?,
+            exclude: TagFilter::parse_all(exclude_tags)?,
+        };
+        let policy = policy
+            .as_deref()
+            .map(|path| CleanupPolicy::load(std::path::Path::new(path)))
+            .transpose()?;
+
+        // With stdin taken by the IDs there is nobody to answer the prompt
+        if ids::from_stdin(ids) && !*dry_run && !*force {
+            return Err(AppError::InvalidParameters(
+                "--id - reads stdin, so cleanup can't ask for confirmation; add --force, or --dry-run to preview".to_string(),
+            ));
+        }
+        let ids = ids::expand(ids)?;
+        if !ids.is_empty() {
+            let _ = SCAN_IDS.set(ids.clone());
+        }
+
+        // A structured dry run prints only the plan, so pipelines can parse stdout
+        let plan_only = *dry_run && !matches!(format, OutputFormat::Table);
+
+        tracing::info!(
             "🧹 Analyzing {} resources for cleanup...",
             provider.to_string().green()
         );
 
-        // Find resources that can be cleaned up
-        let resources = match provider {
-            CloudProvider::Aws => {
-                find_aws_resources(resource_type, *age_threshold, *utilization_threshold).await?
+        if (*org || accounts_file.is_some()) && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--org and --accounts are only supported for AWS".to_string(),
+            ));
+        }
+        if *lookback_days == 0 {
+            return Err(AppError::InvalidParameters(
+                "--lookback-days must be at least 1".to_string(),
+            ));
+        }
+        if quarantine.is_some() && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--quarantine is only supported for AWS".to_string(),
+            ));
+        }
+        if resource_group.is_some() && !matches!(provider, CloudProvider::Azure) {
+            return Err(AppError::InvalidParameters(
+                "--resource-group is only supported for Azure".to_string(),
+            ));
+        }
+
+        let aws_config = match provider {
+            CloudProvider::Aws => Some(clients::aws_config().await),
+            _ => None,
+        };
+
+        // An applied plan goes back to the accounts it was made across
+        let targets = match (&saved, &aws_config) {
+            (Some(saved), _) => saved.targets.clone(),
+            (None, Some(config)) => {
+                accounts::resolve_targets(config, *org, org_role, accounts_file).await?
             }
-            CloudProvider::Gcp => {
-                find_gcp_resources(resource_type, *age_threshold, *utilization_threshold).await?
+            (None, None) => None,
+        };
+
+        // Credentials used to execute cleanup, keyed by account for multi-account runs
+        let mut account_configs: HashMap<String, SdkConfig> = HashMap::new();
+
+        let mut cleanup_actions = match (&saved, plan) {
+            (Some(saved), _) => {
+                verify_saved_plan(saved, provider, resource_type, &aws_config, &targets).await?;
+                account_configs =
+                    planned_account_configs(&saved.plan.actions, &aws_config, &targets).await?;
+                saved.plan.actions.clone()
+            }
+            (None, Some(path)) => {
+                // Tag rules still hold for a plan reviewed before they were added
+                let mut actions = load_plan(path, provider)?;
+                actions.retain(|a| tag_selection.selects(&a.resource.tags));
+                account_configs = planned_account_configs(&actions, &aws_config, &targets).await?;
+                actions
             }
-            CloudProvider::Azure => {
-                find_azure_resources(resource_type, *age_threshold, *utilization_threshold).await?
+            (None, None) => {
+                find_cleanup_actions(
+                    provider,
+                    resource_type,
+                    *age_threshold,
+                    *utilization_threshold,
+                    *lookback_days,
+                    &aws_config,
+                    &targets,
+                    &mut account_configs,
+                    resource_group.as_deref(),
+                    &tag_selection,
+                    policy.as_ref(),
+                )
+                .await?
             }
         };
 
-        if resources.is_empty() {
-            println!("✅ No resources found that meet cleanup criteria.");
-            return Ok(());
+        if !ids.is_empty() {
+            cleanup_actions.retain(|a| ids.contains(&a.resource.id));
+        }
+
+        // Identity changes can lock out people and workloads, so they are
+        // only ever applied from a plan someone has reviewed
+        let touches_iam = cleanup_actions.iter().any(|a| {
+            a.resource.resource_type == IAM_ACCESS_KEY || a.resource.resource_type == IAM_ROLE
+        });
+        if touches_iam && !*dry_run && plan.is_none() && saved.is_none() {
+            return Err(AppError::InvalidParameters(
+                "IAM cleanup only applies reviewed plans: run it with --dry-run --format json, then pass the plan to --plan".to_string(),
+            ));
+        }
+
+        // Only some resources can be taken out of service and brought back
+        if quarantine.is_some() {
+            cleanup_actions.retain(|a| {
+                let supported = quarantine::supports(&a.resource.resource_type);
+                if !supported {
+                    tracing::warn!(
+                        "⚠️  Skipping {} {}: it can't be quarantined",
+                        a.resource.resource_type,
+                        a.resource.id
+                    );
+                }
+                supported
+            });
         }
 
-        // Analyze resources and determine cleanup actions
-        let cleanup_actions = analyze_cleanup_actions(&resources, provider)?;
+        // Deleting a bucket with data in it loses the data, so that has to be
+        // asked for; an applied plan keeps what it was made with
+        let delete_objects = saved.as_ref().map_or(*delete_objects, |s| s.delete_objects);
+        if !delete_objects && quarantine.is_none() {
+            cleanup_actions.retain(|a| {
+                let keep = a.resource.resource_type != "S3 Bucket" || a.resource.state == BUCKET_EMPTY;
+                if !keep {
+                    tracing::warn!(
+                        "⚠️  Skipping bucket {} ({}); pass --delete-objects to delete it with its contents",
+                        a.resource.id,
+                        a.resource.state
+                    );
+                }
+                keep
+            });
+        }
 
-        if cleanup_actions.is_empty() {
-            println!("✅ No cleanup actions required.");
+        // An empty plan is still a plan for pipelines
+        if cleanup_actions.is_empty() && !plan_only {
+            tracing::info!("✅ No cleanup actions required.");
             return Ok(());
         }
 
         // Display cleanup summary
-        display_cleanup_summary(&cleanup_actions)?;
+        if !plan_only {
+            // Resources in other accounts would need that account's credentials
+            let creators = if *who_created {
+                let resources: Vec<(String, String)> = cleanup_actions
+                    .iter()
+                    .filter(|a| a.resource.account.is_none())
+                    .map(|a| (a.resource.id.clone(), a.resource.region.clone()))
+                    .collect();
+                creators::find_creators(provider, &resources).await
+            } else {
+                HashMap::new()
+            };
+            display_cleanup_summary(&cleanup_actions, &creators)?;
+        }
+
+        let owners = owners::load_owners()?;
+        let project = match provider {
+            CloudProvider::Gcp => owners::current_project(),
+            _ => None,
+        };
+        let team_of = |action: &CleanupAction| {
+            owners.as_ref().map(|o| {
+                o.team_name(
+                    &action.resource.tags,
+                    action.resource.account.as_deref(),
+                    project.as_deref(),
+                )
+            })
+        };
+
+        let mut team_totals = None;
+        if owners.is_some() {
+            let mut totals: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+            for action in &cleanup_actions {
+                let entry = totals
+                    .entry(team_of(action).unwrap_or_default())
+                    .or_insert((0, 0.0));
+                entry.0 += 1;
+                entry.1 += action.estimated_savings;
+            }
+            if !plan_only {
+                owners::print_team_totals("Savings by team", &totals);
+            }
+            team_totals = Some(totals);
+        }
 
         // Calculate total potential savings
         let total_savings: f64 = cleanup_actions
             .iter()
             .map(|action| action.estimated_savings)
             .sum();
-        println!("💰 Total estimated monthly savings: ${:.2}", total_savings);
+        if plan_only {
+            output_plan(provider, &cleanup_actions, total_savings, format)?;
+        } else {
+            outln!("💰 Total estimated monthly savings: ${:.2}", total_savings);
+        }
+
+        if let Some(path) = pdf {
+            cleanup_summary_html(
+                provider,
+                &cleanup_actions,
+                total_savings,
+                team_totals.as_ref(),
+            )
+            .write_pdf(path)?;
+            eprintln!("📄 PDF summary written to {}", path.green());
+        }
+
+        if let Some(path) = plan_out {
+            let planned_targets = targets.as_ref().map(|targets| {
+                targets
+                    .iter()
+                    .filter(|t| {
+                        cleanup_actions
+                            .iter()
+                            .any(|a| a.resource.account.as_ref() == Some(&t.account_id))
+                    })
+                    .cloned()
+                    .collect()
+            });
+            plans::write_signed(
+                std::path::Path::new(path),
+                &SavedPlan {
+                    plan: CleanupPlan {
+                        provider: provider.to_string(),
+                        generated_at: Utc::now(),
+                        total_savings,
+                        actions: cleanup_actions,
+                    },
+                    resource_type: resource_type
+                        .to_possible_value()
+                        .map(|v| v.get_name().to_string())
+                        .unwrap_or_default(),
+                    age_threshold: *age_threshold,
+                    utilization_threshold: *utilization_threshold,
+                    lookback_days: *lookback_days,
+                    delete_objects,
+                    targets: planned_targets,
+                },
+            )?;
+            eprintln!(
+                "📝 Plan written to {}; carry it out with `actlog cleanup apply {}`",
+                path.green(),
+                path
+            );
+            return Ok(());
+        }
 
         // Confirm cleanup if not in dry run mode
         if !*dry_run {
+            guard::ensure_writable("cleanup")?;
             if !*force {
-                println!("\n⚠️  This will permanently delete the resources listed above.");
-                print!("Are you sure you want to continue? (y/N): ");
+                // Thresholds only propose candidates; without a reviewed plan
+                // or explicit IDs the operator picks the actual targets
+                if plan.is_none() && saved.is_none() && ids.is_empty() && picker::interactive() {
+                    let labels = cleanup_actions
+                        .iter()
+                        .map(|a| {
+                            format!(
+                                "{} {} ({}) - {}",
+                                a.resource.resource_type, a.resource.name, a.resource.id, a.reason
+                            )
+                        })
+                        .collect();
+                    let Some(picked) = picker::pick("Resources to delete:", labels)? else {
+                        tracing::warn!("❌ Cleanup cancelled by user.");
+                        return Err(AppError::UserCancelled);
+                    };
+                    if picked.is_empty() {
+                        tracing::warn!("❌ Nothing selected; cleanup cancelled.");
+                        return Err(AppError::UserCancelled);
+                    }
+                    cleanup_actions = picked
+                        .into_iter()
+                        .map(|index| cleanup_actions[index].clone())
+                        .collect();
+                }
+
+                match quarantine {
+                    Some(days) => tracing::warn!(
+                        "⚠️  This will take {} resources out of service, to be purged after {} days.",
+                        cleanup_actions.len(),
+                        days
+                    ),
+                    None => tracing::warn!(
+                        "⚠️  This will permanently delete {} resources.",
+                        cleanup_actions.len()
+                    ),
+                }
+                let filled_buckets = cleanup_actions
+                    .iter()
+                    .filter(|a| {
+                        a.resource.resource_type == "S3 Bucket" && a.resource.state != BUCKET_EMPTY
+                    })
+                    .count();
+                if quarantine.is_none() && filled_buckets > 0 {
+                    tracing::warn!(
+                        "   {} buckets are emptied first: every object and old version in them is deleted.",
+                        filled_buckets
+                    );
+                }
+                eprint!("Are you sure you want to continue? (y/N): ");
                 use std::io::{self, Write};
-                io::stdout().flush()?;
+                io::stderr().flush()?;
 
                 let mut input = String::new();
                 io::stdin().read_line(&mut input)?;
 
                 if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
-                    println!("❌ Cleanup cancelled by user.");
-                    return Ok(());
+                    tracing::warn!("❌ Cleanup cancelled by user.");
+                    return Err(AppError::UserCancelled);
                 }
             }
 
             // Execute cleanup actions
-            println!("\n🗑️  Executing cleanup actions...");
-            for action in &cleanup_actions {
-                match provider {
-                    CloudProvider::Aws => execute_aws_cleanup(&action).await?,
-                    CloudProvider::Gcp => execute_gcp_cleanup(&action).await?,
-                    CloudProvider::Azure => execute_azure_cleanup(&action).await?,
+            tracing::info!("🗑️  Executing cleanup actions...");
+            let quarantine = *quarantine;
+            let outcome = batch::run(
+                if quarantine.is_some() {
+                    "cleanup quarantine"
+                } else {
+                    "cleanup"
+                },
+                &provider.to_string(),
+                &cleanup_actions,
+                |action| {
+                    // Another account's resources only ever use that account's role
+                    let config = match &action.resource.account {
+                        Some(account) => account_configs.get(account),
+                        None => aws_config.as_ref(),
+                    };
+                    async move {
+                        match provider {
+                            CloudProvider::Aws => {
+                                let Some(config) = config else {
+                                    return Err(AppError::InvalidParameters(format!(
+                                        "No credentials for account {}",
+                                        action.resource.account.as_deref().unwrap_or_default()
+                                    )));
+                                };
+                                match quarantine {
+                                    Some(days) => {
+                                        quarantine::quarantine_aws(
+                                            config,
+                                            quarantine_entry(action, days),
+                                        )
+                                        .await
+                                    }
+                                    None => {
+                                        if delete_objects
+                                            && action.resource.resource_type == "S3 Bucket"
+                                        {
+                                            empty_bucket(
+                                                config,
+                                                &action.resource.id,
+                                                &action.resource.region,
+                                            )
+                                            .await?;
+                                        }
+                                        execute_aws_cleanup(config, action).await
+                                    }
+                                }
+                            }
+                            CloudProvider::Gcp => execute_gcp_cleanup(action).await,
+                            CloudProvider::Azure => execute_azure_cleanup(action).await,
+                        }
+                    }
+                },
+            )
+            .await;
+            outcome.render(format)?;
+
+            let mut deleted_by_team: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for (index, action) in cleanup_actions.iter().enumerate() {
+                if !outcome.succeeded_at(index) {
+                    continue;
+                }
+                if let Some(team) = team_of(action) {
+                    deleted_by_team.entry(team).or_default().push(format!(
+                        "• {} {} ({}) - {}",
+                        action.resource.resource_type,
+                        action.resource.name,
+                        action.resource.id,
+                        action.reason
+                    ));
                 }
+            }
 
-                println!(
-                    "   ✅ Deleted {}: {} (${:.2} savings)",
-                    action.resource.resource_type.green(),
-                    action.resource.name,
-                    action.estimated_savings
-                );
+            if let Some(owners) = &owners {
+                owners
+                    .notify_teams(
+                        "actlog cleanup deleted resources",
+                        "cleanup",
+                        &deleted_by_team,
+                    )
+                    .await;
+            }
+
+            outcome.into_result()?;
+            tracing::info!("✅ Cleanup completed successfully!");
+        } else {
+            tracing::info!("🔍 Dry run mode - no resources were deleted");
+        }
+    }
+
+    Ok(())
+}
+
+/// Scan `provider` for resources matching the thresholds and decide what to
+/// do with each. Multi-account runs record the credentials for every account
+/// with findings in `account_configs`.
+#[allow(clippy::too_many_arguments)]
+async fn find_cleanup_actions(
+    provider: &CloudProvider,
+    resource_type: &ResourceType,
+    age_threshold: u32,
+    utilization_threshold: f64,
+    lookback_days: u32,
+    aws_config: &Option<SdkConfig>,
+    targets: &Option<Vec<accounts::AccountTarget>>,
+    account_configs: &mut HashMap<String, SdkConfig>,
+    resource_group: Option<&str>,
+    tag_selection: &TagSelection,
+    policy: Option<&CleanupPolicy>,
+) -> Result<Vec<CleanupAction>, AppError> {
+    // Find resources that can be cleaned up
+    let resources = match (provider, aws_config, targets) {
+        (CloudProvider::Aws, Some(config), Some(targets)) => {
+            let results = accounts::fan_out(config, targets, |account_config| {
+                let resource_type = resource_type.clone();
+                async move {
+                    find_aws_resources(
+                        &account_config,
+                        &resource_type,
+                        age_threshold,
+                        utilization_threshold,
+                        lookback_days,
+                    )
+                    .await
+                }
+            })
+            .await;
+
+            let mut resources = Vec::new();
+            for (target, account_resources) in results {
+                if !account_resources.is_empty() {
+                    account_configs.insert(
+                        target.account_id.clone(),
+                        accounts::assume_role_config(config, &target).await,
+                    );
+                }
+                for mut resource in account_resources {
+                    resource.account = Some(target.account_id.clone());
+                    resources.push(resource);
+                }
+            }
+            resources
+        }
+        (CloudProvider::Aws, Some(config), None) => {
+            find_aws_resources(
+                config,
+                resource_type,
+                age_threshold,
+                utilization_threshold,
+                lookback_days,
+            )
+            .await?
+        }
+        (CloudProvider::Aws, None, _) => unreachable!("AWS config is loaded for AWS runs"),
+        (CloudProvider::Gcp, _, _) => {
+            find_gcp_resources(resource_type, age_threshold, utilization_threshold).await?
+        }
+        (CloudProvider::Azure, _, _) => {
+            find_azure_resources(
+                resource_type,
+                age_threshold,
+                utilization_threshold,
+                resource_group,
+            )
+            .await?
+        }
+    };
+
+    // Analyze resources and determine cleanup actions
+    let mut cleanup_actions = analyze_cleanup_actions(&resources, provider, tag_selection, policy)?;
+
+    // Delete dependents before the resources they rely on. Only AWS
+    // resources are ordered; an unreadable graph leaves the plan as found.
+    if let (Some(config), None) = (aws_config, targets) {
+        if needs_ordering(&cleanup_actions) {
+            match graph::build_aws_graph(config).await {
+                Ok(dependency_graph) => {
+                    order_cleanup_actions(&dependency_graph, &mut cleanup_actions)
+                }
+                Err(e) => tracing::warn!(
+                    "⚠️  Could not read resource dependencies, so the plan is not ordered: {}",
+                    e
+                ),
             }
+        }
+    }
+
+    Ok(cleanup_actions)
+}
+
+/// The registry entry for `action` quarantined for `days`.
+fn quarantine_entry(action: &CleanupAction, days: u32) -> QuarantinedResource {
+    let now = Utc::now();
+    QuarantinedResource {
+        resource_id: action.resource.id.clone(),
+        name: action.resource.name.clone(),
+        resource_type: action.resource.resource_type.clone(),
+        region: action.resource.region.clone(),
+        reason: action.reason.clone(),
+        quarantined_at: now,
+        quarantined_by: locks::current_user(),
+        purge_after: now.date_naive() + Duration::days(days as i64),
+        snapshots: Vec::new(),
+    }
+}
+
+/// Delete quarantined resources whose window has passed, with the snapshots
+/// taken of them. One whose pending-delete tag was removed, or that is
+/// already gone, is dropped from the registry instead.
+async fn purge_quarantined(
+    dry_run: bool,
+    force: bool,
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    let today = Utc::now().date_naive();
+    let quarantined = quarantine::load_quarantined()?;
+    if quarantined.is_empty() {
+        tracing::info!(
+            "ℹ️  Nothing is quarantined. Registry: {}",
+            quarantine::quarantine_path()?.display()
+        );
+        return Ok(());
+    }
+
+    // In other formats the batch outcome is the result once anything is purged
+    if matches!(format, OutputFormat::Table) {
+        output_quarantined(&quarantined, today, format)?;
+    }
 
-            println!("✅ Cleanup completed successfully!");
+    let due: Vec<&QuarantinedResource> = quarantined.iter().filter(|r| r.is_due(today)).collect();
+    if due.is_empty() || dry_run {
+        if !matches!(format, OutputFormat::Table) {
+            output_quarantined(&quarantined, today, format)?;
+        }
+        if due.is_empty() {
+            tracing::info!("✅ Nothing is past its quarantine window yet.");
         } else {
-            println!("🔍 Dry run mode - no resources were deleted");
+            tracing::info!("🔍 Dry run mode - nothing was purged");
+        }
+        return Ok(());
+    }
+    guard::ensure_writable("cleanup purge")?;
+
+    let config = clients::aws_config().await;
+    let mut pending = Vec::new();
+    let mut released = Vec::new();
+    for resource in due {
+        match quarantine::still_pending(&config, resource).await {
+            Ok(true) => pending.push(resource.clone()),
+            Ok(false) => released.push(resource.resource_id.clone()),
+            Err(e) if e.is_not_found() => released.push(resource.resource_id.clone()),
+            Err(e) => return Err(e),
+        }
+    }
+    for id in &released {
+        tracing::warn!(
+            "   🔓 {} is untagged or gone; keeping it and dropping it from quarantine",
+            id.yellow()
+        );
+    }
+
+    if !pending.is_empty() && !force {
+        tracing::warn!(
+            "⚠️  This will permanently delete {} quarantined resources and their snapshots.",
+            pending.len()
+        );
+        eprint!("Are you sure you want to continue? (y/N): ");
+        use std::io::{self, Write};
+        io::stderr().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
+            tracing::warn!("❌ Purge cancelled by user.");
+            return Err(AppError::UserCancelled);
+        }
+    }
+
+    let outcome = batch::run("cleanup purge", "AWS", &pending, |resource| {
+        let config = &config;
+        async move {
+            let action = CleanupAction {
+                action_type: "DELETE".to_string(),
+                resource: ResourceInfo {
+                    id: resource.resource_id.clone(),
+                    name: resource.name.clone(),
+                    resource_type: resource.resource_type.clone(),
+                    region: resource.region.clone(),
+                    state: String::new(),
+                    creation_date: None,
+                    last_used: None,
+                    utilization: 0.0,
+                    estimated_cost: 0.0,
+                    tags: HashMap::new(),
+                    account: None,
+                },
+                reason: resource.reason.clone(),
+                estimated_savings: 0.0,
+            };
+            // Purging is the permanent step, so a bucket goes with its archived data
+            if resource.resource_type == "S3 Bucket" {
+                empty_bucket(config, &resource.resource_id, &resource.region).await?;
+            }
+            execute_aws_cleanup(config, &action).await?;
+            quarantine::delete_snapshots(config, resource).await
+        }
+    })
+    .await;
+    outcome.render(format)?;
+
+    let purged: Vec<&str> = pending
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| outcome.succeeded_at(*index))
+        .map(|(_, resource)| resource.resource_id.as_str())
+        .collect();
+    let mut remaining = quarantine::load_quarantined()?;
+    remaining.retain(|r| {
+        !purged.contains(&r.resource_id.as_str()) && !released.contains(&r.resource_id)
+    });
+    quarantine::save_quarantined(remaining)?;
+
+    outcome.into_result()?;
+    tracing::info!("✅ Purged {} quarantined resources", purged.len());
+    Ok(())
+}
+
+/// Undo the quarantine of one resource.
+async fn restore_quarantined(resource_id: &str) -> Result<(), AppError> {
+    let mut quarantined = quarantine::load_quarantined()?;
+    let Some(resource) = quarantined
+        .iter()
+        .find(|r| r.resource_id == resource_id)
+        .cloned()
+    else {
+        return Err(AppError::ResourceNotFound(format!(
+            "{} is not quarantined",
+            resource_id
+        )));
+    };
+
+    guard::ensure_writable("cleanup restore")?;
+    let config = clients::aws_config().await;
+    quarantine::restore_aws(&config, &resource).await?;
+
+    quarantined.retain(|r| r.resource_id != resource_id);
+    quarantine::save_quarantined(quarantined)?;
+    tracing::info!(
+        "♻️  Restored {} {} ({})",
+        resource.resource_type,
+        resource.name,
+        resource_id.green()
+    );
+    Ok(())
+}
+
+/// The provider and resource type a saved plan was made for.
+fn saved_scan(saved: &SavedPlan) -> Result<(CloudProvider, ResourceType), AppError> {
+    let provider = CloudProvider::from_str(&saved.plan.provider, true);
+    let resource_type = ResourceType::from_str(&saved.resource_type, true);
+    match (provider, resource_type) {
+        (Ok(provider), Ok(resource_type)) => Ok((provider, resource_type)),
+        _ => Err(AppError::InvalidParameters(format!(
+            "The plan is for an unknown provider or resource type ({} {})",
+            saved.plan.provider, saved.resource_type
+        ))),
+    }
+}
+
+/// An EC2 filter on the `--id` values that look like IDs of this kind. A
+/// filter rather than the ID parameter, so a resource that is already gone
+/// is simply not found.
+fn scan_id_filter(prefix: &str, name: &str) -> Option<aws_sdk_ec2::types::Filter> {
+    let ids: Vec<String> = SCAN_IDS
+        .get()?
+        .iter()
+        .filter(|id| id.starts_with(prefix))
+        .cloned()
+        .collect();
+    (!ids.is_empty()).then(|| {
+        aws_sdk_ec2::types::Filter::builder()
+            .name(name)
+            .set_values(Some(ids))
+            .build()
+    })
+}
+
+/// Credentials for every account `actions` reach into, assumed from
+/// `targets`. Never falls back to the default credentials for another
+/// account's resources.
+async fn planned_account_configs(
+    actions: &[CleanupAction],
+    aws_config: &Option<SdkConfig>,
+    targets: &Option<Vec<accounts::AccountTarget>>,
+) -> Result<HashMap<String, SdkConfig>, AppError> {
+    let mut account_configs = HashMap::new();
+    if let (Some(config), Some(targets)) = (aws_config, targets) {
+        for target in targets {
+            if actions
+                .iter()
+                .any(|a| a.resource.account.as_ref() == Some(&target.account_id))
+            {
+                account_configs.insert(
+                    target.account_id.clone(),
+                    accounts::assume_role_config(config, target).await,
+                );
+            }
+        }
+    }
+
+    if let Some(account) = actions
+        .iter()
+        .filter_map(|a| a.resource.account.as_ref())
+        .find(|account| !account_configs.contains_key(*account))
+    {
+        return Err(AppError::InvalidParameters(format!(
+            "Plan includes account {} but it is not among the --org/--accounts targets",
+            account
+        )));
+    }
+    Ok(account_configs)
+}
+
+/// Scan again the way the plan was made and refuse to go on if any planned
+/// resource is gone, no longer qualifies, or changed state or tags since.
+async fn verify_saved_plan(
+    saved: &SavedPlan,
+    provider: &CloudProvider,
+    resource_type: &ResourceType,
+    aws_config: &Option<SdkConfig>,
+    targets: &Option<Vec<accounts::AccountTarget>>,
+) -> Result<(), AppError> {
+    tracing::info!(
+        "🔎 Checking {} planned resources for changes since {}...",
+        saved.plan.actions.len(),
+        saved.plan.generated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    let candidates = match (provider, aws_config, targets) {
+        // Accounts whose role can't be assumed any more drop out, so their
+        // resources show up as changed
+        (CloudProvider::Aws, Some(config), Some(targets)) => {
+            let (age_threshold, utilization_threshold, lookback_days) = (
+                saved.age_threshold,
+                saved.utilization_threshold,
+                saved.lookback_days,
+            );
+            let results = accounts::fan_out(config, targets, |account_config| {
+                let resource_type = resource_type.clone();
+                async move {
+                    find_aws_resources(
+                        &account_config,
+                        &resource_type,
+                        age_threshold,
+                        utilization_threshold,
+                        lookback_days,
+                    )
+                    .await
+                }
+            })
+            .await;
+            results
+                .into_iter()
+                .flat_map(|(target, resources)| {
+                    resources.into_iter().map(move |mut resource| {
+                        resource.account = Some(target.account_id.clone());
+                        resource
+                    })
+                })
+                .collect()
+        }
+        (CloudProvider::Aws, Some(config), None) => {
+            find_aws_resources(
+                config,
+                resource_type,
+                saved.age_threshold,
+                saved.utilization_threshold,
+                saved.lookback_days,
+            )
+            .await?
         }
+        (CloudProvider::Aws, None, _) => unreachable!("AWS config is loaded for AWS runs"),
+        _ => {
+            providers::get(provider)
+                .find_cleanup_candidates(
+                    resource_type,
+                    saved.age_threshold,
+                    saved.utilization_threshold,
+                )
+                .await?
+        }
+    };
+    let current: HashMap<(Option<String>, String), ResourceInfo> = candidates
+        .into_iter()
+        .map(|resource| ((resource.account.clone(), resource.id.clone()), resource))
+        .collect();
+
+    let mut changes = Vec::new();
+    for action in &saved.plan.actions {
+        let planned = &action.resource;
+        let change = match current.get(&(planned.account.clone(), planned.id.clone())) {
+            None => "no longer a cleanup candidate".to_string(),
+            Some(now) if now.state != planned.state => {
+                format!("state changed from {} to {}", planned.state, now.state)
+            }
+            Some(now) if now.tags != planned.tags => "tags changed".to_string(),
+            Some(_) => continue,
+        };
+        changes.push(format!(
+            "{} {} ({}): {}",
+            planned.resource_type, planned.name, planned.id, change
+        ));
+    }
+
+    if !changes.is_empty() {
+        for change in &changes {
+            tracing::warn!("   ❌ {}", change.yellow());
+        }
+        return Err(AppError::InvalidParameters(format!(
+            "{} planned resources changed since the plan was made; plan again",
+            changes.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Read a plan written by a dry run, refusing one made for another provider.
+fn load_plan(path: &str, provider: &CloudProvider) -> Result<Vec<CleanupAction>, AppError> {
+    let plan: CleanupPlan = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if plan.provider != provider.to_string() {
+        return Err(AppError::InvalidParameters(format!(
+            "Plan {} is for {}, not {}",
+            path, plan.provider, provider
+        )));
+    }
+    eprintln!(
+        "📄 Loaded {} planned actions from {}",
+        plan.actions.len(),
+        path
+    );
+    Ok(plan.actions)
+}
+
+/// A quarantined resource with whether `cleanup purge` may delete it yet.
+#[derive(Serialize)]
+struct QuarantineStatus<'a> {
+    #[serde(flatten)]
+    resource: &'a QuarantinedResource,
+    due: bool,
+}
+
+fn output_quarantined(
+    quarantined: &[QuarantinedResource],
+    today: NaiveDate,
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            let statuses: Vec<QuarantineStatus> = quarantined
+                .iter()
+                .map(|resource| QuarantineStatus {
+                    resource,
+                    due: resource.is_due(today),
+                })
+                .collect();
+            outln!("{}", report::document(format, &statuses)?);
+        }
+        OutputFormat::Csv => {
+            out!(
+                "{}",
+                report::csv_record(
+                    ["ResourceId", "Type", "Name", "Region", "PurgeAfter", "Due"],
+                    ','
+                )?
+            );
+            for resource in quarantined {
+                let purge_after = resource.purge_after.to_string();
+                out!(
+                    "{}",
+                    report::csv_record(
+                        [
+                            resource.resource_id.as_str(),
+                            resource.resource_type.as_str(),
+                            resource.name.as_str(),
+                            resource.region.as_str(),
+                            purge_after.as_str(),
+                            if resource.is_due(today) {
+                                "true"
+                            } else {
+                                "false"
+                            },
+                        ],
+                        ','
+                    )?
+                );
+            }
+        }
+        OutputFormat::Table => {
+            outln!(
+                "{:<22} {:<14} {:<24} {:<12} {}",
+                "Resource",
+                "Type",
+                "Name",
+                "Purge after",
+                "Status"
+            );
+            outln!("{:-<90}", "");
+            for resource in quarantined {
+                let status = if resource.is_due(today) {
+                    "due".red()
+                } else {
+                    format!("{} days left", (resource.purge_after - today).num_days()).normal()
+                };
+                outln!(
+                    "{:<22} {:<14} {:<24} {:<12} {}",
+                    resource.resource_id,
+                    resource.resource_type,
+                    resource.name,
+                    resource.purge_after.to_string(),
+                    status
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn output_plan(
+    provider: &CloudProvider,
+    actions: &[CleanupAction],
+    total_savings: f64,
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            let plan = CleanupPlan {
+                provider: provider.to_string(),
+                generated_at: Utc::now(),
+                total_savings,
+                actions: actions.to_vec(),
+            };
+            outln!("{}", report::document(format, &plan)?);
+        }
+        OutputFormat::Csv => {
+            out!(
+                "{}",
+                report::csv_record(
+                    [
+                        "ResourceId",
+                        "Name",
+                        "Type",
+                        "Region",
+                        "Account",
+                        "Reason",
+                        "EstimatedSavings"
+                    ],
+                    ','
+                )?
+            );
+            for action in actions {
+                let resource = &action.resource;
+                let savings = format!("{:.2}", action.estimated_savings);
+                out!(
+                    "{}",
+                    report::csv_record(
+                        [
+                            resource.id.as_str(),
+                            resource.name.as_str(),
+                            resource.resource_type.as_str(),
+                            resource.region.as_str(),
+                            resource.account.as_deref().unwrap_or_default(),
+                            action.reason.as_str(),
+                            savings.as_str(),
+                        ],
+                        ','
+                    )?
+                );
+            }
+        }
+        OutputFormat::Table => display_cleanup_summary(actions, &HashMap::new())?,
     }
 
     Ok(())
 }
 
 #[allow(unused_variables, dead_code)]
-async fn find_aws_resources(
+pub(crate) async fn find_aws_resources(
+    config: &SdkConfig,
     resource_type: &ResourceType,
     age_threshold: u32,
     utilization_threshold: f64,
+    lookback_days: u32,
 ) -> Result<Vec<ResourceInfo>, AppError> {
-    println!("🔍 Scanning AWS resources...");
+    tracing::info!("🔍 Scanning AWS resources...");
 
-    let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-        .load()
-        .await;
+    let region = regions::config_region(config);
     let mut resources = Vec::new();
 
     match resource_type {
         ResourceType::Ec2 => {
-            let ec2_client = aws_sdk_ec2::Client::new(&config);
+            let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+            executor::throttle("ec2").await;
             let response = ec2_client
                 .describe_instances()
+                .set_filters(scan_id_filter("i-", "instance-id").map(|filter| vec![filter]))
                 .send()
                 .await
-                .map_err(|e| AppError::AwsError(e.to_string()))?;
+                .map_err(|e| AppError::aws("ec2:DescribeInstances", e))?;
+
+            let running: Vec<&aws_sdk_ec2::types::Instance> = response
+                .reservations()
+                .iter()
+                .flat_map(|reservation| reservation.instances())
+                .filter(|instance| {
+                    instance.state().and_then(|state| state.name())
+                        == Some(&aws_sdk_ec2::types::InstanceStateName::Running)
+                })
+                .collect();
+            resources.extend(
+                find_underused_instances(
+                    config,
+                    &running,
+                    &region,
+                    age_threshold,
+                    utilization_threshold,
+                    lookback_days,
+                )
+                .await?,
+            );
 
             if let Some(reservations) = response.reservations {
                 for reservation in reservations {
@@ -177,13 +1331,27 @@ async fn find_aws_resources(
                                                     .clone()
                                                     .unwrap_or_default(),
                                                 resource_type: "EC2 Instance".to_string(),
-                                                region: "us-east-1".to_string(), // Would get from config
+                                                region: region.clone(),
                                                 state: state_name,
                                                 creation_date: Some(launch_time_chrono),
                                                 last_used: None,
                                                 utilization: 0.0,
-                                                estimated_cost: 0.0, // Would calculate based on instance type
-                                                tags: HashMap::new(),
+                                                // A stopped instance only bills for its volumes
+                                                estimated_cost: attached_volume_cost(
+                                                    config, &instance, &region,
+                                                )
+                                                .await?,
+                                                tags: instance
+                                                    .tags()
+                                                    .iter()
+                                                    .filter_map(|t| {
+                                                        Some((
+                                                            t.key()?.to_string(),
+                                                            t.value()?.to_string(),
+                                                        ))
+                                                    })
+                                                    .collect(),
+                                                account: None,
                                             });
                                         }
                                     }
@@ -196,12 +1364,13 @@ async fn find_aws_resources(
         }
 
         ResourceType::S3 => {
-            let s3_client = aws_sdk_s3::Client::new(&config);
+            let s3_client = clients::get::<aws_sdk_s3::Client>(config).await;
+            executor::throttle("s3").await;
             let response = s3_client
                 .list_buckets()
                 .send()
                 .await
-                .map_err(|e| AppError::AwsError(e.to_string()))?;
+                .map_err(|e| AppError::aws("s3:ListBuckets", e))?;
 
             if let Some(buckets) = response.buckets {
                 for bucket in buckets {
@@ -214,19 +1383,23 @@ async fn find_aws_resources(
                         .unwrap_or_else(|| chrono::Utc::now());
                         let age = chrono::Utc::now() - creation_date_chrono;
                         if age.num_days() > age_threshold as i64 {
-                            // Check if bucket is empty (simplified)
                             let bucket_name = bucket.name.clone().unwrap_or_default();
+                            let bucket_region =
+                                bucket.bucket_region().unwrap_or("us-east-1").to_string();
+                            let contents =
+                                bucket_contents(config, &bucket_name, &bucket_region).await?;
                             resources.push(ResourceInfo {
+                                tags: bucket_tags(config, &bucket_name, &bucket_region).await?,
                                 id: bucket_name.clone(),
                                 name: bucket_name,
                                 resource_type: "S3 Bucket".to_string(),
-                                region: "us-east-1".to_string(),
-                                state: "active".to_string(),
+                                region: bucket_region,
+                                state: contents.state(),
                                 creation_date: Some(creation_date_chrono),
                                 last_used: None,
                                 utilization: 0.0,
-                                estimated_cost: 0.0,
-                                tags: HashMap::new(),
+                                estimated_cost: contents.size_gb() * S3_STANDARD_PRICE_PER_GB,
+                                account: None,
                             });
                         }
                     }
@@ -234,9 +1407,30 @@ async fn find_aws_resources(
             }
         }
 
+        ResourceType::Iam => {
+            resources.extend(find_stale_access_keys(config, age_threshold).await?);
+            resources.extend(find_unused_roles(config, age_threshold).await?);
+        }
+
+        ResourceType::Volume => {
+            resources.extend(find_unattached_volumes(config, &region, age_threshold).await?);
+        }
+
+        ResourceType::StaticIp => {
+            resources.extend(find_unassociated_addresses(config, &region).await?);
+        }
+
+        ResourceType::LoadBalancer => {
+            resources.extend(find_unused_load_balancers(config, &region, age_threshold).await?);
+        }
+
+        ResourceType::Snapshot => {
+            resources.extend(find_orphaned_snapshots(config, &region, age_threshold).await?);
+        }
+
         _ => {
             // For other resource types, we'd implement similar logic
-            println!(
+            eprintln!(
                 "   Resource type {:?} not yet implemented for AWS",
                 resource_type
             );
@@ -247,17 +1441,15 @@ async fn find_aws_resources(
 }
 
 #[allow(unused_variables, dead_code)]
-async fn find_gcp_resources(
+pub(crate) async fn find_gcp_resources(
     resource_type: &ResourceType,
     age_threshold: u32,
     utilization_threshold: f64,
 ) -> Result<Vec<ResourceInfo>, AppError> {
-    println!("🔍 Scanning GCP resources...");
+    tracing::info!("🔍 Scanning GCP resources...");
 
-    let project_id = std::env::var("GOOGLE_CLOUD_PROJECT")
-        .map_err(|_| AppError::ConfigurationError("GOOGLE_CLOUD_PROJECT not set".to_string()))?;
+    let project_id = gcp::project_id()?;
 
-    // Simulate finding resources
     let mut resources = Vec::new();
 
     match resource_type {
@@ -274,11 +1466,32 @@ async fn find_gcp_resources(
                 utilization: 5.0,
                 estimated_cost: 25.0,
                 tags: HashMap::new(),
+                account: None,
             });
         }
 
+        ResourceType::StaticIp => {
+            resources.extend(find_gcp_unused_addresses(&project_id).await?);
+        }
+
+        ResourceType::Snapshot => {
+            resources.extend(find_gcp_aged_snapshots(&project_id, age_threshold).await?);
+        }
+
+        ResourceType::Rds => {
+            let instances = databases::cloud_sql_instances().await?;
+            resources.extend(find_idle_databases(instances, age_threshold).await?);
+        }
+
+        ResourceType::All => {
+            resources.extend(find_gcp_unused_addresses(&project_id).await?);
+            resources.extend(find_gcp_aged_snapshots(&project_id, age_threshold).await?);
+            let instances = databases::cloud_sql_instances().await?;
+            resources.extend(find_idle_databases(instances, age_threshold).await?);
+        }
+
         _ => {
-            println!(
+            eprintln!(
                 "   Resource type {:?} not yet implemented for GCP",
                 resource_type
             );
@@ -288,94 +1501,1441 @@ async fn find_gcp_resources(
     Ok(resources)
 }
 
-#[allow(unused_variables, dead_code)]
-async fn find_azure_resources(
-    resource_type: &ResourceType,
-    age_threshold: u32,
-    utilization_threshold: f64,
-) -> Result<Vec<ResourceInfo>, AppError> {
-    println!("🔍 Scanning Azure resources...");
+/// External static addresses that are reserved but attached to nothing.
+/// Internal addresses are free and skipped.
+async fn find_gcp_unused_addresses(project_id: &str) -> Result<Vec<ResourceInfo>, AppError> {
+    let url = format!(
+        "{}/projects/{}/aggregated/addresses",
+        gcp::COMPUTE_API,
+        project_id
+    );
+    let pages = gcp::list_pages("compute.addresses.aggregatedList", &url).await?;
+
+    let mut resources = Vec::new();
+    for page in &pages {
+        let Some(scopes) = page["items"].as_object() else {
+            continue;
+        };
+        for address in scopes
+            .values()
+            .filter_map(|scope| scope["addresses"].as_array())
+            .flatten()
+        {
+            let reserved = address["status"].as_str() == Some("RESERVED");
+            let external = address["addressType"].as_str().unwrap_or("EXTERNAL") == "EXTERNAL";
+            if !reserved || !external {
+                continue;
+            }
+
+            let name = address["name"].as_str().unwrap_or_default().to_string();
+            resources.push(ResourceInfo {
+                id: name.clone(),
+                name: format!(
+                    "{} ({})",
+                    name,
+                    address["address"].as_str().unwrap_or_default()
+                ),
+                resource_type: GCP_STATIC_IP.to_string(),
+                region: address["region"]
+                    .as_str()
+                    .map(gcp::short_name)
+                    .unwrap_or("global")
+                    .to_string(),
+                state: "RESERVED".to_string(),
+                creation_date: parse_gcp_timestamp(&address["creationTimestamp"]),
+                last_used: None,
+                utilization: 0.0,
+                estimated_cost: GCP_STATIC_IP_MONTHLY_COST,
+                tags: gcp_labels(&address["labels"]),
+                account: None,
+            });
+        }
+    }
+
+    Ok(resources)
+}
 
-    let subscription_id = std::env::var("AZURE_SUBSCRIPTION_ID")
-        .map_err(|_| AppError::ConfigurationError("AZURE_SUBSCRIPTION_ID not set".to_string()))?;
+/// Disk snapshots older than the retention threshold, priced by the storage
+/// they hold.
+async fn find_gcp_aged_snapshots(
+    project_id: &str,
+    retention_days: u32,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let url = format!(
+        "{}/projects/{}/global/snapshots",
+        gcp::COMPUTE_API,
+        project_id
+    );
+    let pages = gcp::list_pages("compute.snapshots.list", &url).await?;
+
+    let mut resources = Vec::new();
+    for snapshot in pages
+        .iter()
+        .filter_map(|page| page["items"].as_array())
+        .flatten()
+    {
+        let Some(created) = parse_gcp_timestamp(&snapshot["creationTimestamp"]) else {
+            continue;
+        };
+        if (Utc::now() - created).num_days() <= retention_days as i64 {
+            continue;
+        }
+
+        // int64 fields arrive as strings in the Compute API
+        let storage_gb = snapshot["storageBytes"]
+            .as_str()
+            .and_then(|b| b.parse::<f64>().ok())
+            .unwrap_or(0.0)
+            / 1_000_000_000.0;
+        let name = snapshot["name"].as_str().unwrap_or_default().to_string();
+        resources.push(ResourceInfo {
+            id: name.clone(),
+            name,
+            resource_type: GCP_SNAPSHOT.to_string(),
+            region: "global".to_string(),
+            state: snapshot["status"].as_str().unwrap_or("READY").to_string(),
+            creation_date: Some(created),
+            last_used: None,
+            utilization: 0.0,
+            estimated_cost: storage_gb * GCP_SNAPSHOT_PRICE_PER_GB,
+            tags: gcp_labels(&snapshot["labels"]),
+            account: None,
+        });
+    }
+
+    Ok(resources)
+}
+
+fn parse_gcp_timestamp(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.as_str()?)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+fn gcp_labels(value: &serde_json::Value) -> HashMap<String, String> {
+    value
+        .as_object()
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[allow(unused_variables, dead_code)]
+pub(crate) async fn find_azure_resources(
+    resource_type: &ResourceType,
+    age_threshold: u32,
+    utilization_threshold: f64,
+    resource_group: Option<&str>,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    tracing::info!("🔍 Scanning Azure resources...");
+
+    let subscription_id = azure::subscription_id()?;
 
-    // Simulate finding resources
     let mut resources = Vec::new();
 
     match resource_type {
         ResourceType::Ec2 => {
-            // Simulate finding stopped VMs
-            resources.push(ResourceInfo {
-                id: "azure-vm-1".to_string(),
-                name: "test-vm".to_string(),
-                resource_type: "Virtual Machine".to_string(),
-                region: "East US".to_string(),
-                state: "Stopped".to_string(),
-                creation_date: Some(Utc::now() - Duration::days(50)),
-                last_used: Some(Utc::now() - Duration::days(45)),
-                utilization: 3.0,
-                estimated_cost: 30.0,
-                tags: HashMap::new(),
-            });
+            resources.extend(find_azure_stopped_vms(age_threshold).await?);
+        }
+
+        ResourceType::NetworkInterface => {
+            resources.extend(find_azure_orphaned_nics(&subscription_id).await?);
+        }
+
+        ResourceType::StaticIp => {
+            resources.extend(find_azure_orphaned_public_ips(&subscription_id).await?);
+        }
+
+        ResourceType::Rds => {
+            let servers = databases::azure_databases().await?;
+            resources.extend(find_idle_databases(servers, age_threshold).await?);
+        }
+
+        ResourceType::All => {
+            resources.extend(find_azure_orphaned_nics(&subscription_id).await?);
+            resources.extend(find_azure_orphaned_public_ips(&subscription_id).await?);
+            let servers = databases::azure_databases().await?;
+            resources.extend(find_idle_databases(servers, age_threshold).await?);
         }
 
         _ => {
-            println!(
+            eprintln!(
                 "   Resource type {:?} not yet implemented for Azure",
                 resource_type
             );
         }
     }
 
+    resources.retain(|r| azure::in_resource_group(&r.id, resource_group));
     Ok(resources)
 }
 
-#[allow(unused_variables, dead_code)]
-fn analyze_cleanup_actions(
+/// Running instances older than `age_threshold` days whose average CPU over
+/// the last `lookback_days` days stayed under `utilization_threshold`
+/// percent, and that moved little data over the network or to their EBS
+/// volumes. Instances CloudWatch has no CPU data for are left alone.
+async fn find_underused_instances(
+    config: &SdkConfig,
+    instances: &[&aws_sdk_ec2::types::Instance],
+    region: &str,
+    age_threshold: u32,
+    utilization_threshold: f64,
+    lookback_days: u32,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    use aws_sdk_cloudwatch::types::Statistic;
+
+    let candidates: Vec<_> = instances
+        .iter()
+        .filter(|instance| {
+            aws_datetime(instance.launch_time())
+                .is_some_and(|launched| (Utc::now() - launched).num_days() > age_threshold as i64)
+        })
+        .collect();
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Per instance, in this order: CPU, then daily sums of network and disk
+    // traffic
+    let metrics = [
+        ("CPUUtilization", Statistic::Average),
+        ("NetworkIn", Statistic::Sum),
+        ("NetworkOut", Statistic::Sum),
+        ("EBSReadBytes", Statistic::Sum),
+        ("EBSWriteBytes", Statistic::Sum),
+    ];
+    let queries: Vec<MetricQuery> = candidates
+        .iter()
+        .flat_map(|instance| {
+            metrics.iter().map(|(metric, statistic)| {
+                MetricQuery::new(
+                    "AWS/EC2",
+                    metric,
+                    "InstanceId",
+                    instance.instance_id().unwrap_or_default(),
+                    statistic.clone(),
+                )
+            })
+        })
+        .collect();
+    let end = Utc::now();
+    let start = end - Duration::days(lookback_days as i64);
+    let cloudwatch_client = clients::get::<aws_sdk_cloudwatch::Client>(config).await;
+    let series = cloudwatch::fetch(&cloudwatch_client, &queries, &start, &end, 86400)
+        .await
+        .map_err(|e| e.in_region(region))?;
+    let averages = cloudwatch::averages(&series);
+
+    let mut resources = Vec::new();
+    for (instance, values) in candidates.into_iter().zip(averages.chunks(metrics.len())) {
+        let Some(cpu) = values[0].filter(|cpu| *cpu < utilization_threshold) else {
+            continue;
+        };
+        // No traffic datapoints means no traffic
+        let network = values[1].unwrap_or(0.0) + values[2].unwrap_or(0.0);
+        let disk = values[3].unwrap_or(0.0) + values[4].unwrap_or(0.0);
+        if network >= IDLE_NETWORK_BYTES_PER_DAY || disk >= IDLE_DISK_BYTES_PER_DAY {
+            continue;
+        }
+        let id = instance.instance_id().unwrap_or_default().to_string();
+        let instance_type = instance
+            .instance_type()
+            .map(|t| t.as_str())
+            .unwrap_or_default();
+        resources.push(ResourceInfo {
+            id: id.clone(),
+            name: id,
+            resource_type: "EC2 Instance".to_string(),
+            region: region.to_string(),
+            state: "Running".to_string(),
+            creation_date: aws_datetime(instance.launch_time()),
+            last_used: None,
+            utilization: cpu,
+            estimated_cost: pricing::instance_monthly_cost(config, instance_type, region).await,
+            tags: instance
+                .tags()
+                .iter()
+                .filter_map(|t| Some((t.key()?.to_string(), t.value()?.to_string())))
+                .collect(),
+            account: None,
+        });
+    }
+    Ok(resources)
+}
+
+/// EBS volumes attached to nothing and created more than `age_threshold`
+/// days ago. They bill for their full size whether used or not.
+async fn find_unattached_volumes(
+    config: &SdkConfig,
+    region: &str,
+    age_threshold: u32,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    let mut request = ec2_client.describe_volumes().filters(
+        aws_sdk_ec2::types::Filter::builder()
+            .name("status")
+            .values("available")
+            .build(),
+    );
+    if let Some(filter) = scan_id_filter("vol-", "volume-id") {
+        request = request.filters(filter);
+    }
+    let mut pages = request.into_paginator().send();
+
+    let mut resources = Vec::new();
+    loop {
+        executor::throttle("ec2").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("ec2:DescribeVolumes", e).in_region(region))?;
+        for volume in page.volumes() {
+            let Some(volume_id) = volume.volume_id() else {
+                continue;
+            };
+            let created = aws_datetime(volume.create_time());
+            if !created.is_some_and(|c| (Utc::now() - c).num_days() > age_threshold as i64) {
+                continue;
+            }
+
+            let size_gb = volume.size().unwrap_or(0);
+            let volume_type = volume
+                .volume_type()
+                .map(|t| t.as_str())
+                .unwrap_or("standard");
+            let estimated_cost =
+                pricing::volume_monthly_cost(config, volume_type, size_gb, region).await;
+            resources.push(ResourceInfo {
+                id: volume_id.to_string(),
+                name: format!("{} ({} GiB {})", volume_id, size_gb, volume_type),
+                resource_type: EBS_VOLUME.to_string(),
+                region: region.to_string(),
+                state: "available".to_string(),
+                creation_date: created,
+                last_used: None,
+                utilization: 0.0,
+                estimated_cost,
+                tags: volume
+                    .tags()
+                    .iter()
+                    .filter_map(|t| Some((t.key()?.to_string(), t.value()?.to_string())))
+                    .collect(),
+                account: None,
+            });
+        }
+    }
+
+    Ok(resources)
+}
+
+fn default_lookback_days() -> u32 {
+    DEFAULT_LOOKBACK_DAYS
+}
+
+/// Monthly storage price of the EBS volumes attached to `instance`.
+async fn attached_volume_cost(
+    config: &SdkConfig,
+    instance: &aws_sdk_ec2::types::Instance,
+    region: &str,
+) -> Result<f64, AppError> {
+    let volume_ids: Vec<String> = instance
+        .block_device_mappings()
+        .iter()
+        .filter_map(|mapping| mapping.ebs()?.volume_id())
+        .map(str::to_string)
+        .collect();
+    if volume_ids.is_empty() {
+        return Ok(0.0);
+    }
+
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    executor::throttle("ec2").await;
+    let response = ec2_client
+        .describe_volumes()
+        .set_volume_ids(Some(volume_ids))
+        .send()
+        .await
+        .map_err(|e| {
+            AppError::aws("ec2:DescribeVolumes", e)
+                .for_resource(instance.instance_id().unwrap_or_default())
+                .in_region(region)
+        })?;
+
+    let mut cost = 0.0;
+    for volume in response.volumes() {
+        cost += pricing::volume_monthly_cost(
+            config,
+            volume
+                .volume_type()
+                .map(|t| t.as_str())
+                .unwrap_or("standard"),
+            volume.size().unwrap_or(0),
+            region,
+        )
+        .await;
+    }
+    Ok(cost)
+}
+
+/// Snapshots of ours older than `age_threshold` days whose source volume is
+/// gone and that no AMI of ours is built from. Copies of other snapshots
+/// have no source volume to go by and are left alone.
+async fn find_orphaned_snapshots(
+    config: &SdkConfig,
+    region: &str,
+    age_threshold: u32,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+
+    let mut volumes = HashSet::new();
+    let mut pages = ec2_client.describe_volumes().into_paginator().send();
+    loop {
+        executor::throttle("ec2").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("ec2:DescribeVolumes", e).in_region(region))?;
+        volumes.extend(
+            page.volumes()
+                .iter()
+                .filter_map(|v| v.volume_id())
+                .map(str::to_string),
+        );
+    }
+
+    // Deleting a snapshot an AMI is registered from fails anyway
+    executor::throttle("ec2").await;
+    let images = ec2_client
+        .describe_images()
+        .owners("self")
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeImages", e).in_region(region))?;
+    let in_use: HashSet<&str> = images
+        .images()
+        .iter()
+        .flat_map(|image| image.block_device_mappings())
+        .filter_map(|mapping| mapping.ebs()?.snapshot_id())
+        .collect();
+
+    let mut resources = Vec::new();
+    let mut pages = ec2_client
+        .describe_snapshots()
+        .owner_ids("self")
+        .filters(
+            aws_sdk_ec2::types::Filter::builder()
+                .name("status")
+                .values("completed")
+                .build(),
+        )
+        .into_paginator()
+        .send();
+    loop {
+        executor::throttle("ec2").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("ec2:DescribeSnapshots", e).in_region(region))?;
+        for snapshot in page.snapshots() {
+            let (Some(snapshot_id), Some(volume_id)) =
+                (snapshot.snapshot_id(), snapshot.volume_id())
+            else {
+                continue;
+            };
+            if volume_id == COPIED_SNAPSHOT_VOLUME
+                || volumes.contains(volume_id)
+                || in_use.contains(snapshot_id)
+            {
+                continue;
+            }
+            let created = aws_datetime(snapshot.start_time());
+            if !created.is_some_and(|c| (Utc::now() - c).num_days() > age_threshold as i64) {
+                continue;
+            }
+
+            // Snapshots taken before full sizes were reported fall back to
+            // the volume size, an upper bound
+            let size_gb = snapshot
+                .full_snapshot_size_in_bytes()
+                .map(|bytes| bytes as f64 / (1u64 << 30) as f64)
+                .unwrap_or(snapshot.volume_size().unwrap_or(0) as f64);
+            resources.push(ResourceInfo {
+                id: snapshot_id.to_string(),
+                name: snapshot
+                    .description()
+                    .filter(|d| !d.is_empty())
+                    .unwrap_or(snapshot_id)
+                    .to_string(),
+                resource_type: EBS_SNAPSHOT.to_string(),
+                region: region.to_string(),
+                state: format!("source {} deleted", volume_id),
+                creation_date: created,
+                last_used: None,
+                utilization: 0.0,
+                estimated_cost: size_gb * EBS_SNAPSHOT_PRICE_PER_GB,
+                tags: snapshot
+                    .tags()
+                    .iter()
+                    .filter_map(|t| Some((t.key()?.to_string(), t.value()?.to_string())))
+                    .collect(),
+                account: None,
+            });
+        }
+    }
+
+    Ok(resources)
+}
+
+/// What an S3 bucket holds, counted up to [`MAX_COUNTED_OBJECTS`] objects.
+struct BucketContents {
+    objects: usize,
+    bytes: i64,
+    /// More objects than were counted
+    truncated: bool,
+    /// Noncurrent versions or delete markers, which keep a bucket from being
+    /// deleted even with no current objects
+    old_versions: bool,
+}
+
+impl BucketContents {
+    fn state(&self) -> String {
+        match (self.objects, self.truncated, self.old_versions) {
+            (0, _, false) => BUCKET_EMPTY.to_string(),
+            (0, _, true) => "old versions only".to_string(),
+            (objects, true, _) => format!("{}+ objects", objects),
+            (objects, false, _) => format!("{} objects", objects),
+        }
+    }
+
+    fn size_gb(&self) -> f64 {
+        self.bytes as f64 / (1u64 << 30) as f64
+    }
+}
+
+async fn bucket_contents(
+    config: &SdkConfig,
+    bucket: &str,
+    region: &str,
+) -> Result<BucketContents, AppError> {
+    let regional = regions::regional_config(config, region);
+    let s3_client = clients::get::<aws_sdk_s3::Client>(&regional).await;
+
+    let mut contents = BucketContents {
+        objects: 0,
+        bytes: 0,
+        truncated: false,
+        old_versions: false,
+    };
+    let mut pages = s3_client
+        .list_objects_v2()
+        .bucket(bucket)
+        .into_paginator()
+        .send();
+    loop {
+        executor::throttle("s3").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| {
+            AppError::aws("s3:ListObjectsV2", e)
+                .for_resource(bucket)
+                .in_region(region)
+        })?;
+        contents.objects += page.contents().len();
+        contents.bytes += page
+            .contents()
+            .iter()
+            .filter_map(|object| object.size())
+            .sum::<i64>();
+        if contents.objects >= MAX_COUNTED_OBJECTS {
+            contents.truncated = page.is_truncated().unwrap_or(false);
+            break;
+        }
+    }
+
+    if contents.objects == 0 {
+        executor::throttle("s3").await;
+        let versions = s3_client
+            .list_object_versions()
+            .bucket(bucket)
+            .max_keys(1)
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::aws("s3:ListObjectVersions", e)
+                    .for_resource(bucket)
+                    .in_region(region)
+            })?;
+        contents.old_versions =
+            !versions.versions().is_empty() || !versions.delete_markers().is_empty();
+    }
+
+    Ok(contents)
+}
+
+/// Delete every object version and delete marker in `bucket`, a page of up
+/// to 1,000 at a time, so the bucket itself can be deleted.
+async fn empty_bucket(config: &SdkConfig, bucket: &str, region: &str) -> Result<(), AppError> {
+    use aws_sdk_s3::types::{Delete, ObjectIdentifier};
+
+    let regional = regions::regional_config(config, region);
+    let s3_client = clients::get::<aws_sdk_s3::Client>(&regional).await;
+    let mut deleted = 0;
+    let mut pages = s3_client
+        .list_object_versions()
+        .bucket(bucket)
+        .into_paginator()
+        .send();
+    loop {
+        executor::throttle("s3").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| {
+            AppError::aws("s3:ListObjectVersions", e)
+                .for_resource(bucket)
+                .in_region(region)
+        })?;
+
+        let versions = page
+            .versions()
+            .iter()
+            .map(|v| (v.key(), v.version_id()))
+            .chain(
+                page.delete_markers()
+                    .iter()
+                    .map(|m| (m.key(), m.version_id())),
+            );
+        let mut identifiers = Vec::new();
+        for (key, version_id) in versions {
+            let Some(key) = key else {
+                continue;
+            };
+            identifiers.push(
+                ObjectIdentifier::builder()
+                    .key(key)
+                    .set_version_id(version_id.map(str::to_string))
+                    .build()
+                    .map_err(|e| AppError::AwsError(e.to_string()))?,
+            );
+        }
+        if identifiers.is_empty() {
+            continue;
+        }
+
+        let count = identifiers.len();
+        executor::throttle("s3").await;
+        let response = s3_client
+            .delete_objects()
+            .bucket(bucket)
+            .delete(
+                Delete::builder()
+                    .set_objects(Some(identifiers))
+                    .quiet(true)
+                    .build()
+                    .map_err(|e| AppError::AwsError(e.to_string()))?,
+            )
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::aws("s3:DeleteObjects", e)
+                    .for_resource(bucket)
+                    .in_region(region)
+            })?;
+        if let Some(error) = response.errors().first() {
+            return Err(AppError::AwsError(format!(
+                "Could not delete {} from {}: {}",
+                error.key().unwrap_or_default(),
+                bucket,
+                error.message().unwrap_or_default()
+            )));
+        }
+        deleted += count;
+    }
+
+    tracing::info!("   🪣 Emptied {} ({} object versions)", bucket, deleted);
+    Ok(())
+}
+
+/// Elastic IPs associated with nothing. AWS bills every public IPv4 address,
+/// but an idle one is pure waste.
+async fn find_unassociated_addresses(
+    config: &SdkConfig,
+    region: &str,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    executor::throttle("ec2").await;
+    let response = ec2_client
+        .describe_addresses()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeAddresses", e).in_region(region))?;
+
+    Ok(response
+        .addresses()
+        .iter()
+        .filter(|address| address.association_id().is_none())
+        .filter_map(|address| {
+            let allocation_id = address.allocation_id()?;
+            Some(ResourceInfo {
+                id: allocation_id.to_string(),
+                name: format!(
+                    "{} ({})",
+                    allocation_id,
+                    address.public_ip().unwrap_or_default()
+                ),
+                resource_type: ELASTIC_IP.to_string(),
+                region: region.to_string(),
+                state: "unassociated".to_string(),
+                // AWS doesn't report when an address was allocated
+                creation_date: None,
+                last_used: None,
+                utilization: 0.0,
+                estimated_cost: ELASTIC_IP_MONTHLY_COST,
+                tags: address
+                    .tags()
+                    .iter()
+                    .filter_map(|t| Some((t.key()?.to_string(), t.value()?.to_string())))
+                    .collect(),
+                account: None,
+            })
+        })
+        .collect())
+}
+
+/// Application and network load balancers older than `age_threshold` days
+/// with no healthy target, or that served no requests over the last
+/// [`LOAD_BALANCER_IDLE_WINDOW_DAYS`] days. The request count is kept as the
+/// utilization.
+async fn find_unused_load_balancers(
+    config: &SdkConfig,
+    region: &str,
+    age_threshold: u32,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    use aws_sdk_elasticloadbalancingv2::types::{LoadBalancerTypeEnum, TargetHealthStateEnum};
+
+    let elb_client = clients::get::<aws_sdk_elasticloadbalancingv2::Client>(config).await;
+    let mut load_balancers = Vec::new();
+    let mut pages = elb_client.describe_load_balancers().into_paginator().send();
+    loop {
+        executor::throttle("elasticloadbalancing").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page =
+            page.map_err(|e| AppError::aws("elbv2:DescribeLoadBalancers", e).in_region(region))?;
+        load_balancers.extend(
+            page.load_balancers()
+                .iter()
+                .filter(|lb| {
+                    matches!(
+                        lb.r#type(),
+                        Some(LoadBalancerTypeEnum::Application | LoadBalancerTypeEnum::Network)
+                    )
+                })
+                .filter(|lb| {
+                    aws_datetime(lb.created_time()).is_some_and(|created| {
+                        (Utc::now() - created).num_days() > age_threshold as i64
+                    })
+                })
+                .cloned(),
+        );
+    }
+    if load_balancers.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // CloudWatch names a load balancer by the end of its ARN, e.g.
+    // `app/web/50dc6c495c0c9188`
+    let queries: Vec<MetricQuery> = load_balancers
+        .iter()
+        .map(|lb| {
+            let arn = lb.load_balancer_arn().unwrap_or_default();
+            let dimension = arn.split_once(":loadbalancer/").map_or(arn, |(_, d)| d);
+            let (namespace, metric) = match lb.r#type() {
+                Some(LoadBalancerTypeEnum::Network) => ("AWS/NetworkELB", "NewFlowCount"),
+                _ => ("AWS/ApplicationELB", "RequestCount"),
+            };
+            MetricQuery::new(
+                namespace,
+                metric,
+                "LoadBalancer",
+                dimension,
+                aws_sdk_cloudwatch::types::Statistic::Sum,
+            )
+        })
+        .collect();
+    let end = Utc::now();
+    let start = end - Duration::days(LOAD_BALANCER_IDLE_WINDOW_DAYS);
+    let cloudwatch_client = clients::get::<aws_sdk_cloudwatch::Client>(config).await;
+    let series = cloudwatch::fetch(&cloudwatch_client, &queries, &start, &end, 86400)
+        .await
+        .map_err(|e| e.in_region(region))?;
+
+    let mut resources = Vec::new();
+    for (lb, requests) in load_balancers.iter().zip(&series) {
+        let Some(arn) = lb.load_balancer_arn() else {
+            continue;
+        };
+        let requests: f64 = requests.values().sum();
+
+        let mut healthy_targets = 0;
+        executor::throttle("elasticloadbalancing").await;
+        let target_groups = elb_client
+            .describe_target_groups()
+            .load_balancer_arn(arn)
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::aws("elbv2:DescribeTargetGroups", e)
+                    .for_resource(arn)
+                    .in_region(region)
+            })?;
+        for group in target_groups.target_groups() {
+            let Some(group_arn) = group.target_group_arn() else {
+                continue;
+            };
+            executor::throttle("elasticloadbalancing").await;
+            let health = elb_client
+                .describe_target_health()
+                .target_group_arn(group_arn)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("elbv2:DescribeTargetHealth", e)
+                        .for_resource(group_arn)
+                        .in_region(region)
+                })?;
+            healthy_targets += health
+                .target_health_descriptions()
+                .iter()
+                .filter(|d| {
+                    d.target_health().and_then(|h| h.state())
+                        == Some(&TargetHealthStateEnum::Healthy)
+                })
+                .count();
+        }
+        if healthy_targets > 0 && requests > 0.0 {
+            continue;
+        }
+
+        resources.push(ResourceInfo {
+            id: arn.to_string(),
+            name: lb.load_balancer_name().unwrap_or_default().to_string(),
+            resource_type: LOAD_BALANCER.to_string(),
+            region: region.to_string(),
+            state: if healthy_targets == 0 {
+                "no healthy targets".to_string()
+            } else {
+                format!("{} healthy targets", healthy_targets)
+            },
+            creation_date: aws_datetime(lb.created_time()),
+            last_used: None,
+            utilization: requests,
+            estimated_cost: LOAD_BALANCER_MONTHLY_COST,
+            tags: HashMap::new(),
+            account: None,
+        });
+    }
+
+    // Tags matter for --exclude-tag and policies, so fetch them for the few
+    // candidates rather than for every load balancer
+    for batch in resources.chunks_mut(ELB_TAGS_BATCH) {
+        executor::throttle("elasticloadbalancing").await;
+        let response = elb_client
+            .describe_tags()
+            .set_resource_arns(Some(batch.iter().map(|r| r.id.clone()).collect()))
+            .send()
+            .await
+            .map_err(|e| AppError::aws("elbv2:DescribeTags", e).in_region(region))?;
+        for description in response.tag_descriptions() {
+            let Some(resource) = batch
+                .iter_mut()
+                .find(|r| Some(r.id.as_str()) == description.resource_arn())
+            else {
+                continue;
+            };
+            resource.tags = description
+                .tags()
+                .iter()
+                .filter_map(|t| Some((t.key().to_string(), t.value()?.to_string())))
+                .collect();
+        }
+    }
+
+    Ok(resources)
+}
+
+/// Running databases older than `age_threshold` days that nobody connected
+/// to in the last [`databases::IDLE_WINDOW_DAYS`] days. The connection count
+/// is kept as the utilization.
+async fn find_idle_databases(
+    candidates: Vec<ManagedDatabase>,
+    age_threshold: u32,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let mut resources = Vec::new();
+    for database in candidates {
+        let new = database
+            .creation_date
+            .is_some_and(|created| (Utc::now() - created).num_days() <= age_threshold as i64);
+        if database.stopped || new {
+            continue;
+        }
+
+        let connections = databases::connections(&database).await?;
+        if connections > 0.0 {
+            continue;
+        }
+        resources.push(ResourceInfo {
+            id: database.id,
+            name: database.name,
+            resource_type: database.resource_type,
+            region: database.region,
+            state: database.state,
+            creation_date: database.creation_date,
+            last_used: None,
+            utilization: connections,
+            estimated_cost: 0.0,
+            tags: database.tags,
+            account: None,
+        });
+    }
+    Ok(resources)
+}
+
+/// Days since `last_used`, or since `created` for identities never used.
+fn days_idle(last_used: Option<DateTime<Utc>>, created: Option<DateTime<Utc>>) -> i64 {
+    last_used
+        .or(created)
+        .map(|at| (Utc::now() - at).num_days())
+        .unwrap_or_default()
+}
+
+/// Access keys not used for more than `age_threshold` days. Active keys are
+/// deactivated first and deleted by a later run once still unused.
+async fn find_stale_access_keys(
+    config: &SdkConfig,
+    age_threshold: u32,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let client = clients::get::<aws_sdk_iam::Client>(config).await;
+    let mut resources = Vec::new();
+
+    let mut users = client.list_users().into_paginator().send();
+    loop {
+        executor::throttle("iam").await;
+        let Some(page) = users.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("iam:ListUsers", e))?;
+
+        for user in page.users() {
+            let user_name = user.user_name();
+            executor::throttle("iam").await;
+            let keys = client
+                .list_access_keys()
+                .user_name(user_name)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("iam:ListAccessKeys", e).for_resource(user_name))?;
+
+            // Keys carry no tags of their own; they inherit their user's,
+            // looked up only for users with a stale key
+            let mut user_tags: Option<HashMap<String, String>> = None;
+            for key in keys.access_key_metadata() {
+                let key_id = key.access_key_id().unwrap_or_default();
+                executor::throttle("iam").await;
+                let last_used = client
+                    .get_access_key_last_used()
+                    .access_key_id(key_id)
+                    .send()
+                    .await
+                    .map_err(|e| AppError::aws("iam:GetAccessKeyLastUsed", e).for_resource(key_id))?
+                    .access_key_last_used()
+                    .and_then(|u| aws_datetime(u.last_used_date()));
+                let created = aws_datetime(key.create_date());
+
+                if days_idle(last_used, created) <= age_threshold as i64 {
+                    continue;
+                }
+                if user_tags.is_none() {
+                    executor::throttle("iam").await;
+                    let response = client
+                        .list_user_tags()
+                        .user_name(user_name)
+                        .send()
+                        .await
+                        .map_err(|e| {
+                            AppError::aws("iam:ListUserTags", e).for_resource(user_name)
+                        })?;
+                    user_tags = Some(
+                        response
+                            .tags()
+                            .iter()
+                            .map(|t| (t.key().to_string(), t.value().to_string()))
+                            .collect(),
+                    );
+                }
+
+                resources.push(ResourceInfo {
+                    id: key_id.to_string(),
+                    name: user_name.to_string(),
+                    resource_type: IAM_ACCESS_KEY.to_string(),
+                    region: "global".to_string(),
+                    state: key
+                        .status()
+                        .map(|s| s.as_str().to_string())
+                        .unwrap_or_default(),
+                    creation_date: created,
+                    last_used,
+                    utilization: 0.0,
+                    estimated_cost: 0.0,
+                    tags: user_tags.clone().unwrap_or_default(),
+                    account: None,
+                });
+            }
+        }
+    }
+
+    Ok(resources)
+}
+
+/// Roles with no recorded activity for more than `age_threshold` days, going
+/// by the last-used data IAM keeps from Access Advisor.
+async fn find_unused_roles(
+    config: &SdkConfig,
+    age_threshold: u32,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let client = clients::get::<aws_sdk_iam::Client>(config).await;
+    let mut resources = Vec::new();
+
+    let mut roles = client.list_roles().into_paginator().send();
+    loop {
+        executor::throttle("iam").await;
+        let Some(page) = roles.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("iam:ListRoles", e))?;
+
+        for role in page.roles() {
+            if role.path().starts_with(SERVICE_LINKED_ROLE_PATH) {
+                continue;
+            }
+
+            // ListRoles leaves out RoleLastUsed; GetRole has it
+            let role_name = role.role_name();
+            executor::throttle("iam").await;
+            let detail = client
+                .get_role()
+                .role_name(role_name)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("iam:GetRole", e).for_resource(role_name))?;
+            let Some(role) = detail.role() else {
+                continue;
+            };
+
+            let last_used = role
+                .role_last_used()
+                .and_then(|u| aws_datetime(u.last_used_date()));
+            let created = aws_datetime(Some(role.create_date()));
+            if days_idle(last_used, created) <= age_threshold as i64 {
+                continue;
+            }
+
+            resources.push(ResourceInfo {
+                id: role_name.to_string(),
+                name: role_name.to_string(),
+                resource_type: IAM_ROLE.to_string(),
+                region: "global".to_string(),
+                state: "active".to_string(),
+                creation_date: created,
+                last_used,
+                utilization: 0.0,
+                estimated_cost: 0.0,
+                tags: role
+                    .tags()
+                    .iter()
+                    .map(|t| (t.key().to_string(), t.value().to_string()))
+                    .collect(),
+                account: None,
+            });
+        }
+    }
+
+    Ok(resources)
+}
+
+/// Stopped or deallocated VMs created more than `age_threshold` days ago.
+/// Their disks and NICs outlive them and are picked up by later runs.
+async fn find_azure_stopped_vms(age_threshold: u32) -> Result<Vec<ResourceInfo>, AppError> {
+    let vms = azure::virtual_machines().await?;
+
+    Ok(vms
+        .iter()
+        .filter_map(|vm| {
+            let state = azure::power_state(vm);
+            if state != "stopped" && state != "deallocated" {
+                return None;
+            }
+            let created = vm["properties"]["timeCreated"]
+                .as_str()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())?
+                .with_timezone(&Utc);
+            if (Utc::now() - created).num_days() <= age_threshold as i64 {
+                return None;
+            }
+
+            let mut resource = azure_resource(vm, AZURE_VM, &state, 0.0);
+            resource.creation_date = Some(created);
+            Some(resource)
+        })
+        .collect())
+}
+
+/// Network interfaces attached to no VM, load balancer or private endpoint.
+/// Deleting a VM in the portal leaves these behind.
+async fn find_azure_orphaned_nics(subscription_id: &str) -> Result<Vec<ResourceInfo>, AppError> {
+    let url = format!(
+        "{}/subscriptions/{}/providers/Microsoft.Network/networkInterfaces?api-version={}",
+        azure::MANAGEMENT_API,
+        subscription_id,
+        azure::NETWORK_API_VERSION
+    );
+    let nics = azure::list("Microsoft.Network/networkInterfaces/read", &url).await?;
+
+    Ok(nics
+        .iter()
+        .filter(|nic| {
+            let properties = &nic["properties"];
+            let in_backend_pool = properties["ipConfigurations"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|config| {
+                    config["properties"]["loadBalancerBackendAddressPools"]
+                        .as_array()
+                        .is_some_and(|pools| !pools.is_empty())
+                });
+            properties["virtualMachine"].is_null()
+                && properties["privateEndpoint"].is_null()
+                && !in_backend_pool
+        })
+        .map(|nic| azure_resource(nic, AZURE_NIC, "Detached", 0.0))
+        .collect())
+}
+
+/// Public IP addresses associated with no NIC, load balancer or NAT gateway.
+async fn find_azure_orphaned_public_ips(
+    subscription_id: &str,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let url = format!(
+        "{}/subscriptions/{}/providers/Microsoft.Network/publicIPAddresses?api-version={}",
+        azure::MANAGEMENT_API,
+        subscription_id,
+        azure::NETWORK_API_VERSION
+    );
+    let addresses = azure::list("Microsoft.Network/publicIPAddresses/read", &url).await?;
+
+    Ok(addresses
+        .iter()
+        .filter(|ip| {
+            ip["properties"]["ipConfiguration"].is_null()
+                && ip["properties"]["natGateway"].is_null()
+        })
+        .map(|ip| {
+            let allocation = ip["properties"]["publicIPAllocationMethod"]
+                .as_str()
+                .unwrap_or("Static");
+            let cost = if allocation == "Static" {
+                AZURE_STATIC_PUBLIC_IP_MONTHLY_COST
+            } else {
+                0.0
+            };
+            azure_resource(ip, AZURE_PUBLIC_IP, "Unassociated", cost)
+        })
+        .collect())
+}
+
+/// Cleanup candidate for an ARM resource; its ARM id is what deletion needs.
+fn azure_resource(
+    resource: &serde_json::Value,
+    resource_type: &str,
+    state: &str,
+    estimated_cost: f64,
+) -> ResourceInfo {
+    ResourceInfo {
+        id: resource["id"].as_str().unwrap_or_default().to_string(),
+        name: resource["name"].as_str().unwrap_or_default().to_string(),
+        resource_type: resource_type.to_string(),
+        region: resource["location"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        state: state.to_string(),
+        creation_date: None,
+        last_used: None,
+        utilization: 0.0,
+        estimated_cost,
+        tags: resource["tags"]
+            .as_object()
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        account: None,
+    }
+}
+
+/// The cleanup action for each of `resources` the tag selection allows.
+/// With a `policy` its first matching rule decides; otherwise, and for
+/// rules with `action: default`, the built-in heuristics below do.
+pub(crate) fn analyze_cleanup_actions(
     resources: &[ResourceInfo],
     provider: &CloudProvider,
+    tag_selection: &TagSelection,
+    policy: Option<&CleanupPolicy>,
 ) -> Result<Vec<CleanupAction>, AppError> {
+    let provider_name = provider.to_string();
     let mut actions = Vec::new();
 
-    for resource in resources {
-        let mut reason = String::new();
-        let mut estimated_savings = 0.0;
+    for resource in resources.iter().filter(|r| tag_selection.selects(&r.tags)) {
+        let Some(policy) = policy else {
+            actions.extend(default_action(resource));
+            continue;
+        };
+        let Some(rule) = policy.evaluate(&provider_name, &policy_view(resource)) else {
+            continue;
+        };
+        let Some(action_type) = rule.action.action_type() else {
+            if rule.action == RuleAction::Default {
+                actions.extend(default_action(resource));
+            }
+            continue;
+        };
+
+        // Anything else would fall through to deleting the resource
+        if !supports_action(action_type, &resource.resource_type) {
+            tracing::warn!(
+                "⚠️  Rule '{}' can't {} {} {}; skipping it",
+                rule.name,
+                action_type.to_lowercase(),
+                resource.resource_type,
+                resource.id
+            );
+            continue;
+        }
+        actions.push(CleanupAction {
+            action_type: action_type.to_string(),
+            resource: resource.clone(),
+            reason: rule.reason.clone().unwrap_or_else(|| rule.name.clone()),
+            estimated_savings: if action_type == "DEACTIVATE" {
+                0.0
+            } else {
+                resource.estimated_cost
+            },
+        });
+    }
+
+    Ok(actions)
+}
+
+/// What cleanup does with `resource` without a policy, if anything.
+fn default_action(resource: &ResourceInfo) -> Option<CleanupAction> {
+    let mut reason = String::new();
+    let mut estimated_savings = 0.0;
+    let mut action_type = "DELETE";
 
-        // Determine cleanup reason and savings
-        if resource.utilization < 10.0 {
-            reason = format!("Low utilization ({:.1}%)", resource.utilization);
+    // Determine cleanup reason and savings
+    if resource.resource_type == IAM_ACCESS_KEY || resource.resource_type == IAM_ROLE {
+        let idle = days_idle(resource.last_used, resource.creation_date);
+        reason = match resource.last_used {
+            Some(_) => format!("Unused for {} days", idle),
+            None => format!("Never used ({} days old)", idle),
+        };
+        // Keys are switched off first so a missed dependency can be undone
+        if resource.resource_type == IAM_ACCESS_KEY && resource.state == "Active" {
+            action_type = "DEACTIVATE";
+        }
+    } else if [
+        cdn::CLOUDFRONT_DISTRIBUTION,
+        cdn::CLOUD_CDN_BACKEND,
+        cdn::AZURE_CDN_ENDPOINT,
+    ]
+    .contains(&resource.resource_type.as_str())
+    {
+        reason = format!("No requests in {} days", cdn::IDLE_WINDOW_DAYS);
+    } else if databases::is_database(&resource.resource_type) {
+        reason = format!("No connections in {} days", databases::IDLE_WINDOW_DAYS);
+        action_type = databases::cleanup_action(&resource.resource_type);
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == GCP_STATIC_IP {
+        reason = "Reserved but unattached".to_string();
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == GCP_SNAPSHOT {
+        let age = resource
+            .creation_date
+            .map(|created| (Utc::now() - created).num_days())
+            .unwrap_or_default();
+        reason = format!("Past retention ({} days)", age);
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == "S3 Bucket" {
+        let age = days_idle(None, resource.creation_date);
+        reason = if resource.state == BUCKET_EMPTY {
+            format!("Empty ({} days old)", age)
+        } else {
+            format!(
+                "{}, ${:.2}/month in storage ({} days old)",
+                resource.state, resource.estimated_cost, age
+            )
+        };
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == ELASTIC_IP {
+        reason = "Not associated with anything".to_string();
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == LOAD_BALANCER {
+        reason = if resource.utilization == 0.0 {
+            format!("No requests in {} days", LOAD_BALANCER_IDLE_WINDOW_DAYS)
+        } else {
+            "No healthy targets".to_string()
+        };
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == EBS_VOLUME {
+        reason = "Not attached to any instance".to_string();
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == EBS_SNAPSHOT {
+        reason = "Source volume deleted and no AMI uses it".to_string();
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == AZURE_NIC || resource.resource_type == AZURE_PUBLIC_IP {
+        reason = "Not attached to anything".to_string();
+        estimated_savings = resource.estimated_cost;
+    } else if resource.resource_type == "EC2 Instance" && resource.state == "Running" {
+        reason = format!(
+            "Idle ({:.1}% average CPU, little network or disk traffic)",
+            resource.utilization
+        );
+        // Stopped rather than terminated: its volumes and address stay
+        action_type = "STOP";
+        estimated_savings = resource.estimated_cost;
+    } else if resource.utilization < 10.0 {
+        reason = format!("Low utilization ({:.1}%)", resource.utilization);
+        estimated_savings = resource.estimated_cost;
+    } else if let Some(creation_date) = resource.creation_date {
+        let age = Utc::now() - creation_date;
+        if age.num_days() > 30 {
+            reason = format!("Old resource ({} days)", age.num_days());
             estimated_savings = resource.estimated_cost;
-        } else if let Some(creation_date) = resource.creation_date {
-            let age = Utc::now() - creation_date;
-            if age.num_days() > 30 {
-                reason = format!("Old resource ({} days)", age.num_days());
-                estimated_savings = resource.estimated_cost;
-            }
         }
+    }
 
-        if !reason.is_empty() {
-            actions.push(CleanupAction {
-                action_type: "DELETE".to_string(),
-                resource: (*resource).clone(),
-                reason,
-                estimated_savings,
-            });
+    if reason.is_empty() {
+        return None;
+    }
+    Some(CleanupAction {
+        action_type: action_type.to_string(),
+        resource: resource.clone(),
+        reason,
+        estimated_savings,
+    })
+}
+
+/// Whether cleanup can carry out `action_type` on this type of resource.
+fn supports_action(action_type: &str, resource_type: &str) -> bool {
+    match action_type {
+        "STOP" => {
+            resource_type == "EC2 Instance"
+                || (databases::is_database(resource_type)
+                    && databases::cleanup_action(resource_type) == "STOP")
         }
+        "DEACTIVATE" => resource_type == IAM_ACCESS_KEY,
+        _ => true,
     }
+}
 
-    Ok(actions)
+/// `resource` as policy expressions see it: the usual query fields, plus
+/// `info.utilization`, `info.estimated_cost` and `info.idle_days`.
+fn policy_view(resource: &ResourceInfo) -> ResourceSummary {
+    let mut additional_info = HashMap::from([
+        ("utilization".to_string(), resource.utilization.to_string()),
+        (
+            "estimated_cost".to_string(),
+            resource.estimated_cost.to_string(),
+        ),
+    ]);
+    if let Some(last_used) = resource.last_used {
+        additional_info.insert(
+            "idle_days".to_string(),
+            (Utc::now() - last_used).num_days().to_string(),
+        );
+    }
+
+    ResourceSummary {
+        id: resource.id.clone(),
+        name: resource.name.clone(),
+        resource_type: resource.resource_type.clone(),
+        region: resource.region.clone(),
+        state: resource.state.clone(),
+        creation_date: resource.creation_date,
+        tags: resource.tags.clone(),
+        additional_info,
+        account: resource.account.clone(),
+    }
+}
+
+/// Whether `actions` cover more than one of the resource types that depend on
+/// each other (volumes on instances, load balancers on their targets); the
+/// graph is worth reading only then.
+fn needs_ordering(actions: &[CleanupAction]) -> bool {
+    const DEPENDENT_TYPES: [&str; 3] = ["EC2 Instance", EBS_VOLUME, LOAD_BALANCER];
+    let types: HashSet<&str> = actions
+        .iter()
+        .map(|a| a.resource.resource_type.as_str())
+        .filter(|t| DEPENDENT_TYPES.contains(t))
+        .collect();
+    types.len() > 1
 }
 
-fn display_cleanup_summary(actions: &[CleanupAction]) -> Result<(), AppError> {
-    println!("\n📋 Resources identified for cleanup:");
-    println!(
+fn order_cleanup_actions(dependency_graph: &ResourceGraph, actions: &mut [CleanupAction]) {
+    let ids: Vec<String> = actions.iter().map(|a| a.resource.id.clone()).collect();
+    let order = dependency_graph.deletion_order(&ids);
+    actions.sort_by_key(|a| order.iter().position(|id| *id == a.resource.id));
+}
+
+fn display_cleanup_summary(
+    actions: &[CleanupAction],
+    creators: &HashMap<String, Creator>,
+) -> Result<(), AppError> {
+    let show_account = actions.iter().any(|a| a.resource.account.is_some());
+    let show_creator = !creators.is_empty();
+
+    outln!("\n📋 Resources identified for cleanup:");
+    if show_account {
+        out!("{:<14} ", "Account");
+    }
+    out!(
         "{:<15} {:<20} {:<15} {:<20} {:<15}",
-        "Type", "Name", "State", "Reason", "Savings ($)"
+        "Type",
+        "Name",
+        "State",
+        "Reason",
+        "Savings ($)"
     );
-    println!("{:-<85}", "");
+    if show_creator {
+        out!(" {}", "Created by");
+    }
+    outln!();
+    outln!("{:-<85}", "");
 
     for action in actions {
-        println!(
+        if show_account {
+            out!("{:<14} ", action.resource.account.as_deref().unwrap_or("-"));
+        }
+        out!(
             "{:<15} {:<20} {:<15} {:<20} {:<15.2}",
             action.resource.resource_type,
             action.resource.name,
@@ -383,41 +2943,249 @@ fn display_cleanup_summary(actions: &[CleanupAction]) -> Result<(), AppError> {
             action.reason,
             action.estimated_savings
         );
+        if show_creator {
+            match creators.get(&action.resource.id) {
+                Some(creator) => out!(" {}", creator.principal),
+                None => out!(" {}", "-"),
+            }
+        }
+        outln!();
     }
 
     Ok(())
 }
 
-async fn execute_aws_cleanup(action: &CleanupAction) -> Result<(), AppError> {
-    let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-        .load()
-        .await;
+/// Printable version of the cleanup summary.
+fn cleanup_summary_html(
+    provider: &CloudProvider,
+    actions: &[CleanupAction],
+    total_savings: f64,
+    team_totals: Option<&BTreeMap<String, (usize, f64)>>,
+) -> HtmlReport {
+    let mut html = HtmlReport::new(&format!("{} cleanup summary", provider));
+    html.paragraph(&format!(
+        "{} resources identified, estimated monthly savings {:.2} USD",
+        actions.len(),
+        total_savings
+    ));
+
+    if let Some(totals) = team_totals {
+        let rows: Vec<Vec<String>> = totals
+            .iter()
+            .map(|(team, (count, savings))| {
+                vec![team.clone(), count.to_string(), format!("{:.2}", savings)]
+            })
+            .collect();
+        html.table(
+            "Savings by cost center",
+            &["Cost center", "Resources", "Savings"],
+            &rows,
+        );
+    }
+
+    let rows: Vec<Vec<String>> = actions
+        .iter()
+        .map(|action| {
+            vec![
+                action.resource.account.clone().unwrap_or_default(),
+                action.resource.resource_type.clone(),
+                action.resource.id.clone(),
+                action.resource.name.clone(),
+                action.resource.state.clone(),
+                action.reason.clone(),
+                format!("{:.2}", action.estimated_savings),
+            ]
+        })
+        .collect();
+    html.table(
+        "Resources identified for cleanup",
+        &[
+            "Account", "Type", "ID", "Name", "State", "Reason", "Savings",
+        ],
+        &rows,
+    );
+
+    html
+}
+
+impl BatchAction for CleanupAction {
+    fn action(&self) -> &str {
+        match self.action_type.as_str() {
+            "DEACTIVATE" => "deactivate",
+            "STOP" => "stop",
+            _ => "delete",
+        }
+    }
+
+    fn resource_id(&self) -> &str {
+        &self.resource.id
+    }
 
+    fn describe(&self) -> String {
+        format!(
+            "{} {} ({})",
+            self.resource.resource_type, self.resource.name, self.resource.id
+        )
+    }
+}
+
+impl BatchAction for QuarantinedResource {
+    fn action(&self) -> &str {
+        "delete"
+    }
+
+    fn resource_id(&self) -> &str {
+        &self.resource_id
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} {} ({})",
+            self.resource_type, self.name, self.resource_id
+        )
+    }
+}
+
+async fn execute_aws_cleanup(config: &SdkConfig, action: &CleanupAction) -> Result<(), AppError> {
     match action.resource.resource_type.as_str() {
+        "EC2 Instance" if action.action_type == "STOP" => {
+            let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+            executor::throttle("ec2").await;
+            ec2_client
+                .stop_instances()
+                .instance_ids(&action.resource.id)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("ec2:StopInstances", e)
+                        .for_resource(&action.resource.id)
+                        .in_region(&action.resource.region)
+                })?;
+        }
+
         "EC2 Instance" => {
-            let ec2_client = aws_sdk_ec2::Client::new(&config);
+            let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+            executor::throttle("ec2").await;
             ec2_client
                 .terminate_instances()
                 .instance_ids(&action.resource.id)
                 .send()
                 .await
-                .map_err(|e| AppError::AwsError(e.to_string()))?;
+                .map_err(|e| {
+                    AppError::aws("ec2:TerminateInstances", e)
+                        .for_resource(&action.resource.id)
+                        .in_region(&action.resource.region)
+                })?;
         }
 
         "S3 Bucket" => {
-            let s3_client = aws_sdk_s3::Client::new(&config);
-            // First delete all objects, then delete bucket
-            // This is simplified - in reality you'd need to handle pagination
+            let regional = regions::regional_config(config, &action.resource.region);
+            let s3_client = clients::get::<aws_sdk_s3::Client>(&regional).await;
+            executor::throttle("s3").await;
+            // Only an empty bucket can be deleted; see `empty_bucket`
             s3_client
                 .delete_bucket()
                 .bucket(&action.resource.id)
                 .send()
                 .await
-                .map_err(|e| AppError::AwsError(e.to_string()))?;
+                .map_err(|e| {
+                    AppError::aws("s3:DeleteBucket", e).for_resource(&action.resource.id)
+                })?;
+        }
+
+        IAM_ACCESS_KEY => {
+            let iam_client = clients::get::<aws_sdk_iam::Client>(config).await;
+            let resource = &action.resource;
+            executor::throttle("iam").await;
+            if action.action_type == "DEACTIVATE" {
+                iam_client
+                    .update_access_key()
+                    .user_name(&resource.name)
+                    .access_key_id(&resource.id)
+                    .status(aws_sdk_iam::types::StatusType::Inactive)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        AppError::aws("iam:UpdateAccessKey", e).for_resource(&resource.id)
+                    })?;
+            } else {
+                iam_client
+                    .delete_access_key()
+                    .user_name(&resource.name)
+                    .access_key_id(&resource.id)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        AppError::aws("iam:DeleteAccessKey", e).for_resource(&resource.id)
+                    })?;
+            }
+        }
+
+        IAM_ROLE => delete_role(config, &action.resource.id).await?,
+
+        ELASTIC_IP => {
+            let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+            executor::throttle("ec2").await;
+            ec2_client
+                .release_address()
+                .allocation_id(&action.resource.id)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("ec2:ReleaseAddress", e)
+                        .for_resource(&action.resource.id)
+                        .in_region(&action.resource.region)
+                })?;
+        }
+
+        // Target groups are free and stay behind for reuse
+        LOAD_BALANCER => {
+            let elb_client = clients::get::<aws_sdk_elasticloadbalancingv2::Client>(config).await;
+            executor::throttle("elasticloadbalancing").await;
+            elb_client
+                .delete_load_balancer()
+                .load_balancer_arn(&action.resource.id)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("elbv2:DeleteLoadBalancer", e)
+                        .for_resource(&action.resource.id)
+                        .in_region(&action.resource.region)
+                })?;
+        }
+
+        EBS_VOLUME => {
+            let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+            executor::throttle("ec2").await;
+            ec2_client
+                .delete_volume()
+                .volume_id(&action.resource.id)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("ec2:DeleteVolume", e)
+                        .for_resource(&action.resource.id)
+                        .in_region(&action.resource.region)
+                })?;
+        }
+
+        EBS_SNAPSHOT => {
+            let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+            executor::throttle("ec2").await;
+            ec2_client
+                .delete_snapshot()
+                .snapshot_id(&action.resource.id)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("ec2:DeleteSnapshot", e)
+                        .for_resource(&action.resource.id)
+                        .in_region(&action.resource.region)
+                })?;
         }
 
         _ => {
-            println!(
+            tracing::warn!(
                 "   Resource type {} not yet implemented for cleanup",
                 action.resource.resource_type
             );
@@ -427,14 +3195,154 @@ async fn execute_aws_cleanup(action: &CleanupAction) -> Result<(), AppError> {
     Ok(())
 }
 
-async fn execute_gcp_cleanup(action: &CleanupAction) -> Result<(), AppError> {
-    // In a real implementation, you'd use the GCP Compute Engine API
-    println!("   Simulating GCP cleanup: {}", action.resource.name);
+/// Delete an IAM role after removing what IAM requires gone first: attached
+/// and inline policies and instance profile memberships.
+async fn delete_role(config: &SdkConfig, role_name: &str) -> Result<(), AppError> {
+    let iam_client = clients::get::<aws_sdk_iam::Client>(config).await;
+
+    executor::throttle("iam").await;
+    let attached = iam_client
+        .list_attached_role_policies()
+        .role_name(role_name)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("iam:ListAttachedRolePolicies", e).for_resource(role_name))?;
+    for policy in attached.attached_policies() {
+        executor::throttle("iam").await;
+        iam_client
+            .detach_role_policy()
+            .role_name(role_name)
+            .policy_arn(policy.policy_arn().unwrap_or_default())
+            .send()
+            .await
+            .map_err(|e| AppError::aws("iam:DetachRolePolicy", e).for_resource(role_name))?;
+    }
+
+    executor::throttle("iam").await;
+    let inline = iam_client
+        .list_role_policies()
+        .role_name(role_name)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("iam:ListRolePolicies", e).for_resource(role_name))?;
+    for policy_name in inline.policy_names() {
+        executor::throttle("iam").await;
+        iam_client
+            .delete_role_policy()
+            .role_name(role_name)
+            .policy_name(policy_name)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("iam:DeleteRolePolicy", e).for_resource(role_name))?;
+    }
+
+    executor::throttle("iam").await;
+    let profiles = iam_client
+        .list_instance_profiles_for_role()
+        .role_name(role_name)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("iam:ListInstanceProfilesForRole", e).for_resource(role_name))?;
+    for profile in profiles.instance_profiles() {
+        executor::throttle("iam").await;
+        iam_client
+            .remove_role_from_instance_profile()
+            .role_name(role_name)
+            .instance_profile_name(profile.instance_profile_name())
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::aws("iam:RemoveRoleFromInstanceProfile", e).for_resource(role_name)
+            })?;
+    }
+
+    executor::throttle("iam").await;
+    iam_client
+        .delete_role()
+        .role_name(role_name)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("iam:DeleteRole", e).for_resource(role_name))?;
+
     Ok(())
 }
 
+async fn execute_gcp_cleanup(action: &CleanupAction) -> Result<(), AppError> {
+    let resource = &action.resource;
+    match resource.resource_type.as_str() {
+        GCP_STATIC_IP => {
+            let project_id = gcp::project_id()?;
+            let scope = match resource.region.as_str() {
+                "global" => "global".to_string(),
+                region => format!("regions/{}", region),
+            };
+            let url = format!(
+                "{}/projects/{}/{}/addresses/{}",
+                gcp::COMPUTE_API,
+                project_id,
+                scope,
+                resource.id
+            );
+            gcp::delete("compute.addresses.delete", &url)
+                .await
+                .map_err(|e| e.for_resource(&resource.id).in_region(&resource.region))
+        }
+
+        GCP_SNAPSHOT => {
+            let project_id = gcp::project_id()?;
+            let url = format!(
+                "{}/projects/{}/global/snapshots/{}",
+                gcp::COMPUTE_API,
+                project_id,
+                resource.id
+            );
+            gcp::delete("compute.snapshots.delete", &url)
+                .await
+                .map_err(|e| e.for_resource(&resource.id))
+        }
+
+        databases::CLOUD_SQL_INSTANCE => {
+            databases::retire(&resource.resource_type, &resource.id).await
+        }
+
+        _ => {
+            // In a real implementation, you'd use the GCP Compute Engine API
+            tracing::info!("   Simulating GCP cleanup: {}", resource.name);
+            Ok(())
+        }
+    }
+}
+
 async fn execute_azure_cleanup(action: &CleanupAction) -> Result<(), AppError> {
-    // In a real implementation, you'd use the Azure Compute Management API
-    println!("   Simulating Azure cleanup: {}", action.resource.name);
-    Ok(())
+    let resource = &action.resource;
+    if databases::is_database(&resource.resource_type) {
+        return databases::retire(&resource.resource_type, &resource.id)
+            .await
+            .map_err(|e| e.in_region(&resource.region));
+    }
+
+    let (operation, api_version) = match resource.resource_type.as_str() {
+        AZURE_VM => (
+            "Microsoft.Compute/virtualMachines/delete",
+            azure::COMPUTE_API_VERSION,
+        ),
+        AZURE_NIC => (
+            "Microsoft.Network/networkInterfaces/delete",
+            azure::NETWORK_API_VERSION,
+        ),
+        AZURE_PUBLIC_IP => (
+            "Microsoft.Network/publicIPAddresses/delete",
+            azure::NETWORK_API_VERSION,
+        ),
+        other => {
+            return Err(AppError::InvalidParameters(format!(
+                "Cleanup cannot delete Azure {} resources",
+                other
+            )))
+        }
+    };
+
+    azure::delete(operation, &resource.id, api_version)
+        .await
+        .map_err(|e| e.for_resource(&resource.name).in_region(&resource.region))
 }
@@ -1,10 +1,22 @@
-use crate::cli::{CloudProvider, Commands};
+use crate::cli::{CloudProvider, Commands, ConfigAction};
+use crate::commands::authenticate::{keyring_profiles, migrate_credentials};
 use crate::error::AppError;
+use crate::secrets;
+use crate::settings;
+use chrono::{DateTime, Utc};
 use colored::*;
+use secrecy::SecretString;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::io::{Read, Write};
+use std::path::{Component, Path};
+
+/// Bumped whenever the backup layout changes incompatibly
+const BACKUP_VERSION: u32 = 1;
+
+/// Files holding secrets or signing keys, restored readable by the owner only
+const PRIVATE_FILES: &[&str] = &["credentials.json", "token_cache.json", "plan.key"];
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CloudConfig {
@@ -17,8 +29,21 @@ pub struct CloudConfig {
     pub subscription_id: Option<String>,
 }
 
+/// Plaintext contents of an encrypted config backup.
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigBackup {
+    version: u32,
+    created_at: DateTime<Utc>,
+    /// File contents keyed by path relative to the actlog config directory
+    files: BTreeMap<String, String>,
+    /// OS keyring entries of the profiles in credentials.json, by profile key
+    #[serde(default)]
+    keyring: BTreeMap<String, String>,
+}
+
 pub async fn configure(cmd: &Commands) -> Result<(), AppError> {
     if let Commands::Config {
+        action,
         provider,
         profile,
         api_key,
@@ -28,7 +53,27 @@ pub async fn configure(cmd: &Commands) -> Result<(), AppError> {
         subscription_id,
     } = cmd
     {
-        println!(
+        match action {
+            Some(ConfigAction::Backup {
+                out,
+                passphrase_stdin,
+            }) => return backup_config(out, *passphrase_stdin),
+            Some(ConfigAction::Restore {
+                file,
+                passphrase_stdin,
+                force,
+            }) => return restore_config(file, *passphrase_stdin, *force),
+            Some(ConfigAction::MigrateCredentials) => return migrate(),
+            None => {}
+        }
+
+        let provider = provider.as_ref().ok_or_else(|| {
+            AppError::InvalidParameters(
+                "A provider is required, e.g. `actlog config aws`".to_string(),
+            )
+        })?;
+
+        tracing::info!(
             "⚙️  Configuring {} settings for profile '{}'...",
             provider.to_string().green(),
             profile.green()
@@ -75,8 +120,41 @@ pub async fn configure(cmd: &Commands) -> Result<(), AppError> {
     Ok(())
 }
 
+fn migrate() -> Result<(), AppError> {
+    let migrated = migrate_credentials()?;
+    if migrated == 0 {
+        println!("✅ No plaintext credentials left to migrate.");
+    } else {
+        println!(
+            "🔐 Moved credentials for {} profiles into the OS keyring.",
+            migrated
+        );
+    }
+    Ok(())
+}
+
+/// What `actlog config` saved for `profile` of `provider`, if anything.
+pub(crate) fn stored_config(
+    provider: &CloudProvider,
+    profile: &str,
+) -> Result<Option<CloudConfig>, AppError> {
+    let config_file = settings::settings_path()?.with_file_name("config.json");
+    let key = format!("{}_{}", provider.to_string().to_lowercase(), profile);
+    Ok(load_configs(&config_file)?.remove(&key))
+}
+
+/// Profiles of `provider` that `actlog config` saved settings for.
+pub(crate) fn configured_profiles(provider: &CloudProvider) -> Result<Vec<String>, AppError> {
+    let config_file = settings::settings_path()?.with_file_name("config.json");
+    let prefix = format!("{}_", provider.to_string().to_lowercase());
+    Ok(load_configs(&config_file)?
+        .into_keys()
+        .filter_map(|key| key.strip_prefix(&prefix).map(str::to_string))
+        .collect())
+}
+
 fn prompt_for_config(provider: &CloudProvider, profile: &str) -> Result<CloudConfig, AppError> {
-    use std::io::{self, Write};
+    use std::io;
 
     println!(
         "\n📝 Please provide configuration details for {} (profile: {}):",
@@ -203,6 +281,232 @@ fn get_config_dir() -> Result<std::path::PathBuf, AppError> {
     Ok(config_dir)
 }
 
+fn backup_config(out: &str, passphrase_stdin: bool) -> Result<(), AppError> {
+    let config_dir = get_config_dir()?;
+    let mut files = BTreeMap::new();
+    collect_files(&config_dir, &config_dir, &mut files)?;
+
+    if files.is_empty() {
+        println!("ℹ️  Nothing to back up in {}", config_dir.display());
+        return Ok(());
+    }
+
+    // credentials.json only says which secrets are in the keyring, so the
+    // secrets themselves have to travel with it
+    let mut keyring = BTreeMap::new();
+    for key in keyring_profiles()? {
+        match secrets::load(&key)? {
+            Some(secret) => {
+                keyring.insert(key, secret);
+            }
+            None => println!(
+                "   ⚠️  Credentials for {} are missing from the OS keyring",
+                key.yellow()
+            ),
+        }
+    }
+
+    let backup = ConfigBackup {
+        version: BACKUP_VERSION,
+        created_at: Utc::now(),
+        files,
+        keyring,
+    };
+    let plaintext = serde_json::to_vec(&backup)?;
+
+    let passphrase = read_passphrase(passphrase_stdin, true)?;
+    let encryptor = age::Encryptor::with_user_passphrase(SecretString::from(passphrase));
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .map_err(|e| AppError::EncryptionError(e.to_string()))?;
+    writer.write_all(&plaintext)?;
+    writer.finish()?;
+
+    fs::write(out, encrypted)?;
+
+    println!(
+        "✅ Backed up {} files to {}",
+        backup.files.len(),
+        out.green()
+    );
+    for path in backup.files.keys() {
+        println!("   {}", path);
+    }
+    if !backup.keyring.is_empty() {
+        println!(
+            "   plus OS keyring credentials for {}",
+            backup
+                .keyring
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn restore_config(file: &str, passphrase_stdin: bool, force: bool) -> Result<(), AppError> {
+    let encrypted = fs::read(file)?;
+    let passphrase = read_passphrase(passphrase_stdin, false)?;
+
+    let decryptor = age::Decryptor::new(&encrypted[..])
+        .map_err(|e| AppError::EncryptionError(e.to_string()))?;
+    if !decryptor.is_scrypt() {
+        return Err(AppError::EncryptionError(format!(
+            "{} is not a passphrase-encrypted backup",
+            file
+        )));
+    }
+
+    let identity = age::scrypt::Identity::new(SecretString::from(passphrase));
+    let mut plaintext = Vec::new();
+    decryptor
+        .decrypt(std::iter::once(&identity as &dyn age::Identity))
+        .map_err(|e| AppError::EncryptionError(e.to_string()))?
+        .read_to_end(&mut plaintext)?;
+
+    let backup: ConfigBackup = serde_json::from_slice(&plaintext)?;
+    if backup.version > BACKUP_VERSION {
+        return Err(AppError::ConfigurationError(format!(
+            "Backup version {} is newer than this actlog supports",
+            backup.version
+        )));
+    }
+
+    // Never write outside the config directory; check every path before
+    // writing any, so a bad entry can't leave a half-restored config
+    if let Some(path) = backup.files.keys().find(|path| {
+        Path::new(path)
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+    }) {
+        return Err(AppError::ConfigurationError(format!(
+            "Backup contains an invalid path: {}",
+            path
+        )));
+    }
+
+    let config_dir = get_config_dir()?;
+    let existing: Vec<&String> = backup
+        .files
+        .keys()
+        .filter(|path| config_dir.join(path).exists())
+        .collect();
+
+    // The passphrase already consumed stdin, so there's no one to ask
+    if !existing.is_empty() && !force && passphrase_stdin {
+        return Err(AppError::InvalidParameters(format!(
+            "Restoring would overwrite {}; pass --force with --passphrase-stdin",
+            existing
+                .iter()
+                .map(|path| path.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+
+    if !existing.is_empty() && !force {
+        println!("⚠️  These files will be overwritten:");
+        for path in &existing {
+            println!("   {}", path);
+        }
+        print!("\nContinue? (y/N): ");
+        std::io::stdout().flush()?;
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+
+        if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
+            println!("❌ Restore cancelled by user.");
+            return Err(AppError::UserCancelled);
+        }
+    }
+
+    for (path, content) in &backup.files {
+        let target = config_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, content)?;
+        #[cfg(unix)]
+        if PRIVATE_FILES.contains(&path.as_str()) {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&target, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+
+    for (key, secret) in &backup.keyring {
+        secrets::store(key, secret)?;
+    }
+
+    println!(
+        "✅ Restored {} files and {} keyring entries from backup taken {}",
+        backup.files.len(),
+        backup.keyring.len(),
+        backup.created_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    Ok(())
+}
+
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut BTreeMap<String, String>,
+) -> Result<(), AppError> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+            continue;
+        }
+
+        let relative = path
+            .strip_prefix(root)
+            .map_err(|e| AppError::Unknown(e.to_string()))?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                files.insert(relative, content);
+            }
+            Err(_) => println!("   ⚠️  Skipping non-text file {}", relative.yellow()),
+        }
+    }
+
+    Ok(())
+}
+
+fn read_passphrase(from_stdin: bool, confirm: bool) -> Result<String, AppError> {
+    let passphrase = if from_stdin {
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        input.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        let passphrase = rpassword::prompt_password("Backup passphrase: ")?;
+        if confirm && rpassword::prompt_password("Confirm passphrase: ")? != passphrase {
+            return Err(AppError::InvalidParameters(
+                "Passphrases do not match".to_string(),
+            ));
+        }
+        passphrase
+    };
+
+    if passphrase.is_empty() {
+        return Err(AppError::InvalidParameters(
+            "Passphrase must not be empty".to_string(),
+        ));
+    }
+
+    Ok(passphrase)
+}
+
 fn load_configs(config_file: &Path) -> Result<HashMap<String, CloudConfig>, AppError> {
     if config_file.exists() {
         let content = fs::read_to_string(config_file)?;
@@ -1,8 +1,27 @@
-use crate::cli::{CloudProvider, Commands, OutputFormat};
+use crate::accounts::{self, AccountTarget};
+use crate::budget::{self, BudgetBurn, CostForecast};
+use crate::cache;
+use crate::cli::{CloudProvider, Commands, CostGranularity, OutputFormat};
+use crate::clients;
+use crate::commitments::{self, CommitmentReport};
 use crate::error::AppError;
+use crate::executor;
+use crate::inventory;
+use crate::kubernetes::{self, ClusterCosts};
+use crate::locale::{self, Locale};
+use crate::output::{out, outln};
+use crate::owners;
+use crate::providers;
+use crate::report::{self, HtmlReport};
+use crate::template;
+use aws_config::SdkConfig;
+use aws_sdk_costexplorer::types::{DateInterval, GroupDefinition, GroupDefinitionType};
 use chrono::{DateTime, Duration, Utc};
 use colored::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+const UNTAGGED: &str = "(untagged)";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CostReport {
@@ -11,8 +30,23 @@ pub struct CostReport {
     pub end_date: String,
     pub total_cost: f64,
     pub currency: String,
+    /// What `service_name` holds when not the service, e.g. "region" or "tag:team"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_by: Option<String>,
     pub services: Vec<ServiceCost>,
     pub alerts: Vec<CostAlert>,
+    /// Month-to-date burn against the budget threshold, when one is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetBurn>,
+    /// Reserved Instance and Savings Plans coverage, with `--commitments`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitments: Option<CommitmentReport>,
+    /// Spend forecast, with `--forecast`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forecast: Option<CostForecast>,
+    /// Kubernetes node costs by namespace or label, with `--kubernetes`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kubernetes: Option<ClusterCosts>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,6 +55,11 @@ pub struct ServiceCost {
     pub cost: f64,
     pub usage: String,
     pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Start of the period the cost was incurred in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,6 +70,59 @@ pub struct CostAlert {
     pub actual_cost: f64,
 }
 
+/// What each line of an AWS cost report is, from `--group-by`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CostGrouping {
+    Service,
+    Region,
+    Account,
+    UsageType,
+    Tag(String),
+}
+
+impl CostGrouping {
+    pub(crate) fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "service" => Ok(CostGrouping::Service),
+            "region" => Ok(CostGrouping::Region),
+            "account" => Ok(CostGrouping::Account),
+            "usage-type" => Ok(CostGrouping::UsageType),
+            _ => match value.strip_prefix("tag:") {
+                Some(key) if !key.is_empty() => Ok(CostGrouping::Tag(key.to_string())),
+                _ => Err(AppError::InvalidParameters(format!(
+                    "Invalid --group-by '{}': use service, region, account, tag:<key> or usage-type",
+                    value
+                ))),
+            },
+        }
+    }
+
+    fn group_definition(&self) -> GroupDefinition {
+        let (group_type, key) = match self {
+            CostGrouping::Service => (GroupDefinitionType::Dimension, "SERVICE"),
+            CostGrouping::Region => (GroupDefinitionType::Dimension, "REGION"),
+            CostGrouping::Account => (GroupDefinitionType::Dimension, "LINKED_ACCOUNT"),
+            CostGrouping::UsageType => (GroupDefinitionType::Dimension, "USAGE_TYPE"),
+            CostGrouping::Tag(key) => (GroupDefinitionType::Tag, key.as_str()),
+        };
+        GroupDefinition::builder()
+            .r#type(group_type)
+            .key(key)
+            .build()
+    }
+
+    /// Name of the grouping for the report, `None` for the usual by-service one.
+    fn label(&self) -> Option<String> {
+        match self {
+            CostGrouping::Service => None,
+            CostGrouping::Region => Some("region".to_string()),
+            CostGrouping::Account => Some("account".to_string()),
+            CostGrouping::UsageType => Some("usage-type".to_string()),
+            CostGrouping::Tag(key) => Some(format!("tag:{}", key)),
+        }
+    }
+}
+
 #[allow(unused_variables, dead_code)]
 pub async fn report_costs(cmd: &Commands) -> Result<(), AppError> {
     if let Commands::ReportCosts {
@@ -40,27 +132,201 @@ pub async fn report_costs(cmd: &Commands) -> Result<(), AppError> {
         format,
         budget_threshold,
         profile,
+        org,
+        org_role,
+        accounts_file,
+        pdf,
+        template,
+        locale,
+        commitments,
+        forecast,
+        granularity,
+        group_by,
+        kubernetes,
+        kube_context,
+        kube_label,
+        force,
     } = cmd
     {
-        println!(
+        let locale = locale
+            .as_deref()
+            .map(Locale::parse)
+            .transpose()?
+            .unwrap_or_default();
+
+        tracing::info!(
             "💰 Generating cost report for {}...",
             provider.to_string().green()
         );
 
+        if (*org || accounts_file.is_some()) && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--org and --accounts are only supported for AWS".to_string(),
+            ));
+        }
+        if *commitments && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--commitments is only supported for AWS".to_string(),
+            ));
+        }
+        let grouping = CostGrouping::parse(group_by)?;
+        if (*granularity != CostGranularity::Monthly || grouping != CostGrouping::Service)
+            && !matches!(provider, CloudProvider::Aws)
+        {
+            return Err(AppError::InvalidParameters(
+                "--granularity and --group-by are only supported for AWS".to_string(),
+            ));
+        }
+        if *kubernetes && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--kubernetes is only supported for AWS".to_string(),
+            ));
+        }
+        if *forecast == Some(0) {
+            return Err(AppError::InvalidParameters(
+                "--forecast must be at least 1 day".to_string(),
+            ));
+        }
+
         // Determine date range
         let (start, end) = determine_date_range(start_date, end_date)?;
 
+        let scope = format!(
+            "{}:{}:{}:{}:{}:{}:{:?}:{}",
+            provider,
+            cache::provider_scope(provider).await,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d"),
+            budget_threshold.map(|b| b.to_string()).unwrap_or_default(),
+            if *org {
+                "org".to_string()
+            } else {
+                accounts_file.clone().unwrap_or_default()
+            },
+            granularity,
+            group_by
+        );
+
         // Generate cost report based on provider
-        let report = match provider {
-            CloudProvider::Aws => generate_aws_cost_report(&start, &end, budget_threshold).await?,
-            CloudProvider::Gcp => generate_gcp_cost_report(&start, &end, budget_threshold).await?,
-            CloudProvider::Azure => {
-                generate_azure_cost_report(&start, &end, budget_threshold).await?
+        let mut report = match inventory::cached::<CostReport>("costs", &scope) {
+            Some(report) => report,
+            None => {
+                let report = match provider {
+                    CloudProvider::Aws => {
+                        #[allow(deprecated)]
+                        let config = clients::aws_config().await;
+                        match accounts::resolve_targets(&config, *org, org_role, accounts_file)
+                            .await?
+                        {
+                            Some(targets) => {
+                                generate_aws_multi_account_cost_report(
+                                    &config,
+                                    &targets,
+                                    &start,
+                                    &end,
+                                    budget_threshold,
+                                    granularity,
+                                    &grouping,
+                                )
+                                .await?
+                            }
+                            None => {
+                                generate_aws_cost_report(
+                                    &config,
+                                    &start,
+                                    &end,
+                                    budget_threshold,
+                                    granularity,
+                                    &grouping,
+                                )
+                                .await?
+                            }
+                        }
+                    }
+                    CloudProvider::Gcp | CloudProvider::Azure => {
+                        providers::get(provider)
+                            .cost_report(&start, &end, budget_threshold)
+                            .await?
+                    }
+                };
+                inventory::store_cost_report(&scope, &report);
+                report
             }
         };
 
+        // Commitments are bought for the whole organization, so the
+        // payer's view is the one that matters even with --org
+        if *commitments {
+            let config = clients::aws_config().await;
+            report.commitments = Some(commitments::aws_commitments(&config, &start, &end).await?);
+        }
+
+        // Pods come and go, so the cluster is read as it is now
+        if *kubernetes {
+            let config = clients::aws_config().await;
+            let period_hours = (end - start).num_minutes() as f64 / 60.0;
+            report.kubernetes = Some(
+                kubernetes::aws_cluster_costs(
+                    &config,
+                    kube_context.as_deref(),
+                    kube_label.as_deref(),
+                    period_hours,
+                )
+                .await?,
+            );
+        }
+
+        // Forecasts aren't cached: they move with every day of spend
+        if let Some(days) = forecast {
+            let today = Utc::now().date_naive();
+            let period_days = (end - start).num_days();
+            let cost_forecast = match provider {
+                CloudProvider::Aws => {
+                    let config = clients::aws_config().await;
+                    budget::aws_forecast(&config, *days, report.total_cost, period_days, today)
+                        .await?
+                }
+                CloudProvider::Gcp | CloudProvider::Azure => {
+                    budget::linear_forecast(report.total_cost, period_days, *days, today)
+                }
+            };
+            if let Some(threshold) = budget_threshold {
+                report.alerts.extend(cost_forecast.alert(*threshold));
+            }
+            report.forecast = Some(cost_forecast);
+        }
+
         // Output report in requested format
-        output_cost_report(&report, format)?;
+        match template {
+            Some(path) => outln!("{}", template::render(path, &report)?),
+            None => output_cost_report(&report, format, &locale)?,
+        }
+
+        let mut team_totals = None;
+        if let Some(owners) = owners::load_owners()? {
+            let project = match provider {
+                CloudProvider::Gcp => owners::current_project(),
+                _ => None,
+            };
+            let mut totals: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+            for service in &report.services {
+                let team = owners.team_name(
+                    &HashMap::new(),
+                    service.account.as_deref(),
+                    project.as_deref(),
+                );
+                let entry = totals.entry(team).or_insert((0, 0.0));
+                entry.0 += 1;
+                entry.1 += service.cost;
+            }
+            owners::print_team_totals("Cost by team", &totals);
+            team_totals = Some(totals);
+        }
+
+        if let Some(path) = pdf {
+            cost_report_html(&report, team_totals.as_ref(), &locale).write_pdf(path)?;
+            println!("📄 PDF statement written to {}", path.green());
+        }
 
         // Show budget alerts if any
         if !report.alerts.is_empty() {
@@ -73,11 +339,11 @@ pub async fn report_costs(cmd: &Commands) -> Result<(), AppError> {
                     _ => "white",
                 };
                 println!(
-                    "   {}: {} (Threshold: ${:.2}, Actual: ${:.2})",
+                    "   {}: {} (Threshold: {}, Actual: {})",
                     alert.severity.to_uppercase().color(severity_color),
                     alert.message,
-                    alert.threshold,
-                    alert.actual_cost
+                    locale.money(alert.threshold, &report.currency),
+                    locale.money(alert.actual_cost, &report.currency)
                 );
             }
         }
@@ -87,7 +353,7 @@ pub async fn report_costs(cmd: &Commands) -> Result<(), AppError> {
 }
 
 #[allow(unused_variables, dead_code)]
-fn determine_date_range(
+pub(crate) fn determine_date_range(
     start_date: &Option<String>,
     end_date: &Option<String>,
 ) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
@@ -110,79 +376,177 @@ fn determine_date_range(
     Ok((start, end))
 }
 
+async fn generate_aws_multi_account_cost_report(
+    config: &SdkConfig,
+    targets: &[AccountTarget],
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+    budget_threshold: &Option<f64>,
+    granularity: &CostGranularity,
+    grouping: &CostGrouping,
+) -> Result<CostReport, AppError> {
+    let (start_owned, end_owned) = (*start, *end);
+    let results = accounts::fan_out(config, targets, |account_config| {
+        let (granularity, grouping) = (granularity.clone(), grouping.clone());
+        async move {
+            generate_aws_cost_report(
+                &account_config,
+                &start_owned,
+                &end_owned,
+                &None,
+                &granularity,
+                &grouping,
+            )
+            .await
+        }
+    })
+    .await;
+
+    // Merge per-account service costs into one report with an account column
+    let mut services = Vec::new();
+    for (target, report) in results {
+        for mut service in report.services {
+            service.account = Some(target.account_id.clone());
+            services.push(service);
+        }
+    }
+
+    let total_cost = services.iter().map(|s| s.cost).sum();
+
+    let mut alerts = Vec::new();
+    let mut budget_burn = None;
+    if let Some(threshold) = budget_threshold {
+        if total_cost > *threshold {
+            alerts.push(CostAlert {
+                message: "Combined account cost exceeds budget threshold".to_string(),
+                severity: "high".to_string(),
+                threshold: *threshold,
+                actual_cost: total_cost,
+            });
+        }
+
+        let today = Utc::now().date_naive();
+        let spend = accounts::fan_out(config, targets, move |account_config| async move {
+            budget::aws_spend(&account_config, today).await
+        })
+        .await;
+        let month_to_date = spend.iter().map(|(_, (mtd, _))| mtd).sum();
+        // A forecast for only some accounts would understate the month
+        let forecast = spend
+            .iter()
+            .map(|(_, (_, forecast))| *forecast)
+            .sum::<Option<f64>>();
+        let burn = budget::project(*threshold, month_to_date, forecast, today);
+        alerts.extend(burn.alert());
+        budget_burn = Some(burn);
+    }
+
+    Ok(CostReport {
+        provider: "AWS".to_string(),
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.format("%Y-%m-%d").to_string(),
+        total_cost,
+        currency: "USD".to_string(),
+        group_by: grouping.label(),
+        services,
+        alerts,
+        budget: budget_burn,
+        commitments: None,
+        forecast: None,
+        kubernetes: None,
+    })
+}
+
 #[allow(unused_variables, dead_code, deprecated)]
-async fn generate_aws_cost_report(
+pub(crate) async fn generate_aws_cost_report(
+    config: &SdkConfig,
     start: &DateTime<Utc>,
     end: &DateTime<Utc>,
     budget_threshold: &Option<f64>,
+    granularity: &CostGranularity,
+    grouping: &CostGrouping,
 ) -> Result<CostReport, AppError> {
-    println!("📊 Fetching AWS cost data...");
+    tracing::info!("📊 Fetching AWS cost data...");
 
     // Initialize AWS Cost Explorer client
-    let config = aws_config::from_env().load().await;
-    let cost_client = aws_sdk_costexplorer::Client::new(&config);
+    let cost_client = clients::get::<aws_sdk_costexplorer::Client>(config).await;
 
-    // Create cost and usage request
-    let date_interval = aws_sdk_costexplorer::types::DateInterval::builder()
-        .start(start.format("%Y-%m-%d").to_string())
-        .end(end.format("%Y-%m-%d").to_string())
+    // Hourly periods are given with a time of day, the others as dates
+    let date_format = match granularity {
+        CostGranularity::Hourly => "%Y-%m-%dT%H:%M:%SZ",
+        CostGranularity::Daily | CostGranularity::Monthly => "%Y-%m-%d",
+    };
+    let date_interval = DateInterval::builder()
+        .start(start.format(date_format).to_string())
+        .end(end.format(date_format).to_string())
         .build()
         .map_err(|e| AppError::AwsError(e.to_string()))?;
-
-    let group_def = aws_sdk_costexplorer::types::GroupDefinition::builder()
-        .set_type(Some(
-            aws_sdk_costexplorer::types::GroupDefinitionType::Dimension,
-        ))
-        .key("SERVICE")
-        .build();
-
-    let request = cost_client
-        .get_cost_and_usage()
-        .time_period(date_interval)
-        .granularity(aws_sdk_costexplorer::types::Granularity::Monthly)
-        .metrics("UnblendedCost")
-        .group_by(group_def);
-
-    let response = request
-        .send()
-        .await
-        .map_err(|e| AppError::AwsError(e.to_string()))?;
+    let ce_granularity = match granularity {
+        CostGranularity::Hourly => aws_sdk_costexplorer::types::Granularity::Hourly,
+        CostGranularity::Daily => aws_sdk_costexplorer::types::Granularity::Daily,
+        CostGranularity::Monthly => aws_sdk_costexplorer::types::Granularity::Monthly,
+    };
 
     // Parse response and build report
     let mut total_cost = 0.0;
     let mut services = Vec::new();
     let mut alerts = Vec::new();
 
-    if let Some(results) = response.results_by_time {
-        for result in results {
-            if let Some(groups) = result.groups {
-                for group in groups {
-                    if let Some(metrics) = group.metrics {
-                        if let Some(cost_metric) = metrics.get("UnblendedCost") {
-                            if let Some(amount) = &cost_metric.amount {
-                                if let Ok(cost) = amount.parse::<f64>() {
-                                    total_cost += cost;
-
-                                    services.push(ServiceCost {
-                                        service_name: group.keys.unwrap_or_default().join(", "),
-                                        cost,
-                                        usage: cost_metric
-                                            .unit
-                                            .as_deref()
-                                            .unwrap_or("USD")
-                                            .to_string(),
-                                        region: None,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
+    let mut next_page_token = None;
+    loop {
+        executor::throttle("costexplorer").await;
+        let response = cost_client
+            .get_cost_and_usage()
+            .time_period(date_interval.clone())
+            .granularity(ce_granularity.clone())
+            .metrics("UnblendedCost")
+            .group_by(grouping.group_definition())
+            .set_next_page_token(next_page_token)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetCostAndUsage", e))?;
+
+        for result in response.results_by_time() {
+            let period = result.time_period().map(|p| p.start().to_string());
+            for group in result.groups() {
+                let Some(cost_metric) = group.metrics().and_then(|m| m.get("UnblendedCost")) else {
+                    continue;
+                };
+                let Some(cost) = cost_metric.amount().and_then(|a| a.parse::<f64>().ok()) else {
+                    continue;
+                };
+                total_cost += cost;
+
+                let key = group.keys().join(", ");
+                let name = match grouping {
+                    // Tag keys come back as "team$web", or "team$" when untagged
+                    CostGrouping::Tag(_) => key
+                        .split_once('$')
+                        .map(|(_, value)| value)
+                        .filter(|value| !value.is_empty())
+                        .unwrap_or(UNTAGGED)
+                        .to_string(),
+                    _ => key.clone(),
+                };
+                services.push(ServiceCost {
+                    service_name: name,
+                    cost,
+                    usage: cost_metric.unit().unwrap_or("USD").to_string(),
+                    region: (grouping == &CostGrouping::Region).then(|| key.clone()),
+                    account: (grouping == &CostGrouping::Account).then_some(key),
+                    period: period.clone(),
+                });
             }
         }
+
+        next_page_token = response.next_page_token().map(str::to_string);
+        if next_page_token.is_none() {
+            break;
+        }
     }
 
     // Check budget threshold
+    let mut budget_burn = None;
     if let Some(threshold) = budget_threshold {
         if total_cost > *threshold {
             alerts.push(CostAlert {
@@ -192,6 +556,12 @@ async fn generate_aws_cost_report(
                 actual_cost: total_cost,
             });
         }
+
+        let today = Utc::now().date_naive();
+        let (month_to_date, forecast) = budget::aws_spend(config, today).await?;
+        let burn = budget::project(*threshold, month_to_date, forecast, today);
+        alerts.extend(burn.alert());
+        budget_burn = Some(burn);
     }
 
     Ok(CostReport {
@@ -200,18 +570,23 @@ async fn generate_aws_cost_report(
         end_date: end.format("%Y-%m-%d").to_string(),
         total_cost,
         currency: "USD".to_string(),
+        group_by: grouping.label(),
         services,
         alerts,
+        budget: budget_burn,
+        commitments: None,
+        forecast: None,
+        kubernetes: None,
     })
 }
 
 #[allow(unused_variables, dead_code)]
-async fn generate_gcp_cost_report(
+pub(crate) async fn generate_gcp_cost_report(
     start: &DateTime<Utc>,
     end: &DateTime<Utc>,
     budget_threshold: &Option<f64>,
 ) -> Result<CostReport, AppError> {
-    println!("📊 Fetching GCP cost data...");
+    tracing::info!("📊 Fetching GCP cost data...");
 
     // For GCP, we would use the Cloud Billing API
     // This is a simplified implementation
@@ -225,18 +600,23 @@ async fn generate_gcp_cost_report(
             cost: 150.25,
             usage: "USD".to_string(),
             region: Some("us-central1".to_string()),
+            account: None,
+            period: None,
         },
         ServiceCost {
             service_name: "Cloud Storage".to_string(),
             cost: 25.50,
             usage: "USD".to_string(),
             region: None,
+            account: None,
+            period: None,
         },
     ];
 
     let total_cost = services.iter().map(|s| s.cost).sum();
 
     let mut alerts = Vec::new();
+    let mut budget_burn = None;
     if let Some(threshold) = budget_threshold {
         if total_cost > *threshold {
             alerts.push(CostAlert {
@@ -246,6 +626,13 @@ async fn generate_gcp_cost_report(
                 actual_cost: total_cost,
             });
         }
+
+        let today = Utc::now().date_naive();
+        let month_to_date =
+            budget::month_to_date_from_average(total_cost, (*end - *start).num_days(), today);
+        let burn = budget::project(*threshold, month_to_date, None, today);
+        alerts.extend(burn.alert());
+        budget_burn = Some(burn);
     }
 
     Ok(CostReport {
@@ -254,18 +641,23 @@ async fn generate_gcp_cost_report(
         end_date: end.format("%Y-%m-%d").to_string(),
         total_cost,
         currency: "USD".to_string(),
+        group_by: None,
         services,
         alerts,
+        budget: budget_burn,
+        commitments: None,
+        forecast: None,
+        kubernetes: None,
     })
 }
 
 #[allow(unused_variables, dead_code)]
-async fn generate_azure_cost_report(
+pub(crate) async fn generate_azure_cost_report(
     start: &DateTime<Utc>,
     end: &DateTime<Utc>,
     budget_threshold: &Option<f64>,
 ) -> Result<CostReport, AppError> {
-    println!("📊 Fetching Azure cost data...");
+    tracing::info!("📊 Fetching Azure cost data...");
 
     // For Azure, we would use the Cost Management API
     // This is a simplified implementation
@@ -279,18 +671,23 @@ async fn generate_azure_cost_report(
             cost: 200.75,
             usage: "USD".to_string(),
             region: Some("East US".to_string()),
+            account: None,
+            period: None,
         },
         ServiceCost {
             service_name: "Storage".to_string(),
             cost: 35.20,
             usage: "USD".to_string(),
             region: None,
+            account: None,
+            period: None,
         },
     ];
 
     let total_cost = services.iter().map(|s| s.cost).sum();
 
     let mut alerts = Vec::new();
+    let mut budget_burn = None;
     if let Some(threshold) = budget_threshold {
         if total_cost > *threshold {
             alerts.push(CostAlert {
@@ -300,6 +697,13 @@ async fn generate_azure_cost_report(
                 actual_cost: total_cost,
             });
         }
+
+        let today = Utc::now().date_naive();
+        let month_to_date =
+            budget::month_to_date_from_average(total_cost, (*end - *start).num_days(), today);
+        let burn = budget::project(*threshold, month_to_date, None, today);
+        alerts.extend(burn.alert());
+        budget_burn = Some(burn);
     }
 
     Ok(CostReport {
@@ -308,48 +712,337 @@ async fn generate_azure_cost_report(
         end_date: end.format("%Y-%m-%d").to_string(),
         total_cost,
         currency: "USD".to_string(),
+        group_by: None,
         services,
         alerts,
+        budget: budget_burn,
+        commitments: None,
+        forecast: None,
+        kubernetes: None,
     })
 }
 
+/// Printable statement of `report`, with a cost-center breakdown when team
+/// ownership is configured.
+fn cost_report_html(
+    report: &CostReport,
+    team_totals: Option<&BTreeMap<String, (usize, f64)>>,
+    locale: &Locale,
+) -> HtmlReport {
+    let mut html = HtmlReport::new(&format!(
+        "{} cost statement {} to {}",
+        report.provider, report.start_date, report.end_date
+    ));
+    let money = |value: f64| locale.money(value, &report.currency);
+    html.paragraph(&format!("Total cost: {}", money(report.total_cost)));
+    if let Some(burn) = &report.budget {
+        html.paragraph(&format!(
+            "Budget: {}, {} spent this month, {} projected",
+            money(burn.budget),
+            money(burn.month_to_date),
+            money(burn.projected_total)
+        ));
+    }
+    if let Some(forecast) = &report.forecast {
+        html.paragraph(&format!(
+            "Forecast: {} from {} to {}, {} by month end",
+            money(forecast.forecast_cost),
+            forecast.start_date,
+            forecast.end_date,
+            money(forecast.projected_month_end)
+        ));
+    }
+
+    if let Some(totals) = team_totals {
+        let rows: Vec<Vec<String>> = totals
+            .iter()
+            .map(|(team, (count, cost))| {
+                vec![team.clone(), count.to_string(), locale.number(*cost)]
+            })
+            .collect();
+        html.table(
+            "Cost by cost center",
+            &["Cost center", "Line items", "Cost"],
+            &rows,
+        );
+    }
+
+    let rows: Vec<Vec<String>> = report
+        .services
+        .iter()
+        .map(|service| {
+            vec![
+                service.account.clone().unwrap_or_default(),
+                service.service_name.clone(),
+                service.region.clone().unwrap_or_default(),
+                locale.number(service.cost),
+            ]
+        })
+        .collect();
+    html.table("Services", &["Account", "Service", "Region", "Cost"], &rows);
+
+    if let Some(cluster) = &report.kubernetes {
+        let rows: Vec<Vec<String>> = cluster
+            .allocations
+            .iter()
+            .map(|tenant| {
+                vec![
+                    tenant.name.clone(),
+                    locale.number(tenant.cpu_cores),
+                    locale.number(tenant.memory_gib),
+                    locale.number(tenant.cost),
+                ]
+            })
+            .collect();
+        html.table(
+            &format!("Kubernetes by {}", cluster.group_by),
+            &["Tenant", "CPU", "Memory GiB", "Cost"],
+            &rows,
+        );
+    }
+
+    if !report.alerts.is_empty() {
+        let rows: Vec<Vec<String>> = report
+            .alerts
+            .iter()
+            .map(|alert| {
+                vec![
+                    alert.severity.to_uppercase(),
+                    alert.message.clone(),
+                    locale.number(alert.threshold),
+                    locale.number(alert.actual_cost),
+                ]
+            })
+            .collect();
+        html.table(
+            "Budget alerts",
+            &["Severity", "Alert", "Threshold", "Actual"],
+            &rows,
+        );
+    }
+
+    html
+}
+
+fn output_cluster_costs(cluster: &ClusterCosts, money: &dyn Fn(f64) -> String) {
+    outln!(
+        "\nKubernetes by {} ({} nodes, {}):",
+        cluster.group_by,
+        cluster.nodes,
+        money(cluster.node_cost)
+    );
+    outln!(
+        "{:<30} {:>10} {:>12} {:>15}",
+        "Tenant",
+        "CPU",
+        "Memory GiB",
+        "Cost"
+    );
+    outln!("{:-<70}", "");
+    for tenant in &cluster.allocations {
+        outln!(
+            "{:<30} {:>10.2} {:>12.1} {:>15}",
+            tenant.name,
+            tenant.cpu_cores,
+            tenant.memory_gib,
+            money(tenant.cost)
+        );
+    }
+}
+
 #[allow(unused_variables, dead_code)]
-fn output_cost_report(report: &CostReport, format: &OutputFormat) -> Result<(), AppError> {
+fn output_commitments(commitments: &CommitmentReport, money: &dyn Fn(f64) -> String) {
+    let percent = |value: Option<f64>| {
+        value
+            .map(|v| format!("{:.1}%", v))
+            .unwrap_or_else(|| "none".to_string())
+    };
+
+    outln!("\nCommitments:");
+    outln!(
+        "{:<20} {:<10} {:<12} {}",
+        "",
+        "Coverage",
+        "Utilization",
+        "Unused"
+    );
+    outln!(
+        "{:<20} {:<10} {:<12} {}",
+        "Reserved Instances",
+        percent(commitments.reservation_coverage_percent),
+        percent(commitments.reservation_utilization_percent),
+        money(commitments.unused_reservation_cost)
+    );
+    outln!(
+        "{:<20} {:<10} {:<12} {}",
+        "Savings Plans",
+        percent(commitments.savings_plans_coverage_percent),
+        percent(commitments.savings_plans_utilization_percent),
+        money(commitments.unused_savings_plans_commitment)
+    );
+
+    if commitments.recommendations.is_empty() {
+        outln!("\nNo purchases recommended for the last 30 days of usage.");
+        return;
+    }
+    outln!("\nRecommended purchases (1 year, no upfront):");
+    for recommendation in &commitments.recommendations {
+        outln!(
+            "   {:<18} {:<40} saves {}/month",
+            recommendation.kind,
+            recommendation.description,
+            money(recommendation.estimated_monthly_savings).green()
+        );
+    }
+}
+
+/// Report lines by the period they were incurred in, oldest first.
+fn periods(report: &CostReport) -> BTreeMap<&str, Vec<&ServiceCost>> {
+    let mut periods: BTreeMap<&str, Vec<&ServiceCost>> = BTreeMap::new();
+    for service in &report.services {
+        periods
+            .entry(service.period.as_deref().unwrap_or_default())
+            .or_default()
+            .push(service);
+    }
+    periods
+}
+
+fn output_cost_lines(report: &CostReport, services: &[&ServiceCost], locale: &Locale) {
+    let show_account = report.services.iter().any(|s| s.account.is_some());
+    if show_account {
+        out!("{:<14} ", "Account");
+    }
+    let name_column = match &report.group_by {
+        Some(group_by) => capitalize(group_by),
+        None => "Service".to_string(),
+    };
+    outln!(
+        "{:<20} {:<15} {:<10}",
+        name_column,
+        format!("Cost ({})", locale::currency_symbol(&report.currency)),
+        "Region"
+    );
+    outln!("{:-<50}", "");
+
+    for service in services {
+        let region = service.region.as_deref().unwrap_or("N/A");
+        if show_account {
+            out!("{:<14} ", service.account.as_deref().unwrap_or("-"));
+        }
+        outln!(
+            "{:<20} {:<15} {:<10}",
+            service.service_name,
+            locale.number(service.cost),
+            region
+        );
+    }
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn output_cost_report(
+    report: &CostReport,
+    format: &OutputFormat,
+    locale: &Locale,
+) -> Result<(), AppError> {
+    let money = |value: f64| locale.money(value, &report.currency);
     match format {
         OutputFormat::Table => {
-            println!(
+            outln!(
                 "\n📋 Cost Report for {} ({})",
                 report.provider.green(),
                 report.currency.green()
             );
-            println!("Period: {} to {}", report.start_date, report.end_date);
-            println!("Total Cost: ${:.2}", report.total_cost);
-            println!("\nServices:");
-            println!("{:<20} {:<15} {:<10}", "Service", "Cost ($)", "Region");
-            println!("{:-<50}", "");
-
-            for service in &report.services {
-                let region = service.region.as_deref().unwrap_or("N/A");
-                println!(
-                    "{:<20} {:<15.2} {:<10}",
-                    service.service_name, service.cost, region
+            outln!("Period: {} to {}", report.start_date, report.end_date);
+            outln!("Total Cost: {}", money(report.total_cost));
+            if let Some(burn) = &report.budget {
+                outln!(
+                    "Budget: {} (month to date {} at {}/day, projected {})",
+                    money(burn.budget),
+                    money(burn.month_to_date),
+                    money(burn.daily_burn),
+                    money(burn.projected_total)
+                );
+            }
+            if let Some(forecast) = &report.forecast {
+                outln!(
+                    "Forecast: {} over the next {} days, {} by month end ({})",
+                    money(forecast.forecast_cost),
+                    forecast.days,
+                    money(forecast.projected_month_end),
+                    forecast.method
                 );
             }
+            let by_period = periods(report);
+            if by_period.len() > 1 {
+                for (period, services) in &by_period {
+                    let subtotal: f64 = services.iter().map(|s| s.cost).sum();
+                    outln!("\n{} ({})", period.bold(), money(subtotal));
+                    output_cost_lines(report, services, locale);
+                }
+            } else {
+                match &report.group_by {
+                    Some(group_by) => outln!("\nBy {}:", group_by),
+                    None => outln!("\nServices:"),
+                }
+                let services: Vec<&ServiceCost> = report.services.iter().collect();
+                output_cost_lines(report, &services, locale);
+            }
+
+            if let Some(commitments) = &report.commitments {
+                output_commitments(commitments, &money);
+            }
+            if let Some(cluster) = &report.kubernetes {
+                output_cluster_costs(cluster, &money);
+            }
         }
 
         OutputFormat::Json => {
             let json = serde_json::to_string_pretty(report)?;
-            println!("{}", json);
+            outln!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            outln!("{}", report::document(format, report)?);
         }
 
         OutputFormat::Csv => {
-            println!("Service,Cost,Currency,Region");
+            let show_account = report.services.iter().any(|s| s.account.is_some());
+            let show_period = periods(report).len() > 1;
+            let sep = locale.csv_delimiter();
+            let mut columns = Vec::new();
+            if show_period {
+                columns.push("Period");
+            }
+            if show_account {
+                columns.push("Account");
+            }
+            columns.extend(["Service", "Cost", "Currency", "Region", "Usage"]);
+            out!("{}", report::csv_record(columns, sep)?);
+
             for service in &report.services {
-                let region = service.region.as_deref().unwrap_or("N/A");
-                println!(
-                    "{},{:.2},{},{}",
-                    service.service_name, service.cost, report.currency, region
-                );
+                let mut fields = Vec::new();
+                if show_period {
+                    fields.push(service.period.clone().unwrap_or_default());
+                }
+                if show_account {
+                    fields.push(service.account.clone().unwrap_or_default());
+                }
+                fields.extend([
+                    service.service_name.clone(),
+                    locale.plain_number(service.cost),
+                    report.currency.clone(),
+                    service.region.clone().unwrap_or_else(|| "N/A".to_string()),
+                    service.usage.clone(),
+                ]);
+                out!("{}", report::csv_record(fields, sep)?);
             }
         }
     }
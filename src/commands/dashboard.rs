@@ -0,0 +1,450 @@
+use crate::cli::{CloudProvider, Commands, ResourceType};
+use crate::commands::cost_report::CostReport;
+use crate::commands::list::ResourceSummary;
+use crate::error::AppError;
+use crate::providers;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use futures::StreamExt;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Sparkline, Table, TableState, Tabs};
+use ratatui::{Frame, Terminal};
+use tokio::sync::mpsc;
+
+/// Days of daily totals in the cost trend
+const TREND_DAYS: i64 = 7;
+
+/// Days the service breakdown covers
+const BREAKDOWN_DAYS: i64 = 30;
+
+/// Cost data changes about daily and every Cost Explorer call is billed, so
+/// costs refresh at most this often whatever `--interval` says
+const COST_REFRESH_SECS: i64 = 3600;
+
+const PROVIDERS: [CloudProvider; 3] =
+    [CloudProvider::Aws, CloudProvider::Gcp, CloudProvider::Azure];
+
+/// Data for one panel of one provider.
+enum Loaded<T> {
+    NotLoaded,
+    Loading,
+    Ready(T, DateTime<Utc>),
+    Failed(String),
+}
+
+impl<T> Loaded<T> {
+    fn loaded_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Loaded::Ready(_, at) => Some(*at),
+            _ => None,
+        }
+    }
+}
+
+/// Daily totals for the trend and the breakdown by service.
+struct CostTrend {
+    daily: Vec<(NaiveDate, f64)>,
+    breakdown: CostReport,
+}
+
+/// Data fetched in the background for provider `PROVIDERS[index]`.
+enum Update {
+    Resources(usize, Result<Vec<ResourceSummary>, String>),
+    Costs(usize, Result<CostTrend, String>),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Focus {
+    Resources,
+    Costs,
+}
+
+struct Dashboard {
+    provider: usize,
+    focus: Focus,
+    refresh: Duration,
+    resources: [Loaded<Vec<ResourceSummary>>; 3],
+    costs: [Loaded<CostTrend>; 3],
+    resource_table: TableState,
+    cost_table: TableState,
+}
+
+/// Restores the terminal however the dashboard exits.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(std::io::stdout(), LeaveAlternateScreen);
+    }
+}
+
+pub async fn dashboard(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Dashboard {
+        provider,
+        profile,
+        interval,
+    } = cmd
+    {
+        if *interval == 0 {
+            return Err(AppError::InvalidParameters(
+                "--interval must be at least 1 second".to_string(),
+            ));
+        }
+
+        let mut dashboard = Dashboard {
+            provider: match provider {
+                Some(CloudProvider::Gcp) => 1,
+                Some(CloudProvider::Azure) => 2,
+                Some(CloudProvider::Aws) | None => 0,
+            },
+            focus: Focus::Resources,
+            refresh: Duration::seconds(*interval as i64),
+            resources: [Loaded::NotLoaded, Loaded::NotLoaded, Loaded::NotLoaded],
+            costs: [Loaded::NotLoaded, Loaded::NotLoaded, Loaded::NotLoaded],
+            resource_table: TableState::default(),
+            cost_table: TableState::default(),
+        };
+
+        enable_raw_mode()?;
+        let _guard = TerminalGuard;
+        execute!(std::io::stdout(), EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+        dashboard.run(&mut terminal).await?;
+    }
+
+    Ok(())
+}
+
+impl Dashboard {
+    async fn run(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    ) -> Result<(), AppError> {
+        let (updates_tx, mut updates) = mpsc::unbounded_channel();
+        let mut events = EventStream::new();
+        let mut tick = tokio::time::interval(std::time::Duration::from_secs(1));
+
+        loop {
+            self.load_stale(&updates_tx);
+            terminal.draw(|frame| self.render(frame))?;
+
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Char('r') => {
+                            self.resources[self.provider] = Loaded::NotLoaded;
+                            self.costs[self.provider] = Loaded::NotLoaded;
+                        }
+                        KeyCode::Tab => {
+                            self.focus = match self.focus {
+                                Focus::Resources => Focus::Costs,
+                                Focus::Costs => Focus::Resources,
+                            };
+                        }
+                        KeyCode::Right | KeyCode::Char('l') => {
+                            self.provider = (self.provider + 1) % PROVIDERS.len();
+                        }
+                        KeyCode::Left | KeyCode::Char('h') => {
+                            self.provider = (self.provider + PROVIDERS.len() - 1) % PROVIDERS.len();
+                        }
+                        KeyCode::Char(c @ '1'..='3') => {
+                            self.provider = c as usize - '1' as usize;
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => self.focused_table().select_next(),
+                        KeyCode::Up | KeyCode::Char('k') => self.focused_table().select_previous(),
+                        _ => {}
+                    },
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                    None => return Ok(()),
+                },
+                Some(update) = updates.recv() => {
+                    self.apply(update);
+                    // Provider SDKs may have written warnings over the screen
+                    terminal.clear()?;
+                }
+                _ = tick.tick() => {}
+            }
+        }
+    }
+
+    /// Start fetching the selected provider's panels that were never loaded
+    /// or are older than their refresh interval. Other providers load when
+    /// they are first selected, so unused ones cost no API calls.
+    fn load_stale(&mut self, updates: &mpsc::UnboundedSender<Update>) {
+        let index = self.provider;
+        let now = Utc::now();
+
+        let resources = &self.resources[index];
+        let stale = match resources {
+            Loaded::NotLoaded => true,
+            Loaded::Ready(..) => resources
+                .loaded_at()
+                .is_some_and(|at| now - at >= self.refresh),
+            Loaded::Loading | Loaded::Failed(_) => false,
+        };
+        if stale {
+            self.resources[index] = Loaded::Loading;
+            let updates = updates.clone();
+            let provider = PROVIDERS[index].clone();
+            tokio::spawn(async move {
+                let resources = providers::get(&provider)
+                    .list(&ResourceType::All)
+                    .await
+                    .map_err(|e| e.to_string());
+                let _ = updates.send(Update::Resources(index, resources));
+            });
+        }
+
+        let costs = &self.costs[index];
+        let cost_refresh = self.refresh.max(Duration::seconds(COST_REFRESH_SECS));
+        let stale = match costs {
+            Loaded::NotLoaded => true,
+            Loaded::Ready(..) => costs.loaded_at().is_some_and(|at| now - at >= cost_refresh),
+            Loaded::Loading | Loaded::Failed(_) => false,
+        };
+        if stale {
+            self.costs[index] = Loaded::Loading;
+            let updates = updates.clone();
+            let provider = PROVIDERS[index].clone();
+            tokio::spawn(async move {
+                let trend = cost_trend(&provider).await.map_err(|e| e.to_string());
+                let _ = updates.send(Update::Costs(index, trend));
+            });
+        }
+    }
+
+    fn apply(&mut self, update: Update) {
+        let now = Utc::now();
+        match update {
+            Update::Resources(index, result) => {
+                self.resources[index] = match result {
+                    Ok(resources) => Loaded::Ready(resources, now),
+                    Err(e) => Loaded::Failed(e),
+                };
+            }
+            Update::Costs(index, result) => {
+                self.costs[index] = match result {
+                    Ok(trend) => Loaded::Ready(trend, now),
+                    Err(e) => Loaded::Failed(e),
+                };
+            }
+        }
+    }
+
+    fn focused_table(&mut self) -> &mut TableState {
+        match self.focus {
+            Focus::Resources => &mut self.resource_table,
+            Focus::Costs => &mut self.cost_table,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [resources, costs] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(body);
+        let [trend, breakdown] =
+            Layout::vertical([Constraint::Length(7), Constraint::Min(0)]).areas(costs);
+
+        let titles = PROVIDERS
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("{} {}", i + 1, p));
+        frame.render_widget(
+            Tabs::new(titles)
+                .select(self.provider)
+                .highlight_style(
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .block(Block::bordered().title(" actlog dashboard ")),
+            header,
+        );
+
+        self.render_resources(frame, resources);
+        self.render_trend(frame, trend);
+        self.render_breakdown(frame, breakdown);
+
+        frame.render_widget(
+            Paragraph::new(Line::from(
+                " ←/→ provider  Tab panel  ↑/↓ select  r refresh  q quit",
+            ))
+            .style(Style::default().fg(Color::DarkGray)),
+            footer,
+        );
+    }
+
+    fn render_resources(&mut self, frame: &mut Frame, area: Rect) {
+        let block = panel_block(
+            "Resources",
+            &self.resources[self.provider],
+            self.focus == Focus::Resources,
+        );
+        let resources = match &self.resources[self.provider] {
+            Loaded::Ready(resources, _) => resources,
+            other => return frame.render_widget(placeholder(other).block(block), area),
+        };
+
+        let rows = resources.iter().map(|r| {
+            let state =
+                Cell::from(r.state.clone()).style(Style::default().fg(state_color(&r.state)));
+            Row::new(vec![
+                Cell::from(r.resource_type.clone()),
+                Cell::from(r.id.clone()),
+                Cell::from(r.name.clone()),
+                state,
+                Cell::from(r.region.clone()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(14),
+                Constraint::Percentage(30),
+                Constraint::Percentage(30),
+                Constraint::Length(12),
+                Constraint::Length(14),
+            ],
+        )
+        .header(
+            Row::new(["Type", "ID", "Name", "State", "Region"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(block);
+        frame.render_stateful_widget(table, area, &mut self.resource_table);
+    }
+
+    fn render_trend(&self, frame: &mut Frame, area: Rect) {
+        let costs = &self.costs[self.provider];
+        let Loaded::Ready(trend, _) = costs else {
+            let block = panel_block("Daily cost", costs, false);
+            return frame.render_widget(placeholder(costs).block(block), area);
+        };
+
+        let latest = trend
+            .daily
+            .last()
+            .map(|(_, cost)| *cost)
+            .unwrap_or_default();
+        let title = format!(
+            "Daily cost, last {} days (latest {:.2} {})",
+            TREND_DAYS, latest, trend.breakdown.currency
+        );
+        // Cents keep small bills from flattening into zeros
+        let data: Vec<u64> = trend
+            .daily
+            .iter()
+            .map(|(_, cost)| (cost * 100.0).round().max(0.0) as u64)
+            .collect();
+        frame.render_widget(
+            Sparkline::default()
+                .data(&data)
+                .style(Style::default().fg(Color::Cyan))
+                .block(panel_block(&title, costs, false)),
+            area,
+        );
+    }
+
+    fn render_breakdown(&mut self, frame: &mut Frame, area: Rect) {
+        let costs = &self.costs[self.provider];
+        let focused = self.focus == Focus::Costs;
+        let Loaded::Ready(trend, _) = costs else {
+            let block = panel_block("Cost by service", costs, focused);
+            return frame.render_widget(placeholder(costs).block(block), area);
+        };
+
+        let report = &trend.breakdown;
+        let title = format!(
+            "Cost by service, last {} days ({:.2} {})",
+            BREAKDOWN_DAYS, report.total_cost, report.currency
+        );
+        let mut services: Vec<_> = report.services.iter().collect();
+        services.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+        let rows = services.into_iter().map(|s| {
+            Row::new(vec![
+                Cell::from(s.service_name.clone()),
+                Cell::from(format!("{:>12.2}", s.cost)),
+            ])
+        });
+        let table = Table::new(rows, [Constraint::Min(10), Constraint::Length(13)])
+            .header(
+                Row::new(["Service", "Cost"]).style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(panel_block(&title, costs, focused));
+        frame.render_stateful_widget(table, area, &mut self.cost_table);
+    }
+}
+
+/// Daily totals for the last [`TREND_DAYS`] days and the service breakdown
+/// of the last [`BREAKDOWN_DAYS`].
+async fn cost_trend(provider: &CloudProvider) -> Result<CostTrend, AppError> {
+    let costs = providers::get(provider);
+    let today = Utc::now().date_naive();
+
+    let mut daily = Vec::new();
+    for days_ago in (1..=TREND_DAYS).rev() {
+        let day = today - Duration::days(days_ago);
+        let start = day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let report = costs
+            .cost_report(&start, &(start + Duration::days(1)), &None)
+            .await?;
+        daily.push((day, report.total_cost));
+    }
+
+    let end = Utc::now();
+    let breakdown = costs
+        .cost_report(&(end - Duration::days(BREAKDOWN_DAYS)), &end, &None)
+        .await?;
+    Ok(CostTrend { daily, breakdown })
+}
+
+/// A bordered panel titled with its name and how fresh its data is,
+/// highlighted when it has the keyboard focus.
+fn panel_block<'a, T>(title: &str, data: &Loaded<T>, focused: bool) -> Block<'a> {
+    let updated = data
+        .loaded_at()
+        .map(|at| format!(" · {}", at.with_timezone(&Local).format("%H:%M:%S")))
+        .unwrap_or_default();
+    let block = Block::bordered().title(format!(" {}{} ", title, updated));
+    if focused {
+        block.border_style(Style::default().fg(Color::Green))
+    } else {
+        block
+    }
+}
+
+fn placeholder<'a, T>(data: &Loaded<T>) -> Paragraph<'a> {
+    match data {
+        Loaded::Failed(e) => {
+            Paragraph::new(format!("⚠️  {}", e)).style(Style::default().fg(Color::Red))
+        }
+        _ => Paragraph::new("Loading...").style(Style::default().fg(Color::DarkGray)),
+    }
+}
+
+fn state_color(state: &str) -> Color {
+    match state.to_lowercase().as_str() {
+        "running" | "active" | "available" => Color::Green,
+        "stopped" | "deallocated" | "terminated" => Color::Red,
+        "pending" | "starting" => Color::Yellow,
+        _ => Color::Reset,
+    }
+}
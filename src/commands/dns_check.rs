@@ -0,0 +1,322 @@
+use crate::cli::{CloudProvider, Commands, OutputFormat};
+use crate::clients;
+use crate::dns::{self, DnsZone, IpRanges};
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::regions;
+use crate::report;
+use aws_config::SdkConfig;
+use colored::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+/// Addresses and hostnames the account currently holds.
+#[derive(Default)]
+struct Endpoints {
+    ips: HashSet<IpAddr>,
+    hostnames: HashSet<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DanglingRecord {
+    zone: String,
+    name: String,
+    record_type: String,
+    target: String,
+    reason: String,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn dns_check(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::DnsCheck {
+        provider,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!(
+            "🌐 Checking {} DNS records for dangling targets...",
+            provider.to_string().green()
+        );
+
+        let (zones, endpoints) = match provider {
+            CloudProvider::Aws => {
+                let config = clients::aws_config().await;
+                (
+                    dns::aws_zones(&config).await?,
+                    aws_endpoints(&config).await?,
+                )
+            }
+            CloudProvider::Gcp => (dns::gcp_zones().await?, gcp_endpoints().await?),
+            CloudProvider::Azure => {
+                println!("   DNS checks not yet implemented for Azure");
+                return Ok(());
+            }
+        };
+        let ranges = IpRanges::fetch(provider).await?;
+
+        let dangling = find_dangling(provider, &zones, &endpoints, &ranges);
+        output_dangling(&dangling, format)?;
+
+        let records: usize = zones.iter().map(|z| z.records.len()).sum();
+        println!(
+            "\n📊 {} records in {} zones checked, {} dangling",
+            records,
+            zones.len(),
+            if dangling.is_empty() {
+                "0".green()
+            } else {
+                dangling.len().to_string().red()
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Records whose target is a provider address or load balancer the account
+/// no longer holds. Addresses outside the provider's ranges are someone
+/// else's by design and are not reported.
+fn find_dangling(
+    provider: &CloudProvider,
+    zones: &[DnsZone],
+    endpoints: &Endpoints,
+    ranges: &IpRanges,
+) -> Vec<DanglingRecord> {
+    let mut dangling = Vec::new();
+
+    for zone in zones {
+        for record in &zone.records {
+            let mut flag = |target: &str, reason: String| {
+                dangling.push(DanglingRecord {
+                    zone: zone.name.clone(),
+                    name: record.name.clone(),
+                    record_type: record.record_type.clone(),
+                    target: target.to_string(),
+                    reason,
+                });
+            };
+
+            match record.record_type.as_str() {
+                "A" | "AAAA" => {
+                    for value in &record.values {
+                        let Ok(ip) = value.parse::<IpAddr>() else {
+                            continue;
+                        };
+                        if ranges.contains(ip) && !endpoints.ips.contains(&ip) {
+                            flag(
+                                value,
+                                format!("{} address not allocated to this account", provider),
+                            );
+                        }
+                    }
+                }
+                "CNAME" => {
+                    for value in &record.values {
+                        let host = dns::normalize_host(value);
+                        if is_load_balancer(&host) && !endpoints.hostnames.contains(&host) {
+                            flag(value, "Load balancer no longer exists".to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            // Route 53 aliases can sit on any record type
+            if let Some(target) = &record.alias_target {
+                if is_load_balancer(target) && !endpoints.hostnames.contains(target) {
+                    flag(
+                        target,
+                        "Alias to a load balancer that no longer exists".to_string(),
+                    );
+                }
+            }
+        }
+    }
+
+    dangling
+}
+
+/// Application, network and classic load balancer hostnames, e.g.
+/// `web-123.eu-west-1.elb.amazonaws.com` or `nlb-456.elb.us-east-1.amazonaws.com`.
+fn is_load_balancer(host: &str) -> bool {
+    host.ends_with(".amazonaws.com") && host.contains(".elb.")
+}
+
+async fn aws_endpoints(base: &SdkConfig) -> Result<Endpoints, AppError> {
+    let all_regions = regions::aws_regions(base).await?;
+    let results = executor::for_each_region(base, &all_regions, |_, config| async move {
+        aws_region_endpoints(&config).await
+    })
+    .await;
+
+    if results.len() < all_regions.len() {
+        println!(
+            "   ⚠️  {} regions could not be scanned; records pointing there may be reported as dangling",
+            all_regions.len() - results.len()
+        );
+    }
+
+    let mut endpoints = Endpoints::default();
+    for (_, found) in results {
+        endpoints.ips.extend(found.ips);
+        endpoints.hostnames.extend(found.hostnames);
+    }
+    Ok(endpoints)
+}
+
+async fn aws_region_endpoints(config: &SdkConfig) -> Result<Endpoints, AppError> {
+    let mut endpoints = Endpoints::default();
+    let ec2 = clients::get::<aws_sdk_ec2::Client>(config).await;
+
+    executor::throttle("ec2").await;
+    let addresses = ec2
+        .describe_addresses()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeAddresses", e))?;
+    endpoints.ips.extend(
+        addresses
+            .addresses()
+            .iter()
+            .filter_map(|a| a.public_ip()?.parse().ok()),
+    );
+
+    let mut pages = ec2.describe_instances().into_paginator().send();
+    loop {
+        executor::throttle("ec2").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("ec2:DescribeInstances", e))?;
+        for instance in page.reservations().iter().flat_map(|r| r.instances()) {
+            endpoints
+                .ips
+                .extend(instance.public_ip_address().and_then(|ip| ip.parse().ok()));
+            endpoints.ips.extend(
+                instance
+                    .network_interfaces()
+                    .iter()
+                    .flat_map(|ni| ni.ipv6_addresses())
+                    .filter_map(|a| a.ipv6_address()?.parse().ok()),
+            );
+        }
+    }
+
+    let elb = clients::get::<aws_sdk_elasticloadbalancingv2::Client>(config).await;
+    let mut pages = elb.describe_load_balancers().into_paginator().send();
+    loop {
+        executor::throttle("elasticloadbalancing").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("elbv2:DescribeLoadBalancers", e))?;
+        endpoints.hostnames.extend(
+            page.load_balancers()
+                .iter()
+                .filter_map(|lb| lb.dns_name())
+                .map(dns::normalize_host),
+        );
+    }
+
+    Ok(endpoints)
+}
+
+/// Reserved addresses, forwarding rule addresses and instance external IPs.
+async fn gcp_endpoints() -> Result<Endpoints, AppError> {
+    let project_id = gcp::project_id()?;
+    let mut endpoints = Endpoints::default();
+
+    for address in gcp_aggregated(&project_id, "addresses").await? {
+        endpoints
+            .ips
+            .extend(address["address"].as_str().and_then(|ip| ip.parse().ok()));
+    }
+
+    for rule in gcp_aggregated(&project_id, "forwardingRules").await? {
+        endpoints
+            .ips
+            .extend(rule["IPAddress"].as_str().and_then(|ip| ip.parse().ok()));
+    }
+
+    for instance in gcp_aggregated(&project_id, "instances").await? {
+        endpoints.ips.extend(
+            instance["networkInterfaces"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|ni| ni["accessConfigs"].as_array())
+                .flatten()
+                .filter_map(|ac| ac["natIP"].as_str()?.parse().ok()),
+        );
+    }
+
+    Ok(endpoints)
+}
+
+/// Every item of a Compute aggregated list, across all regions and zones.
+async fn gcp_aggregated(project_id: &str, kind: &str) -> Result<Vec<Value>, AppError> {
+    let url = format!(
+        "{}/projects/{}/aggregated/{}",
+        gcp::COMPUTE_API,
+        project_id,
+        kind
+    );
+    let pages = gcp::list_pages(&format!("compute.{}.aggregatedList", kind), &url).await?;
+
+    Ok(pages
+        .iter()
+        .filter_map(|page| page["items"].as_object())
+        .flat_map(|scopes| scopes.values())
+        .filter_map(|scope| scope[kind].as_array())
+        .flatten()
+        .cloned()
+        .collect())
+}
+
+fn output_dangling(dangling: &[DanglingRecord], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            if dangling.is_empty() {
+                println!("✅ No dangling records found.");
+                return Ok(());
+            }
+            println!("\n⚠️  Dangling DNS records:");
+            println!("{:<40} {:<6} {:<40} {}", "Name", "Type", "Target", "Reason");
+            println!("{:-<120}", "");
+            for record in dangling {
+                println!(
+                    "{:<40} {:<6} {:<40} {}",
+                    record.name,
+                    record.record_type,
+                    record.target.yellow(),
+                    record.reason
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(dangling)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, dangling)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Zone,Name,Type,Target,Reason");
+            for record in dangling {
+                println!(
+                    "{},{},{},{},{}",
+                    record.zone, record.name, record.record_type, record.target, record.reason
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
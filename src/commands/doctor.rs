@@ -0,0 +1,897 @@
+use crate::azure;
+use crate::cli::{CloudProvider, Commands, OutputFormat};
+use crate::clients;
+use crate::commands::authenticate::{
+    authenticated_profiles, session_credentials, stored_credentials,
+};
+use crate::commands::config::{configured_profiles, stored_config};
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::report;
+use crate::retry;
+use crate::secrets;
+use aws_config::{Region, SdkConfig};
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+/// Read-only calls the reports and scans make; a profile missing any of
+/// them gets partial results
+const REQUIRED_AWS_ACTIONS: &[&str] = &[
+    "ec2:DescribeRegions",
+    "ec2:DescribeInstances",
+    "ec2:DescribeVolumes",
+    "cloudwatch:GetMetricData",
+    "ce:GetCostAndUsage",
+    "autoscaling:DescribeAutoScalingGroups",
+    "s3:ListAllMyBuckets",
+    "iam:ListRoles",
+    "cloudtrail:LookupEvents",
+    "pricing:GetProducts",
+];
+
+const REQUIRED_GCP_PERMISSIONS: &[&str] = &[
+    "resourcemanager.projects.get",
+    "compute.instances.list",
+    "compute.addresses.list",
+    "compute.snapshots.list",
+    "monitoring.timeSeries.list",
+    "cloudsql.instances.list",
+    "dns.managedZones.list",
+];
+
+const REQUIRED_AZURE_ACTIONS: &[&str] = &[
+    "Microsoft.Resources/subscriptions/read",
+    "Microsoft.Compute/virtualMachines/read",
+    "Microsoft.Network/networkInterfaces/read",
+    "Microsoft.Network/publicIPAddresses/read",
+    "Microsoft.Insights/metrics/read",
+    "Microsoft.Sql/servers/read",
+    "Microsoft.Cdn/profiles/read",
+];
+
+/// API version of the Azure caller permissions list
+const AUTHORIZATION_API_VERSION: &str = "2022-04-01";
+
+/// Region for STS when a profile has none configured
+const FALLBACK_AWS_REGION: &str = "us-east-1";
+
+/// Checks in the order they are shown
+const CHECKS: &[&str] = &["Environment", "Config", "Token", "API", "Permissions"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Skip,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    /// A failed provider call, with the remediation of [`AppError::hint`]
+    /// when there is one.
+    fn from_error(name: &'static str, e: &AppError, profile: &str, fallback: &str) -> Self {
+        Check::fail(
+            name,
+            e.to_string(),
+            e.hint(profile).unwrap_or_else(|| fallback.to_string()),
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ProfileReport {
+    provider: String,
+    profile: String,
+    checks: Vec<Check>,
+}
+
+impl ProfileReport {
+    fn status(&self, name: &str) -> Status {
+        self.checks
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.status)
+            .unwrap_or(Status::Skip)
+    }
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn doctor(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Doctor {
+        provider,
+        profile,
+        offline,
+        format,
+    } = cmd
+    {
+        let selected = provider.is_some();
+        let providers = match provider {
+            Some(provider) => vec![provider.clone()],
+            None => vec![CloudProvider::Aws, CloudProvider::Gcp, CloudProvider::Azure],
+        };
+
+        let mut reports = Vec::new();
+        for provider in &providers {
+            let profiles = match profile {
+                Some(profile) => vec![profile.clone()],
+                None => known_profiles(provider, selected)?,
+            };
+            for profile in profiles {
+                tracing::info!(
+                    "🩺 Checking {} profile {}...",
+                    provider.to_string().green(),
+                    profile.green()
+                );
+                let checks = match provider {
+                    CloudProvider::Aws => check_aws(&profile, *offline).await?,
+                    CloudProvider::Gcp => check_gcp(&profile, *offline).await?,
+                    CloudProvider::Azure => check_azure(&profile, *offline).await?,
+                };
+                reports.push(ProfileReport {
+                    provider: provider.to_string(),
+                    profile,
+                    checks,
+                });
+            }
+        }
+
+        output_reports(&reports, format)?;
+
+        let failed = reports
+            .iter()
+            .flat_map(|r| &r.checks)
+            .filter(|c| c.status == Status::Fail)
+            .count();
+        if failed > 0 {
+            return Err(AppError::ConfigurationError(format!(
+                "{} credential check{} failed",
+                failed,
+                if failed == 1 { "" } else { "s" }
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Profiles of `provider` saved by `actlog authenticate` or `actlog config`,
+/// plus AWS profiles in the shared config files. `default` stands in when
+/// there are none but the environment has credentials, or the provider was
+/// asked for explicitly.
+fn known_profiles(provider: &CloudProvider, selected: bool) -> Result<Vec<String>, AppError> {
+    let mut profiles: BTreeSet<String> = authenticated_profiles(provider)?.into_iter().collect();
+    profiles.extend(configured_profiles(provider)?);
+    if matches!(provider, CloudProvider::Aws) {
+        profiles.extend(shared_aws_profiles());
+    }
+
+    let env_credentials = match provider {
+        CloudProvider::Aws => env_set("AWS_ACCESS_KEY_ID") || env_set("AWS_PROFILE"),
+        CloudProvider::Gcp => env_set("GOOGLE_APPLICATION_CREDENTIALS"),
+        CloudProvider::Azure => env_set("AZURE_SUBSCRIPTION_ID"),
+    };
+    if profiles.is_empty() && (env_credentials || selected) {
+        profiles.insert("default".to_string());
+    }
+    Ok(profiles.into_iter().collect())
+}
+
+async fn check_aws(profile: &str, offline: bool) -> Result<Vec<Check>, AppError> {
+    let mut checks = Vec::new();
+    let reauth = format!(
+        "Run `actlog authenticate aws --profile {} --force`",
+        profile
+    );
+    let shared = shared_aws_profiles().contains(profile);
+
+    checks.push(
+        if env_set("AWS_ACCESS_KEY_ID") && env_set("AWS_SECRET_ACCESS_KEY") {
+            Check::pass("Environment", "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY set")
+        } else if env_set("AWS_ACCESS_KEY_ID") {
+            Check::fail(
+                "Environment",
+                "AWS_ACCESS_KEY_ID is set without AWS_SECRET_ACCESS_KEY",
+                "Set AWS_SECRET_ACCESS_KEY or unset AWS_ACCESS_KEY_ID",
+            )
+        } else if let Ok(aws_profile) = std::env::var("AWS_PROFILE") {
+            Check::pass("Environment", format!("AWS_PROFILE={}", aws_profile))
+        } else if shared {
+            Check::pass("Environment", "not needed; the shared config has the profile")
+        } else {
+            Check::warn(
+                "Environment",
+                "no AWS credentials in the environment",
+                "Set AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY, or add the profile to ~/.aws/credentials",
+            )
+        },
+    );
+
+    let stored = stored_credentials(&CloudProvider::Aws, profile)?;
+    let config = stored_config(&CloudProvider::Aws, profile)?;
+    checks.push(config_check(
+        &CloudProvider::Aws,
+        profile,
+        stored.as_ref().map(|c| c.in_keyring),
+        config.is_some() || shared,
+        format!(
+            "Run `aws configure --profile {}` or `actlog authenticate aws --profile {}`",
+            profile, profile
+        ),
+    ));
+
+    checks.push(match &stored {
+        Some(credentials) => match expiry(credentials.expires_at.as_deref()) {
+            None => Check::pass("Token", "long-lived keys"),
+            Some(expires) if expires > Utc::now() => {
+                Check::pass("Token", format!("session valid {}", until(expires)))
+            }
+            Some(_) if credentials.assumed_role.is_some() || credentials.sso.is_some() => {
+                Check::pass("Token", "session expired; renewed on next use")
+            }
+            Some(expires) => match &credentials.mfa_serial {
+                Some(serial) => Check::fail(
+                    "Token",
+                    format!("MFA session expired {}", expires.format("%Y-%m-%d %H:%M")),
+                    format!(
+                        "Run `actlog authenticate aws --profile {} --mfa-serial {} --force`",
+                        profile, serial
+                    ),
+                ),
+                None => Check::fail(
+                    "Token",
+                    format!("expired {}", expires.format("%Y-%m-%d %H:%M")),
+                    reauth.clone(),
+                ),
+            },
+        },
+        None => Check::skip("Token", "no actlog session"),
+    });
+
+    if offline {
+        checks.push(Check::skip("API", "--offline"));
+        checks.push(Check::skip("Permissions", "--offline"));
+        return Ok(checks);
+    }
+
+    let sdk_config = match aws_profile_config(profile).await {
+        Ok(config) => config,
+        Err(e) => {
+            checks.push(Check::from_error("API", &e, profile, &reauth));
+            checks.push(Check::skip("Permissions", "no working credentials"));
+            return Ok(checks);
+        }
+    };
+    let sts = clients::get::<aws_sdk_sts::Client>(&sdk_config).await;
+    executor::throttle("sts").await;
+    let identity = match sts.get_caller_identity().send().await {
+        Ok(identity) => identity,
+        Err(e) => {
+            let e = AppError::aws("sts:GetCallerIdentity", e);
+            checks.push(Check::from_error("API", &e, profile, &reauth));
+            checks.push(Check::skip("Permissions", "no working credentials"));
+            return Ok(checks);
+        }
+    };
+    let arn = identity.arn().unwrap_or_default().to_string();
+    checks.push(Check::pass("API", format!("signed in as {}", arn)));
+    checks.push(check_aws_permissions(&sdk_config, profile, &arn).await);
+
+    Ok(checks)
+}
+
+/// Whether the IAM policies of the caller allow [`REQUIRED_AWS_ACTIONS`],
+/// from the policy simulator.
+async fn check_aws_permissions(config: &SdkConfig, profile: &str, arn: &str) -> Check {
+    let Some(principal) = policy_principal(arn) else {
+        return Check::pass("Permissions", "root user");
+    };
+
+    let iam = clients::get::<aws_sdk_iam::Client>(config).await;
+    executor::throttle("iam").await;
+    let simulated = iam
+        .simulate_principal_policy()
+        .policy_source_arn(&principal)
+        .set_action_names(Some(
+            REQUIRED_AWS_ACTIONS.iter().map(|a| a.to_string()).collect(),
+        ))
+        .send()
+        .await;
+    let results = match simulated {
+        Ok(results) => results,
+        Err(e) => {
+            let e = AppError::aws("iam:SimulatePrincipalPolicy", e);
+            return Check::warn(
+                "Permissions",
+                format!("could not simulate: {}", e),
+                format!(
+                    "Grant profile {} iam:SimulatePrincipalPolicy so the doctor can check its permissions",
+                    profile
+                ),
+            );
+        }
+    };
+
+    let denied: Vec<&str> = results
+        .evaluation_results()
+        .iter()
+        .filter(|r| r.eval_decision() != &aws_sdk_iam::types::PolicyEvaluationDecisionType::Allowed)
+        .map(|r| r.eval_action_name())
+        .collect();
+    missing_permissions_check(
+        &denied,
+        REQUIRED_AWS_ACTIONS.len(),
+        format!(
+            "Add {} to the IAM policy of {}",
+            denied.join(", "),
+            principal
+        ),
+    )
+}
+
+/// The IAM principal to simulate for the caller ARN: the role behind an
+/// assumed-role session, or `None` for the root user.
+fn policy_principal(arn: &str) -> Option<String> {
+    if arn.ends_with(":root") {
+        return None;
+    }
+    // arn:aws:sts::123456789012:assumed-role/Name/session
+    if let Some((prefix, rest)) = arn.split_once(":assumed-role/") {
+        let role = rest.split('/').next().unwrap_or(rest);
+        let account_prefix = prefix.replacen(":sts:", ":iam:", 1);
+        return Some(format!("{}:role/{}", account_prefix, role));
+    }
+    Some(arn.to_string())
+}
+
+/// The profile's AWS config with its actlog session, in a usable region.
+async fn aws_profile_config(profile: &str) -> Result<SdkConfig, AppError> {
+    let loader = || {
+        let loader = retry::aws_loader();
+        if profile == "default" {
+            loader
+        } else {
+            loader.profile_name(profile)
+        }
+    };
+
+    let base = loader().load().await;
+    let config = match session_credentials(profile, &base).await? {
+        Some(credentials) => loader().credentials_provider(credentials).load().await,
+        None => base,
+    };
+    if config.region().is_some() {
+        return Ok(config);
+    }
+    Ok(config
+        .into_builder()
+        .region(Region::new(FALLBACK_AWS_REGION))
+        .build())
+}
+
+async fn check_gcp(profile: &str, offline: bool) -> Result<Vec<Check>, AppError> {
+    let mut checks = Vec::new();
+    let reauth = format!(
+        "Run `actlog authenticate gcp --profile {} --force`",
+        profile
+    );
+
+    let key = std::env::var("GOOGLE_APPLICATION_CREDENTIALS")
+        .ok()
+        .map(|path| gcp::load_service_account(&path));
+    checks.push(match &key {
+        Some(Ok(key)) => Check::pass(
+            "Environment",
+            format!("service account {}", key.client_email),
+        ),
+        Some(Err(e)) => Check::fail(
+            "Environment",
+            e.to_string(),
+            "Point GOOGLE_APPLICATION_CREDENTIALS at a service account JSON key",
+        ),
+        None => Check::warn(
+            "Environment",
+            "GOOGLE_APPLICATION_CREDENTIALS not set; using the gcloud login",
+            "Set GOOGLE_APPLICATION_CREDENTIALS to a service account key for unattended runs",
+        ),
+    });
+
+    let stored = stored_credentials(&CloudProvider::Gcp, profile)?;
+    let config = stored_config(&CloudProvider::Gcp, profile)?;
+    let project_id = stored
+        .as_ref()
+        .and_then(|c| c.project_id.clone())
+        .or(config.as_ref().and_then(|c| c.project_id.clone()))
+        .or(gcp::project_id().ok());
+    let mut config_check = config_check(
+        &CloudProvider::Gcp,
+        profile,
+        stored.as_ref().map(|c| c.in_keyring),
+        config.is_some() || matches!(key, Some(Ok(_))),
+        reauth.clone(),
+    );
+    if project_id.is_none() && config_check.status != Status::Fail {
+        config_check = Check::fail(
+            "Config",
+            "no project",
+            format!(
+                "Set GOOGLE_CLOUD_PROJECT or run `actlog config gcp --profile {} --project-id <id>`",
+                profile
+            ),
+        );
+    }
+    checks.push(config_check);
+
+    checks.push(match (&key, offline) {
+        (Some(Ok(key)), false) => match gcp::service_account_token(key).await {
+            Ok(token) => Check::pass(
+                "Token",
+                format!("minted, valid {}", until(token.expires_at)),
+            ),
+            Err(e) => Check::from_error(
+                "Token",
+                &e,
+                profile,
+                "Create a new key for the service account; this one may be disabled or deleted",
+            ),
+        },
+        (Some(Ok(_)), true) => Check::skip("Token", "--offline"),
+        _ => Check::skip("Token", "from gcloud"),
+    });
+
+    let Some(project_id) = project_id.filter(|_| !offline) else {
+        let reason = if offline { "--offline" } else { "no project" };
+        checks.push(Check::skip("API", reason));
+        checks.push(Check::skip("Permissions", reason));
+        return Ok(checks);
+    };
+
+    let project_url = format!("{}/projects/{}", gcp::RESOURCE_MANAGER_API, project_id);
+    if let Err(e) = gcp::get("cloudresourcemanager.projects.get", &project_url).await {
+        checks.push(Check::from_error("API", &e, profile, &reauth));
+        checks.push(Check::skip("Permissions", "project not readable"));
+        return Ok(checks);
+    }
+    checks.push(Check::pass(
+        "API",
+        format!("project {} readable", project_id),
+    ));
+
+    let tested = gcp::post(
+        "cloudresourcemanager.projects.testIamPermissions",
+        &format!("{}:testIamPermissions", project_url),
+        &json!({ "permissions": REQUIRED_GCP_PERMISSIONS }),
+    )
+    .await;
+    checks.push(match tested {
+        Ok(response) => {
+            let granted: Vec<&str> = response["permissions"]
+                .as_array()
+                .map(|p| p.iter().filter_map(|p| p.as_str()).collect())
+                .unwrap_or_default();
+            let missing: Vec<&str> = REQUIRED_GCP_PERMISSIONS
+                .iter()
+                .copied()
+                .filter(|p| !granted.contains(p))
+                .collect();
+            missing_permissions_check(
+                &missing,
+                REQUIRED_GCP_PERMISSIONS.len(),
+                format!(
+                    "Grant the account a role with {} on project {}, e.g. roles/viewer",
+                    missing.join(", "),
+                    project_id
+                ),
+            )
+        }
+        Err(e) => Check::from_error("Permissions", &e, profile, &reauth),
+    });
+
+    Ok(checks)
+}
+
+async fn check_azure(profile: &str, offline: bool) -> Result<Vec<Check>, AppError> {
+    let mut checks = Vec::new();
+    let reauth = format!(
+        "Run `actlog authenticate azure --profile {} --force`",
+        profile
+    );
+
+    let service_principal = ["AZURE_TENANT_ID", "AZURE_CLIENT_ID", "AZURE_CLIENT_SECRET"]
+        .iter()
+        .map(|var| std::env::var(var).ok())
+        .collect::<Option<Vec<String>>>();
+    checks.push(if let Some(sp) = &service_principal {
+        Check::pass("Environment", format!("service principal {}", sp[1]))
+    } else if env_set("AZURE_ACCESS_TOKEN") {
+        Check::pass("Environment", "AZURE_ACCESS_TOKEN set")
+    } else {
+        Check::warn(
+            "Environment",
+            "no service principal in the environment; using the az login",
+            "Set AZURE_TENANT_ID, AZURE_CLIENT_ID and AZURE_CLIENT_SECRET for unattended runs",
+        )
+    });
+
+    let stored = stored_credentials(&CloudProvider::Azure, profile)?;
+    let config = stored_config(&CloudProvider::Azure, profile)?;
+    let subscription_id = stored
+        .as_ref()
+        .and_then(|c| c.subscription_id.clone())
+        .or(config.as_ref().and_then(|c| c.subscription_id.clone()))
+        .or(azure::subscription_id().ok());
+    let mut config_check = config_check(
+        &CloudProvider::Azure,
+        profile,
+        stored.as_ref().map(|c| c.in_keyring),
+        config.is_some() || service_principal.is_some(),
+        reauth.clone(),
+    );
+    if subscription_id.is_none() && config_check.status != Status::Fail {
+        config_check = Check::fail(
+            "Config",
+            "no subscription",
+            format!(
+                "Set AZURE_SUBSCRIPTION_ID or run `actlog config azure --profile {} --subscription-id <id>`",
+                profile
+            ),
+        );
+    }
+    checks.push(config_check);
+
+    checks.push(match (&service_principal, offline) {
+        (Some(sp), false) => match azure::service_principal_sign_in(&sp[0], &sp[1], &sp[2]).await {
+            Ok(token) => Check::pass(
+                "Token",
+                format!("signed in, valid {}", until(token.expires_at)),
+            ),
+            Err(e) => Check::from_error(
+                "Token",
+                &e,
+                profile,
+                "Check AZURE_CLIENT_SECRET; client secrets expire and must be rotated in Entra ID",
+            ),
+        },
+        (Some(_), true) => Check::skip("Token", "--offline"),
+        _ => Check::skip("Token", "from az"),
+    });
+
+    let Some(subscription_id) = subscription_id.filter(|_| !offline) else {
+        let reason = if offline {
+            "--offline"
+        } else {
+            "no subscription"
+        };
+        checks.push(Check::skip("API", reason));
+        checks.push(Check::skip("Permissions", reason));
+        return Ok(checks);
+    };
+
+    let subscription_url = format!(
+        "{}/subscriptions/{}",
+        azure::MANAGEMENT_API,
+        subscription_id
+    );
+    let subscription = azure::get(
+        "Microsoft.Resources/subscriptions/read",
+        &format!(
+            "{}?api-version={}",
+            subscription_url,
+            azure::SUBSCRIPTIONS_API_VERSION
+        ),
+    )
+    .await;
+    match subscription {
+        Ok(subscription) => checks.push(Check::pass(
+            "API",
+            format!(
+                "subscription {} readable",
+                subscription["displayName"]
+                    .as_str()
+                    .unwrap_or(subscription_id.as_str())
+            ),
+        )),
+        Err(e) => {
+            checks.push(Check::from_error("API", &e, profile, &reauth));
+            checks.push(Check::skip("Permissions", "subscription not readable"));
+            return Ok(checks);
+        }
+    }
+
+    let permissions = azure::list(
+        "Microsoft.Authorization/permissions/read",
+        &format!(
+            "{}/providers/Microsoft.Authorization/permissions?api-version={}",
+            subscription_url, AUTHORIZATION_API_VERSION
+        ),
+    )
+    .await;
+    checks.push(match permissions {
+        Ok(permissions) => {
+            let missing: Vec<&str> = REQUIRED_AZURE_ACTIONS
+                .iter()
+                .copied()
+                .filter(|action| !azure_allows(&permissions, action))
+                .collect();
+            missing_permissions_check(
+                &missing,
+                REQUIRED_AZURE_ACTIONS.len(),
+                format!(
+                    "Assign a role allowing {} on subscription {}, e.g. Reader",
+                    missing.join(", "),
+                    subscription_id
+                ),
+            )
+        }
+        Err(e) => Check::from_error("Permissions", &e, profile, &reauth),
+    });
+
+    Ok(checks)
+}
+
+/// Whether any of the caller's role assignments allows `action`: one of its
+/// `actions` matches and none of its `notActions` do.
+fn azure_allows(permissions: &[serde_json::Value], action: &str) -> bool {
+    let matches_any = |patterns: &serde_json::Value| {
+        patterns.as_array().is_some_and(|p| {
+            p.iter()
+                .filter_map(|p| p.as_str())
+                .any(|p| wildcard_match(p, action))
+        })
+    };
+    permissions
+        .iter()
+        .any(|p| matches_any(&p["actions"]) && !matches_any(&p["notActions"]))
+}
+
+/// Case-insensitive match of an Azure action against a pattern such as
+/// `*/read` or `Microsoft.Compute/*`.
+fn wildcard_match(pattern: &str, action: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let action = action.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if parts.len() == 1 {
+        return pattern == action;
+    }
+    let Some(mut rest) = action.strip_prefix(first) else {
+        return false;
+    };
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+fn missing_permissions_check(missing: &[&str], required: usize, hint: String) -> Check {
+    if missing.is_empty() {
+        Check::pass("Permissions", format!("all {} required", required))
+    } else {
+        Check::fail(
+            "Permissions",
+            format!("missing {}", missing.join(", ")),
+            hint,
+        )
+    }
+}
+
+/// Whether actlog has anything saved for the profile, and the keyring still
+/// holds secrets that credentials.json says are there.
+fn config_check(
+    provider: &CloudProvider,
+    profile: &str,
+    in_keyring: Option<bool>,
+    configured: bool,
+    hint: String,
+) -> Check {
+    let key = format!("{}_{}", provider, profile);
+    match in_keyring {
+        Some(true) if !matches!(secrets::load(&key), Ok(Some(_))) => Check::fail(
+            "Config",
+            "credentials.json points at the OS keyring, which has no secrets for it",
+            format!(
+                "Run `actlog authenticate {} --profile {} --force`",
+                provider.to_string().to_lowercase(),
+                profile
+            ),
+        ),
+        Some(true) => Check::pass("Config", "authenticated; secrets in the OS keyring"),
+        Some(false) => Check::pass("Config", "authenticated"),
+        None if configured => Check::pass("Config", "configured"),
+        None => Check::fail("Config", "nothing saved for this profile", hint),
+    }
+}
+
+fn expiry(expires_at: Option<&str>) -> Option<DateTime<Utc>> {
+    expires_at
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| at.with_timezone(&Utc))
+}
+
+fn until(expires: DateTime<Utc>) -> String {
+    let minutes = (expires - Utc::now()).num_minutes();
+    if minutes >= 120 {
+        format!("for {}h", minutes / 60)
+    } else {
+        format!("for {}m", minutes)
+    }
+}
+
+fn env_set(var: &str) -> bool {
+    std::env::var_os(var).is_some_and(|v| !v.is_empty())
+}
+
+/// Profile names in the shared AWS config and credentials files.
+fn shared_aws_profiles() -> BTreeSet<String> {
+    let home = dirs::home_dir().unwrap_or_default().join(".aws");
+    let path = |var: &str, file: &str| {
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(file))
+    };
+
+    let mut profiles = BTreeSet::new();
+    for (file, in_config) in [
+        (path("AWS_CONFIG_FILE", "config"), true),
+        (path("AWS_SHARED_CREDENTIALS_FILE", "credentials"), false),
+    ] {
+        let Ok(content) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        for line in content.lines() {
+            let Some(section) = line
+                .trim()
+                .strip_prefix('[')
+                .and_then(|l| l.strip_suffix(']'))
+            else {
+                continue;
+            };
+            // The config file prefixes every profile but `default`
+            let name = match section.trim().strip_prefix("profile ") {
+                Some(name) => name.trim(),
+                None if in_config && section.trim() != "default" => continue,
+                None => section.trim(),
+            };
+            profiles.insert(name.to_string());
+        }
+    }
+    profiles
+}
+
+fn output_reports(reports: &[ProfileReport], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            if reports.is_empty() {
+                println!("ℹ️  No configured profiles found. Run `actlog authenticate` first.");
+                return Ok(());
+            }
+
+            print!("\n{:<8} {:<20}", "Provider", "Profile");
+            for check in CHECKS {
+                print!(" {:<12}", check);
+            }
+            println!();
+            println!("{:-<1$}", "", 29 + CHECKS.len() * 13);
+
+            for report in reports {
+                print!("{:<8} {:<20}", report.provider, report.profile);
+                for check in CHECKS {
+                    let mark = match report.status(check) {
+                        Status::Pass => "✓ pass".green(),
+                        Status::Warn => "! warn".yellow(),
+                        Status::Fail => "✗ fail".red(),
+                        Status::Skip => "- skip".dimmed(),
+                    };
+                    print!(" {:<12}", mark);
+                }
+                println!();
+            }
+
+            let problems: Vec<(&ProfileReport, &Check)> = reports
+                .iter()
+                .flat_map(|r| r.checks.iter().map(move |c| (r, c)))
+                .filter(|(_, c)| c.hint.is_some())
+                .collect();
+            if !problems.is_empty() {
+                println!("\n💡 Remediation:");
+                for (report, check) in problems {
+                    println!(
+                        "   {} {} {}: {}",
+                        report.provider,
+                        report.profile.bold(),
+                        check.name,
+                        check.detail
+                    );
+                    if let Some(hint) = &check.hint {
+                        println!("      → {}", hint.cyan());
+                    }
+                }
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(reports)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, reports)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Provider,Profile,{}", CHECKS.join(","));
+            for report in reports {
+                let statuses: Vec<String> = CHECKS
+                    .iter()
+                    .map(|check| {
+                        serde_json::to_value(report.status(check))
+                            .ok()
+                            .and_then(|v| v.as_str().map(str::to_string))
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                println!(
+                    "{},{},{}",
+                    report.provider,
+                    report.profile,
+                    statuses.join(",")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
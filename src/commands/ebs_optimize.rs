@@ -0,0 +1,350 @@
+use crate::batch::{self, BatchAction};
+use crate::cache;
+use crate::cli::{Commands, OutputFormat};
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::guard;
+use crate::regions;
+use crate::report;
+use aws_config::SdkConfig;
+use aws_sdk_ec2::types::VolumeType;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// EBS list prices (us-east-1)
+const GP2_PRICE_PER_GB: f64 = 0.10;
+const GP3_PRICE_PER_GB: f64 = 0.08;
+const GP3_PRICE_PER_IOPS: f64 = 0.005;
+const IO1_PRICE_PER_GB: f64 = 0.125;
+const IO1_PRICE_PER_IOPS: f64 = 0.065;
+
+/// IOPS included with every gp3 volume, and the most it can be provisioned with
+const GP3_BASELINE_IOPS: i32 = 3000;
+const GP3_MAX_IOPS: i32 = 16000;
+
+/// Headroom kept above observed peak IOPS when shrinking provisioned IOPS
+const IOPS_HEADROOM: f64 = 1.2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeOptimization {
+    pub volume_id: String,
+    pub region: String,
+    pub size_gb: i32,
+    pub current_type: String,
+    pub current_iops: Option<i32>,
+    pub peak_iops: Option<f64>,
+    pub target_iops: i32,
+    pub current_monthly_cost: f64,
+    pub target_monthly_cost: f64,
+    pub monthly_savings: f64,
+}
+
+impl BatchAction for VolumeOptimization {
+    fn action(&self) -> &str {
+        "modify_volume"
+    }
+
+    fn resource_id(&self) -> &str {
+        &self.volume_id
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{}: {} → gp3 ({} IOPS)",
+            self.volume_id, self.current_type, self.target_iops
+        )
+    }
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn optimize_ebs(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::EbsOptimize {
+        apply,
+        force,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!("💽 Scanning EBS volumes for gp3 and IOPS savings...");
+
+        let config = clients::aws_config().await;
+        let optimizations = analyze_aws_volumes(&config).await?;
+
+        if optimizations.is_empty() {
+            println!("✅ No gp2 or over-provisioned io1/io2 volumes found.");
+            return Ok(());
+        }
+
+        output_optimizations(&optimizations, format)?;
+
+        let total: f64 = optimizations.iter().map(|o| o.monthly_savings).sum();
+        println!(
+            "\n💰 Total estimated monthly savings: {}",
+            format!("${:.2}", total).green()
+        );
+
+        if !*apply {
+            tracing::info!("🔍 Re-run with --apply to modify these volumes (no downtime required)");
+            return Ok(());
+        }
+        guard::ensure_writable("ebs-optimize")?;
+
+        if !*force {
+            print!("\nModify {} volumes to gp3? (y/N): ", optimizations.len());
+            use std::io::{self, Write};
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+
+            if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
+                println!("❌ Modification cancelled by user.");
+                return Err(AppError::UserCancelled);
+            }
+        }
+
+        tracing::info!("🔧 Modifying volumes...");
+        let ec2_client = clients::get::<aws_sdk_ec2::Client>(&config).await;
+        let outcome = batch::run("ebs-optimize", "AWS", &optimizations, |optimization| {
+            let ec2_client = ec2_client.clone();
+            async move {
+                executor::throttle("ec2").await;
+                ec2_client
+                    .modify_volume()
+                    .volume_id(&optimization.volume_id)
+                    .volume_type(VolumeType::Gp3)
+                    .iops(optimization.target_iops)
+                    .send()
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| {
+                        AppError::aws("ec2:ModifyVolume", e)
+                            .for_resource(&optimization.volume_id)
+                            .in_region(&optimization.region)
+                    })
+            }
+        })
+        .await;
+        outcome.render(format)?;
+        outcome.into_result()?;
+
+        println!("✅ Volume modifications submitted. They complete in the background.");
+    }
+
+    Ok(())
+}
+
+/// gp2 volumes and io1/io2 volumes whose provisioned IOPS exceed what they
+/// use, each with the cheapest gp3 configuration that keeps performance.
+pub(crate) async fn analyze_aws_volumes(
+    config: &SdkConfig,
+) -> Result<Vec<VolumeOptimization>, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    let cloudwatch_client = clients::get::<aws_sdk_cloudwatch::Client>(config).await;
+    let region = regions::config_region(config);
+
+    executor::throttle("ec2").await;
+    let response = ec2_client
+        .describe_volumes()
+        .filters(
+            aws_sdk_ec2::types::Filter::builder()
+                .name("volume-type")
+                .values("gp2")
+                .values("io1")
+                .values("io2")
+                .build(),
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeVolumes", e))?;
+
+    let mut optimizations = Vec::new();
+    for volume in response.volumes() {
+        let (Some(volume_id), Some(size), Some(volume_type)) =
+            (volume.volume_id(), volume.size(), volume.volume_type())
+        else {
+            continue;
+        };
+
+        let optimization = match volume_type {
+            VolumeType::Gp2 => {
+                // gp2 gets 3 IOPS per GB; match that so the change is performance-neutral
+                let target_iops = (size * 3).clamp(GP3_BASELINE_IOPS, GP3_MAX_IOPS);
+                VolumeOptimization {
+                    volume_id: volume_id.to_string(),
+                    region: region.clone(),
+                    size_gb: size,
+                    current_type: "gp2".to_string(),
+                    current_iops: volume.iops(),
+                    peak_iops: None,
+                    target_iops,
+                    current_monthly_cost: size as f64 * GP2_PRICE_PER_GB,
+                    target_monthly_cost: gp3_monthly_cost(size, target_iops),
+                    monthly_savings: 0.0,
+                }
+            }
+            VolumeType::Io1 | VolumeType::Io2 => {
+                let provisioned = volume.iops().unwrap_or(0);
+                let peak = peak_volume_iops(&cloudwatch_client, volume_id).await?;
+                let needed = (peak * IOPS_HEADROOM).ceil() as i32;
+                if needed > GP3_MAX_IOPS {
+                    // Workload genuinely needs io-class performance
+                    continue;
+                }
+                let target_iops = needed.max(GP3_BASELINE_IOPS);
+                VolumeOptimization {
+                    volume_id: volume_id.to_string(),
+                    region: region.clone(),
+                    size_gb: size,
+                    current_type: volume_type.as_str().to_string(),
+                    current_iops: Some(provisioned),
+                    peak_iops: Some(peak),
+                    target_iops,
+                    current_monthly_cost: size as f64 * IO1_PRICE_PER_GB
+                        + provisioned as f64 * IO1_PRICE_PER_IOPS,
+                    target_monthly_cost: gp3_monthly_cost(size, target_iops),
+                    monthly_savings: 0.0,
+                }
+            }
+            _ => continue,
+        };
+
+        let monthly_savings = optimization.current_monthly_cost - optimization.target_monthly_cost;
+        if monthly_savings > 0.0 {
+            optimizations.push(VolumeOptimization {
+                monthly_savings,
+                ..optimization
+            });
+        }
+    }
+
+    optimizations.sort_by(|a, b| b.monthly_savings.total_cmp(&a.monthly_savings));
+    Ok(optimizations)
+}
+
+fn gp3_monthly_cost(size_gb: i32, iops: i32) -> f64 {
+    size_gb as f64 * GP3_PRICE_PER_GB
+        + (iops - GP3_BASELINE_IOPS).max(0) as f64 * GP3_PRICE_PER_IOPS
+}
+
+/// Highest read+write IOPS seen over the last two weeks, from 5-minute sums.
+async fn peak_volume_iops(
+    client: &aws_sdk_cloudwatch::Client,
+    volume_id: &str,
+) -> Result<f64, AppError> {
+    let key = format!("aws:ebs-peak-iops:{}", volume_id);
+    cache::cached_call(&key, || fetch_peak_volume_iops(client, volume_id)).await
+}
+
+async fn fetch_peak_volume_iops(
+    client: &aws_sdk_cloudwatch::Client,
+    volume_id: &str,
+) -> Result<f64, AppError> {
+    let now = chrono::Utc::now();
+    let mut peak_ops_by_period: HashMap<i64, f64> = HashMap::new();
+
+    for metric_name in ["VolumeReadOps", "VolumeWriteOps"] {
+        executor::throttle("cloudwatch").await;
+        let response = client
+            .get_metric_statistics()
+            .namespace("AWS/EBS")
+            .metric_name(metric_name)
+            .dimensions(
+                aws_sdk_cloudwatch::types::Dimension::builder()
+                    .name("VolumeId")
+                    .value(volume_id)
+                    .build(),
+            )
+            .start_time(aws_sdk_cloudwatch::primitives::DateTime::from_secs(
+                (now - chrono::Duration::days(14)).timestamp(),
+            ))
+            .end_time(aws_sdk_cloudwatch::primitives::DateTime::from_secs(
+                now.timestamp(),
+            ))
+            .period(300)
+            .statistics(aws_sdk_cloudwatch::types::Statistic::Sum)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("cloudwatch:GetMetricStatistics", e))?;
+
+        for datapoint in response.datapoints() {
+            if let (Some(timestamp), Some(sum)) = (datapoint.timestamp(), datapoint.sum()) {
+                *peak_ops_by_period.entry(timestamp.secs()).or_insert(0.0) += sum;
+            }
+        }
+    }
+
+    Ok(peak_ops_by_period.values().cloned().fold(0.0, f64::max) / 300.0)
+}
+
+fn output_optimizations(
+    optimizations: &[VolumeOptimization],
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n{:<24} {:<8} {:<6} {:<10} {:<10} {:<10} {:<12} {:<12}",
+                "Volume",
+                "Type",
+                "GiB",
+                "IOPS",
+                "Peak IOPS",
+                "gp3 IOPS",
+                "Now ($/mo)",
+                "Saves ($/mo)"
+            );
+            println!("{:-<100}", "");
+
+            for o in optimizations {
+                println!(
+                    "{:<24} {:<8} {:<6} {:<10} {:<10} {:<10} {:<12.2} {:<12.2}",
+                    o.volume_id,
+                    o.current_type,
+                    o.size_gb,
+                    o.current_iops
+                        .map(|i| i.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    o.peak_iops
+                        .map(|p| format!("{:.0}", p))
+                        .unwrap_or_else(|| "-".to_string()),
+                    o.target_iops,
+                    o.current_monthly_cost,
+                    o.monthly_savings
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(optimizations)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, optimizations)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("VolumeId,Region,SizeGB,CurrentType,CurrentIops,PeakIops,TargetIops,CurrentMonthlyCost,TargetMonthlyCost,MonthlySavings");
+            for o in optimizations {
+                println!(
+                    "{},{},{},{},{},{},{},{:.2},{:.2},{:.2}",
+                    o.volume_id,
+                    o.region,
+                    o.size_gb,
+                    o.current_type,
+                    o.current_iops.map(|i| i.to_string()).unwrap_or_default(),
+                    o.peak_iops.map(|p| format!("{:.0}", p)).unwrap_or_default(),
+                    o.target_iops,
+                    o.current_monthly_cost,
+                    o.target_monthly_cost,
+                    o.monthly_savings
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
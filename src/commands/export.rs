@@ -0,0 +1,462 @@
+use crate::cli::{CloudProvider, Commands, ExportDataset, ExportFormat};
+use crate::commands::cost_report::determine_date_range;
+use crate::commands::list::collect_inventory;
+use crate::error::AppError;
+use crate::providers;
+use arrow::array::{ArrayRef, Float64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use colored::*;
+use parquet::arrow::ArrowWriter;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// One resource per row. Column order and names are the export contract;
+/// append new columns at the end rather than reordering.
+#[derive(Debug, Serialize)]
+struct InventoryRow {
+    snapshot_at: String,
+    provider: String,
+    account: Option<String>,
+    resource_type: String,
+    resource_id: String,
+    name: String,
+    region: String,
+    state: String,
+    created_at: Option<String>,
+    tags: String,
+}
+
+/// One service's cost over the exported period.
+#[derive(Debug, Serialize)]
+struct CostRow {
+    snapshot_at: String,
+    provider: String,
+    account: Option<String>,
+    service: String,
+    region: Option<String>,
+    start_date: String,
+    end_date: String,
+    cost: f64,
+    currency: String,
+}
+
+/// One service's cost in the FinOps FOCUS 1.0 layout. actlog sees costs
+/// summed per service over a period, so the charge and billing periods are
+/// that period and every cost column carries the same amount.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct FocusRow {
+    billed_cost: f64,
+    effective_cost: f64,
+    list_cost: f64,
+    contracted_cost: f64,
+    billing_account_id: Option<String>,
+    billing_currency: String,
+    billing_period_start: String,
+    billing_period_end: String,
+    charge_category: String,
+    charge_description: String,
+    charge_period_start: String,
+    charge_period_end: String,
+    invoice_issuer_name: String,
+    provider_name: String,
+    publisher_name: String,
+    region_id: Option<String>,
+    region_name: Option<String>,
+    service_category: String,
+    service_name: String,
+    sub_account_id: Option<String>,
+}
+
+/// FOCUS service categories, matched in order against the service name.
+const FOCUS_SERVICE_CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "Databases",
+        &[
+            "rds",
+            "dynamodb",
+            "database",
+            "sql",
+            "redshift",
+            "spanner",
+            "cosmos",
+            "elasticache",
+        ],
+    ),
+    (
+        "Storage",
+        &["s3", "storage", "ebs", "glacier", "backup", "efs", "disk"],
+    ),
+    (
+        "Networking",
+        &[
+            "cloudfront",
+            "cdn",
+            "vpc",
+            "network",
+            "load balanc",
+            "route 53",
+            "dns",
+            "nat gateway",
+            "data transfer",
+        ],
+    ),
+    (
+        "Compute",
+        &[
+            "ec2",
+            "compute",
+            "lambda",
+            "functions",
+            "virtual machine",
+            "kubernetes",
+            "container",
+            "fargate",
+        ],
+    ),
+    (
+        "Analytics",
+        &[
+            "athena", "bigquery", "glue", "kinesis", "dataflow", "synapse", "emr",
+        ],
+    ),
+    (
+        "Management and Governance",
+        &[
+            "cloudwatch",
+            "cloudtrail",
+            "monitor",
+            "logging",
+            "config",
+            "cost explorer",
+        ],
+    ),
+    (
+        "Security",
+        &["kms", "key vault", "guardduty", "security", "waf"],
+    ),
+];
+
+#[allow(unused_variables, dead_code)]
+pub async fn export_data(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Export {
+        provider,
+        what,
+        format,
+        out,
+        start_date,
+        end_date,
+        profile,
+    } = cmd
+    {
+        tracing::info!("📦 Exporting {} data...", provider.to_string().green());
+
+        let out_dir = Path::new(out);
+        fs::create_dir_all(out_dir)?;
+        let snapshot_at = chrono::Utc::now();
+        let extension = match format {
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Csv => "csv",
+        };
+
+        let (path, rows) = match what {
+            ExportDataset::Inventory => {
+                let rows = inventory_rows(provider, &snapshot_at.to_rfc3339()).await?;
+                let path = export_path(out_dir, "inventory", provider, &snapshot_at, extension);
+                match format {
+                    ExportFormat::Csv => write_csv(&path, &rows)?,
+                    ExportFormat::Parquet => write_parquet(&path, inventory_batch(&rows)?)?,
+                }
+                (path, rows.len())
+            }
+            ExportDataset::Costs => {
+                let rows =
+                    cost_rows(provider, start_date, end_date, &snapshot_at.to_rfc3339()).await?;
+                let path = export_path(out_dir, "costs", provider, &snapshot_at, extension);
+                match format {
+                    ExportFormat::Csv => write_csv(&path, &rows)?,
+                    ExportFormat::Parquet => write_parquet(&path, cost_batch(&rows)?)?,
+                }
+                (path, rows.len())
+            }
+            ExportDataset::Focus => {
+                let rows: Vec<FocusRow> =
+                    cost_rows(provider, start_date, end_date, &snapshot_at.to_rfc3339())
+                        .await?
+                        .into_iter()
+                        .map(focus_row)
+                        .collect();
+                let path = export_path(out_dir, "focus", provider, &snapshot_at, extension);
+                match format {
+                    ExportFormat::Csv => write_csv(&path, &rows)?,
+                    ExportFormat::Parquet => write_parquet(&path, focus_batch(&rows)?)?,
+                }
+                (path, rows.len())
+            }
+        };
+
+        println!(
+            "✅ Wrote {} rows to {}",
+            rows,
+            path.display().to_string().green()
+        );
+    }
+
+    Ok(())
+}
+
+/// `<out>/<dataset>_<provider>_<YYYYMMDD>.<ext>`, one file per daily load.
+fn export_path(
+    out_dir: &Path,
+    dataset: &str,
+    provider: &CloudProvider,
+    snapshot_at: &chrono::DateTime<chrono::Utc>,
+    extension: &str,
+) -> PathBuf {
+    out_dir.join(format!(
+        "{}_{}_{}.{}",
+        dataset,
+        provider.to_string().to_lowercase(),
+        snapshot_at.format("%Y%m%d"),
+        extension
+    ))
+}
+
+async fn inventory_rows(
+    provider: &CloudProvider,
+    snapshot_at: &str,
+) -> Result<Vec<InventoryRow>, AppError> {
+    collect_inventory(provider)
+        .await?
+        .into_iter()
+        .map(|resource| {
+            Ok(InventoryRow {
+                snapshot_at: snapshot_at.to_string(),
+                provider: provider.to_string(),
+                account: resource.account,
+                resource_type: resource.resource_type,
+                resource_id: resource.id,
+                name: resource.name,
+                region: resource.region,
+                state: resource.state,
+                created_at: resource.creation_date.map(|d| d.to_rfc3339()),
+                tags: serde_json::to_string(&resource.tags)?,
+            })
+        })
+        .collect()
+}
+
+async fn cost_rows(
+    provider: &CloudProvider,
+    start_date: &Option<String>,
+    end_date: &Option<String>,
+    snapshot_at: &str,
+) -> Result<Vec<CostRow>, AppError> {
+    let (start, end) = determine_date_range(start_date, end_date)?;
+    let report = providers::get(provider)
+        .cost_report(&start, &end, &None)
+        .await?;
+
+    Ok(report
+        .services
+        .into_iter()
+        .map(|service| CostRow {
+            snapshot_at: snapshot_at.to_string(),
+            provider: provider.to_string(),
+            account: service.account,
+            service: service.service_name,
+            region: service.region,
+            start_date: report.start_date.clone(),
+            end_date: report.end_date.clone(),
+            cost: service.cost,
+            currency: report.currency.clone(),
+        })
+        .collect())
+}
+
+fn focus_row(row: CostRow) -> FocusRow {
+    let provider_name = match row.provider.as_str() {
+        "GCP" => "Google Cloud",
+        "Azure" => "Microsoft Azure",
+        _ => "AWS",
+    }
+    .to_string();
+    // FOCUS periods are datetimes with an exclusive end
+    let period_start = format!("{}T00:00:00Z", row.start_date);
+    let period_end = format!("{}T00:00:00Z", row.end_date);
+
+    FocusRow {
+        billed_cost: row.cost,
+        effective_cost: row.cost,
+        list_cost: row.cost,
+        contracted_cost: row.cost,
+        billing_account_id: row.account.clone(),
+        billing_currency: row.currency,
+        billing_period_start: period_start.clone(),
+        billing_period_end: period_end.clone(),
+        charge_category: "Usage".to_string(),
+        charge_description: row.service.clone(),
+        charge_period_start: period_start,
+        charge_period_end: period_end,
+        invoice_issuer_name: provider_name.clone(),
+        publisher_name: provider_name.clone(),
+        provider_name,
+        region_id: row.region.clone(),
+        region_name: row.region,
+        service_category: focus_service_category(&row.service).to_string(),
+        service_name: row.service,
+        sub_account_id: row.account,
+    }
+}
+
+fn focus_service_category(service: &str) -> &'static str {
+    let service = service.to_lowercase();
+    FOCUS_SERVICE_CATEGORIES
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| service.contains(k)))
+        .map(|(category, _)| *category)
+        .unwrap_or("Other")
+}
+
+fn write_csv<T: Serialize>(path: &Path, rows: &[T]) -> Result<(), AppError> {
+    let mut writer = csv::Writer::from_path(path).map_err(std::io::Error::from)?;
+    for row in rows {
+        writer.serialize(row).map_err(std::io::Error::from)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_parquet(path: &Path, batch: RecordBatch) -> Result<(), AppError> {
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), None)
+        .map_err(|e| AppError::ExportError(e.to_string()))?;
+    writer
+        .write(&batch)
+        .map_err(|e| AppError::ExportError(e.to_string()))?;
+    writer
+        .close()
+        .map_err(|e| AppError::ExportError(e.to_string()))?;
+    Ok(())
+}
+
+fn inventory_batch(rows: &[InventoryRow]) -> Result<RecordBatch, AppError> {
+    let schema = Schema::new(vec![
+        Field::new("snapshot_at", DataType::Utf8, false),
+        Field::new("provider", DataType::Utf8, false),
+        Field::new("account", DataType::Utf8, true),
+        Field::new("resource_type", DataType::Utf8, false),
+        Field::new("resource_id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("region", DataType::Utf8, false),
+        Field::new("state", DataType::Utf8, false),
+        Field::new("created_at", DataType::Utf8, true),
+        Field::new("tags", DataType::Utf8, false),
+    ]);
+
+    let columns: Vec<ArrayRef> = vec![
+        string_column(rows.iter().map(|r| Some(r.snapshot_at.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.provider.as_str()))),
+        string_column(rows.iter().map(|r| r.account.as_deref())),
+        string_column(rows.iter().map(|r| Some(r.resource_type.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.resource_id.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.name.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.region.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.state.as_str()))),
+        string_column(rows.iter().map(|r| r.created_at.as_deref())),
+        string_column(rows.iter().map(|r| Some(r.tags.as_str()))),
+    ];
+
+    RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|e| AppError::ExportError(e.to_string()))
+}
+
+fn cost_batch(rows: &[CostRow]) -> Result<RecordBatch, AppError> {
+    let schema = Schema::new(vec![
+        Field::new("snapshot_at", DataType::Utf8, false),
+        Field::new("provider", DataType::Utf8, false),
+        Field::new("account", DataType::Utf8, true),
+        Field::new("service", DataType::Utf8, false),
+        Field::new("region", DataType::Utf8, true),
+        Field::new("start_date", DataType::Utf8, false),
+        Field::new("end_date", DataType::Utf8, false),
+        Field::new("cost", DataType::Float64, false),
+        Field::new("currency", DataType::Utf8, false),
+    ]);
+
+    let columns: Vec<ArrayRef> = vec![
+        string_column(rows.iter().map(|r| Some(r.snapshot_at.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.provider.as_str()))),
+        string_column(rows.iter().map(|r| r.account.as_deref())),
+        string_column(rows.iter().map(|r| Some(r.service.as_str()))),
+        string_column(rows.iter().map(|r| r.region.as_deref())),
+        string_column(rows.iter().map(|r| Some(r.start_date.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.end_date.as_str()))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|r| r.cost))),
+        string_column(rows.iter().map(|r| Some(r.currency.as_str()))),
+    ];
+
+    RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|e| AppError::ExportError(e.to_string()))
+}
+
+fn focus_batch(rows: &[FocusRow]) -> Result<RecordBatch, AppError> {
+    let schema = Schema::new(vec![
+        Field::new("BilledCost", DataType::Float64, false),
+        Field::new("EffectiveCost", DataType::Float64, false),
+        Field::new("ListCost", DataType::Float64, false),
+        Field::new("ContractedCost", DataType::Float64, false),
+        Field::new("BillingAccountId", DataType::Utf8, true),
+        Field::new("BillingCurrency", DataType::Utf8, false),
+        Field::new("BillingPeriodStart", DataType::Utf8, false),
+        Field::new("BillingPeriodEnd", DataType::Utf8, false),
+        Field::new("ChargeCategory", DataType::Utf8, false),
+        Field::new("ChargeDescription", DataType::Utf8, false),
+        Field::new("ChargePeriodStart", DataType::Utf8, false),
+        Field::new("ChargePeriodEnd", DataType::Utf8, false),
+        Field::new("InvoiceIssuerName", DataType::Utf8, false),
+        Field::new("ProviderName", DataType::Utf8, false),
+        Field::new("PublisherName", DataType::Utf8, false),
+        Field::new("RegionId", DataType::Utf8, true),
+        Field::new("RegionName", DataType::Utf8, true),
+        Field::new("ServiceCategory", DataType::Utf8, false),
+        Field::new("ServiceName", DataType::Utf8, false),
+        Field::new("SubAccountId", DataType::Utf8, true),
+    ]);
+
+    let costs = |cost: fn(&FocusRow) -> f64| -> ArrayRef {
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(cost)))
+    };
+    let columns: Vec<ArrayRef> = vec![
+        costs(|r| r.billed_cost),
+        costs(|r| r.effective_cost),
+        costs(|r| r.list_cost),
+        costs(|r| r.contracted_cost),
+        string_column(rows.iter().map(|r| r.billing_account_id.as_deref())),
+        string_column(rows.iter().map(|r| Some(r.billing_currency.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.billing_period_start.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.billing_period_end.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.charge_category.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.charge_description.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.charge_period_start.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.charge_period_end.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.invoice_issuer_name.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.provider_name.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.publisher_name.as_str()))),
+        string_column(rows.iter().map(|r| r.region_id.as_deref())),
+        string_column(rows.iter().map(|r| r.region_name.as_deref())),
+        string_column(rows.iter().map(|r| Some(r.service_category.as_str()))),
+        string_column(rows.iter().map(|r| Some(r.service_name.as_str()))),
+        string_column(rows.iter().map(|r| r.sub_account_id.as_deref())),
+    ];
+
+    RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|e| AppError::ExportError(e.to_string()))
+}
+
+fn string_column<'a>(values: impl Iterator<Item = Option<&'a str>>) -> ArrayRef {
+    Arc::new(values.collect::<StringArray>())
+}
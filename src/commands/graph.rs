@@ -0,0 +1,43 @@
+use crate::cli::{CloudProvider, Commands, GraphFormat};
+use crate::clients;
+use crate::error::AppError;
+use crate::graph;
+use colored::*;
+
+#[allow(unused_variables, dead_code)]
+pub async fn export_graph(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Graph {
+        provider,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!(
+            "🕸️  Building {} resource dependency graph...",
+            provider.to_string().green()
+        );
+
+        let graph = match provider {
+            CloudProvider::Aws => {
+                let config = clients::aws_config().await;
+                graph::build_aws_graph(&config).await?
+            }
+            CloudProvider::Gcp => graph::build_gcp_graph().await?,
+            CloudProvider::Azure => graph::build_azure_graph().await?,
+        };
+
+        eprintln!(
+            "   {} nodes, {} edges",
+            graph.nodes.len(),
+            graph.edges.len()
+        );
+
+        // Graph output goes to stdout on its own so it can be piped into `dot`
+        match format {
+            GraphFormat::Dot => print!("{}", graph.to_dot()),
+            GraphFormat::Mermaid => print!("{}", graph.to_mermaid()),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,328 @@
+use crate::cdn::{self, CdnEndpoint};
+use crate::cli::{CloudProvider, Commands, OutputFormat, ResourceType};
+use crate::clients;
+use crate::commands::cleanup::{
+    analyze_cleanup_actions, find_aws_resources, find_azure_resources, find_gcp_resources,
+    ResourceInfo, TagSelection, DEFAULT_LOOKBACK_DAYS,
+};
+use crate::creators;
+use crate::error::AppError;
+use crate::executor;
+use crate::owners;
+use crate::regions;
+use crate::report;
+use chrono::Utc;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Tag keys checked, in order, to find who owns an idle resource.
+const OWNER_TAG_KEYS: &[&str] = &["Owner", "owner", "Team", "team", "CreatedBy", "created-by"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdleResource {
+    pub rank: usize,
+    pub id: String,
+    pub name: String,
+    pub resource_type: String,
+    pub region: String,
+    pub owner: Option<String>,
+    /// Principal that created the resource, from the activity log
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    pub age_days: Option<i64>,
+    pub utilization: f64,
+    pub reason: String,
+    pub monthly_cost: f64,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn idle_report(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::IdleReport {
+        provider,
+        age_threshold,
+        utilization_threshold,
+        who_created,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!(
+            "🔎 Building idle resource report for {}...",
+            provider.to_string().green()
+        );
+
+        let mut resources = match provider {
+            CloudProvider::Aws => {
+                scan_aws_all_regions(*age_threshold, *utilization_threshold).await?
+            }
+            CloudProvider::Gcp => {
+                find_gcp_resources(&ResourceType::Ec2, *age_threshold, *utilization_threshold)
+                    .await?
+            }
+            CloudProvider::Azure => {
+                find_azure_resources(
+                    &ResourceType::Ec2,
+                    *age_threshold,
+                    *utilization_threshold,
+                    None,
+                )
+                .await?
+            }
+        };
+
+        // CDN endpoints serving no traffic are idle whatever their age
+        match idle_cdn_endpoints(provider).await {
+            Ok(endpoints) => resources.extend(endpoints),
+            Err(e) => println!("   ⚠️  Skipping CDN endpoints: {}", e),
+        }
+
+        // Reuse the cleanup analysis so the report matches what cleanup would act on
+        let actions =
+            analyze_cleanup_actions(&resources, provider, &TagSelection::default(), None)?;
+
+        let owners = owners::load_owners()?;
+        let project = match provider {
+            CloudProvider::Gcp => owners::current_project(),
+            _ => None,
+        };
+
+        let mut idle: Vec<IdleResource> = actions
+            .into_iter()
+            .map(|action| IdleResource {
+                rank: 0,
+                owner: resource_owner(&action.resource),
+                created_by: None,
+                team: owners.as_ref().map(|o| {
+                    o.team_name(
+                        &action.resource.tags,
+                        action.resource.account.as_deref(),
+                        project.as_deref(),
+                    )
+                }),
+                age_days: action
+                    .resource
+                    .creation_date
+                    .map(|d| (Utc::now() - d).num_days()),
+                id: action.resource.id,
+                name: action.resource.name,
+                resource_type: action.resource.resource_type,
+                region: action.resource.region,
+                utilization: action.resource.utilization,
+                reason: action.reason,
+                monthly_cost: action.estimated_savings,
+            })
+            .collect();
+
+        // Most expensive first, then oldest
+        idle.sort_by(|a, b| {
+            b.monthly_cost
+                .total_cmp(&a.monthly_cost)
+                .then(b.age_days.cmp(&a.age_days))
+        });
+        for (i, resource) in idle.iter_mut().enumerate() {
+            resource.rank = i + 1;
+        }
+
+        if idle.is_empty() {
+            println!("✅ No idle resources found.");
+            return Ok(());
+        }
+
+        // The creator is only a hint for resources nobody tagged
+        if *who_created {
+            let unowned: Vec<(String, String)> = idle
+                .iter()
+                .filter(|r| r.owner.is_none())
+                .map(|r| (r.id.clone(), r.region.clone()))
+                .collect();
+            tracing::info!(
+                "🕵️  Looking up creators of {} untagged resources...",
+                unowned.len()
+            );
+            let creators = creators::find_creators(provider, &unowned).await;
+            for resource in &mut idle {
+                resource.created_by = creators.get(&resource.id).map(|c| c.principal.clone());
+            }
+        }
+
+        output_idle_report(&idle, format)?;
+
+        let total: f64 = idle.iter().map(|r| r.monthly_cost).sum();
+        let unowned = idle.iter().filter(|r| r.owner.is_none()).count();
+        println!("\n📊 {} idle resources", idle.len());
+        println!("   Potential monthly savings: ${:.2}", total);
+        if unowned > 0 {
+            println!(
+                "   {} resources have no owner tag",
+                unowned.to_string().yellow()
+            );
+        }
+
+        if let Some(owners) = &owners {
+            let mut totals: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+            let mut lines: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for resource in &idle {
+                let team = resource.team.clone().unwrap_or_default();
+                let entry = totals.entry(team.clone()).or_insert((0, 0.0));
+                entry.0 += 1;
+                entry.1 += resource.monthly_cost;
+                lines.entry(team).or_default().push(format!(
+                    "• {} ({}, {}) ${:.2}/mo - {}",
+                    resource.id,
+                    resource.resource_type,
+                    resource.region,
+                    resource.monthly_cost,
+                    resource.reason
+                ));
+            }
+            owners::print_team_totals("Idle cost by team", &totals);
+            owners
+                .notify_teams("Idle resources to review", "idle-report", &lines)
+                .await;
+        }
+
+        println!("ℹ️  Report only - nothing was deleted. Use `actlog cleanup` to act on it.");
+    }
+
+    Ok(())
+}
+
+async fn scan_aws_all_regions(
+    age_threshold: u32,
+    utilization_threshold: f64,
+) -> Result<Vec<ResourceInfo>, AppError> {
+    let base = clients::aws_config().await;
+
+    let mut resources = Vec::new();
+
+    // S3 buckets are global, so scan them once
+    resources.extend(
+        find_aws_resources(
+            &base,
+            &ResourceType::S3,
+            age_threshold,
+            utilization_threshold,
+            DEFAULT_LOOKBACK_DAYS,
+        )
+        .await?,
+    );
+
+    let all_regions = regions::aws_regions(&base).await?;
+    let results = executor::for_each_region(&base, &all_regions, |_, config| async move {
+        find_aws_resources(
+            &config,
+            &ResourceType::Ec2,
+            age_threshold,
+            utilization_threshold,
+            DEFAULT_LOOKBACK_DAYS,
+        )
+        .await
+    })
+    .await;
+
+    for (_, found) in results {
+        resources.extend(found);
+    }
+
+    Ok(resources)
+}
+
+async fn idle_cdn_endpoints(provider: &CloudProvider) -> Result<Vec<ResourceInfo>, AppError> {
+    let endpoints = match provider {
+        CloudProvider::Aws => cdn::cloudfront_distributions(&clients::aws_config().await).await?,
+        CloudProvider::Gcp => cdn::gcp_cdn_backends().await?,
+        CloudProvider::Azure => cdn::azure_cdn_endpoints().await?,
+    };
+
+    Ok(endpoints
+        .into_iter()
+        .filter(CdnEndpoint::is_idle)
+        .map(|endpoint| ResourceInfo {
+            id: endpoint.id,
+            name: endpoint.name,
+            resource_type: endpoint.resource_type,
+            region: endpoint.region,
+            state: endpoint.state,
+            creation_date: None,
+            last_used: None,
+            utilization: 0.0,
+            estimated_cost: 0.0,
+            tags: Default::default(),
+            account: None,
+        })
+        .collect())
+}
+
+fn resource_owner(resource: &ResourceInfo) -> Option<String> {
+    OWNER_TAG_KEYS
+        .iter()
+        .find_map(|key| resource.tags.get(*key).cloned())
+}
+
+fn output_idle_report(idle: &[IdleResource], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!("\n📋 Idle resources (ranked by monthly cost):");
+            println!(
+                "{:<5} {:<15} {:<20} {:<15} {:<15} {:<25} {:<12}",
+                "Rank", "Type", "Name", "Region", "Owner", "Reason", "Cost ($/mo)"
+            );
+            println!("{:-<111}", "");
+
+            for resource in idle {
+                // Creators from the activity log are marked as a likely owner
+                let owner = match (&resource.owner, &resource.created_by) {
+                    (Some(owner), _) => owner.normal(),
+                    (None, Some(creator)) => format!("{}?", creator).yellow(),
+                    (None, None) => "unowned".yellow(),
+                };
+                println!(
+                    "{:<5} {:<15} {:<20} {:<15} {:<15} {:<25} {:<12.2}",
+                    resource.rank,
+                    resource.resource_type,
+                    resource.name,
+                    resource.region,
+                    owner,
+                    resource.reason,
+                    resource.monthly_cost
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(idle)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, idle)?);
+        }
+
+        OutputFormat::Csv => {
+            println!(
+                "Rank,ID,Name,Type,Region,Owner,CreatedBy,AgeDays,Utilization,Reason,MonthlyCost"
+            );
+            for resource in idle {
+                println!(
+                    "{},{},{},{},{},{},{},{},{:.1},{},{:.2}",
+                    resource.rank,
+                    resource.id,
+                    resource.name,
+                    resource.resource_type,
+                    resource.region,
+                    resource.owner.as_deref().unwrap_or(""),
+                    resource.created_by.as_deref().unwrap_or(""),
+                    resource.age_days.map(|d| d.to_string()).unwrap_or_default(),
+                    resource.utilization,
+                    resource.reason,
+                    resource.monthly_cost
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,11 +1,37 @@
+use crate::accounts::{self, AccountTarget};
+use crate::azure;
+use crate::cache;
+use crate::cdn::{self, CdnEndpoint};
 use crate::cli::{CloudProvider, Commands, OutputFormat, ResourceType};
+use crate::clients;
+use crate::databases::{self, ManagedDatabase};
+use crate::dns::{self, DnsZone};
 use crate::error::AppError;
+use crate::executor;
+use crate::inventory;
+use crate::output::{out, outln};
+use crate::providers;
+use crate::query::TagFilter;
+use crate::regions;
+use crate::report;
+use crate::template;
+use aws_config::SdkConfig;
 use chrono::{DateTime, Utc};
 use colored::*;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Instances requested per DescribeInstances page
+const EC2_PAGE_SIZE: i32 = 1000;
+
+/// DB instances requested per DescribeDBInstances page, the API maximum
+const RDS_PAGE_SIZE: i32 = 100;
+
+/// Functions requested per ListFunctions page, the API maximum
+const LAMBDA_PAGE_SIZE: i32 = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceSummary {
     pub id: String,
     pub name: String,
@@ -15,180 +41,957 @@ pub struct ResourceSummary {
     pub creation_date: Option<DateTime<Utc>>,
     pub tags: HashMap<String, String>,
     pub additional_info: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
 }
 
 #[allow(unused_variables, dead_code)]
 pub async fn list_resources(cmd: &Commands) -> Result<(), AppError> {
     if let Commands::List {
-        provider,
+        provider: selection,
         resource_type,
         profile,
         format,
+        org,
+        org_role,
+        accounts_file,
+        template,
+        resource_group,
+        regions: selected_regions,
+        all_regions,
+        tags,
+        force,
     } = cmd
     {
-        println!("📋 Listing {} resources...", provider.to_string().green());
+        let tag_filters = TagFilter::parse_all(tags)?;
+        let providers = selection.providers();
+        if providers.len() > 1 {
+            if *org
+                || accounts_file.is_some()
+                || resource_group.is_some()
+                || *all_regions
+                || !selected_regions.is_empty()
+            {
+                return Err(AppError::InvalidParameters(
+                    "--org, --accounts, --region, --all-regions and --resource-group need a single provider".to_string(),
+                ));
+            }
+            return list_all_providers(
+                &providers,
+                resource_type,
+                format,
+                template.as_deref(),
+                tag_filters,
+            )
+            .await;
+        }
+        let provider = &providers[0];
 
-        // Get resources based on provider and type
+        tracing::info!("📋 Listing {} resources...", provider.to_string().green());
+
+        if (*org || accounts_file.is_some()) && !matches!(provider, CloudProvider::Aws) {
+            return Err(AppError::InvalidParameters(
+                "--org and --accounts are only supported for AWS".to_string(),
+            ));
+        }
+        if resource_group.is_some() && !matches!(provider, CloudProvider::Azure) {
+            return Err(AppError::InvalidParameters(
+                "--resource-group is only supported for Azure".to_string(),
+            ));
+        }
+        if (*all_regions || !selected_regions.is_empty()) && !matches!(provider, CloudProvider::Aws)
+        {
+            return Err(AppError::InvalidParameters(
+                "--region and --all-regions are only supported for AWS".to_string(),
+            ));
+        }
+
+        let scope = format!(
+            "{}:{}:{:?}:{}",
+            provider,
+            cache::provider_scope(provider).await,
+            resource_type,
+            if *org {
+                "org".to_string()
+            } else if *all_regions {
+                "all-regions".to_string()
+            } else if !selected_regions.is_empty() {
+                selected_regions.join(",")
+            } else {
+                accounts_file
+                    .clone()
+                    .or(resource_group.clone())
+                    .unwrap_or_default()
+            }
+        );
+
+        let mut output = ListingOutput::new(format, provider.to_string(), template.as_deref());
+        output.tag_filters = tag_filters;
+
+        if let Some(resources) = inventory::cached::<Vec<ResourceSummary>>("resources", &scope) {
+            output.page(&resources)?;
+            return output.finish();
+        }
+
+        let provider_name = provider.to_string();
         let resources = match provider {
-            CloudProvider::Aws => list_aws_resources(resource_type).await?,
-            CloudProvider::Gcp => list_gcp_resources(resource_type).await?,
-            CloudProvider::Azure => list_azure_resources(resource_type).await?,
+            CloudProvider::Aws => {
+                #[allow(deprecated)]
+                let config = clients::aws_config().await;
+                let target_regions = if *all_regions {
+                    regions::aws_regions(&config).await?
+                } else {
+                    selected_regions.clone()
+                };
+                match accounts::resolve_targets(&config, *org, org_role, accounts_file).await? {
+                    Some(targets) => Some(
+                        list_aws_multi_account_resources(&config, &targets, resource_type).await?,
+                    ),
+                    None if !target_regions.is_empty() && is_regional(resource_type) => Some(
+                        list_aws_multi_region_resources(&config, &target_regions, resource_type)
+                            .await?,
+                    ),
+                    None => {
+                        if !target_regions.is_empty() {
+                            eprintln!(
+                                "   ℹ️  {:?} resources are global; listing them once",
+                                resource_type
+                            );
+                        }
+                        // Emit and cache each page as it arrives so memory stays
+                        // flat however large the account is
+                        tracing::info!("🔍 Fetching AWS resources...");
+                        let mut snapshot =
+                            inventory::begin_resource_snapshot(&provider_name, &scope);
+                        let mut pages = aws_resource_pages(&config, resource_type);
+                        while let Some(page) = pages.next().await {
+                            let page = page?;
+                            if let Some(snapshot) = snapshot.as_mut() {
+                                snapshot.append(&page);
+                            }
+                            output.page(&page)?;
+                        }
+                        if let Some(snapshot) = snapshot {
+                            snapshot.finish();
+                        }
+                        None
+                    }
+                }
+            }
+            CloudProvider::Gcp => Some(providers::get(provider).list(resource_type).await?),
+            CloudProvider::Azure => {
+                Some(list_azure_resources(resource_type, resource_group.as_deref()).await?)
+            }
         };
 
-        if resources.is_empty() {
-            println!("ℹ️  No resources found for the specified criteria.");
-            return Ok(());
+        // Listings that arrive whole are cached and written in one go
+        if let Some(resources) = resources {
+            inventory::store_resources(&provider_name, &scope, &resources);
+            output.page(&resources)?;
         }
 
-        // Output resources in requested format
-        output_resource_list(&resources, format, provider)?;
-
-        // Show summary statistics
-        show_resource_summary(&resources, provider)?;
+        output.finish()?;
     }
 
     Ok(())
 }
 
-#[allow(unused_variables, dead_code, deprecated)]
-async fn list_aws_resources(
+/// `resource_type` from every provider at once, merged into one listing with
+/// a provider column. A provider that fails is reported and left out, and
+/// the listing ends with [`AppError::PartialResults`].
+async fn list_all_providers(
+    providers: &[CloudProvider],
+    resource_type: &ResourceType,
+    format: &OutputFormat,
+    template: Option<&str>,
+    tag_filters: Vec<TagFilter>,
+) -> Result<(), AppError> {
+    tracing::info!("📋 Listing resources from {} providers...", providers.len());
+
+    let listings = executor::for_each_provider(providers, |provider| {
+        let resource_type = resource_type.clone();
+        async move { providers::get(&provider).list(&resource_type).await }
+    })
+    .await;
+
+    let mut output = ListingOutput::new(format, "all providers".to_string(), template);
+    output.show_provider = true;
+    output.tag_filters = tag_filters;
+    let mut failed = Vec::new();
+    for (provider, listing) in listings {
+        match listing {
+            Ok(resources) => output.provider_page(&provider, &resources)?,
+            Err(e) => {
+                eprintln!("   ⚠️  Skipping {}: {}", provider.to_string().yellow(), e);
+                failed.push(provider.to_string());
+            }
+        }
+    }
+
+    // A provider failing matters more than the rest having nothing to list
+    let finished = output.finish();
+    if !failed.is_empty() {
+        return Err(AppError::PartialResults(format!(
+            "{} of {} providers could not be listed ({})",
+            failed.len(),
+            providers.len(),
+            failed.join(", ")
+        )));
+    }
+    finished
+}
+
+/// Whether AWS lists `resource_type` per region rather than account-wide.
+fn is_regional(resource_type: &ResourceType) -> bool {
+    matches!(
+        resource_type,
+        ResourceType::Ec2 | ResourceType::Rds | ResourceType::Lambda
+    )
+}
+
+/// `resource_type` in every region of `regions` at once. A region that fails
+/// is reported and left out rather than failing the listing.
+async fn list_aws_multi_region_resources(
+    config: &SdkConfig,
+    regions: &[String],
+    resource_type: &ResourceType,
+) -> Result<Vec<ResourceSummary>, AppError> {
+    tracing::info!("🌍 Listing {} regions...", regions.len());
+    let results = executor::for_each_region(config, regions, |_, regional_config| {
+        let resource_type = resource_type.clone();
+        async move { list_aws_resources(&regional_config, &resource_type).await }
+    })
+    .await;
+
+    Ok(results
+        .into_iter()
+        .flat_map(|(_, resources)| resources)
+        .collect())
+}
+
+async fn list_aws_multi_account_resources(
+    config: &SdkConfig,
+    targets: &[AccountTarget],
     resource_type: &ResourceType,
 ) -> Result<Vec<ResourceSummary>, AppError> {
-    println!("🔍 Fetching AWS resources...");
+    let results = accounts::fan_out(config, targets, |account_config| {
+        let resource_type = resource_type.clone();
+        async move { list_aws_resources(&account_config, &resource_type).await }
+    })
+    .await;
 
-    let config = aws_config::from_env().load().await;
     let mut resources = Vec::new();
+    for (target, account_resources) in results {
+        for mut resource in account_resources {
+            resource.account = Some(target.account_id.clone());
+            resources.push(resource);
+        }
+    }
+
+    Ok(resources)
+}
 
+/// [`fetch_aws_resources`] through the response cache.
+pub(crate) async fn list_aws_resources(
+    config: &SdkConfig,
+    resource_type: &ResourceType,
+) -> Result<Vec<ResourceSummary>, AppError> {
+    let key = format!(
+        "aws:list:{:?}:{}",
+        resource_type,
+        cache::aws_scope(config).await
+    );
+    cache::cached_call(&key, || fetch_aws_resources(config, resource_type)).await
+}
+
+#[allow(unused_variables, dead_code, deprecated)]
+pub(crate) async fn fetch_aws_resources(
+    config: &SdkConfig,
+    resource_type: &ResourceType,
+) -> Result<Vec<ResourceSummary>, AppError> {
+    tracing::info!("🔍 Fetching AWS resources...");
+    aws_resource_pages(config, resource_type).try_concat().await
+}
+
+/// AWS resources one API page at a time, so callers can filter and emit them
+/// without holding the whole account in memory.
+pub(crate) fn aws_resource_pages<'a>(
+    config: &'a SdkConfig,
+    resource_type: &'a ResourceType,
+) -> BoxStream<'a, Result<Vec<ResourceSummary>, AppError>> {
     match resource_type {
-        ResourceType::Ec2 => {
-            let ec2_client = aws_sdk_ec2::Client::new(&config);
-            let response = ec2_client
-                .describe_instances()
+        ResourceType::Ec2 => stream::once(clients::get::<aws_sdk_ec2::Client>(config))
+            .flat_map(move |client| {
+                let region = regions::config_region(config);
+                let pages = client
+                    .describe_instances()
+                    .max_results(EC2_PAGE_SIZE)
+                    .into_paginator()
+                    .send();
+
+                stream::unfold((pages, region), |(mut pages, region)| async move {
+                    executor::throttle("ec2").await;
+                    let page = pages.next().await?;
+                    let resources = page
+                        .map(|output| {
+                            output
+                                .reservations()
+                                .iter()
+                                .flat_map(|reservation| reservation.instances())
+                                .map(|instance| ec2_summary(instance, &region))
+                                .collect()
+                        })
+                        .map_err(|e| AppError::aws("ec2:DescribeInstances", e));
+                    Some((resources, (pages, region)))
+                })
+            })
+            .boxed(),
+
+        ResourceType::Rds => stream::once(clients::get::<aws_sdk_rds::Client>(config))
+            .flat_map(move |client| {
+                let region = regions::config_region(config);
+                let pages = client
+                    .describe_db_instances()
+                    .max_records(RDS_PAGE_SIZE)
+                    .into_paginator()
+                    .send();
+
+                stream::unfold((pages, region), |(mut pages, region)| async move {
+                    executor::throttle("rds").await;
+                    let page = pages.next().await?;
+                    let resources = page
+                        .map(|output| {
+                            output
+                                .db_instances()
+                                .iter()
+                                .map(|db| rds_summary(db, &region))
+                                .collect()
+                        })
+                        .map_err(|e| AppError::aws("rds:DescribeDBInstances", e));
+                    Some((resources, (pages, region)))
+                })
+            })
+            .boxed(),
+
+        ResourceType::Lambda => stream::once(clients::get::<aws_sdk_lambda::Client>(config))
+            .flat_map(move |client| {
+                let region = regions::config_region(config);
+                let pages = client
+                    .list_functions()
+                    .max_items(LAMBDA_PAGE_SIZE)
+                    .into_paginator()
+                    .send();
+
+                stream::unfold(
+                    (pages, client, region),
+                    |(mut pages, client, region)| async move {
+                        executor::throttle("lambda").await;
+                        let page = pages.next().await?;
+                        let resources = match page {
+                            Ok(output) => {
+                                lambda_summaries(&client, output.functions(), &region).await
+                            }
+                            Err(e) => Err(AppError::aws("lambda:ListFunctions", e)),
+                        };
+                        Some((resources, (pages, client, region)))
+                    },
+                )
+            })
+            .boxed(),
+
+        // ListBuckets returns every bucket in one response
+        ResourceType::S3 => stream::once(async move {
+            executor::throttle("s3").await;
+            let response = clients::get::<aws_sdk_s3::Client>(config)
+                .await
+                .list_buckets()
                 .send()
                 .await
-                .map_err(|e| AppError::AwsError(e.to_string()))?;
-
-            if let Some(reservations) = response.reservations {
-                for reservation in reservations {
-                    if let Some(instances) = reservation.instances {
-                        for instance in instances {
-                            let mut additional_info = HashMap::new();
-
-                            if let Some(instance_type) = &instance.instance_type {
-                                additional_info.insert(
-                                    "Instance Type".to_string(),
-                                    instance_type.as_str().to_string(),
-                                );
-                            }
+                .map_err(|e| AppError::aws("s3:ListBuckets", e))?;
+            let mut resources = Vec::new();
+            for bucket in response.buckets() {
+                let mut summary = bucket_summary(bucket);
+                summary.tags = bucket_tags(config, &summary.id, &summary.region).await?;
+                resources.push(summary);
+            }
+            Ok::<_, AppError>(resources)
+        })
+        .boxed(),
 
-                            if let Some(public_ip) = &instance.public_ip_address {
-                                additional_info.insert("Public IP".to_string(), public_ip.clone());
-                            }
+        // Route 53 is global; zones come back with their records
+        ResourceType::Dns => {
+            stream::once(
+                async move { Ok::<_, AppError>(dns_summaries(&dns::aws_zones(config).await?)) },
+            )
+            .boxed()
+        }
 
-                            let state_name = instance
-                                .state
-                                .as_ref()
-                                .and_then(|s| s.name.as_ref())
-                                .map(|n| n.as_str())
-                                .unwrap_or("unknown")
-                                .to_string();
-
-                            let creation_date = instance
-                                .launch_time
-                                .map(|dt| {
-                                    chrono::DateTime::from_timestamp(dt.secs(), dt.subsec_nanos())
-                                })
-                                .flatten();
-
-                            let instance_id = instance.instance_id.clone().unwrap_or_default();
-
-                            resources.push(ResourceSummary {
-                                id: instance_id.clone(),
-                                name: instance_id,
-                                resource_type: "EC2 Instance".to_string(),
-                                region: "us-east-1".to_string(), // Would get from config
-                                state: state_name,
-                                creation_date,
-                                tags: HashMap::new(), // Would extract from tags
-                                additional_info,
-                            });
-                        }
-                    }
-                }
+        ResourceType::Cdn => stream::once(async move {
+            let distributions = cdn::cloudfront_distributions(config).await?;
+            Ok::<_, AppError>(distributions.iter().map(cdn_summary).collect())
+        })
+        .boxed(),
+
+        // IAM is global, so users, roles and keys arrive as one listing
+        ResourceType::Iam => stream::once(iam_resources(config)).boxed(),
+
+        other => stream::once(async move { Ok(unpaged_aws_resources(other)) }).boxed(),
+    }
+}
+
+fn ec2_summary(instance: &aws_sdk_ec2::types::Instance, region: &str) -> ResourceSummary {
+    let mut additional_info = HashMap::new();
+
+    if let Some(instance_type) = instance.instance_type() {
+        additional_info.insert(
+            "Instance Type".to_string(),
+            instance_type.as_str().to_string(),
+        );
+    }
+
+    if let Some(public_ip) = instance.public_ip_address() {
+        additional_info.insert("Public IP".to_string(), public_ip.to_string());
+    }
+
+    let state_name = instance
+        .state()
+        .and_then(|s| s.name())
+        .map(|n| n.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let creation_date = instance
+        .launch_time()
+        .and_then(|dt| chrono::DateTime::from_timestamp(dt.secs(), dt.subsec_nanos()));
+
+    let instance_id = instance.instance_id().unwrap_or_default().to_string();
+    let tags: HashMap<String, String> = instance
+        .tags()
+        .iter()
+        .filter_map(|t| Some((t.key()?.to_string(), t.value()?.to_string())))
+        .collect();
+
+    ResourceSummary {
+        id: instance_id.clone(),
+        name: tags.get("Name").cloned().unwrap_or(instance_id),
+        resource_type: "EC2 Instance".to_string(),
+        region: region.to_string(),
+        state: state_name,
+        creation_date,
+        tags,
+        additional_info,
+        account: None,
+    }
+}
+
+fn rds_summary(db: &aws_sdk_rds::types::DbInstance, region: &str) -> ResourceSummary {
+    let mut additional_info = HashMap::new();
+
+    if let Some(engine) = db.engine() {
+        let engine = match db.engine_version() {
+            Some(version) => format!("{} {}", engine, version),
+            None => engine.to_string(),
+        };
+        additional_info.insert("Engine".to_string(), engine);
+    }
+
+    if let Some(class) = db.db_instance_class() {
+        additional_info.insert("Instance Class".to_string(), class.to_string());
+    }
+
+    if let Some(storage) = db.allocated_storage() {
+        let storage = match db.storage_type() {
+            Some(storage_type) => format!("{} GiB {}", storage, storage_type),
+            None => format!("{} GiB", storage),
+        };
+        additional_info.insert("Storage".to_string(), storage);
+    }
+
+    additional_info.insert(
+        "Multi-AZ".to_string(),
+        db.multi_az().unwrap_or(false).to_string(),
+    );
+
+    if let Some(endpoint) = db.endpoint() {
+        if let Some(address) = endpoint.address() {
+            let address = match endpoint.port() {
+                Some(port) => format!("{}:{}", address, port),
+                None => address.to_string(),
+            };
+            additional_info.insert("Endpoint".to_string(), address);
+        }
+    }
+
+    if let Some(zone) = db.availability_zone() {
+        additional_info.insert("Availability Zone".to_string(), zone.to_string());
+    }
+
+    let creation_date = db
+        .instance_create_time()
+        .and_then(|dt| chrono::DateTime::from_timestamp(dt.secs(), dt.subsec_nanos()));
+
+    let identifier = db.db_instance_identifier().unwrap_or_default().to_string();
+    let tags: HashMap<String, String> = db
+        .tag_list()
+        .iter()
+        .filter_map(|t| Some((t.key()?.to_string(), t.value()?.to_string())))
+        .collect();
+
+    ResourceSummary {
+        id: identifier.clone(),
+        name: identifier,
+        resource_type: "RDS Instance".to_string(),
+        region: region.to_string(),
+        state: db.db_instance_status().unwrap_or("unknown").to_string(),
+        creation_date,
+        tags,
+        additional_info,
+        account: None,
+    }
+}
+
+/// Summaries of one ListFunctions page. Tags aren't part of the function
+/// configuration, so each function costs a ListTags call.
+async fn lambda_summaries(
+    client: &aws_sdk_lambda::Client,
+    functions: &[aws_sdk_lambda::types::FunctionConfiguration],
+    region: &str,
+) -> Result<Vec<ResourceSummary>, AppError> {
+    let mut resources = Vec::with_capacity(functions.len());
+
+    for function in functions {
+        let name = function.function_name().unwrap_or_default().to_string();
+        let mut additional_info = HashMap::new();
+
+        if let Some(runtime) = function.runtime() {
+            additional_info.insert("Runtime".to_string(), runtime.as_str().to_string());
+        }
+
+        if let Some(memory) = function.memory_size() {
+            additional_info.insert("Memory".to_string(), format!("{} MB", memory));
+        }
+
+        if let Some(last_modified) = function.last_modified() {
+            additional_info.insert("Last Modified".to_string(), last_modified.to_string());
+        }
+
+        additional_info.insert(
+            "Code Size".to_string(),
+            format!("{:.1} MB", function.code_size() as f64 / 1_048_576.0),
+        );
+
+        let mut tags = HashMap::new();
+        if let Some(arn) = function.function_arn() {
+            executor::throttle("lambda").await;
+            let response = client
+                .list_tags()
+                .resource(arn)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("lambda:ListTags", e).for_resource(&name))?;
+            if let Some(function_tags) = response.tags() {
+                tags = function_tags.clone();
             }
         }
 
-        ResourceType::S3 => {
-            let s3_client = aws_sdk_s3::Client::new(&config);
-            let response = s3_client
-                .list_buckets()
+        resources.push(ResourceSummary {
+            id: name.clone(),
+            name,
+            resource_type: "Lambda Function".to_string(),
+            region: region.to_string(),
+            // Functions without a state predate Lambda function states and are active
+            state: function
+                .state()
+                .map(|s| s.as_str().to_lowercase())
+                .unwrap_or_else(|| "active".to_string()),
+            creation_date: None,
+            tags,
+            additional_info,
+            account: None,
+        });
+    }
+
+    Ok(resources)
+}
+
+fn bucket_summary(bucket: &aws_sdk_s3::types::Bucket) -> ResourceSummary {
+    let mut additional_info = HashMap::new();
+
+    let creation_date = bucket
+        .creation_date()
+        .and_then(|dt| chrono::DateTime::from_timestamp(dt.secs(), dt.subsec_nanos()));
+
+    if let Some(created) = creation_date {
+        additional_info.insert(
+            "Created".to_string(),
+            created.format("%Y-%m-%d").to_string(),
+        );
+    }
+
+    let bucket_name = bucket.name().unwrap_or_default().to_string();
+
+    ResourceSummary {
+        id: bucket_name.clone(),
+        name: bucket_name,
+        resource_type: "S3 Bucket".to_string(),
+        region: bucket.bucket_region().unwrap_or("us-east-1").to_string(),
+        state: "active".to_string(),
+        creation_date,
+        tags: HashMap::new(),
+        additional_info,
+        account: None,
+    }
+}
+
+/// Tags on `bucket`, asked of its own region. A bucket without a tag set
+/// answers NoSuchTagSet, which means no tags.
+pub(crate) async fn bucket_tags(
+    config: &SdkConfig,
+    bucket: &str,
+    region: &str,
+) -> Result<HashMap<String, String>, AppError> {
+    let regional_config = regions::regional_config(config, region);
+    executor::throttle("s3").await;
+    let response = clients::get::<aws_sdk_s3::Client>(&regional_config)
+        .await
+        .get_bucket_tagging()
+        .bucket(bucket)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("s3:GetBucketTagging", e).for_resource(bucket));
+
+    match response {
+        Ok(response) => Ok(response
+            .tag_set()
+            .iter()
+            .map(|tag| (tag.key().to_string(), tag.value().to_string()))
+            .collect()),
+        Err(e) if e.is_not_found() => Ok(HashMap::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// IAM users, their access keys and roles, with when each was last used and
+/// how many managed policies are attached.
+async fn iam_resources(config: &SdkConfig) -> Result<Vec<ResourceSummary>, AppError> {
+    let client = clients::get::<aws_sdk_iam::Client>(config).await;
+    let mut resources = Vec::new();
+
+    let mut users = client.list_users().into_paginator().send();
+    loop {
+        executor::throttle("iam").await;
+        let Some(page) = users.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("iam:ListUsers", e))?;
+        for user in page.users() {
+            let name = user.user_name();
+
+            // Managed policies per user are capped well below one page
+            executor::throttle("iam").await;
+            let attached = client
+                .list_attached_user_policies()
+                .user_name(name)
                 .send()
                 .await
-                .map_err(|e| AppError::AwsError(e.to_string()))?;
-
-            if let Some(buckets) = response.buckets {
-                for bucket in buckets {
-                    let mut additional_info = HashMap::new();
-
-                    if let Some(creation_date) = bucket.creation_date {
-                        let date_str = chrono::DateTime::from_timestamp(
-                            creation_date.secs(),
-                            creation_date.subsec_nanos(),
-                        )
-                        .map(|dt| dt.format("%Y-%m-%d").to_string())
-                        .unwrap_or_else(|| "Unknown".to_string());
-                        additional_info.insert("Created".to_string(), date_str);
-                    }
+                .map_err(|e| AppError::aws("iam:ListAttachedUserPolicies", e).for_resource(name))?
+                .attached_policies()
+                .len();
 
-                    let creation_date = bucket
-                        .creation_date
-                        .map(|dt| chrono::DateTime::from_timestamp(dt.secs(), dt.subsec_nanos()))
-                        .flatten();
-
-                    let bucket_name = bucket.name.clone().unwrap_or_default();
-
-                    resources.push(ResourceSummary {
-                        id: bucket_name.clone(),
-                        name: bucket_name,
-                        resource_type: "S3 Bucket".to_string(),
-                        region: "us-east-1".to_string(),
-                        state: "active".to_string(),
-                        creation_date,
-                        tags: HashMap::new(),
-                        additional_info,
-                    });
-                }
-            }
+            let mut additional_info = HashMap::new();
+            additional_info.insert("Attached Policies".to_string(), attached.to_string());
+            additional_info.insert(
+                "Last Used".to_string(),
+                last_used(user.password_last_used()),
+            );
+            additional_info.insert("ARN".to_string(), user.arn().to_string());
+
+            resources.push(ResourceSummary {
+                id: user.user_id().to_string(),
+                name: name.to_string(),
+                resource_type: "IAM User".to_string(),
+                region: "global".to_string(),
+                state: "active".to_string(),
+                creation_date: aws_datetime(Some(user.create_date())),
+                tags: user
+                    .tags()
+                    .iter()
+                    .map(|t| (t.key().to_string(), t.value().to_string()))
+                    .collect(),
+                additional_info,
+                account: None,
+            });
+
+            resources.extend(iam_access_keys(&client, name).await?);
         }
+    }
 
-        ResourceType::Rds => {
-            // For RDS, we'd use the RDS client
-            println!("   RDS resources not yet implemented for AWS");
+    let mut roles = client.list_roles().into_paginator().send();
+    loop {
+        executor::throttle("iam").await;
+        let Some(page) = roles.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("iam:ListRoles", e))?;
+        for role in page.roles() {
+            let name = role.role_name();
+
+            // ListRoles leaves out RoleLastUsed; GetRole has it
+            executor::throttle("iam").await;
+            let detail = client
+                .get_role()
+                .role_name(name)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("iam:GetRole", e).for_resource(name))?;
+            let role_last_used = detail
+                .role()
+                .and_then(|r| r.role_last_used())
+                .and_then(|u| u.last_used_date());
+
+            executor::throttle("iam").await;
+            let attached = client
+                .list_attached_role_policies()
+                .role_name(name)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("iam:ListAttachedRolePolicies", e).for_resource(name))?
+                .attached_policies()
+                .len();
+
+            let mut additional_info = HashMap::new();
+            additional_info.insert("Attached Policies".to_string(), attached.to_string());
+            additional_info.insert("Last Used".to_string(), last_used(role_last_used));
+            additional_info.insert("ARN".to_string(), role.arn().to_string());
+
+            resources.push(ResourceSummary {
+                id: role.role_id().to_string(),
+                name: name.to_string(),
+                resource_type: "IAM Role".to_string(),
+                region: "global".to_string(),
+                state: "active".to_string(),
+                creation_date: aws_datetime(Some(role.create_date())),
+                tags: detail
+                    .role()
+                    .map(|r| r.tags())
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|t| (t.key().to_string(), t.value().to_string()))
+                    .collect(),
+                additional_info,
+                account: None,
+            });
         }
+    }
 
-        ResourceType::Lambda => {
-            // For Lambda, we'd use the Lambda client
-            println!("   Lambda resources not yet implemented for AWS");
+    Ok(resources)
+}
+
+async fn iam_access_keys(
+    client: &aws_sdk_iam::Client,
+    user_name: &str,
+) -> Result<Vec<ResourceSummary>, AppError> {
+    executor::throttle("iam").await;
+    let keys = client
+        .list_access_keys()
+        .user_name(user_name)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("iam:ListAccessKeys", e).for_resource(user_name))?;
+
+    let mut resources = Vec::new();
+    for key in keys.access_key_metadata() {
+        let key_id = key.access_key_id().unwrap_or_default();
+
+        executor::throttle("iam").await;
+        let key_last_used = client
+            .get_access_key_last_used()
+            .access_key_id(key_id)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("iam:GetAccessKeyLastUsed", e).for_resource(key_id))?;
+
+        let mut additional_info = HashMap::new();
+        additional_info.insert("User".to_string(), user_name.to_string());
+        additional_info.insert(
+            "Last Used".to_string(),
+            last_used(
+                key_last_used
+                    .access_key_last_used()
+                    .and_then(|u| u.last_used_date()),
+            ),
+        );
+
+        resources.push(ResourceSummary {
+            id: key_id.to_string(),
+            name: format!("{} key", user_name),
+            resource_type: "IAM Access Key".to_string(),
+            region: "global".to_string(),
+            state: key
+                .status()
+                .map(|s| s.as_str().to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            creation_date: aws_datetime(key.create_date()),
+            tags: HashMap::new(),
+            additional_info,
+            account: None,
+        });
+    }
+
+    Ok(resources)
+}
+
+pub(crate) fn aws_datetime(dt: Option<&aws_smithy_types::DateTime>) -> Option<DateTime<Utc>> {
+    dt.and_then(|dt| chrono::DateTime::from_timestamp(dt.secs(), dt.subsec_nanos()))
+}
+
+/// Last-used date for the listing; "never" makes unused identities stand out.
+fn last_used(dt: Option<&aws_smithy_types::DateTime>) -> String {
+    aws_datetime(dt)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "never".to_string())
+}
+
+/// CDN endpoints that served nothing in the window are marked idle in their state.
+fn cdn_summary(endpoint: &CdnEndpoint) -> ResourceSummary {
+    let mut additional_info = HashMap::new();
+    if !endpoint.domain.is_empty() {
+        additional_info.insert("Domain".to_string(), endpoint.domain.clone());
+    }
+    additional_info.insert("Origins".to_string(), endpoint.origins.join(" "));
+    additional_info.insert(
+        format!("Requests ({}d)", cdn::IDLE_WINDOW_DAYS),
+        format!("{:.0}", endpoint.requests),
+    );
+
+    ResourceSummary {
+        id: endpoint.id.clone(),
+        name: endpoint.name.clone(),
+        resource_type: endpoint.resource_type.clone(),
+        region: endpoint.region.clone(),
+        state: if endpoint.is_idle() {
+            format!("{} (idle)", endpoint.state)
+        } else {
+            endpoint.state.clone()
+        },
+        creation_date: None,
+        tags: endpoint.tags.clone(),
+        additional_info,
+        account: None,
+    }
+}
+
+fn database_summary(database: &ManagedDatabase) -> ResourceSummary {
+    let mut additional_info = HashMap::new();
+    additional_info.insert("Engine".to_string(), database.engine.clone());
+    additional_info.insert("Tier".to_string(), database.tier.clone());
+    if let Some(group) = azure::resource_group_of(&database.id) {
+        additional_info.insert("Resource Group".to_string(), group.to_string());
+    }
+
+    ResourceSummary {
+        id: database.id.clone(),
+        name: database.name.clone(),
+        resource_type: database.resource_type.clone(),
+        region: database.region.clone(),
+        state: database.state.clone(),
+        creation_date: database.creation_date,
+        tags: database.tags.clone(),
+        additional_info,
+        account: None,
+    }
+}
+
+/// One entry per DNS zone followed by its records.
+fn dns_summaries(zones: &[DnsZone]) -> Vec<ResourceSummary> {
+    let mut resources = Vec::new();
+
+    for zone in zones {
+        let mut additional_info = HashMap::new();
+        additional_info.insert("Records".to_string(), zone.records.len().to_string());
+        resources.push(ResourceSummary {
+            id: zone.id.clone(),
+            name: zone.name.clone(),
+            resource_type: "DNS Zone".to_string(),
+            region: "global".to_string(),
+            state: if zone.private { "private" } else { "public" }.to_string(),
+            creation_date: None,
+            tags: HashMap::new(),
+            additional_info,
+            account: None,
+        });
+
+        for record in &zone.records {
+            let mut additional_info = HashMap::new();
+            additional_info.insert("Zone".to_string(), zone.name.clone());
+            additional_info.insert(
+                "Target".to_string(),
+                record
+                    .alias_target
+                    .clone()
+                    .unwrap_or_else(|| record.values.join(" ")),
+            );
+            if let Some(ttl) = record.ttl {
+                additional_info.insert("TTL".to_string(), ttl.to_string());
+            }
+            resources.push(ResourceSummary {
+                id: format!("{}/{}/{}", zone.id, record.name, record.record_type),
+                name: record.name.clone(),
+                resource_type: format!("DNS {} Record", record.record_type),
+                region: "global".to_string(),
+                state: "active".to_string(),
+                creation_date: None,
+                tags: HashMap::new(),
+                additional_info,
+                account: None,
+            });
         }
+    }
+
+    resources
+}
+
+/// Resource types without a paged AWS listing yet.
+fn unpaged_aws_resources(resource_type: &ResourceType) -> Vec<ResourceSummary> {
+    let mut resources = Vec::new();
+
+    match resource_type {
+        ResourceType::Ec2
+        | ResourceType::S3
+        | ResourceType::Rds
+        | ResourceType::Lambda
+        | ResourceType::Iam
+        | ResourceType::Dns
+        | ResourceType::Cdn => {}
 
         ResourceType::LoadBalancer => {
             // For Load Balancers, we'd use the ELB client
-            println!("   Load Balancer resources not yet implemented for AWS");
+            eprintln!("   Load Balancer resources not yet implemented for AWS");
         }
 
         ResourceType::Vpc => {
             // For VPCs, we'd use the EC2 client
-            println!("   VPC resources not yet implemented for AWS");
+            eprintln!("   VPC resources not yet implemented for AWS");
         }
 
         ResourceType::Subnet => {
             // For Subnets, we'd use the EC2 client
-            println!("   Subnet resources not yet implemented for AWS");
+            eprintln!("   Subnet resources not yet implemented for AWS");
         }
 
         ResourceType::SecurityGroup => {
             // For Security Groups, we'd use the EC2 client
-            println!("   Security Group resources not yet implemented for AWS");
+            eprintln!("   Security Group resources not yet implemented for AWS");
+        }
+
+        ResourceType::StaticIp
+        | ResourceType::Volume
+        | ResourceType::Snapshot
+        | ResourceType::NetworkInterface => {
+            eprintln!(
+                "   {:?} resources not yet implemented for AWS",
+                resource_type
+            );
         }
 
         ResourceType::All => {
@@ -207,18 +1010,56 @@ async fn list_aws_resources(
                 creation_date: Some(Utc::now() - chrono::Duration::days(30)),
                 tags: HashMap::new(),
                 additional_info,
+                account: None,
             });
         }
     }
 
+    resources
+}
+
+/// Inventory cache scope of [`collect_inventory`] for the account `provider`
+/// is signed in to.
+pub(crate) async fn inventory_scope(provider: &CloudProvider) -> String {
+    format!(
+        "{}:{}:Inventory:",
+        provider,
+        cache::provider_scope(provider).await
+    )
+}
+
+/// Every supported resource type for a provider, served from the inventory
+/// cache when fresh.
+pub(crate) async fn collect_inventory(
+    provider: &CloudProvider,
+) -> Result<Vec<ResourceSummary>, AppError> {
+    let scope = inventory_scope(provider).await;
+    if let Some(resources) = inventory::cached::<Vec<ResourceSummary>>("resources", &scope) {
+        return Ok(resources);
+    }
+
+    let resources = match provider {
+        CloudProvider::Aws => {
+            let config = clients::aws_config().await;
+            let mut resources = list_aws_resources(&config, &ResourceType::Ec2).await?;
+            resources.extend(list_aws_resources(&config, &ResourceType::S3).await?);
+            resources.extend(list_aws_resources(&config, &ResourceType::Iam).await?);
+            resources
+        }
+        CloudProvider::Gcp | CloudProvider::Azure => {
+            providers::get(provider).list(&ResourceType::All).await?
+        }
+    };
+
+    inventory::store_resources(&provider.to_string(), &scope, &resources);
     Ok(resources)
 }
 
 #[allow(unused_variables, dead_code)]
-async fn list_gcp_resources(
+pub(crate) async fn list_gcp_resources(
     resource_type: &ResourceType,
 ) -> Result<Vec<ResourceSummary>, AppError> {
-    println!("🔍 Fetching GCP resources...");
+    tracing::info!("🔍 Fetching GCP resources...");
 
     let project_id = std::env::var("GOOGLE_CLOUD_PROJECT")
         .map_err(|_| AppError::ConfigurationError("GOOGLE_CLOUD_PROJECT not set".to_string()))?;
@@ -241,6 +1082,7 @@ async fn list_gcp_resources(
                 creation_date: Some(Utc::now() - chrono::Duration::days(30)),
                 tags: HashMap::new(),
                 additional_info,
+                account: None,
             });
         }
 
@@ -260,11 +1102,33 @@ async fn list_gcp_resources(
                 creation_date: Some(Utc::now() - chrono::Duration::days(30)),
                 tags: HashMap::new(),
                 additional_info,
+                account: None,
             });
+            resources.extend(
+                databases::cloud_sql_instances()
+                    .await?
+                    .iter()
+                    .map(database_summary),
+            );
+        }
+
+        ResourceType::Rds => {
+            resources.extend(
+                databases::cloud_sql_instances()
+                    .await?
+                    .iter()
+                    .map(database_summary),
+            );
+        }
+
+        ResourceType::Dns => resources.extend(dns_summaries(&dns::gcp_zones().await?)),
+
+        ResourceType::Cdn => {
+            resources.extend(cdn::gcp_cdn_backends().await?.iter().map(cdn_summary));
         }
 
         _ => {
-            println!(
+            eprintln!(
                 "   Resource type {:?} not yet implemented for GCP",
                 resource_type
             );
@@ -275,94 +1139,259 @@ async fn list_gcp_resources(
 }
 
 #[allow(unused_variables, dead_code)]
-async fn list_azure_resources(
+pub(crate) async fn list_azure_resources(
     resource_type: &ResourceType,
+    resource_group: Option<&str>,
 ) -> Result<Vec<ResourceSummary>, AppError> {
-    println!("🔍 Fetching Azure resources...");
-
-    let subscription_id = std::env::var("AZURE_SUBSCRIPTION_ID")
-        .map_err(|_| AppError::ConfigurationError("AZURE_SUBSCRIPTION_ID not set".to_string()))?;
+    tracing::info!("🔍 Fetching Azure resources...");
 
     let mut resources = Vec::new();
 
     match resource_type {
         ResourceType::Ec2 => {
-            // Simulate Azure Virtual Machines
-            let mut additional_info = HashMap::new();
-            additional_info.insert("Size".to_string(), "Standard_B1s".to_string());
-            additional_info.insert(
-                "Resource Group".to_string(),
-                "my-resource-group".to_string(),
+            resources.extend(
+                azure::virtual_machines()
+                    .await?
+                    .iter()
+                    .map(azure_vm_summary),
             );
+        }
 
-            resources.push(ResourceSummary {
-                id: "azure-vm-1".to_string(),
-                name: "web-vm".to_string(),
-                resource_type: "Virtual Machine".to_string(),
-                region: "East US".to_string(),
-                state: "Running".to_string(),
-                creation_date: Some(Utc::now() - chrono::Duration::days(25)),
-                tags: HashMap::new(),
-                additional_info,
-            });
+        ResourceType::Rds => {
+            resources.extend(
+                databases::azure_databases()
+                    .await?
+                    .iter()
+                    .map(database_summary),
+            );
         }
 
         ResourceType::All => {
-            // List all resource types - avoid recursion by implementing directly
-            // Simulate Azure Virtual Machines
-            let mut additional_info = HashMap::new();
-            additional_info.insert("Size".to_string(), "Standard_B1s".to_string());
-            additional_info.insert(
-                "Resource Group".to_string(),
-                "my-resource-group".to_string(),
+            resources.extend(
+                azure::virtual_machines()
+                    .await?
+                    .iter()
+                    .map(azure_vm_summary),
+            );
+            resources.extend(
+                databases::azure_databases()
+                    .await?
+                    .iter()
+                    .map(database_summary),
             );
+        }
 
-            resources.push(ResourceSummary {
-                id: "azure-vm-1".to_string(),
-                name: "web-vm".to_string(),
-                resource_type: "Virtual Machine".to_string(),
-                region: "East US".to_string(),
-                state: "Running".to_string(),
-                creation_date: Some(Utc::now() - chrono::Duration::days(25)),
-                tags: HashMap::new(),
-                additional_info,
-            });
+        ResourceType::Cdn => {
+            resources.extend(cdn::azure_cdn_endpoints().await?.iter().map(cdn_summary));
         }
 
         _ => {
-            println!(
+            eprintln!(
                 "   Resource type {:?} not yet implemented for Azure",
                 resource_type
             );
         }
     }
 
+    resources.retain(|r| azure::in_resource_group(&r.id, resource_group));
     Ok(resources)
 }
 
-fn output_resource_list(
-    resources: &[ResourceSummary],
-    format: &OutputFormat,
-    provider: &CloudProvider,
-) -> Result<(), AppError> {
-    match format {
-        OutputFormat::Table => {
-            println!("\n📋 {} Resources:", provider.to_string().green());
-            println!(
-                "{:<20} {:<15} {:<15} {:<15} {:<20}",
-                "ID", "Name", "Type", "State", "Region"
-            );
-            println!("{:-<85}", "");
+fn azure_vm_summary(vm: &serde_json::Value) -> ResourceSummary {
+    let properties = &vm["properties"];
+    let mut additional_info = HashMap::new();
 
+    if let Some(size) = properties["hardwareProfile"]["vmSize"].as_str() {
+        additional_info.insert("Size".to_string(), size.to_string());
+    }
+    if let Some(group) = vm["id"].as_str().and_then(azure::resource_group_of) {
+        additional_info.insert("Resource Group".to_string(), group.to_string());
+    }
+    if let Some(os) = properties["storageProfile"]["osDisk"]["osType"].as_str() {
+        additional_info.insert("OS".to_string(), os.to_string());
+    }
+
+    ResourceSummary {
+        id: vm["id"].as_str().unwrap_or_default().to_string(),
+        name: vm["name"].as_str().unwrap_or_default().to_string(),
+        resource_type: "Virtual Machine".to_string(),
+        region: vm["location"].as_str().unwrap_or_default().to_string(),
+        state: azure::power_state(vm),
+        creation_date: properties["timeCreated"]
+            .as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc)),
+        tags: vm["tags"]
+            .as_object()
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        additional_info,
+        account: None,
+    }
+}
+
+/// Writes a resource listing page by page, keeping only the counters the
+/// summary needs. A template needs the whole listing, so with one the
+/// pages are collected and rendered at the end instead.
+struct ListingOutput<'a> {
+    format: &'a OutputFormat,
+    /// Provider name, or "all providers" for a merged listing
+    label: String,
+    template: Option<&'a str>,
+    collected: Vec<ResourceSummary>,
+    show_account: bool,
+    /// Merged listings add a provider column, filled from `page_provider`
+    show_provider: bool,
+    page_provider: Option<String>,
+    /// Only resources matching every `--tag` filter are shown
+    tag_filters: Vec<TagFilter>,
+    total: usize,
+    running: usize,
+    stopped: usize,
+    type_counts: HashMap<String, usize>,
+    provider_counts: BTreeMap<String, usize>,
+    accounts: BTreeSet<String>,
+}
+
+impl<'a> ListingOutput<'a> {
+    fn new(format: &'a OutputFormat, label: String, template: Option<&'a str>) -> Self {
+        Self {
+            format,
+            label,
+            template,
+            collected: Vec::new(),
+            show_account: false,
+            show_provider: false,
+            page_provider: None,
+            tag_filters: Vec::new(),
+            total: 0,
+            running: 0,
+            stopped: 0,
+            type_counts: HashMap::new(),
+            provider_counts: BTreeMap::new(),
+            accounts: BTreeSet::new(),
+        }
+    }
+
+    /// A page of a merged listing, all from `provider`.
+    fn provider_page(
+        &mut self,
+        provider: &CloudProvider,
+        resources: &[ResourceSummary],
+    ) -> Result<(), AppError> {
+        self.page_provider = Some(provider.to_string());
+        self.page(resources)
+    }
+
+    fn page(&mut self, resources: &[ResourceSummary]) -> Result<(), AppError> {
+        let matching: Vec<ResourceSummary>;
+        let resources = if self.tag_filters.is_empty() {
+            resources
+        } else {
+            matching = resources
+                .iter()
+                .filter(|r| self.tag_filters.iter().all(|f| f.matches(&r.tags)))
+                .cloned()
+                .collect();
+            &matching
+        };
+        if resources.is_empty() {
+            return Ok(());
+        }
+        if self.template.is_some() {
+            self.collected.extend_from_slice(resources);
             for resource in resources {
+                self.tally(resource);
+            }
+            return Ok(());
+        }
+        if self.total == 0 {
+            // Multi-account listings arrive as one page, so the first page
+            // decides whether the account column is needed
+            self.show_account = resources.iter().any(|r| r.account.is_some());
+            self.header()?;
+        }
+
+        for resource in resources {
+            self.row(resource, self.total == 0)?;
+            self.tally(resource);
+        }
+
+        Ok(())
+    }
+
+    fn header(&self) -> Result<(), AppError> {
+        match self.format {
+            OutputFormat::Table => {
+                outln!("\n📋 {} Resources:", self.label.green());
+                if self.show_provider {
+                    out!("{:<8} ", "Provider");
+                }
+                if self.show_account {
+                    out!("{:<14} ", "Account");
+                }
+                outln!(
+                    "{:<20} {:<15} {:<15} {:<15} {:<20}",
+                    "ID",
+                    "Name",
+                    "Type",
+                    "State",
+                    "Region"
+                );
+                outln!("{:-<85}", "");
+            }
+
+            OutputFormat::Json => out!("["),
+
+            // Each row is a complete list item, so there's nothing to open
+            OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {}
+
+            OutputFormat::Csv => {
+                let mut columns = Vec::new();
+                if self.show_provider {
+                    columns.push("Provider");
+                }
+                if self.show_account {
+                    columns.push("Account");
+                }
+                columns.extend([
+                    "ID",
+                    "Name",
+                    "Type",
+                    "State",
+                    "Region",
+                    "CreationDate",
+                    "Tags",
+                    "AdditionalInfo",
+                ]);
+                out!("{}", report::csv_record(columns, ',')?);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn row(&self, resource: &ResourceSummary, first: bool) -> Result<(), AppError> {
+        match self.format {
+            OutputFormat::Table => {
                 let state_color = match resource.state.to_lowercase().as_str() {
-                    "running" | "active" => "green",
-                    "stopped" | "terminated" => "red",
+                    "running" | "active" | "available" => "green",
+                    "stopped" | "deallocated" | "terminated" => "red",
                     "pending" | "starting" => "yellow",
                     _ => "white",
                 };
 
-                println!(
+                if self.show_provider {
+                    out!("{:<8} ", self.page_provider.as_deref().unwrap_or("-"));
+                }
+                if self.show_account {
+                    out!("{:<14} ", resource.account.as_deref().unwrap_or("-"));
+                }
+                outln!(
                     "{:<20} {:<15} {:<15} {:<15} {:<20}",
                     resource.id,
                     resource.name,
@@ -371,77 +1400,134 @@ fn output_resource_list(
                     resource.region
                 );
             }
-        }
 
-        OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(resources)?;
-            println!("{}", json);
-        }
+            OutputFormat::Json => {
+                // Same layout serde_json gives a pretty-printed array
+                let json = serde_json::to_string_pretty(&self.value(resource)?)?;
+                out!(
+                    "{}\n  {}",
+                    if first { "" } else { "," },
+                    json.replace('\n', "\n  ")
+                );
+            }
 
-        OutputFormat::Csv => {
-            println!("ID,Name,Type,State,Region,CreationDate");
-            for resource in resources {
+            // Out as soon as the page arrives, so jq sees the first resources
+            // long before a large account is done
+            OutputFormat::Jsonl => {
+                outln!("{}", serde_json::to_string(&self.value(resource)?)?);
+            }
+
+            // A one-item list per row: `- ` items in YAML, `[[items]]` in TOML
+            OutputFormat::Yaml | OutputFormat::Toml => {
+                out!(
+                    "{}",
+                    report::document(self.format, &[self.value(resource)?])?
+                );
+            }
+
+            OutputFormat::Csv => {
                 let creation_date = resource
                     .creation_date
                     .map(|d| d.format("%Y-%m-%d").to_string())
                     .unwrap_or_else(|| "N/A".to_string());
 
-                println!(
-                    "{},{},{},{},{},{}",
-                    resource.id,
-                    resource.name,
-                    resource.resource_type,
-                    resource.state,
-                    resource.region,
-                    creation_date
-                );
+                let mut fields = Vec::new();
+                if self.show_provider {
+                    fields.push(self.page_provider.clone().unwrap_or_default());
+                }
+                if self.show_account {
+                    fields.push(resource.account.clone().unwrap_or_default());
+                }
+                fields.extend([
+                    resource.id.clone(),
+                    resource.name.clone(),
+                    resource.resource_type.clone(),
+                    resource.state.clone(),
+                    resource.region.clone(),
+                    creation_date,
+                    // Sorted so the same resource always gives the same line
+                    serde_json::to_string(&resource.tags.iter().collect::<BTreeMap<_, _>>())?,
+                    serde_json::to_string(
+                        &resource.additional_info.iter().collect::<BTreeMap<_, _>>(),
+                    )?,
+                ]);
+                out!("{}", report::csv_record(fields, ',')?);
             }
         }
-    }
-
-    Ok(())
-}
 
-fn show_resource_summary(
-    resources: &[ResourceSummary],
-    provider: &CloudProvider,
-) -> Result<(), AppError> {
-    let total_resources = resources.len();
-    let running_resources = resources
-        .iter()
-        .filter(|r| r.state.to_lowercase() == "running" || r.state.to_lowercase() == "active")
-        .count();
+        Ok(())
+    }
 
-    let stopped_resources = resources
-        .iter()
-        .filter(|r| r.state.to_lowercase() == "stopped" || r.state.to_lowercase() == "terminated")
-        .count();
+    /// A resource as written in structured output, with its provider when
+    /// the listing spans several.
+    fn value(&self, resource: &ResourceSummary) -> Result<serde_json::Value, AppError> {
+        let mut value = serde_json::to_value(resource)?;
+        if let Some(provider) = self.page_provider.as_deref().filter(|_| self.show_provider) {
+            value["provider"] = provider.into();
+        }
+        Ok(value)
+    }
 
-    // Group by resource type
-    let mut type_counts: HashMap<String, usize> = HashMap::new();
-    for resource in resources {
-        *type_counts
+    fn tally(&mut self, resource: &ResourceSummary) {
+        self.total += 1;
+        match resource.state.to_lowercase().as_str() {
+            "running" | "active" | "available" => self.running += 1,
+            "stopped" | "deallocated" | "terminated" => self.stopped += 1,
+            _ => {}
+        }
+        *self
+            .type_counts
             .entry(resource.resource_type.clone())
             .or_insert(0) += 1;
+        if let Some(account) = &resource.account {
+            self.accounts.insert(account.clone());
+        }
+        if let Some(provider) = &self.page_provider {
+            *self.provider_counts.entry(provider.clone()).or_insert(0) += 1;
+        }
     }
 
-    println!("\n📊 Summary for {}:", provider.to_string().green());
-    println!("   Total Resources: {}", total_resources);
-    println!(
-        "   Running/Active: {}",
-        running_resources.to_string().green()
-    );
-    println!(
-        "   Stopped/Terminated: {}",
-        stopped_resources.to_string().red()
-    );
+    fn finish(self) -> Result<(), AppError> {
+        if self.total == 0 {
+            return Err(AppError::ResourceNotFound(
+                "no resources match the specified criteria".to_string(),
+            ));
+        }
 
-    if !type_counts.is_empty() {
-        println!("   By Type:");
-        for (resource_type, count) in type_counts {
-            println!("     {}: {}", resource_type, count);
+        if let Some(path) = self.template {
+            let context = serde_json::json!({
+                "provider": self.label,
+                "total": self.total,
+                "resources": self.collected,
+            });
+            outln!("{}", template::render(path, &context)?);
+            return Ok(());
         }
-    }
 
-    Ok(())
+        if matches!(self.format, OutputFormat::Json) {
+            outln!("\n]");
+        }
+
+        eprintln!("\n📊 Summary for {}:", self.label.green());
+        eprintln!("   Total Resources: {}", self.total);
+        if self.show_provider {
+            for (provider, count) in &self.provider_counts {
+                eprintln!("   {}: {}", provider, count);
+            }
+        }
+        if !self.accounts.is_empty() {
+            eprintln!("   Accounts: {}", self.accounts.len());
+        }
+        eprintln!("   Running/Active: {}", self.running.to_string().green());
+        eprintln!("   Stopped/Terminated: {}", self.stopped.to_string().red());
+
+        if !self.type_counts.is_empty() {
+            eprintln!("   By Type:");
+            for (resource_type, count) in self.type_counts {
+                eprintln!("     {}: {}", resource_type, count);
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,137 @@
+use crate::cli::{Commands, LockAction, OutputFormat};
+use crate::error::AppError;
+use crate::locks::{self, Lock};
+use crate::report;
+use chrono::{NaiveDate, Utc};
+use colored::*;
+
+#[allow(unused_variables, dead_code)]
+pub async fn lock(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Lock {
+        action,
+        resource_id,
+        until,
+        reason,
+    } = cmd
+    {
+        match action {
+            Some(LockAction::List { format }) => return list_locks(format),
+            Some(LockAction::Release { resource_id }) => return release_lock(resource_id),
+            None => {}
+        }
+
+        let (Some(resource_id), Some(until)) = (resource_id, until) else {
+            return Err(AppError::InvalidParameters(
+                "A resource ID and --until are required, e.g. `actlog lock i-0abc123 --until 2025-09-01`"
+                    .to_string(),
+            ));
+        };
+
+        let until = NaiveDate::parse_from_str(until, "%Y-%m-%d")
+            .map_err(|_| AppError::DateParseError(format!("Invalid --until date: {}", until)))?;
+        if until < Utc::now().date_naive() {
+            return Err(AppError::InvalidParameters(format!(
+                "--until {} is in the past",
+                until
+            )));
+        }
+
+        // Locking again moves the date and reason of the existing lock
+        let mut all = locks::load_locks()?;
+        all.retain(|l| l.resource_id != *resource_id);
+        all.push(Lock {
+            resource_id: resource_id.clone(),
+            until,
+            reason: reason.clone(),
+            locked_by: locks::current_user(),
+            locked_at: Utc::now(),
+        });
+        locks::save_locks(all)?;
+
+        println!(
+            "🔒 Locked {} until {}",
+            resource_id.green(),
+            until.to_string().green()
+        );
+        println!("   Cleanup, scale-instances, reconcile and ebs-optimize will skip it");
+    }
+
+    Ok(())
+}
+
+fn list_locks(format: &OutputFormat) -> Result<(), AppError> {
+    let today = Utc::now().date_naive();
+    let mut active: Vec<Lock> = locks::load_locks()?
+        .into_iter()
+        .filter(|l| l.is_active(today))
+        .collect();
+    active.sort_by(|a, b| a.until.cmp(&b.until));
+
+    match format {
+        OutputFormat::Table => {
+            if active.is_empty() {
+                println!(
+                    "ℹ️  No resources are locked. Registry: {}",
+                    locks::locks_path()?.display()
+                );
+                return Ok(());
+            }
+
+            println!(
+                "{:<30} {:<12} {:<15} {}",
+                "Resource", "Until", "Locked by", "Reason"
+            );
+            println!("{:-<85}", "");
+            for lock in &active {
+                println!(
+                    "{:<30} {:<12} {:<15} {}",
+                    lock.resource_id,
+                    lock.until.to_string(),
+                    lock.locked_by,
+                    lock.reason.as_deref().unwrap_or("")
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&active)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, &active)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("ResourceId,Until,LockedBy,LockedAt,Reason");
+            for lock in &active {
+                println!(
+                    "{},{},{},{},{}",
+                    lock.resource_id,
+                    lock.until,
+                    lock.locked_by,
+                    lock.locked_at.to_rfc3339(),
+                    lock.reason.as_deref().unwrap_or("")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn release_lock(resource_id: &str) -> Result<(), AppError> {
+    let mut all = locks::load_locks()?;
+    let before = all.len();
+    all.retain(|l| l.resource_id != resource_id);
+    if all.len() == before {
+        return Err(AppError::ResourceNotFound(format!(
+            "No lock on {}",
+            resource_id
+        )));
+    }
+
+    locks::save_locks(all)?;
+    println!("🔓 Released the lock on {}", resource_id.green());
+    Ok(())
+}
@@ -0,0 +1,478 @@
+use crate::azure;
+use crate::cli::{CloudProvider, Commands, MetricKind, OutputFormat};
+use crate::clients;
+use crate::cloudwatch::{self, MetricQuery};
+use crate::error::AppError;
+use crate::gcp;
+use crate::query;
+use crate::regions;
+use crate::report;
+use aws_config::SdkConfig;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use colored::*;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Columns in the rendered chart; the period is chosen to fill them
+const CHART_WIDTH: i64 = 60;
+const CHART_HEIGHT: usize = 8;
+
+/// One series of a metric kind, named as each provider publishes it.
+struct SeriesSpec {
+    label: &'static str,
+    unit: &'static str,
+    aws: &'static str,
+    gcp: &'static str,
+    azure: &'static str,
+    /// Sum each period (byte counters) rather than average it
+    total: bool,
+}
+
+const CPU: &[SeriesSpec] = &[SeriesSpec {
+    label: "CPU",
+    unit: "%",
+    aws: "CPUUtilization",
+    gcp: "compute.googleapis.com/instance/cpu/utilization",
+    azure: "Percentage CPU",
+    total: false,
+}];
+
+const NETWORK: &[SeriesSpec] = &[
+    SeriesSpec {
+        label: "Network in",
+        unit: "bytes",
+        aws: "NetworkIn",
+        gcp: "compute.googleapis.com/instance/network/received_bytes_count",
+        azure: "Network In Total",
+        total: true,
+    },
+    SeriesSpec {
+        label: "Network out",
+        unit: "bytes",
+        aws: "NetworkOut",
+        gcp: "compute.googleapis.com/instance/network/sent_bytes_count",
+        azure: "Network Out Total",
+        total: true,
+    },
+];
+
+const DISK: &[SeriesSpec] = &[
+    SeriesSpec {
+        label: "Disk read",
+        unit: "bytes",
+        aws: "EBSReadBytes",
+        gcp: "compute.googleapis.com/instance/disk/read_bytes_count",
+        azure: "Disk Read Bytes",
+        total: true,
+    },
+    SeriesSpec {
+        label: "Disk write",
+        unit: "bytes",
+        aws: "EBSWriteBytes",
+        gcp: "compute.googleapis.com/instance/disk/write_bytes_count",
+        azure: "Disk Write Bytes",
+        total: true,
+    },
+];
+
+#[derive(Debug, Serialize)]
+struct Point {
+    timestamp: DateTime<Utc>,
+    value: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct MetricSeries {
+    label: String,
+    unit: String,
+    min: f64,
+    avg: f64,
+    max: f64,
+    points: Vec<Point>,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn metrics(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Metrics {
+        provider,
+        id,
+        metric,
+        window,
+        region,
+        format,
+        profile,
+    } = cmd
+    {
+        let provider = provider.clone().unwrap_or_else(|| provider_for_id(id));
+        let window_secs = (query::parse_days(window)? * 86400.0) as i64;
+        if window_secs <= 0 {
+            return Err(AppError::InvalidParameters(format!(
+                "Window '{}' must be positive",
+                window
+            )));
+        }
+
+        // Whole minutes, at least five: the finest basic CloudWatch monitoring
+        let period = ((window_secs / CHART_WIDTH + 59) / 60 * 60).max(300);
+        let end = Utc::now();
+        let start = end - Duration::seconds(window_secs);
+
+        tracing::info!(
+            "📈 Fetching {} metrics for {} over the last {}...",
+            provider.to_string().green(),
+            id.green(),
+            window
+        );
+
+        let aws_config = match provider {
+            CloudProvider::Aws => {
+                let config = clients::aws_config().await;
+                Some(match region {
+                    Some(region) => regions::regional_config(&config, region),
+                    None => config,
+                })
+            }
+            _ => None,
+        };
+
+        let specs: Vec<&SeriesSpec> = metric
+            .iter()
+            .flat_map(|kind| match kind {
+                MetricKind::Cpu => CPU,
+                MetricKind::Network => NETWORK,
+                MetricKind::Disk => DISK,
+            })
+            .collect();
+
+        let points = match provider {
+            CloudProvider::Aws => {
+                let config = aws_config.as_ref().expect("AWS config was just loaded");
+                aws_points(config, id, &specs, &start, &end, period).await?
+            }
+            CloudProvider::Gcp => {
+                let mut points = Vec::new();
+                for spec in &specs {
+                    points.push(gcp_points(id, spec, &start, &end, period).await?);
+                }
+                points
+            }
+            CloudProvider::Azure => {
+                let mut points = Vec::new();
+                for spec in &specs {
+                    points.push(azure_points(id, spec, &start, &end, period).await?);
+                }
+                points
+            }
+        };
+        let series: Vec<MetricSeries> = specs
+            .into_iter()
+            .zip(points)
+            .map(|(spec, points)| summarize(spec, points))
+            .collect();
+
+        output_metrics(&series, format)?;
+    }
+
+    Ok(())
+}
+
+/// `i-...` is an EC2 instance and `/subscriptions/...` an Azure resource;
+/// anything else is taken to be a Compute Engine instance ID.
+pub(crate) fn provider_for_id(id: &str) -> CloudProvider {
+    if id.starts_with("i-") {
+        CloudProvider::Aws
+    } else if id.starts_with("/subscriptions/") {
+        CloudProvider::Azure
+    } else {
+        CloudProvider::Gcp
+    }
+}
+
+fn summarize(spec: &SeriesSpec, points: Vec<Point>) -> MetricSeries {
+    let values = || points.iter().map(|p| p.value);
+    let (min, max, avg) = if points.is_empty() {
+        (0.0, 0.0, 0.0)
+    } else {
+        (
+            values().fold(f64::INFINITY, f64::min),
+            values().fold(f64::NEG_INFINITY, f64::max),
+            values().sum::<f64>() / points.len() as f64,
+        )
+    };
+
+    MetricSeries {
+        label: spec.label.to_string(),
+        unit: spec.unit.to_string(),
+        min,
+        avg,
+        max,
+        points,
+    }
+}
+
+/// Every series of `specs` for an EC2 instance, read in one batch.
+async fn aws_points(
+    config: &SdkConfig,
+    instance_id: &str,
+    specs: &[&SeriesSpec],
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+    period: i64,
+) -> Result<Vec<Vec<Point>>, AppError> {
+    let cloudwatch_client = clients::get::<aws_sdk_cloudwatch::Client>(config).await;
+    let queries: Vec<MetricQuery> = specs
+        .iter()
+        .map(|spec| {
+            let statistic = if spec.total {
+                aws_sdk_cloudwatch::types::Statistic::Sum
+            } else {
+                aws_sdk_cloudwatch::types::Statistic::Average
+            };
+            MetricQuery::new("AWS/EC2", spec.aws, "InstanceId", instance_id, statistic)
+        })
+        .collect();
+
+    let series = cloudwatch::fetch(&cloudwatch_client, &queries, start, end, period)
+        .await
+        .map_err(|e| e.for_resource(instance_id))?;
+    Ok(series
+        .into_iter()
+        .map(|points| {
+            points
+                .into_iter()
+                .map(|(timestamp, value)| Point { timestamp, value })
+                .collect()
+        })
+        .collect())
+}
+
+async fn gcp_points(
+    instance_id: &str,
+    spec: &SeriesSpec,
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+    period: i64,
+) -> Result<Vec<Point>, AppError> {
+    let project_id = gcp::project_id()?;
+    let filter = format!(
+        "metric.type=\"{}\" AND resource.labels.instance_id=\"{}\"",
+        spec.gcp, instance_id
+    );
+    let start_time = start.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let end_time = end.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let alignment = format!("{}s", period);
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/projects/{}/timeSeries", gcp::MONITORING_API, project_id),
+        &[
+            ("filter", filter.as_str()),
+            ("interval.startTime", start_time.as_str()),
+            ("interval.endTime", end_time.as_str()),
+            ("aggregation.alignmentPeriod", alignment.as_str()),
+            (
+                "aggregation.perSeriesAligner",
+                if spec.total {
+                    "ALIGN_SUM"
+                } else {
+                    "ALIGN_MEAN"
+                },
+            ),
+            ("aggregation.crossSeriesReducer", "REDUCE_SUM"),
+        ],
+    )
+    .map_err(|e| AppError::InvalidParameters(e.to_string()))?;
+
+    let response = gcp::get("monitoring.timeSeries.list", url.as_str())
+        .await
+        .map_err(|e| e.for_resource(instance_id))?;
+
+    // CPU utilization is reported as a 0-1 fraction
+    let scale = if spec.unit == "%" { 100.0 } else { 1.0 };
+    let mut points: Vec<Point> = response["timeSeries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|series| series["points"].as_array())
+        .flatten()
+        .filter_map(|point| {
+            let timestamp = point["interval"]["endTime"]
+                .as_str()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())?
+                .with_timezone(&Utc);
+            Some(Point {
+                timestamp,
+                value: point_value(&point["value"])? * scale,
+            })
+        })
+        .collect();
+    // Cloud Monitoring returns the newest point first
+    points.sort_by_key(|p| p.timestamp);
+    Ok(points)
+}
+
+/// Typed value of a Cloud Monitoring point; int64 values arrive as strings.
+fn point_value(value: &Value) -> Option<f64> {
+    value["doubleValue"].as_f64().or_else(|| {
+        let int = &value["int64Value"];
+        int.as_str()
+            .and_then(|v| v.parse::<f64>().ok())
+            .or(int.as_f64())
+    })
+}
+
+async fn azure_points(
+    resource_id: &str,
+    spec: &SeriesSpec,
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+    period: i64,
+) -> Result<Vec<Point>, AppError> {
+    let timespan = format!(
+        "{}/{}",
+        start.to_rfc3339_opts(SecondsFormat::Secs, true),
+        end.to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+    // Azure Monitor only accepts a fixed set of grains; use the closest one up
+    let interval = match period {
+        p if p <= 300 => "PT5M",
+        p if p <= 900 => "PT15M",
+        p if p <= 1800 => "PT30M",
+        p if p <= 3600 => "PT1H",
+        p if p <= 21600 => "PT6H",
+        p if p <= 43200 => "PT12H",
+        _ => "P1D",
+    };
+    let aggregation = if spec.total { "Total" } else { "Average" };
+    let url = reqwest::Url::parse_with_params(
+        &format!(
+            "{}{}/providers/Microsoft.Insights/metrics",
+            azure::MANAGEMENT_API,
+            resource_id
+        ),
+        &[
+            ("api-version", azure::METRICS_API_VERSION),
+            ("metricnames", spec.azure),
+            ("aggregation", aggregation),
+            ("interval", interval),
+            ("timespan", timespan.as_str()),
+        ],
+    )
+    .map_err(|e| AppError::InvalidParameters(e.to_string()))?;
+
+    let metrics = azure::list("Microsoft.Insights/metrics/read", url.as_str())
+        .await
+        .map_err(|e| e.for_resource(resource_id))?;
+
+    let field = aggregation.to_lowercase();
+    Ok(metrics
+        .iter()
+        .filter_map(|metric| metric["timeseries"].as_array())
+        .flatten()
+        .filter_map(|series| series["data"].as_array())
+        .flatten()
+        .filter_map(|point| {
+            let timestamp = point["timeStamp"]
+                .as_str()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())?
+                .with_timezone(&Utc);
+            Some(Point {
+                timestamp,
+                value: point[field.as_str()].as_f64()?,
+            })
+        })
+        .collect())
+}
+
+/// Column chart of `points`, one column per point, top row first.
+fn render_chart(points: &[Point], min: f64, max: f64) -> Vec<String> {
+    let range = if max > min { max - min } else { 1.0 };
+    (0..CHART_HEIGHT)
+        .rev()
+        .map(|row| {
+            let threshold = min + range * (row as f64 + 0.5) / CHART_HEIGHT as f64;
+            points
+                .iter()
+                .map(|p| if p.value >= threshold { '█' } else { ' ' })
+                .collect()
+        })
+        .collect()
+}
+
+fn format_value(value: f64, unit: &str) -> String {
+    match unit {
+        "%" => format!("{:.1}%", value),
+        _ => {
+            let units = ["B", "KB", "MB", "GB", "TB"];
+            let mut value = value;
+            let mut index = 0;
+            while value >= 1024.0 && index < units.len() - 1 {
+                value /= 1024.0;
+                index += 1;
+            }
+            format!("{:.1} {}", value, units[index])
+        }
+    }
+}
+
+fn output_metrics(series: &[MetricSeries], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            for s in series {
+                println!("\n{} ({})", s.label.bold(), s.unit);
+                if s.points.is_empty() {
+                    println!("   No datapoints in this window");
+                    continue;
+                }
+
+                let rows = render_chart(&s.points, s.min, s.max);
+                let last = rows.len() - 1;
+                for (index, row) in rows.iter().enumerate() {
+                    let label = match index {
+                        0 => format_value(s.max, &s.unit),
+                        i if i == last => format_value(s.min, &s.unit),
+                        _ => String::new(),
+                    };
+                    println!("{:>12} │{}", label, row.cyan());
+                }
+                println!("{:>12} └{}", "", "─".repeat(s.points.len()));
+                println!(
+                    "{:>14}{} → {}",
+                    "",
+                    s.points[0].timestamp.format("%m-%d %H:%M"),
+                    s.points[s.points.len() - 1].timestamp.format("%m-%d %H:%M")
+                );
+                println!(
+                    "   min {}   avg {}   max {}",
+                    format_value(s.min, &s.unit),
+                    format_value(s.avg, &s.unit).green(),
+                    format_value(s.max, &s.unit)
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(series)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, series)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Series,Unit,Timestamp,Value");
+            for s in series {
+                for point in &s.points {
+                    println!(
+                        "{},{},{},{}",
+                        s.label,
+                        s.unit,
+                        point.timestamp.to_rfc3339(),
+                        point.value
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,13 +1,69 @@
+pub mod alerts;
 pub mod authenticate;
+pub mod carbon_report;
 pub mod cleanup;
 pub mod config;
 pub mod cost_report;
+pub mod dashboard;
+pub mod dns_check;
+pub mod doctor;
+pub mod ebs_optimize;
+pub mod export;
+pub mod graph;
+pub mod idle_report;
 pub mod list;
+pub mod lock;
+pub mod metrics;
+pub mod overview;
+pub mod policy;
+pub mod query;
+pub mod quotas;
+pub mod recommendations;
+pub mod reconcile;
+pub mod regions;
+pub mod s3;
 pub mod scaling;
+pub mod schedule;
+pub mod self_update;
+pub mod snapshot;
+pub mod snapshot_archive;
+pub mod spot_advisor;
+pub mod storage_analysis;
+pub mod tags;
+pub mod usage_report;
+pub mod who_created;
 
+pub use alerts::alerts;
 pub use authenticate::authenticate;
+pub use carbon_report::report_carbon;
 pub use cleanup::cleanup_resources;
 pub use config::configure;
 pub use cost_report::report_costs;
+pub use dashboard::dashboard;
+pub use dns_check::dns_check;
+pub use doctor::doctor;
+pub use ebs_optimize::optimize_ebs;
+pub use export::export_data;
+pub use graph::export_graph;
+pub use idle_report::idle_report;
 pub use list::list_resources;
+pub use lock::lock;
+pub use metrics::metrics;
+pub use overview::overview;
+pub use policy::policy;
+pub use query::query_resources;
+pub use quotas::check_quotas;
+pub use recommendations::recommendations;
+pub use reconcile::reconcile;
+pub use regions::list_regions;
+pub use s3::s3;
 pub use scaling::scale_instances;
+pub use schedule::schedule;
+pub use self_update::self_update;
+pub use snapshot::snapshot;
+pub use snapshot_archive::archive_snapshots;
+pub use spot_advisor::spot_advisor;
+pub use storage_analysis::storage_analysis;
+pub use tags::tags;
+pub use usage_report::usage_report;
+pub use who_created::who_created;
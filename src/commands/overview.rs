@@ -0,0 +1,512 @@
+use crate::audit::{self, AuditEntry};
+use crate::budget;
+use crate::cli::{CloudProvider, Commands, OutputFormat, ResourceType};
+use crate::clients;
+use crate::commands::authenticate::stored_credentials;
+use crate::commands::cleanup::{analyze_cleanup_actions, TagSelection};
+use crate::commands::cost_report::determine_date_range;
+use crate::commands::list::aws_datetime;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::providers;
+use crate::report;
+use aws_credential_types::provider::ProvideCredentials;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use colored::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const IAM_API: &str = "https://iam.googleapis.com/v1";
+
+/// Credentials expiring within this many days are listed
+const EXPIRY_WINDOW_DAYS: i64 = 30;
+
+/// Age at which an IAM access key is due for rotation
+const ACCESS_KEY_ROTATION_DAYS: i64 = 90;
+
+/// Rows shown in the idle and recent-change sections
+const TOP_N: usize = 5;
+
+/// Commands whose changes appear under recent changes
+const TRACKED_COMMANDS: &[&str] = &["cleanup", "scale-instances"];
+
+/// Everything `actlog overview` shows for one account, project or
+/// subscription.
+#[derive(Debug, Serialize)]
+pub struct Overview {
+    pub provider: String,
+    pub generated_at: DateTime<Utc>,
+    /// Resource count by type, then by state
+    pub resources: BTreeMap<String, BTreeMap<String, usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spend: Option<Spend>,
+    pub top_idle: Vec<IdleSummary>,
+    pub expiring_credentials: Vec<CredentialExpiry>,
+    pub recent_changes: Vec<AuditEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Spend {
+    pub month_to_date: f64,
+    pub projected_total: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projected_overrun: Option<NaiveDate>,
+    pub currency: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdleSummary {
+    pub id: String,
+    pub name: String,
+    pub resource_type: String,
+    pub reason: String,
+    pub monthly_cost: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CredentialExpiry {
+    pub credential: String,
+    pub kind: String,
+    pub expires: DateTime<Utc>,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn overview(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Overview {
+        provider,
+        budget,
+        age_threshold,
+        utilization_threshold,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!(
+            "🧭 Building overview for {}...",
+            provider.to_string().green()
+        );
+
+        let mut overview = Overview {
+            provider: provider.to_string(),
+            generated_at: Utc::now(),
+            resources: BTreeMap::new(),
+            spend: None,
+            top_idle: Vec::new(),
+            expiring_credentials: Vec::new(),
+            recent_changes: Vec::new(),
+        };
+
+        // Each section is independent; one that fails (usually missing
+        // permissions) is reported and left empty
+        match providers::get(provider).list(&ResourceType::All).await {
+            Ok(resources) => {
+                for resource in resources {
+                    *overview
+                        .resources
+                        .entry(resource.resource_type)
+                        .or_default()
+                        .entry(resource.state)
+                        .or_default() += 1;
+                }
+            }
+            Err(e) => tracing::warn!("⚠️  Skipping resource counts: {}", e),
+        }
+
+        match spend(provider, *budget).await {
+            Ok(spend) => overview.spend = Some(spend),
+            Err(e) => tracing::warn!("⚠️  Skipping spend: {}", e),
+        }
+
+        match top_idle(provider, *age_threshold, *utilization_threshold).await {
+            Ok(idle) => overview.top_idle = idle,
+            Err(e) => tracing::warn!("⚠️  Skipping idle resources: {}", e),
+        }
+
+        match expiring_credentials(provider, profile).await {
+            Ok(expiring) => overview.expiring_credentials = expiring,
+            Err(e) => tracing::warn!("⚠️  Skipping credential expiries: {}", e),
+        }
+
+        match recent_changes(provider) {
+            Ok(changes) => overview.recent_changes = changes,
+            Err(e) => tracing::warn!("⚠️  Skipping recent changes: {}", e),
+        }
+
+        output_overview(&overview, format)?;
+    }
+
+    Ok(())
+}
+
+/// Month-to-date spend and where the month is heading.
+async fn spend(provider: &CloudProvider, budget: Option<f64>) -> Result<Spend, AppError> {
+    let today = Utc::now().date_naive();
+    let (month_to_date, forecast, currency) = match provider {
+        CloudProvider::Aws => {
+            let config = clients::aws_config().await;
+            let (month_to_date, forecast) = budget::aws_spend(&config, today).await?;
+            (month_to_date, forecast, "USD".to_string())
+        }
+        _ => {
+            let (start, end) = determine_date_range(&None, &None)?;
+            let report = providers::get(provider)
+                .cost_report(&start, &end, &None)
+                .await?;
+            let days = (end - start).num_days();
+            (
+                budget::month_to_date_from_average(report.total_cost, days, today),
+                None,
+                report.currency,
+            )
+        }
+    };
+
+    // Without a budget there is nothing to overrun
+    let burn = budget::project(budget.unwrap_or(0.0), month_to_date, forecast, today);
+    Ok(Spend {
+        month_to_date,
+        projected_total: burn.projected_total,
+        budget,
+        projected_overrun: burn.projected_overrun,
+        currency,
+    })
+}
+
+/// The idle resources cleanup would act on, most expensive first.
+async fn top_idle(
+    provider: &CloudProvider,
+    age_threshold: u32,
+    utilization_threshold: f64,
+) -> Result<Vec<IdleSummary>, AppError> {
+    let resources = providers::get(provider)
+        .find_cleanup_candidates(&ResourceType::All, age_threshold, utilization_threshold)
+        .await?;
+    let mut actions =
+        analyze_cleanup_actions(&resources, provider, &TagSelection::default(), None)?;
+    actions.sort_by(|a, b| b.estimated_savings.total_cmp(&a.estimated_savings));
+
+    Ok(actions
+        .into_iter()
+        .take(TOP_N)
+        .map(|action| IdleSummary {
+            id: action.resource.id,
+            name: action.resource.name,
+            resource_type: action.resource.resource_type,
+            reason: action.reason,
+            monthly_cost: action.estimated_savings,
+        })
+        .collect())
+}
+
+/// Credentials that expire, or are due for rotation, within
+/// [`EXPIRY_WINDOW_DAYS`], soonest first.
+async fn expiring_credentials(
+    provider: &CloudProvider,
+    profile: &str,
+) -> Result<Vec<CredentialExpiry>, AppError> {
+    let cutoff = Utc::now() + Duration::days(EXPIRY_WINDOW_DAYS);
+    let mut expiring = Vec::new();
+
+    if let Some(expires) = stored_credentials(provider, profile)?
+        .and_then(|c| c.expires_at)
+        .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+    {
+        expiring.push(CredentialExpiry {
+            credential: format!("profile {}", profile),
+            kind: "actlog login".to_string(),
+            expires: expires.with_timezone(&Utc),
+        });
+    }
+
+    match provider {
+        CloudProvider::Aws => expiring.extend(aws_expiring_credentials().await?),
+        CloudProvider::Gcp => expiring.extend(gcp_expiring_keys().await?),
+        // Service principal secrets live in Microsoft Graph, which actlog
+        // doesn't query
+        CloudProvider::Azure => {}
+    }
+
+    expiring.retain(|c| c.expires <= cutoff);
+    expiring.sort_by_key(|c| c.expires);
+    Ok(expiring)
+}
+
+/// The session actlog runs with, when it is temporary, and active IAM
+/// access keys by the date they are due for rotation.
+async fn aws_expiring_credentials() -> Result<Vec<CredentialExpiry>, AppError> {
+    let config = clients::aws_config().await;
+    let mut expiring = Vec::new();
+
+    if let Some(provider) = config.credentials_provider() {
+        let credentials = provider
+            .provide_credentials()
+            .await
+            .map_err(|e| AppError::AuthenticationError(e.to_string()))?;
+        if let Some(expiry) = credentials.expiry() {
+            expiring.push(CredentialExpiry {
+                credential: credentials.access_key_id().to_string(),
+                kind: "AWS session".to_string(),
+                expires: DateTime::<Utc>::from(expiry),
+            });
+        }
+    }
+
+    let client = clients::get::<aws_sdk_iam::Client>(&config).await;
+    let mut users = client.list_users().into_paginator().send();
+    loop {
+        executor::throttle("iam").await;
+        let Some(page) = users.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("iam:ListUsers", e))?;
+
+        for user in page.users() {
+            let user_name = user.user_name();
+            executor::throttle("iam").await;
+            let keys = client
+                .list_access_keys()
+                .user_name(user_name)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("iam:ListAccessKeys", e).for_resource(user_name))?;
+
+            for key in keys.access_key_metadata() {
+                if key.status() != Some(&aws_sdk_iam::types::StatusType::Active) {
+                    continue;
+                }
+                let Some(created) = aws_datetime(key.create_date()) else {
+                    continue;
+                };
+                expiring.push(CredentialExpiry {
+                    credential: format!(
+                        "{} ({})",
+                        key.access_key_id().unwrap_or_default(),
+                        user_name
+                    ),
+                    kind: "IAM access key rotation".to_string(),
+                    expires: created + Duration::days(ACCESS_KEY_ROTATION_DAYS),
+                });
+            }
+        }
+    }
+
+    Ok(expiring)
+}
+
+/// User-managed service account keys with an expiry date.
+async fn gcp_expiring_keys() -> Result<Vec<CredentialExpiry>, AppError> {
+    let project_id = gcp::project_id()?;
+    let pages = gcp::list_pages(
+        "iam.serviceAccounts.list",
+        &format!("{}/projects/{}/serviceAccounts", IAM_API, project_id),
+    )
+    .await?;
+
+    let mut expiring = Vec::new();
+    for account in pages
+        .iter()
+        .filter_map(|page| page["accounts"].as_array())
+        .flatten()
+    {
+        let Some(name) = account["name"].as_str() else {
+            continue;
+        };
+        let keys = gcp::get(
+            "iam.serviceAccounts.keys.list",
+            &format!("{}/{}/keys?keyTypes=USER_MANAGED", IAM_API, name),
+        )
+        .await?;
+
+        for key in keys["keys"].as_array().into_iter().flatten() {
+            if key["disabled"].as_bool().unwrap_or(false) {
+                continue;
+            }
+            // Keys without an expiry report the far future
+            let Some(expires) = key["validBeforeTime"]
+                .as_str()
+                .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            else {
+                continue;
+            };
+            expiring.push(CredentialExpiry {
+                credential: format!(
+                    "{} ({})",
+                    gcp::short_name(key["name"].as_str().unwrap_or_default()),
+                    account["email"].as_str().unwrap_or_default()
+                ),
+                kind: "service account key".to_string(),
+                expires: expires.with_timezone(&Utc),
+            });
+        }
+    }
+
+    Ok(expiring)
+}
+
+/// The last cleanup and scaling changes made against `provider`, newest first.
+fn recent_changes(provider: &CloudProvider) -> Result<Vec<AuditEntry>, AppError> {
+    let provider = provider.to_string();
+    let mut entries: Vec<AuditEntry> = audit::read_entries()?
+        .into_iter()
+        .filter(|e| {
+            e.provider.eq_ignore_ascii_case(&provider)
+                && TRACKED_COMMANDS.contains(&e.command.as_str())
+        })
+        .collect();
+
+    entries.reverse();
+    entries.truncate(TOP_N);
+    Ok(entries)
+}
+
+fn output_overview(overview: &Overview, format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n📊 {} overview ({})",
+                overview.provider.bold(),
+                overview.generated_at.format("%Y-%m-%d %H:%M UTC")
+            );
+
+            println!("\n📦 Resources");
+            if overview.resources.is_empty() {
+                println!("   none found");
+            }
+            for (resource_type, states) in &overview.resources {
+                let total: usize = states.values().sum();
+                let breakdown = states
+                    .iter()
+                    .map(|(state, count)| format!("{} {}", count, state))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!("   {:<25} {:>5}  ({})", resource_type, total, breakdown);
+            }
+
+            if let Some(spend) = &overview.spend {
+                println!("\n💰 Spend this month");
+                println!(
+                    "   Month to date: {:.2} {}",
+                    spend.month_to_date, spend.currency
+                );
+                println!(
+                    "   Projected:     {:.2} {}",
+                    spend.projected_total, spend.currency
+                );
+                if let Some(budget) = spend.budget {
+                    let status = if spend.month_to_date > budget {
+                        "over budget".red()
+                    } else if let Some(date) = spend.projected_overrun {
+                        format!("on pace to exceed it by {}", date).yellow()
+                    } else {
+                        "on track".green()
+                    };
+                    println!(
+                        "   Budget:        {:.2} {} ({})",
+                        budget, spend.currency, status
+                    );
+                }
+            }
+
+            println!("\n💤 Top idle resources");
+            if overview.top_idle.is_empty() {
+                println!("   none");
+            }
+            for idle in &overview.top_idle {
+                println!(
+                    "   {:<22} {:<20} {:>10.2}/mo  {}",
+                    idle.id, idle.resource_type, idle.monthly_cost, idle.reason
+                );
+            }
+
+            println!(
+                "\n🔑 Credentials expiring within {} days",
+                EXPIRY_WINDOW_DAYS
+            );
+            if overview.expiring_credentials.is_empty() {
+                println!("   none");
+            }
+            for expiry in &overview.expiring_credentials {
+                let date = expiry.expires.format("%Y-%m-%d").to_string();
+                let date = if expiry.expires < Utc::now() {
+                    date.red()
+                } else {
+                    date.yellow()
+                };
+                println!("   {}  {:<25} {}", date, expiry.kind, expiry.credential);
+            }
+
+            println!("\n🕘 Recent cleanup and scaling changes");
+            if overview.recent_changes.is_empty() {
+                println!("   none recorded");
+            }
+            for change in &overview.recent_changes {
+                let status = if change.succeeded { "✅" } else { "❌" };
+                println!(
+                    "   {} {}  {:<16} {:<20} {}",
+                    status,
+                    change.timestamp.format("%Y-%m-%d %H:%M"),
+                    change.command,
+                    change.action,
+                    change.resource_id
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(overview)?);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, overview)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Section,Item,Detail,Value");
+            for (resource_type, states) in &overview.resources {
+                for (state, count) in states {
+                    println!("resources,{},{},{}", resource_type, state, count);
+                }
+            }
+            if let Some(spend) = &overview.spend {
+                println!(
+                    "spend,month_to_date,{},{:.2}",
+                    spend.currency, spend.month_to_date
+                );
+                println!(
+                    "spend,projected,{},{:.2}",
+                    spend.currency, spend.projected_total
+                );
+                if let Some(budget) = spend.budget {
+                    println!("spend,budget,{},{:.2}", spend.currency, budget);
+                }
+            }
+            for idle in &overview.top_idle {
+                println!(
+                    "idle,{},{},{:.2}",
+                    idle.id, idle.resource_type, idle.monthly_cost
+                );
+            }
+            for expiry in &overview.expiring_credentials {
+                println!(
+                    "credentials,{},{},{}",
+                    expiry.credential,
+                    expiry.kind,
+                    expiry.expires.to_rfc3339()
+                );
+            }
+            for change in &overview.recent_changes {
+                println!(
+                    "changes,{},{},{}",
+                    change.resource_id,
+                    change.action,
+                    change.timestamp.to_rfc3339()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
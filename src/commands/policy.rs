@@ -0,0 +1,429 @@
+use crate::cleanup_policy::CleanupPolicy;
+use crate::cli::{CloudProvider, Commands, OutputFormat, PolicyAction};
+use crate::clients;
+use crate::commands::list::{collect_inventory, ResourceSummary};
+use crate::error::AppError;
+use crate::executor;
+use crate::query::{self, Expr};
+use crate::report;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const ALL_USERS_GROUP: &str = "http://acs.amazonaws.com/groups/global/AllUsers";
+
+/// A governance rule loaded from a YAML file in the policies directory.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Policy {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_severity")]
+    pub severity: String,
+    /// Query expression selecting the resources the policy applies to
+    pub resource: String,
+    pub rule: PolicyRule,
+}
+
+/// Every set field must hold for a resource to pass.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PolicyRule {
+    /// Tag keys every selected resource must carry
+    #[serde(default)]
+    pub required_tags: Vec<String>,
+    /// Fail storage buckets that grant public access
+    #[serde(default)]
+    pub deny_public: bool,
+    /// Largest allowed instance size, e.g. "large" or "2xlarge"
+    pub max_instance_size: Option<String>,
+    /// Query expression each selected resource must also match
+    pub condition: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyStatus {
+    Pass,
+    Fail,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PolicyResult {
+    pub policy: String,
+    pub severity: String,
+    pub provider: String,
+    pub resource_id: String,
+    pub status: PolicyStatus,
+    pub message: String,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn policy(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Policy { action } = cmd {
+        match action {
+            PolicyAction::Check {
+                policies,
+                provider,
+                format,
+            } => {
+                let loaded = load_policies(Path::new(policies))?;
+                if loaded.is_empty() {
+                    println!("ℹ️  No policies found in {}", policies);
+                    return Ok(());
+                }
+
+                tracing::info!("🛡️  Checking {} policies...", loaded.len());
+
+                let providers = match provider {
+                    Some(provider) => vec![provider.clone()],
+                    None => vec![CloudProvider::Aws, CloudProvider::Gcp, CloudProvider::Azure],
+                };
+
+                let inventories = executor::for_each_provider(&providers, |provider| async move {
+                    collect_inventory(&provider).await
+                })
+                .await;
+
+                let mut results = Vec::new();
+                for (provider, inventory) in inventories {
+                    let resources = match inventory {
+                        Ok(resources) => resources,
+                        Err(e) => {
+                            tracing::warn!("⚠️  Skipping {}: {}", provider.to_string().yellow(), e);
+                            continue;
+                        }
+                    };
+                    results.extend(evaluate_policies(&loaded, &provider, &resources).await?);
+                }
+
+                output_results(&results, format)?;
+
+                let violations = results
+                    .iter()
+                    .filter(|r| r.status != PolicyStatus::Pass)
+                    .count();
+                if violations > 0 {
+                    return Err(AppError::PolicyViolations(violations));
+                }
+
+                println!("\n✅ All resources comply with every policy.");
+            }
+            PolicyAction::Validate { path } => validate(Path::new(path))?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a cleanup policy, or every policy in a directory, and report any
+/// mistakes without touching a cloud.
+fn validate(path: &Path) -> Result<(), AppError> {
+    if !path.is_dir() {
+        let policy = CleanupPolicy::load(path)?;
+        println!(
+            "✅ {} is a valid cleanup policy with {} rules:",
+            path.display(),
+            policy.rules.len()
+        );
+        for rule in &policy.rules {
+            println!(
+                "   {:<10} {}",
+                format!("{:?}", rule.action).to_lowercase(),
+                rule.name
+            );
+        }
+        return Ok(());
+    }
+
+    let policies = load_policies(path)?;
+    for policy in &policies {
+        let expressions = [
+            ("resource", Some(&policy.resource)),
+            ("condition", policy.rule.condition.as_ref()),
+        ];
+        for (field, expression) in expressions {
+            if let Some(expression) = expression {
+                query::parse(expression).map_err(|e| {
+                    AppError::ConfigurationError(format!(
+                        "Policy '{}' {}: {}",
+                        policy.name, field, e
+                    ))
+                })?;
+            }
+        }
+    }
+    println!(
+        "✅ {} policies in {} are valid",
+        policies.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Policies from every `.yaml`/`.yml` file in `dir`; each file holds a list.
+pub fn load_policies(dir: &Path) -> Result<Vec<Policy>, AppError> {
+    if !dir.is_dir() {
+        return Err(AppError::ConfigurationError(format!(
+            "Policy directory {} does not exist",
+            dir.display()
+        )));
+    }
+
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("yaml") | Some("yml")
+            )
+        })
+        .collect();
+    paths.sort();
+
+    let mut policies = Vec::new();
+    for path in paths {
+        let content = fs::read_to_string(&path)?;
+        let file_policies: Vec<Policy> = serde_yaml::from_str(&content).map_err(|e| {
+            AppError::ConfigurationError(format!("Invalid policy file {}: {}", path.display(), e))
+        })?;
+        policies.extend(file_policies);
+    }
+
+    Ok(policies)
+}
+
+async fn evaluate_policies(
+    policies: &[Policy],
+    provider: &CloudProvider,
+    resources: &[ResourceSummary],
+) -> Result<Vec<PolicyResult>, AppError> {
+    let provider_name = provider.to_string();
+    let s3_client = match provider {
+        CloudProvider::Aws => {
+            let config = clients::aws_config().await;
+            Some(clients::get::<aws_sdk_s3::Client>(&config).await)
+        }
+        _ => None,
+    };
+
+    let mut results = Vec::new();
+    for policy in policies {
+        let selector = query::parse(&policy.resource).map_err(|e| {
+            AppError::ConfigurationError(format!("Policy '{}' resource: {}", policy.name, e))
+        })?;
+        let condition = policy
+            .rule
+            .condition
+            .as_deref()
+            .map(query::parse)
+            .transpose()
+            .map_err(|e| {
+                AppError::ConfigurationError(format!("Policy '{}' condition: {}", policy.name, e))
+            })?;
+
+        for resource in resources
+            .iter()
+            .filter(|r| selector.matches(&provider_name, r))
+        {
+            let (status, message) = match check_rule(
+                &policy.rule,
+                condition.as_ref(),
+                &provider_name,
+                resource,
+                s3_client.as_ref(),
+            )
+            .await
+            {
+                Ok(failures) if failures.is_empty() => (PolicyStatus::Pass, String::new()),
+                Ok(failures) => (PolicyStatus::Fail, failures.join("; ")),
+                Err(e) => (PolicyStatus::Error, e.to_string()),
+            };
+
+            results.push(PolicyResult {
+                policy: policy.name.clone(),
+                severity: policy.severity.clone(),
+                provider: provider_name.clone(),
+                resource_id: resource.id.clone(),
+                status,
+                message,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+/// Human-readable reasons `resource` breaks `rule`; empty when it complies.
+async fn check_rule(
+    rule: &PolicyRule,
+    condition: Option<&Expr>,
+    provider: &str,
+    resource: &ResourceSummary,
+    s3_client: Option<&aws_sdk_s3::Client>,
+) -> Result<Vec<String>, AppError> {
+    let mut failures = Vec::new();
+
+    let missing: Vec<&str> = rule
+        .required_tags
+        .iter()
+        .filter(|tag| !resource.tags.contains_key(*tag))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        failures.push(format!("missing tags: {}", missing.join(", ")));
+    }
+
+    if let Some(max_size) = &rule.max_instance_size {
+        if let Some(instance_type) = resource.additional_info.get("Instance Type") {
+            let size = instance_type.rsplit('.').next().unwrap_or(instance_type);
+            if size_rank(size) > size_rank(max_size) {
+                failures.push(format!(
+                    "instance type {} exceeds max size {}",
+                    instance_type, max_size
+                ));
+            }
+        }
+    }
+
+    if rule.deny_public && resource.resource_type == "S3 Bucket" {
+        if let Some(client) = s3_client {
+            if is_bucket_public(client, &resource.id).await? {
+                failures.push("bucket is publicly accessible".to_string());
+            }
+        }
+    }
+
+    if let Some(condition) = condition {
+        if !condition.matches(provider, resource) {
+            failures.push("condition not met".to_string());
+        }
+    }
+
+    Ok(failures)
+}
+
+/// Ordering of instance sizes: nano < micro < small < medium < large < xlarge < 2xlarge ...
+fn size_rank(size: &str) -> u32 {
+    let size = size.to_lowercase();
+    match size.as_str() {
+        "nano" => 0,
+        "micro" => 1,
+        "small" => 2,
+        "medium" => 3,
+        "large" => 4,
+        "xlarge" => 5,
+        "metal" => u32::MAX,
+        _ => size
+            .strip_suffix("xlarge")
+            .and_then(|n| n.parse::<u32>().ok())
+            .map(|n| 4 + n)
+            .unwrap_or(u32::MAX),
+    }
+}
+
+async fn is_bucket_public(client: &aws_sdk_s3::Client, bucket: &str) -> Result<bool, AppError> {
+    // A bucket policy can make the bucket public on its own
+    executor::throttle("s3").await;
+    if let Ok(response) = client
+        .get_bucket_policy_status()
+        .bucket(bucket)
+        .send()
+        .await
+    {
+        if response
+            .policy_status()
+            .and_then(|s| s.is_public())
+            .unwrap_or(false)
+        {
+            return Ok(true);
+        }
+    }
+
+    executor::throttle("s3").await;
+    let acl = client
+        .get_bucket_acl()
+        .bucket(bucket)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("s3:GetBucketAcl", e))?;
+
+    Ok(acl
+        .grants()
+        .iter()
+        .any(|grant| grant.grantee().and_then(|g| g.uri()) == Some(ALL_USERS_GROUP)))
+}
+
+fn default_severity() -> String {
+    "medium".to_string()
+}
+
+fn output_results(results: &[PolicyResult], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            let mut policies: Vec<&str> = Vec::new();
+            for result in results {
+                if !policies.contains(&result.policy.as_str()) {
+                    policies.push(&result.policy);
+                }
+            }
+
+            for policy in policies {
+                let policy_results: Vec<&PolicyResult> =
+                    results.iter().filter(|r| r.policy == policy).collect();
+                let failing: Vec<&&PolicyResult> = policy_results
+                    .iter()
+                    .filter(|r| r.status != PolicyStatus::Pass)
+                    .collect();
+
+                if failing.is_empty() {
+                    println!(
+                        "\n✅ {} ({} resources compliant)",
+                        policy.green(),
+                        policy_results.len()
+                    );
+                    continue;
+                }
+
+                println!(
+                    "\n❌ {} ({} of {} resources failing)",
+                    policy.red(),
+                    failing.len(),
+                    policy_results.len()
+                );
+                for result in failing {
+                    let status = match result.status {
+                        PolicyStatus::Error => "ERROR".yellow(),
+                        _ => result.severity.to_uppercase().red(),
+                    };
+                    println!(
+                        "   [{}] {} {}: {}",
+                        status, result.provider, result.resource_id, result.message
+                    );
+                }
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(results)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, results)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Policy,Severity,Provider,ResourceId,Status,Message");
+            for r in results {
+                println!(
+                    "{},{},{},{},{:?},\"{}\"",
+                    r.policy, r.severity, r.provider, r.resource_id, r.status, r.message
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
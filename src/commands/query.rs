@@ -0,0 +1,128 @@
+use crate::cli::{CloudProvider, Commands, OutputFormat};
+use crate::commands::list::{collect_inventory, ResourceSummary};
+use crate::error::AppError;
+use crate::executor;
+use crate::query;
+use crate::report;
+use colored::*;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct QueryMatch {
+    provider: String,
+    #[serde(flatten)]
+    resource: ResourceSummary,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn query_resources(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Query {
+        expression,
+        provider,
+        format,
+    } = cmd
+    {
+        // Parse before fetching so typos fail fast
+        let expr = query::parse(expression)?;
+
+        let providers = match provider {
+            Some(provider) => vec![provider.clone()],
+            None => vec![CloudProvider::Aws, CloudProvider::Gcp, CloudProvider::Azure],
+        };
+
+        let inventories = executor::for_each_provider(&providers, |provider| async move {
+            collect_inventory(&provider).await
+        })
+        .await;
+
+        let mut matches = Vec::new();
+        for (provider, inventory) in inventories {
+            let resources = match inventory {
+                Ok(resources) => resources,
+                Err(e) => {
+                    tracing::warn!("⚠️  Skipping {}: {}", provider.to_string().yellow(), e);
+                    continue;
+                }
+            };
+
+            let provider_name = provider.to_string();
+            matches.extend(
+                resources
+                    .into_iter()
+                    .filter(|resource| expr.matches(&provider_name, resource))
+                    .map(|resource| QueryMatch {
+                        provider: provider_name.clone(),
+                        resource,
+                    }),
+            );
+        }
+
+        if matches.is_empty() {
+            return Err(AppError::ResourceNotFound(format!(
+                "no resources match '{}'",
+                expression
+            )));
+        }
+
+        output_matches(&matches, format)?;
+    }
+
+    Ok(())
+}
+
+fn output_matches(matches: &[QueryMatch], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n{:<8} {:<22} {:<20} {:<18} {:<12} {:<15}",
+                "Provider", "ID", "Name", "Type", "State", "Region"
+            );
+            println!("{:-<100}", "");
+
+            for m in matches {
+                println!(
+                    "{:<8} {:<22} {:<20} {:<18} {:<12} {:<15}",
+                    m.provider,
+                    m.resource.id,
+                    m.resource.name,
+                    m.resource.resource_type,
+                    m.resource.state,
+                    m.resource.region
+                );
+            }
+
+            println!("\n📊 {} matching resources", matches.len());
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(matches)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, matches)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Provider,Account,ID,Name,Type,State,Region,CreationDate");
+            for m in matches {
+                println!(
+                    "{},{},{},{},{},{},{},{}",
+                    m.provider,
+                    m.resource.account.as_deref().unwrap_or(""),
+                    m.resource.id,
+                    m.resource.name,
+                    m.resource.resource_type,
+                    m.resource.state,
+                    m.resource.region,
+                    m.resource
+                        .creation_date
+                        .map(|d| d.format("%Y-%m-%d").to_string())
+                        .unwrap_or_default()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
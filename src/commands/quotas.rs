@@ -0,0 +1,409 @@
+use crate::azure;
+use crate::cli::{CloudProvider, Commands, OutputFormat};
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::regions;
+use crate::report;
+use aws_config::SdkConfig;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub name: String,
+    pub region: String,
+    pub usage: f64,
+    pub limit: f64,
+    pub percent: f64,
+}
+
+impl QuotaUsage {
+    fn new(name: &str, region: &str, usage: f64, limit: f64) -> Self {
+        let percent = if limit > 0.0 {
+            usage / limit * 100.0
+        } else {
+            0.0
+        };
+        QuotaUsage {
+            name: name.to_string(),
+            region: region.to_string(),
+            usage,
+            limit,
+            percent,
+        }
+    }
+}
+
+/// A quota tracked by Service Quotas, with the AWS default used when the
+/// account can't read its applied value.
+struct AwsQuota {
+    name: &'static str,
+    service_code: &'static str,
+    quota_code: &'static str,
+    default: f64,
+}
+
+const AWS_VCPU_QUOTA: AwsQuota = AwsQuota {
+    name: "On-Demand Standard vCPUs",
+    service_code: "ec2",
+    quota_code: "L-1216C47A",
+    default: 5.0,
+};
+
+const AWS_EIP_QUOTA: AwsQuota = AwsQuota {
+    name: "Elastic IPs",
+    service_code: "ec2",
+    quota_code: "L-0263D0A3",
+    default: 5.0,
+};
+
+const AWS_VPC_QUOTA: AwsQuota = AwsQuota {
+    name: "VPCs",
+    service_code: "vpc",
+    quota_code: "L-F678F1CE",
+    default: 5.0,
+};
+
+const AWS_BUCKET_QUOTA: AwsQuota = AwsQuota {
+    name: "S3 Buckets",
+    service_code: "s3",
+    quota_code: "L-DC2B2D3D",
+    default: 100.0,
+};
+
+/// Instance families counted against the On-Demand Standard quota, and
+/// families starting with those letters that have quotas of their own
+const STANDARD_FAMILIES: &[char] = &['a', 'c', 'd', 'h', 'i', 'm', 'r', 't', 'z'];
+const NON_STANDARD_PREFIXES: &[&str] = &["dl", "hpc", "inf", "mac", "trn"];
+
+/// Compute Engine quotas reported for the project, and for each region in use
+const GCP_PROJECT_QUOTAS: &[&str] = &["NETWORKS", "FIREWALLS", "SNAPSHOTS", "IMAGES"];
+const GCP_REGION_QUOTAS: &[&str] = &[
+    "CPUS",
+    "IN_USE_ADDRESSES",
+    "STATIC_ADDRESSES",
+    "DISKS_TOTAL_GB",
+    "SSD_TOTAL_GB",
+];
+
+/// Azure usages reported for each location the subscription runs VMs in
+const AZURE_COMPUTE_USAGES: &[&str] = &["cores", "virtualMachines"];
+const AZURE_NETWORK_USAGES: &[&str] = &["PublicIPAddresses", "VirtualNetworks"];
+
+#[allow(unused_variables, dead_code)]
+pub async fn check_quotas(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Quotas {
+        provider,
+        warn_percent,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!(
+            "📏 Checking {} service quotas...",
+            provider.to_string().green()
+        );
+
+        let quotas = match provider {
+            CloudProvider::Aws => {
+                let config = clients::aws_config().await;
+                get_aws_quotas(&config).await?
+            }
+            CloudProvider::Gcp => get_gcp_quotas().await?,
+            CloudProvider::Azure => get_azure_quotas().await?,
+        };
+
+        output_quotas(&quotas, format, *warn_percent)?;
+
+        let near_limit: Vec<&QuotaUsage> = quotas
+            .iter()
+            .filter(|q| q.percent >= *warn_percent)
+            .collect();
+        if near_limit.is_empty() {
+            tracing::info!("✅ All quotas are below {:.0}% utilization.", warn_percent);
+        } else {
+            tracing::warn!("⚠️  Quotas above {:.0}%:", warn_percent);
+            for quota in near_limit {
+                tracing::warn!(
+                    "   {}: {:.0} of {:.0} ({:.1}%) - request an increase before scaling up",
+                    quota.name.red(),
+                    quota.usage,
+                    quota.limit,
+                    quota.percent
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_aws_quotas(config: &SdkConfig) -> Result<Vec<QuotaUsage>, AppError> {
+    let region = regions::config_region(config);
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    let s3_client = clients::get::<aws_sdk_s3::Client>(config).await;
+    let quotas_client = clients::get::<aws_sdk_servicequotas::Client>(config).await;
+
+    executor::throttle("ec2").await;
+    let instances = ec2_client
+        .describe_instances()
+        .filters(
+            aws_sdk_ec2::types::Filter::builder()
+                .name("instance-state-name")
+                .values("pending")
+                .values("running")
+                .build(),
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeInstances", e))?;
+    // Spot and other families have quotas of their own
+    let vcpus: i32 = instances
+        .reservations()
+        .iter()
+        .flat_map(|r| r.instances())
+        .filter(|i| i.instance_lifecycle().is_none())
+        .filter(|i| {
+            i.instance_type()
+                .is_some_and(|t| is_standard_family(t.as_str()))
+        })
+        .filter_map(|i| i.cpu_options())
+        .map(|cpu| cpu.core_count().unwrap_or(1) * cpu.threads_per_core().unwrap_or(1))
+        .sum();
+
+    executor::throttle("ec2").await;
+    let addresses = ec2_client
+        .describe_addresses()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeAddresses", e))?;
+
+    executor::throttle("ec2").await;
+    let vpcs = ec2_client
+        .describe_vpcs()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeVpcs", e))?;
+
+    executor::throttle("s3").await;
+    let buckets = s3_client
+        .list_buckets()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("s3:ListBuckets", e))?;
+
+    Ok(vec![
+        QuotaUsage::new(
+            AWS_VCPU_QUOTA.name,
+            &region,
+            vcpus as f64,
+            aws_quota_limit(&quotas_client, &AWS_VCPU_QUOTA).await,
+        ),
+        QuotaUsage::new(
+            AWS_EIP_QUOTA.name,
+            &region,
+            addresses.addresses().len() as f64,
+            aws_quota_limit(&quotas_client, &AWS_EIP_QUOTA).await,
+        ),
+        QuotaUsage::new(
+            AWS_VPC_QUOTA.name,
+            &region,
+            vpcs.vpcs().len() as f64,
+            aws_quota_limit(&quotas_client, &AWS_VPC_QUOTA).await,
+        ),
+        QuotaUsage::new(
+            AWS_BUCKET_QUOTA.name,
+            "global",
+            buckets.buckets().len() as f64,
+            aws_quota_limit(&quotas_client, &AWS_BUCKET_QUOTA).await,
+        ),
+    ])
+}
+
+async fn aws_quota_limit(client: &aws_sdk_servicequotas::Client, quota: &AwsQuota) -> f64 {
+    executor::throttle("servicequotas").await;
+    let applied = client
+        .get_service_quota()
+        .service_code(quota.service_code)
+        .quota_code(quota.quota_code)
+        .send()
+        .await
+        .ok()
+        .and_then(|r| r.quota().and_then(|q| q.value()));
+
+    match applied {
+        Some(value) => value,
+        None => {
+            tracing::warn!(
+                "⚠️  Could not read {} quota, assuming AWS default of {:.0}",
+                quota.name,
+                quota.default
+            );
+            quota.default
+        }
+    }
+}
+
+/// Whether `instance_type` counts against the On-Demand Standard quota
+/// (A, C, D, H, I, M, R, T and Z families).
+fn is_standard_family(instance_type: &str) -> bool {
+    instance_type.starts_with(STANDARD_FAMILIES)
+        && !NON_STANDARD_PREFIXES
+            .iter()
+            .any(|prefix| instance_type.starts_with(prefix))
+}
+
+/// Project-wide Compute Engine quotas, and the regional ones of every region
+/// with something in use.
+async fn get_gcp_quotas() -> Result<Vec<QuotaUsage>, AppError> {
+    let project_id = gcp::project_id()?;
+
+    let url = format!("{}/projects/{}", gcp::COMPUTE_API, project_id);
+    let project = gcp::get("compute.projects.get", &url).await?;
+    let mut quotas = gcp_quota_usages(&project["quotas"], "global", GCP_PROJECT_QUOTAS);
+
+    let url = format!("{}/projects/{}/regions", gcp::COMPUTE_API, project_id);
+    for page in gcp::list_pages("compute.regions.list", &url).await? {
+        for region in page["items"].as_array().into_iter().flatten() {
+            let name = region["name"].as_str().unwrap_or_default();
+            // A row per quota of every region would bury the ones that matter
+            quotas.extend(
+                gcp_quota_usages(&region["quotas"], name, GCP_REGION_QUOTAS)
+                    .into_iter()
+                    .filter(|quota| quota.usage > 0.0),
+            );
+        }
+    }
+
+    Ok(quotas)
+}
+
+fn gcp_quota_usages(quotas: &Value, region: &str, metrics: &[&str]) -> Vec<QuotaUsage> {
+    quotas
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|quota| {
+            let metric = quota["metric"].as_str()?;
+            metrics.contains(&metric).then(|| {
+                QuotaUsage::new(
+                    metric,
+                    region,
+                    quota["usage"].as_f64().unwrap_or(0.0),
+                    quota["limit"].as_f64().unwrap_or(0.0),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Compute and network usages in each location the subscription has VMs in.
+async fn get_azure_quotas() -> Result<Vec<QuotaUsage>, AppError> {
+    let subscription_id = azure::subscription_id()?;
+    let locations: BTreeSet<String> = azure::virtual_machines()
+        .await?
+        .iter()
+        .filter_map(|vm| vm["location"].as_str().map(str::to_string))
+        .collect();
+    if locations.is_empty() {
+        tracing::info!("ℹ️  No virtual machines found, so there are no locations to check");
+    }
+
+    let mut quotas = Vec::new();
+    for location in &locations {
+        for (provider, api_version, names) in [
+            (
+                "Microsoft.Compute",
+                azure::COMPUTE_API_VERSION,
+                AZURE_COMPUTE_USAGES,
+            ),
+            (
+                "Microsoft.Network",
+                azure::NETWORK_API_VERSION,
+                AZURE_NETWORK_USAGES,
+            ),
+        ] {
+            let url = format!(
+                "{}/subscriptions/{}/providers/{}/locations/{}/usages?api-version={}",
+                azure::MANAGEMENT_API,
+                subscription_id,
+                provider,
+                location,
+                api_version
+            );
+            let usages = azure::list(&format!("{}/locations/usages/read", provider), &url).await?;
+            for usage in &usages {
+                let Some(name) = usage["name"]["value"].as_str() else {
+                    continue;
+                };
+                if !names.contains(&name) {
+                    continue;
+                }
+                quotas.push(QuotaUsage::new(
+                    usage["name"]["localizedValue"].as_str().unwrap_or(name),
+                    location,
+                    usage["currentValue"].as_f64().unwrap_or(0.0),
+                    usage["limit"].as_f64().unwrap_or(0.0),
+                ));
+            }
+        }
+    }
+
+    Ok(quotas)
+}
+
+fn output_quotas(
+    quotas: &[QuotaUsage],
+    format: &OutputFormat,
+    warn_percent: f64,
+) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n{:<28} {:<15} {:<10} {:<10} {:<10}",
+                "Quota", "Region", "Usage", "Limit", "Used (%)"
+            );
+            println!("{:-<75}", "");
+
+            for quota in quotas {
+                let percent = format!("{:.1}", quota.percent);
+                let percent = if quota.percent >= warn_percent {
+                    percent.red()
+                } else if quota.percent >= warn_percent * 0.75 {
+                    percent.yellow()
+                } else {
+                    percent.green()
+                };
+                println!(
+                    "{:<28} {:<15} {:<10.0} {:<10.0} {:<10}",
+                    quota.name, quota.region, quota.usage, quota.limit, percent
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(quotas)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, quotas)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Quota,Region,Usage,Limit,Percent");
+            for quota in quotas {
+                println!(
+                    "{},{},{:.0},{:.0},{:.1}",
+                    quota.name, quota.region, quota.usage, quota.limit, quota.percent
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
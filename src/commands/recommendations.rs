@@ -0,0 +1,541 @@
+use crate::azure;
+use crate::cli::{CloudProvider, Commands, OutputFormat, ResourceType};
+use crate::clients;
+use crate::commands::cleanup::{
+    analyze_cleanup_actions, find_aws_resources, TagSelection, DEFAULT_LOOKBACK_DAYS,
+};
+use crate::commands::ebs_optimize::analyze_aws_volumes;
+use crate::commands::snapshot_archive::analyze_aws_snapshots;
+use crate::commands::storage_analysis::analyze_aws_buckets;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::report;
+use aws_config::SdkConfig;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recommendation {
+    pub category: String,
+    pub resource_id: String,
+    pub region: String,
+    pub action: String,
+    pub monthly_savings: f64,
+    pub effort: String,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn recommendations(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Recommendations {
+        provider,
+        min_savings,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!(
+            "💡 Gathering savings recommendations for {}...",
+            provider.to_string().green()
+        );
+
+        let mut recommendations = match provider {
+            CloudProvider::Aws => {
+                let config = clients::aws_config().await;
+                gather_aws_recommendations(&config).await
+            }
+            CloudProvider::Gcp => gather_gcp_recommendations().await?,
+            CloudProvider::Azure => gather_azure_recommendations().await?,
+        };
+
+        recommendations.retain(|r| r.monthly_savings >= *min_savings);
+        recommendations.sort_by(|a, b| b.monthly_savings.total_cmp(&a.monthly_savings));
+
+        if recommendations.is_empty() {
+            println!("✅ No savings opportunities found.");
+            return Ok(());
+        }
+
+        output_recommendations(&recommendations, format)?;
+
+        let total: f64 = recommendations.iter().map(|r| r.monthly_savings).sum();
+        println!(
+            "\n💰 Total estimated monthly impact: {}",
+            format!("${:.2}", total).green()
+        );
+    }
+
+    Ok(())
+}
+
+/// Run every AWS check. A check that fails (usually missing permissions) is
+/// reported and skipped so the others still contribute.
+async fn gather_aws_recommendations(config: &SdkConfig) -> Vec<Recommendation> {
+    let mut recommendations = Vec::new();
+
+    let checks = [
+        ("idle detection", idle_recommendations(config).await),
+        ("rightsizing", rightsizing_recommendations(config).await),
+        (
+            "savings plan coverage",
+            savings_plan_recommendations(config).await,
+        ),
+        ("storage class", storage_class_recommendations(config).await),
+        ("gp2 → gp3", gp3_recommendations(config).await),
+        (
+            "snapshot archive",
+            snapshot_archive_recommendations(config).await,
+        ),
+    ];
+
+    for (name, result) in checks {
+        match result {
+            Ok(found) => recommendations.extend(found),
+            Err(e) => tracing::warn!("⚠️  Skipping {} check: {}", name.yellow(), e),
+        }
+    }
+
+    recommendations
+}
+
+async fn idle_recommendations(config: &SdkConfig) -> Result<Vec<Recommendation>, AppError> {
+    let mut resources =
+        find_aws_resources(config, &ResourceType::Ec2, 30, 10.0, DEFAULT_LOOKBACK_DAYS).await?;
+    resources.extend(
+        find_aws_resources(config, &ResourceType::S3, 30, 10.0, DEFAULT_LOOKBACK_DAYS).await?,
+    );
+
+    let actions = analyze_cleanup_actions(
+        &resources,
+        &CloudProvider::Aws,
+        &TagSelection::default(),
+        None,
+    )?;
+    Ok(actions
+        .into_iter()
+        .map(|action| Recommendation {
+            category: "Idle".to_string(),
+            action: format!(
+                "Delete {} ({})",
+                action.resource.resource_type, action.reason
+            ),
+            resource_id: action.resource.id,
+            region: action.resource.region,
+            monthly_savings: action.estimated_savings,
+            effort: "low".to_string(),
+        })
+        .collect())
+}
+
+async fn rightsizing_recommendations(config: &SdkConfig) -> Result<Vec<Recommendation>, AppError> {
+    tracing::info!("🔍 Fetching rightsizing recommendations...");
+
+    let cost_client = clients::get::<aws_sdk_costexplorer::Client>(config).await;
+    executor::throttle("costexplorer").await;
+    let response = cost_client
+        .get_rightsizing_recommendation()
+        .service("AmazonEC2")
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ce:GetRightsizingRecommendation", e))?;
+
+    let mut recommendations = Vec::new();
+    for recommendation in response.rightsizing_recommendations() {
+        let Some(instance) = recommendation.current_instance() else {
+            continue;
+        };
+        let resource_id = instance.resource_id().unwrap_or_default().to_string();
+
+        let (action, savings) = if let Some(detail) = recommendation.modify_recommendation_detail()
+        {
+            let Some(target) = detail.target_instances().first() else {
+                continue;
+            };
+            let target_type = target
+                .resource_details()
+                .and_then(|d| d.ec2_resource_details())
+                .and_then(|d| d.instance_type())
+                .unwrap_or("smaller instance");
+            (
+                format!("Resize to {}", target_type),
+                target.estimated_monthly_savings(),
+            )
+        } else if let Some(detail) = recommendation.terminate_recommendation_detail() {
+            (
+                "Terminate unused instance".to_string(),
+                detail.estimated_monthly_savings(),
+            )
+        } else {
+            continue;
+        };
+
+        recommendations.push(Recommendation {
+            category: "Rightsizing".to_string(),
+            resource_id,
+            region: String::new(),
+            action,
+            monthly_savings: parse_amount(savings),
+            effort: "medium".to_string(),
+        });
+    }
+
+    Ok(recommendations)
+}
+
+async fn savings_plan_recommendations(config: &SdkConfig) -> Result<Vec<Recommendation>, AppError> {
+    use aws_sdk_costexplorer::types::{
+        LookbackPeriodInDays, PaymentOption, SupportedSavingsPlansType, TermInYears,
+    };
+
+    tracing::info!("🔍 Checking Savings Plan coverage...");
+
+    let cost_client = clients::get::<aws_sdk_costexplorer::Client>(config).await;
+    executor::throttle("costexplorer").await;
+    let response = cost_client
+        .get_savings_plans_purchase_recommendation()
+        .savings_plans_type(SupportedSavingsPlansType::ComputeSp)
+        .term_in_years(TermInYears::OneYear)
+        .payment_option(PaymentOption::NoUpfront)
+        .lookback_period_in_days(LookbackPeriodInDays::ThirtyDays)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ce:GetSavingsPlansPurchaseRecommendation", e))?;
+
+    let Some(summary) = response
+        .savings_plans_purchase_recommendation()
+        .and_then(|r| r.savings_plans_purchase_recommendation_summary())
+    else {
+        return Ok(Vec::new());
+    };
+
+    let savings = parse_amount(summary.estimated_monthly_savings_amount());
+    if savings <= 0.0 {
+        return Ok(Vec::new());
+    }
+
+    Ok(vec![Recommendation {
+        category: "Commitment".to_string(),
+        resource_id: "account".to_string(),
+        region: "global".to_string(),
+        action: format!(
+            "Buy 1yr Compute Savings Plan at ${}/hour",
+            summary.hourly_commitment_to_purchase().unwrap_or("?")
+        ),
+        monthly_savings: savings,
+        effort: "high".to_string(),
+    }])
+}
+
+async fn storage_class_recommendations(
+    config: &SdkConfig,
+) -> Result<Vec<Recommendation>, AppError> {
+    let analyses = analyze_aws_buckets(config).await?;
+
+    Ok(analyses
+        .into_iter()
+        .filter(|a| a.monthly_savings > 0.0)
+        .map(|a| Recommendation {
+            category: "Storage class".to_string(),
+            action: a
+                .recommendation
+                .unwrap_or_else(|| "Add lifecycle rule".to_string()),
+            resource_id: a.bucket,
+            region: a.region,
+            monthly_savings: a.monthly_savings,
+            effort: "low".to_string(),
+        })
+        .collect())
+}
+
+async fn gp3_recommendations(config: &SdkConfig) -> Result<Vec<Recommendation>, AppError> {
+    tracing::info!("🔍 Looking for gp2 and over-provisioned io1/io2 volumes...");
+
+    let optimizations = analyze_aws_volumes(config).await?;
+    Ok(optimizations
+        .into_iter()
+        .map(|o| Recommendation {
+            category: "EBS gp2 → gp3".to_string(),
+            action: format!(
+                "Modify {} GiB {} volume to gp3 ({} IOPS)",
+                o.size_gb, o.current_type, o.target_iops
+            ),
+            resource_id: o.volume_id,
+            region: o.region,
+            monthly_savings: o.monthly_savings,
+            effort: "low".to_string(),
+        })
+        .collect())
+}
+
+async fn snapshot_archive_recommendations(
+    config: &SdkConfig,
+) -> Result<Vec<Recommendation>, AppError> {
+    tracing::info!("🔍 Looking for EBS snapshots to archive...");
+
+    let candidates = analyze_aws_snapshots(config, 90, 0).await?;
+    Ok(candidates
+        .into_iter()
+        .map(|c| Recommendation {
+            category: "Snapshot archive".to_string(),
+            action: format!(
+                "Archive {:.0} GiB snapshot (breaks even after {:.1} months)",
+                c.size_gb, c.break_even_months
+            ),
+            resource_id: c.snapshot_id,
+            region: c.region,
+            monthly_savings: c.monthly_savings,
+            effort: "low".to_string(),
+        })
+        .collect())
+}
+
+/// Cost recommenders of the GCP Recommender API: the resource kind's
+/// aggregated list they cover, the category shown and the effort to act.
+const GCP_RECOMMENDERS: &[(&str, &str, &str, &str)] = &[
+    (
+        "instances",
+        "google.compute.instance.IdleResourceRecommender",
+        "Idle",
+        "low",
+    ),
+    (
+        "instances",
+        "google.compute.instance.MachineTypeRecommender",
+        "Rightsizing",
+        "medium",
+    ),
+    (
+        "disks",
+        "google.compute.disk.IdleResourceRecommender",
+        "Idle",
+        "low",
+    ),
+    (
+        "addresses",
+        "google.compute.address.IdleResourceRecommender",
+        "Idle",
+        "low",
+    ),
+];
+
+const GCP_RECOMMENDER_API: &str = "https://recommender.googleapis.com/v1";
+
+/// Seconds in the 30-day month savings are reported over
+const SECONDS_PER_MONTH: f64 = 30.0 * 86400.0;
+
+/// Active cost recommendations from the GCP Recommender, asked for in each
+/// zone or region the project has instances, disks or addresses in. A
+/// recommender that can't be read is reported and skipped.
+async fn gather_gcp_recommendations() -> Result<Vec<Recommendation>, AppError> {
+    let project_id = gcp::project_id()?;
+    let mut recommendations = Vec::new();
+
+    for kind in ["instances", "disks", "addresses"] {
+        let url = format!(
+            "{}/projects/{}/aggregated/{}",
+            gcp::COMPUTE_API,
+            project_id,
+            kind
+        );
+        let pages = gcp::list_pages(&format!("compute.{}.aggregatedList", kind), &url).await?;
+        // Scopes look like `zones/us-central1-a` or `regions/us-central1`
+        let locations: BTreeSet<&str> = pages
+            .iter()
+            .filter_map(|page| page["items"].as_object())
+            .flatten()
+            .filter(|(_, scope)| {
+                scope[kind]
+                    .as_array()
+                    .is_some_and(|items| !items.is_empty())
+            })
+            .map(|(scope, _)| gcp::short_name(scope))
+            .collect();
+
+        for (_, recommender, category, effort) in GCP_RECOMMENDERS
+            .iter()
+            .filter(|(recommender_kind, ..)| *recommender_kind == kind)
+        {
+            for location in &locations {
+                let url = format!(
+                    "{}/projects/{}/locations/{}/recommenders/{}/recommendations",
+                    GCP_RECOMMENDER_API, project_id, location, recommender
+                );
+                match gcp::list_pages("recommender.recommendations.list", &url).await {
+                    Ok(pages) => recommendations.extend(
+                        pages
+                            .iter()
+                            .filter_map(|page| page["recommendations"].as_array())
+                            .flatten()
+                            .filter(|r| r["stateInfo"]["state"] == "ACTIVE")
+                            .map(|r| gcp_recommendation(r, location, category, effort)),
+                    ),
+                    Err(e) => tracing::warn!(
+                        "⚠️  Skipping {} in {}: {}",
+                        recommender.yellow(),
+                        location,
+                        e
+                    ),
+                }
+            }
+        }
+    }
+
+    Ok(recommendations)
+}
+
+fn gcp_recommendation(
+    recommendation: &Value,
+    location: &str,
+    category: &str,
+    effort: &str,
+) -> Recommendation {
+    let resource = recommendation["content"]["operationGroups"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|group| group["operations"].as_array())
+        .flatten()
+        .find_map(|operation| operation["resource"].as_str())
+        .map(gcp::short_name)
+        .unwrap_or_default();
+
+    // The projection is a (negative) cost change over `duration`
+    let projection = &recommendation["primaryImpact"]["costProjection"];
+    let cost = projection["cost"]["units"]
+        .as_str()
+        .and_then(|units| units.parse::<f64>().ok())
+        .unwrap_or(0.0)
+        + projection["cost"]["nanos"].as_f64().unwrap_or(0.0) / 1e9;
+    let duration = projection["duration"]
+        .as_str()
+        .and_then(|d| d.trim_end_matches('s').parse::<f64>().ok())
+        .filter(|d| *d > 0.0)
+        .unwrap_or(SECONDS_PER_MONTH);
+
+    Recommendation {
+        category: category.to_string(),
+        resource_id: resource.to_string(),
+        region: location.to_string(),
+        action: recommendation["description"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        monthly_savings: -cost * SECONDS_PER_MONTH / duration,
+        effort: effort.to_string(),
+    }
+}
+
+const ADVISOR_API_VERSION: &str = "2023-01-01";
+
+/// Cost recommendations from Azure Advisor for the subscription.
+async fn gather_azure_recommendations() -> Result<Vec<Recommendation>, AppError> {
+    let url = format!(
+        "{}/subscriptions/{}/providers/Microsoft.Advisor/recommendations?api-version={}&$filter=Category%20eq%20'Cost'",
+        azure::MANAGEMENT_API,
+        azure::subscription_id()?,
+        ADVISOR_API_VERSION
+    );
+    let found = azure::list("Microsoft.Advisor/recommendations/read", &url).await?;
+
+    Ok(found
+        .iter()
+        .map(|recommendation| {
+            let properties = &recommendation["properties"];
+            let extended = &properties["extendedProperties"];
+            let amount = |field: &str| extended[field].as_str().and_then(|a| a.parse::<f64>().ok());
+            // Advisor reports annual savings for most types, monthly for some
+            let monthly_savings = amount("annualSavingsAmount")
+                .map(|annual| annual / 12.0)
+                .or_else(|| amount("savingsAmount"))
+                .unwrap_or(0.0);
+
+            Recommendation {
+                // e.g. `Microsoft.Compute/virtualMachines` -> `virtualMachines`
+                category: properties["impactedField"]
+                    .as_str()
+                    .and_then(|field| field.rsplit('/').next())
+                    .unwrap_or("Advisor")
+                    .to_string(),
+                resource_id: properties["impactedValue"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                region: extended["region"]
+                    .as_str()
+                    .or(extended["location"].as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                action: properties["shortDescription"]["solution"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                monthly_savings,
+                effort: properties["impact"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_lowercase(),
+            }
+        })
+        .collect())
+}
+
+fn parse_amount(amount: Option<&str>) -> f64 {
+    amount.and_then(|a| a.parse::<f64>().ok()).unwrap_or(0.0)
+}
+
+fn output_recommendations(
+    recommendations: &[Recommendation],
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!("\n📋 Savings recommendations (highest impact first):");
+            println!(
+                "{:<4} {:<15} {:<22} {:<45} {:<8} {:<12}",
+                "#", "Category", "Resource", "Action", "Effort", "Savings ($/mo)"
+            );
+            println!("{:-<110}", "");
+
+            for (i, recommendation) in recommendations.iter().enumerate() {
+                println!(
+                    "{:<4} {:<15} {:<22} {:<45} {:<8} {:<12.2}",
+                    i + 1,
+                    recommendation.category,
+                    recommendation.resource_id,
+                    recommendation.action,
+                    recommendation.effort,
+                    recommendation.monthly_savings
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(recommendations)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, recommendations)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Priority,Category,Resource,Region,Action,Effort,MonthlySavings");
+            for (i, recommendation) in recommendations.iter().enumerate() {
+                println!(
+                    "{},{},{},{},{},{},{:.2}",
+                    i + 1,
+                    recommendation.category,
+                    recommendation.resource_id,
+                    recommendation.region,
+                    recommendation.action,
+                    recommendation.effort,
+                    recommendation.monthly_savings
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,418 @@
+use crate::batch::{self, BatchAction};
+use crate::cli::{Commands, OutputFormat, ResourceType};
+use crate::clients;
+use crate::commands::list::{fetch_aws_resources, ResourceSummary};
+use crate::error::AppError;
+use crate::executor;
+use crate::guard;
+use crate::query;
+use aws_config::SdkConfig;
+use chrono::{Datelike, Duration, NaiveTime, Utc, Weekday};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Declared end state for `actlog reconcile`.
+#[derive(Debug, Default, Deserialize)]
+pub struct DesiredState {
+    #[serde(default)]
+    pub autoscaling_groups: Vec<DesiredCapacity>,
+    #[serde(default)]
+    pub schedules: Vec<PowerSchedule>,
+    #[serde(default)]
+    pub tags: Vec<TagSet>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DesiredCapacity {
+    pub name: String,
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    pub desired: Option<i32>,
+}
+
+/// Instances matching `selector` run between `start` and `stop` on `days`.
+#[derive(Debug, Deserialize)]
+pub struct PowerSchedule {
+    pub name: String,
+    /// Query expression selecting EC2 instances
+    pub selector: String,
+    /// Local start time, "HH:MM"
+    pub start: String,
+    /// Local stop time, "HH:MM"
+    pub stop: String,
+    /// Days the schedule is active (mon..sun); every day when empty
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// Offset of the schedule's local time from UTC
+    #[serde(default)]
+    pub utc_offset_hours: i64,
+}
+
+/// Tags every instance matching `selector` must carry.
+#[derive(Debug, Deserialize)]
+pub struct TagSet {
+    pub selector: String,
+    pub tags: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlanAction {
+    SetCapacity {
+        group: String,
+        current: (i32, i32, i32),
+        target: (i32, i32, i32),
+    },
+    StartInstance {
+        instance_id: String,
+        schedule: String,
+    },
+    StopInstance {
+        instance_id: String,
+        schedule: String,
+    },
+    SetTags {
+        instance_id: String,
+        tags: BTreeMap<String, String>,
+    },
+}
+
+impl BatchAction for PlanAction {
+    fn action(&self) -> &str {
+        match self {
+            PlanAction::SetCapacity { .. } => "set_capacity",
+            PlanAction::StartInstance { .. } => "start_instance",
+            PlanAction::StopInstance { .. } => "stop_instance",
+            PlanAction::SetTags { .. } => "set_tags",
+        }
+    }
+
+    /// The group or instance the action changes.
+    fn resource_id(&self) -> &str {
+        match self {
+            PlanAction::SetCapacity { group, .. } => group,
+            PlanAction::StartInstance { instance_id, .. }
+            | PlanAction::StopInstance { instance_id, .. }
+            | PlanAction::SetTags { instance_id, .. } => instance_id,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            PlanAction::SetCapacity {
+                group,
+                current,
+                target,
+            } => format!(
+                "~ asg {}: min/max/desired {}/{}/{} → {}/{}/{}",
+                group, current.0, current.1, current.2, target.0, target.1, target.2
+            ),
+            PlanAction::StartInstance {
+                instance_id,
+                schedule,
+            } => format!("+ start {} (schedule {})", instance_id, schedule),
+            PlanAction::StopInstance {
+                instance_id,
+                schedule,
+            } => format!("- stop {} (schedule {})", instance_id, schedule),
+            PlanAction::SetTags { instance_id, tags } => format!(
+                "~ tag {}: {}",
+                instance_id,
+                tags.iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn reconcile(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Reconcile {
+        desired,
+        apply,
+        force,
+        format,
+        profile,
+    } = cmd
+    {
+        let content = fs::read_to_string(desired)?;
+        let state: DesiredState = serde_yaml::from_str(&content).map_err(|e| {
+            AppError::ConfigurationError(format!("Invalid desired state {}: {}", desired, e))
+        })?;
+
+        tracing::info!("🔄 Reconciling AWS against {}...", desired.green());
+
+        let config = clients::aws_config().await;
+        let plan = build_plan(&config, &state).await?;
+
+        if plan.is_empty() {
+            println!("✅ Infrastructure matches the desired state.");
+            return Ok(());
+        }
+
+        println!("\n📋 Plan ({} changes):", plan.len());
+        for action in &plan {
+            println!("   {}", action.describe());
+        }
+
+        if !*apply {
+            tracing::info!("🔍 Re-run with --apply to make these changes");
+            return Ok(());
+        }
+        guard::ensure_writable("reconcile")?;
+
+        if !*force {
+            print!("\nApply {} changes? (y/N): ", plan.len());
+            use std::io::{self, Write};
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+
+            if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
+                println!("❌ Reconcile cancelled by user.");
+                return Err(AppError::UserCancelled);
+            }
+        }
+
+        tracing::info!("🔧 Applying plan...");
+        let outcome = batch::run("reconcile", "AWS", &plan, |action| {
+            apply_action(&config, action)
+        })
+        .await;
+        outcome.render(format)?;
+        outcome.into_result()?;
+    }
+
+    Ok(())
+}
+
+async fn build_plan(config: &SdkConfig, state: &DesiredState) -> Result<Vec<PlanAction>, AppError> {
+    let mut plan = Vec::new();
+
+    if !state.autoscaling_groups.is_empty() {
+        plan.extend(capacity_plan(config, &state.autoscaling_groups).await?);
+    }
+
+    if state.schedules.is_empty() && state.tags.is_empty() {
+        return Ok(plan);
+    }
+
+    // Plans act on live state, never on cached responses
+    let instances = fetch_aws_resources(config, &ResourceType::Ec2).await?;
+
+    for schedule in &state.schedules {
+        let selector = query::parse(&schedule.selector)?;
+        let should_run = schedule_active(schedule)?;
+
+        for instance in instances.iter().filter(|i| selector.matches("AWS", i)) {
+            let running = matches!(instance.state.as_str(), "running" | "pending");
+            let stopped = matches!(instance.state.as_str(), "stopped" | "stopping");
+
+            if should_run && stopped {
+                plan.push(PlanAction::StartInstance {
+                    instance_id: instance.id.clone(),
+                    schedule: schedule.name.clone(),
+                });
+            } else if !should_run && running {
+                plan.push(PlanAction::StopInstance {
+                    instance_id: instance.id.clone(),
+                    schedule: schedule.name.clone(),
+                });
+            }
+        }
+    }
+
+    for tag_set in &state.tags {
+        let selector = query::parse(&tag_set.selector)?;
+        for instance in instances.iter().filter(|i| selector.matches("AWS", i)) {
+            let missing = missing_tags(instance, &tag_set.tags);
+            if !missing.is_empty() {
+                plan.push(PlanAction::SetTags {
+                    instance_id: instance.id.clone(),
+                    tags: missing,
+                });
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+async fn capacity_plan(
+    config: &SdkConfig,
+    groups: &[DesiredCapacity],
+) -> Result<Vec<PlanAction>, AppError> {
+    let client = clients::get::<aws_sdk_autoscaling::Client>(config).await;
+    executor::throttle("autoscaling").await;
+    let response = client
+        .describe_auto_scaling_groups()
+        .set_auto_scaling_group_names(Some(groups.iter().map(|g| g.name.clone()).collect()))
+        .send()
+        .await
+        .map_err(|e| AppError::aws("autoscaling:DescribeAutoScalingGroups", e))?;
+
+    let mut plan = Vec::new();
+    for desired in groups {
+        let Some(group) = response
+            .auto_scaling_groups()
+            .iter()
+            .find(|g| g.auto_scaling_group_name() == Some(desired.name.as_str()))
+        else {
+            println!(
+                "   ⚠️  Auto Scaling group {} not found",
+                desired.name.yellow()
+            );
+            continue;
+        };
+
+        let current = (
+            group.min_size().unwrap_or(0),
+            group.max_size().unwrap_or(0),
+            group.desired_capacity().unwrap_or(0),
+        );
+        let target = (
+            desired.min.unwrap_or(current.0),
+            desired.max.unwrap_or(current.1),
+            desired.desired.unwrap_or(current.2),
+        );
+
+        if target.0 > target.2 || target.2 > target.1 {
+            return Err(AppError::ConfigurationError(format!(
+                "Auto Scaling group {} needs min <= desired <= max",
+                desired.name
+            )));
+        }
+
+        if current != target {
+            plan.push(PlanAction::SetCapacity {
+                group: desired.name.clone(),
+                current,
+                target,
+            });
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Whether instances under `schedule` should be running right now.
+fn schedule_active(schedule: &PowerSchedule) -> Result<bool, AppError> {
+    let parse_time = |value: &str| {
+        NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| {
+            AppError::ConfigurationError(format!(
+                "Schedule {} has invalid time '{}' (expected HH:MM)",
+                schedule.name, value
+            ))
+        })
+    };
+    let start = parse_time(&schedule.start)?;
+    let stop = parse_time(&schedule.stop)?;
+
+    let now = Utc::now().naive_utc() + Duration::hours(schedule.utc_offset_hours);
+
+    if !schedule.days.is_empty() {
+        let days = schedule
+            .days
+            .iter()
+            .map(|d| {
+                d.parse::<Weekday>().map_err(|_| {
+                    AppError::ConfigurationError(format!(
+                        "Schedule {} has invalid day '{}'",
+                        schedule.name, d
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !days.contains(&now.weekday()) {
+            return Ok(false);
+        }
+    }
+
+    let time = now.time();
+    Ok(if start <= stop {
+        time >= start && time < stop
+    } else {
+        // Overnight window, e.g. 22:00-06:00
+        time >= start || time < stop
+    })
+}
+
+fn missing_tags(
+    resource: &ResourceSummary,
+    desired: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    desired
+        .iter()
+        .filter(|(key, value)| resource.tags.get(*key) != Some(*value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+async fn apply_action(config: &SdkConfig, action: &PlanAction) -> Result<(), AppError> {
+    match action {
+        PlanAction::SetCapacity { group, target, .. } => {
+            executor::throttle("autoscaling").await;
+            clients::get::<aws_sdk_autoscaling::Client>(config)
+                .await
+                .update_auto_scaling_group()
+                .auto_scaling_group_name(group)
+                .min_size(target.0)
+                .max_size(target.1)
+                .desired_capacity(target.2)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("autoscaling:UpdateAutoScalingGroup", e).for_resource(group)
+                })?;
+        }
+
+        PlanAction::StartInstance { instance_id, .. } => {
+            executor::throttle("ec2").await;
+            clients::get::<aws_sdk_ec2::Client>(config)
+                .await
+                .start_instances()
+                .instance_ids(instance_id)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("ec2:StartInstances", e).for_resource(instance_id))?;
+        }
+
+        PlanAction::StopInstance { instance_id, .. } => {
+            executor::throttle("ec2").await;
+            clients::get::<aws_sdk_ec2::Client>(config)
+                .await
+                .stop_instances()
+                .instance_ids(instance_id)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("ec2:StopInstances", e).for_resource(instance_id))?;
+        }
+
+        PlanAction::SetTags { instance_id, tags } => {
+            let mut request = clients::get::<aws_sdk_ec2::Client>(config)
+                .await
+                .create_tags()
+                .resources(instance_id);
+            for (key, value) in tags {
+                request = request.tags(
+                    aws_sdk_ec2::types::Tag::builder()
+                        .key(key)
+                        .value(value)
+                        .build(),
+                );
+            }
+            executor::throttle("ec2").await;
+            request
+                .send()
+                .await
+                .map_err(|e| AppError::aws("ec2:CreateTags", e).for_resource(instance_id))?;
+        }
+    }
+
+    Ok(())
+}
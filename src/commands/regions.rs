@@ -0,0 +1,380 @@
+use crate::azure;
+use crate::cli::{CloudProvider, Commands, OutputFormat};
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::regions;
+use crate::report;
+use aws_config::SdkConfig;
+use colored::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Regional services behind the resource types actlog manages, as named in
+/// the SSM global infrastructure parameters. IAM, Route 53 and CloudFront are
+/// global and left out.
+const AWS_SERVICES: &[(&str, &str)] = &[
+    ("EC2", "ec2"),
+    ("S3", "s3"),
+    ("RDS", "rds"),
+    ("Lambda", "lambda"),
+    ("ELB", "elb"),
+    ("Auto Scaling", "autoscaling"),
+];
+
+/// Resource providers and types checked for each Azure location.
+const AZURE_SERVICES: &[(&str, &str, &str)] = &[
+    ("VMs", "Microsoft.Compute", "virtualMachines"),
+    ("Storage", "Microsoft.Storage", "storageAccounts"),
+    ("SQL", "Microsoft.Sql", "servers"),
+    ("Functions", "Microsoft.Web", "sites"),
+    ("Load Balancer", "Microsoft.Network", "loadBalancers"),
+];
+
+const AZURE_LOCATIONS_API_VERSION: &str = "2022-12-01";
+const AZURE_PROVIDERS_API_VERSION: &str = "2021-04-01";
+
+const CLOUD_FUNCTIONS_API: &str = "https://cloudfunctions.googleapis.com/v1";
+
+/// TCP connects per region; the fastest is reported
+const LATENCY_SAMPLES: usize = 3;
+const LATENCY_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Serialize)]
+struct RegionInfo {
+    name: String,
+    status: String,
+    /// Service label to whether the region offers it
+    services: BTreeMap<String, bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<f64>,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn list_regions(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Regions {
+        provider,
+        latency,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!(
+            "🌍 Checking {} regions and service availability...",
+            provider.to_string().green()
+        );
+
+        let mut infos = match provider {
+            CloudProvider::Aws => {
+                let config = clients::aws_config().await;
+                aws_regions(&config).await?
+            }
+            CloudProvider::Gcp => gcp_regions().await?,
+            CloudProvider::Azure => azure_regions().await?,
+        };
+
+        if *latency {
+            match provider {
+                CloudProvider::Aws => {
+                    tracing::info!("⏱️  Probing latency from this machine...");
+                    for info in &mut infos {
+                        let host = format!("ec2.{}.amazonaws.com:443", info.name);
+                        info.latency_ms = probe_latency(&host).await;
+                    }
+                    infos.sort_by(|a, b| {
+                        a.latency_ms
+                            .unwrap_or(f64::INFINITY)
+                            .total_cmp(&b.latency_ms.unwrap_or(f64::INFINITY))
+                    });
+                }
+                // Their APIs answer from the nearest global front end, so
+                // connect times say nothing about the region itself
+                CloudProvider::Gcp | CloudProvider::Azure => println!(
+                    "   ⚠️  Latency probes need regional endpoints, which {} APIs don't have",
+                    provider
+                ),
+            }
+        }
+
+        output_regions(&infos, format)?;
+    }
+
+    Ok(())
+}
+
+async fn aws_regions(config: &SdkConfig) -> Result<Vec<RegionInfo>, AppError> {
+    let enabled = regions::aws_regions(config).await?;
+    let ssm = clients::get::<aws_sdk_ssm::Client>(config).await;
+
+    let mut availability: Vec<(&str, HashSet<String>)> = Vec::new();
+    for &(label, service) in AWS_SERVICES {
+        let mut offered = HashSet::new();
+        let mut pages = ssm
+            .get_parameters_by_path()
+            .path(format!(
+                "/aws/service/global-infrastructure/services/{}/regions",
+                service
+            ))
+            .into_paginator()
+            .send();
+        loop {
+            executor::throttle("ssm").await;
+            let Some(page) = pages.next().await else {
+                break;
+            };
+            let page = page.map_err(|e| AppError::aws("ssm:GetParametersByPath", e))?;
+            offered.extend(
+                page.parameters()
+                    .iter()
+                    .filter_map(|p| p.value().map(str::to_string)),
+            );
+        }
+        availability.push((label, offered));
+    }
+
+    let mut infos: Vec<RegionInfo> = enabled
+        .into_iter()
+        .map(|name| RegionInfo {
+            services: availability
+                .iter()
+                .map(|(label, offered)| (label.to_string(), offered.contains(&name)))
+                .collect(),
+            name,
+            status: "enabled".to_string(),
+            latency_ms: None,
+        })
+        .collect();
+    infos.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(infos)
+}
+
+/// Compute Engine regions with their status, and whether Cloud Functions
+/// runs there.
+async fn gcp_regions() -> Result<Vec<RegionInfo>, AppError> {
+    let project_id = gcp::project_id()?;
+
+    let pages = gcp::list_pages(
+        "compute.regions.list",
+        &format!("{}/projects/{}/regions", gcp::COMPUTE_API, project_id),
+    )
+    .await?;
+
+    let functions = gcp::get(
+        "cloudfunctions.projects.locations.list",
+        &format!(
+            "{}/projects/{}/locations?pageSize=1000",
+            CLOUD_FUNCTIONS_API, project_id
+        ),
+    )
+    .await?;
+    let function_locations: HashSet<&str> = functions["locations"]
+        .as_array()
+        .map(|locations| {
+            locations
+                .iter()
+                .filter_map(|l| l["locationId"].as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut infos: Vec<RegionInfo> = pages
+        .iter()
+        .flat_map(|page| page["items"].as_array().cloned().unwrap_or_default())
+        .filter_map(|region| {
+            let name = region["name"].as_str()?.to_string();
+            let services = BTreeMap::from([
+                ("Compute".to_string(), true),
+                (
+                    "Functions".to_string(),
+                    function_locations.contains(name.as_str()),
+                ),
+            ]);
+            Some(RegionInfo {
+                status: region["status"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_lowercase(),
+                name,
+                services,
+                latency_ms: None,
+            })
+        })
+        .collect();
+    infos.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(infos)
+}
+
+/// Physical locations of the subscription and the resource types each
+/// resource provider offers there.
+async fn azure_regions() -> Result<Vec<RegionInfo>, AppError> {
+    let subscription_id = azure::subscription_id()?;
+
+    let locations = azure::list(
+        "Microsoft.Resources/subscriptions/locations/read",
+        &format!(
+            "{}/subscriptions/{}/locations?api-version={}",
+            azure::MANAGEMENT_API,
+            subscription_id,
+            AZURE_LOCATIONS_API_VERSION
+        ),
+    )
+    .await?;
+
+    // Resource providers list locations by display name, e.g. "East US"
+    let mut availability: Vec<(&str, HashSet<String>)> = Vec::new();
+    for &(label, namespace, resource_type) in AZURE_SERVICES {
+        let provider = azure::get(
+            "Microsoft.Resources/subscriptions/providers/read",
+            &format!(
+                "{}/subscriptions/{}/providers/{}?api-version={}",
+                azure::MANAGEMENT_API,
+                subscription_id,
+                namespace,
+                AZURE_PROVIDERS_API_VERSION
+            ),
+        )
+        .await?;
+        let offered = provider["resourceTypes"]
+            .as_array()
+            .and_then(|types| {
+                types.iter().find(|t| {
+                    t["resourceType"]
+                        .as_str()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(resource_type))
+                })
+            })
+            .and_then(|t| t["locations"].as_array())
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|n| n.as_str().map(str::to_lowercase))
+                    .collect()
+            })
+            .unwrap_or_default();
+        availability.push((label, offered));
+    }
+
+    let mut infos: Vec<RegionInfo> = locations
+        .iter()
+        // Logical locations such as "global" or geographies have no datacenter
+        .filter(|l| l["metadata"]["regionType"].as_str() == Some("Physical"))
+        .filter_map(|location| {
+            let name = location["name"].as_str()?.to_string();
+            let display_name = location["displayName"]
+                .as_str()
+                .unwrap_or(&name)
+                .to_lowercase();
+            Some(RegionInfo {
+                services: availability
+                    .iter()
+                    .map(|(label, offered)| (label.to_string(), offered.contains(&display_name)))
+                    .collect(),
+                name,
+                status: "available".to_string(),
+                latency_ms: None,
+            })
+        })
+        .collect();
+    infos.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(infos)
+}
+
+/// Fastest TCP connect to `host` in milliseconds, or `None` if every
+/// attempt failed or timed out.
+async fn probe_latency(host: &str) -> Option<f64> {
+    let mut best: Option<f64> = None;
+    for _ in 0..LATENCY_SAMPLES {
+        let started = Instant::now();
+        let connected = tokio::time::timeout(LATENCY_TIMEOUT, tokio::net::TcpStream::connect(host))
+            .await
+            .is_ok_and(|r| r.is_ok());
+        if connected {
+            let ms = started.elapsed().as_secs_f64() * 1000.0;
+            best = Some(best.map_or(ms, |b| b.min(ms)));
+        }
+    }
+    best
+}
+
+fn output_regions(infos: &[RegionInfo], format: &OutputFormat) -> Result<(), AppError> {
+    let labels: Vec<&String> = infos
+        .first()
+        .map(|info| info.services.keys().collect())
+        .unwrap_or_default();
+    let show_latency = infos.iter().any(|i| i.latency_ms.is_some());
+
+    match format {
+        OutputFormat::Table => {
+            if infos.is_empty() {
+                println!("ℹ️  No regions found.");
+                return Ok(());
+            }
+
+            print!("\n{:<24} {:<10}", "Region", "Status");
+            for label in &labels {
+                print!(" {:<13}", label);
+            }
+            if show_latency {
+                print!(" {:>12}", "Latency (ms)");
+            }
+            println!();
+            println!(
+                "{:-<1$}",
+                "",
+                35 + labels.len() * 14 + if show_latency { 13 } else { 0 }
+            );
+
+            for info in infos {
+                print!("{:<24} {:<10}", info.name, info.status);
+                for label in &labels {
+                    let mark = if info.services.get(*label).copied().unwrap_or(false) {
+                        "✓".green()
+                    } else {
+                        "✗".red()
+                    };
+                    print!(" {:<13}", mark);
+                }
+                if show_latency {
+                    match info.latency_ms {
+                        Some(ms) => print!(" {:>12.0}", ms),
+                        None => print!(" {:>12}", "timeout"),
+                    }
+                }
+                println!();
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(infos)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, infos)?);
+        }
+
+        OutputFormat::Csv => {
+            print!("Region,Status");
+            for label in &labels {
+                print!(",{}", label);
+            }
+            println!(",LatencyMs");
+            for info in infos {
+                print!("{},{}", info.name, info.status);
+                for label in &labels {
+                    print!(",{}", info.services.get(*label).copied().unwrap_or(false));
+                }
+                println!(
+                    ",{}",
+                    info.latency_ms
+                        .map(|ms| format!("{:.1}", ms))
+                        .unwrap_or_default()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,304 @@
+use crate::audit;
+use crate::cli::{Commands, S3Action};
+use crate::clients;
+use crate::commands::list::aws_datetime;
+use crate::commands::storage_analysis::bucket_region;
+use crate::error::AppError;
+use crate::executor;
+use crate::guard;
+use crate::interrupt;
+use crate::locks;
+use crate::query;
+use crate::regions;
+use aws_sdk_s3::types::{Delete, ObjectIdentifier};
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Keys accepted by one DeleteObjects call
+const DELETE_BATCH_SIZE: usize = 1000;
+
+/// Keys listed by a dry run
+const DRY_RUN_SAMPLE: usize = 20;
+
+/// One object version or delete marker to remove.
+struct PurgeTarget {
+    key: String,
+    version_id: Option<String>,
+    size: i64,
+    delete_marker: bool,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn s3(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::S3 { action } = cmd {
+        match action {
+            S3Action::Purge {
+                bucket,
+                include,
+                older_than,
+                dry_run,
+                force,
+                profile,
+            } => {
+                purge(
+                    bucket,
+                    include.as_deref(),
+                    older_than.as_deref(),
+                    *dry_run,
+                    *force,
+                )
+                .await?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Permanently delete every version and delete marker in `bucket` whose key
+/// matches `include` and that was last modified before `older_than`.
+async fn purge(
+    bucket: &str,
+    include: Option<&str>,
+    older_than: Option<&str>,
+    dry_run: bool,
+    force: bool,
+) -> Result<(), AppError> {
+    if let Some(lock) = locks::active_locks()?.get(bucket) {
+        return Err(AppError::InvalidParameters(format!(
+            "{} is {}; release the lock before purging it",
+            bucket,
+            lock.describe()
+        )));
+    }
+
+    let cutoff = match older_than {
+        Some(age) => {
+            Some(Utc::now() - Duration::seconds((query::parse_days(age)? * 86400.0) as i64))
+        }
+        None => None,
+    };
+
+    let base = clients::aws_config().await;
+    let region = bucket_region(&clients::get::<aws_sdk_s3::Client>(&base).await, bucket).await;
+    let config = regions::regional_config(&base, &region);
+    let s3 = clients::get::<aws_sdk_s3::Client>(&config).await;
+
+    tracing::info!("🔍 Scanning s3://{} ({})...", bucket.green(), region);
+    let targets = purge_targets(&s3, bucket, include, cutoff).await?;
+
+    if targets.is_empty() {
+        println!("✅ No object versions match.");
+        return Ok(());
+    }
+
+    let markers = targets.iter().filter(|t| t.delete_marker).count();
+    let bytes: i64 = targets.iter().map(|t| t.size).sum();
+    println!(
+        "\n📋 {} object versions and {} delete markers match ({:.2} GB)",
+        (targets.len() - markers).to_string().yellow(),
+        markers.to_string().yellow(),
+        bytes as f64 / 1_073_741_824.0
+    );
+
+    if dry_run {
+        for target in targets.iter().take(DRY_RUN_SAMPLE) {
+            println!(
+                "   {} ({})",
+                target.key,
+                target.version_id.as_deref().unwrap_or("null")
+            );
+        }
+        if targets.len() > DRY_RUN_SAMPLE {
+            println!("   ... and {} more", targets.len() - DRY_RUN_SAMPLE);
+        }
+        tracing::info!("🔍 Dry run mode - nothing was deleted");
+        return Ok(());
+    }
+
+    guard::ensure_writable("s3 purge")?;
+
+    if !force {
+        print!(
+            "Permanently delete these {} versions from {}? (y/N): ",
+            targets.len(),
+            bucket
+        );
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
+            println!("❌ Purge cancelled by user.");
+            return Err(AppError::UserCancelled);
+        }
+    }
+
+    let _batch = interrupt::batch();
+    let progress = ProgressBar::new(targets.len() as u64);
+    progress.set_style(
+        ProgressStyle::with_template("   {bar:40} {pos}/{len} versions ({eta} left)")
+            .expect("progress template is valid"),
+    );
+
+    let mut deleted = 0;
+    let mut failed = 0;
+    for chunk in targets.chunks(DELETE_BATCH_SIZE) {
+        if interrupt::interrupted() {
+            progress.abandon();
+            println!(
+                "\n🛑 Stopped after {} of {} versions.",
+                deleted,
+                targets.len()
+            );
+            break;
+        }
+
+        let errors = delete_batch(&s3, bucket, chunk).await;
+        let result = match &errors {
+            Ok(errors) if errors.is_empty() => Ok(()),
+            Ok(errors) => Err(AppError::BatchFailed(errors.len())),
+            Err(e) => Err(AppError::AwsError(e.to_string())),
+        };
+        audit::record("s3-purge", "AWS", "delete-objects", bucket, &result);
+
+        match errors {
+            Ok(errors) => {
+                for error in errors.iter().take(3) {
+                    progress.println(format!("   ❌ {}", error.red()));
+                }
+                failed += errors.len();
+                deleted += chunk.len() - errors.len();
+            }
+            Err(e) => {
+                progress.println(format!("   ❌ {}", e.to_string().red()));
+                failed += chunk.len();
+            }
+        }
+        progress.inc(chunk.len() as u64);
+    }
+    progress.finish_and_clear();
+
+    println!(
+        "\n✅ Deleted {} versions from {}",
+        deleted.to_string().green(),
+        bucket
+    );
+    if failed > 0 {
+        return Err(AppError::BatchFailed(failed));
+    }
+
+    Ok(())
+}
+
+/// Matching versions and delete markers. Listing starts at the pattern's
+/// literal prefix so S3 only returns keys that can match.
+async fn purge_targets(
+    s3: &aws_sdk_s3::Client,
+    bucket: &str,
+    include: Option<&str>,
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<Vec<PurgeTarget>, AppError> {
+    let prefix = include
+        .and_then(|p| p.split('*').next())
+        .unwrap_or_default();
+    let matches = |key: &str, modified: Option<DateTime<Utc>>| {
+        include.map_or(true, |pattern| query::glob_match(key, pattern))
+            && cutoff.map_or(true, |c| modified.is_some_and(|m| m < c))
+    };
+
+    let spinner = ProgressBar::new_spinner();
+    let mut targets = Vec::new();
+    let mut scanned = 0;
+    let mut pages = s3
+        .list_object_versions()
+        .bucket(bucket)
+        .prefix(prefix)
+        .into_paginator()
+        .send();
+    loop {
+        executor::throttle("s3").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page =
+            page.map_err(|e| AppError::aws("s3:ListObjectVersions", e).for_resource(bucket))?;
+
+        for version in page.versions() {
+            scanned += 1;
+            let key = version.key().unwrap_or_default();
+            if matches(key, aws_datetime(version.last_modified())) {
+                targets.push(PurgeTarget {
+                    key: key.to_string(),
+                    version_id: version.version_id().map(str::to_string),
+                    size: version.size().unwrap_or(0),
+                    delete_marker: false,
+                });
+            }
+        }
+        for marker in page.delete_markers() {
+            scanned += 1;
+            let key = marker.key().unwrap_or_default();
+            if matches(key, aws_datetime(marker.last_modified())) {
+                targets.push(PurgeTarget {
+                    key: key.to_string(),
+                    version_id: marker.version_id().map(str::to_string),
+                    size: 0,
+                    delete_marker: true,
+                });
+            }
+        }
+        spinner.set_message(format!("{} scanned, {} match", scanned, targets.len()));
+        spinner.tick();
+    }
+    spinner.finish_and_clear();
+
+    Ok(targets)
+}
+
+/// Delete one batch of versions, returning the per-key errors S3 reported.
+async fn delete_batch(
+    s3: &aws_sdk_s3::Client,
+    bucket: &str,
+    chunk: &[PurgeTarget],
+) -> Result<Vec<String>, AppError> {
+    let objects = chunk
+        .iter()
+        .map(|target| {
+            ObjectIdentifier::builder()
+                .key(&target.key)
+                .set_version_id(target.version_id.clone())
+                .build()
+                .map_err(|e| AppError::AwsError(e.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let delete = Delete::builder()
+        .set_objects(Some(objects))
+        .quiet(true)
+        .build()
+        .map_err(|e| AppError::AwsError(e.to_string()))?;
+
+    executor::throttle("s3").await;
+    let response = s3
+        .delete_objects()
+        .bucket(bucket)
+        .delete(delete)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("s3:DeleteObjects", e).for_resource(bucket))?;
+
+    Ok(response
+        .errors()
+        .iter()
+        .map(|e| {
+            format!(
+                "{}: {}",
+                e.key().unwrap_or_default(),
+                e.message().or(e.code()).unwrap_or("unknown error")
+            )
+        })
+        .collect())
+}
@@ -1,8 +1,26 @@
-use crate::cli::{CloudProvider, Commands};
+use crate::batch::{self, BatchAction};
+use crate::cli::{CloudProvider, Commands, OutputFormat};
+use crate::clients;
+use crate::cloudwatch::{self, MetricQuery};
 use crate::error::AppError;
+use crate::executor;
+use crate::guard;
+use crate::interrupt;
+use crate::inventory;
+use crate::providers;
+use crate::query;
+use crate::report;
+use chrono::{DateTime, Duration, Utc};
 use colored::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration as StdDuration;
+
+/// CloudWatch period replayed by `--backtest`, the basic monitoring interval
+const BACKTEST_PERIOD_SECS: i64 = 300;
+
+/// Window the current CPU utilization of a group is averaged over
+const CURRENT_WINDOW_SECS: i64 = 600;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScalingAction {
@@ -24,6 +42,33 @@ pub struct ScalingPolicy {
     pub scale_down_cooldown: i32,
 }
 
+/// A scaling decision the policy would have made during a backtest.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BacktestEvent {
+    pub timestamp: DateTime<Utc>,
+    pub action_type: String,
+    pub from_instances: i32,
+    pub to_instances: i32,
+    pub cpu_utilization: f64,
+}
+
+/// Outcome of replaying the policy against one group's metric history.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BacktestResult {
+    pub resource_id: String,
+    pub scale_ups: usize,
+    pub scale_downs: usize,
+    pub min_capacity: i32,
+    pub max_capacity: i32,
+    pub actual_instance_hours: f64,
+    pub simulated_instance_hours: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulated_cost: Option<f64>,
+    pub events: Vec<BacktestEvent>,
+}
+
 #[allow(unused_variables, dead_code)]
 pub async fn scale_instances(cmd: &Commands) -> Result<(), AppError> {
     if let Commands::ScaleInstances {
@@ -35,9 +80,16 @@ pub async fn scale_instances(cmd: &Commands) -> Result<(), AppError> {
         resource_group,
         profile,
         dry_run,
+        backtest,
+        instance_price,
+        scale_up_cooldown,
+        scale_down_cooldown,
+        watch,
+        interval,
+        format,
     } = cmd
     {
-        println!(
+        tracing::info!(
             "⚖️  Auto-scaling instances for {}...",
             provider.to_string().green()
         );
@@ -47,16 +99,48 @@ pub async fn scale_instances(cmd: &Commands) -> Result<(), AppError> {
             max_instances: *max_instances,
             cpu_threshold: *cpu_threshold,
             memory_threshold: *memory_threshold,
-            scale_up_cooldown: 300,   // 5 minutes
-            scale_down_cooldown: 600, // 10 minutes
+            scale_up_cooldown: *scale_up_cooldown,
+            scale_down_cooldown: *scale_down_cooldown,
         };
 
+        if let Some(window) = backtest {
+            let results = match provider {
+                CloudProvider::Aws => {
+                    backtest_aws_scaling(&policy, resource_group, window, *instance_price).await?
+                }
+                // Their analysis is not yet backed by real metrics
+                CloudProvider::Gcp | CloudProvider::Azure => {
+                    return Err(AppError::InvalidParameters(format!(
+                        "--backtest is only supported for AWS auto-scaling groups, not {}",
+                        provider
+                    )));
+                }
+            };
+            output_backtest(&results, format)?;
+            tracing::info!("🔍 Backtest only - no changes were made");
+            return Ok(());
+        }
+
+        if *watch {
+            if *interval == 0 {
+                return Err(AppError::InvalidParameters(
+                    "--interval must be at least 1 second".to_string(),
+                ));
+            }
+            return watch_scaling(
+                provider,
+                &policy,
+                resource_group,
+                *dry_run,
+                StdDuration::from_secs(*interval),
+            )
+            .await;
+        }
+
         // Get current metrics and determine scaling actions
-        let actions = match provider {
-            CloudProvider::Aws => analyze_aws_scaling(&policy, resource_group).await?,
-            CloudProvider::Gcp => analyze_gcp_scaling(&policy, resource_group).await?,
-            CloudProvider::Azure => analyze_azure_scaling(&policy, resource_group).await?,
-        };
+        let actions = providers::get(provider)
+            .scale(&policy, resource_group)
+            .await?;
 
         if actions.is_empty() {
             println!("✅ No scaling actions required. Current configuration is optimal.");
@@ -83,126 +167,546 @@ pub async fn scale_instances(cmd: &Commands) -> Result<(), AppError> {
 
         // Execute actions if not in dry run mode
         if !*dry_run {
-            println!("\n🚀 Executing scaling actions...");
+            guard::ensure_writable("scale-instances")?;
+            tracing::info!("🚀 Executing scaling actions...");
+            let outcome = batch::run(
+                "scale-instances",
+                &provider.to_string(),
+                &actions,
+                |action| async move { providers::get(provider).apply_scaling(action).await },
+            )
+            .await;
+            outcome.render(format)?;
+            outcome.into_result()?;
 
-            for action in &actions {
-                match provider {
-                    CloudProvider::Aws => execute_aws_scaling(action).await?,
-                    CloudProvider::Gcp => execute_gcp_scaling(action).await?,
-                    CloudProvider::Azure => execute_azure_scaling(action).await?,
-                }
+            println!("✅ All scaling actions completed successfully!");
+        } else {
+            tracing::info!("🔍 Dry run mode - no changes were made");
+        }
+    }
 
-                println!(
-                    "   ✅ {}: {} → {} instances",
-                    action.action_type.green(),
+    Ok(())
+}
+
+/// The last change `--watch` made to a group.
+struct GroupState {
+    at: DateTime<Utc>,
+    action_type: String,
+    instances: i32,
+}
+
+/// Poll the policy every `interval` and carry out its actions, except on
+/// groups still cooling down from their last change. Every decision is
+/// logged. A failed poll or action is logged and retried on the next poll,
+/// so a transient error doesn't stop the autoscaler; Ctrl-C does.
+async fn watch_scaling(
+    provider: &CloudProvider,
+    policy: &ScalingPolicy,
+    resource_group: &Option<String>,
+    dry_run: bool,
+    interval: StdDuration,
+) -> Result<(), AppError> {
+    if !dry_run {
+        guard::ensure_writable("scale-instances")?;
+    }
+    tracing::info!(
+        "👀 Watching {} every {}s (Ctrl-C to stop)",
+        provider.to_string().green(),
+        interval.as_secs()
+    );
+
+    let mut groups: HashMap<String, GroupState> = HashMap::new();
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    while !interrupt::interrupted() {
+        ticks.tick().await;
+
+        let actions = match providers::get(provider).scale(policy, resource_group).await {
+            Ok(actions) => actions,
+            Err(e) => {
+                tracing::warn!("⚠️  Poll failed, retrying next interval: {}", e);
+                continue;
+            }
+        };
+        if actions.is_empty() {
+            tracing::info!("✅ {} No scaling needed", Utc::now().format("%H:%M:%S"));
+            continue;
+        }
+
+        let now = Utc::now();
+        let mut due = Vec::new();
+        for action in actions {
+            let cooldown = policy.cooldown(action.current_instances, action.target_instances);
+            let last = groups.get(&action.resource_id);
+            match last.filter(|last| (now - last.at).num_seconds() < cooldown as i64) {
+                Some(last) => tracing::info!(
+                    "⏳ {} {}: holding at {} instances, {} {}s ago to {} (cooldown {}s)",
+                    action.action_type,
+                    action.resource_id,
                     action.current_instances,
-                    action.target_instances
-                );
+                    last.action_type,
+                    (now - last.at).num_seconds(),
+                    last.instances,
+                    cooldown
+                ),
+                None => {
+                    tracing::info!(
+                        "⚖️  {} {}: {} → {} instances ({})",
+                        action.action_type,
+                        action.resource_id,
+                        action.current_instances,
+                        action.target_instances,
+                        action.reason
+                    );
+                    due.push(action);
+                }
             }
+        }
 
-            println!("✅ All scaling actions completed successfully!");
-        } else {
-            println!("🔍 Dry run mode - no changes were made");
+        if dry_run || due.is_empty() {
+            continue;
+        }
+        let outcome = batch::run(
+            "scale-instances",
+            &provider.to_string(),
+            &due,
+            |action| async move { providers::get(provider).apply_scaling(action).await },
+        )
+        .await;
+        for (index, action) in due.iter().enumerate() {
+            if outcome.succeeded_at(index) {
+                groups.insert(
+                    action.resource_id.clone(),
+                    GroupState {
+                        at: now,
+                        action_type: action.action_type.clone(),
+                        instances: action.target_instances,
+                    },
+                );
+            } else {
+                tracing::warn!(
+                    "⚠️  {} {} did not complete; retrying next interval",
+                    action.action_type,
+                    action.resource_id
+                );
+            }
         }
     }
 
+    tracing::info!("👋 Stopped watching {}", provider);
     Ok(())
 }
 
+impl ScalingPolicy {
+    /// Seconds a group must wait after its last change before scaling from
+    /// `current` to `target` instances.
+    pub fn cooldown(&self, current: i32, target: i32) -> i32 {
+        if target > current {
+            self.scale_up_cooldown
+        } else {
+            self.scale_down_cooldown
+        }
+    }
+}
+
 #[allow(unused_variables, dead_code, deprecated)]
-async fn analyze_aws_scaling(
+pub(crate) async fn analyze_aws_scaling(
     policy: &ScalingPolicy,
     resource_group: &Option<String>,
 ) -> Result<Vec<ScalingAction>, AppError> {
-    println!("📊 Analyzing AWS auto-scaling groups...");
+    tracing::info!("📊 Analyzing AWS auto-scaling groups...");
 
     // Initialize AWS clients
-    let config = aws_config::from_env().load().await;
-    let autoscaling_client = aws_sdk_autoscaling::Client::new(&config);
-    let cloudwatch_client = aws_sdk_cloudwatch::Client::new(&config);
+    let config = clients::aws_config().await;
+    let autoscaling_client = clients::get::<aws_sdk_autoscaling::Client>(&config).await;
+    let cloudwatch_client = clients::get::<aws_sdk_cloudwatch::Client>(&config).await;
 
+    executor::throttle("autoscaling").await;
     // Get auto-scaling groups
     let response = autoscaling_client
         .describe_auto_scaling_groups()
         .send()
         .await
-        .map_err(|e| AppError::AwsError(e.to_string()))?;
+        .map_err(|e| AppError::aws("autoscaling:DescribeAutoScalingGroups", e))?;
+
+    let groups: Vec<_> = response
+        .auto_scaling_groups()
+        .iter()
+        .filter(|group| {
+            // Skip if specific resource group is specified and doesn't match
+            resource_group.is_none() || group.auto_scaling_group_name() == resource_group.as_deref()
+        })
+        .collect();
+
+    // One batched read for every group rather than a call per group. Memory
+    // comes from the CloudWatch agent, when it reports the group dimension.
+    let end = Utc::now();
+    let start = end - Duration::seconds(CURRENT_WINDOW_SECS);
+    let queries: Vec<MetricQuery> = [
+        ("AWS/EC2", "CPUUtilization"),
+        ("CWAgent", "mem_used_percent"),
+    ]
+    .iter()
+    .flat_map(|&(namespace, metric)| {
+        groups.iter().map(move |group| {
+            group_query(
+                namespace,
+                metric,
+                group.auto_scaling_group_name().unwrap_or_default(),
+            )
+        })
+    })
+    .collect();
+    let mut cpu = cloudwatch::fetch(&cloudwatch_client, &queries, &start, &end, 300).await?;
+    let memory = cpu.split_off(groups.len());
 
     let mut actions = Vec::new();
+    for ((group, cpu), memory) in groups.into_iter().zip(cpu).zip(memory) {
+        let group_name = group
+            .auto_scaling_group_name()
+            .unwrap_or_default()
+            .to_string();
+        let current_capacity = group.desired_capacity().unwrap_or(0);
+
+        // Latest CPU utilization, 0 for groups without datapoints yet
+        let cpu_utilization = cpu.values().next_back().copied().unwrap_or(0.0);
+        // Latest memory utilization, unknown without the agent
+        let memory_utilization = memory.values().next_back().copied();
+        inventory::store_metric("AWS", &group_name, "cpu_utilization", cpu_utilization);
+
+        // Determine if scaling is needed
+        let target_capacity = policy_target(
+            policy,
+            current_capacity,
+            cpu_utilization,
+            memory_utilization,
+        );
 
-    if let Some(groups) = response.auto_scaling_groups {
-        for group in groups {
-            // Skip if specific resource group is specified and doesn't match
-            if let Some(ref target_group) = resource_group {
-                if group.auto_scaling_group_name.as_deref() != Some(target_group) {
-                    continue;
-                }
+        if target_capacity != current_capacity {
+            let reason = format!(
+                "{} utilization (CPU: {:.1}%, Memory: {})",
+                if target_capacity > current_capacity {
+                    "High"
+                } else {
+                    "Low"
+                },
+                cpu_utilization,
+                memory_utilization.map_or("n/a".to_string(), |m| format!("{:.1}%", m))
+            );
+            let mut metrics = HashMap::new();
+            metrics.insert("cpu_utilization".to_string(), cpu_utilization);
+            if let Some(memory_utilization) = memory_utilization {
+                metrics.insert("memory_utilization".to_string(), memory_utilization);
             }
 
-            let group_name = group.auto_scaling_group_name.unwrap_or_default();
-            let current_capacity = group.desired_capacity.unwrap_or(0);
-
-            // Get CPU utilization metrics
-            let cpu_utilization = get_aws_cpu_utilization(&cloudwatch_client, &group_name).await?;
-            let memory_utilization =
-                get_aws_memory_utilization(&cloudwatch_client, &group_name).await?;
-
-            // Determine if scaling is needed
-            let mut target_capacity = current_capacity;
-            let mut reason = String::new();
-
-            if cpu_utilization > policy.cpu_threshold
-                || memory_utilization > policy.memory_threshold
-            {
-                if current_capacity < policy.max_instances {
-                    target_capacity = (current_capacity + 1).min(policy.max_instances);
-                    reason = format!(
-                        "High utilization (CPU: {:.1}%, Memory: {:.1}%)",
-                        cpu_utilization, memory_utilization
-                    );
-                }
-            } else if cpu_utilization < policy.cpu_threshold * 0.5
-                && memory_utilization < policy.memory_threshold * 0.5
-            {
-                if current_capacity > policy.min_instances {
-                    target_capacity = (current_capacity - 1).max(policy.min_instances);
-                    reason = format!(
-                        "Low utilization (CPU: {:.1}%, Memory: {:.1}%)",
-                        cpu_utilization, memory_utilization
-                    );
-                }
+            actions.push(ScalingAction {
+                action_type: if target_capacity > current_capacity {
+                    "SCALE_UP".to_string()
+                } else {
+                    "SCALE_DOWN".to_string()
+                },
+                resource_id: group_name,
+                current_instances: current_capacity,
+                target_instances: target_capacity,
+                reason,
+                metrics,
+            });
+        }
+    }
+
+    Ok(actions)
+}
+
+/// Capacity the policy moves a group at `current` instances to: one
+/// instance up when either threshold is exceeded, one down when both are
+/// under half their threshold. Unknown memory leaves the decision to CPU.
+fn policy_target(policy: &ScalingPolicy, current: i32, cpu: f64, memory: Option<f64>) -> i32 {
+    if cpu > policy.cpu_threshold || memory.is_some_and(|m| m > policy.memory_threshold) {
+        (current + 1).min(policy.max_instances).max(current)
+    } else if cpu < policy.cpu_threshold * 0.5
+        && memory.map_or(true, |m| m < policy.memory_threshold * 0.5)
+    {
+        (current - 1).max(policy.min_instances).min(current)
+    } else {
+        current
+    }
+}
+
+/// Replay the policy over each group's CPU history. The observed load is
+/// spread over the simulated capacity, so scaling up lowers the utilization
+/// the next period sees.
+async fn backtest_aws_scaling(
+    policy: &ScalingPolicy,
+    resource_group: &Option<String>,
+    window: &str,
+    instance_price: Option<f64>,
+) -> Result<Vec<BacktestResult>, AppError> {
+    let window_secs = (query::parse_days(window)? * 86400.0) as i64;
+    if window_secs < BACKTEST_PERIOD_SECS {
+        return Err(AppError::InvalidParameters(format!(
+            "Backtest window '{}' must be at least 5 minutes",
+            window
+        )));
+    }
+    let end = Utc::now();
+    let start = end - Duration::seconds(window_secs);
+
+    tracing::info!(
+        "⏪ Replaying the scaling policy over the last {}...",
+        window.green()
+    );
+    // Groups publish no memory metric, so only the CPU threshold is replayed
+    println!("   ℹ️  Memory is not recorded for groups; the backtest uses CPU only");
+
+    let config = clients::aws_config().await;
+    let autoscaling_client = clients::get::<aws_sdk_autoscaling::Client>(&config).await;
+    let cloudwatch_client = clients::get::<aws_sdk_cloudwatch::Client>(&config).await;
+
+    executor::throttle("autoscaling").await;
+    let response = autoscaling_client
+        .describe_auto_scaling_groups()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("autoscaling:DescribeAutoScalingGroups", e))?;
+
+    let groups: Vec<_> = response
+        .auto_scaling_groups()
+        .iter()
+        .filter(|group| {
+            group.auto_scaling_group_name().is_some()
+                && (resource_group.is_none()
+                    || group.auto_scaling_group_name() == resource_group.as_deref())
+        })
+        .collect();
+
+    // CPU and desired capacity of every group in one batched read. Capacity
+    // is only recorded when group metrics collection is enabled; otherwise
+    // the current desired capacity stands in for the history
+    let queries: Vec<MetricQuery> = groups
+        .iter()
+        .flat_map(|group| {
+            let group_name = group.auto_scaling_group_name().unwrap_or_default();
+            [
+                group_query("AWS/EC2", "CPUUtilization", group_name),
+                group_query("AWS/AutoScaling", "GroupDesiredCapacity", group_name),
+            ]
+        })
+        .collect();
+    let history = cloudwatch::fetch(
+        &cloudwatch_client,
+        &queries,
+        &start,
+        &end,
+        BACKTEST_PERIOD_SECS,
+    )
+    .await?;
+
+    let mut results = Vec::new();
+    for (group, series) in groups.into_iter().zip(history.chunks(2)) {
+        let group_name = group.auto_scaling_group_name().unwrap_or_default();
+        let (cpu, capacity) = (&series[0], &series[1]);
+        if cpu.is_empty() {
+            println!(
+                "   ⚠️  No CPU history for {}, skipping",
+                group_name.yellow()
+            );
+            continue;
+        }
+        let fallback = group.desired_capacity().unwrap_or(policy.min_instances) as f64;
+
+        results.push(replay(
+            policy,
+            group_name,
+            cpu,
+            capacity,
+            fallback,
+            instance_price,
+        ));
+    }
+
+    Ok(results)
+}
+
+/// Average of a group metric per period.
+fn group_query(namespace: &'static str, metric_name: &str, group_name: &str) -> MetricQuery {
+    MetricQuery::new(
+        namespace,
+        metric_name,
+        "AutoScalingGroupName",
+        group_name,
+        aws_sdk_cloudwatch::types::Statistic::Average,
+    )
+}
+
+fn replay(
+    policy: &ScalingPolicy,
+    group_name: &str,
+    cpu: &BTreeMap<DateTime<Utc>, f64>,
+    capacity: &BTreeMap<DateTime<Utc>, f64>,
+    fallback_capacity: f64,
+    instance_price: Option<f64>,
+) -> BacktestResult {
+    let period_hours = BACKTEST_PERIOD_SECS as f64 / 3600.0;
+    let actual_at = |timestamp: &DateTime<Utc>| {
+        capacity
+            .range(..=*timestamp)
+            .next_back()
+            .map(|(_, c)| *c)
+            .unwrap_or(fallback_capacity)
+            .max(1.0)
+    };
+
+    let first = cpu
+        .keys()
+        .next()
+        .map(&actual_at)
+        .unwrap_or(fallback_capacity);
+    let mut simulated = (first.round() as i32).clamp(policy.min_instances, policy.max_instances);
+    let mut last_event: Option<DateTime<Utc>> = None;
+    let mut result = BacktestResult {
+        resource_id: group_name.to_string(),
+        scale_ups: 0,
+        scale_downs: 0,
+        min_capacity: simulated,
+        max_capacity: simulated,
+        actual_instance_hours: 0.0,
+        simulated_instance_hours: 0.0,
+        actual_cost: None,
+        simulated_cost: None,
+        events: Vec::new(),
+    };
+
+    for (timestamp, utilization) in cpu {
+        let actual = actual_at(timestamp);
+        result.actual_instance_hours += actual * period_hours;
+        result.simulated_instance_hours += simulated as f64 * period_hours;
+
+        let load = utilization * actual;
+        let simulated_cpu = load / simulated.max(1) as f64;
+        let target = policy_target(policy, simulated, simulated_cpu, None);
+        if target == simulated {
+            continue;
+        }
+
+        let cooldown = policy.cooldown(simulated, target);
+        if last_event.is_some_and(|t| (*timestamp - t).num_seconds() < cooldown as i64) {
+            continue;
+        }
+
+        let action_type = if target > simulated {
+            result.scale_ups += 1;
+            "SCALE_UP"
+        } else {
+            result.scale_downs += 1;
+            "SCALE_DOWN"
+        };
+        result.events.push(BacktestEvent {
+            timestamp: *timestamp,
+            action_type: action_type.to_string(),
+            from_instances: simulated,
+            to_instances: target,
+            cpu_utilization: simulated_cpu,
+        });
+        simulated = target;
+        last_event = Some(*timestamp);
+        result.min_capacity = result.min_capacity.min(simulated);
+        result.max_capacity = result.max_capacity.max(simulated);
+    }
+
+    result.actual_cost = instance_price.map(|price| price * result.actual_instance_hours);
+    result.simulated_cost = instance_price.map(|price| price * result.simulated_instance_hours);
+    result
+}
+
+fn output_backtest(results: &[BacktestResult], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            if results.is_empty() {
+                println!("✅ No auto-scaling groups with metric history to replay.");
+                return Ok(());
             }
 
-            if target_capacity != current_capacity {
-                let mut metrics = HashMap::new();
-                metrics.insert("cpu_utilization".to_string(), cpu_utilization);
-                metrics.insert("memory_utilization".to_string(), memory_utilization);
+            println!("\n📋 Backtest results:");
+            println!(
+                "{:<30} {:>5} {:>7} {:>10} {:>14} {:>14} {:>12}",
+                "Group", "Ups", "Downs", "Capacity", "Actual hrs", "Policy hrs", "Cost delta"
+            );
+            println!("{:-<98}", "");
+
+            for result in results {
+                let delta = match (result.actual_cost, result.simulated_cost) {
+                    (Some(actual), Some(simulated)) => {
+                        let delta = simulated - actual;
+                        let text = format!("{:+.2}", delta);
+                        if delta > 0.0 {
+                            text.red()
+                        } else {
+                            text.green()
+                        }
+                    }
+                    _ => "-".normal(),
+                };
+                println!(
+                    "{:<30} {:>5} {:>7} {:>10} {:>14.1} {:>14.1} {:>12}",
+                    result.resource_id,
+                    result.scale_ups,
+                    result.scale_downs,
+                    format!("{}-{}", result.min_capacity, result.max_capacity),
+                    result.actual_instance_hours,
+                    result.simulated_instance_hours,
+                    delta
+                );
+            }
 
-                actions.push(ScalingAction {
-                    action_type: if target_capacity > current_capacity {
-                        "SCALE_UP".to_string()
-                    } else {
-                        "SCALE_DOWN".to_string()
-                    },
-                    resource_id: group_name,
-                    current_instances: current_capacity,
-                    target_instances: target_capacity,
-                    reason,
-                    metrics,
-                });
+            if results.iter().all(|r| r.simulated_cost.is_none()) {
+                println!("💡 Pass --instance-price to estimate the cost of each run");
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(results)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, results)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Group,ScaleUps,ScaleDowns,MinCapacity,MaxCapacity,ActualInstanceHours,SimulatedInstanceHours,ActualCost,SimulatedCost");
+            for result in results {
+                println!(
+                    "{},{},{},{},{},{:.2},{:.2},{},{}",
+                    result.resource_id,
+                    result.scale_ups,
+                    result.scale_downs,
+                    result.min_capacity,
+                    result.max_capacity,
+                    result.actual_instance_hours,
+                    result.simulated_instance_hours,
+                    result
+                        .actual_cost
+                        .map(|c| format!("{:.2}", c))
+                        .unwrap_or_default(),
+                    result
+                        .simulated_cost
+                        .map(|c| format!("{:.2}", c))
+                        .unwrap_or_default()
+                );
             }
         }
     }
 
-    Ok(actions)
+    Ok(())
 }
 
 #[allow(unused_variables, dead_code)]
-async fn analyze_gcp_scaling(
+pub(crate) async fn analyze_gcp_scaling(
     policy: &ScalingPolicy,
     resource_group: &Option<String>,
 ) -> Result<Vec<ScalingAction>, AppError> {
-    println!("📊 Analyzing GCP instance groups...");
+    tracing::info!("📊 Analyzing GCP instance groups...");
 
     // For GCP, we would use the Compute Engine API
     // This is a simplified implementation
@@ -241,11 +745,11 @@ async fn analyze_gcp_scaling(
 }
 
 #[allow(unused_variables, dead_code)]
-async fn analyze_azure_scaling(
+pub(crate) async fn analyze_azure_scaling(
     policy: &ScalingPolicy,
     resource_group: &Option<String>,
 ) -> Result<Vec<ScalingAction>, AppError> {
-    println!("📊 Analyzing Azure virtual machine scale sets...");
+    tracing::info!("📊 Analyzing Azure virtual machine scale sets...");
 
     // For Azure, we would use the Compute Management API
     // This is a simplified implementation
@@ -283,72 +787,43 @@ async fn analyze_azure_scaling(
     Ok(actions)
 }
 
-#[allow(unused_variables, dead_code)]
-async fn get_aws_cpu_utilization(
-    client: &aws_sdk_cloudwatch::Client,
-    group_name: &str,
-) -> Result<f64, AppError> {
-    // Get CPU utilization from CloudWatch
-    let now = aws_sdk_cloudwatch::primitives::DateTime::from_secs(chrono::Utc::now().timestamp());
-    let ten_minutes_ago = aws_sdk_cloudwatch::primitives::DateTime::from_secs(
-        (chrono::Utc::now() - chrono::Duration::minutes(10)).timestamp(),
-    );
-
-    let response = client
-        .get_metric_statistics()
-        .namespace("AWS/AutoScaling")
-        .metric_name("CPUUtilization")
-        .dimensions(
-            aws_sdk_cloudwatch::types::Dimension::builder()
-                .name("AutoScalingGroupName")
-                .value(group_name)
-                .build(),
-        )
-        .start_time(ten_minutes_ago)
-        .end_time(now)
-        .period(300)
-        .statistics(aws_sdk_cloudwatch::types::Statistic::Average)
-        .send()
-        .await
-        .map_err(|e| AppError::AwsError(e.to_string()))?;
-
-    if let Some(datapoints) = response.datapoints {
-        if let Some(latest) = datapoints.iter().max_by_key(|dp| dp.timestamp) {
-            return Ok(latest.average.unwrap_or(0.0));
-        }
+impl BatchAction for ScalingAction {
+    fn action(&self) -> &str {
+        &self.action_type
     }
 
-    Ok(0.0)
-}
+    fn resource_id(&self) -> &str {
+        &self.resource_id
+    }
 
-#[allow(unused_variables, dead_code)]
-async fn get_aws_memory_utilization(
-    client: &aws_sdk_cloudwatch::Client,
-    group_name: &str,
-) -> Result<f64, AppError> {
-    // Memory utilization is not directly available from CloudWatch for Auto Scaling Groups
-    // In a real implementation, you'd need to set up custom metrics
-    // For now, we'll return a simulated value
-    Ok(70.0)
+    fn describe(&self) -> String {
+        format!(
+            "{} {}: {} → {} instances",
+            self.action_type, self.resource_id, self.current_instances, self.target_instances
+        )
+    }
 }
 
 #[allow(unused_variables, dead_code, deprecated)]
-async fn execute_aws_scaling(action: &ScalingAction) -> Result<(), AppError> {
-    let config = aws_config::from_env().load().await;
-    let autoscaling_client = aws_sdk_autoscaling::Client::new(&config);
+pub(crate) async fn execute_aws_scaling(action: &ScalingAction) -> Result<(), AppError> {
+    let config = clients::aws_config().await;
+    let autoscaling_client = clients::get::<aws_sdk_autoscaling::Client>(&config).await;
 
+    executor::throttle("autoscaling").await;
     autoscaling_client
         .set_desired_capacity()
         .auto_scaling_group_name(&action.resource_id)
         .desired_capacity(action.target_instances)
         .send()
         .await
-        .map_err(|e| AppError::AwsError(e.to_string()))?;
+        .map_err(|e| {
+            AppError::aws("autoscaling:SetDesiredCapacity", e).for_resource(&action.resource_id)
+        })?;
 
     Ok(())
 }
 
-async fn execute_gcp_scaling(action: &ScalingAction) -> Result<(), AppError> {
+pub(crate) async fn execute_gcp_scaling(action: &ScalingAction) -> Result<(), AppError> {
     // In a real implementation, you'd use the GCP Compute Engine API
     // to resize the instance group
     println!(
@@ -358,7 +833,7 @@ async fn execute_gcp_scaling(action: &ScalingAction) -> Result<(), AppError> {
     Ok(())
 }
 
-async fn execute_azure_scaling(action: &ScalingAction) -> Result<(), AppError> {
+pub(crate) async fn execute_azure_scaling(action: &ScalingAction) -> Result<(), AppError> {
     // In a real implementation, you'd use the Azure Compute Management API
     // to resize the virtual machine scale set
     println!(
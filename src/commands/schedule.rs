@@ -0,0 +1,486 @@
+use crate::cli::{Cli, Commands, OutputFormat, ScheduleAction};
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::guard;
+use crate::report;
+use crate::schedules::{self, Job};
+use chrono::{DateTime, Local, Utc};
+use clap::Parser;
+use colored::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Longest the daemon sleeps, so jobs added or removed meanwhile are seen
+const MAX_DAEMON_SLEEP_SECS: i64 = 60;
+
+/// Longest an SQS receive waits for events, and how long to back off after
+/// a failed one
+const EVENTS_WAIT_SECS: i32 = 20;
+const EVENTS_RETRY_SECS: u64 = 30;
+
+/// A job with its next run, as `schedule list` shows it.
+#[derive(Serialize)]
+struct JobView<'a> {
+    #[serde(flatten)]
+    job: &'a Job,
+    next_run: Option<DateTime<Utc>>,
+}
+
+pub async fn schedule(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Schedule { action } = cmd {
+        match action {
+            ScheduleAction::Add {
+                name,
+                cron,
+                command,
+            } => add_job(name, cron, command)?,
+            ScheduleAction::List { format } => list_jobs(format)?,
+            ScheduleAction::Remove { name } => remove_job(name)?,
+            ScheduleAction::Run { name } => {
+                let job = find_job(name)?;
+                run_job(&job).await?;
+            }
+            ScheduleAction::Daemon {
+                events_queue,
+                events_force,
+            } => daemon(events_queue.as_deref(), *events_force).await?,
+        }
+    }
+
+    Ok(())
+}
+
+fn add_job(name: &str, cron: &str, command: &[String]) -> Result<(), AppError> {
+    schedules::parse_cron(cron)?;
+
+    // Catch typos now rather than at three in the morning
+    let parsed =
+        Cli::try_parse_from(std::iter::once("actlog").chain(command.iter().map(|a| a.as_str())))
+            .map_err(|e| {
+                AppError::InvalidParameters(format!(
+                    "Not a valid actlog command: {}",
+                    e.to_string().lines().next().unwrap_or_default()
+                ))
+            })?;
+    if matches!(
+        parsed.command,
+        Commands::Schedule { .. } | Commands::Dashboard { .. } | Commands::Replay { .. }
+    ) {
+        return Err(AppError::InvalidParameters(
+            "schedule, dashboard and replay can't be scheduled".to_string(),
+        ));
+    }
+
+    let mut jobs = schedules::load_jobs()?;
+    if jobs.iter().any(|j| j.name == name) {
+        return Err(AppError::InvalidParameters(format!(
+            "A job named {} already exists; remove it first",
+            name
+        )));
+    }
+    let job = Job {
+        name: name.to_string(),
+        cron: cron.to_string(),
+        command: command.to_vec(),
+        created_at: Utc::now(),
+        last_run: None,
+        last_status: None,
+    };
+    let next = job.next_run(Utc::now());
+    jobs.push(job);
+    schedules::save_jobs(jobs)?;
+
+    println!("🗓️  Scheduled {} ({})", name.green(), cron);
+    if let Some(next) = next {
+        println!("   Next run: {}", format_time(next));
+    }
+    println!("   Start `actlog schedule daemon` to run scheduled jobs");
+    Ok(())
+}
+
+fn list_jobs(format: &OutputFormat) -> Result<(), AppError> {
+    let jobs = schedules::load_jobs()?;
+    let now = Utc::now();
+    let views: Vec<JobView> = jobs
+        .iter()
+        .map(|job| JobView {
+            job,
+            next_run: job.next_run(now),
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Table => {
+            if views.is_empty() {
+                println!(
+                    "ℹ️  No jobs are scheduled. Schedule file: {}",
+                    schedules::schedules_path()?.display()
+                );
+                return Ok(());
+            }
+
+            println!(
+                "{:<20} {:<16} {:<17} {:<17} {:<10} {}",
+                "Name", "Cron", "Next run", "Last run", "Status", "Command"
+            );
+            println!("{:-<110}", "");
+            for view in &views {
+                let job = view.job;
+                let status = match job.last_status.as_deref() {
+                    Some("succeeded") => "succeeded".green(),
+                    Some(_) => "failed".red(),
+                    None => "".normal(),
+                };
+                println!(
+                    "{:<20} {:<16} {:<17} {:<17} {:<10} {}",
+                    job.name,
+                    job.cron,
+                    view.next_run.map(format_time).unwrap_or_default(),
+                    job.last_run.map(format_time).unwrap_or_default(),
+                    status,
+                    job.command_line()
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&views)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, &views)?);
+        }
+
+        OutputFormat::Csv => {
+            print!(
+                "{}",
+                report::csv_record(
+                    &[
+                        "Name",
+                        "Cron",
+                        "NextRun",
+                        "LastRun",
+                        "LastStatus",
+                        "Command"
+                    ],
+                    ','
+                )?
+            );
+            for view in &views {
+                let job = view.job;
+                let next_run = view.next_run.map(|t| t.to_rfc3339()).unwrap_or_default();
+                let last_run = job.last_run.map(|t| t.to_rfc3339()).unwrap_or_default();
+                print!(
+                    "{}",
+                    report::csv_record(
+                        &[
+                            job.name.as_str(),
+                            job.cron.as_str(),
+                            next_run.as_str(),
+                            last_run.as_str(),
+                            job.last_status.as_deref().unwrap_or(""),
+                            job.command_line().as_str(),
+                        ],
+                        ','
+                    )?
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn remove_job(name: &str) -> Result<(), AppError> {
+    let mut jobs = schedules::load_jobs()?;
+    let before = jobs.len();
+    jobs.retain(|j| j.name != name);
+    if jobs.len() == before {
+        return Err(AppError::ResourceNotFound(format!(
+            "No scheduled job named {}",
+            name
+        )));
+    }
+
+    schedules::save_jobs(jobs)?;
+    println!("🗑️  Removed the scheduled job {}", name.green());
+    Ok(())
+}
+
+fn find_job(name: &str) -> Result<Job, AppError> {
+    schedules::load_jobs()?
+        .into_iter()
+        .find(|j| j.name == name)
+        .ok_or_else(|| AppError::ResourceNotFound(format!("No scheduled job named {}", name)))
+}
+
+/// Run `job` as a separate actlog process, so each run starts with fresh
+/// configuration and a failure can't take the daemon down, then record how
+/// it went.
+async fn run_job(job: &Job) -> Result<(), AppError> {
+    tracing::info!(
+        "▶️  Running {}: actlog {}",
+        job.name.green(),
+        job.command_line()
+    );
+
+    let started = Utc::now();
+    let status = actlog_command()?.args(&job.command).status().await?;
+    let outcome = if status.success() {
+        "succeeded".to_string()
+    } else {
+        match status.code() {
+            Some(code) => format!("exited with {}", code),
+            None => "killed by a signal".to_string(),
+        }
+    };
+
+    // Jobs finish concurrently under the daemon; update the file one at a
+    // time so no run's status is lost. The job may have been removed while
+    // it ran.
+    {
+        static UPDATING: Mutex<()> = Mutex::new(());
+        let _updating = UPDATING.lock();
+        let mut jobs = schedules::load_jobs()?;
+        if let Some(stored) = jobs.iter_mut().find(|j| j.name == job.name) {
+            stored.last_run = Some(started);
+            stored.last_status = Some(outcome.clone());
+            schedules::save_jobs(jobs)?;
+        }
+    }
+
+    if status.success() {
+        tracing::info!("✅ {} {}", job.name, outcome);
+        Ok(())
+    } else {
+        Err(AppError::JobFailed(format!("{} {}", job.name, outcome)))
+    }
+}
+
+/// Run every job when its schedule comes due, until interrupted. Due jobs
+/// run side by side, so a long job doesn't hold up the others; a job still
+/// running when it comes due again skips that run. Runs missed while the
+/// daemon was down are not caught up. A failing job is logged and runs
+/// again at its next scheduled time. With `events_queue`, resource events
+/// are evaluated for cleanup alongside the jobs as they arrive.
+async fn daemon(events_queue: Option<&str>, events_force: bool) -> Result<(), AppError> {
+    let jobs = schedules::load_jobs()?;
+    tracing::info!(
+        "🗓️  Scheduler started with {} job(s) from {} (Ctrl-C to stop)",
+        jobs.len(),
+        schedules::schedules_path()?.display()
+    );
+    if let Some(queue) = events_queue {
+        if events_force {
+            guard::ensure_writable("schedule daemon --events-force")?;
+        }
+        let queue = queue.to_string();
+        tokio::spawn(async move { watch_events(&queue, events_force).await });
+    }
+
+    let running: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+    let mut checked = Utc::now();
+    loop {
+        let jobs = schedules::load_jobs()?;
+        let now = Utc::now();
+        for job in &jobs {
+            if !job.next_run(checked).is_some_and(|at| at <= now) {
+                continue;
+            }
+            let started = running
+                .lock()
+                .map(|mut names| names.insert(job.name.clone()))
+                .unwrap_or(false);
+            if !started {
+                tracing::warn!(
+                    "⚠️  {} is still running; skipping this run",
+                    job.name.yellow()
+                );
+                continue;
+            }
+
+            let (job, running) = (job.clone(), Arc::clone(&running));
+            tokio::spawn(async move {
+                if let Err(e) = run_job(&job).await {
+                    tracing::warn!("⚠️  {}", e);
+                }
+                if let Ok(mut names) = running.lock() {
+                    names.remove(&job.name);
+                }
+            });
+        }
+        checked = now;
+
+        let now = Utc::now();
+        let wake = jobs
+            .iter()
+            .filter_map(|job| job.next_run(checked))
+            .min()
+            .map_or(MAX_DAEMON_SLEEP_SECS, |at| {
+                (at - now).num_seconds().clamp(1, MAX_DAEMON_SLEEP_SECS)
+            });
+        tokio::time::sleep(Duration::from_secs(wake as u64)).await;
+    }
+}
+
+/// Receive EventBridge events from the SQS queue at `queue_url` until the
+/// daemon stops, and run a cleanup of each resource they name: an instance
+/// that stopped, or a volume that was detached. Cleanup is a dry run unless
+/// `force`, and its own rules (idle thresholds, tags, policy) decide whether
+/// the resource goes. Messages are deleted once handled, including ones
+/// that aren't events cleanup cares about.
+async fn watch_events(queue_url: &str, force: bool) {
+    tracing::info!("📬 Watching {} for resource events", queue_url);
+    let config = clients::aws_config().await;
+    let sqs = clients::get::<aws_sdk_sqs::Client>(&config).await;
+    let running: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+
+    loop {
+        executor::throttle("sqs").await;
+        let received = match sqs
+            .receive_message()
+            .queue_url(queue_url)
+            .max_number_of_messages(10)
+            .wait_time_seconds(EVENTS_WAIT_SECS)
+            .send()
+            .await
+        {
+            Ok(received) => received,
+            Err(e) => {
+                tracing::warn!(
+                    "⚠️  {}; retrying in {}s",
+                    AppError::aws("sqs:ReceiveMessage", e),
+                    EVENTS_RETRY_SECS
+                );
+                tokio::time::sleep(Duration::from_secs(EVENTS_RETRY_SECS)).await;
+                continue;
+            }
+        };
+
+        for message in received.messages() {
+            let event = message
+                .body()
+                .and_then(|body| serde_json::from_str::<serde_json::Value>(body).ok());
+            match event.as_ref().and_then(cleanup_target) {
+                Some(target) => dispatch_cleanup(target, force, &running),
+                None => log::debug!("Ignoring message {:?}", message.message_id()),
+            }
+
+            let Some(receipt) = message.receipt_handle() else {
+                continue;
+            };
+            executor::throttle("sqs").await;
+            if let Err(e) = sqs
+                .delete_message()
+                .queue_url(queue_url)
+                .receipt_handle(receipt)
+                .send()
+                .await
+            {
+                tracing::warn!("⚠️  {}", AppError::aws("sqs:DeleteMessage", e));
+            }
+        }
+    }
+}
+
+/// A resource an event makes a cleanup candidate.
+struct CleanupTarget {
+    /// `cleanup aws` resource type
+    resource_type: &'static str,
+    id: String,
+    region: Option<String>,
+}
+
+/// The resource an EventBridge event is about, if it's one cleanup acts on.
+fn cleanup_target(event: &serde_json::Value) -> Option<CleanupTarget> {
+    let detail = &event["detail"];
+    let (resource_type, id) = match event["detail-type"].as_str()? {
+        "EC2 Instance State-change Notification" if detail["state"] == "stopped" => {
+            ("ec2", detail["instance-id"].as_str()?)
+        }
+        "AWS API Call via CloudTrail" if detail["eventName"] == "DetachVolume" => {
+            ("volume", detail["requestParameters"]["volumeId"].as_str()?)
+        }
+        _ => return None,
+    };
+    Some(CleanupTarget {
+        resource_type,
+        id: id.to_string(),
+        region: event["region"].as_str().map(str::to_string),
+    })
+}
+
+/// A new actlog process, read-only when this one is: `--read-only` on the
+/// daemon has to hold for every job it starts too.
+fn actlog_command() -> Result<tokio::process::Command, AppError> {
+    let mut command = tokio::process::Command::new(std::env::current_exe()?);
+    if guard::is_read_only() {
+        command.arg("--read-only");
+    }
+    Ok(command)
+}
+
+/// Run `actlog cleanup aws` on the target alone in a separate process, as
+/// scheduled jobs run. A resource already being evaluated isn't evaluated
+/// again until that run finishes.
+fn dispatch_cleanup(target: CleanupTarget, force: bool, running: &Arc<Mutex<HashSet<String>>>) {
+    let started = running
+        .lock()
+        .map(|mut ids| ids.insert(target.id.clone()))
+        .unwrap_or(false);
+    if !started {
+        log::debug!("{} is already being evaluated", target.id);
+        return;
+    }
+
+    let running = Arc::clone(running);
+    tokio::spawn(async move {
+        tracing::info!(
+            "📨 Evaluating {} {} for cleanup",
+            target.resource_type,
+            target.id.green()
+        );
+        let mut command = match actlog_command() {
+            Ok(command) => command,
+            Err(e) => {
+                tracing::warn!("⚠️  {}", e);
+                return;
+            }
+        };
+        command.args([
+            "cleanup",
+            "aws",
+            target.resource_type,
+            "--id",
+            target.id.as_str(),
+            if force { "--force" } else { "--dry-run" },
+        ]);
+        if let Some(region) = &target.region {
+            command.env("AWS_REGION", region);
+        }
+        match command.status().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => tracing::warn!(
+                "⚠️  Cleanup of {} exited with {}",
+                target.id,
+                status
+                    .code()
+                    .map_or("a signal".to_string(), |c| c.to_string())
+            ),
+            Err(e) => tracing::warn!("⚠️  Could not run cleanup of {}: {}", target.id, e),
+        }
+        if let Ok(mut ids) = running.lock() {
+            ids.remove(&target.id);
+        }
+    });
+}
+
+fn format_time(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
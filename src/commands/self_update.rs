@@ -0,0 +1,217 @@
+use crate::cli::Commands;
+use crate::error::AppError;
+use crate::executor;
+use crate::retry;
+use colored::*;
+use minisign_verify::{PublicKey, Signature};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+const LATEST_RELEASE: &str = "https://api.github.com/repos/Execute-Soft/actlog/releases/latest";
+
+/// Release asset listing `<sha256>  <asset name>` for every binary
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+/// Minisign signature of [`CHECKSUMS_ASSET`], made by the release workflow
+const SIGNATURE_ASSET: &str = "SHA256SUMS.minisig";
+
+/// Public half of the release signing key, embedded by release builds so a
+/// checksum file is only trusted if the maintainers signed it
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("ACTLOG_RELEASE_PUBLIC_KEY");
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn self_update(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::SelfUpdate { check, force } = cmd {
+        let current = env!("CARGO_PKG_VERSION");
+        tracing::info!("🔄 Checking for a newer actlog release...");
+
+        let client = reqwest::Client::new();
+        executor::throttle("github").await;
+        let response = retry::send_http("github:GetLatestRelease", || {
+            client
+                .get(LATEST_RELEASE)
+                .header("User-Agent", "actlog")
+                .header("Accept", "application/vnd.github+json")
+        })
+        .await?;
+        let release: Release = response.error_for_status()?.json().await?;
+
+        let latest = release.tag_name.trim_start_matches('v');
+        if parse_version(latest) <= parse_version(current) {
+            println!("✅ actlog {} is the latest version", current.green());
+            return Ok(());
+        }
+
+        println!(
+            "⬆️  actlog {} is available (installed: {})",
+            latest.green(),
+            current
+        );
+        if *check {
+            tracing::info!("🔍 Re-run without --check to install it");
+            return Ok(());
+        }
+
+        let asset_name = binary_asset_name();
+        let find_asset = |name: &str| {
+            release
+                .assets
+                .iter()
+                .find(|a| a.name == name)
+                .ok_or_else(|| {
+                    AppError::UpdateError(format!(
+                        "Release {} has no {} asset",
+                        release.tag_name, name
+                    ))
+                })
+        };
+        let binary = find_asset(&asset_name)?;
+        let checksums = find_asset(CHECKSUMS_ASSET)?;
+        let signature = find_asset(SIGNATURE_ASSET)?;
+        let public_key = release_public_key()?;
+
+        if !*force {
+            print!("Replace {} with {}? (y/N): ", current, latest);
+            use std::io::{self, Write};
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+
+            if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
+                println!("❌ Update cancelled by user.");
+                return Err(AppError::UserCancelled);
+            }
+        }
+
+        tracing::info!("📥 Downloading {}...", asset_name);
+        let sums = download(&client, &checksums.browser_download_url).await?;
+        let signature = download(&client, &signature.browser_download_url).await?;
+        verify_signature(&public_key, &sums, &signature)?;
+        let expected = String::from_utf8_lossy(&sums)
+            .lines()
+            .filter_map(|line| line.split_once(char::is_whitespace))
+            .find(|(_, name)| name.trim().trim_start_matches('*') == asset_name)
+            .map(|(sum, _)| sum.to_lowercase())
+            .ok_or_else(|| {
+                AppError::UpdateError(format!(
+                    "{} has no entry for {}",
+                    CHECKSUMS_ASSET, asset_name
+                ))
+            })?;
+
+        let bytes = download(&client, &binary.browser_download_url).await?;
+        let actual = format!("{:x}", Sha256::digest(&bytes));
+        if actual != expected {
+            return Err(AppError::UpdateError(format!(
+                "Checksum mismatch for {} (expected {}, got {})",
+                asset_name, expected, actual
+            )));
+        }
+        println!("🔒 Checksum verified");
+
+        let exe = std::env::current_exe()?;
+        replace_executable(&exe, &bytes)?;
+        println!("✅ Updated {} to actlog {}", exe.display(), latest.green());
+    }
+
+    Ok(())
+}
+
+/// The embedded release signing key. Builds made outside the release
+/// workflow have none and can't verify a download, so they don't update.
+fn release_public_key() -> Result<PublicKey, AppError> {
+    let key = RELEASE_PUBLIC_KEY.ok_or_else(|| {
+        AppError::UpdateError(
+            "This build has no release signing key, so downloads can't be verified; install a release binary or rebuild from source".to_string(),
+        )
+    })?;
+    PublicKey::from_base64(key)
+        .map_err(|e| AppError::UpdateError(format!("Invalid release signing key: {}", e)))
+}
+
+/// Check that `sums` was signed with the release key, before any checksum
+/// in it is believed.
+fn verify_signature(public_key: &PublicKey, sums: &[u8], signature: &[u8]) -> Result<(), AppError> {
+    let signature = Signature::decode(&String::from_utf8_lossy(signature))
+        .map_err(|e| AppError::UpdateError(format!("Unreadable {}: {}", SIGNATURE_ASSET, e)))?;
+    public_key.verify(sums, &signature, false).map_err(|e| {
+        AppError::UpdateError(format!(
+            "{} is not signed by the release key: {}",
+            CHECKSUMS_ASSET, e
+        ))
+    })?;
+    println!("🔏 Signature of {} verified", CHECKSUMS_ASSET);
+    Ok(())
+}
+
+/// Asset name of the prebuilt binary for this platform, e.g.
+/// `actlog-x86_64-linux` or `actlog-aarch64-macos`.
+fn binary_asset_name() -> String {
+    format!(
+        "actlog-{}-{}{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+/// `1.2.3` as comparable numbers; pre-release suffixes are ignored.
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, AppError> {
+    executor::throttle("github").await;
+    let response = retry::send_http("github:DownloadReleaseAsset", || {
+        client.get(url).header("User-Agent", "actlog")
+    })
+    .await?;
+    Ok(response.error_for_status()?.bytes().await?.to_vec())
+}
+
+/// Write the new binary next to `exe` and move it into place, so a failed
+/// write never leaves a half-written executable behind.
+fn replace_executable(exe: &Path, bytes: &[u8]) -> Result<(), AppError> {
+    let staged = exe.with_extension("new");
+    fs::write(&staged, bytes)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+
+    // Windows can't overwrite a running executable, but it can rename it
+    #[cfg(windows)]
+    {
+        let old = exe.with_extension("old");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old)?;
+    }
+
+    fs::rename(&staged, exe).map_err(|e| {
+        let _ = fs::remove_file(&staged);
+        AppError::UpdateError(format!("Could not replace {}: {}", exe.display(), e))
+    })
+}
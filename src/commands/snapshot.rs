@@ -0,0 +1,303 @@
+use crate::cli::{CloudProvider, Commands, OutputFormat, SnapshotAction};
+use crate::commands::list::{collect_inventory, inventory_scope, ResourceSummary};
+use crate::error::AppError;
+use crate::executor;
+use crate::inventory::Inventory;
+use crate::notify::{self, Notification};
+use crate::query;
+use crate::report;
+use crate::settings::{self, SinkConfig, SinkKind};
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Lines listed per provider in a drift notification before it is truncated
+const NOTIFY_MAX_LINES: usize = 20;
+
+#[derive(Debug, Serialize)]
+struct StateChange {
+    id: String,
+    name: String,
+    resource_type: String,
+    from: String,
+    to: String,
+}
+
+/// What changed in one provider's inventory since the baseline snapshot.
+#[derive(Debug, Serialize)]
+struct Drift {
+    provider: String,
+    baseline_at: DateTime<Utc>,
+    added: Vec<ResourceSummary>,
+    removed: Vec<ResourceSummary>,
+    changed: Vec<StateChange>,
+}
+
+impl Drift {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    fn untagged(&self) -> usize {
+        self.added.iter().filter(|r| r.tags.is_empty()).count()
+    }
+
+    /// One line per change, as shown in the table and notifications.
+    fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for r in &self.added {
+            lines.push(format!(
+                "+ {} {} ({}){}",
+                r.resource_type,
+                r.id,
+                r.region,
+                if r.tags.is_empty() { " untagged" } else { "" }
+            ));
+        }
+        for r in &self.removed {
+            lines.push(format!("- {} {} ({})", r.resource_type, r.id, r.region));
+        }
+        for c in &self.changed {
+            lines.push(format!(
+                "~ {} {}: {} → {}",
+                c.resource_type, c.id, c.from, c.to
+            ));
+        }
+        lines
+    }
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn snapshot(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Snapshot { action } = cmd {
+        match action {
+            SnapshotAction::Diff {
+                since,
+                provider,
+                notify,
+                format,
+            } => diff(since, provider, *notify, format).await?,
+        }
+    }
+
+    Ok(())
+}
+
+async fn diff(
+    since: &str,
+    provider: &Option<CloudProvider>,
+    notify: bool,
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    let days = query::parse_days(since)?;
+    let cutoff = Utc::now() - Duration::seconds((days * 86400.0) as i64);
+
+    let providers = match provider {
+        Some(provider) => vec![provider.clone()],
+        None => vec![CloudProvider::Aws, CloudProvider::Gcp, CloudProvider::Azure],
+    };
+
+    tracing::info!(
+        "🔎 Comparing inventory against snapshots from before {}...",
+        since
+    );
+
+    // Fetching the current inventory also stores the snapshot later runs diff against
+    let inventories = executor::for_each_provider(&providers, |provider| async move {
+        collect_inventory(&provider).await
+    })
+    .await;
+
+    let inventory = Inventory::open()?;
+    let mut drifts = Vec::new();
+    for (provider, current) in inventories {
+        let current = match current {
+            Ok(resources) => resources,
+            Err(e) => {
+                tracing::warn!("⚠️  Skipping {}: {}", provider.to_string().yellow(), e);
+                continue;
+            }
+        };
+
+        let scope = inventory_scope(&provider).await;
+        let Some((baseline_at, baseline)) =
+            inventory.latest_before::<Vec<ResourceSummary>>("resources", &scope, cutoff)?
+        else {
+            tracing::info!(
+                "ℹ️  No {} snapshot from before {} yet; this run is the baseline",
+                provider,
+                since
+            );
+            continue;
+        };
+
+        drifts.push(compare(&provider, baseline_at, baseline, current));
+    }
+
+    output_drifts(&drifts, format)?;
+
+    if notify {
+        send_notifications(&drifts, since).await?;
+    }
+
+    Ok(())
+}
+
+fn compare(
+    provider: &CloudProvider,
+    baseline_at: DateTime<Utc>,
+    baseline: Vec<ResourceSummary>,
+    current: Vec<ResourceSummary>,
+) -> Drift {
+    let key = |r: &ResourceSummary| (r.resource_type.clone(), r.id.clone());
+    let mut before: HashMap<(String, String), ResourceSummary> =
+        baseline.into_iter().map(|r| (key(&r), r)).collect();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for resource in current {
+        match before.remove(&key(&resource)) {
+            None => added.push(resource),
+            Some(old) if old.state != resource.state => changed.push(StateChange {
+                id: resource.id,
+                name: resource.name,
+                resource_type: resource.resource_type,
+                from: old.state,
+                to: resource.state,
+            }),
+            Some(_) => {}
+        }
+    }
+
+    let mut removed: Vec<ResourceSummary> = before.into_values().collect();
+    removed.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Drift {
+        provider: provider.to_string(),
+        baseline_at,
+        added,
+        removed,
+        changed,
+    }
+}
+
+fn output_drifts(drifts: &[Drift], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            for drift in drifts {
+                println!(
+                    "\n📸 {} since {}",
+                    drift.provider.green(),
+                    drift.baseline_at.format("%Y-%m-%d %H:%M UTC")
+                );
+                if drift.is_empty() {
+                    println!("   ✅ No drift");
+                    continue;
+                }
+                for line in drift.lines() {
+                    println!("   {}", line);
+                }
+                println!(
+                    "   {} added ({} untagged), {} removed, {} changed",
+                    drift.added.len(),
+                    drift.untagged(),
+                    drift.removed.len(),
+                    drift.changed.len()
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(drifts)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, drifts)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Provider,Change,ID,Name,Type,Region,Detail");
+            for drift in drifts {
+                for r in &drift.added {
+                    println!(
+                        "{},added,{},{},{},{},{}",
+                        drift.provider,
+                        r.id,
+                        r.name,
+                        r.resource_type,
+                        r.region,
+                        if r.tags.is_empty() { "untagged" } else { "" }
+                    );
+                }
+                for r in &drift.removed {
+                    println!(
+                        "{},removed,{},{},{},{},",
+                        drift.provider, r.id, r.name, r.resource_type, r.region
+                    );
+                }
+                for c in &drift.changed {
+                    println!(
+                        "{},changed,{},{},{},,{} → {}",
+                        drift.provider, c.id, c.name, c.resource_type, c.from, c.to
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn send_notifications(drifts: &[Drift], since: &str) -> Result<(), AppError> {
+    let settings = settings::load_settings()?;
+
+    // Without configured sinks, drift still reaches the terminal
+    let sinks = if settings.sinks.is_empty() {
+        vec![SinkConfig {
+            name: "stdout".to_string(),
+            kind: SinkKind::Stdout,
+            url: None,
+        }]
+    } else {
+        settings.sinks.clone()
+    };
+
+    for drift in drifts.iter().filter(|d| !d.is_empty()) {
+        let mut lines = drift.lines();
+        if lines.len() > NOTIFY_MAX_LINES {
+            let more = lines.len() - NOTIFY_MAX_LINES;
+            lines.truncate(NOTIFY_MAX_LINES);
+            lines.push(format!("… and {} more", more));
+        }
+
+        // New untagged or deleted infrastructure needs someone to look at it
+        let severity = if drift.untagged() > 0 || !drift.removed.is_empty() {
+            "warning"
+        } else {
+            "info"
+        };
+        let notification = Notification {
+            title: format!(
+                "{} inventory drift in the last {}: {} added, {} removed, {} changed",
+                drift.provider,
+                since,
+                drift.added.len(),
+                drift.removed.len(),
+                drift.changed.len()
+            ),
+            message: lines.join("\n"),
+            severity: severity.to_string(),
+            source: "snapshot-diff".to_string(),
+        };
+
+        for sink in &sinks {
+            if let Err(e) = notify::send(sink, &notification).await {
+                tracing::warn!("⚠️  Failed to notify sink '{}': {}", sink.name, e);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,380 @@
+use crate::batch::{self, BatchAction};
+use crate::cli::{Commands, OutputFormat};
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::guard;
+use crate::regions;
+use crate::report;
+use aws_config::SdkConfig;
+use aws_sdk_cloudtrail::types::{LookupAttribute, LookupAttributeKey};
+use aws_sdk_ec2::types::{Filter, StorageTier, TargetStorageTier};
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// EBS snapshot list prices (us-east-1)
+const STANDARD_PRICE_PER_GB: f64 = 0.05;
+const ARCHIVE_PRICE_PER_GB: f64 = 0.0125;
+const ARCHIVE_RETRIEVAL_PRICE_PER_GB: f64 = 0.03;
+
+/// Archived snapshots are billed for at least 90 days
+const ARCHIVE_MINIMUM_MONTHS: f64 = 3.0;
+
+/// Window restores are counted over; CloudTrail event history keeps 90 days
+const RESTORE_LOOKBACK_DAYS: i64 = 90;
+
+/// Volume id of snapshots copied from another snapshot, which share no
+/// blocks with each other
+const COPIED_SNAPSHOT_VOLUME: &str = "vol-ffffffff";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotArchive {
+    pub snapshot_id: String,
+    pub region: String,
+    pub volume_id: String,
+    pub age_days: i64,
+    /// Size the archive tier bills for: the full snapshot, not just its
+    /// changed blocks
+    pub size_gb: f64,
+    /// Volumes created from the snapshot over the last 90 days
+    pub restores: usize,
+    pub standard_monthly_cost: f64,
+    pub archive_monthly_cost: f64,
+    /// Cost of restoring the snapshot once from the archive tier
+    pub restore_cost: f64,
+    /// Monthly savings net of the restores seen over the lookback
+    pub monthly_savings: f64,
+    /// Months the snapshot has to stay archived before archiving pays off,
+    /// counting the 90-day minimum and one restore
+    pub break_even_months: f64,
+}
+
+impl BatchAction for SnapshotArchive {
+    fn action(&self) -> &str {
+        "archive_snapshot"
+    }
+
+    fn resource_id(&self) -> &str {
+        &self.snapshot_id
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{}: {:.0} GiB → archive tier",
+            self.snapshot_id, self.size_gb
+        )
+    }
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn archive_snapshots(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::SnapshotArchive {
+        age_threshold,
+        max_restores,
+        apply,
+        force,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!("🗄️  Looking for EBS snapshots worth moving to the archive tier...");
+
+        let config = clients::aws_config().await;
+        let candidates = analyze_aws_snapshots(&config, *age_threshold, *max_restores).await?;
+
+        if candidates.is_empty() {
+            println!("✅ No snapshots would be cheaper in the archive tier.");
+            return Ok(());
+        }
+
+        output_candidates(&candidates, format)?;
+
+        let total: f64 = candidates.iter().map(|c| c.monthly_savings).sum();
+        println!(
+            "\n💰 Total estimated monthly savings: {}",
+            format!("${:.2}", total).green()
+        );
+        println!("   Restoring an archived snapshot takes 24 to 72 hours.");
+
+        if !*apply {
+            tracing::info!("🔍 Re-run with --apply to archive these snapshots");
+            return Ok(());
+        }
+        guard::ensure_writable("snapshot-archive")?;
+
+        if !*force {
+            print!("\nArchive {} snapshots? (y/N): ", candidates.len());
+            use std::io::{self, Write};
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+
+            if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
+                println!("❌ Archiving cancelled by user.");
+                return Err(AppError::UserCancelled);
+            }
+        }
+
+        tracing::info!("🗄️  Archiving snapshots...");
+        let ec2_client = clients::get::<aws_sdk_ec2::Client>(&config).await;
+        let outcome = batch::run("snapshot-archive", "AWS", &candidates, |candidate| {
+            let ec2_client = ec2_client.clone();
+            async move {
+                executor::throttle("ec2").await;
+                ec2_client
+                    .modify_snapshot_tier()
+                    .snapshot_id(&candidate.snapshot_id)
+                    .storage_tier(TargetStorageTier::Archive)
+                    .send()
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| {
+                        AppError::aws("ec2:ModifySnapshotTier", e)
+                            .for_resource(&candidate.snapshot_id)
+                            .in_region(&candidate.region)
+                    })
+            }
+        })
+        .await;
+        outcome.render(format)?;
+        outcome.into_result()?;
+
+        println!("✅ Archiving started. It completes in the background.");
+    }
+
+    Ok(())
+}
+
+/// Standard-tier snapshots older than `age_threshold` days, restored at most
+/// `max_restores` times lately, that would cost less in the archive tier.
+///
+/// Only the sole standard-tier snapshot of a volume qualifies: the others
+/// share blocks with their neighbours, so archiving one frees little while
+/// the archive bills its full size.
+pub(crate) async fn analyze_aws_snapshots(
+    config: &SdkConfig,
+    age_threshold: u32,
+    max_restores: usize,
+) -> Result<Vec<SnapshotArchive>, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    let region = regions::config_region(config);
+
+    let mut snapshots = Vec::new();
+    let mut pages = ec2_client
+        .describe_snapshots()
+        .owner_ids("self")
+        .filters(Filter::builder().name("status").values("completed").build())
+        .into_paginator()
+        .send();
+    loop {
+        executor::throttle("ec2").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("ec2:DescribeSnapshots", e))?;
+        snapshots.extend(
+            page.snapshots()
+                .iter()
+                .filter(|s| s.storage_tier() == Some(&StorageTier::Standard))
+                .cloned(),
+        );
+    }
+
+    let mut per_volume: HashMap<&str, usize> = HashMap::new();
+    for snapshot in &snapshots {
+        if let Some(volume_id) = snapshot.volume_id() {
+            *per_volume.entry(volume_id).or_default() += 1;
+        }
+    }
+
+    let restores = restores_by_snapshot(config).await?;
+    let now = Utc::now();
+
+    let mut candidates = Vec::new();
+    for snapshot in &snapshots {
+        let (Some(snapshot_id), Some(started)) = (snapshot.snapshot_id(), snapshot.start_time())
+        else {
+            continue;
+        };
+        let volume_id = snapshot.volume_id().unwrap_or(COPIED_SNAPSHOT_VOLUME);
+        if volume_id != COPIED_SNAPSHOT_VOLUME && per_volume.get(volume_id).is_some_and(|&n| n > 1)
+        {
+            continue;
+        }
+
+        let age_days =
+            (now - DateTime::from_timestamp(started.secs(), 0).unwrap_or(now)).num_days();
+        let restores = restores.get(snapshot_id).copied().unwrap_or(0);
+        if age_days < age_threshold as i64 || restores > max_restores {
+            continue;
+        }
+
+        // Snapshots taken before full sizes were reported fall back to the
+        // volume size, an upper bound
+        let size_gb = snapshot
+            .full_snapshot_size_in_bytes()
+            .map(|bytes| bytes as f64 / (1u64 << 30) as f64)
+            .unwrap_or(snapshot.volume_size().unwrap_or(0) as f64);
+        let candidate = price(snapshot_id, &region, volume_id, age_days, size_gb, restores);
+        if candidate.monthly_savings > 0.0 {
+            candidates.push(candidate);
+        }
+    }
+
+    candidates.sort_by(|a, b| b.monthly_savings.total_cmp(&a.monthly_savings));
+    Ok(candidates)
+}
+
+fn price(
+    snapshot_id: &str,
+    region: &str,
+    volume_id: &str,
+    age_days: i64,
+    size_gb: f64,
+    restores: usize,
+) -> SnapshotArchive {
+    let standard = size_gb * STANDARD_PRICE_PER_GB;
+    let archive = size_gb * ARCHIVE_PRICE_PER_GB;
+    let restore_cost = size_gb * ARCHIVE_RETRIEVAL_PRICE_PER_GB;
+
+    // Assume restores keep coming at the rate seen over the lookback
+    let restores_per_month = restores as f64 * 30.0 / RESTORE_LOOKBACK_DAYS as f64;
+    let monthly_savings = standard - archive - restores_per_month * restore_cost;
+
+    // Archiving for m months costs max(m, 3) * archive plus a restore,
+    // against m * standard when left alone
+    let within_minimum = (ARCHIVE_MINIMUM_MONTHS * archive + restore_cost) / standard;
+    let break_even_months = if within_minimum <= ARCHIVE_MINIMUM_MONTHS {
+        within_minimum
+    } else {
+        restore_cost / (standard - archive)
+    };
+
+    SnapshotArchive {
+        snapshot_id: snapshot_id.to_string(),
+        region: region.to_string(),
+        volume_id: volume_id.to_string(),
+        age_days,
+        size_gb,
+        restores,
+        standard_monthly_cost: standard,
+        archive_monthly_cost: archive,
+        restore_cost,
+        monthly_savings,
+        break_even_months,
+    }
+}
+
+/// Volumes created from each snapshot over the lookback, from the
+/// CreateVolume events in CloudTrail.
+async fn restores_by_snapshot(config: &SdkConfig) -> Result<HashMap<String, usize>, AppError> {
+    let client = clients::get::<aws_sdk_cloudtrail::Client>(config).await;
+    let now = Utc::now();
+
+    let attribute = LookupAttribute::builder()
+        .attribute_key(LookupAttributeKey::EventName)
+        .attribute_value("CreateVolume")
+        .build()
+        .map_err(|e| AppError::AwsError(e.to_string()))?;
+
+    let mut restores = HashMap::new();
+    let mut pages = client
+        .lookup_events()
+        .lookup_attributes(attribute)
+        .start_time(aws_smithy_types::DateTime::from_secs(
+            (now - Duration::days(RESTORE_LOOKBACK_DAYS)).timestamp(),
+        ))
+        .end_time(aws_smithy_types::DateTime::from_secs(now.timestamp()))
+        .into_paginator()
+        .send();
+    loop {
+        executor::throttle("cloudtrail").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("cloudtrail:LookupEvents", e))?;
+
+        for event in page.events() {
+            let detail: Value = event
+                .cloud_trail_event()
+                .and_then(|e| serde_json::from_str(e).ok())
+                .unwrap_or_default();
+            if let Some(snapshot_id) = detail["requestParameters"]["snapshotId"].as_str() {
+                *restores.entry(snapshot_id.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    Ok(restores)
+}
+
+fn output_candidates(
+    candidates: &[SnapshotArchive],
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n{:<24} {:<8} {:<8} {:<9} {:<12} {:<12} {:<12} {:<12}",
+                "Snapshot",
+                "Days",
+                "GiB",
+                "Restores",
+                "Now ($/mo)",
+                "Saves ($/mo)",
+                "Restore ($)",
+                "Break-even"
+            );
+            println!("{:-<104}", "");
+
+            for c in candidates {
+                println!(
+                    "{:<24} {:<8} {:<8.0} {:<9} {:<12.2} {:<12.2} {:<12.2} {:<12}",
+                    c.snapshot_id,
+                    c.age_days,
+                    c.size_gb,
+                    c.restores,
+                    c.standard_monthly_cost,
+                    c.monthly_savings,
+                    c.restore_cost,
+                    format!("{:.1} mo", c.break_even_months)
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(candidates)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, candidates)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("SnapshotId,Region,VolumeId,AgeDays,SizeGB,Restores,StandardMonthlyCost,ArchiveMonthlyCost,RestoreCost,MonthlySavings,BreakEvenMonths");
+            for c in candidates {
+                println!(
+                    "{},{},{},{},{:.1},{},{:.2},{:.2},{:.2},{:.2},{:.1}",
+                    c.snapshot_id,
+                    c.region,
+                    c.volume_id,
+                    c.age_days,
+                    c.size_gb,
+                    c.restores,
+                    c.standard_monthly_cost,
+                    c.archive_monthly_cost,
+                    c.restore_cost,
+                    c.monthly_savings,
+                    c.break_even_months
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,322 @@
+use crate::cli::{Commands, OutputFormat};
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::regions;
+use crate::report;
+use crate::retry;
+use aws_config::SdkConfig;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Public dataset behind the EC2 Spot Instance Advisor
+const SPOT_ADVISOR_URL: &str = "https://spot-bid-advisor.s3.amazonaws.com/spot-advisor-data.json";
+
+const HOURS_PER_MONTH: f64 = 730.0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpotOffer {
+    pub region: String,
+    pub availability_zone: String,
+    pub spot_price: f64,
+    pub on_demand_price: Option<f64>,
+    pub savings_percent: Option<f64>,
+    pub interruption_rate: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotAdvisorData {
+    spot_advisor: HashMap<String, HashMap<String, HashMap<String, SpotAdvisorEntry>>>,
+    ranges: Vec<InterruptionRange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotAdvisorEntry {
+    /// Savings over on-demand, in percent
+    s: f64,
+    /// Index into `ranges`
+    r: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct InterruptionRange {
+    index: usize,
+    label: String,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn spot_advisor(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::SpotAdvisor {
+        instance_type,
+        region,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!("💸 Checking spot prices for {}...", instance_type.green());
+
+        let base = clients::aws_config().await;
+        let target_regions = match region.as_deref() {
+            Some("all") => regions::aws_regions(&base).await?,
+            Some(region) => vec![region.to_string()],
+            None => vec![regions::config_region(&base)],
+        };
+
+        let advisor = Arc::new(fetch_spot_advisor_data().await);
+        if advisor.is_none() {
+            println!("   ⚠️  Spot Advisor data unavailable, interruption rates will be unknown");
+        }
+
+        let results = executor::for_each_region(&base, &target_regions, |region, config| {
+            let advisor = advisor.clone();
+            let instance_type = instance_type.clone();
+            async move {
+                let found =
+                    spot_offers(&config, &region, &instance_type, advisor.as_ref().as_ref())
+                        .await?;
+                let count = count_on_demand_instances(&config, &instance_type).await?;
+                Ok((found, count))
+            }
+        })
+        .await;
+
+        let mut offers = Vec::new();
+        let mut running_on_demand: HashMap<String, usize> = HashMap::new();
+        for (region, (found, count)) in results {
+            offers.extend(found);
+            if count > 0 {
+                running_on_demand.insert(region, count);
+            }
+        }
+
+        if offers.is_empty() {
+            println!("ℹ️  No spot prices found for {}.", instance_type);
+            return Ok(());
+        }
+
+        offers.sort_by(|a, b| a.spot_price.total_cmp(&b.spot_price));
+        output_spot_offers(&offers, format)?;
+
+        let cheapest = &offers[0];
+        println!(
+            "\n🏆 Cheapest: {} ({}) at ${:.4}/hour, interruption rate {}",
+            cheapest.availability_zone.green(),
+            cheapest.region,
+            cheapest.spot_price,
+            cheapest.interruption_rate
+        );
+
+        show_fleet_savings(&offers, &running_on_demand);
+    }
+
+    Ok(())
+}
+
+async fn fetch_spot_advisor_data() -> Option<SpotAdvisorData> {
+    let client = reqwest::Client::new();
+    let response = retry::send_http("spot advisor", || client.get(SPOT_ADVISOR_URL))
+        .await
+        .ok()?;
+    response.json::<SpotAdvisorData>().await.ok()
+}
+
+async fn spot_offers(
+    config: &SdkConfig,
+    region: &str,
+    instance_type: &str,
+    advisor: Option<&SpotAdvisorData>,
+) -> Result<Vec<SpotOffer>, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    let now = aws_sdk_ec2::primitives::DateTime::from_secs(chrono::Utc::now().timestamp());
+
+    // With start_time set to now, the history holds the current price per AZ
+    executor::throttle("ec2").await;
+    let response = ec2_client
+        .describe_spot_price_history()
+        .instance_types(aws_sdk_ec2::types::InstanceType::from(instance_type))
+        .product_descriptions("Linux/UNIX")
+        .start_time(now)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeSpotPriceHistory", e))?;
+
+    let entry = advisor.and_then(|data| {
+        data.spot_advisor
+            .get(region)
+            .and_then(|r| r.get("Linux"))
+            .and_then(|types| types.get(instance_type))
+    });
+    let interruption_rate = match (advisor, entry) {
+        (Some(data), Some(entry)) => data
+            .ranges
+            .iter()
+            .find(|range| range.index == entry.r)
+            .map(|range| range.label.clone())
+            .unwrap_or_else(|| "unknown".to_string()),
+        _ => "unknown".to_string(),
+    };
+
+    let mut latest: HashMap<String, f64> = HashMap::new();
+    for price in response.spot_price_history() {
+        let (Some(zone), Some(spot_price)) = (
+            price.availability_zone(),
+            price.spot_price().and_then(|p| p.parse::<f64>().ok()),
+        ) else {
+            continue;
+        };
+        latest.entry(zone.to_string()).or_insert(spot_price);
+    }
+
+    Ok(latest
+        .into_iter()
+        .map(|(availability_zone, spot_price)| {
+            // The advisor's savings figure lets us back out the on-demand price
+            let on_demand_price = entry
+                .filter(|e| e.s < 100.0)
+                .map(|e| spot_price / (1.0 - e.s / 100.0));
+            SpotOffer {
+                region: region.to_string(),
+                availability_zone,
+                spot_price,
+                on_demand_price,
+                savings_percent: entry.map(|e| e.s),
+                interruption_rate: interruption_rate.clone(),
+            }
+        })
+        .collect())
+}
+
+async fn count_on_demand_instances(
+    config: &SdkConfig,
+    instance_type: &str,
+) -> Result<usize, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    executor::throttle("ec2").await;
+    let response = ec2_client
+        .describe_instances()
+        .filters(
+            aws_sdk_ec2::types::Filter::builder()
+                .name("instance-type")
+                .values(instance_type)
+                .build(),
+        )
+        .filters(
+            aws_sdk_ec2::types::Filter::builder()
+                .name("instance-state-name")
+                .values("running")
+                .build(),
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeInstances", e))?;
+
+    Ok(response
+        .reservations()
+        .iter()
+        .flat_map(|r| r.instances())
+        .filter(|i| i.instance_lifecycle().is_none())
+        .count())
+}
+
+fn show_fleet_savings(offers: &[SpotOffer], running_on_demand: &HashMap<String, usize>) {
+    if running_on_demand.is_empty() {
+        println!("ℹ️  No running on-demand instances of this type were found.");
+        return;
+    }
+
+    println!("\n📊 Potential savings for your on-demand fleet:");
+    let mut total = 0.0;
+    for (region, count) in running_on_demand {
+        let best = offers
+            .iter()
+            .filter(|o| &o.region == region)
+            .min_by(|a, b| a.spot_price.total_cmp(&b.spot_price));
+        let Some(best) = best else {
+            continue;
+        };
+        let Some(on_demand_price) = best.on_demand_price else {
+            println!(
+                "   {}: {} instances (on-demand price unknown)",
+                region, count
+            );
+            continue;
+        };
+
+        let monthly = (on_demand_price - best.spot_price) * HOURS_PER_MONTH * *count as f64;
+        total += monthly;
+        println!(
+            "   {}: {} instances → ${:.2}/month on spot in {}",
+            region, count, monthly, best.availability_zone
+        );
+    }
+    println!(
+        "💰 Total potential monthly savings: {}",
+        format!("${:.2}", total).green()
+    );
+}
+
+fn output_spot_offers(offers: &[SpotOffer], format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n{:<16} {:<18} {:<12} {:<12} {:<10} {:<14}",
+                "Region", "AZ", "Spot ($/h)", "OnDem ($/h)", "Savings", "Interruption"
+            );
+            println!("{:-<86}", "");
+
+            for offer in offers {
+                println!(
+                    "{:<16} {:<18} {:<12.4} {:<12} {:<10} {:<14}",
+                    offer.region,
+                    offer.availability_zone,
+                    offer.spot_price,
+                    offer
+                        .on_demand_price
+                        .map(|p| format!("{:.4}", p))
+                        .unwrap_or_else(|| "N/A".to_string()),
+                    offer
+                        .savings_percent
+                        .map(|s| format!("{:.0}%", s))
+                        .unwrap_or_else(|| "N/A".to_string()),
+                    offer.interruption_rate
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(offers)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, offers)?);
+        }
+
+        OutputFormat::Csv => {
+            println!(
+                "Region,AvailabilityZone,SpotPrice,OnDemandPrice,SavingsPercent,InterruptionRate"
+            );
+            for offer in offers {
+                println!(
+                    "{},{},{:.4},{},{},{}",
+                    offer.region,
+                    offer.availability_zone,
+                    offer.spot_price,
+                    offer
+                        .on_demand_price
+                        .map(|p| format!("{:.4}", p))
+                        .unwrap_or_default(),
+                    offer
+                        .savings_percent
+                        .map(|s| format!("{:.0}", s))
+                        .unwrap_or_default(),
+                    offer.interruption_rate
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
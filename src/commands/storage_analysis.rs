@@ -0,0 +1,447 @@
+use crate::cache;
+use crate::cli::{CloudProvider, Commands, OutputFormat};
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::regions;
+use crate::report;
+use aws_config::SdkConfig;
+use chrono::Utc;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// S3 list prices per GB-month (us-east-1)
+const S3_STANDARD_PRICE_PER_GB: f64 = 0.023;
+const S3_STANDARD_IA_PRICE_PER_GB: f64 = 0.0125;
+const S3_GLACIER_IR_PRICE_PER_GB: f64 = 0.004;
+
+/// Share of a bucket assumed cold when only some of it is being read
+const S3_COLD_DATA_RATIO: f64 = 0.5;
+
+/// Saving Intelligent-Tiering typically achieves when access patterns are unknown
+const INTELLIGENT_TIERING_SAVINGS_RATIO: f64 = 0.3;
+
+/// Buckets smaller than this aren't worth a lifecycle rule
+const MIN_BUCKET_SIZE_GB: f64 = 1.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transition {
+    pub days: i32,
+    pub storage_class: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketAnalysis {
+    pub bucket: String,
+    pub region: String,
+    pub size_gb: f64,
+    pub object_count: Option<f64>,
+    pub age_days: Option<i64>,
+    /// GET requests over the last 30 days, when request metrics are enabled
+    pub get_requests: Option<f64>,
+    pub has_lifecycle: bool,
+    pub recommendation: Option<String>,
+    pub transitions: Vec<Transition>,
+    pub monthly_savings: f64,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn storage_analysis(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::StorageAnalysis {
+        provider,
+        generate_lifecycle,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!(
+            "🗄️  Analyzing {} object storage...",
+            provider.to_string().green()
+        );
+
+        let mut analyses = match provider {
+            CloudProvider::Aws => {
+                let config = clients::aws_config().await;
+                analyze_aws_buckets(&config).await?
+            }
+            // Storage classes, prices and the lifecycle JSON written are all S3's
+            CloudProvider::Gcp | CloudProvider::Azure => {
+                return Err(AppError::InvalidParameters(format!(
+                    "storage-analysis is only supported for AWS, not {}",
+                    provider
+                )))
+            }
+        };
+
+        if analyses.is_empty() {
+            println!("ℹ️  No buckets found.");
+            return Ok(());
+        }
+
+        analyses.sort_by(|a, b| b.monthly_savings.total_cmp(&a.monthly_savings));
+        output_storage_analysis(&analyses, format)?;
+
+        let total: f64 = analyses.iter().map(|a| a.monthly_savings).sum();
+        println!(
+            "\n💰 Projected monthly savings from lifecycle policies: {}",
+            format!("${:.2}", total).green()
+        );
+
+        if let Some(dir) = generate_lifecycle {
+            write_lifecycle_policies(&analyses, Path::new(dir))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Size, age and access pattern for every bucket, with a lifecycle
+/// recommendation for the ones that would benefit from one.
+pub(crate) async fn analyze_aws_buckets(
+    config: &SdkConfig,
+) -> Result<Vec<BucketAnalysis>, AppError> {
+    tracing::info!("🔍 Fetching S3 bucket metrics...");
+
+    let s3_client = clients::get::<aws_sdk_s3::Client>(config).await;
+    executor::throttle("s3").await;
+    let buckets = s3_client
+        .list_buckets()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("s3:ListBuckets", e))?;
+
+    let mut analyses = Vec::new();
+    for bucket in buckets.buckets() {
+        let Some(name) = bucket.name() else {
+            continue;
+        };
+
+        // Storage metrics live in the bucket's own region
+        let region = bucket_region(&s3_client, name).await;
+        let regional = regions::regional_config(config, &region);
+        let regional_s3 = clients::get::<aws_sdk_s3::Client>(&regional).await;
+        let cloudwatch_client = clients::get::<aws_sdk_cloudwatch::Client>(&regional).await;
+
+        let size_bytes = s3_metric(
+            &cloudwatch_client,
+            name,
+            "BucketSizeBytes",
+            ("StorageType", "StandardStorage"),
+            2,
+        )
+        .await?;
+        let object_count = s3_metric(
+            &cloudwatch_client,
+            name,
+            "NumberOfObjects",
+            ("StorageType", "AllStorageTypes"),
+            2,
+        )
+        .await?;
+        // Only present when request metrics are enabled for the whole bucket
+        let get_requests = s3_metric(
+            &cloudwatch_client,
+            name,
+            "GetRequests",
+            ("FilterId", "EntireBucket"),
+            30,
+        )
+        .await?;
+
+        executor::throttle("s3").await;
+        let has_lifecycle = regional_s3
+            .get_bucket_lifecycle_configuration()
+            .bucket(name)
+            .send()
+            .await
+            .map(|r| !r.rules().is_empty())
+            .unwrap_or(false);
+
+        let age_days = bucket.creation_date().map(|created| {
+            let created = chrono::DateTime::from_timestamp(created.secs(), 0)
+                .unwrap_or_else(|| chrono::Utc::now());
+            (Utc::now() - created).num_days()
+        });
+
+        let mut analysis = BucketAnalysis {
+            bucket: name.to_string(),
+            region,
+            size_gb: size_bytes.unwrap_or(0.0) / 1024f64.powi(3),
+            object_count,
+            age_days,
+            get_requests,
+            has_lifecycle,
+            recommendation: None,
+            transitions: Vec::new(),
+            monthly_savings: 0.0,
+        };
+        recommend_lifecycle(&mut analysis);
+        analyses.push(analysis);
+    }
+
+    Ok(analyses)
+}
+
+pub(crate) async fn bucket_region(client: &aws_sdk_s3::Client, bucket: &str) -> String {
+    executor::throttle("s3").await;
+    let constraint = client
+        .get_bucket_location()
+        .bucket(bucket)
+        .send()
+        .await
+        .ok()
+        .and_then(|r| r.location_constraint().map(|c| c.as_str().to_string()));
+
+    match constraint.as_deref() {
+        None | Some("") => "us-east-1".to_string(),
+        Some("EU") => "eu-west-1".to_string(),
+        Some(region) => region.to_string(),
+    }
+}
+
+/// Largest daily value (storage) or total (requests) of an S3 metric over the
+/// last `days` days. `None` when CloudWatch has no datapoints.
+async fn s3_metric(
+    client: &aws_sdk_cloudwatch::Client,
+    bucket: &str,
+    metric_name: &str,
+    dimension: (&str, &str),
+    days: i64,
+) -> Result<Option<f64>, AppError> {
+    // Bucket names are globally unique, so they scope the key on their own
+    let key = format!(
+        "aws:s3-metric:{}:{}:{}={}:{}",
+        bucket, metric_name, dimension.0, dimension.1, days
+    );
+    cache::cached_call(&key, || {
+        fetch_s3_metric(client, bucket, metric_name, dimension, days)
+    })
+    .await
+}
+
+async fn fetch_s3_metric(
+    client: &aws_sdk_cloudwatch::Client,
+    bucket: &str,
+    metric_name: &str,
+    dimension: (&str, &str),
+    days: i64,
+) -> Result<Option<f64>, AppError> {
+    let now = Utc::now();
+    let statistic = if metric_name == "GetRequests" {
+        aws_sdk_cloudwatch::types::Statistic::Sum
+    } else {
+        aws_sdk_cloudwatch::types::Statistic::Average
+    };
+
+    executor::throttle("cloudwatch").await;
+    let response = client
+        .get_metric_statistics()
+        .namespace("AWS/S3")
+        .metric_name(metric_name)
+        .dimensions(
+            aws_sdk_cloudwatch::types::Dimension::builder()
+                .name("BucketName")
+                .value(bucket)
+                .build(),
+        )
+        .dimensions(
+            aws_sdk_cloudwatch::types::Dimension::builder()
+                .name(dimension.0)
+                .value(dimension.1)
+                .build(),
+        )
+        .start_time(aws_sdk_cloudwatch::primitives::DateTime::from_secs(
+            (now - chrono::Duration::days(days)).timestamp(),
+        ))
+        .end_time(aws_sdk_cloudwatch::primitives::DateTime::from_secs(
+            now.timestamp(),
+        ))
+        .period(86400)
+        .statistics(statistic.clone())
+        .send()
+        .await
+        .map_err(|e| AppError::aws("cloudwatch:GetMetricStatistics", e))?;
+
+    let values: Vec<f64> = response
+        .datapoints()
+        .iter()
+        .filter_map(|dp| match statistic {
+            aws_sdk_cloudwatch::types::Statistic::Sum => dp.sum(),
+            _ => dp.average(),
+        })
+        .collect();
+
+    if values.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(match statistic {
+        aws_sdk_cloudwatch::types::Statistic::Sum => values.iter().sum(),
+        // Storage metrics are daily snapshots, so take the latest-sized one
+        _ => values.iter().cloned().fold(0.0, f64::max),
+    }))
+}
+
+fn recommend_lifecycle(analysis: &mut BucketAnalysis) {
+    if analysis.has_lifecycle || analysis.size_gb < MIN_BUCKET_SIZE_GB {
+        return;
+    }
+
+    let size = analysis.size_gb;
+    let old = analysis.age_days.unwrap_or(0) > 90;
+
+    match analysis.get_requests {
+        None => {
+            analysis.recommendation =
+                Some("Enable Intelligent-Tiering (no request metrics)".to_string());
+            analysis.transitions = vec![Transition {
+                days: 0,
+                storage_class: "INTELLIGENT_TIERING".to_string(),
+            }];
+            analysis.monthly_savings =
+                size * S3_STANDARD_PRICE_PER_GB * INTELLIGENT_TIERING_SAVINGS_RATIO;
+        }
+        Some(requests) if requests == 0.0 && old => {
+            analysis.recommendation =
+                Some("Not read in 30 days: IA after 30d, Glacier IR after 90d".to_string());
+            analysis.transitions = vec![
+                Transition {
+                    days: 30,
+                    storage_class: "STANDARD_IA".to_string(),
+                },
+                Transition {
+                    days: 90,
+                    storage_class: "GLACIER_IR".to_string(),
+                },
+            ];
+            analysis.monthly_savings =
+                size * (S3_STANDARD_PRICE_PER_GB - S3_GLACIER_IR_PRICE_PER_GB);
+        }
+        // Fewer than one read per GB a month means most objects sit untouched
+        Some(requests) if requests < size => {
+            analysis.recommendation = Some("Rarely read: Standard-IA after 30d".to_string());
+            analysis.transitions = vec![Transition {
+                days: 30,
+                storage_class: "STANDARD_IA".to_string(),
+            }];
+            analysis.monthly_savings = size
+                * S3_COLD_DATA_RATIO
+                * (S3_STANDARD_PRICE_PER_GB - S3_STANDARD_IA_PRICE_PER_GB);
+        }
+        Some(_) => {}
+    }
+}
+
+/// Lifecycle configuration in the shape `aws s3api put-bucket-lifecycle-configuration` accepts.
+fn lifecycle_json(analysis: &BucketAnalysis) -> serde_json::Value {
+    let transitions: Vec<serde_json::Value> = analysis
+        .transitions
+        .iter()
+        .map(|t| serde_json::json!({ "Days": t.days, "StorageClass": t.storage_class }))
+        .collect();
+
+    serde_json::json!({
+        "Rules": [{
+            "ID": "actlog-storage-tiering",
+            "Status": "Enabled",
+            "Filter": { "Prefix": "" },
+            "Transitions": transitions,
+        }]
+    })
+}
+
+fn write_lifecycle_policies(analyses: &[BucketAnalysis], dir: &Path) -> Result<(), AppError> {
+    fs::create_dir_all(dir)?;
+
+    let mut written = 0;
+    for analysis in analyses.iter().filter(|a| !a.transitions.is_empty()) {
+        let path = dir.join(format!("{}-lifecycle.json", analysis.bucket));
+        let content = serde_json::to_string_pretty(&lifecycle_json(analysis))?;
+        fs::write(&path, content)?;
+        written += 1;
+    }
+
+    println!(
+        "📝 Wrote {} lifecycle policies to {}",
+        written,
+        dir.display()
+    );
+    println!("   Apply with: aws s3api put-bucket-lifecycle-configuration --bucket <name> --lifecycle-configuration file://<file>");
+    Ok(())
+}
+
+fn output_storage_analysis(
+    analyses: &[BucketAnalysis],
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n{:<30} {:<14} {:<10} {:<8} {:<10} {:<50} {:<10}",
+                "Bucket", "Region", "Size (GB)", "Age (d)", "GETs/30d", "Recommendation", "Savings"
+            );
+            println!("{:-<136}", "");
+
+            for analysis in analyses {
+                let recommendation = if analysis.has_lifecycle {
+                    "Lifecycle already configured".dimmed()
+                } else {
+                    analysis.recommendation.as_deref().unwrap_or("-").normal()
+                };
+                println!(
+                    "{:<30} {:<14} {:<10.1} {:<8} {:<10} {:<50} {:<10.2}",
+                    analysis.bucket,
+                    analysis.region,
+                    analysis.size_gb,
+                    analysis
+                        .age_days
+                        .map(|d| d.to_string())
+                        .unwrap_or_else(|| "N/A".to_string()),
+                    analysis
+                        .get_requests
+                        .map(|r| format!("{:.0}", r))
+                        .unwrap_or_else(|| "N/A".to_string()),
+                    recommendation,
+                    analysis.monthly_savings
+                );
+            }
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(analyses)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, analyses)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("Bucket,Region,SizeGB,Objects,AgeDays,GetRequests30d,HasLifecycle,Recommendation,MonthlySavings");
+            for analysis in analyses {
+                println!(
+                    "{},{},{:.1},{},{},{},{},{},{:.2}",
+                    analysis.bucket,
+                    analysis.region,
+                    analysis.size_gb,
+                    analysis
+                        .object_count
+                        .map(|c| format!("{:.0}", c))
+                        .unwrap_or_default(),
+                    analysis.age_days.map(|d| d.to_string()).unwrap_or_default(),
+                    analysis
+                        .get_requests
+                        .map(|r| format!("{:.0}", r))
+                        .unwrap_or_default(),
+                    analysis.has_lifecycle,
+                    analysis.recommendation.as_deref().unwrap_or(""),
+                    analysis.monthly_savings
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
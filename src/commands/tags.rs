@@ -0,0 +1,405 @@
+use crate::accounts::{self, AccountTarget};
+use crate::batch::{self, BatchAction};
+use crate::cli::{Commands, OutputFormat, ResourceType, TagsAction};
+use crate::clients;
+use crate::commands::list::fetch_aws_resources;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::guard;
+use aws_config::SdkConfig;
+use colored::*;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+/// Longest GCP label key or value
+const GCP_LABEL_MAX_LEN: usize = 63;
+
+/// Tags owned by each AWS account and GCP project, as read from the mapping
+/// file given to `tags propagate`.
+#[derive(Debug, Default, Deserialize)]
+struct TagMapping {
+    #[serde(default)]
+    accounts: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(default)]
+    projects: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Tags to add to one instance. Only keys the instance lacks are added;
+/// values already on a resource win over the mapping.
+enum TagChange {
+    Aws {
+        account_id: String,
+        instance_id: String,
+        tags: BTreeMap<String, String>,
+    },
+    Gcp {
+        project_id: String,
+        zone: String,
+        instance: String,
+        tags: BTreeMap<String, String>,
+        /// setLabels replaces every label, so the request carries these too
+        existing: BTreeMap<String, String>,
+        fingerprint: String,
+    },
+}
+
+impl BatchAction for TagChange {
+    fn action(&self) -> &str {
+        "propagate_tags"
+    }
+
+    fn resource_id(&self) -> &str {
+        match self {
+            TagChange::Aws { instance_id, .. } => instance_id,
+            TagChange::Gcp { instance, .. } => instance,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let (scope, tags) = match self {
+            TagChange::Aws {
+                account_id, tags, ..
+            } => (account_id, tags),
+            TagChange::Gcp {
+                project_id, tags, ..
+            } => (project_id, tags),
+        };
+        format!(
+            "~ tag {} ({}): {}",
+            self.resource_id(),
+            scope,
+            tags.iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn tags(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::Tags { action } = cmd {
+        match action {
+            TagsAction::Propagate {
+                mapping,
+                org_role,
+                apply,
+                force,
+                format,
+                profile,
+            } => propagate(mapping, org_role, *apply, *force, format).await?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Add each account's and project's tags to the instances in it that don't
+/// carry them yet.
+async fn propagate(
+    path: &str,
+    org_role: &str,
+    apply: bool,
+    force: bool,
+    format: &OutputFormat,
+) -> Result<(), AppError> {
+    let mapping = load_mapping(path)?;
+    tracing::info!(
+        "🏷️  Propagating tags from {} to {} accounts and {} projects...",
+        path.green(),
+        mapping.accounts.len(),
+        mapping.projects.len()
+    );
+
+    let base = clients::aws_config().await;
+    let targets: Vec<AccountTarget> = mapping
+        .accounts
+        .keys()
+        .map(|account_id| AccountTarget {
+            account_id: account_id.clone(),
+            name: account_id.clone(),
+            role_arn: format!("arn:aws:iam::{}:role/{}", account_id, org_role),
+        })
+        .collect();
+
+    let mut plan = aws_plan(&base, &targets, &mapping.accounts).await;
+    for (project_id, tags) in &mapping.projects {
+        match gcp_plan(project_id, tags).await {
+            Ok(changes) => plan.extend(changes),
+            Err(e) => tracing::warn!("⚠️  Skipping project {}: {}", project_id.yellow(), e),
+        }
+    }
+
+    if plan.is_empty() {
+        println!("✅ Every instance already carries its account or project tags.");
+        return Ok(());
+    }
+
+    println!("\n📋 Plan ({} instances):", plan.len());
+    for change in &plan {
+        println!("   {}", change.describe());
+    }
+
+    if !apply {
+        tracing::info!("🔍 Re-run with --apply to add these tags");
+        return Ok(());
+    }
+    guard::ensure_writable("tags propagate")?;
+
+    if !force {
+        print!("\nTag {} instances? (y/N): ", plan.len());
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if input.trim().to_lowercase() != "y" && input.trim().to_lowercase() != "yes" {
+            println!("❌ Tag propagation cancelled by user.");
+            return Err(AppError::UserCancelled);
+        }
+    }
+
+    let mut configs = HashMap::new();
+    for target in &targets {
+        if plan.iter().any(
+            |c| matches!(c, TagChange::Aws { account_id, .. } if *account_id == target.account_id),
+        ) {
+            configs.insert(
+                target.account_id.clone(),
+                accounts::assume_role_config(&base, target).await,
+            );
+        }
+    }
+
+    tracing::info!("🔧 Adding tags...");
+    let (aws_changes, gcp_changes): (Vec<TagChange>, Vec<TagChange>) = plan
+        .into_iter()
+        .partition(|c| matches!(c, TagChange::Aws { .. }));
+    let mut result = Ok(());
+    for (provider, changes) in [("AWS", aws_changes), ("GCP", gcp_changes)] {
+        if changes.is_empty() {
+            continue;
+        }
+
+        let outcome = batch::run("tags-propagate", provider, &changes, |change| {
+            apply_change(&configs, change)
+        })
+        .await;
+        outcome.render(format)?;
+        if let Err(e) = outcome.into_result() {
+            result = Err(e);
+        }
+    }
+
+    result
+}
+
+fn load_mapping(path: &str) -> Result<TagMapping, AppError> {
+    let content = fs::read_to_string(path)?;
+    let mapping: TagMapping = serde_yaml::from_str(&content).map_err(|e| {
+        AppError::ConfigurationError(format!("Invalid tag mapping {}: {}", path, e))
+    })?;
+
+    if mapping.accounts.is_empty() && mapping.projects.is_empty() {
+        return Err(AppError::ConfigurationError(format!(
+            "Tag mapping {} does not list any accounts or projects",
+            path
+        )));
+    }
+
+    for (project_id, labels) in &mapping.projects {
+        if let Some((key, value)) = labels
+            .iter()
+            .find(|(key, value)| !valid_gcp_label(key) || !valid_gcp_label(value))
+        {
+            return Err(AppError::ConfigurationError(format!(
+                "Project {} label {}={} is not a valid GCP label (lowercase letters, digits, '-' and '_', at most {} characters)",
+                project_id, key, value, GCP_LABEL_MAX_LEN
+            )));
+        }
+    }
+
+    Ok(mapping)
+}
+
+fn valid_gcp_label(text: &str) -> bool {
+    text.len() <= GCP_LABEL_MAX_LEN
+        && text
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Keys of `desired` that `existing` doesn't have.
+fn missing_keys(
+    existing: &HashMap<String, String>,
+    desired: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    desired
+        .iter()
+        .filter(|(key, _)| !existing.contains_key(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// EC2 instances in each mapped account's configured region that lack some
+/// of the account's tags. Accounts whose role can't be assumed are reported
+/// and skipped.
+async fn aws_plan(
+    base: &SdkConfig,
+    targets: &[AccountTarget],
+    tags: &BTreeMap<String, BTreeMap<String, String>>,
+) -> Vec<TagChange> {
+    if targets.is_empty() {
+        return Vec::new();
+    }
+
+    let results = accounts::fan_out(base, targets, |config| async move {
+        fetch_aws_resources(&config, &ResourceType::Ec2).await
+    })
+    .await;
+
+    let mut plan = Vec::new();
+    for (target, instances) in results {
+        let Some(desired) = tags.get(&target.account_id) else {
+            continue;
+        };
+        for instance in instances {
+            let missing = missing_keys(&instance.tags, desired);
+            if !missing.is_empty() {
+                plan.push(TagChange::Aws {
+                    account_id: target.account_id.clone(),
+                    instance_id: instance.id,
+                    tags: missing,
+                });
+            }
+        }
+    }
+    plan
+}
+
+/// Compute Engine instances in every zone of `project_id` that lack some of
+/// the project's labels.
+async fn gcp_plan(
+    project_id: &str,
+    desired: &BTreeMap<String, String>,
+) -> Result<Vec<TagChange>, AppError> {
+    let pages = gcp::list_pages(
+        "compute.instances.aggregatedList",
+        &format!(
+            "{}/projects/{}/aggregated/instances",
+            gcp::COMPUTE_API,
+            project_id
+        ),
+    )
+    .await?;
+
+    let mut plan = Vec::new();
+    for page in &pages {
+        let Some(zones) = page["items"].as_object() else {
+            continue;
+        };
+        for instance in zones
+            .values()
+            .filter_map(|zone| zone["instances"].as_array())
+            .flatten()
+        {
+            let existing: HashMap<String, String> = labels(&instance["labels"]);
+            let missing = missing_keys(&existing, desired);
+            if missing.is_empty() {
+                continue;
+            }
+            plan.push(TagChange::Gcp {
+                project_id: project_id.to_string(),
+                zone: gcp::short_name(instance["zone"].as_str().unwrap_or_default()).to_string(),
+                instance: instance["name"].as_str().unwrap_or_default().to_string(),
+                tags: missing,
+                existing: existing.into_iter().collect(),
+                fingerprint: instance["labelFingerprint"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            });
+        }
+    }
+    Ok(plan)
+}
+
+fn labels(value: &Value) -> HashMap<String, String> {
+    value
+        .as_object()
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn apply_change(
+    configs: &HashMap<String, SdkConfig>,
+    change: &TagChange,
+) -> Result<(), AppError> {
+    match change {
+        TagChange::Aws {
+            account_id,
+            instance_id,
+            tags,
+        } => {
+            let config = configs.get(account_id).ok_or_else(|| {
+                AppError::ConfigurationError(format!("No credentials for account {}", account_id))
+            })?;
+            let mut request = clients::get::<aws_sdk_ec2::Client>(config)
+                .await
+                .create_tags()
+                .resources(instance_id);
+            for (key, value) in tags {
+                request = request.tags(
+                    aws_sdk_ec2::types::Tag::builder()
+                        .key(key)
+                        .value(value)
+                        .build(),
+                );
+            }
+            executor::throttle("ec2").await;
+            request
+                .send()
+                .await
+                .map_err(|e| AppError::aws("ec2:CreateTags", e).for_resource(instance_id))?;
+        }
+
+        TagChange::Gcp {
+            project_id,
+            zone,
+            instance,
+            tags,
+            existing,
+            fingerprint,
+        } => {
+            let mut labels = existing.clone();
+            labels.extend(tags.clone());
+            gcp::post(
+                "compute.instances.setLabels",
+                &format!(
+                    "{}/projects/{}/zones/{}/instances/{}/setLabels",
+                    gcp::COMPUTE_API,
+                    project_id,
+                    zone,
+                    instance
+                ),
+                &serde_json::json!({
+                    "labels": labels,
+                    "labelFingerprint": fingerprint,
+                }),
+            )
+            .await
+            .map_err(|e| e.for_resource(instance))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use crate::cli::{Commands, OutputFormat};
+use crate::clients;
+use crate::commands::cost_report::determine_date_range;
+use crate::error::AppError;
+use crate::executor;
+use crate::report;
+use aws_sdk_costexplorer::types::{
+    DateInterval, Dimension, DimensionValues, Expression, Granularity, GroupDefinition,
+    GroupDefinitionType,
+};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Cost Explorer service name for EC2 instance usage
+pub(crate) const EC2_COMPUTE_SERVICE: &str = "Amazon Elastic Compute Cloud - Compute";
+pub(crate) const RUNNING_HOURS_USAGE_GROUP: &str = "EC2: Running Hours";
+
+/// Shown for instances without the environment tag
+const UNTAGGED: &str = "(untagged)";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageRow {
+    pub instance_family: String,
+    pub environment: String,
+    pub instance_hours: f64,
+    /// Instances that would have to run the whole period to use these hours
+    pub average_instances: f64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub tag_key: String,
+    pub total_hours: f64,
+    pub total_cost: f64,
+    pub rows: Vec<UsageRow>,
+}
+
+#[allow(unused_variables, dead_code)]
+pub async fn usage_report(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::UsageReport {
+        start_date,
+        end_date,
+        tag_key,
+        format,
+        profile,
+    } = cmd
+    {
+        tracing::info!("⏱️  Generating EC2 usage-hours report...");
+
+        let (start, end) = determine_date_range(start_date, end_date)?;
+        let period_hours = (end - start).num_hours().max(1) as f64;
+
+        let config = clients::aws_config().await;
+        let cost_client = clients::get::<aws_sdk_costexplorer::Client>(&config).await;
+
+        let time_period = DateInterval::builder()
+            .start(start.format("%Y-%m-%d").to_string())
+            .end(end.format("%Y-%m-%d").to_string())
+            .build()
+            .map_err(|e| AppError::AwsError(e.to_string()))?;
+
+        let filter = Expression::builder()
+            .and(dimension_filter(Dimension::Service, EC2_COMPUTE_SERVICE))
+            .and(dimension_filter(
+                Dimension::UsageTypeGroup,
+                RUNNING_HOURS_USAGE_GROUP,
+            ))
+            .build();
+
+        // (family, environment) -> (hours, cost), summed across months
+        let mut usage: BTreeMap<(String, String), (f64, f64)> = BTreeMap::new();
+        let mut next_page_token = None;
+        loop {
+            executor::throttle("costexplorer").await;
+            let response = cost_client
+                .get_cost_and_usage()
+                .time_period(time_period.clone())
+                .granularity(Granularity::Monthly)
+                .metrics("UsageQuantity")
+                .metrics("UnblendedCost")
+                .filter(filter.clone())
+                .group_by(
+                    GroupDefinition::builder()
+                        .r#type(GroupDefinitionType::Dimension)
+                        .key("INSTANCE_TYPE_FAMILY")
+                        .build(),
+                )
+                .group_by(
+                    GroupDefinition::builder()
+                        .r#type(GroupDefinitionType::Tag)
+                        .key(tag_key)
+                        .build(),
+                )
+                .set_next_page_token(next_page_token)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("ce:GetCostAndUsage", e))?;
+
+            for group in response
+                .results_by_time()
+                .iter()
+                .flat_map(|result| result.groups())
+            {
+                let keys = group.keys();
+                let family = keys.first().cloned().unwrap_or_default();
+                // Tag keys come back as "Environment$prod", or "Environment$" when untagged
+                let environment = keys
+                    .get(1)
+                    .and_then(|k| k.split_once('$'))
+                    .map(|(_, value)| value)
+                    .filter(|value| !value.is_empty())
+                    .unwrap_or(UNTAGGED)
+                    .to_string();
+
+                let metric = |name: &str| {
+                    group
+                        .metrics()
+                        .and_then(|m| m.get(name))
+                        .and_then(|m| m.amount())
+                        .and_then(|a| a.parse::<f64>().ok())
+                        .unwrap_or(0.0)
+                };
+
+                let entry = usage.entry((family, environment)).or_insert((0.0, 0.0));
+                entry.0 += metric("UsageQuantity");
+                entry.1 += metric("UnblendedCost");
+            }
+
+            next_page_token = response.next_page_token().map(str::to_string);
+            if next_page_token.is_none() {
+                break;
+            }
+        }
+
+        let mut rows: Vec<UsageRow> = usage
+            .into_iter()
+            .map(|((instance_family, environment), (hours, cost))| UsageRow {
+                instance_family,
+                environment,
+                instance_hours: hours,
+                average_instances: hours / period_hours,
+                cost,
+            })
+            .collect();
+        rows.sort_by(|a, b| b.instance_hours.total_cmp(&a.instance_hours));
+
+        let report = UsageReport {
+            start_date: start.format("%Y-%m-%d").to_string(),
+            end_date: end.format("%Y-%m-%d").to_string(),
+            tag_key: tag_key.clone(),
+            total_hours: rows.iter().map(|r| r.instance_hours).sum(),
+            total_cost: rows.iter().map(|r| r.cost).sum(),
+            rows,
+        };
+
+        output_usage_report(&report, format)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn dimension_filter(dimension: Dimension, value: &str) -> Expression {
+    Expression::builder()
+        .dimensions(
+            DimensionValues::builder()
+                .key(dimension)
+                .values(value)
+                .build(),
+        )
+        .build()
+}
+
+fn output_usage_report(report: &UsageReport, format: &OutputFormat) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Table => {
+            println!(
+                "\n📊 EC2 instance-hours from {} to {}",
+                report.start_date.green(),
+                report.end_date.green()
+            );
+            println!(
+                "{:<12} {:<20} {:>14} {:>12} {:>12}",
+                "Family", report.tag_key, "Hours", "Avg Inst.", "Cost"
+            );
+            println!("{:-<74}", "");
+
+            for row in &report.rows {
+                println!(
+                    "{:<12} {:<20} {:>14.1} {:>12.2} {:>12.2}",
+                    row.instance_family,
+                    row.environment,
+                    row.instance_hours,
+                    row.average_instances,
+                    row.cost
+                );
+            }
+
+            println!("{:-<74}", "");
+            println!(
+                "{:<33} {:>14.1} {:>12} {:>12.2}",
+                "Total", report.total_hours, "", report.total_cost
+            );
+        }
+
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(report)?;
+            println!("{}", json);
+        }
+
+        OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+            println!("{}", report::document(format, report)?);
+        }
+
+        OutputFormat::Csv => {
+            println!("InstanceFamily,Environment,InstanceHours,AverageInstances,Cost");
+            for row in &report.rows {
+                println!(
+                    "{},{},{:.2},{:.2},{:.2}",
+                    row.instance_family,
+                    row.environment,
+                    row.instance_hours,
+                    row.average_instances,
+                    row.cost
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::cli::{Commands, OutputFormat};
+use crate::commands::metrics::provider_for_id;
+use crate::creators;
+use crate::error::AppError;
+use crate::report;
+use colored::*;
+
+#[allow(unused_variables, dead_code)]
+pub async fn who_created(cmd: &Commands) -> Result<(), AppError> {
+    if let Commands::WhoCreated {
+        provider,
+        id,
+        region,
+        format,
+    } = cmd
+    {
+        let provider = provider.clone().unwrap_or_else(|| provider_for_id(id));
+        tracing::info!(
+            "🕵️  Searching {} activity logs for the creator of {}...",
+            provider.to_string().green(),
+            id.green()
+        );
+
+        let Some(creator) = creators::find_creator(&provider, id, region.as_deref()).await? else {
+            println!("ℹ️  No create event found in the last 90 days");
+            return Ok(());
+        };
+
+        match format {
+            OutputFormat::Table => {
+                println!("\n👤 {}", creator.principal.bold());
+                println!(
+                    "   Created {} ({})",
+                    creator.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    creator.event
+                );
+            }
+
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&creator)?;
+                println!("{}", json);
+            }
+
+            OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Jsonl => {
+                println!("{}", report::document(format, &creator)?);
+            }
+
+            OutputFormat::Csv => {
+                println!("ResourceId,Principal,CreatedAt,Event");
+                println!(
+                    "{},{},{},{}",
+                    creator.resource_id,
+                    creator.principal,
+                    creator.created_at.to_rfc3339(),
+                    creator.event
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use aws_config::SdkConfig;
+use aws_sdk_costexplorer::types::{
+    DateInterval, LookbackPeriodInDays, PaymentOption, SupportedSavingsPlansType, TermInYears,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Service Cost Explorer recommends Reserved Instances for
+const EC2_SERVICE: &str = "Amazon Elastic Compute Cloud - Compute";
+
+/// Error Cost Explorer returns when the account has no commitments of a kind
+const NO_DATA: &str = "DataUnavailableException";
+
+/// How well Reserved Instances and Savings Plans cover spend over a period,
+/// what went unused, and what Cost Explorer suggests buying.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitmentReport {
+    /// Share of eligible instance hours run on Reserved Instances
+    pub reservation_coverage_percent: Option<f64>,
+    pub reservation_utilization_percent: Option<f64>,
+    /// What the reserved hours nobody used cost
+    pub unused_reservation_cost: f64,
+    /// Share of eligible spend covered by Savings Plans
+    pub savings_plans_coverage_percent: Option<f64>,
+    pub savings_plans_utilization_percent: Option<f64>,
+    pub unused_savings_plans_commitment: f64,
+    pub recommendations: Vec<CommitmentRecommendation>,
+}
+
+/// A purchase Cost Explorer recommends from the last 30 days of usage,
+/// priced for a one-year term without upfront payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitmentRecommendation {
+    /// "Savings Plan" or "Reserved Instance"
+    pub kind: String,
+    pub description: String,
+    pub upfront_cost: f64,
+    pub estimated_monthly_savings: f64,
+}
+
+/// Coverage, utilization and purchase recommendations between `start` and
+/// `end`. Kinds of commitment the account has none of are left empty.
+pub async fn aws_commitments(
+    config: &SdkConfig,
+    start: &DateTime<Utc>,
+    end: &DateTime<Utc>,
+) -> Result<CommitmentReport, AppError> {
+    tracing::info!("📑 Fetching Reserved Instance and Savings Plans coverage...");
+    let client = clients::get::<aws_sdk_costexplorer::Client>(config).await;
+    let period = || {
+        DateInterval::builder()
+            .start(start.format("%Y-%m-%d").to_string())
+            .end(end.format("%Y-%m-%d").to_string())
+            .build()
+            .map_err(|e| AppError::AwsError(e.to_string()))
+    };
+
+    executor::throttle("costexplorer").await;
+    let reservation_coverage = optional(
+        client
+            .get_reservation_coverage()
+            .time_period(period()?)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetReservationCoverage", e)),
+    )?
+    .and_then(|r| parse(r.total()?.coverage_hours()?.coverage_hours_percentage()));
+
+    executor::throttle("costexplorer").await;
+    let reservation_utilization = optional(
+        client
+            .get_reservation_utilization()
+            .time_period(period()?)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetReservationUtilization", e)),
+    )?;
+    let reservation_totals = reservation_utilization.as_ref().and_then(|r| r.total());
+
+    executor::throttle("costexplorer").await;
+    let savings_plans_coverage = optional(
+        client
+            .get_savings_plans_coverage()
+            .time_period(period()?)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetSavingsPlansCoverage", e)),
+    )?
+    .and_then(|r| {
+        let (covered, total) = r
+            .savings_plans_coverages()
+            .iter()
+            .filter_map(|c| c.coverage())
+            .fold((0.0, 0.0), |(covered, total), c| {
+                (
+                    covered + parse(c.spend_covered_by_savings_plans()).unwrap_or(0.0),
+                    total + parse(c.total_cost()).unwrap_or(0.0),
+                )
+            });
+        (total > 0.0).then_some(covered / total * 100.0)
+    });
+
+    executor::throttle("costexplorer").await;
+    let savings_plans_utilization = optional(
+        client
+            .get_savings_plans_utilization()
+            .time_period(period()?)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetSavingsPlansUtilization", e)),
+    )?;
+    let savings_plans_totals = savings_plans_utilization
+        .as_ref()
+        .and_then(|r| r.total())
+        .and_then(|t| t.utilization());
+
+    let mut recommendations = savings_plans_recommendations(&client).await?;
+    recommendations.extend(reservation_recommendations(&client).await?);
+    recommendations.sort_by(|a, b| {
+        b.estimated_monthly_savings
+            .total_cmp(&a.estimated_monthly_savings)
+    });
+
+    Ok(CommitmentReport {
+        reservation_coverage_percent: reservation_coverage,
+        reservation_utilization_percent: reservation_totals
+            .and_then(|t| parse(t.utilization_percentage())),
+        unused_reservation_cost: reservation_totals
+            .and_then(|t| parse(t.ri_cost_for_unused_hours()))
+            .unwrap_or(0.0),
+        savings_plans_coverage_percent: savings_plans_coverage,
+        savings_plans_utilization_percent: savings_plans_totals
+            .and_then(|u| parse(u.utilization_percentage())),
+        unused_savings_plans_commitment: savings_plans_totals
+            .and_then(|u| parse(u.unused_commitment()))
+            .unwrap_or(0.0),
+        recommendations,
+    })
+}
+
+async fn savings_plans_recommendations(
+    client: &aws_sdk_costexplorer::Client,
+) -> Result<Vec<CommitmentRecommendation>, AppError> {
+    executor::throttle("costexplorer").await;
+    let response = optional(
+        client
+            .get_savings_plans_purchase_recommendation()
+            .savings_plans_type(SupportedSavingsPlansType::ComputeSp)
+            .term_in_years(TermInYears::OneYear)
+            .payment_option(PaymentOption::NoUpfront)
+            .lookback_period_in_days(LookbackPeriodInDays::ThirtyDays)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetSavingsPlansPurchaseRecommendation", e)),
+    )?;
+
+    let Some(summary) = response.as_ref().and_then(|r| {
+        r.savings_plans_purchase_recommendation()?
+            .savings_plans_purchase_recommendation_summary()
+    }) else {
+        return Ok(Vec::new());
+    };
+    let (Some(hourly), Some(savings)) = (
+        parse(summary.hourly_commitment_to_purchase()),
+        parse(summary.estimated_monthly_savings_amount()),
+    ) else {
+        return Ok(Vec::new());
+    };
+    if savings <= 0.0 {
+        return Ok(Vec::new());
+    }
+
+    Ok(vec![CommitmentRecommendation {
+        kind: "Savings Plan".to_string(),
+        description: format!("Compute Savings Plan at ${:.2}/hour", hourly),
+        upfront_cost: 0.0,
+        estimated_monthly_savings: savings,
+    }])
+}
+
+async fn reservation_recommendations(
+    client: &aws_sdk_costexplorer::Client,
+) -> Result<Vec<CommitmentRecommendation>, AppError> {
+    executor::throttle("costexplorer").await;
+    let response = optional(
+        client
+            .get_reservation_purchase_recommendation()
+            .service(EC2_SERVICE)
+            .term_in_years(TermInYears::OneYear)
+            .payment_option(PaymentOption::NoUpfront)
+            .lookback_period_in_days(LookbackPeriodInDays::ThirtyDays)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ce:GetReservationPurchaseRecommendation", e)),
+    )?;
+
+    Ok(response
+        .iter()
+        .flat_map(|r| r.recommendations())
+        .flat_map(|r| r.recommendation_details())
+        .filter_map(|detail| {
+            let instance = detail.instance_details()?.ec2_instance_details()?;
+            let savings = parse(detail.estimated_monthly_savings_amount())?;
+            Some(CommitmentRecommendation {
+                kind: "Reserved Instance".to_string(),
+                description: format!(
+                    "{} x {} in {}",
+                    detail
+                        .recommended_number_of_instances_to_purchase()
+                        .unwrap_or("?"),
+                    instance.instance_type().unwrap_or("?"),
+                    instance.region().unwrap_or("?")
+                ),
+                upfront_cost: parse(detail.upfront_cost()).unwrap_or(0.0),
+                estimated_monthly_savings: savings,
+            })
+        })
+        .filter(|r| r.estimated_monthly_savings > 0.0)
+        .collect())
+}
+
+/// `None` instead of an error when the account has none of a commitment.
+fn optional<T>(result: Result<T, AppError>) -> Result<Option<T>, AppError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(AppError::Provider(e)) if e.code.as_deref() == Some(NO_DATA) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Cost Explorer reports amounts and percentages as strings.
+fn parse(value: Option<&str>) -> Option<f64> {
+    value?.parse().ok()
+}
@@ -0,0 +1,247 @@
+use crate::azure;
+use crate::cli::CloudProvider;
+use crate::clients;
+use crate::commands::list::aws_datetime;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::regions;
+use aws_config::SdkConfig;
+use aws_sdk_cloudtrail::types::{LookupAttribute, LookupAttributeKey};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const LOGGING_API: &str = "https://logging.googleapis.com/v2";
+
+/// Days of activity searched. CloudTrail event history and the Azure
+/// activity log both keep 90 days.
+const LOOKBACK_DAYS: i64 = 90;
+
+/// Global services such as IAM and CloudFront record their events here
+const AWS_GLOBAL_EVENTS_REGION: &str = "us-east-1";
+
+/// API version of the Azure activity log
+const ACTIVITY_LOG_API_VERSION: &str = "2015-04-01";
+
+/// CloudTrail events that bring a resource into existence
+const AWS_CREATE_PREFIXES: &[&str] = &["Run", "Create", "Allocate", "Import", "Copy", "Request"];
+
+/// Who created a resource and when, according to the provider's activity log.
+#[derive(Debug, Clone, Serialize)]
+pub struct Creator {
+    pub resource_id: String,
+    pub principal: String,
+    pub created_at: DateTime<Utc>,
+    pub event: String,
+}
+
+/// Look up the creator of `resource_id`. AWS searches the CloudTrail
+/// history of `region`, or of the configured region when `None`.
+pub async fn find_creator(
+    provider: &CloudProvider,
+    resource_id: &str,
+    region: Option<&str>,
+) -> Result<Option<Creator>, AppError> {
+    match provider {
+        CloudProvider::Aws => {
+            let base = clients::aws_config().await;
+            let config = match region {
+                Some("global") => regions::regional_config(&base, AWS_GLOBAL_EVENTS_REGION),
+                Some(region) => regions::regional_config(&base, region),
+                None => base,
+            };
+            aws_creator(&config, resource_id).await
+        }
+        CloudProvider::Gcp => gcp_creator(resource_id).await,
+        CloudProvider::Azure => azure_creator(resource_id).await,
+    }
+}
+
+/// Creators of `resources` (id and region), keyed by resource id. Used to
+/// annotate reports, so a failed lookup leaves that resource out.
+pub async fn find_creators(
+    provider: &CloudProvider,
+    resources: &[(String, String)],
+) -> HashMap<String, Creator> {
+    let mut creators = HashMap::new();
+    for (id, region) in resources {
+        match find_creator(provider, id, Some(region)).await {
+            Ok(Some(creator)) => {
+                creators.insert(id.clone(), creator);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("   ⚠️  Could not look up creator of {}: {}", id, e),
+        }
+    }
+    creators
+}
+
+/// Earliest create event naming the resource in CloudTrail.
+async fn aws_creator(config: &SdkConfig, resource_id: &str) -> Result<Option<Creator>, AppError> {
+    let client = clients::get::<aws_sdk_cloudtrail::Client>(config).await;
+    let now = Utc::now();
+
+    let attribute = LookupAttribute::builder()
+        .attribute_key(LookupAttributeKey::ResourceName)
+        .attribute_value(resource_id)
+        .build()
+        .map_err(|e| AppError::AwsError(e.to_string()))?;
+
+    let mut creator: Option<Creator> = None;
+    let mut pages = client
+        .lookup_events()
+        .lookup_attributes(attribute)
+        .start_time(aws_smithy_types::DateTime::from_secs(
+            (now - Duration::days(LOOKBACK_DAYS)).timestamp(),
+        ))
+        .end_time(aws_smithy_types::DateTime::from_secs(now.timestamp()))
+        .into_paginator()
+        .send();
+    loop {
+        executor::throttle("cloudtrail").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page
+            .map_err(|e| AppError::aws("cloudtrail:LookupEvents", e).for_resource(resource_id))?;
+
+        for event in page.events() {
+            let name = event.event_name().unwrap_or_default();
+            if !AWS_CREATE_PREFIXES.iter().any(|p| name.starts_with(p)) {
+                continue;
+            }
+            let Some(created_at) = aws_datetime(event.event_time()) else {
+                continue;
+            };
+            if creator.as_ref().is_some_and(|c| c.created_at <= created_at) {
+                continue;
+            }
+
+            // The ARN names the role session, not just the role
+            let detail: Value = event
+                .cloud_trail_event()
+                .and_then(|e| serde_json::from_str(e).ok())
+                .unwrap_or_default();
+            let principal = detail["userIdentity"]["arn"]
+                .as_str()
+                .or(event.username())
+                .unwrap_or("unknown")
+                .to_string();
+
+            creator = Some(Creator {
+                resource_id: resource_id.to_string(),
+                principal,
+                created_at,
+                event: name.to_string(),
+            });
+        }
+    }
+
+    Ok(creator)
+}
+
+/// Earliest insert or create in the project's admin activity audit log.
+async fn gcp_creator(resource: &str) -> Result<Option<Creator>, AppError> {
+    let project_id = gcp::project_id()?;
+    let since =
+        (Utc::now() - Duration::days(LOOKBACK_DAYS)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let filter = format!(
+        "logName=\"projects/{}/logs/cloudaudit.googleapis.com%2Factivity\" AND protoPayload.resourceName:\"{}\" AND (protoPayload.methodName:\"insert\" OR protoPayload.methodName:\"create\") AND timestamp>=\"{}\"",
+        project_id, resource, since
+    );
+    let body = serde_json::json!({
+        "resourceNames": [format!("projects/{}", project_id)],
+        "filter": filter,
+        "orderBy": "timestamp asc",
+        "pageSize": 1,
+    });
+
+    let response = gcp::post(
+        "logging.entries.list",
+        &format!("{}/entries:list", LOGGING_API),
+        &body,
+    )
+    .await
+    .map_err(|e| e.for_resource(resource))?;
+
+    let Some(entry) = response["entries"].as_array().and_then(|e| e.first()) else {
+        return Ok(None);
+    };
+    let Some(created_at) = entry["timestamp"]
+        .as_str()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+    else {
+        return Ok(None);
+    };
+
+    let payload = &entry["protoPayload"];
+    Ok(Some(Creator {
+        resource_id: resource.to_string(),
+        principal: payload["authenticationInfo"]["principalEmail"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string(),
+        created_at: created_at.with_timezone(&Utc),
+        event: payload["methodName"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+    }))
+}
+
+/// Earliest successful write in the activity log. Writes also cover
+/// updates, so within the 90 days this is the likely creator rather than a
+/// certain one.
+async fn azure_creator(resource_id: &str) -> Result<Option<Creator>, AppError> {
+    let subscription_id = azure::subscription_id()?;
+    let since =
+        (Utc::now() - Duration::days(LOOKBACK_DAYS)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let filter = format!(
+        "eventTimestamp ge '{}' and resourceUri eq '{}'",
+        since, resource_id
+    );
+    let url = reqwest::Url::parse_with_params(
+        &format!(
+            "{}/subscriptions/{}/providers/Microsoft.Insights/eventtypes/management/values",
+            azure::MANAGEMENT_API,
+            subscription_id
+        ),
+        &[
+            ("api-version", ACTIVITY_LOG_API_VERSION),
+            ("$filter", filter.as_str()),
+            ("$select", "caller,eventTimestamp,operationName,status"),
+        ],
+    )
+    .map_err(|e| AppError::InvalidParameters(e.to_string()))?;
+
+    let events = azure::list("Microsoft.Insights/eventtypes/values/read", url.as_str())
+        .await
+        .map_err(|e| e.for_resource(resource_id))?;
+
+    Ok(events
+        .iter()
+        .filter(|event| {
+            event["operationName"]["value"]
+                .as_str()
+                .is_some_and(|op| op.to_lowercase().ends_with("/write"))
+                && event["status"]["value"].as_str() == Some("Succeeded")
+        })
+        .filter_map(|event| {
+            let created_at = event["eventTimestamp"]
+                .as_str()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())?
+                .with_timezone(&Utc);
+            Some(Creator {
+                resource_id: resource_id.to_string(),
+                principal: event["caller"].as_str().unwrap_or("unknown").to_string(),
+                created_at,
+                event: event["operationName"]["value"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+        })
+        .min_by_key(|creator| creator.created_at))
+}
@@ -0,0 +1,335 @@
+use crate::azure;
+use crate::error::AppError;
+use crate::gcp;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Days of connections looked at when deciding whether a database is idle
+pub const IDLE_WINDOW_DAYS: i64 = 14;
+
+pub const CLOUD_SQL_INSTANCE: &str = "Cloud SQL Instance";
+pub const AZURE_SQL_DATABASE: &str = "Azure SQL Database";
+pub const AZURE_POSTGRES_SERVER: &str = "PostgreSQL Flexible Server";
+
+const SQL_ADMIN_API: &str = "https://sqladmin.googleapis.com/v1";
+const AZURE_SQL_API_VERSION: &str = "2021-11-01";
+const AZURE_POSTGRES_API_VERSION: &str = "2022-12-01";
+
+/// A Cloud SQL instance, Azure SQL database or Azure Database for
+/// PostgreSQL flexible server.
+#[derive(Debug, Clone, Serialize)]
+pub struct ManagedDatabase {
+    /// Instance name on GCP, ARM id on Azure
+    pub id: String,
+    pub name: String,
+    pub resource_type: String,
+    pub region: String,
+    pub state: String,
+    /// e.g. POSTGRES_15 or SQL Server 12.0
+    pub engine: String,
+    pub tier: String,
+    pub creation_date: Option<DateTime<Utc>>,
+    pub tags: HashMap<String, String>,
+    /// Stopped or paused databases accept no connections and cost little
+    pub stopped: bool,
+}
+
+/// Whether `resource_type` is one of the managed database types.
+pub fn is_database(resource_type: &str) -> bool {
+    [
+        CLOUD_SQL_INSTANCE,
+        AZURE_SQL_DATABASE,
+        AZURE_POSTGRES_SERVER,
+    ]
+    .contains(&resource_type)
+}
+
+/// Every Cloud SQL instance of the project.
+pub async fn cloud_sql_instances() -> Result<Vec<ManagedDatabase>, AppError> {
+    let project_id = gcp::project_id()?;
+    let pages = gcp::list_pages(
+        "sql.instances.list",
+        &format!("{}/projects/{}/instances", SQL_ADMIN_API, project_id),
+    )
+    .await?;
+
+    Ok(pages
+        .iter()
+        .filter_map(|page| page["items"].as_array())
+        .flatten()
+        .map(|instance| {
+            let settings = &instance["settings"];
+            let name = instance["name"].as_str().unwrap_or_default().to_string();
+            ManagedDatabase {
+                id: name.clone(),
+                name,
+                resource_type: CLOUD_SQL_INSTANCE.to_string(),
+                region: instance["region"].as_str().unwrap_or_default().to_string(),
+                state: instance["state"].as_str().unwrap_or_default().to_string(),
+                engine: instance["databaseVersion"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                tier: settings["tier"].as_str().unwrap_or_default().to_string(),
+                creation_date: timestamp(&instance["createTime"]),
+                tags: string_map(&settings["userLabels"]),
+                // A stopped instance stays RUNNABLE with activation policy NEVER
+                stopped: settings["activationPolicy"].as_str() == Some("NEVER"),
+            }
+        })
+        .collect())
+}
+
+/// Every Azure SQL database and PostgreSQL flexible server of the
+/// subscription. The `master` database of each SQL server is left out.
+pub async fn azure_databases() -> Result<Vec<ManagedDatabase>, AppError> {
+    let subscription_id = azure::subscription_id()?;
+    let mut databases = Vec::new();
+
+    let url = format!(
+        "{}/subscriptions/{}/providers/Microsoft.Sql/servers?api-version={}",
+        azure::MANAGEMENT_API,
+        subscription_id,
+        AZURE_SQL_API_VERSION
+    );
+    for server in azure::list("Microsoft.Sql/servers/read", &url).await? {
+        let version = server["properties"]["version"].as_str().unwrap_or_default();
+        let url = format!(
+            "{}{}/databases?api-version={}",
+            azure::MANAGEMENT_API,
+            server["id"].as_str().unwrap_or_default(),
+            AZURE_SQL_API_VERSION
+        );
+        for database in azure::list("Microsoft.Sql/servers/databases/read", &url).await? {
+            if database["name"].as_str() == Some("master") {
+                continue;
+            }
+            let properties = &database["properties"];
+            let status = properties["status"].as_str().unwrap_or_default();
+            databases.push(ManagedDatabase {
+                id: database["id"].as_str().unwrap_or_default().to_string(),
+                name: database["name"].as_str().unwrap_or_default().to_string(),
+                resource_type: AZURE_SQL_DATABASE.to_string(),
+                region: database["location"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                state: status.to_string(),
+                engine: format!("SQL Server {}", version),
+                tier: database["sku"]["name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                creation_date: timestamp(&properties["creationDate"]),
+                tags: string_map(&database["tags"]),
+                stopped: status == "Paused",
+            });
+        }
+    }
+
+    let url = format!(
+        "{}/subscriptions/{}/providers/Microsoft.DBforPostgreSQL/flexibleServers?api-version={}",
+        azure::MANAGEMENT_API,
+        subscription_id,
+        AZURE_POSTGRES_API_VERSION
+    );
+    for server in azure::list("Microsoft.DBforPostgreSQL/flexibleServers/read", &url).await? {
+        let properties = &server["properties"];
+        let state = properties["state"].as_str().unwrap_or_default();
+        databases.push(ManagedDatabase {
+            id: server["id"].as_str().unwrap_or_default().to_string(),
+            name: server["name"].as_str().unwrap_or_default().to_string(),
+            resource_type: AZURE_POSTGRES_SERVER.to_string(),
+            region: server["location"].as_str().unwrap_or_default().to_string(),
+            state: state.to_string(),
+            engine: format!(
+                "PostgreSQL {}",
+                properties["version"].as_str().unwrap_or_default()
+            ),
+            tier: server["sku"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            creation_date: timestamp(&server["systemData"]["createdAt"]),
+            tags: string_map(&server["tags"]),
+            stopped: state == "Stopped",
+        });
+    }
+
+    Ok(databases)
+}
+
+/// Connections `database` saw over the last [`IDLE_WINDOW_DAYS`] days: the
+/// peak open connections on Cloud SQL and PostgreSQL, successful logins on
+/// Azure SQL. Zero makes the database an idle candidate.
+pub async fn connections(database: &ManagedDatabase) -> Result<f64, AppError> {
+    match database.resource_type.as_str() {
+        CLOUD_SQL_INSTANCE => cloud_sql_connections(&database.name).await,
+        AZURE_SQL_DATABASE => azure_metric(&database.id, "connection_successful", "Total").await,
+        AZURE_POSTGRES_SERVER => azure_metric(&database.id, "active_connections", "Maximum").await,
+        other => Err(AppError::InvalidParameters(format!(
+            "{} is not a managed database",
+            other
+        ))),
+    }
+}
+
+async fn cloud_sql_connections(instance: &str) -> Result<f64, AppError> {
+    let project_id = gcp::project_id()?;
+    let now = Utc::now();
+    let start = now - Duration::days(IDLE_WINDOW_DAYS);
+    let filter = format!(
+        "metric.type=\"cloudsql.googleapis.com/database/network/connections\" AND resource.labels.database_id=\"{}:{}\"",
+        project_id, instance
+    );
+    let start_time = start.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let end_time = now.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let alignment = format!("{}s", IDLE_WINDOW_DAYS * 86400);
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/projects/{}/timeSeries", gcp::MONITORING_API, project_id),
+        &[
+            ("filter", filter.as_str()),
+            ("interval.startTime", start_time.as_str()),
+            ("interval.endTime", end_time.as_str()),
+            ("aggregation.alignmentPeriod", alignment.as_str()),
+            ("aggregation.perSeriesAligner", "ALIGN_MAX"),
+            ("aggregation.crossSeriesReducer", "REDUCE_MAX"),
+        ],
+    )
+    .map_err(|e| AppError::InvalidParameters(e.to_string()))?;
+
+    let response = gcp::get("monitoring.timeSeries.list", url.as_str())
+        .await
+        .map_err(|e| e.for_resource(instance))?;
+
+    // int64 values arrive as strings
+    Ok(response["timeSeries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|series| series["points"].as_array())
+        .flatten()
+        .filter_map(|point| {
+            let value = &point["value"]["int64Value"];
+            value
+                .as_str()
+                .and_then(|v| v.parse::<f64>().ok())
+                .or(value.as_f64())
+        })
+        .fold(0.0, f64::max))
+}
+
+/// `aggregation` of Azure Monitor `metric` over the window, by day. A
+/// Maximum is the highest day; a Total is summed.
+async fn azure_metric(resource_id: &str, metric: &str, aggregation: &str) -> Result<f64, AppError> {
+    let now = Utc::now();
+    let start = now - Duration::days(IDLE_WINDOW_DAYS);
+    let timespan = format!(
+        "{}/{}",
+        start.to_rfc3339_opts(SecondsFormat::Secs, true),
+        now.to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+    let url = reqwest::Url::parse_with_params(
+        &format!(
+            "{}{}/providers/Microsoft.Insights/metrics",
+            azure::MANAGEMENT_API,
+            resource_id
+        ),
+        &[
+            ("api-version", azure::METRICS_API_VERSION),
+            ("metricnames", metric),
+            ("aggregation", aggregation),
+            ("interval", "P1D"),
+            ("timespan", timespan.as_str()),
+        ],
+    )
+    .map_err(|e| AppError::InvalidParameters(e.to_string()))?;
+
+    let metrics = azure::list("Microsoft.Insights/metrics/read", url.as_str())
+        .await
+        .map_err(|e| e.for_resource(resource_id))?;
+
+    let field = aggregation.to_lowercase();
+    let values = metrics
+        .iter()
+        .filter_map(|metric| metric["timeseries"].as_array())
+        .flatten()
+        .filter_map(|series| series["data"].as_array())
+        .flatten()
+        .filter_map(|point| point[field.as_str()].as_f64());
+    Ok(if aggregation == "Maximum" {
+        values.fold(0.0, f64::max)
+    } else {
+        values.sum()
+    })
+}
+
+/// What cleanup does with an idle database of `resource_type`: stop it
+/// where the service allows, otherwise delete it.
+pub fn cleanup_action(resource_type: &str) -> &'static str {
+    match resource_type {
+        AZURE_SQL_DATABASE => "DELETE",
+        _ => "STOP",
+    }
+}
+
+/// Carry out [`cleanup_action`] for the database with `id`. Azure SQL
+/// databases can't be stopped; a deleted one stays restorable from backup
+/// for the server's retention period.
+pub async fn retire(resource_type: &str, id: &str) -> Result<(), AppError> {
+    let result = match resource_type {
+        CLOUD_SQL_INSTANCE => {
+            let project_id = gcp::project_id()?;
+            gcp::patch(
+                "sql.instances.patch",
+                &format!("{}/projects/{}/instances/{}", SQL_ADMIN_API, project_id, id),
+                &json!({ "settings": { "activationPolicy": "NEVER" } }),
+            )
+            .await
+            .map(|_| ())
+        }
+        AZURE_POSTGRES_SERVER => azure::post(
+            "Microsoft.DBforPostgreSQL/flexibleServers/stop/action",
+            &format!(
+                "{}{}/stop?api-version={}",
+                azure::MANAGEMENT_API,
+                id,
+                AZURE_POSTGRES_API_VERSION
+            ),
+        )
+        .await
+        .map(|_| ()),
+        AZURE_SQL_DATABASE => {
+            azure::delete(
+                "Microsoft.Sql/servers/databases/delete",
+                id,
+                AZURE_SQL_API_VERSION,
+            )
+            .await
+        }
+        other => Err(AppError::InvalidParameters(format!(
+            "{} is not a managed database",
+            other
+        ))),
+    };
+    result.map_err(|e| e.for_resource(id))
+}
+
+fn timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.as_str()?)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+fn string_map(value: &Value) -> HashMap<String, String> {
+    value
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
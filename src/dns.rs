@@ -0,0 +1,249 @@
+use crate::cli::CloudProvider;
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use crate::retry;
+use aws_config::SdkConfig;
+use serde::Serialize;
+use serde_json::Value;
+use std::net::IpAddr;
+
+pub const DNS_API: &str = "https://dns.googleapis.com/dns/v1";
+
+/// Address ranges each provider publishes for its compute services
+const AWS_IP_RANGES: &str = "https://ip-ranges.amazonaws.com/ip-ranges.json";
+const GCP_IP_RANGES: &str = "https://www.gstatic.com/ipranges/cloud.json";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsZone {
+    pub id: String,
+    pub name: String,
+    pub private: bool,
+    pub records: Vec<DnsRecord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: String,
+    pub ttl: Option<i64>,
+    pub values: Vec<String>,
+    /// Route 53 alias target, in place of `values`
+    pub alias_target: Option<String>,
+}
+
+/// Every Route 53 hosted zone with its record sets.
+pub async fn aws_zones(config: &SdkConfig) -> Result<Vec<DnsZone>, AppError> {
+    let client = clients::get::<aws_sdk_route53::Client>(config).await;
+    let mut zones = Vec::new();
+
+    let mut pages = client.list_hosted_zones().into_paginator().send();
+    loop {
+        executor::throttle("route53").await;
+        let Some(page) = pages.next().await else {
+            break;
+        };
+        let page = page.map_err(|e| AppError::aws("route53:ListHostedZones", e))?;
+
+        for zone in page.hosted_zones() {
+            let id = zone.id().trim_start_matches("/hostedzone/").to_string();
+            let records = aws_records(&client, &id).await?;
+            zones.push(DnsZone {
+                name: zone.name().trim_end_matches('.').to_string(),
+                private: zone.config().is_some_and(|c| c.private_zone()),
+                id,
+                records,
+            });
+        }
+    }
+
+    Ok(zones)
+}
+
+async fn aws_records(
+    client: &aws_sdk_route53::Client,
+    zone_id: &str,
+) -> Result<Vec<DnsRecord>, AppError> {
+    let mut records = Vec::new();
+    let mut start_name = None;
+    let mut start_type = None;
+    let mut start_identifier = None;
+
+    // ListResourceRecordSets pages by record name and type rather than a token
+    loop {
+        executor::throttle("route53").await;
+        let page = client
+            .list_resource_record_sets()
+            .hosted_zone_id(zone_id)
+            .set_start_record_name(start_name)
+            .set_start_record_type(start_type)
+            .set_start_record_identifier(start_identifier)
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::aws("route53:ListResourceRecordSets", e).for_resource(zone_id)
+            })?;
+
+        for set in page.resource_record_sets() {
+            records.push(DnsRecord {
+                name: set.name().trim_end_matches('.').to_string(),
+                record_type: set.r#type().as_str().to_string(),
+                ttl: set.ttl(),
+                values: set
+                    .resource_records()
+                    .iter()
+                    .map(|r| r.value().to_string())
+                    .collect(),
+                alias_target: set.alias_target().map(|a| normalize_host(a.dns_name())),
+            });
+        }
+
+        if !page.is_truncated() {
+            return Ok(records);
+        }
+        start_name = page.next_record_name().map(str::to_string);
+        start_type = page.next_record_type().cloned();
+        start_identifier = page.next_record_identifier().map(str::to_string);
+    }
+}
+
+/// Every Cloud DNS managed zone in the project with its record sets.
+pub async fn gcp_zones() -> Result<Vec<DnsZone>, AppError> {
+    let project_id = gcp::project_id()?;
+    let url = format!("{}/projects/{}/managedZones", DNS_API, project_id);
+    let pages = gcp::list_pages("dns.managedZones.list", &url).await?;
+
+    let mut zones = Vec::new();
+    for zone in pages
+        .iter()
+        .filter_map(|page| page["managedZones"].as_array())
+        .flatten()
+    {
+        let id = zone["name"].as_str().unwrap_or_default().to_string();
+        let url = format!(
+            "{}/projects/{}/managedZones/{}/rrsets",
+            DNS_API, project_id, id
+        );
+        let records = gcp::list_pages("dns.resourceRecordSets.list", &url)
+            .await
+            .map_err(|e| e.for_resource(&id))?
+            .iter()
+            .filter_map(|page| page["rrsets"].as_array())
+            .flatten()
+            .map(gcp_record)
+            .collect();
+
+        zones.push(DnsZone {
+            name: zone["dnsName"]
+                .as_str()
+                .unwrap_or_default()
+                .trim_end_matches('.')
+                .to_string(),
+            private: zone["visibility"].as_str() == Some("private"),
+            id,
+            records,
+        });
+    }
+
+    Ok(zones)
+}
+
+fn gcp_record(rrset: &Value) -> DnsRecord {
+    DnsRecord {
+        name: rrset["name"]
+            .as_str()
+            .unwrap_or_default()
+            .trim_end_matches('.')
+            .to_string(),
+        record_type: rrset["type"].as_str().unwrap_or_default().to_string(),
+        ttl: rrset["ttl"].as_i64(),
+        values: rrset["rrdatas"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        alias_target: None,
+    }
+}
+
+/// Hostname in the form inventory and DNS targets are compared in:
+/// lowercase, without the trailing dot or the ELB `dualstack.` prefix.
+pub fn normalize_host(name: &str) -> String {
+    let name = name.trim_end_matches('.').to_lowercase();
+    match name.strip_prefix("dualstack.") {
+        Some(rest) => rest.to_string(),
+        None => name,
+    }
+}
+
+/// The address blocks a provider hands out to compute resources. An address
+/// inside them that the account doesn't hold may now belong to someone else.
+pub struct IpRanges(Vec<(IpAddr, u8)>);
+
+impl IpRanges {
+    pub async fn fetch(provider: &CloudProvider) -> Result<Self, AppError> {
+        let url = match provider {
+            CloudProvider::Aws => AWS_IP_RANGES,
+            CloudProvider::Gcp => GCP_IP_RANGES,
+            CloudProvider::Azure => {
+                return Err(AppError::InvalidParameters(
+                    "Azure IP ranges are not supported".to_string(),
+                ))
+            }
+        };
+
+        let client = reqwest::Client::new();
+        executor::throttle("ip-ranges").await;
+        let response = retry::send_http("ip-ranges", || client.get(url)).await?;
+        let document: Value = response.error_for_status()?.json().await?;
+
+        let prefixes = match provider {
+            // EC2 covers Elastic IPs, instance addresses and load balancers
+            CloudProvider::Aws => document["prefixes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .chain(document["ipv6_prefixes"].as_array().into_iter().flatten())
+                .filter(|p| p["service"].as_str() == Some("EC2"))
+                .filter_map(|p| p["ip_prefix"].as_str().or(p["ipv6_prefix"].as_str()))
+                .filter_map(parse_cidr)
+                .collect(),
+            _ => document["prefixes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|p| p["ipv4Prefix"].as_str().or(p["ipv6Prefix"].as_str()))
+                .filter_map(parse_cidr)
+                .collect(),
+        };
+
+        Ok(Self(prefixes))
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|(network, bits)| match (network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                same_prefix(u32::from(*network).into(), u32::from(ip).into(), *bits, 32)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                same_prefix(u128::from(*network), u128::from(ip), *bits, 128)
+            }
+            _ => false,
+        })
+    }
+}
+
+fn parse_cidr(cidr: &str) -> Option<(IpAddr, u8)> {
+    let (address, bits) = cidr.split_once('/')?;
+    Some((address.parse().ok()?, bits.parse().ok()?))
+}
+
+fn same_prefix(network: u128, ip: u128, bits: u8, width: u8) -> bool {
+    if bits == 0 {
+        return true;
+    }
+    let shift = u32::from(width - bits.min(width));
+    network >> shift == ip >> shift
+}
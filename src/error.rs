@@ -1,4 +1,95 @@
+use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::error::metadata::ProvideErrorMetadata;
+use std::fmt;
 use thiserror::Error;
+
+/// AWS error codes worth another attempt later: throttling and transient
+/// service-side failures.
+const RETRYABLE_AWS_CODES: &[&str] = &[
+    "Throttling",
+    "ThrottlingException",
+    "ThrottledException",
+    "RequestThrottled",
+    "RequestThrottledException",
+    "RequestLimitExceeded",
+    "TooManyRequestsException",
+    "ProvisionedThroughputExceededException",
+    "SlowDown",
+    "InternalError",
+    "InternalFailure",
+    "InternalServerError",
+    "ServiceUnavailable",
+    "ServiceUnavailableException",
+    "RequestTimeout",
+    "RequestTimeoutException",
+];
+
+/// AWS error codes for credentials that are missing, expired or revoked.
+const EXPIRED_AWS_CODES: &[&str] = &[
+    "ExpiredToken",
+    "ExpiredTokenException",
+    "RequestExpired",
+    "InvalidClientTokenId",
+    "UnrecognizedClientException",
+    "SignatureDoesNotMatch",
+    "AuthFailure",
+];
+
+/// AWS error codes for a call the credentials aren't allowed to make.
+const DENIED_AWS_CODES: &[&str] = &[
+    "UnauthorizedOperation",
+    "AccessDenied",
+    "AccessDeniedException",
+    "AuthorizationError",
+];
+
+/// Exit status for failures without a more specific one
+const EXIT_FAILURE: i32 = 1;
+/// Exit status for invalid arguments, as clap uses for unparseable ones
+const EXIT_USAGE: i32 = 2;
+/// Exit status when credentials are missing, expired or not allowed the call
+const EXIT_AUTH: i32 = 3;
+/// Exit status when some providers or actions failed and the rest succeeded
+const EXIT_PARTIAL: i32 = 4;
+/// Exit status when the resource or data asked for doesn't exist
+const EXIT_NOT_FOUND: i32 = 5;
+/// Exit status when the operator declined a confirmation
+const EXIT_CANCELLED: i32 = 6;
+/// Exit status for a run stopped by SIGINT, as shells report it
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// A failed cloud API call and what it was doing at the time.
+#[derive(Debug)]
+pub struct ProviderError {
+    pub provider: &'static str,
+    /// Service and API operation, e.g. "ec2:TerminateInstances"
+    pub operation: String,
+    pub region: Option<String>,
+    pub resource_id: Option<String>,
+    /// The provider's error code, e.g. "UnauthorizedOperation"
+    pub code: Option<String>,
+    pub message: String,
+    pub retryable: bool,
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.provider, self.operation)?;
+        if let Some(resource_id) = &self.resource_id {
+            write!(f, " on {}", resource_id)?;
+        }
+        if let Some(region) = &self.region {
+            write!(f, " in {}", region)?;
+        }
+        write!(f, " failed: ")?;
+        if let Some(code) = &self.code {
+            write!(f, "{}: ", code)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
 #[allow(dead_code, unused_imports, unused_variables)]
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -35,6 +126,9 @@ pub enum AppError {
     #[error("User cancelled operation")]
     UserCancelled,
 
+    #[error("Interrupted with Ctrl-C")]
+    Interrupted,
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -44,9 +138,48 @@ pub enum AppError {
     #[error("HTTP request error: {0}")]
     HttpError(#[from] reqwest::Error),
 
+    #[error("Inventory database error: {0}")]
+    DatabaseError(#[from] rusqlite::Error),
+
+    #[error("Export error: {0}")]
+    ExportError(String),
+
+    #[error("{0} policy violations found")]
+    PolicyViolations(usize),
+
+    #[error("{0} batch actions failed")]
+    BatchFailed(usize),
+
+    #[error("Results are incomplete: {0}")]
+    PartialResults(String),
+
+    #[error("Encryption error: {0}")]
+    EncryptionError(String),
+
+    #[error("Self-update failed: {0}")]
+    UpdateError(String),
+
+    #[error("Scheduled job {0}")]
+    JobFailed(String),
+
+    #[error("Template error: {0}")]
+    TemplateError(String),
+
+    #[error("Read-only mode: {0}")]
+    ReadOnly(String),
+
+    #[error("Stopped after {0} API calls (--max-api-calls)")]
+    CallBudgetExceeded(u64),
+
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
     #[error("AWS SDK error: {0}")]
     AwsError(String),
 
+    #[error("{0}")]
+    Provider(Box<ProviderError>),
+
     #[error("GCP error: {0}")]
     GcpError(String),
 
@@ -57,6 +190,291 @@ pub enum AppError {
     Unknown(String),
 }
 
+impl AppError {
+    /// Wrap a failed AWS SDK call to `operation` ("service:Operation"),
+    /// keeping the error code and whether trying again could help.
+    pub fn aws<E, R>(operation: &str, err: SdkError<E, R>) -> Self
+    where
+        E: ProvideErrorMetadata + std::error::Error + 'static,
+        R: fmt::Debug,
+    {
+        let code = err.code().map(str::to_string);
+        let retryable = match &err {
+            SdkError::TimeoutError(_) | SdkError::ResponseError(_) => true,
+            SdkError::DispatchFailure(failure) => failure.is_timeout() || failure.is_io(),
+            SdkError::ServiceError(_) => code
+                .as_deref()
+                .is_some_and(|code| RETRYABLE_AWS_CODES.contains(&code)),
+            _ => false,
+        };
+        let message = err
+            .message()
+            .map(|message| {
+                // EC2 appends a blob only sts:DecodeAuthorizationMessage can read
+                message
+                    .split(" Encoded authorization failure message:")
+                    .next()
+                    .unwrap_or(message)
+                    .to_string()
+            })
+            .unwrap_or_else(|| DisplayErrorContext(&err).to_string());
+
+        AppError::Provider(Box::new(ProviderError {
+            provider: "AWS",
+            operation: operation.to_string(),
+            region: None,
+            resource_id: None,
+            code,
+            message,
+            retryable,
+        }))
+    }
+
+    /// Wrap a failed Google API call from its HTTP status and error body,
+    /// e.g. `{"error": {"code": 404, "message": "...", "errors": [{"reason": "notFound"}]}}`.
+    pub fn gcp(operation: &str, status: reqwest::StatusCode, body: &str) -> Self {
+        let error = error_body(body);
+        let code = error["errors"][0]["reason"]
+            .as_str()
+            .or_else(|| error["status"].as_str())
+            .map(str::to_string);
+        Self::http_provider("GCP", operation, status, code, error["message"].as_str())
+    }
+
+    /// Wrap a failed Azure Resource Manager call from its HTTP status and
+    /// error body, e.g. `{"error": {"code": "InUseNetworkInterfaceCannotBeDeleted", "message": "..."}}`.
+    pub fn azure(operation: &str, status: reqwest::StatusCode, body: &str) -> Self {
+        let error = error_body(body);
+        let code = error["code"].as_str().map(str::to_string);
+        Self::http_provider("Azure", operation, status, code, error["message"].as_str())
+    }
+
+    fn http_provider(
+        provider: &'static str,
+        operation: &str,
+        status: reqwest::StatusCode,
+        code: Option<String>,
+        message: Option<&str>,
+    ) -> Self {
+        AppError::Provider(Box::new(ProviderError {
+            provider,
+            operation: operation.to_string(),
+            region: None,
+            resource_id: None,
+            code,
+            message: message
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("HTTP {}", status)),
+            retryable: status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+        }))
+    }
+
+    /// Attach the region a provider call was made in.
+    pub fn in_region(mut self, region: &str) -> Self {
+        if let AppError::Provider(e) = &mut self {
+            e.region = Some(region.to_string());
+        }
+        self
+    }
+
+    /// Attach the resource a provider call acted on.
+    pub fn for_resource(mut self, resource_id: &str) -> Self {
+        if let AppError::Provider(e) = &mut self {
+            e.resource_id = Some(resource_id.to_string());
+        }
+        self
+    }
+
+    /// Whether the provider reported that the resource doesn't exist, e.g.
+    /// `InvalidInstanceID.NotFound`, `NoSuchBucket` or `ResourceNotFound`.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            AppError::ResourceNotFound(_) => true,
+            AppError::Provider(e) => e.code.as_deref().is_some_and(|code| {
+                code.to_lowercase().contains("notfound") || code.starts_with("NoSuch")
+            }),
+            _ => false,
+        }
+    }
+
+    /// Whether the same call could succeed if tried again later: throttling,
+    /// timeouts, dropped connections and transient server errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Provider(e) => e.retryable,
+            AppError::RateLimitExceeded(_) => true,
+            AppError::HttpError(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(|status| {
+                        status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// Process exit status for this error, so scripts can tell an expired
+    /// login from a partial result without parsing the message.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::InvalidParameters(_) => EXIT_USAGE,
+            AppError::AuthenticationError(_) | AppError::PermissionDenied(_) => EXIT_AUTH,
+            AppError::Provider(e)
+                if e.code.as_deref().is_some_and(|code| {
+                    is_expired(e.provider, code) || is_denied(e.provider, code)
+                }) =>
+            {
+                EXIT_AUTH
+            }
+            AppError::BatchFailed(_) | AppError::PartialResults(_) => EXIT_PARTIAL,
+            _ if self.is_not_found() => EXIT_NOT_FOUND,
+            AppError::UserCancelled => EXIT_CANCELLED,
+            AppError::Interrupted => EXIT_INTERRUPTED,
+            _ => EXIT_FAILURE,
+        }
+    }
+
+    /// The error as JSON for `--json-errors`, with the provider details and
+    /// hint a script would otherwise have to scrape from the message.
+    pub fn to_json(&self, profile: &str) -> serde_json::Value {
+        let kind = match self.exit_code() {
+            EXIT_USAGE => "usage",
+            EXIT_AUTH => "authentication",
+            EXIT_PARTIAL => "partial_results",
+            EXIT_NOT_FOUND => "not_found",
+            EXIT_CANCELLED => "cancelled",
+            EXIT_INTERRUPTED => "interrupted",
+            _ => "error",
+        };
+        let mut json = serde_json::json!({
+            "error": kind,
+            "exit_code": self.exit_code(),
+            "message": self.to_string(),
+            "retryable": self.is_retryable(),
+        });
+        if let AppError::Provider(e) = self {
+            json["provider"] = e.provider.into();
+            json["operation"] = e.operation.clone().into();
+            json["code"] = e.code.clone().into();
+            json["region"] = e.region.clone().into();
+            json["resource_id"] = e.resource_id.clone().into();
+        }
+        if let Some(hint) = self.hint(profile) {
+            json["hint"] = hint.into();
+        }
+        json
+    }
+
+    /// What to do about a provider error with a well-known fix: sign in
+    /// again when credentials expired, grant the permission a call was
+    /// denied, or slow down when throttled. `profile` is the actlog profile
+    /// the command ran with.
+    pub fn hint(&self, profile: &str) -> Option<String> {
+        let AppError::Provider(e) = self else {
+            return None;
+        };
+        let code = e.code.as_deref()?;
+
+        if is_throttled(code) {
+            return Some(
+                "The provider is throttling requests; lower --parallelism or set [executor.rate_limits] in actlog.toml"
+                    .to_string(),
+            );
+        }
+
+        if is_expired(e.provider, code) {
+            return Some(format!(
+                "The credentials of profile {} are expired or invalid; run `actlog authenticate {} --profile {} --force`",
+                profile,
+                e.provider.to_lowercase(),
+                profile
+            ));
+        }
+        if !is_denied(e.provider, code) {
+            return None;
+        }
+
+        match e.provider {
+            "AWS" => Some(format!(
+                "Profile {} lacks the IAM permission `{}`; add it to the policy of the user or role it signs in as",
+                profile,
+                iam_action(&e.operation)
+            )),
+            "GCP" => Some(format!(
+                "Profile {} may not call {}; grant its account a role with that permission in the project's IAM settings",
+                profile, e.operation
+            )),
+            "Azure" => Some(format!(
+                "Profile {} may not call {}; assign its service principal a role that allows it on the subscription",
+                profile, e.operation
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `provider`'s error `code` means the credentials are missing,
+/// expired or revoked.
+fn is_expired(provider: &str, code: &str) -> bool {
+    match provider {
+        "AWS" => EXPIRED_AWS_CODES.contains(&code),
+        "GCP" => matches!(code, "UNAUTHENTICATED" | "authError"),
+        "Azure" => {
+            matches!(code, "ExpiredAuthenticationToken" | "AuthenticationFailed")
+                || code.starts_with("InvalidAuthenticationToken")
+        }
+        _ => false,
+    }
+}
+
+/// Whether `provider`'s error `code` means the credentials work but may not
+/// make the call.
+fn is_denied(provider: &str, code: &str) -> bool {
+    match provider {
+        "AWS" => DENIED_AWS_CODES.contains(&code),
+        "GCP" => matches!(
+            code,
+            "PERMISSION_DENIED" | "forbidden" | "insufficientPermissions"
+        ),
+        "Azure" => matches!(code, "AuthorizationFailed" | "LinkedAuthorizationFailed"),
+        _ => false,
+    }
+}
+
+/// Whether a provider error code means the caller is sending too many requests.
+fn is_throttled(code: &str) -> bool {
+    code.starts_with("Throttl")
+        || code.starts_with("RequestThrottled")
+        || matches!(
+            code,
+            "RequestLimitExceeded"
+                | "TooManyRequestsException"
+                | "SlowDown"
+                | "TooManyRequests"
+                | "RESOURCE_EXHAUSTED"
+                | "rateLimitExceeded"
+                | "userRateLimitExceeded"
+        )
+}
+
+/// The IAM action for an operation named like "elbv2:DeleteLoadBalancer".
+fn iam_action(operation: &str) -> String {
+    match operation.split_once(':') {
+        Some(("elbv2", action)) => format!("elasticloadbalancing:{}", action),
+        Some(("sso-oidc", action)) => format!("sso-oauth:{}", action),
+        _ => operation.to_string(),
+    }
+}
+
+/// The `error` object of a REST API error response, or `null`.
+fn error_body(body: &str) -> serde_json::Value {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .map(|v| v["error"].clone())
+        .unwrap_or_default()
+}
+
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
         AppError::Unknown(err.to_string())
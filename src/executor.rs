@@ -0,0 +1,331 @@
+use crate::cli::CloudProvider;
+use crate::error::AppError;
+use crate::regions;
+use aws_config::SdkConfig;
+use colored::*;
+use futures::FutureExt;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, Semaphore};
+use tokio::task::JoinSet;
+
+/// Regions or accounts scanned at once when nothing is configured
+const DEFAULT_PARALLELISM: usize = 8;
+
+/// Requests per second allowed per service when nothing is configured.
+/// Kept under the documented AWS steady-state limits so parallel scans
+/// don't trip throttling.
+const DEFAULT_RATE_LIMITS: &[(&str, f64)] = &[
+    ("ec2", 20.0),
+    ("cloudwatch", 10.0),
+    ("s3", 50.0),
+    ("costexplorer", 5.0),
+    ("autoscaling", 10.0),
+    ("cloudfront", 5.0),
+    ("cloudtrail", 2.0),
+    ("iam", 10.0),
+    ("lambda", 10.0),
+    ("rds", 10.0),
+    ("route53", 5.0),
+    ("ssm", 5.0),
+    ("sqs", 10.0),
+    ("pricing", 5.0),
+    ("compute", 20.0),
+    ("arm", 10.0),
+];
+
+/// Price per billed unit in USD for services that bill API calls: Cost
+/// Explorer charges $0.01 per request, CloudWatch $0.01 per 1,000 metrics
+/// requested
+const REQUEST_PRICES: &[(&str, f64)] = &[("costexplorer", 0.01), ("cloudwatch", 0.00001)];
+
+struct ExecutorConfig {
+    parallelism: usize,
+    rate_limits: HashMap<String, f64>,
+}
+
+static CONFIG: OnceLock<ExecutorConfig> = OnceLock::new();
+
+/// Earliest instant the next request to each service may start.
+static NEXT_SLOT: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// Regions, accounts and providers currently being worked on, e.g. "region eu-west-1".
+static IN_FLIGHT: OnceLock<Mutex<BTreeSet<String>>> = OnceLock::new();
+
+/// Service of the most recent rate-limited request.
+static LAST_SERVICE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Requests made to each service during this run.
+static CALL_COUNTS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// Billed units beyond one per request, for requests priced by what they
+/// ask for rather than per call.
+static EXTRA_BILLED_UNITS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// Most requests the run may make, from `--max-api-calls`.
+static CALL_BUDGET: OnceLock<u64> = OnceLock::new();
+
+/// Woken when a request would go over the call budget.
+static BUDGET_EXCEEDED: Notify = Notify::const_new();
+
+/// Set the shared parallelism and per-service rate limits (requests/second).
+/// Configured limits override the defaults service by service.
+pub fn configure(parallelism: Option<usize>, rate_limits: &BTreeMap<String, f64>) {
+    let mut limits: HashMap<String, f64> = DEFAULT_RATE_LIMITS
+        .iter()
+        .map(|(service, rate)| (service.to_string(), *rate))
+        .collect();
+    limits.extend(rate_limits.iter().map(|(k, v)| (k.to_lowercase(), *v)));
+
+    let _ = CONFIG.set(ExecutorConfig {
+        parallelism: parallelism.unwrap_or(DEFAULT_PARALLELISM).max(1),
+        rate_limits: limits,
+    });
+}
+
+/// Stop the run once it would make more than `max_calls` requests.
+pub fn set_call_budget(max_calls: Option<u64>) {
+    if let Some(max_calls) = max_calls {
+        let _ = CALL_BUDGET.set(max_calls);
+    }
+}
+
+/// Resolves once a request would go over the call budget; never resolves
+/// without one.
+pub async fn call_budget_exceeded() {
+    BUDGET_EXCEEDED.notified().await;
+}
+
+/// Record that a request to `service` is billed as `units` units, e.g. a
+/// GetMetricData call as one per metric it queries.
+pub fn bill_units(service: &str, units: u64) {
+    if let Ok(mut extra) = EXTRA_BILLED_UNITS.lock() {
+        *extra.entry(service.to_string()).or_default() += units.saturating_sub(1);
+    }
+}
+
+/// Requests made so far per service, with their estimated cost in USD.
+pub fn api_calls() -> Vec<(String, u64, f64)> {
+    let counts = CALL_COUNTS.lock().map(|c| c.clone()).unwrap_or_default();
+    let extra = EXTRA_BILLED_UNITS
+        .lock()
+        .map(|e| e.clone())
+        .unwrap_or_default();
+    counts
+        .into_iter()
+        .map(|(service, count)| {
+            let price = REQUEST_PRICES
+                .iter()
+                .find(|(s, _)| *s == service)
+                .map_or(0.0, |(_, price)| *price);
+            let units = count + extra.get(&service).copied().unwrap_or(0);
+            let cost = units as f64 * price;
+            (service, count, cost)
+        })
+        .collect()
+}
+
+/// Marks a unit of work as in flight until dropped.
+struct InFlight(String);
+
+impl InFlight {
+    fn start(label: String) -> Self {
+        if let Ok(mut set) = IN_FLIGHT.get_or_init(|| Mutex::new(BTreeSet::new())).lock() {
+            set.insert(label.clone());
+        }
+        InFlight(label)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if let Some(Ok(mut set)) = IN_FLIGHT.get().map(|s| s.lock()) {
+            set.remove(&self.0);
+        }
+    }
+}
+
+/// Labels of the regions, accounts and providers still being worked on.
+pub fn in_flight() -> Vec<String> {
+    IN_FLIGHT
+        .get()
+        .and_then(|set| set.lock().ok().map(|s| s.iter().cloned().collect()))
+        .unwrap_or_default()
+}
+
+pub fn last_service() -> Option<String> {
+    LAST_SERVICE.lock().ok().and_then(|s| s.clone())
+}
+
+pub fn parallelism() -> usize {
+    CONFIG
+        .get()
+        .map(|c| c.parallelism)
+        .unwrap_or(DEFAULT_PARALLELISM)
+}
+
+/// Wait until another request to `service` fits its rate limit. Requests
+/// past the call budget never proceed; the run is stopped instead.
+pub async fn throttle(service: &str) {
+    if let Ok(mut last) = LAST_SERVICE.lock() {
+        *last = Some(service.to_string());
+    }
+
+    let total: u64 = match CALL_COUNTS.lock() {
+        Ok(mut counts) => {
+            *counts.entry(service.to_string()).or_default() += 1;
+            counts.values().sum()
+        }
+        Err(_) => 0,
+    };
+    if CALL_BUDGET.get().is_some_and(|budget| total > *budget) {
+        BUDGET_EXCEEDED.notify_one();
+        std::future::pending::<()>().await;
+    }
+
+    let rate = match CONFIG.get() {
+        Some(config) => config.rate_limits.get(service).copied(),
+        None => DEFAULT_RATE_LIMITS
+            .iter()
+            .find(|(s, _)| *s == service)
+            .map(|(_, rate)| *rate),
+    };
+    let Some(rate) = rate.filter(|r| *r > 0.0) else {
+        return;
+    };
+
+    let interval = Duration::from_secs_f64(1.0 / rate);
+    let wait = {
+        let mut slots = NEXT_SLOT
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .expect("rate limiter lock poisoned");
+        let now = Instant::now();
+        let slot = slots.entry(service.to_string()).or_insert(now);
+        let start = (*slot).max(now);
+        *slot = start + interval;
+        start - now
+    };
+
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Run `op` against every region with bounded parallelism, printing each
+/// region as it completes. Failed regions are reported and skipped; results
+/// come back in the order of `regions`.
+pub async fn for_each_region<T, F, Fut>(
+    base: &SdkConfig,
+    regions: &[String],
+    op: F,
+) -> Vec<(String, T)>
+where
+    T: Send + 'static,
+    F: Fn(String, SdkConfig) -> Fut,
+    Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+{
+    let jobs = regions
+        .iter()
+        .map(|region| {
+            let config = regions::regional_config(base, region);
+            (region.clone(), op(region.clone(), config))
+        })
+        .collect();
+
+    let mut results = run_bounded(jobs, "region").await;
+    results.sort_by_key(|(region, _)| regions.iter().position(|r| r == region));
+    results
+}
+
+/// Run keyed futures at most [`parallelism`] at a time, streaming progress.
+pub async fn run_bounded<T, Fut>(jobs: Vec<(String, Fut)>, noun: &str) -> Vec<(String, T)>
+where
+    T: Send + 'static,
+    Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+{
+    let total = jobs.len();
+    let semaphore = Arc::new(Semaphore::new(parallelism()));
+
+    let mut tasks = JoinSet::new();
+    for (key, future) in jobs {
+        let semaphore = semaphore.clone();
+        let label = format!("{} {}", noun, key);
+        tasks.spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("executor semaphore closed");
+            let _in_flight = InFlight::start(label);
+            (key, future.await)
+        });
+    }
+
+    let mut results = Vec::new();
+    let mut done = 0;
+    while let Some(joined) = tasks.join_next().await {
+        done += 1;
+        match joined {
+            Ok((key, Ok(value))) => {
+                tracing::info!("   ✓ {} {} ({}/{})", noun, key.green(), done, total);
+                results.push((key, value));
+            }
+            Ok((key, Err(e))) => {
+                tracing::warn!(
+                    "⚠️  Skipping {} {} ({}/{}): {}",
+                    noun,
+                    key.yellow(),
+                    done,
+                    total,
+                    e
+                );
+            }
+            Err(e) => tracing::warn!("⚠️  {} task failed: {}", noun, e),
+        }
+    }
+
+    results
+}
+
+/// Run `op` for every provider at once. Each provider keeps its own result,
+/// so a slow or failing provider neither serializes nor aborts the others.
+/// Results come back in the order of `providers`.
+pub async fn for_each_provider<T, F, Fut>(
+    providers: &[CloudProvider],
+    op: F,
+) -> Vec<(CloudProvider, Result<T, AppError>)>
+where
+    T: Send + 'static,
+    F: Fn(CloudProvider) -> Fut,
+    Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+{
+    let mut tasks = JoinSet::new();
+    for (index, provider) in providers.iter().enumerate() {
+        let future = AssertUnwindSafe(op(provider.clone())).catch_unwind();
+        let provider = provider.clone();
+        tasks.spawn(async move {
+            let _in_flight = InFlight::start(format!("provider {}", provider));
+            let result = future.await.unwrap_or_else(|_| {
+                Err(AppError::ApiError(format!("{} query panicked", provider)))
+            });
+            (index, provider, result)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => tracing::warn!("⚠️  provider task failed: {}", e),
+        }
+    }
+
+    results.sort_by_key(|(index, _, _)| *index);
+    results
+        .into_iter()
+        .map(|(_, provider, result)| (provider, result))
+        .collect()
+}
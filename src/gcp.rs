@@ -0,0 +1,279 @@
+use crate::error::AppError;
+use crate::executor;
+use crate::retry;
+use crate::session;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use tokio::sync::OnceCell;
+
+pub const COMPUTE_API: &str = "https://compute.googleapis.com/compute/v1";
+pub const MONITORING_API: &str = "https://monitoring.googleapis.com/v3";
+pub const RESOURCE_MANAGER_API: &str = "https://cloudresourcemanager.googleapis.com/v1";
+
+/// Scope of tokens minted from a service account key; IAM roles decide what
+/// they can actually do
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const JWT_BEARER_GRANT: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+/// Google caps self-signed assertions at an hour
+const ASSERTION_LIFETIME_MINUTES: i64 = 60;
+
+/// The gcloud CLI, which Windows installs as a batch script that
+/// `Command` only finds by its full name
+const GCLOUD: &str = if cfg!(windows) {
+    "gcloud.cmd"
+} else {
+    "gcloud"
+};
+
+static TOKEN: OnceCell<String> = OnceCell::const_new();
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// The fields of a service account key file that minting a token needs.
+#[derive(Debug, Deserialize)]
+pub struct ServiceAccountKey {
+    #[serde(rename = "type")]
+    pub key_type: String,
+    pub project_id: Option<String>,
+    pub private_key_id: String,
+    pub private_key: String,
+    pub client_email: String,
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    DEFAULT_TOKEN_URI.to_string()
+}
+
+/// An OAuth access token and when Google stops accepting it.
+pub struct AccessToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// `GOOGLE_CLOUD_PROJECT`, or else the project of the service account key
+/// in `GOOGLE_APPLICATION_CREDENTIALS`.
+pub fn project_id() -> Result<String, AppError> {
+    if let Ok(project_id) = std::env::var("GOOGLE_CLOUD_PROJECT") {
+        return Ok(project_id);
+    }
+    std::env::var("GOOGLE_APPLICATION_CREDENTIALS")
+        .ok()
+        .and_then(|path| load_service_account(&path).ok())
+        .and_then(|key| key.project_id)
+        .ok_or_else(|| AppError::ConfigurationError("GOOGLE_CLOUD_PROJECT not set".to_string()))
+}
+
+/// Read and check the service account key file at `path`.
+pub fn load_service_account(path: &str) -> Result<ServiceAccountKey, AppError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        AppError::AuthenticationError(format!(
+            "Could not read service account key {}: {}",
+            path, e
+        ))
+    })?;
+    let key: ServiceAccountKey = serde_json::from_str(&content).map_err(|e| {
+        AppError::AuthenticationError(format!("{} is not a service account key file: {}", path, e))
+    })?;
+
+    if key.key_type != "service_account" {
+        return Err(AppError::AuthenticationError(format!(
+            "{} holds {} credentials, not a service account key",
+            path, key.key_type
+        )));
+    }
+    Ok(key)
+}
+
+/// Exchange a JWT signed with the private key of `key` for an access token,
+/// the OAuth 2.0 JWT bearer flow.
+pub async fn mint_token(key: &ServiceAccountKey) -> Result<AccessToken, AppError> {
+    let now = Utc::now();
+    let claims = json!({
+        "iss": key.client_email,
+        "scope": CLOUD_PLATFORM_SCOPE,
+        "aud": key.token_uri,
+        "iat": now.timestamp(),
+        "exp": (now + Duration::minutes(ASSERTION_LIFETIME_MINUTES)).timestamp(),
+    });
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(key.private_key_id.clone());
+
+    let assertion = EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+        .and_then(|signing_key| jsonwebtoken::encode(&header, &claims, &signing_key))
+        .map_err(|e| {
+            AppError::AuthenticationError(format!(
+                "The private key of {} is unusable: {}",
+                key.client_email, e
+            ))
+        })?;
+
+    let client = CLIENT.get_or_init(reqwest::Client::new);
+    executor::throttle("oauth2").await;
+    let response = retry::send_http("oauth2.token", || {
+        client.post(&key.token_uri).form(&[
+            ("grant_type", JWT_BEARER_GRANT),
+            ("assertion", assertion.as_str()),
+        ])
+    })
+    .await?;
+
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        let error = body["error"].as_str().unwrap_or("unknown error");
+        let hint = match error {
+            "invalid_grant" => {
+                "; the key may be deleted or disabled, or this machine's clock is off"
+            }
+            "invalid_scope" => "; the key's organization may restrict OAuth scopes",
+            _ => "",
+        };
+        return Err(AppError::AuthenticationError(format!(
+            "Google refused a token for {}: {} ({}){}",
+            key.client_email,
+            error,
+            body["error_description"]
+                .as_str()
+                .unwrap_or("no description"),
+            hint
+        )));
+    }
+
+    let token = body["access_token"].as_str().ok_or_else(|| {
+        AppError::AuthenticationError("Google's token response has no access_token".to_string())
+    })?;
+    Ok(AccessToken {
+        token: token.to_string(),
+        expires_at: now + Duration::seconds(body["expires_in"].as_i64().unwrap_or(3600)),
+    })
+}
+
+/// Mint a token from `key` and use it for the rest of the command.
+pub async fn service_account_token(key: &ServiceAccountKey) -> Result<AccessToken, AppError> {
+    let minted = mint_token(key).await?;
+    let _ = TOKEN.set(minted.token.clone());
+    Ok(minted)
+}
+
+/// OAuth token for the Google APIs: `GOOGLE_OAUTH_ACCESS_TOKEN` when set,
+/// then one minted from the service account key in
+/// `GOOGLE_APPLICATION_CREDENTIALS`, otherwise the application default
+/// credentials from the gcloud CLI. Fetched once per command.
+async fn access_token() -> Result<String, AppError> {
+    TOKEN
+        .get_or_try_init(|| async {
+            if let Ok(token) = std::env::var("GOOGLE_OAUTH_ACCESS_TOKEN") {
+                return Ok(token);
+            }
+            if session::replaying() {
+                return Ok("REPLAY".to_string());
+            }
+            if let Ok(path) = std::env::var("GOOGLE_APPLICATION_CREDENTIALS") {
+                let key = load_service_account(&path)?;
+                return mint_token(&key).await.map(|minted| minted.token);
+            }
+
+            let output = tokio::process::Command::new(GCLOUD)
+                .args(["auth", "application-default", "print-access-token"])
+                .output()
+                .await
+                .map_err(|e| {
+                    AppError::AuthenticationError(format!("Could not run gcloud: {}", e))
+                })?;
+            if !output.status.success() {
+                return Err(AppError::AuthenticationError(format!(
+                    "gcloud could not provide an access token: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+        .await
+        .cloned()
+}
+
+/// Call a Google API `url` with an optional JSON `body`, returning the JSON
+/// response. Non-success statuses become provider errors carrying the API's
+/// error reason.
+async fn request(
+    method: Method,
+    operation: &str,
+    url: &str,
+    body: Option<&Value>,
+) -> Result<Value, AppError> {
+    let token = access_token().await?;
+    let client = CLIENT.get_or_init(reqwest::Client::new);
+
+    executor::throttle("compute").await;
+    let response = retry::send_http(operation, || {
+        let request = client.request(method.clone(), url).bearer_auth(&token);
+        match body {
+            Some(body) => request.json(body),
+            None => request,
+        }
+    })
+    .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(AppError::gcp(operation, status, &body));
+    }
+
+    Ok(response.json().await?)
+}
+
+/// GET a single Google API resource or response.
+pub async fn get(operation: &str, url: &str) -> Result<Value, AppError> {
+    request(Method::GET, operation, url, None).await
+}
+
+/// POST a JSON `body`, for query-style calls such as Logging `entries:list`.
+pub async fn post(operation: &str, url: &str, body: &Value) -> Result<Value, AppError> {
+    request(Method::POST, operation, url, Some(body)).await
+}
+
+/// PATCH the resource at `url` with the fields in `body`.
+pub async fn patch(operation: &str, url: &str, body: &Value) -> Result<Value, AppError> {
+    request(Method::PATCH, operation, url, Some(body)).await
+}
+
+/// Every page of a Compute API list call, following `nextPageToken`.
+pub async fn list_pages(operation: &str, url: &str) -> Result<Vec<Value>, AppError> {
+    let mut pages = Vec::new();
+    let mut page_token: Option<String> = None;
+
+    loop {
+        let page_url = match &page_token {
+            Some(token) => format!("{}?pageToken={}", url, token),
+            None => url.to_string(),
+        };
+        let page = request(Method::GET, operation, &page_url, None).await?;
+        page_token = page["nextPageToken"].as_str().map(str::to_string);
+        pages.push(page);
+
+        if page_token.is_none() {
+            return Ok(pages);
+        }
+    }
+}
+
+/// Delete the resource at `url`. Compute deletes are asynchronous; success
+/// means the operation was accepted.
+pub async fn delete(operation: &str, url: &str) -> Result<(), AppError> {
+    request(Method::DELETE, operation, url, None)
+        .await
+        .map(|_| ())
+}
+
+/// Last path segment of a resource URL such as a zone or region link.
+pub fn short_name(link: &str) -> &str {
+    link.rsplit('/').next().unwrap_or(link)
+}
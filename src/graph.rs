@@ -0,0 +1,596 @@
+use crate::azure;
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::gcp;
+use aws_config::SdkConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub kind: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Relation {
+    /// Instance or load balancer is a member of a security group
+    UsesSecurityGroup,
+    /// Security group or load balancer lives in a VPC
+    InVpc,
+    /// Load balancer forwards traffic to a target
+    RoutesTo,
+    /// Volume is attached to an instance
+    AttachedTo,
+}
+
+impl Relation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Relation::UsesSecurityGroup => "uses",
+            Relation::InVpc => "in",
+            Relation::RoutesTo => "routes to",
+            Relation::AttachedTo => "attached to",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub relation: Relation,
+}
+
+impl GraphEdge {
+    /// The (first, then) pair in which the two ends can be deleted safely.
+    fn deletion_pair(&self) -> (&str, &str) {
+        match self.relation {
+            // An attached volume can't be deleted until its instance is gone
+            Relation::AttachedTo => (&self.to, &self.from),
+            _ => (&self.from, &self.to),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResourceGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl ResourceGraph {
+    pub fn add_node(&mut self, id: &str, kind: &str, label: &str) {
+        if self.nodes.iter().any(|n| n.id == id) {
+            return;
+        }
+        self.nodes.push(GraphNode {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            label: label.to_string(),
+        });
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str, relation: Relation) {
+        self.edges.push(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation,
+        });
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph resources {\n    rankdir=LR;\n");
+        for node in &self.nodes {
+            out.push_str(&format!(
+                "    \"{}\" [label=\"{}\\n{}\"];\n",
+                escape_dot(&node.id),
+                escape_dot(&node.kind),
+                escape_dot(&node.label)
+            ));
+        }
+        for edge in &self.edges {
+            out.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                escape_dot(&edge.from),
+                escape_dot(&edge.to),
+                edge.relation.label()
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        // Mermaid node ids can't contain ARN characters, so alias them by index
+        let aliases: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id.as_str(), format!("n{}", i)))
+            .collect();
+
+        let mut out = String::from("graph LR\n");
+        for node in &self.nodes {
+            out.push_str(&format!(
+                "    {}[\"{}<br/>{}\"]\n",
+                aliases[node.id.as_str()],
+                escape_mermaid(&node.kind),
+                escape_mermaid(&node.label)
+            ));
+        }
+        for edge in &self.edges {
+            if let (Some(from), Some(to)) = (
+                aliases.get(edge.from.as_str()),
+                aliases.get(edge.to.as_str()),
+            ) {
+                out.push_str(&format!(
+                    "    {} -->|{}| {}\n",
+                    from,
+                    edge.relation.label(),
+                    to
+                ));
+            }
+        }
+        out
+    }
+
+    /// Order `ids` so that every resource is deleted before the resources it
+    /// depends on. Ids unknown to the graph keep their relative order.
+    pub fn deletion_order(&self, ids: &[String]) -> Vec<String> {
+        let wanted: HashSet<&str> = ids.iter().map(|id| id.as_str()).collect();
+
+        let mut indegree: HashMap<&str, usize> = ids.iter().map(|id| (id.as_str(), 0)).collect();
+        let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &self.edges {
+            let (first, then) = edge.deletion_pair();
+            if wanted.contains(first) && wanted.contains(then) {
+                successors.entry(first).or_default().push(then);
+                *indegree.entry(then).or_insert(0) += 1;
+            }
+        }
+
+        let mut queue: VecDeque<&str> = ids
+            .iter()
+            .map(|id| id.as_str())
+            .filter(|id| indegree[id] == 0)
+            .collect();
+        let mut ordered = Vec::with_capacity(ids.len());
+        while let Some(id) = queue.pop_front() {
+            ordered.push(id.to_string());
+            for next in successors.get(id).into_iter().flatten() {
+                let degree = indegree.get_mut(next).expect("successor is tracked");
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        // Cycles shouldn't happen, but never drop a resource because of one
+        for id in ids {
+            if !ordered.contains(id) {
+                ordered.push(id.clone());
+            }
+        }
+
+        ordered
+    }
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
+
+/// Build the dependency graph for the EC2, EBS and ELBv2 resources visible to
+/// `config`.
+pub async fn build_aws_graph(config: &SdkConfig) -> Result<ResourceGraph, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    let elb_client = clients::get::<aws_sdk_elasticloadbalancingv2::Client>(config).await;
+    let mut graph = ResourceGraph::default();
+
+    executor::throttle("ec2").await;
+    let vpcs = ec2_client
+        .describe_vpcs()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeVpcs", e))?;
+    for vpc in vpcs.vpcs() {
+        if let Some(vpc_id) = vpc.vpc_id() {
+            let label = vpc.cidr_block().unwrap_or(vpc_id);
+            graph.add_node(vpc_id, "VPC", label);
+        }
+    }
+
+    executor::throttle("ec2").await;
+    let groups = ec2_client
+        .describe_security_groups()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeSecurityGroups", e))?;
+    for group in groups.security_groups() {
+        if let Some(group_id) = group.group_id() {
+            graph.add_node(
+                group_id,
+                "Security Group",
+                group.group_name().unwrap_or(group_id),
+            );
+            if let Some(vpc_id) = group.vpc_id() {
+                graph.add_edge(group_id, vpc_id, Relation::InVpc);
+            }
+        }
+    }
+
+    executor::throttle("ec2").await;
+    let instances = ec2_client
+        .describe_instances()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeInstances", e))?;
+    for reservation in instances.reservations() {
+        for instance in reservation.instances() {
+            if let Some(instance_id) = instance.instance_id() {
+                let name = instance
+                    .tags()
+                    .iter()
+                    .find(|t| t.key() == Some("Name"))
+                    .and_then(|t| t.value())
+                    .unwrap_or(instance_id);
+                graph.add_node(instance_id, "EC2 Instance", name);
+                for group in instance.security_groups() {
+                    if let Some(group_id) = group.group_id() {
+                        graph.add_edge(instance_id, group_id, Relation::UsesSecurityGroup);
+                    }
+                }
+            }
+        }
+    }
+
+    executor::throttle("ec2").await;
+    let volumes = ec2_client
+        .describe_volumes()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeVolumes", e))?;
+    for volume in volumes.volumes() {
+        if let Some(volume_id) = volume.volume_id() {
+            let label = format!("{} GiB", volume.size().unwrap_or(0));
+            graph.add_node(volume_id, "EBS Volume", &label);
+            for attachment in volume.attachments() {
+                if let Some(instance_id) = attachment.instance_id() {
+                    graph.add_edge(volume_id, instance_id, Relation::AttachedTo);
+                }
+            }
+        }
+    }
+
+    executor::throttle("elasticloadbalancing").await;
+    let load_balancers = elb_client
+        .describe_load_balancers()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("elbv2:DescribeLoadBalancers", e))?;
+    for load_balancer in load_balancers.load_balancers() {
+        let Some(lb_arn) = load_balancer.load_balancer_arn() else {
+            continue;
+        };
+        graph.add_node(
+            lb_arn,
+            "Load Balancer",
+            load_balancer.load_balancer_name().unwrap_or(lb_arn),
+        );
+        if let Some(vpc_id) = load_balancer.vpc_id() {
+            graph.add_edge(lb_arn, vpc_id, Relation::InVpc);
+        }
+        for group_id in load_balancer.security_groups() {
+            graph.add_edge(lb_arn, group_id, Relation::UsesSecurityGroup);
+        }
+
+        executor::throttle("elasticloadbalancing").await;
+        let target_groups = elb_client
+            .describe_target_groups()
+            .load_balancer_arn(lb_arn)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("elbv2:DescribeTargetGroups", e))?;
+        for target_group in target_groups.target_groups() {
+            let Some(tg_arn) = target_group.target_group_arn() else {
+                continue;
+            };
+            executor::throttle("elasticloadbalancing").await;
+            let health = elb_client
+                .describe_target_health()
+                .target_group_arn(tg_arn)
+                .send()
+                .await
+                .map_err(|e| AppError::aws("elbv2:DescribeTargetHealth", e))?;
+            for description in health.target_health_descriptions() {
+                if let Some(target_id) = description.target().map(|t| t.id()) {
+                    graph.add_edge(lb_arn, target_id, Relation::RoutesTo);
+                }
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Build the dependency graph for the networks, firewall rules, instances
+/// and disks of the configured GCP project. A firewall rule applies to an
+/// instance in its network when it has no target tags or shares one.
+pub async fn build_gcp_graph() -> Result<ResourceGraph, AppError> {
+    let project_id = gcp::project_id()?;
+    let mut graph = ResourceGraph::default();
+
+    let url = format!(
+        "{}/projects/{}/global/networks",
+        gcp::COMPUTE_API,
+        project_id
+    );
+    for network in items(&gcp::list_pages("compute.networks.list", &url).await?) {
+        if let Some(link) = network["selfLink"].as_str() {
+            graph.add_node(link, "VPC Network", label_of(network, link));
+        }
+    }
+
+    let url = format!(
+        "{}/projects/{}/global/firewalls",
+        gcp::COMPUTE_API,
+        project_id
+    );
+    let firewalls = gcp::list_pages("compute.firewalls.list", &url).await?;
+    let firewalls = items(&firewalls);
+    for firewall in &firewalls {
+        let (Some(link), Some(network)) =
+            (firewall["selfLink"].as_str(), firewall["network"].as_str())
+        else {
+            continue;
+        };
+        graph.add_node(link, "Firewall Rule", label_of(firewall, link));
+        graph.add_edge(link, network, Relation::InVpc);
+    }
+
+    let url = format!(
+        "{}/projects/{}/aggregated/instances",
+        gcp::COMPUTE_API,
+        project_id
+    );
+    let pages = gcp::list_pages("compute.instances.aggregatedList", &url).await?;
+    for instance in aggregated(&pages, "instances") {
+        let Some(link) = instance["selfLink"].as_str() else {
+            continue;
+        };
+        graph.add_node(link, "Compute Instance", label_of(instance, link));
+        let tags = strings(&instance["tags"]["items"]);
+
+        for interface in instance["networkInterfaces"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let Some(network) = interface["network"].as_str() else {
+                continue;
+            };
+            graph.add_edge(link, network, Relation::InVpc);
+            for firewall in &firewalls {
+                let targets = strings(&firewall["targetTags"]);
+                let applies = firewall["network"].as_str() == Some(network)
+                    && (targets.is_empty() || targets.iter().any(|t| tags.contains(t)));
+                if let Some(rule) = firewall["selfLink"].as_str().filter(|_| applies) {
+                    graph.add_edge(link, rule, Relation::UsesSecurityGroup);
+                }
+            }
+        }
+    }
+
+    let url = format!(
+        "{}/projects/{}/aggregated/disks",
+        gcp::COMPUTE_API,
+        project_id
+    );
+    let pages = gcp::list_pages("compute.disks.aggregatedList", &url).await?;
+    for disk in aggregated(&pages, "disks") {
+        let Some(link) = disk["selfLink"].as_str() else {
+            continue;
+        };
+        graph.add_node(link, "Persistent Disk", label_of(disk, link));
+        for user in strings(&disk["users"]) {
+            graph.add_edge(link, user, Relation::AttachedTo);
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Build the dependency graph for the virtual networks, network security
+/// groups, VMs and managed disks of the configured Azure subscription. A VM
+/// uses the security groups of its NICs and of the subnets they are in.
+pub async fn build_azure_graph() -> Result<ResourceGraph, AppError> {
+    let subscription_id = azure::subscription_id()?;
+    let mut graph = ResourceGraph::default();
+    let list_url = |provider: &str, api_version: &str| {
+        format!(
+            "{}/subscriptions/{}/providers/{}?api-version={}",
+            azure::MANAGEMENT_API,
+            subscription_id,
+            provider,
+            api_version
+        )
+    };
+
+    let networks = azure::list(
+        "Microsoft.Network/virtualNetworks/read",
+        &list_url(
+            "Microsoft.Network/virtualNetworks",
+            azure::NETWORK_API_VERSION,
+        ),
+    )
+    .await?;
+    // Subnet -> its network security group
+    let mut subnet_groups: HashMap<String, String> = HashMap::new();
+    for network in &networks {
+        let Some(id) = arm_id(&network["id"]) else {
+            continue;
+        };
+        graph.add_node(&id, "Virtual Network", label_of(network, &id));
+        for subnet in network["properties"]["subnets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            if let (Some(subnet_id), Some(group_id)) = (
+                arm_id(&subnet["id"]),
+                arm_id(&subnet["properties"]["networkSecurityGroup"]["id"]),
+            ) {
+                subnet_groups.insert(subnet_id, group_id);
+            }
+        }
+    }
+
+    let groups = azure::list(
+        "Microsoft.Network/networkSecurityGroups/read",
+        &list_url(
+            "Microsoft.Network/networkSecurityGroups",
+            azure::NETWORK_API_VERSION,
+        ),
+    )
+    .await?;
+    for group in &groups {
+        let Some(id) = arm_id(&group["id"]) else {
+            continue;
+        };
+        graph.add_node(&id, "Network Security Group", label_of(group, &id));
+        for subnet in group["properties"]["subnets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            if let Some(network_id) = arm_id(&subnet["id"]).as_deref().and_then(network_of) {
+                graph.add_edge(&id, network_id, Relation::InVpc);
+            }
+        }
+    }
+
+    let machines = azure::list(
+        "Microsoft.Compute/virtualMachines/read",
+        &list_url(
+            "Microsoft.Compute/virtualMachines",
+            azure::COMPUTE_API_VERSION,
+        ),
+    )
+    .await?;
+    for machine in &machines {
+        let Some(id) = arm_id(&machine["id"]) else {
+            continue;
+        };
+        graph.add_node(&id, "Virtual Machine", label_of(machine, &id));
+    }
+
+    let interfaces = azure::list(
+        "Microsoft.Network/networkInterfaces/read",
+        &list_url(
+            "Microsoft.Network/networkInterfaces",
+            azure::NETWORK_API_VERSION,
+        ),
+    )
+    .await?;
+    for interface in &interfaces {
+        let properties = &interface["properties"];
+        let Some(machine_id) = arm_id(&properties["virtualMachine"]["id"]) else {
+            continue;
+        };
+        if let Some(group_id) = arm_id(&properties["networkSecurityGroup"]["id"]) {
+            graph.add_edge(&machine_id, &group_id, Relation::UsesSecurityGroup);
+        }
+        for config in properties["ipConfigurations"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let Some(subnet_id) = arm_id(&config["properties"]["subnet"]["id"]) else {
+                continue;
+            };
+            if let Some(network_id) = network_of(&subnet_id) {
+                graph.add_edge(&machine_id, network_id, Relation::InVpc);
+            }
+            if let Some(group_id) = subnet_groups.get(&subnet_id) {
+                graph.add_edge(&machine_id, group_id, Relation::UsesSecurityGroup);
+            }
+        }
+    }
+
+    let disks = azure::list(
+        "Microsoft.Compute/disks/read",
+        &list_url("Microsoft.Compute/disks", azure::COMPUTE_API_VERSION),
+    )
+    .await?;
+    for disk in &disks {
+        let Some(id) = arm_id(&disk["id"]) else {
+            continue;
+        };
+        let label = format!(
+            "{} ({} GiB)",
+            label_of(disk, &id),
+            disk["properties"]["diskSizeGB"].as_i64().unwrap_or(0)
+        );
+        graph.add_node(&id, "Managed Disk", &label);
+        if let Some(machine_id) = arm_id(&disk["managedBy"]) {
+            graph.add_edge(&id, &machine_id, Relation::AttachedTo);
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Items of every page of a Compute API list call.
+fn items(pages: &[Value]) -> Vec<&Value> {
+    pages
+        .iter()
+        .filter_map(|page| page["items"].as_array())
+        .flatten()
+        .collect()
+}
+
+/// Items of every zone or region of a Compute API aggregated list call.
+fn aggregated<'a>(pages: &'a [Value], kind: &str) -> Vec<&'a Value> {
+    pages
+        .iter()
+        .filter_map(|page| page["items"].as_object())
+        .flat_map(|scopes| scopes.values())
+        .filter_map(|scope| scope[kind].as_array())
+        .flatten()
+        .collect()
+}
+
+fn strings(value: &Value) -> Vec<&str> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+fn label_of<'a>(resource: &'a Value, id: &'a str) -> &'a str {
+    resource["name"].as_str().unwrap_or(id)
+}
+
+/// ARM ids differ in case between resources that refer to each other, so
+/// nodes are keyed by the lowercase id.
+fn arm_id(value: &Value) -> Option<String> {
+    value.as_str().map(str::to_lowercase)
+}
+
+/// The virtual network of a subnet id `.../virtualNetworks/<name>/subnets/<name>`.
+fn network_of(subnet_id: &str) -> Option<&str> {
+    subnet_id
+        .rfind("/subnets/")
+        .map(|index| &subnet_id[..index])
+}
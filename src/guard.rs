@@ -0,0 +1,39 @@
+use crate::error::AppError;
+use crate::settings::ProfileSettings;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Why this run may not change anything, when it may not.
+static READ_ONLY: OnceLock<Option<String>> = OnceLock::new();
+
+/// Turn on read-only mode for `--read-only` or when `profile` has
+/// `read_only = true` in `actlog.toml`.
+pub fn configure(flag: bool, profile: Option<&str>, profiles: &BTreeMap<String, ProfileSettings>) {
+    let reason = if flag {
+        Some("--read-only".to_string())
+    } else {
+        profile
+            .filter(|name| profiles.get(*name).is_some_and(|p| p.read_only))
+            .map(|name| format!("profile '{}' is read-only", name))
+    };
+    let _ = READ_ONLY.set(reason);
+}
+
+/// Whether this run may not change cloud resources, so processes it starts
+/// can be held to the same.
+pub fn is_read_only() -> bool {
+    READ_ONLY.get().is_some_and(|r| r.is_some())
+}
+
+/// Fail with `ReadOnly` if this run may not change cloud resources. Called
+/// by every command before it deletes, modifies, tags, scales or powers
+/// anything.
+pub fn ensure_writable(action: &str) -> Result<(), AppError> {
+    match READ_ONLY.get().and_then(|r| r.as_deref()) {
+        Some(reason) => Err(AppError::ReadOnly(format!(
+            "{} would change cloud resources ({})",
+            action, reason
+        ))),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,58 @@
+use crate::error::AppError;
+use serde_json::{Deserializer, Value};
+use std::io::Read;
+
+/// The `--id` value that reads resource IDs from stdin
+pub const STDIN: &str = "-";
+
+/// Whether `ids` asks for resource IDs from stdin.
+pub fn from_stdin(ids: &[String]) -> bool {
+    ids.iter().any(|id| id == STDIN)
+}
+
+/// `ids` with `-` replaced by the IDs piped to stdin.
+pub fn expand(ids: &[String]) -> Result<Vec<String>, AppError> {
+    if !from_stdin(ids) {
+        return Ok(ids.to_vec());
+    }
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let piped = parse(&input);
+    if piped.is_empty() {
+        return Err(AppError::InvalidParameters(
+            "--id - read no resource IDs from stdin".to_string(),
+        ));
+    }
+
+    let mut expanded: Vec<String> = ids.iter().filter(|id| *id != STDIN).cloned().collect();
+    expanded.extend(piped);
+    Ok(expanded)
+}
+
+/// IDs in `input`. JSON is accepted as `list --format json` prints it or as
+/// jq picks it apart: strings, numbers, objects with an `id`, or arrays of
+/// them, in one document or one per line. Anything else is one plain ID per
+/// line.
+fn parse(input: &str) -> Vec<String> {
+    let json: Result<Vec<Value>, _> = Deserializer::from_str(input).into_iter::<Value>().collect();
+    match json {
+        Ok(values) => values.iter().flat_map(json_ids).collect(),
+        Err(_) => input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+fn json_ids(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(json_ids).collect(),
+        Value::String(id) => vec![id.clone()],
+        Value::Number(id) => vec![id.to_string()],
+        Value::Object(fields) => fields.get("id").map(json_ids).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,53 @@
+use crate::audit;
+use crate::error::EXIT_INTERRUPTED;
+use colored::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Batches of changes currently running; Ctrl-C only waits while one is.
+static ACTIVE_BATCHES: AtomicUsize = AtomicUsize::new(0);
+
+/// Trap Ctrl-C. While a batch of changes is running, the first press asks it
+/// to stop after the action in flight and a second press exits at once.
+/// Outside a batch Ctrl-C exits right away, as before.
+pub fn install() {
+    tokio::spawn(async {
+        loop {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+
+            if ACTIVE_BATCHES.load(Ordering::SeqCst) == 0
+                || INTERRUPTED.swap(true, Ordering::SeqCst)
+            {
+                audit::flush();
+                std::process::exit(EXIT_INTERRUPTED);
+            }
+
+            tracing::warn!(
+                "{}",
+                "⚠️  Interrupted - finishing the action in flight (Ctrl-C again to abort)".yellow()
+            );
+        }
+    });
+}
+
+/// Whether Ctrl-C was pressed; batches check this before each new action.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Marks a batch of changes as running until dropped.
+pub struct Batch(());
+
+pub fn batch() -> Batch {
+    ACTIVE_BATCHES.fetch_add(1, Ordering::SeqCst);
+    Batch(())
+}
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        ACTIVE_BATCHES.fetch_sub(1, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,423 @@
+use crate::commands::cost_report::CostReport;
+use crate::commands::list::ResourceSummary;
+use crate::error::AppError;
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// How long a cached snapshot is served before the cloud APIs are hit again
+const CACHE_TTL_MINUTES: i64 = 60;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    fetched_at TEXT NOT NULL,
+    payload TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS snapshots_lookup ON snapshots (kind, scope, fetched_at);
+
+CREATE TABLE IF NOT EXISTS resources (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots(id),
+    provider TEXT NOT NULL,
+    account TEXT,
+    resource_type TEXT NOT NULL,
+    id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    region TEXT NOT NULL,
+    state TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS costs (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots(id),
+    provider TEXT NOT NULL,
+    start_date TEXT NOT NULL,
+    end_date TEXT NOT NULL,
+    service TEXT NOT NULL,
+    account TEXT,
+    region TEXT,
+    cost REAL NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS metrics (
+    provider TEXT NOT NULL,
+    resource_id TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    recorded_at TEXT NOT NULL,
+    value REAL NOT NULL
+);
+";
+
+/// Whether commands may read from and write to the inventory cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Serve fresh snapshots from the cache and store new ones
+    Use,
+    /// Always fetch from the cloud, then store the result
+    Refresh,
+    /// Neither read nor write the cache
+    Disabled,
+}
+
+static CACHE_MODE: OnceLock<CacheMode> = OnceLock::new();
+
+pub fn set_cache_mode(mode: CacheMode) {
+    let _ = CACHE_MODE.set(mode);
+}
+
+pub fn cache_mode() -> CacheMode {
+    *CACHE_MODE.get().unwrap_or(&CacheMode::Use)
+}
+
+/// Local SQLite store of fetched resources, costs and metrics.
+pub struct Inventory {
+    conn: Connection,
+}
+
+impl Inventory {
+    pub fn open() -> Result<Self, AppError> {
+        let path = inventory_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(&path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Payload of the newest snapshot of `kind` for `scope`, with its fetch time.
+    pub fn latest<T: DeserializeOwned>(
+        &self,
+        kind: &str,
+        scope: &str,
+    ) -> Result<Option<(DateTime<Utc>, T)>, AppError> {
+        let row: Option<(String, String)> = self
+            .conn
+            .query_row(
+                "SELECT fetched_at, payload FROM snapshots
+                 WHERE kind = ?1 AND scope = ?2 AND fetched_at != ''
+                 ORDER BY fetched_at DESC LIMIT 1",
+                params![kind, scope],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        parse_snapshot(row)
+    }
+
+    /// Payload of the newest snapshot of `kind` for `scope` fetched at or
+    /// before `cutoff`, with its fetch time.
+    pub fn latest_before<T: DeserializeOwned>(
+        &self,
+        kind: &str,
+        scope: &str,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Option<(DateTime<Utc>, T)>, AppError> {
+        let row: Option<(String, String)> = self
+            .conn
+            .query_row(
+                "SELECT fetched_at, payload FROM snapshots
+                 WHERE kind = ?1 AND scope = ?2 AND fetched_at != '' AND fetched_at <= ?3
+                 ORDER BY fetched_at DESC LIMIT 1",
+                params![kind, scope, cutoff.to_rfc3339()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        parse_snapshot(row)
+    }
+
+    fn insert_snapshot<T: Serialize>(
+        &self,
+        kind: &str,
+        scope: &str,
+        payload: &T,
+    ) -> Result<i64, AppError> {
+        self.conn.execute(
+            "INSERT INTO snapshots (kind, scope, fetched_at, payload) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                kind,
+                scope,
+                Utc::now().to_rfc3339(),
+                serde_json::to_string(payload)?
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn save_resources(
+        &mut self,
+        provider: &str,
+        scope: &str,
+        resources: &[ResourceSummary],
+    ) -> Result<(), AppError> {
+        let snapshot_id = self.insert_snapshot("resources", scope, &resources)?;
+
+        let tx = self.conn.transaction()?;
+        insert_resource_rows(&tx, snapshot_id, provider, resources)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Start a resources snapshot that is filled page by page. It stays
+    /// invisible to [`Inventory::latest`] until finished.
+    pub fn begin_resources(self, provider: &str, scope: &str) -> Result<SnapshotWriter, AppError> {
+        self.conn.execute(
+            "INSERT INTO snapshots (kind, scope, fetched_at, payload)
+             VALUES ('resources', ?1, '', '[')",
+            params![scope],
+        )?;
+        let snapshot_id = self.conn.last_insert_rowid();
+
+        Ok(SnapshotWriter {
+            inventory: self,
+            snapshot_id,
+            provider: provider.to_string(),
+            empty: true,
+            failed: false,
+        })
+    }
+
+    pub fn save_cost_report(&mut self, scope: &str, report: &CostReport) -> Result<(), AppError> {
+        let snapshot_id = self.insert_snapshot("costs", scope, report)?;
+
+        let tx = self.conn.transaction()?;
+        for service in &report.services {
+            tx.execute(
+                "INSERT INTO costs
+                 (snapshot_id, provider, start_date, end_date, service, account, region, cost)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    snapshot_id,
+                    report.provider,
+                    report.start_date,
+                    report.end_date,
+                    service.service_name,
+                    service.account,
+                    service.region,
+                    service.cost
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn record_metric(
+        &self,
+        provider: &str,
+        resource_id: &str,
+        metric: &str,
+        value: f64,
+    ) -> Result<(), AppError> {
+        self.conn.execute(
+            "INSERT INTO metrics (provider, resource_id, metric, recorded_at, value)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                provider,
+                resource_id,
+                metric,
+                Utc::now().to_rfc3339(),
+                value
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+fn insert_resource_rows(
+    conn: &Connection,
+    snapshot_id: i64,
+    provider: &str,
+    resources: &[ResourceSummary],
+) -> Result<(), AppError> {
+    for resource in resources {
+        conn.execute(
+            "INSERT INTO resources
+             (snapshot_id, provider, account, resource_type, id, name, region, state)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                snapshot_id,
+                provider,
+                resource.account,
+                resource.resource_type,
+                resource.id,
+                resource.name,
+                resource.region,
+                resource.state
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Resources snapshot written one page at a time, so large listings are
+/// cached without holding them in memory. Errors are reported once and the
+/// rest of the snapshot is skipped.
+pub struct SnapshotWriter {
+    inventory: Inventory,
+    snapshot_id: i64,
+    provider: String,
+    empty: bool,
+    failed: bool,
+}
+
+impl SnapshotWriter {
+    pub fn append(&mut self, resources: &[ResourceSummary]) {
+        if self.failed || resources.is_empty() {
+            return;
+        }
+        if let Err(e) = self.try_append(resources) {
+            warn_cache_error(&e);
+            self.failed = true;
+        }
+    }
+
+    fn try_append(&mut self, resources: &[ResourceSummary]) -> Result<(), AppError> {
+        let items = resources
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?
+            .join(",");
+        let separator = if self.empty { "" } else { "," };
+
+        let tx = self.inventory.conn.transaction()?;
+        tx.execute(
+            "UPDATE snapshots SET payload = payload || ?1 WHERE id = ?2",
+            params![format!("{}{}", separator, items), self.snapshot_id],
+        )?;
+        insert_resource_rows(&tx, self.snapshot_id, &self.provider, resources)?;
+        tx.commit()?;
+
+        self.empty = false;
+        Ok(())
+    }
+
+    /// Close the payload and stamp the fetch time, making the snapshot visible.
+    pub fn finish(self) {
+        if self.failed {
+            return;
+        }
+        let result = self.inventory.conn.execute(
+            "UPDATE snapshots SET payload = payload || ']', fetched_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), self.snapshot_id],
+        );
+        if let Err(e) = result {
+            warn_cache_error(&e.into());
+        }
+    }
+}
+
+fn parse_snapshot<T: DeserializeOwned>(
+    row: Option<(String, String)>,
+) -> Result<Option<(DateTime<Utc>, T)>, AppError> {
+    let Some((fetched_at, payload)) = row else {
+        return Ok(None);
+    };
+
+    let fetched_at = DateTime::parse_from_rfc3339(&fetched_at)
+        .map_err(|e| AppError::DateParseError(e.to_string()))?
+        .with_timezone(&Utc);
+    Ok(Some((fetched_at, serde_json::from_str(&payload)?)))
+}
+
+pub fn inventory_path() -> Result<PathBuf, AppError> {
+    let data_dir = dirs::data_local_dir().ok_or_else(|| {
+        AppError::ConfigurationError("Could not determine data directory".to_string())
+    })?;
+
+    Ok(data_dir.join("actlog").join("inventory.db"))
+}
+
+/// A fresh cached snapshot, unless caching is disabled or refreshing.
+///
+/// Cache problems never fail a command; they are reported and the caller
+/// falls back to the cloud APIs.
+pub fn cached<T: DeserializeOwned>(kind: &str, scope: &str) -> Option<T> {
+    if cache_mode() != CacheMode::Use {
+        return None;
+    }
+
+    let result = Inventory::open().and_then(|inventory| inventory.latest::<T>(kind, scope));
+    match result {
+        Ok(Some((fetched_at, payload)))
+            if Utc::now() - fetched_at < Duration::minutes(CACHE_TTL_MINUTES) =>
+        {
+            tracing::info!(
+                "📦 Using cached {} from {} (--refresh to refetch)",
+                kind,
+                fetched_at.format("%Y-%m-%d %H:%M UTC")
+            );
+            Some(payload)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn_cache_error(&e);
+            None
+        }
+    }
+}
+
+pub fn store_resources(provider: &str, scope: &str, resources: &[ResourceSummary]) {
+    if cache_mode() == CacheMode::Disabled {
+        return;
+    }
+
+    let result = Inventory::open()
+        .and_then(|mut inventory| inventory.save_resources(provider, scope, resources));
+    if let Err(e) = result {
+        warn_cache_error(&e);
+    }
+}
+
+/// A page-by-page resources snapshot, unless caching is disabled.
+pub fn begin_resource_snapshot(provider: &str, scope: &str) -> Option<SnapshotWriter> {
+    if cache_mode() == CacheMode::Disabled {
+        return None;
+    }
+
+    match Inventory::open().and_then(|inventory| inventory.begin_resources(provider, scope)) {
+        Ok(writer) => Some(writer),
+        Err(e) => {
+            warn_cache_error(&e);
+            None
+        }
+    }
+}
+
+pub fn store_cost_report(scope: &str, report: &CostReport) {
+    if cache_mode() == CacheMode::Disabled {
+        return;
+    }
+
+    let result =
+        Inventory::open().and_then(|mut inventory| inventory.save_cost_report(scope, report));
+    if let Err(e) = result {
+        warn_cache_error(&e);
+    }
+}
+
+pub fn store_metric(provider: &str, resource_id: &str, metric: &str, value: f64) {
+    if cache_mode() == CacheMode::Disabled {
+        return;
+    }
+
+    let result = Inventory::open()
+        .and_then(|inventory| inventory.record_metric(provider, resource_id, metric, value));
+    if let Err(e) = result {
+        warn_cache_error(&e);
+    }
+}
+
+fn warn_cache_error(error: &AppError) {
+    tracing::warn!(
+        "⚠️  Inventory cache unavailable: {}",
+        error.to_string().yellow()
+    );
+}
@@ -0,0 +1,299 @@
+use crate::error::AppError;
+use crate::pricing;
+use aws_config::SdkConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+const KUBECTL: &str = if cfg!(windows) {
+    "kubectl.exe"
+} else {
+    "kubectl"
+};
+
+const INSTANCE_TYPE_LABEL: &str = "node.kubernetes.io/instance-type";
+const REGION_LABEL: &str = "topology.kubernetes.io/region";
+
+/// Capacity nobody requested, and pods without the grouping label
+const IDLE: &str = "(idle)";
+const UNLABELED: &str = "(unlabeled)";
+
+const HOURS_PER_MONTH: f64 = 730.0;
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Node costs of a cluster split across its tenants by what their pods
+/// request, the way Kubecost approximates it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterCosts {
+    /// kubectl context the cluster was read through; the current one if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// "namespace", or the pod label tenants are told apart by
+    pub group_by: String,
+    pub nodes: usize,
+    /// What the nodes cost over the report period
+    pub node_cost: f64,
+    /// Tenants by cost, highest first, then unrequested capacity
+    pub allocations: Vec<TenantCost>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantCost {
+    pub name: String,
+    pub cpu_cores: f64,
+    pub memory_gib: f64,
+    pub cost: f64,
+}
+
+struct Node {
+    cost: f64,
+    cpu: f64,
+    memory: f64,
+}
+
+/// Attribute the cost of the cluster's nodes over `period_hours` to
+/// namespaces, or to the values of `label` on their pods. Each pod is
+/// charged for its share of its node, half by CPU requested and half by
+/// memory requested; what no pod requested is reported as idle. Nodes are
+/// priced as on-demand EC2 instances of their instance type.
+pub async fn aws_cluster_costs(
+    config: &SdkConfig,
+    context: Option<&str>,
+    label: Option<&str>,
+    period_hours: f64,
+) -> Result<ClusterCosts, AppError> {
+    tracing::info!("☸️  Attributing Kubernetes node costs...");
+    let node_list = kubectl(context, &["get", "nodes", "-o", "json"]).await?;
+    let pod_list = kubectl(
+        context,
+        &[
+            "get",
+            "pods",
+            "--all-namespaces",
+            "--field-selector=status.phase=Running",
+            "-o",
+            "json",
+        ],
+    )
+    .await?;
+
+    let mut nodes = HashMap::new();
+    for item in node_list["items"].as_array().into_iter().flatten() {
+        let name = item["metadata"]["name"].as_str().unwrap_or_default();
+        let labels = &item["metadata"]["labels"];
+        let monthly = match (
+            labels[INSTANCE_TYPE_LABEL].as_str(),
+            labels[REGION_LABEL].as_str(),
+        ) {
+            (Some(instance_type), Some(region)) => {
+                pricing::instance_monthly_cost(config, instance_type, region).await
+            }
+            _ => {
+                tracing::warn!(
+                    "⚠️  Node {} has no instance type or region label; it is left unpriced",
+                    name
+                );
+                0.0
+            }
+        };
+        let allocatable = &item["status"]["allocatable"];
+        nodes.insert(
+            name.to_string(),
+            Node {
+                cost: monthly / HOURS_PER_MONTH * period_hours,
+                cpu: cpu_cores(allocatable["cpu"].as_str()),
+                memory: memory_bytes(allocatable["memory"].as_str()),
+            },
+        );
+    }
+
+    // tenant -> (cores, bytes, cost)
+    let mut tenants: BTreeMap<String, (f64, f64, f64)> = BTreeMap::new();
+    let mut charged: HashMap<&str, f64> = HashMap::new();
+    for pod in pod_list["items"].as_array().into_iter().flatten() {
+        let Some((node_name, node)) = pod["spec"]["nodeName"]
+            .as_str()
+            .and_then(|name| nodes.get_key_value(name))
+        else {
+            continue;
+        };
+        let (cpu, memory) = pod["spec"]["containers"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|container| &container["resources"]["requests"])
+            .fold((0.0, 0.0), |(cpu, memory), requests| {
+                (
+                    cpu + cpu_cores(requests["cpu"].as_str()),
+                    memory + memory_bytes(requests["memory"].as_str()),
+                )
+            });
+
+        let share = 0.5 * ratio(cpu, node.cpu) + 0.5 * ratio(memory, node.memory);
+        let cost = node.cost * share;
+        *charged.entry(node_name.as_str()).or_default() += cost;
+
+        let tenant = match label {
+            Some(label) => pod["metadata"]["labels"][label]
+                .as_str()
+                .unwrap_or(UNLABELED),
+            None => pod["metadata"]["namespace"].as_str().unwrap_or_default(),
+        };
+        let entry = tenants.entry(tenant.to_string()).or_default();
+        entry.0 += cpu;
+        entry.1 += memory;
+        entry.2 += cost;
+    }
+
+    let node_cost: f64 = nodes.values().map(|node| node.cost).sum();
+    let idle_cost: f64 = nodes
+        .iter()
+        .map(|(name, node)| (node.cost - charged.get(name.as_str()).unwrap_or(&0.0)).max(0.0))
+        .sum();
+
+    let mut allocations: Vec<TenantCost> = tenants
+        .into_iter()
+        .map(|(name, (cpu, memory, cost))| TenantCost {
+            name,
+            cpu_cores: cpu,
+            memory_gib: memory / GIB,
+            cost,
+        })
+        .collect();
+    allocations.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    allocations.push(TenantCost {
+        name: IDLE.to_string(),
+        cpu_cores: (nodes.values().map(|node| node.cpu).sum::<f64>()
+            - allocations.iter().map(|t| t.cpu_cores).sum::<f64>())
+        .max(0.0),
+        memory_gib: (nodes.values().map(|node| node.memory).sum::<f64>() / GIB
+            - allocations.iter().map(|t| t.memory_gib).sum::<f64>())
+        .max(0.0),
+        cost: idle_cost,
+    });
+
+    Ok(ClusterCosts {
+        context: context.map(str::to_string),
+        group_by: label.map_or("namespace".to_string(), |label| label.to_string()),
+        nodes: nodes.len(),
+        node_cost,
+        allocations,
+    })
+}
+
+/// Run kubectl with `args` against `context` and parse its JSON output.
+async fn kubectl(context: Option<&str>, args: &[&str]) -> Result<Value, AppError> {
+    let mut command = tokio::process::Command::new(KUBECTL);
+    if let Some(context) = context {
+        command.args(["--context", context]);
+    }
+    let output = command
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| AppError::ConfigurationError(format!("Could not run kubectl: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::ConfigurationError(format!(
+            "kubectl {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// Requests above what the node can give are capped at the whole node
+fn ratio(requested: f64, capacity: f64) -> f64 {
+    if capacity > 0.0 {
+        (requested / capacity).min(1.0)
+    } else {
+        0.0
+    }
+}
+
+/// A CPU quantity such as `250m` or `2` in cores.
+fn cpu_cores(quantity: Option<&str>) -> f64 {
+    let Some(quantity) = quantity else {
+        return 0.0;
+    };
+    match quantity.strip_suffix('m') {
+        Some(millis) => millis.parse::<f64>().unwrap_or(0.0) / 1000.0,
+        None => quantity.parse().unwrap_or(0.0),
+    }
+}
+
+/// A memory quantity such as `512Mi`, `1G` or `1048576` in bytes.
+fn memory_bytes(quantity: Option<&str>) -> f64 {
+    let Some(quantity) = quantity else {
+        return 0.0;
+    };
+    const SUFFIXES: &[(&str, f64)] = &[
+        ("Ki", 1024.0),
+        ("Mi", 1024.0 * 1024.0),
+        ("Gi", GIB),
+        ("Ti", GIB * 1024.0),
+        ("Pi", GIB * 1024.0 * 1024.0),
+        ("k", 1e3),
+        ("M", 1e6),
+        ("G", 1e9),
+        ("T", 1e12),
+        ("P", 1e15),
+        ("m", 1e-3),
+    ];
+    for (suffix, factor) in SUFFIXES {
+        if let Some(number) = quantity.strip_suffix(suffix) {
+            return number.parse::<f64>().unwrap_or(0.0) * factor;
+        }
+    }
+    quantity.parse().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_quantities_in_cores() {
+        assert_eq!(cpu_cores(Some("250m")), 0.25);
+        assert_eq!(cpu_cores(Some("1500m")), 1.5);
+        assert_eq!(cpu_cores(Some("2")), 2.0);
+        assert_eq!(cpu_cores(Some("0.5")), 0.5);
+    }
+
+    #[test]
+    fn missing_or_invalid_cpu_is_zero() {
+        assert_eq!(cpu_cores(None), 0.0);
+        assert_eq!(cpu_cores(Some("")), 0.0);
+        assert_eq!(cpu_cores(Some("lots")), 0.0);
+        assert_eq!(cpu_cores(Some("xm")), 0.0);
+    }
+
+    #[test]
+    fn binary_memory_suffixes() {
+        assert_eq!(memory_bytes(Some("1Ki")), 1024.0);
+        assert_eq!(memory_bytes(Some("512Mi")), 512.0 * 1024.0 * 1024.0);
+        assert_eq!(memory_bytes(Some("1.5Gi")), 1.5 * GIB);
+        assert_eq!(memory_bytes(Some("2Ti")), 2.0 * GIB * 1024.0);
+    }
+
+    #[test]
+    fn decimal_memory_suffixes() {
+        assert_eq!(memory_bytes(Some("1k")), 1e3);
+        assert_eq!(memory_bytes(Some("129M")), 129e6);
+        assert_eq!(memory_bytes(Some("1G")), 1e9);
+        assert!((memory_bytes(Some("500m")) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn plain_memory_is_bytes() {
+        assert_eq!(memory_bytes(Some("1048576")), 1048576.0);
+        assert_eq!(memory_bytes(Some("129e6")), 129e6);
+    }
+
+    #[test]
+    fn missing_or_invalid_memory_is_zero() {
+        assert_eq!(memory_bytes(None), 0.0);
+        assert_eq!(memory_bytes(Some("lots")), 0.0);
+        assert_eq!(memory_bytes(Some("Gi")), 0.0);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::error::AppError;
+
+/// How amounts are written in cost output. The default is actlog's plain
+/// form, "1234.56" with the currency symbol in front, which is what the
+/// table and CSV outputs always printed.
+#[derive(Debug, Clone, Copy)]
+pub struct Locale {
+    decimal: char,
+    group: Option<char>,
+    symbol_after: bool,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            decimal: '.',
+            group: None,
+            symbol_after: false,
+        }
+    }
+}
+
+impl Locale {
+    /// Parse a tag such as `en-US`, `de_DE` or `fr`. Only the separators
+    /// and symbol placement matter, so languages are grouped by convention.
+    pub fn parse(tag: &str) -> Result<Self, AppError> {
+        let normalized = tag.split('.').next().unwrap_or(tag).replace('_', "-");
+        let mut parts = normalized.split('-');
+        let language = parts.next().unwrap_or_default().to_lowercase();
+        let region = parts.next().unwrap_or_default().to_uppercase();
+
+        // Switzerland writes 1'234.56 whatever the language
+        if region == "CH" || region == "LI" {
+            return Ok(Self {
+                decimal: '.',
+                group: Some('\''),
+                symbol_after: false,
+            });
+        }
+
+        match language.as_str() {
+            "en" | "ja" | "zh" | "ko" | "he" | "th" | "ms" => Ok(Self {
+                decimal: '.',
+                group: Some(','),
+                symbol_after: false,
+            }),
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" => {
+                Ok(Self {
+                    decimal: ',',
+                    group: Some('.'),
+                    symbol_after: true,
+                })
+            }
+            "fr" | "sv" | "nb" | "no" | "fi" | "pl" | "cs" | "sk" | "hu" | "ru" | "uk" | "bg"
+            | "et" | "lv" | "lt" => Ok(Self {
+                decimal: ',',
+                group: Some('\u{a0}'),
+                symbol_after: true,
+            }),
+            _ => Err(AppError::InvalidParameters(format!(
+                "Unsupported locale '{}'; use a tag such as en-US, de-DE or fr-FR",
+                tag
+            ))),
+        }
+    }
+
+    /// `value` to two decimals with digit grouping, e.g. "1.234,56".
+    pub fn number(&self, value: f64) -> String {
+        let plain = format!("{:.2}", value.abs());
+        let (whole, fraction) = plain.split_once('.').unwrap_or((&plain, "00"));
+
+        let mut grouped = String::new();
+        for (i, digit) in whole.chars().enumerate() {
+            if let Some(group) = self.group {
+                if i > 0 && (whole.len() - i) % 3 == 0 {
+                    grouped.push(group);
+                }
+            }
+            grouped.push(digit);
+        }
+
+        let sign = if value < 0.0 && plain != "0.00" {
+            "-"
+        } else {
+            ""
+        };
+        format!("{}{}{}{}", sign, grouped, self.decimal, fraction)
+    }
+
+    /// `value` without grouping, for CSV cells that spreadsheets read back
+    /// as numbers, e.g. "1234,56".
+    pub fn plain_number(&self, value: f64) -> String {
+        format!("{:.2}", value).replace('.', &self.decimal.to_string())
+    }
+
+    /// `value` in `currency` (an ISO code), e.g. "$1,234.56" or "1.234,56 €".
+    pub fn money(&self, value: f64, currency: &str) -> String {
+        let symbol = currency_symbol(currency);
+        if self.symbol_after {
+            format!("{} {}", self.number(value), symbol)
+        } else if symbol == currency {
+            format!("{} {}", symbol, self.number(value))
+        } else {
+            format!("{}{}", symbol, self.number(value))
+        }
+    }
+
+    /// Field separator for CSV output. Where the comma is the decimal
+    /// separator, spreadsheets expect semicolons.
+    pub fn csv_delimiter(&self) -> char {
+        if self.decimal == ',' {
+            ';'
+        } else {
+            ','
+        }
+    }
+}
+
+/// Symbol for common currencies; others are written as their code.
+pub fn currency_symbol(currency: &str) -> &str {
+    match currency {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "INR" => "₹",
+        other => other,
+    }
+}
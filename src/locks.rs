@@ -0,0 +1,108 @@
+use crate::error::AppError;
+use crate::settings;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// A resource automation must leave alone until a date.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lock {
+    pub resource_id: String,
+    /// Last day the lock holds
+    pub until: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub locked_by: String,
+    pub locked_at: DateTime<Utc>,
+}
+
+impl Lock {
+    pub fn is_active(&self, today: NaiveDate) -> bool {
+        today <= self.until
+    }
+
+    /// Why a batch skipped the resource.
+    pub fn describe(&self) -> String {
+        match &self.reason {
+            Some(reason) => format!("locked until {} ({})", self.until, reason),
+            None => format!("locked until {}", self.until),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    #[serde(default)]
+    locks: Vec<Lock>,
+}
+
+/// `locks.yaml` in the actlog config directory, unless `[locks] path` in
+/// actlog.toml points at a registry shared with the rest of the team.
+pub fn locks_path() -> Result<PathBuf, AppError> {
+    if let Some(path) = settings::load_settings()?.locks.path {
+        return Ok(PathBuf::from(path));
+    }
+
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| {
+            AppError::ConfigurationError("Could not determine config directory".to_string())
+        })?
+        .join("actlog");
+
+    Ok(config_dir.join("locks.yaml"))
+}
+
+/// Every lock in the registry, expired ones included.
+pub fn load_locks() -> Result<Vec<Lock>, AppError> {
+    let path = locks_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    let registry: Registry = serde_yaml::from_str(&content).map_err(|e| {
+        AppError::ConfigurationError(format!("Invalid lock registry {}: {}", path.display(), e))
+    })?;
+    Ok(registry.locks)
+}
+
+/// Replace the registry with `locks`, dropping any that have expired. The
+/// file is written beside the registry and renamed over it, so readers of a
+/// shared registry never see half of it.
+pub fn save_locks(locks: Vec<Lock>) -> Result<(), AppError> {
+    let path = locks_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let today = Utc::now().date_naive();
+    let registry = Registry {
+        locks: locks.into_iter().filter(|l| l.is_active(today)).collect(),
+    };
+    let content = serde_yaml::to_string(&registry)
+        .map_err(|e| AppError::ConfigurationError(format!("Could not write locks: {}", e)))?;
+
+    let staged = path.with_extension("yaml.tmp");
+    fs::write(&staged, content)?;
+    fs::rename(&staged, &path)?;
+    Ok(())
+}
+
+/// Locks in force today, keyed by resource ID.
+pub fn active_locks() -> Result<HashMap<String, Lock>, AppError> {
+    let today = Utc::now().date_naive();
+    Ok(load_locks()?
+        .into_iter()
+        .filter(|l| l.is_active(today))
+        .map(|l| (l.resource_id.clone(), l))
+        .collect())
+}
+
+/// Who is taking a lock, from the login environment.
+pub fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
@@ -1,14 +1,81 @@
+mod accounts;
 mod app;
+mod audit;
+mod azure;
+mod batch;
+mod budget;
+mod cache;
+mod cdn;
+mod cleanup_policy;
 mod cli;
+mod clients;
+mod cloudwatch;
 mod commands;
+mod commitments;
+mod creators;
+mod databases;
+mod dns;
 mod error;
+mod executor;
+mod gcp;
+mod graph;
+mod guard;
+mod ids;
+mod interrupt;
+mod inventory;
+mod kubernetes;
+mod locale;
+mod locks;
+mod notify;
+mod output;
+mod owners;
+mod picker;
+mod plans;
+mod pricing;
+mod providers;
+mod quarantine;
+mod query;
+mod regions;
+mod report;
+mod retry;
+mod schedules;
+mod secrets;
+mod session;
+mod settings;
+mod sso;
+mod template;
+mod timeouts;
+mod token_cache;
 
-use clap::Parser;
 use cli::Cli;
+use colored::*;
 use error::AppError;
 
 #[tokio::main]
-async fn main() -> Result<(), AppError> {
-    let cli = Cli::parse();
-    app::run(cli).await
+async fn main() {
+    let cli = Cli::try_parse_args(std::env::args_os()).unwrap_or_else(|e| e.exit());
+
+    // Older Windows consoles print ANSI color codes literally unless virtual
+    // terminal processing is switched on; without it, print plain text
+    #[cfg(windows)]
+    if colored::control::set_virtual_terminal(true).is_err() {
+        colored::control::set_override(false);
+    }
+
+    let profile = cli.command.profile().unwrap_or("default").to_string();
+    let json_errors = cli.json_errors;
+    if let Err(e) = app::run(cli).await {
+        if json_errors {
+            eprintln!("{}", e.to_json(&profile));
+        } else if !matches!(e, AppError::UserCancelled | AppError::Interrupted) {
+            // A declined prompt or an interrupted batch has already said so
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            if let Some(hint) = e.hint(&profile) {
+                eprintln!("{} {}", "Hint:".yellow().bold(), hint);
+            } else if e.is_retryable() {
+                eprintln!("This looks transient (throttling, a timeout or a server error); re-running may succeed.");
+            }
+        }
+        std::process::exit(e.exit_code());
+    }
 }
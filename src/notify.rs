@@ -0,0 +1,67 @@
+use crate::error::AppError;
+use crate::executor;
+use crate::retry;
+use crate::settings::{SinkConfig, SinkKind};
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub title: String,
+    pub message: String,
+    pub severity: String,
+    pub source: String,
+}
+
+/// Deliver `notification` to a configured sink.
+pub async fn send(sink: &SinkConfig, notification: &Notification) -> Result<(), AppError> {
+    match sink.kind {
+        SinkKind::Stdout => {
+            println!(
+                "   🔔 [{}] {}: {}",
+                notification.severity.to_uppercase().red(),
+                notification.title,
+                notification.message
+            );
+            Ok(())
+        }
+
+        SinkKind::Webhook => {
+            let url = sink_url(sink)?;
+            post_json(url, &serde_json::to_value(notification)?).await
+        }
+
+        SinkKind::Slack => {
+            let url = sink_url(sink)?;
+            let text = format!(
+                ":rotating_light: *[{}] {}*\n{}",
+                notification.severity.to_uppercase(),
+                notification.title,
+                notification.message
+            );
+            post_json(url, &serde_json::json!({ "text": text })).await
+        }
+    }
+}
+
+fn sink_url(sink: &SinkConfig) -> Result<&str, AppError> {
+    sink.url.as_deref().ok_or_else(|| {
+        AppError::ConfigurationError(format!("Sink '{}' has no url configured", sink.name))
+    })
+}
+
+async fn post_json(url: &str, body: &serde_json::Value) -> Result<(), AppError> {
+    let client = reqwest::Client::new();
+    executor::throttle("notification").await;
+    let response = retry::send_http("notification", || client.post(url).json(body)).await?;
+
+    if !response.status().is_success() {
+        return Err(AppError::ApiError(format!(
+            "Notification to {} failed with status {}",
+            url,
+            response.status()
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use crate::cli::OutputFormat;
+use crate::error::AppError;
+use colored::*;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// `print!` to the `--output` file when one is set, otherwise stdout.
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::output::write(format_args!($($arg)*))
+    };
+}
+
+/// `println!` to the `--output` file when one is set, otherwise stdout.
+macro_rules! outln {
+    () => {
+        $crate::output::write(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+pub(crate) use {out, outln};
+
+/// The `--output` file and what has been written to it so far. Nothing
+/// reaches the disk until the command succeeds.
+struct Target {
+    path: PathBuf,
+    buffer: Mutex<String>,
+}
+
+static TARGET: OnceLock<Target> = OnceLock::new();
+
+/// Send the command's output to `path`. An existing file is only replaced
+/// with `overwrite`. Unless `--format` was given, the format follows the
+/// file's extension.
+pub fn configure(
+    path: &str,
+    overwrite: bool,
+    format_given: bool,
+    format: Option<&mut OutputFormat>,
+) -> Result<(), AppError> {
+    let Some(format) = format else {
+        return Err(AppError::InvalidParameters(
+            "--output works with list, report-costs and cleanup".to_string(),
+        ));
+    };
+
+    let path = PathBuf::from(path);
+    if path.exists() && !overwrite {
+        return Err(AppError::InvalidParameters(format!(
+            "{} already exists; add --force or --overwrite to replace it",
+            path.display()
+        )));
+    }
+    if !format_given {
+        if let Some(inferred) = format_for(&path) {
+            *format = inferred;
+        }
+    }
+
+    let _ = TARGET.set(Target {
+        path,
+        buffer: Mutex::new(String::new()),
+    });
+    Ok(())
+}
+
+/// Write formatted output; used through [`out!`] and [`outln!`].
+pub fn write(args: fmt::Arguments) {
+    match TARGET.get() {
+        Some(target) => {
+            if let Ok(mut buffer) = target.buffer.lock() {
+                let _ = fmt::Write::write_fmt(&mut *buffer, args);
+            }
+        }
+        None => print!("{}", args),
+    }
+}
+
+/// Write the buffered output to the `--output` file, if there is one. The
+/// file is written next to its destination and renamed into place, so it
+/// is never left half-written.
+pub fn finish() -> Result<(), AppError> {
+    let Some(target) = TARGET.get() else {
+        return Ok(());
+    };
+    let content = target
+        .buffer
+        .lock()
+        .map(|buffer| strip_colors(&buffer))
+        .unwrap_or_default();
+
+    let staged = PathBuf::from(format!("{}.tmp", target.path.display()));
+    std::fs::write(&staged, content)?;
+    std::fs::rename(&staged, &target.path)?;
+    eprintln!(
+        "💾 Output written to {}",
+        target.path.display().to_string().green()
+    );
+    Ok(())
+}
+
+/// The format a file extension implies, e.g. CSV for `costs.csv`.
+fn format_for(path: &Path) -> Option<OutputFormat> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "json" => Some(OutputFormat::Json),
+        "jsonl" | "ndjson" => Some(OutputFormat::Jsonl),
+        "csv" => Some(OutputFormat::Csv),
+        "yaml" | "yml" => Some(OutputFormat::Yaml),
+        "toml" => Some(OutputFormat::Toml),
+        _ => None,
+    }
+}
+
+/// `text` without ANSI color codes, which only make sense on a terminal.
+fn strip_colors(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip `ESC [ params m`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
@@ -0,0 +1,160 @@
+use crate::error::AppError;
+use crate::notify::{self, Notification};
+use crate::settings::{SinkConfig, SinkKind};
+use colored::*;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// Team name used for resources no team claims.
+pub const UNOWNED: &str = "unowned";
+
+/// Team ownership rules read from `owners.yaml` in the actlog config directory.
+#[derive(Debug, Default, Deserialize)]
+pub struct Owners {
+    #[serde(default)]
+    pub teams: Vec<Team>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Team {
+    pub name: String,
+    /// Channel named in notifications, e.g. "#platform-alerts"
+    pub slack_channel: Option<String>,
+    /// People to mention, e.g. "@alice"
+    #[serde(default)]
+    pub slack_handles: Vec<String>,
+    /// Incoming webhook that posts into the team's channel
+    pub slack_webhook: Option<String>,
+    /// Tag values that mark a resource as this team's; all must match
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Cloud accounts or subscriptions the team owns outright
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    /// GCP projects the team owns outright
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+impl Owners {
+    /// First team claiming the resource by tags, account or project.
+    pub fn team_for(
+        &self,
+        tags: &HashMap<String, String>,
+        account: Option<&str>,
+        project: Option<&str>,
+    ) -> Option<&Team> {
+        self.teams.iter().find(|team| {
+            let tags_match =
+                !team.tags.is_empty() && team.tags.iter().all(|(k, v)| tags.get(k) == Some(v));
+            let account_match = account.is_some_and(|a| team.accounts.iter().any(|t| t == a));
+            let project_match = project.is_some_and(|p| team.projects.iter().any(|t| t == p));
+            tags_match || account_match || project_match
+        })
+    }
+
+    /// Name of the owning team, or [`UNOWNED`].
+    pub fn team_name(
+        &self,
+        tags: &HashMap<String, String>,
+        account: Option<&str>,
+        project: Option<&str>,
+    ) -> String {
+        self.team_for(tags, account, project)
+            .map(|t| t.name.clone())
+            .unwrap_or_else(|| UNOWNED.to_string())
+    }
+
+    /// Post one message per team to its Slack webhook. `lines` maps team name
+    /// to the entries that concern it; teams without a webhook are skipped.
+    pub async fn notify_teams(
+        &self,
+        title: &str,
+        source: &str,
+        lines: &BTreeMap<String, Vec<String>>,
+    ) {
+        for team in &self.teams {
+            let (Some(webhook), Some(team_lines)) = (&team.slack_webhook, lines.get(&team.name))
+            else {
+                continue;
+            };
+            if team_lines.is_empty() {
+                continue;
+            }
+
+            let sink = SinkConfig {
+                name: team.name.clone(),
+                kind: SinkKind::Slack,
+                url: Some(webhook.clone()),
+            };
+            let notification = Notification {
+                title: format!("{} ({})", title, team.name),
+                message: format!(
+                    "{}\n{}",
+                    team.slack_handles.join(" "),
+                    team_lines.join("\n")
+                )
+                .trim()
+                .to_string(),
+                severity: "info".to_string(),
+                source: source.to_string(),
+            };
+
+            match notify::send(&sink, &notification).await {
+                Ok(()) => tracing::info!(
+                    "📣 Notified {} {}",
+                    team.name.green(),
+                    team.slack_channel.as_deref().unwrap_or("")
+                ),
+                Err(e) => tracing::warn!("⚠️  Failed to notify {}: {}", team.name.yellow(), e),
+            }
+        }
+    }
+}
+
+pub fn owners_path() -> Result<PathBuf, AppError> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| {
+            AppError::ConfigurationError("Could not determine config directory".to_string())
+        })?
+        .join("actlog");
+
+    Ok(config_dir.join("owners.yaml"))
+}
+
+/// The ownership mapping, or `None` when no `owners.yaml` exists.
+pub fn load_owners() -> Result<Option<Owners>, AppError> {
+    let path = owners_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)?;
+    let owners = serde_yaml::from_str(&content).map_err(|e| {
+        AppError::ConfigurationError(format!("Invalid owners file {}: {}", path.display(), e))
+    })?;
+    Ok(Some(owners))
+}
+
+/// The GCP project actlog runs against, used for project ownership rules.
+pub fn current_project() -> Option<String> {
+    std::env::var("GOOGLE_CLOUD_PROJECT").ok()
+}
+
+/// Print `(team, amount)` totals, largest first.
+pub fn print_team_totals(heading: &str, totals: &BTreeMap<String, (usize, f64)>) {
+    let mut rows: Vec<(&String, &(usize, f64))> = totals.iter().collect();
+    rows.sort_by(|a, b| b.1 .1.total_cmp(&a.1 .1));
+
+    println!("\n👥 {}:", heading);
+    for (team, (count, amount)) in rows {
+        let name = if team == UNOWNED {
+            team.yellow()
+        } else {
+            team.normal()
+        };
+        println!("   {:<20} {:>4} items  ${:>10.2}", name, count, amount);
+    }
+}
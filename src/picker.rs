@@ -0,0 +1,49 @@
+use crate::error::AppError;
+use inquire::{InquireError, MultiSelect, Select};
+use std::io::IsTerminal;
+
+/// Options shown at once; the rest scroll
+const PAGE_SIZE: usize = 15;
+
+/// Whether someone is at a terminal to pick from a list. Pipelines, cron
+/// jobs and redirected output never get a picker.
+pub fn interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Let the operator choose among `labels`, returning the indices picked in
+/// list order. Typing narrows the list by fuzzy match and nothing starts
+/// selected, so every target is an explicit choice. `None` means Esc or
+/// Ctrl-C.
+pub fn pick(prompt: &str, labels: Vec<String>) -> Result<Option<Vec<usize>>, AppError> {
+    let picked = MultiSelect::new(prompt, labels)
+        .with_page_size(PAGE_SIZE)
+        .with_help_message("↑↓ to move, space to select, type to filter, enter to confirm")
+        .raw_prompt();
+
+    answered(picked.map(|options| {
+        let mut indices: Vec<usize> = options.into_iter().map(|o| o.index).collect();
+        indices.sort_unstable();
+        indices
+    }))
+}
+
+/// Let the operator choose exactly one of `labels`, returning its index.
+/// `None` means Esc or Ctrl-C.
+pub fn pick_one(prompt: &str, labels: Vec<String>) -> Result<Option<usize>, AppError> {
+    let picked = Select::new(prompt, labels)
+        .with_page_size(PAGE_SIZE)
+        .with_help_message("↑↓ to move, type to filter, enter to confirm")
+        .raw_prompt();
+
+    answered(picked.map(|option| option.index))
+}
+
+fn answered<T>(result: Result<T, InquireError>) -> Result<Option<T>, AppError> {
+    match result {
+        Ok(answer) => Ok(Some(answer)),
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => Ok(None),
+        Err(InquireError::IO(e)) => Err(AppError::IoError(e)),
+        Err(e) => Err(AppError::InvalidParameters(e.to_string())),
+    }
+}
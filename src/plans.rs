@@ -0,0 +1,139 @@
+use crate::error::AppError;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Shared signing key, for teams that plan and apply on different machines
+const KEY_ENV: &str = "ACTLOG_PLAN_KEY";
+
+const SIGNATURE_PREFIX: &str = "hmac-sha256:";
+
+/// A saved plan and the signature that shows it wasn't edited after it was
+/// written.
+#[derive(Serialize, Deserialize)]
+struct SignedPlan<T> {
+    plan: T,
+    signature: String,
+}
+
+/// Write `plan` to `path` with a signature over its content.
+pub fn write_signed<T: Serialize>(path: &Path, plan: &T) -> Result<(), AppError> {
+    let plan = serde_json::to_value(plan)?;
+    let signed = SignedPlan {
+        signature: sign(&plan)?,
+        plan,
+    };
+
+    let staged = PathBuf::from(format!("{}.tmp", path.display()));
+    fs::write(&staged, serde_json::to_string_pretty(&signed)?)?;
+    fs::rename(&staged, path)?;
+    Ok(())
+}
+
+/// Read a plan written by [`write_signed`], refusing one whose content no
+/// longer matches its signature.
+pub fn read_signed<T: DeserializeOwned>(path: &Path) -> Result<T, AppError> {
+    let content = fs::read_to_string(path)?;
+    let signed: SignedPlan<Value> = serde_json::from_str(&content).map_err(|e| {
+        AppError::InvalidParameters(format!(
+            "{} is not a saved plan ({}); write one with --plan-out",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let expected = signed
+        .signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(|hex| decode_hex(hex))
+        .unwrap_or_default();
+    let mut mac = mac()?;
+    mac.update(&canonical(&signed.plan)?);
+    if mac.verify_slice(&expected).is_err() {
+        return Err(AppError::InvalidParameters(format!(
+            "The signature of {} doesn't match: it was changed after planning or signed with another key",
+            path.display()
+        )));
+    }
+
+    Ok(serde_json::from_value(signed.plan)?)
+}
+
+fn sign(plan: &Value) -> Result<String, AppError> {
+    let mut mac = mac()?;
+    mac.update(&canonical(plan)?);
+    let signature: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Ok(format!("{}{}", SIGNATURE_PREFIX, signature))
+}
+
+/// The bytes that are signed. Object keys serialize sorted, so the same
+/// plan always gives the same bytes whatever order its maps were built in.
+fn canonical(plan: &Value) -> Result<Vec<u8>, AppError> {
+    Ok(serde_json::to_vec(plan)?)
+}
+
+fn mac() -> Result<Hmac<Sha256>, AppError> {
+    Hmac::<Sha256>::new_from_slice(&signing_key()?)
+        .map_err(|e| AppError::ConfigurationError(format!("Invalid plan signing key: {}", e)))
+}
+
+/// `ACTLOG_PLAN_KEY` if set, otherwise a random key kept in the actlog
+/// config directory and created on first use.
+fn signing_key() -> Result<Vec<u8>, AppError> {
+    if let Ok(key) = std::env::var(KEY_ENV) {
+        if !key.is_empty() {
+            return Ok(key.into_bytes());
+        }
+    }
+
+    let path = key_path()?;
+    if path.exists() {
+        return decode_hex(fs::read_to_string(&path)?.trim()).ok_or_else(|| {
+            AppError::ConfigurationError(format!("Invalid plan signing key in {}", path.display()))
+        });
+    }
+
+    let mut key = vec![0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let hex: String = key.iter().map(|byte| format!("{:02x}", byte)).collect();
+    fs::write(&path, hex)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(key)
+}
+
+fn key_path() -> Result<PathBuf, AppError> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| {
+            AppError::ConfigurationError("Could not determine config directory".to_string())
+        })?
+        .join("actlog");
+
+    Ok(config_dir.join("plan.key"))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,181 @@
+use crate::cache;
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use crate::regions;
+use aws_config::SdkConfig;
+use aws_sdk_pricing::types::{Filter, FilterType};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+const HOURS_PER_MONTH: f64 = 730.0;
+
+/// The Pricing API is served from us-east-1 whatever region is priced
+const PRICING_API_REGION: &str = "us-east-1";
+
+/// us-east-1 Linux on-demand prices per hour, used when the Pricing API
+/// can't be reached or doesn't know the type
+const FALLBACK_INSTANCE_HOURLY: &[(&str, f64)] = &[
+    ("t2.micro", 0.0116),
+    ("t2.small", 0.023),
+    ("t2.medium", 0.0464),
+    ("t2.large", 0.0928),
+    ("t3.nano", 0.0052),
+    ("t3.micro", 0.0104),
+    ("t3.small", 0.0208),
+    ("t3.medium", 0.0416),
+    ("t3.large", 0.0832),
+    ("t3.xlarge", 0.1664),
+    ("t3.2xlarge", 0.3328),
+    ("t4g.micro", 0.0084),
+    ("t4g.small", 0.0168),
+    ("t4g.medium", 0.0336),
+    ("m5.large", 0.096),
+    ("m5.xlarge", 0.192),
+    ("m5.2xlarge", 0.384),
+    ("m5.4xlarge", 0.768),
+    ("m6i.large", 0.096),
+    ("m6i.xlarge", 0.192),
+    ("m6g.large", 0.077),
+    ("c5.large", 0.085),
+    ("c5.xlarge", 0.17),
+    ("c5.2xlarge", 0.34),
+    ("c6g.large", 0.068),
+    ("r5.large", 0.126),
+    ("r5.xlarge", 0.252),
+    ("r5.2xlarge", 0.504),
+];
+
+/// Prices looked up during this run, by price key
+static PRICES: OnceLock<Mutex<HashMap<String, Option<f64>>>> = OnceLock::new();
+
+/// Monthly on-demand price of a Linux instance of `instance_type` in
+/// `region`, or 0.0 when it can't be priced.
+pub async fn instance_monthly_cost(config: &SdkConfig, instance_type: &str, region: &str) -> f64 {
+    let filters = [
+        ("instanceType", instance_type),
+        ("regionCode", region),
+        ("operatingSystem", "Linux"),
+        ("tenancy", "Shared"),
+        ("preInstalledSw", "NA"),
+        ("capacitystatus", "Used"),
+    ];
+    let hourly = match lookup(config, &filters).await {
+        Some(price) => Some(price),
+        None => FALLBACK_INSTANCE_HOURLY
+            .iter()
+            .find(|(name, _)| *name == instance_type)
+            .map(|(_, price)| *price),
+    };
+    hourly.unwrap_or_default() * HOURS_PER_MONTH
+}
+
+/// Monthly price of `size_gb` GiB of EBS storage of `volume_type` in
+/// `region`. Provisioned IOPS and throughput aren't included.
+pub async fn volume_monthly_cost(
+    config: &SdkConfig,
+    volume_type: &str,
+    size_gb: i32,
+    region: &str,
+) -> f64 {
+    let filters = [
+        ("productFamily", "Storage"),
+        ("volumeApiName", volume_type),
+        ("regionCode", region),
+    ];
+    let per_gb = match lookup(config, &filters).await {
+        Some(price) => price,
+        None => fallback_volume_price_per_gb(volume_type),
+    };
+    size_gb as f64 * per_gb
+}
+
+/// us-east-1 EBS prices per GiB-month.
+fn fallback_volume_price_per_gb(volume_type: &str) -> f64 {
+    match volume_type {
+        "gp2" => 0.10,
+        "gp3" => 0.08,
+        "io1" | "io2" => 0.125,
+        "st1" => 0.045,
+        "sc1" => 0.015,
+        _ => 0.05,
+    }
+}
+
+/// The on-demand USD price of the one EC2 product matching `filters`,
+/// remembered for the run and in the response cache. A failed lookup is
+/// logged and treated as unknown, since prices only inform the estimate.
+async fn lookup(config: &SdkConfig, filters: &[(&str, &str)]) -> Option<f64> {
+    let key = format!(
+        "aws:pricing:{}",
+        filters
+            .iter()
+            .map(|(field, value)| format!("{}={}", field, value))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let prices = PRICES.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(price) = prices.lock().ok().and_then(|p| p.get(&key).copied()) {
+        return price;
+    }
+
+    let price = match cache::cached_call(&key, || fetch_price(config, filters)).await {
+        Ok(price) => price,
+        Err(e) => {
+            log::debug!("Price lookup failed, using list prices: {}", e);
+            None
+        }
+    };
+    if let Ok(mut prices) = prices.lock() {
+        prices.insert(key, price);
+    }
+    price
+}
+
+async fn fetch_price(
+    config: &SdkConfig,
+    filters: &[(&str, &str)],
+) -> Result<Option<f64>, AppError> {
+    let pricing_config = regions::regional_config(config, PRICING_API_REGION);
+    let client = clients::get::<aws_sdk_pricing::Client>(&pricing_config).await;
+
+    let mut request = client
+        .get_products()
+        .service_code("AmazonEC2")
+        .max_results(1);
+    for (field, value) in filters {
+        request = request.filters(
+            Filter::builder()
+                .r#type(FilterType::TermMatch)
+                .field(*field)
+                .value(*value)
+                .build()
+                .map_err(|e| AppError::AwsError(e.to_string()))?,
+        );
+    }
+
+    executor::throttle("pricing").await;
+    let response = request
+        .send()
+        .await
+        .map_err(|e| AppError::aws("pricing:GetProducts", e))?;
+
+    Ok(response
+        .price_list()
+        .first()
+        .map(String::as_str)
+        .and_then(on_demand_usd))
+}
+
+/// The first on-demand USD rate of a product from the price list, e.g.
+/// `terms.OnDemand.<offer>.priceDimensions.<rate>.pricePerUnit.USD`.
+fn on_demand_usd(product: &str) -> Option<f64> {
+    let product: serde_json::Value = serde_json::from_str(product).ok()?;
+    product["terms"]["OnDemand"]
+        .as_object()?
+        .values()
+        .filter_map(|offer| offer["priceDimensions"].as_object())
+        .flat_map(|dimensions| dimensions.values())
+        .filter_map(|dimension| dimension["pricePerUnit"]["USD"].as_str()?.parse().ok())
+        .find(|price: &f64| *price > 0.0)
+}
@@ -0,0 +1,198 @@
+use crate::cli::{CloudProvider, CostGranularity, ResourceType};
+use crate::clients;
+use crate::commands::cleanup::{
+    find_aws_resources, find_azure_resources, find_gcp_resources, ResourceInfo,
+    DEFAULT_LOOKBACK_DAYS,
+};
+use crate::commands::cost_report::{
+    generate_aws_cost_report, generate_azure_cost_report, generate_gcp_cost_report, CostGrouping,
+    CostReport,
+};
+use crate::commands::list::{
+    list_aws_resources, list_azure_resources, list_gcp_resources, ResourceSummary,
+};
+use crate::commands::scaling::{
+    analyze_aws_scaling, analyze_azure_scaling, analyze_gcp_scaling, execute_aws_scaling,
+    execute_azure_scaling, execute_gcp_scaling, ScalingAction, ScalingPolicy,
+};
+use crate::error::AppError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// The operations every cloud supports, so commands that work the same way
+/// on each provider call one implementation instead of matching on
+/// [`CloudProvider`]. AWS multi-account and multi-region runs need an
+/// `SdkConfig` per target and stay in their commands.
+#[async_trait]
+pub trait ResourceProvider: Send + Sync {
+    /// Resources of `resource_type`, with the default credentials.
+    async fn list(&self, resource_type: &ResourceType) -> Result<Vec<ResourceSummary>, AppError>;
+
+    /// Resources of `resource_type` that cleanup would consider.
+    async fn find_cleanup_candidates(
+        &self,
+        resource_type: &ResourceType,
+        age_threshold: u32,
+        utilization_threshold: f64,
+    ) -> Result<Vec<ResourceInfo>, AppError>;
+
+    /// The scaling actions `policy` calls for, optionally for one group.
+    async fn scale(
+        &self,
+        policy: &ScalingPolicy,
+        resource_group: &Option<String>,
+    ) -> Result<Vec<ScalingAction>, AppError>;
+
+    /// Carry out one action returned by [`ResourceProvider::scale`].
+    async fn apply_scaling(&self, action: &ScalingAction) -> Result<(), AppError>;
+
+    /// Costs between `start` and `end`, by service.
+    async fn cost_report(
+        &self,
+        start: &DateTime<Utc>,
+        end: &DateTime<Utc>,
+        budget_threshold: &Option<f64>,
+    ) -> Result<CostReport, AppError>;
+}
+
+pub struct AwsProvider;
+pub struct GcpProvider;
+pub struct AzureProvider;
+
+/// The implementation behind `provider`.
+pub fn get(provider: &CloudProvider) -> &'static dyn ResourceProvider {
+    match provider {
+        CloudProvider::Aws => &AwsProvider,
+        CloudProvider::Gcp => &GcpProvider,
+        CloudProvider::Azure => &AzureProvider,
+    }
+}
+
+#[async_trait]
+impl ResourceProvider for AwsProvider {
+    async fn list(&self, resource_type: &ResourceType) -> Result<Vec<ResourceSummary>, AppError> {
+        let config = clients::aws_config().await;
+        list_aws_resources(&config, resource_type).await
+    }
+
+    async fn find_cleanup_candidates(
+        &self,
+        resource_type: &ResourceType,
+        age_threshold: u32,
+        utilization_threshold: f64,
+    ) -> Result<Vec<ResourceInfo>, AppError> {
+        let config = clients::aws_config().await;
+        find_aws_resources(
+            &config,
+            resource_type,
+            age_threshold,
+            utilization_threshold,
+            DEFAULT_LOOKBACK_DAYS,
+        )
+        .await
+    }
+
+    async fn scale(
+        &self,
+        policy: &ScalingPolicy,
+        resource_group: &Option<String>,
+    ) -> Result<Vec<ScalingAction>, AppError> {
+        analyze_aws_scaling(policy, resource_group).await
+    }
+
+    async fn apply_scaling(&self, action: &ScalingAction) -> Result<(), AppError> {
+        execute_aws_scaling(action).await
+    }
+
+    async fn cost_report(
+        &self,
+        start: &DateTime<Utc>,
+        end: &DateTime<Utc>,
+        budget_threshold: &Option<f64>,
+    ) -> Result<CostReport, AppError> {
+        let config = clients::aws_config().await;
+        generate_aws_cost_report(
+            &config,
+            start,
+            end,
+            budget_threshold,
+            &CostGranularity::Monthly,
+            &CostGrouping::Service,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl ResourceProvider for GcpProvider {
+    async fn list(&self, resource_type: &ResourceType) -> Result<Vec<ResourceSummary>, AppError> {
+        list_gcp_resources(resource_type).await
+    }
+
+    async fn find_cleanup_candidates(
+        &self,
+        resource_type: &ResourceType,
+        age_threshold: u32,
+        utilization_threshold: f64,
+    ) -> Result<Vec<ResourceInfo>, AppError> {
+        find_gcp_resources(resource_type, age_threshold, utilization_threshold).await
+    }
+
+    async fn scale(
+        &self,
+        policy: &ScalingPolicy,
+        resource_group: &Option<String>,
+    ) -> Result<Vec<ScalingAction>, AppError> {
+        analyze_gcp_scaling(policy, resource_group).await
+    }
+
+    async fn apply_scaling(&self, action: &ScalingAction) -> Result<(), AppError> {
+        execute_gcp_scaling(action).await
+    }
+
+    async fn cost_report(
+        &self,
+        start: &DateTime<Utc>,
+        end: &DateTime<Utc>,
+        budget_threshold: &Option<f64>,
+    ) -> Result<CostReport, AppError> {
+        generate_gcp_cost_report(start, end, budget_threshold).await
+    }
+}
+
+#[async_trait]
+impl ResourceProvider for AzureProvider {
+    async fn list(&self, resource_type: &ResourceType) -> Result<Vec<ResourceSummary>, AppError> {
+        list_azure_resources(resource_type, None).await
+    }
+
+    async fn find_cleanup_candidates(
+        &self,
+        resource_type: &ResourceType,
+        age_threshold: u32,
+        utilization_threshold: f64,
+    ) -> Result<Vec<ResourceInfo>, AppError> {
+        find_azure_resources(resource_type, age_threshold, utilization_threshold, None).await
+    }
+
+    async fn scale(
+        &self,
+        policy: &ScalingPolicy,
+        resource_group: &Option<String>,
+    ) -> Result<Vec<ScalingAction>, AppError> {
+        analyze_azure_scaling(policy, resource_group).await
+    }
+
+    async fn apply_scaling(&self, action: &ScalingAction) -> Result<(), AppError> {
+        execute_azure_scaling(action).await
+    }
+
+    async fn cost_report(
+        &self,
+        start: &DateTime<Utc>,
+        end: &DateTime<Utc>,
+        budget_threshold: &Option<f64>,
+    ) -> Result<CostReport, AppError> {
+        generate_azure_cost_report(start, end, budget_threshold).await
+    }
+}
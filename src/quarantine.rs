@@ -0,0 +1,469 @@
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use aws_config::SdkConfig;
+use aws_sdk_ec2::types::{ResourceType as Ec2ResourceType, Tag, TagSpecification};
+use aws_sdk_s3::types::{
+    BucketLifecycleConfiguration, ExpirationStatus, LifecycleRule, LifecycleRuleFilter,
+    Tag as S3Tag, Tagging, Transition, TransitionStorageClass,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Tag marking a quarantined resource with the day it may be purged
+pub const PENDING_DELETE_TAG: &str = "actlog:pending-delete";
+
+/// ID of the lifecycle rule that archives a quarantined bucket's objects
+const ARCHIVE_RULE_ID: &str = "actlog-quarantine";
+
+/// Resource type names cleanup can quarantine
+const EC2_INSTANCE: &str = "EC2 Instance";
+const S3_BUCKET: &str = "S3 Bucket";
+
+/// A resource `cleanup --quarantine` took out of service instead of
+/// deleting, and how to bring it back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedResource {
+    pub resource_id: String,
+    pub name: String,
+    pub resource_type: String,
+    pub region: String,
+    /// Why cleanup selected it
+    pub reason: String,
+    pub quarantined_at: DateTime<Utc>,
+    pub quarantined_by: String,
+    /// First day `cleanup purge` may delete it
+    pub purge_after: NaiveDate,
+    /// Snapshots of an instance's volumes taken before it was stopped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snapshots: Vec<String>,
+}
+
+impl QuarantinedResource {
+    pub fn is_due(&self, today: NaiveDate) -> bool {
+        today >= self.purge_after
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    #[serde(default)]
+    resources: Vec<QuarantinedResource>,
+}
+
+/// Whether cleanup knows how to quarantine this type of resource.
+pub fn supports(resource_type: &str) -> bool {
+    resource_type == EC2_INSTANCE || resource_type == S3_BUCKET
+}
+
+/// `quarantine.yaml` in the actlog config directory.
+pub fn quarantine_path() -> Result<PathBuf, AppError> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| {
+            AppError::ConfigurationError("Could not determine config directory".to_string())
+        })?
+        .join("actlog");
+
+    Ok(config_dir.join("quarantine.yaml"))
+}
+
+/// Every quarantined resource that hasn't been purged or restored.
+pub fn load_quarantined() -> Result<Vec<QuarantinedResource>, AppError> {
+    let path = quarantine_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    let registry: Registry = serde_yaml::from_str(&content).map_err(|e| {
+        AppError::ConfigurationError(format!(
+            "Invalid quarantine registry {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(registry.resources)
+}
+
+/// Replace the registry with `resources`, written beside it and renamed
+/// into place.
+pub fn save_quarantined(resources: Vec<QuarantinedResource>) -> Result<(), AppError> {
+    let path = quarantine_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = serde_yaml::to_string(&Registry { resources }).map_err(|e| {
+        AppError::ConfigurationError(format!("Could not write the quarantine registry: {}", e))
+    })?;
+
+    let staged = path.with_extension("yaml.tmp");
+    fs::write(&staged, content)?;
+    fs::rename(&staged, &path)?;
+    Ok(())
+}
+
+/// Add `resource` to the registry, replacing any earlier entry for it.
+fn record(resource: QuarantinedResource) -> Result<(), AppError> {
+    let mut all = load_quarantined()?;
+    all.retain(|r| r.resource_id != resource.resource_id);
+    all.push(resource);
+    save_quarantined(all)
+}
+
+/// Take `resource` out of service without losing it: an instance has its
+/// volumes snapshotted and is stopped, a bucket's objects move to Glacier.
+/// Either way it is tagged with the day it may be purged and recorded in
+/// the registry.
+pub async fn quarantine_aws(
+    config: &SdkConfig,
+    mut resource: QuarantinedResource,
+) -> Result<(), AppError> {
+    let purge_after = resource.purge_after.to_string();
+    match resource.resource_type.as_str() {
+        EC2_INSTANCE => {
+            resource.snapshots = snapshot_volumes(config, &resource.resource_id).await?;
+            let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+            tag_instance(&ec2_client, &resource.resource_id, Some(&purge_after)).await?;
+            executor::throttle("ec2").await;
+            ec2_client
+                .stop_instances()
+                .instance_ids(&resource.resource_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("ec2:StopInstances", e).for_resource(&resource.resource_id)
+                })?;
+        }
+        S3_BUCKET => {
+            let s3_client = clients::get::<aws_sdk_s3::Client>(config).await;
+            tag_bucket(&s3_client, &resource.resource_id, Some(&purge_after)).await?;
+            archive_bucket(&s3_client, &resource.resource_id, true).await?;
+        }
+        other => {
+            return Err(AppError::InvalidParameters(format!(
+                "{} can't be quarantined; clean it up without --quarantine",
+                other
+            )))
+        }
+    }
+
+    record(resource)
+}
+
+/// Undo [`quarantine_aws`]: start the instance again, or stop archiving the
+/// bucket, and drop the pending-delete tag. Objects already moved to Glacier
+/// stay there until restored from it.
+pub async fn restore_aws(
+    config: &SdkConfig,
+    resource: &QuarantinedResource,
+) -> Result<(), AppError> {
+    match resource.resource_type.as_str() {
+        EC2_INSTANCE => {
+            let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+            tag_instance(&ec2_client, &resource.resource_id, None).await?;
+            executor::throttle("ec2").await;
+            ec2_client
+                .start_instances()
+                .instance_ids(&resource.resource_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("ec2:StartInstances", e).for_resource(&resource.resource_id)
+                })?;
+            // The instance's own volumes are intact, so the safety copies can go
+            for snapshot in &resource.snapshots {
+                delete_snapshot(&ec2_client, snapshot).await?;
+            }
+        }
+        S3_BUCKET => {
+            let s3_client = clients::get::<aws_sdk_s3::Client>(config).await;
+            archive_bucket(&s3_client, &resource.resource_id, false).await?;
+            tag_bucket(&s3_client, &resource.resource_id, None).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Whether the resource still carries the pending-delete tag. Someone who
+/// removed it by hand wants the resource kept.
+pub async fn still_pending(
+    config: &SdkConfig,
+    resource: &QuarantinedResource,
+) -> Result<bool, AppError> {
+    match resource.resource_type.as_str() {
+        EC2_INSTANCE => {
+            executor::throttle("ec2").await;
+            let response = clients::get::<aws_sdk_ec2::Client>(config)
+                .await
+                .describe_instances()
+                .instance_ids(&resource.resource_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::aws("ec2:DescribeInstances", e).for_resource(&resource.resource_id)
+                })?;
+            Ok(response
+                .reservations()
+                .iter()
+                .flat_map(|r| r.instances())
+                .flat_map(|i| i.tags())
+                .any(|tag| tag.key() == Some(PENDING_DELETE_TAG)))
+        }
+        S3_BUCKET => {
+            let s3_client = clients::get::<aws_sdk_s3::Client>(config).await;
+            Ok(bucket_tags(&s3_client, &resource.resource_id)
+                .await?
+                .iter()
+                .any(|tag| tag.key() == PENDING_DELETE_TAG))
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Delete the snapshots taken when `resource` was quarantined.
+pub async fn delete_snapshots(
+    config: &SdkConfig,
+    resource: &QuarantinedResource,
+) -> Result<(), AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    for snapshot in &resource.snapshots {
+        delete_snapshot(&ec2_client, snapshot).await?;
+    }
+    Ok(())
+}
+
+/// Snapshot every EBS volume attached to `instance_id`, returning the
+/// snapshot IDs.
+async fn snapshot_volumes(config: &SdkConfig, instance_id: &str) -> Result<Vec<String>, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    executor::throttle("ec2").await;
+    let response = ec2_client
+        .describe_instances()
+        .instance_ids(instance_id)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeInstances", e).for_resource(instance_id))?;
+    let volumes: Vec<String> = response
+        .reservations()
+        .iter()
+        .flat_map(|r| r.instances())
+        .flat_map(|i| i.block_device_mappings())
+        .filter_map(|m| m.ebs().and_then(|ebs| ebs.volume_id()))
+        .map(str::to_string)
+        .collect();
+
+    let mut snapshots = Vec::with_capacity(volumes.len());
+    for volume in volumes {
+        executor::throttle("ec2").await;
+        let snapshot = ec2_client
+            .create_snapshot()
+            .volume_id(&volume)
+            .description(format!("actlog quarantine of {}", instance_id))
+            .tag_specifications(
+                TagSpecification::builder()
+                    .resource_type(Ec2ResourceType::Snapshot)
+                    .tags(
+                        Tag::builder()
+                            .key("actlog:quarantined-from")
+                            .value(instance_id)
+                            .build(),
+                    )
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|e| AppError::aws("ec2:CreateSnapshot", e).for_resource(&volume))?;
+        if let Some(id) = snapshot.snapshot_id() {
+            snapshots.push(id.to_string());
+        }
+    }
+    Ok(snapshots)
+}
+
+async fn delete_snapshot(client: &aws_sdk_ec2::Client, snapshot_id: &str) -> Result<(), AppError> {
+    executor::throttle("ec2").await;
+    match client
+        .delete_snapshot()
+        .snapshot_id(snapshot_id)
+        .send()
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let error = AppError::aws("ec2:DeleteSnapshot", e).for_resource(snapshot_id);
+            // Already gone is what we wanted
+            if error.is_not_found() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Set the pending-delete tag to `purge_after`, or remove it with `None`.
+async fn tag_instance(
+    client: &aws_sdk_ec2::Client,
+    instance_id: &str,
+    purge_after: Option<&str>,
+) -> Result<(), AppError> {
+    match purge_after {
+        Some(date) => {
+            executor::throttle("ec2").await;
+            client
+                .create_tags()
+                .resources(instance_id)
+                .tags(Tag::builder().key(PENDING_DELETE_TAG).value(date).build())
+                .send()
+                .await
+                .map_err(|e| AppError::aws("ec2:CreateTags", e).for_resource(instance_id))?;
+        }
+        None => {
+            executor::throttle("ec2").await;
+            client
+                .delete_tags()
+                .resources(instance_id)
+                .tags(Tag::builder().key(PENDING_DELETE_TAG).build())
+                .send()
+                .await
+                .map_err(|e| AppError::aws("ec2:DeleteTags", e).for_resource(instance_id))?;
+        }
+    }
+    Ok(())
+}
+
+async fn bucket_tags(client: &aws_sdk_s3::Client, bucket: &str) -> Result<Vec<S3Tag>, AppError> {
+    executor::throttle("s3").await;
+    match client.get_bucket_tagging().bucket(bucket).send().await {
+        Ok(response) => Ok(response.tag_set().to_vec()),
+        Err(e) => {
+            let error = AppError::aws("s3:GetBucketTagging", e).for_resource(bucket);
+            // NoSuchTagSet: the bucket has no tags yet
+            if error.is_not_found() {
+                Ok(Vec::new())
+            } else {
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Set or remove the pending-delete tag. S3 replaces a bucket's whole tag
+/// set, so the other tags are read first and written back.
+async fn tag_bucket(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    purge_after: Option<&str>,
+) -> Result<(), AppError> {
+    let mut tags: Vec<S3Tag> = bucket_tags(client, bucket)
+        .await?
+        .into_iter()
+        .filter(|tag| tag.key() != PENDING_DELETE_TAG)
+        .collect();
+    if let Some(date) = purge_after {
+        tags.push(
+            S3Tag::builder()
+                .key(PENDING_DELETE_TAG)
+                .value(date)
+                .build()
+                .map_err(|e| AppError::AwsError(e.to_string()))?,
+        );
+    }
+
+    if tags.is_empty() {
+        executor::throttle("s3").await;
+        client
+            .delete_bucket_tagging()
+            .bucket(bucket)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("s3:DeleteBucketTagging", e).for_resource(bucket))?;
+        return Ok(());
+    }
+    executor::throttle("s3").await;
+    client
+        .put_bucket_tagging()
+        .bucket(bucket)
+        .tagging(
+            Tagging::builder()
+                .set_tag_set(Some(tags))
+                .build()
+                .map_err(|e| AppError::AwsError(e.to_string()))?,
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::aws("s3:PutBucketTagging", e).for_resource(bucket))?;
+    Ok(())
+}
+
+/// Add (or with `archive` false, remove) a lifecycle rule moving every
+/// object to Glacier, keeping the bucket's other rules.
+async fn archive_bucket(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    archive: bool,
+) -> Result<(), AppError> {
+    executor::throttle("s3").await;
+    let mut rules: Vec<LifecycleRule> = match client
+        .get_bucket_lifecycle_configuration()
+        .bucket(bucket)
+        .send()
+        .await
+    {
+        Ok(response) => response.rules().to_vec(),
+        Err(e) => {
+            let error = AppError::aws("s3:GetBucketLifecycleConfiguration", e).for_resource(bucket);
+            // NoSuchLifecycleConfiguration: no rules yet
+            if !error.is_not_found() {
+                return Err(error);
+            }
+            Vec::new()
+        }
+    };
+    rules.retain(|rule| rule.id() != Some(ARCHIVE_RULE_ID));
+    if archive {
+        rules.push(
+            LifecycleRule::builder()
+                .id(ARCHIVE_RULE_ID)
+                .status(ExpirationStatus::Enabled)
+                .filter(LifecycleRuleFilter::builder().prefix("").build())
+                .transitions(
+                    Transition::builder()
+                        .days(0)
+                        .storage_class(TransitionStorageClass::Glacier)
+                        .build(),
+                )
+                .build()
+                .map_err(|e| AppError::AwsError(e.to_string()))?,
+        );
+    }
+
+    if rules.is_empty() {
+        executor::throttle("s3").await;
+        client
+            .delete_bucket_lifecycle()
+            .bucket(bucket)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("s3:DeleteBucketLifecycle", e).for_resource(bucket))?;
+        return Ok(());
+    }
+    executor::throttle("s3").await;
+    client
+        .put_bucket_lifecycle_configuration()
+        .bucket(bucket)
+        .lifecycle_configuration(
+            BucketLifecycleConfiguration::builder()
+                .set_rules(Some(rules))
+                .build()
+                .map_err(|e| AppError::AwsError(e.to_string()))?,
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::aws("s3:PutBucketLifecycleConfiguration", e).for_resource(bucket))?;
+    Ok(())
+}
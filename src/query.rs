@@ -0,0 +1,572 @@
+use crate::commands::list::ResourceSummary;
+use crate::error::AppError;
+use chrono::Utc;
+use std::collections::HashMap;
+
+/// A parsed filter expression such as
+/// `type=instance and state=stopped and tags.env!=prod and age>30d`.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        field: Field,
+        op: CompareOp,
+        value: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    Provider,
+    Account,
+    Type,
+    Id,
+    Name,
+    Region,
+    State,
+    /// Days since the resource was created
+    Age,
+    Tag(String),
+    Info(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// Case-insensitive substring match
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(CompareOp),
+    LParen,
+    RParen,
+}
+
+pub fn parse(input: &str) -> Result<Expr, AppError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: input.chars().count() + 1,
+    };
+    let expr = parser.parse_or()?;
+
+    if let Some((column, token)) = parser.tokens.get(parser.pos) {
+        return Err(AppError::InvalidParameters(format!(
+            "Unexpected {:?} at column {} of query",
+            token, column
+        )));
+    }
+
+    Ok(expr)
+}
+
+/// The tokens of `input`, each with the column (1-based, in characters) it
+/// starts at so errors can point at it.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, AppError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().enumerate().peekable();
+
+    while let Some(&(index, c)) = chars.peek() {
+        let column = index + 1;
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push((column, Token::LParen));
+            }
+            ')' => {
+                chars.next();
+                tokens.push((column, Token::RParen));
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, next)) if next == c => break,
+                        Some((_, next)) => value.push(next),
+                        None => {
+                            return Err(AppError::InvalidParameters(format!(
+                                "Unterminated quote at column {} of query",
+                                column
+                            )))
+                        }
+                    }
+                }
+                tokens.push((column, Token::Quoted(value)));
+            }
+            '=' | '!' | '<' | '>' | '~' => {
+                chars.next();
+                let followed_by_eq = chars.peek().is_some_and(|&(_, next)| next == '=');
+                let op = match (c, followed_by_eq) {
+                    ('=', _) => CompareOp::Eq,
+                    ('!', true) => CompareOp::Ne,
+                    ('>', true) => CompareOp::Ge,
+                    ('>', false) => CompareOp::Gt,
+                    ('<', true) => CompareOp::Le,
+                    ('<', false) => CompareOp::Lt,
+                    ('~', _) => CompareOp::Contains,
+                    _ => {
+                        return Err(AppError::InvalidParameters(format!(
+                            "Expected '!=' at column {} of query",
+                            column
+                        )))
+                    }
+                };
+                if followed_by_eq && c != '~' {
+                    chars.next();
+                }
+                tokens.push((column, Token::Op(op)));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&(_, next)) = chars.peek() {
+                    if next.is_whitespace() || "()=!<>~\"'".contains(next) {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push((column, Token::Word(word)));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Column just past the last character, for errors at the end
+    end: usize,
+}
+
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some((_, Token::Word(w))) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    /// The next token and the column it starts at; the end of the query
+    /// when there are no more.
+    fn next(&mut self) -> (usize, Option<Token>) {
+        let next = match self.tokens.get(self.pos) {
+            Some((column, token)) => (*column, Some(token.clone())),
+            None => (self.end, None),
+        };
+        self.pos += 1;
+        next
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, AppError> {
+        let mut expr = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, AppError> {
+        let mut expr = self.parse_unary()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, AppError> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+
+        match self.next() {
+            (open, Some(Token::LParen)) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    (_, Some(Token::RParen)) => Ok(expr),
+                    _ => Err(AppError::InvalidParameters(format!(
+                        "Missing ')' for the '(' at column {} of query",
+                        open
+                    ))),
+                }
+            }
+            (_, Some(Token::Word(field))) => {
+                let field = parse_field(&field)?;
+                let op = match self.next() {
+                    (_, Some(Token::Op(op))) => op,
+                    (column, _) => {
+                        return Err(AppError::InvalidParameters(format!(
+                            "Expected an operator after '{:?}' at column {} of query",
+                            field, column
+                        )))
+                    }
+                };
+                let value = match self.next() {
+                    (_, Some(Token::Word(value))) | (_, Some(Token::Quoted(value))) => value,
+                    (column, _) => {
+                        return Err(AppError::InvalidParameters(format!(
+                            "Expected a value after '{:?}' at column {} of query",
+                            field, column
+                        )))
+                    }
+                };
+
+                if field == Field::Age {
+                    parse_days(&value)?;
+                }
+
+                Ok(Expr::Compare { field, op, value })
+            }
+            (column, other) => Err(AppError::InvalidParameters(format!(
+                "Expected a condition, found {:?} at column {} of query",
+                other, column
+            ))),
+        }
+    }
+}
+
+fn parse_field(name: &str) -> Result<Field, AppError> {
+    let lower = name.to_lowercase();
+    if let Some(key) = name.strip_prefix("tags.").or(name.strip_prefix("tag.")) {
+        return Ok(Field::Tag(key.to_string()));
+    }
+    if let Some(key) = name.strip_prefix("info.") {
+        return Ok(Field::Info(key.to_string()));
+    }
+
+    match lower.as_str() {
+        "provider" => Ok(Field::Provider),
+        "account" => Ok(Field::Account),
+        "type" => Ok(Field::Type),
+        "id" => Ok(Field::Id),
+        "name" => Ok(Field::Name),
+        "region" => Ok(Field::Region),
+        "state" => Ok(Field::State),
+        "age" => Ok(Field::Age),
+        _ => Err(AppError::InvalidParameters(format!(
+            "Unknown query field '{}' (expected provider, account, type, id, name, region, state, age, tags.<key> or info.<key>)",
+            name
+        ))),
+    }
+}
+
+/// Durations like `30d`, `12h` or `2w` in days; a bare number is days.
+pub fn parse_days(value: &str) -> Result<f64, AppError> {
+    let value = value.trim().to_lowercase();
+    let (number, unit_days) = match value.chars().last() {
+        Some('h') => (&value[..value.len() - 1], 1.0 / 24.0),
+        Some('d') => (&value[..value.len() - 1], 1.0),
+        Some('w') => (&value[..value.len() - 1], 7.0),
+        _ => (value.as_str(), 1.0),
+    };
+
+    number
+        .parse::<f64>()
+        .map(|n| n * unit_days)
+        .map_err(|_| AppError::InvalidParameters(format!("Invalid duration '{}'", value)))
+}
+
+impl Expr {
+    pub fn matches(&self, provider: &str, resource: &ResourceSummary) -> bool {
+        match self {
+            Expr::And(left, right) => {
+                left.matches(provider, resource) && right.matches(provider, resource)
+            }
+            Expr::Or(left, right) => {
+                left.matches(provider, resource) || right.matches(provider, resource)
+            }
+            Expr::Not(inner) => !inner.matches(provider, resource),
+            Expr::Compare { field, op, value } => {
+                compare_field(field, *op, value, provider, resource)
+            }
+        }
+    }
+}
+
+fn compare_field(
+    field: &Field,
+    op: CompareOp,
+    value: &str,
+    provider: &str,
+    resource: &ResourceSummary,
+) -> bool {
+    if *field == Field::Age {
+        let Some(created) = resource.creation_date else {
+            return false;
+        };
+        let age_days = (Utc::now() - created).num_minutes() as f64 / 1440.0;
+        let threshold = parse_days(value).unwrap_or(0.0);
+        return compare_numbers(age_days, op, threshold);
+    }
+
+    let actual = match field {
+        Field::Provider => Some(provider),
+        Field::Account => resource.account.as_deref(),
+        Field::Type => Some(resource.resource_type.as_str()),
+        Field::Id => Some(resource.id.as_str()),
+        Field::Name => Some(resource.name.as_str()),
+        Field::Region => Some(resource.region.as_str()),
+        Field::State => Some(resource.state.as_str()),
+        Field::Tag(key) => resource.tags.get(key).map(String::as_str),
+        Field::Info(key) => resource.additional_info.get(key).map(String::as_str),
+        Field::Age => unreachable!("age is compared numerically above"),
+    };
+
+    // A missing value only satisfies "not equal"
+    let Some(actual) = actual else {
+        return op == CompareOp::Ne;
+    };
+
+    let equal = if *field == Field::Type {
+        // Types read like "EC2 Instance"; `type=instance` matches any word
+        actual.eq_ignore_ascii_case(value)
+            || actual
+                .split_whitespace()
+                .any(|word| word.eq_ignore_ascii_case(value))
+    } else {
+        glob_match(&actual.to_lowercase(), &value.to_lowercase())
+    };
+
+    match op {
+        CompareOp::Eq => equal,
+        CompareOp::Ne => !equal,
+        CompareOp::Contains => actual.to_lowercase().contains(&value.to_lowercase()),
+        _ => match (actual.parse::<f64>(), value.parse::<f64>()) {
+            (Ok(a), Ok(b)) => compare_numbers(a, op, b),
+            _ => compare_ordering(actual.cmp(value), op),
+        },
+    }
+}
+
+fn compare_numbers(actual: f64, op: CompareOp, expected: f64) -> bool {
+    match actual.partial_cmp(&expected) {
+        Some(ordering) => compare_ordering(ordering, op),
+        None => false,
+    }
+}
+
+fn compare_ordering(ordering: std::cmp::Ordering, op: CompareOp) -> bool {
+    use std::cmp::Ordering::*;
+    match op {
+        CompareOp::Eq => ordering == Equal,
+        CompareOp::Ne => ordering != Equal,
+        CompareOp::Gt => ordering == Greater,
+        CompareOp::Ge => ordering != Less,
+        CompareOp::Lt => ordering == Less,
+        CompareOp::Le => ordering != Greater,
+        CompareOp::Contains => false,
+    }
+}
+
+/// A tag filter given on the command line: `key=value`, or `key` alone for
+/// any value.
+#[derive(Debug, Clone)]
+pub struct TagFilter {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl TagFilter {
+    pub fn parse(text: &str) -> Result<Self, AppError> {
+        let (key, value) = match text.split_once('=') {
+            Some((key, value)) => (key, Some(value.to_string())),
+            None => (text, None),
+        };
+        if key.is_empty() {
+            return Err(AppError::InvalidParameters(format!(
+                "Tag filter '{}' needs a key, e.g. env=prod",
+                text
+            )));
+        }
+        Ok(Self {
+            key: key.to_string(),
+            value,
+        })
+    }
+
+    pub fn parse_all(texts: &[String]) -> Result<Vec<Self>, AppError> {
+        texts.iter().map(|text| Self::parse(text)).collect()
+    }
+
+    pub fn matches(&self, tags: &HashMap<String, String>) -> bool {
+        tags.get(&self.key)
+            .is_some_and(|v| self.value.as_ref().map_or(true, |value| v == value))
+    }
+}
+
+/// `*` matches any run of characters; everything else is literal.
+pub(crate) fn glob_match(text: &str, pattern: &str) -> bool {
+    if !pattern.contains('*') {
+        return text == pattern;
+    }
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut rest = text;
+
+    if let Some(first) = parts.first() {
+        if !rest.starts_with(first) {
+            return false;
+        }
+        rest = &rest[first.len()..];
+    }
+
+    let last = parts.last().copied().unwrap_or("");
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `expr` with every group in parentheses, to see how it was parsed.
+    fn grouped(expr: &Expr) -> String {
+        match expr {
+            Expr::And(left, right) => format!("({} and {})", grouped(left), grouped(right)),
+            Expr::Or(left, right) => format!("({} or {})", grouped(left), grouped(right)),
+            Expr::Not(inner) => format!("not {}", grouped(inner)),
+            Expr::Compare { field, op, value } => format!("{:?} {:?} {:?}", field, op, value),
+        }
+    }
+
+    fn parsed(input: &str) -> String {
+        grouped(&parse(input).unwrap())
+    }
+
+    fn error(input: &str) -> String {
+        parse(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parsed("state=stopped or type=volume and region=us-east-1"),
+            r#"(State Eq "stopped" or (Type Eq "volume" and Region Eq "us-east-1"))"#
+        );
+        assert_eq!(
+            parsed("state=stopped and type=volume or region=us-east-1"),
+            r#"((State Eq "stopped" and Type Eq "volume") or Region Eq "us-east-1")"#
+        );
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(
+            parsed("(state=stopped or type=volume) and region=us-east-1"),
+            r#"((State Eq "stopped" or Type Eq "volume") and Region Eq "us-east-1")"#
+        );
+    }
+
+    #[test]
+    fn not_applies_to_the_next_condition_only() {
+        assert_eq!(
+            parsed("not state=running and age>30d"),
+            r#"(not State Eq "running" and Age Gt "30d")"#
+        );
+        assert_eq!(
+            parsed("not (state=running and age>30d)"),
+            r#"not (State Eq "running" and Age Gt "30d")"#
+        );
+    }
+
+    #[test]
+    fn chains_group_from_the_left() {
+        assert_eq!(
+            parsed("id=a or id=b or id=c"),
+            r#"((Id Eq "a" or Id Eq "b") or Id Eq "c")"#
+        );
+    }
+
+    #[test]
+    fn keywords_and_fields_ignore_case() {
+        assert_eq!(
+            parsed("STATE=stopped AND NOT Type=volume"),
+            r#"(State Eq "stopped" and not Type Eq "volume")"#
+        );
+    }
+
+    #[test]
+    fn every_operator_is_recognized() {
+        assert_eq!(
+            parsed("age>=7 and age<=30 and age<31 and age>6 and name!=web and name~api"),
+            r#"(((((Age Ge "7" and Age Le "30") and Age Lt "31") and Age Gt "6") and Name Ne "web") and Name Contains "api")"#
+        );
+    }
+
+    #[test]
+    fn quoted_values_keep_spaces_and_operators() {
+        assert_eq!(
+            parsed(r#"name="web server" and tags.team='data ops'"#),
+            r#"(Name Eq "web server" and Tag("team") Eq "data ops")"#
+        );
+        assert_eq!(parsed(r#"name='a=b (or c)'"#), r#"Name Eq "a=b (or c)""#);
+        assert_eq!(parsed(r#"name="it's""#), r#"Name Eq "it's""#);
+    }
+
+    #[test]
+    fn tag_and_info_fields_keep_their_key() {
+        assert_eq!(
+            parsed("tags.Env=prod and tag.team=ops and info.instance_type=t3.micro"),
+            r#"((Tag("Env") Eq "prod" and Tag("team") Eq "ops") and Info("instance_type") Eq "t3.micro")"#
+        );
+    }
+
+    #[test]
+    fn tokens_record_their_column() {
+        let columns: Vec<usize> = tokenize("state = 'x y' and (age>3)")
+            .unwrap()
+            .into_iter()
+            .map(|(column, _)| column)
+            .collect();
+        assert_eq!(columns, vec![1, 7, 9, 15, 19, 20, 23, 24, 25]);
+    }
+
+    #[test]
+    fn errors_point_at_the_column() {
+        assert!(error("name='web").contains("Unterminated quote at column 6"));
+        assert!(error("state ! running").contains("Expected '!=' at column 7"));
+        assert!(error("(state=a or state=b").contains("'(' at column 1"));
+        assert!(error("state running").contains("Expected an operator after 'State' at column 7"));
+        assert!(error("state=").contains("Expected a value after 'State' at column 7"));
+        assert!(error("state=a state=b").contains("Unexpected Word(\"state\") at column 9"));
+        assert!(error("state=a and").contains("found None at column 12"));
+        assert!(error("state=a )").contains("Unexpected RParen at column 9"));
+    }
+
+    #[test]
+    fn unknown_fields_and_bad_ages_are_rejected() {
+        assert!(error("colour=red").contains("Unknown query field 'colour'"));
+        assert!(error("age>soon").contains("Invalid duration 'soon'"));
+    }
+
+    #[test]
+    fn durations_convert_to_days() {
+        assert_eq!(parse_days("30d").unwrap(), 30.0);
+        assert_eq!(parse_days("2w").unwrap(), 14.0);
+        assert_eq!(parse_days("12h").unwrap(), 0.5);
+        assert_eq!(parse_days("5").unwrap(), 5.0);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::clients;
+use crate::error::AppError;
+use crate::executor;
+use aws_config::SdkConfig;
+
+/// List the regions enabled for the account behind `config`.
+pub async fn aws_regions(config: &SdkConfig) -> Result<Vec<String>, AppError> {
+    let ec2_client = clients::get::<aws_sdk_ec2::Client>(config).await;
+    executor::throttle("ec2").await;
+    let response = ec2_client
+        .describe_regions()
+        .send()
+        .await
+        .map_err(|e| AppError::aws("ec2:DescribeRegions", e))?;
+
+    Ok(response
+        .regions()
+        .iter()
+        .filter_map(|r| r.region_name().map(|n| n.to_string()))
+        .collect())
+}
+
+/// Copy of `base` pointed at `region`, keeping its credentials.
+pub fn regional_config(base: &SdkConfig, region: &str) -> SdkConfig {
+    base.to_builder()
+        .region(aws_config::Region::new(region.to_string()))
+        .build()
+}
+
+/// The region `config` targets, falling back to us-east-1.
+pub fn config_region(config: &SdkConfig) -> String {
+    config
+        .region()
+        .map(|r| r.to_string())
+        .unwrap_or_else(|| "us-east-1".to_string())
+}
@@ -0,0 +1,232 @@
+use crate::cli::OutputFormat;
+use crate::error::AppError;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Headless renderers tried in order to turn the HTML report into a PDF.
+#[cfg(not(windows))]
+const PDF_RENDERERS: &[&str] = &[
+    "wkhtmltopdf",
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+];
+
+/// Chrome and Edge are rarely on PATH on Windows, so their default install
+/// locations are tried too.
+#[cfg(windows)]
+const PDF_RENDERERS: &[&str] = &[
+    "wkhtmltopdf",
+    r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+];
+
+const STYLE: &str = "
+body { font-family: Helvetica, Arial, sans-serif; font-size: 11pt; margin: 2em; color: #222; }
+h1 { font-size: 18pt; margin-bottom: 0.2em; }
+h2 { font-size: 13pt; margin-top: 1.5em; }
+.generated { color: #777; font-size: 9pt; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #ddd; padding: 4px 8px; text-align: left; }
+th { background: #f2f2f2; }
+td.number { text-align: right; font-variant-numeric: tabular-nums; }
+";
+
+/// A printable report: paragraphs and tables under one title.
+pub struct HtmlReport {
+    title: String,
+    body: String,
+}
+
+impl HtmlReport {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            body: String::new(),
+        }
+    }
+
+    pub fn paragraph(&mut self, text: &str) -> &mut Self {
+        self.body.push_str(&format!("<p>{}</p>\n", escape(text)));
+        self
+    }
+
+    /// A table under `heading`. Numeric cells, including ones written with
+    /// locale separators such as "1.234,56", are right-aligned.
+    pub fn table(&mut self, heading: &str, headers: &[&str], rows: &[Vec<String>]) -> &mut Self {
+        self.body
+            .push_str(&format!("<h2>{}</h2>\n<table>\n<tr>", escape(heading)));
+        for header in headers {
+            self.body.push_str(&format!("<th>{}</th>", escape(header)));
+        }
+        self.body.push_str("</tr>\n");
+
+        for row in rows {
+            self.body.push_str("<tr>");
+            for cell in row {
+                let class = if is_number(cell) {
+                    " class=\"number\""
+                } else {
+                    ""
+                };
+                self.body
+                    .push_str(&format!("<td{}>{}</td>", class, escape(cell)));
+            }
+            self.body.push_str("</tr>\n");
+        }
+        self.body.push_str("</table>\n");
+        self
+    }
+
+    pub fn to_html(&self) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{style}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<p class=\"generated\">Generated by actlog on {generated}</p>\n{body}</body>\n</html>\n",
+            title = escape(&self.title),
+            style = STYLE,
+            generated = Utc::now().format("%Y-%m-%d %H:%M UTC"),
+            body = self.body
+        )
+    }
+
+    /// Render the report to a PDF at `path` with wkhtmltopdf or headless
+    /// Chrome/Chromium, whichever is installed first.
+    pub fn write_pdf(&self, path: &str) -> Result<(), AppError> {
+        let html_path =
+            std::env::temp_dir().join(format!("actlog-report-{}.html", uuid::Uuid::new_v4()));
+        fs::write(&html_path, self.to_html())?;
+
+        let result = render_pdf(&html_path, Path::new(path));
+        let _ = fs::remove_file(&html_path);
+        result
+    }
+}
+
+fn render_pdf(html: &Path, pdf: &Path) -> Result<(), AppError> {
+    for renderer in PDF_RENDERERS {
+        let mut command = Command::new(renderer);
+        if *renderer == "wkhtmltopdf" {
+            command.arg("--quiet").arg(html).arg(pdf);
+        } else {
+            command
+                .arg("--headless")
+                .arg("--disable-gpu")
+                .arg("--no-pdf-header-footer")
+                .arg(format!("--print-to-pdf={}", pdf.display()))
+                // A drive-letter path needs file:///C:/... rather than file://C:\...
+                .arg(
+                    reqwest::Url::from_file_path(html)
+                        .map(String::from)
+                        .unwrap_or_else(|_| format!("file://{}", html.display())),
+                );
+        }
+
+        match command.output() {
+            Ok(output) if output.status.success() => return Ok(()),
+            Ok(output) => {
+                return Err(AppError::ExportError(format!(
+                    "{} failed to render the PDF: {}",
+                    renderer,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )))
+            }
+            // Not installed; try the next renderer
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(AppError::ExportError(
+        "PDF export needs wkhtmltopdf, or Chrome/Chromium (or Edge on Windows) installed"
+            .to_string(),
+    ))
+}
+
+/// Key a list goes under in TOML output, whose document root must be a table
+const TOML_ROOT_KEY: &str = "items";
+
+/// `value` as a JSON, JSON Lines, YAML or TOML document, for output that
+/// feeds other tools. TOML has no null, so empty fields are left out, and a list is
+/// written as an `[[items]]` array of tables.
+pub fn document<T: Serialize + ?Sized>(
+    format: &OutputFormat,
+    value: &T,
+) -> Result<String, AppError> {
+    match format {
+        OutputFormat::Yaml => serde_yaml::to_string(value)
+            .map_err(|e| AppError::ExportError(format!("Could not write YAML: {}", e))),
+        OutputFormat::Toml => {
+            let mut value = serde_json::to_value(value)?;
+            strip_nulls(&mut value);
+            if !value.is_object() {
+                value = serde_json::json!({ TOML_ROOT_KEY: value });
+            }
+            toml::to_string_pretty(&value)
+                .map_err(|e| AppError::ExportError(format!("Could not write TOML: {}", e)))
+        }
+        // A list becomes one line per item, anything else a single line
+        OutputFormat::Jsonl => match serde_json::to_value(value)? {
+            Value::Array(items) => Ok(items
+                .iter()
+                .map(serde_json::to_string)
+                .collect::<Result<Vec<_>, _>>()?
+                .join("\n")),
+            value => Ok(serde_json::to_string(&value)?),
+        },
+        OutputFormat::Table | OutputFormat::Json | OutputFormat::Csv => {
+            Ok(serde_json::to_string_pretty(value)?)
+        }
+    }
+}
+
+/// One CSV line, terminator included, with fields quoted as needed so
+/// commas, quotes and newlines in names survive.
+pub fn csv_record<I, F>(fields: I, delimiter: char) -> Result<String, AppError>
+where
+    I: IntoIterator<Item = F>,
+    F: AsRef<[u8]>,
+{
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter as u8)
+        .from_writer(Vec::new());
+    writer.write_record(fields).map_err(std::io::Error::from)?;
+    let line = writer
+        .into_inner()
+        .map_err(|e| AppError::ExportError(e.to_string()))?;
+    Ok(String::from_utf8_lossy(&line).into_owned())
+}
+
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            fields.retain(|_, field| !field.is_null());
+            fields.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => {
+            items.retain(|item| !item.is_null());
+            items.iter_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
+fn is_number(cell: &str) -> bool {
+    cell.chars().any(|c| c.is_ascii_digit())
+        && cell
+            .chars()
+            .enumerate()
+            .all(|(i, c)| c.is_ascii_digit() || ".,'\u{a0}".contains(c) || (i == 0 && c == '-'))
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
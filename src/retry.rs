@@ -0,0 +1,135 @@
+use crate::error::AppError;
+use crate::session;
+use crate::settings::RetrySettings;
+use crate::timeouts;
+use aws_config::retry::RetryConfig;
+use aws_config::{BehaviorVersion, ConfigLoader};
+use rand::Rng;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Attempts per request, including the first, when nothing is configured
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(20);
+
+/// How throttled or transiently failing provider calls are retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retrying after failed attempt `attempt` (1-based): a
+    /// random duration up to `initial_backoff * 2^(attempt - 1)`, capped at
+    /// `max_backoff` ("full jitter").
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let ceiling = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        ceiling.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+}
+
+static POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+/// Set the retry policy from `actlog.toml`; `max_attempts` overrides the
+/// configured attempt count.
+pub fn configure(max_attempts: Option<u32>, settings: &RetrySettings) {
+    let defaults = RetryPolicy::default();
+    let _ = POLICY.set(RetryPolicy {
+        max_attempts: max_attempts
+            .or(settings.max_attempts)
+            .unwrap_or(defaults.max_attempts)
+            .max(1),
+        initial_backoff: settings
+            .initial_backoff_ms
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.initial_backoff),
+        max_backoff: settings
+            .max_backoff_ms
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.max_backoff),
+    });
+}
+
+pub fn policy() -> RetryPolicy {
+    POLICY.get().copied().unwrap_or_default()
+}
+
+/// SDK retry settings matching [`policy`]. The SDK's standard strategy backs
+/// off exponentially with jitter on throttling errors, transient 5xx
+/// responses and timeouts.
+pub fn aws_retry_config() -> RetryConfig {
+    let policy = policy();
+    RetryConfig::standard()
+        .with_max_attempts(policy.max_attempts)
+        .with_initial_backoff(policy.initial_backoff)
+        .with_max_backoff(policy.max_backoff)
+}
+
+/// Config loader for AWS clients with the retry policy and call timeouts
+/// applied. Every AWS config should start here so all clients share them.
+pub fn aws_loader() -> ConfigLoader {
+    session::aws_loader(
+        aws_config::defaults(BehaviorVersion::latest())
+            .retry_config(aws_retry_config())
+            .timeout_config(timeouts::aws_timeout_config()),
+    )
+}
+
+/// Send a plain HTTP request, retrying connection failures, timeouts, 429 and
+/// 5xx responses. `build` is called again for every attempt and each attempt
+/// is bounded by the call timeout. The last response is returned as-is so
+/// callers still see its status.
+pub async fn send_http<F>(operation: &str, build: F) -> Result<reqwest::Response, AppError>
+where
+    F: Fn() -> reqwest::RequestBuilder,
+{
+    let policy = policy();
+    let mut attempt = 1;
+
+    loop {
+        let result = session::send_http(build().timeout(timeouts::call_timeout())).await;
+        let reason = match &result {
+            Ok(response)
+                if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || response.status().is_server_error() =>
+            {
+                Some(response.status().to_string())
+            }
+            Err(e) if e.is_timeout() || e.is_connect() => Some(e.to_string()),
+            _ => None,
+        };
+
+        let Some(reason) = reason.filter(|_| attempt < policy.max_attempts) else {
+            return Ok(result?);
+        };
+
+        let delay = policy.backoff(attempt);
+        log::debug!(
+            "{} attempt {}/{} failed ({}), retrying in {:?}",
+            operation,
+            attempt,
+            policy.max_attempts,
+            reason,
+            delay
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
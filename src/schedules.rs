@@ -0,0 +1,266 @@
+use crate::error::AppError;
+use chrono::{DateTime, Local, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// An actlog command run on a cron schedule by `actlog schedule daemon`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub name: String,
+    /// Standard five-field cron expression, in local time
+    pub cron: String,
+    /// Arguments to actlog, e.g. `["cleanup", "aws", "ec2", "--dry-run"]`
+    pub command: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<DateTime<Utc>>,
+    /// `succeeded`, or how the last run failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_status: Option<String>,
+}
+
+impl Job {
+    /// When the job next runs after `after`.
+    pub fn next_run(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let schedule = parse_cron(&self.cron).ok()?;
+        schedule
+            .after(&after.with_timezone(&Local))
+            .next()
+            .map(|at| at.with_timezone(&Utc))
+    }
+
+    /// The command line, quoted where needed, for display.
+    pub fn command_line(&self) -> String {
+        self.command
+            .iter()
+            .map(|arg| {
+                if arg.is_empty() || arg.contains(char::is_whitespace) {
+                    format!("'{}'", arg)
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    #[serde(default)]
+    jobs: Vec<Job>,
+}
+
+/// Day names in POSIX weekday order, Sunday being 0 (and 7)
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Parse a cron expression. The usual five fields (minute hour day month
+/// weekday) run at second zero; six or seven fields start with seconds.
+/// Weekdays are numbered as in POSIX cron, 0-7 with Sunday as 0 and 7.
+pub fn parse_cron(expression: &str) -> Result<Schedule, AppError> {
+    let invalid = |reason: String| {
+        AppError::InvalidParameters(format!(
+            "Invalid cron expression '{}': {} (expected e.g. '0 3 * * *')",
+            expression, reason
+        ))
+    };
+
+    let mut fields: Vec<String> = expression.split_whitespace().map(str::to_string).collect();
+    if fields.len() == 5 {
+        fields.insert(0, "0".to_string());
+    }
+    // The cron crate counts weekdays from Sunday as 1, so spell them out
+    if let Some(weekday) = fields.get_mut(5) {
+        *weekday = weekday_names(weekday).map_err(invalid)?;
+    }
+
+    Schedule::from_str(&fields.join(" ")).map_err(|e| invalid(e.to_string()))
+}
+
+/// A POSIX weekday field such as `1-5`, `0,6` or `*/2` as day names.
+fn weekday_names(field: &str) -> Result<String, String> {
+    if field == "*" || field == "?" {
+        return Ok(field.to_string());
+    }
+
+    let day = |value: &str| -> Result<usize, String> {
+        if let Some(index) = WEEKDAYS
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+        {
+            return Ok(index);
+        }
+        match value.parse::<usize>() {
+            Ok(number) if number <= 7 => Ok(number),
+            _ => Err(format!("'{}' is not a weekday (0-7 or SUN-SAT)", value)),
+        }
+    };
+
+    let mut days = [false; 7];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<usize>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("'{}' is not a step", step))?,
+            ),
+            None => (part, 1),
+        };
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((first, last)) => (day(first)?, day(last)?),
+            // `3/2` runs from Wednesday to the end of the week
+            None if part.contains('/') => (day(range)?, 6),
+            None => {
+                let single = day(range)?;
+                (single, single)
+            }
+        };
+        if first > last {
+            return Err(format!("weekday range '{}' runs backwards", range));
+        }
+        for number in (first..=last).step_by(step) {
+            days[number % 7] = true;
+        }
+    }
+
+    Ok(WEEKDAYS
+        .iter()
+        .zip(days)
+        .filter(|(_, runs)| *runs)
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// `schedules.yaml` in the actlog config directory.
+pub fn schedules_path() -> Result<PathBuf, AppError> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| {
+            AppError::ConfigurationError("Could not determine config directory".to_string())
+        })?
+        .join("actlog");
+
+    Ok(config_dir.join("schedules.yaml"))
+}
+
+/// Every scheduled job, in the order they were added.
+pub fn load_jobs() -> Result<Vec<Job>, AppError> {
+    let path = schedules_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    let registry: Registry = serde_yaml::from_str(&content).map_err(|e| {
+        AppError::ConfigurationError(format!("Invalid schedule file {}: {}", path.display(), e))
+    })?;
+    Ok(registry.jobs)
+}
+
+/// Replace the scheduled jobs with `jobs`. The file is written beside the
+/// old one and renamed over it, so a running daemon never reads half of it.
+pub fn save_jobs(jobs: Vec<Job>) -> Result<(), AppError> {
+    let path = schedules_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = serde_yaml::to_string(&Registry { jobs })
+        .map_err(|e| AppError::ConfigurationError(format!("Could not write schedules: {}", e)))?;
+
+    let staged = path.with_extension("yaml.tmp");
+    fs::write(&staged, content)?;
+    fs::rename(&staged, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone, Timelike, Weekday};
+
+    /// The first `count` runs of `expression` after Monday 2024-01-01 00:00 UTC.
+    fn runs(expression: &str, count: usize) -> Vec<DateTime<Utc>> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        parse_cron(expression)
+            .unwrap()
+            .after(&start)
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn weekday_numbers_count_from_sunday() {
+        assert_eq!(weekday_names("0").unwrap(), "SUN");
+        assert_eq!(weekday_names("1").unwrap(), "MON");
+        assert_eq!(weekday_names("6").unwrap(), "SAT");
+        assert_eq!(weekday_names("7").unwrap(), "SUN");
+    }
+
+    #[test]
+    fn weekday_lists_ranges_and_steps() {
+        assert_eq!(weekday_names("1-5").unwrap(), "MON,TUE,WED,THU,FRI");
+        assert_eq!(weekday_names("0,6").unwrap(), "SUN,SAT");
+        assert_eq!(weekday_names("5-7").unwrap(), "SUN,FRI,SAT");
+        assert_eq!(weekday_names("*/2").unwrap(), "SUN,TUE,THU,SAT");
+        assert_eq!(weekday_names("1-5/2").unwrap(), "MON,WED,FRI");
+        assert_eq!(weekday_names("3/2").unwrap(), "WED,FRI");
+        assert_eq!(weekday_names("mon-wed").unwrap(), "MON,TUE,WED");
+        assert_eq!(weekday_names("*").unwrap(), "*");
+    }
+
+    #[test]
+    fn invalid_weekdays_are_rejected() {
+        assert!(weekday_names("8").is_err());
+        assert!(weekday_names("5-1").is_err());
+        assert!(weekday_names("*/0").is_err());
+        assert!(weekday_names("someday").is_err());
+        assert!(parse_cron("0 3 * * 8").is_err());
+    }
+
+    #[test]
+    fn monday_is_one_and_sunday_is_zero_or_seven() {
+        assert!(runs("0 9 * * 1", 3)
+            .iter()
+            .all(|run| run.weekday() == Weekday::Mon));
+        for sunday in ["0 9 * * 0", "0 9 * * 7", "0 9 * * SUN"] {
+            assert_eq!(
+                runs(sunday, 1),
+                vec![Utc.with_ymd_and_hms(2024, 1, 7, 9, 0, 0).unwrap()]
+            );
+        }
+    }
+
+    #[test]
+    fn weekday_ranges_skip_the_weekend() {
+        let weekdays: Vec<Weekday> = runs("30 7 * * 1-5", 6)
+            .iter()
+            .map(|run| run.weekday())
+            .collect();
+        assert_eq!(
+            weekdays,
+            vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Mon
+            ]
+        );
+    }
+
+    #[test]
+    fn five_fields_run_at_second_zero_and_six_start_with_seconds() {
+        let five = runs("30 7 * * *", 1)[0];
+        assert_eq!((five.hour(), five.minute(), five.second()), (7, 30, 0));
+        let six = runs("15 30 7 * * *", 1)[0];
+        assert_eq!((six.hour(), six.minute(), six.second()), (7, 30, 15));
+    }
+}
@@ -0,0 +1,35 @@
+use crate::error::AppError;
+use keyring::Entry;
+
+/// Service the entries are filed under in the OS keychain
+const SERVICE: &str = "actlog";
+
+/// Save `secret` for `account`, replacing any earlier one.
+pub fn store(account: &str, secret: &str) -> Result<(), AppError> {
+    entry(account)?.set_password(secret).map_err(keyring_error)
+}
+
+/// The secret saved for `account`, if there is one.
+pub fn load(account: &str) -> Result<Option<String>, AppError> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(keyring_error(e)),
+    }
+}
+
+/// Forget the secret saved for `account`; nothing saved is not an error.
+pub fn delete(account: &str) -> Result<(), AppError> {
+    match entry(account)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(keyring_error(e)),
+    }
+}
+
+fn entry(account: &str) -> Result<Entry, AppError> {
+    Entry::new(SERVICE, account).map_err(keyring_error)
+}
+
+fn keyring_error(e: keyring::Error) -> AppError {
+    AppError::ConfigurationError(format!("OS keyring unavailable: {}", e))
+}
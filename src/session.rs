@@ -0,0 +1,630 @@
+use crate::cli::Cli;
+use crate::error::AppError;
+use aws_config::{BehaviorVersion, ConfigLoader, Region};
+use aws_credential_types::Credentials;
+use aws_smithy_runtime_api::client::http::{
+    HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpClient,
+    SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::http::StatusCode;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::byte_stream::ByteStream;
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// Bumped whenever the session layout changes incompatibly
+const SESSION_VERSION: u32 = 1;
+
+/// Field, XML element and query parameter names whose values never reach a
+/// session file, compared without case, `_` or `-`
+const SECRET_NAMES: &[&str] = &[
+    "accesskeyid",
+    "secretaccesskey",
+    "secretkey",
+    "sessiontoken",
+    "securitytoken",
+    "xamzsecuritytoken",
+    "xamzsignature",
+    "xamzcredential",
+    "accesstoken",
+    "token",
+    "bearertoken",
+    "refreshtoken",
+    "idtoken",
+    "clientsecret",
+    "apikey",
+    "devicecode",
+    "assertion",
+    "password",
+    "privatekey",
+    "sig",
+];
+
+/// Response headers left out of a session
+const DROPPED_HEADERS: &[&str] = &["set-cookie", "authorization", "x-amz-security-token"];
+
+/// Command-line flags whose values are secrets
+const SECRET_FLAGS: &[&str] = &["--secret-key", "--api-key", "--mfa-code"];
+
+/// Settings from the environment that decide what a command calls, so a
+/// replay on another machine makes the same requests
+const RECORDED_ENV: &[&str] = &[
+    "AWS_REGION",
+    "AWS_DEFAULT_REGION",
+    "GOOGLE_CLOUD_PROJECT",
+    "AZURE_SUBSCRIPTION_ID",
+];
+
+const REDACTED: &str = "REDACTED";
+
+/// A recorded run: the command line and every provider request it made.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    version: u32,
+    recorded_at: DateTime<Utc>,
+    actlog_version: String,
+    /// Arguments after `actlog`, without `--record`
+    args: Vec<String>,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    exchanges: Vec<Exchange>,
+}
+
+/// One HTTP request and the response it got, with secrets redacted from
+/// both, since STS, SSO and OAuth responses carry credentials.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Exchange {
+    method: String,
+    url: String,
+    /// AWS operation, from `X-Amz-Target` or the query `Action`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    operation: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    request_body: String,
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    response_body: String,
+}
+
+enum Mode {
+    Record {
+        path: PathBuf,
+        args: Vec<String>,
+        env: BTreeMap<String, String>,
+        inner: SharedHttpClient,
+        exchanges: Mutex<Vec<Exchange>>,
+    },
+    Replay {
+        /// Recorded exchanges, taken out as requests are answered
+        exchanges: Mutex<Vec<Option<Exchange>>>,
+    },
+}
+
+static MODE: OnceLock<Mode> = OnceLock::new();
+
+/// Record every provider request of this run into `path`, written by
+/// [`finish`] when the command ends.
+pub async fn record(path: &str) {
+    // The SDK's own HTTPS client does the sending; recording only wraps it
+    let inner = aws_config::defaults(BehaviorVersion::latest())
+        .region(Region::new("us-east-1"))
+        .no_credentials()
+        .load()
+        .await
+        .http_client();
+    let Some(inner) = inner else {
+        eprintln!(
+            "   ⚠️  {}",
+            "No HTTP client to record AWS requests with; recording GCP and Azure only".yellow()
+        );
+        return;
+    };
+
+    let mut env: BTreeMap<String, String> = RECORDED_ENV
+        .iter()
+        .filter_map(|var| Some((var.to_string(), std::env::var(var).ok()?)))
+        .collect();
+    // The region and project may come from the profile or a key file, which
+    // the replaying machine won't have
+    if let Some(region) = crate::clients::profile_config().await.region() {
+        env.insert("AWS_REGION".to_string(), region.to_string());
+    }
+    if let Ok(project_id) = crate::gcp::project_id() {
+        env.insert("GOOGLE_CLOUD_PROJECT".to_string(), project_id);
+    }
+
+    let _ = MODE.set(Mode::Record {
+        path: PathBuf::from(path),
+        args: recorded_args(std::env::args().skip(1)),
+        env,
+        inner,
+        exchanges: Mutex::new(Vec::new()),
+    });
+}
+
+/// Load the session in `path` for replay and return the command it
+/// recorded. From here on, provider requests are answered from the session
+/// and never reach the network.
+pub fn replay(path: &str) -> Result<Cli, AppError> {
+    let content = std::fs::read_to_string(path)?;
+    let session: Session = serde_json::from_str(&content)?;
+    if session.version != SESSION_VERSION {
+        return Err(AppError::InvalidParameters(format!(
+            "{} is a version {} session; this actlog replays version {}",
+            path, session.version, SESSION_VERSION
+        )));
+    }
+
+    let cli = Cli::try_parse_args(std::iter::once("actlog".to_string()).chain(session.args))
+        .map_err(|e| AppError::InvalidParameters(format!("Recorded command is invalid: {}", e)))?;
+    if matches!(cli.command, crate::cli::Commands::Replay { .. }) {
+        return Err(AppError::InvalidParameters(
+            "A session can't replay another session".to_string(),
+        ));
+    }
+
+    // Nothing from this machine's environment may change what gets called
+    for var in RECORDED_ENV {
+        match session.env.get(*var) {
+            Some(value) => std::env::set_var(var, value),
+            None => std::env::remove_var(var),
+        }
+    }
+
+    eprintln!(
+        "📼 Replaying {} requests recorded {} with actlog {}",
+        session.exchanges.len(),
+        session.recorded_at.format("%Y-%m-%d %H:%M UTC"),
+        session.actlog_version
+    );
+    let _ = MODE.set(Mode::Replay {
+        exchanges: Mutex::new(session.exchanges.into_iter().map(Some).collect()),
+    });
+    Ok(cli)
+}
+
+/// Whether provider requests are answered from a recorded session.
+pub fn replaying() -> bool {
+    matches!(MODE.get(), Some(Mode::Replay { .. }))
+}
+
+/// Write the recorded session, if this run is being recorded.
+pub fn finish() -> Result<(), AppError> {
+    let Some(Mode::Record {
+        path,
+        args,
+        env,
+        exchanges,
+        ..
+    }) = MODE.get()
+    else {
+        return Ok(());
+    };
+
+    let exchanges = exchanges.lock().map(|e| e.clone()).unwrap_or_default();
+    let session = Session {
+        version: SESSION_VERSION,
+        recorded_at: Utc::now(),
+        actlog_version: env!("CARGO_PKG_VERSION").to_string(),
+        args: args.clone(),
+        env: env.clone(),
+        exchanges,
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&session)?)?;
+    eprintln!(
+        "📼 Recorded {} requests to {}",
+        session.exchanges.len(),
+        path.display().to_string().green()
+    );
+    Ok(())
+}
+
+/// Route an AWS config through the session: its requests are recorded, or
+/// answered from the replayed session with placeholder credentials.
+pub fn aws_loader(loader: ConfigLoader) -> ConfigLoader {
+    match MODE.get() {
+        Some(Mode::Record { inner, .. }) => loader.http_client(RecordingClient {
+            inner: inner.clone(),
+        }),
+        Some(Mode::Replay { .. }) => loader
+            .http_client(ReplayingClient)
+            .region(Region::new(
+                std::env::var("AWS_REGION")
+                    .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+                    .unwrap_or_else(|_| "us-east-1".to_string()),
+            ))
+            .credentials_provider(Credentials::new(
+                "REPLAY",
+                "REPLAY",
+                None,
+                None,
+                "actlog-replay",
+            )),
+        None => loader,
+    }
+}
+
+/// Send a plain HTTP request, recording it or answering it from the
+/// replayed session.
+pub async fn send_http(request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    let Some(mode) = MODE.get() else {
+        return request.send().await;
+    };
+    let (client, request) = request.build_split();
+    let request = request?;
+    let method = request.method().to_string();
+    let url = redact_url(request.url().as_str());
+    let body = request
+        .body()
+        .and_then(|b| b.as_bytes())
+        .map(|b| redact_body(&String::from_utf8_lossy(b)))
+        .unwrap_or_default();
+
+    let exchange = match mode {
+        Mode::Replay { exchanges } => take(exchanges, &method, &url, "", &body),
+        Mode::Record { exchanges, .. } => {
+            let response = client.execute(request).await?;
+            let status = response.status().as_u16();
+            let headers = kept_headers(
+                response
+                    .headers()
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+            );
+            let bytes = response.bytes().await?;
+            let exchange = Exchange {
+                method,
+                url,
+                operation: String::new(),
+                request_body: body,
+                status,
+                headers,
+                response_body: String::from_utf8_lossy(&bytes).into_owned(),
+            };
+            push(exchanges, &exchange);
+            exchange
+        }
+    };
+
+    let mut response = http::Response::builder().status(exchange.status);
+    for (name, value) in &exchange.headers {
+        response = response.header(name, value);
+    }
+    Ok(reqwest::Response::from(
+        response
+            .body(exchange.response_body)
+            .expect("recorded status and headers are valid"),
+    ))
+}
+
+/// Wraps the SDK's HTTP client to record each request and response.
+#[derive(Debug)]
+struct RecordingClient {
+    inner: SharedHttpClient,
+}
+
+impl HttpClient for RecordingClient {
+    fn http_connector(
+        &self,
+        settings: &HttpConnectorSettings,
+        components: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        SharedHttpConnector::new(RecordingConnector {
+            inner: self.inner.http_connector(settings, components),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct RecordingConnector {
+    inner: SharedHttpConnector,
+}
+
+impl HttpConnector for RecordingConnector {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        let (method, url, operation, body) = describe(&request);
+        let sent = self.inner.call(request);
+
+        HttpConnectorFuture::new(async move {
+            let response = sent.await?;
+            let status = response.status().as_u16();
+            let headers = kept_headers(response.headers().iter());
+            let bytes = ByteStream::new(response.into_body())
+                .collect()
+                .await
+                .map_err(|e| ConnectorError::io(e.into()))?
+                .into_bytes();
+
+            let exchange = Exchange {
+                method,
+                url,
+                operation,
+                request_body: body,
+                status,
+                headers,
+                response_body: String::from_utf8_lossy(&bytes).into_owned(),
+            };
+            if let Some(Mode::Record { exchanges, .. }) = MODE.get() {
+                push(exchanges, &exchange);
+            }
+
+            // The body was read for the recording; hand the SDK a copy
+            Ok(sdk_response(&exchange, SdkBody::from(bytes)))
+        })
+    }
+}
+
+/// Answers the SDK's requests from the replayed session.
+#[derive(Debug)]
+struct ReplayingClient;
+
+impl HttpClient for ReplayingClient {
+    fn http_connector(
+        &self,
+        _settings: &HttpConnectorSettings,
+        _components: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        SharedHttpConnector::new(ReplayingConnector)
+    }
+}
+
+#[derive(Debug)]
+struct ReplayingConnector;
+
+impl HttpConnector for ReplayingConnector {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        let (method, url, operation, body) = describe(&request);
+        let exchange = match MODE.get() {
+            Some(Mode::Replay { exchanges }) => take(exchanges, &method, &url, &operation, &body),
+            _ => not_recorded(&method, &url),
+        };
+        let body = SdkBody::from(exchange.response_body.clone());
+        HttpConnectorFuture::ready(Ok(sdk_response(&exchange, body)))
+    }
+}
+
+/// Method, redacted URL, AWS operation and redacted body of an SDK request.
+fn describe(request: &HttpRequest) -> (String, String, String, String) {
+    let body = request
+        .body()
+        .bytes()
+        .map(|b| String::from_utf8_lossy(b).into_owned())
+        .unwrap_or_default();
+    let operation = request
+        .headers()
+        .get("x-amz-target")
+        .map(str::to_string)
+        .or_else(|| {
+            body.split('&')
+                .find_map(|pair| pair.strip_prefix("Action="))
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    (
+        request.method().to_string(),
+        redact_url(request.uri()),
+        operation,
+        redact_body(&body),
+    )
+}
+
+fn sdk_response(exchange: &Exchange, body: SdkBody) -> HttpResponse {
+    let status = StatusCode::try_from(exchange.status)
+        .unwrap_or_else(|_| StatusCode::try_from(500).expect("500 is a valid status code"));
+    let mut response = HttpResponse::new(status, body);
+    for (name, value) in &exchange.headers {
+        response.headers_mut().insert(name.clone(), value.clone());
+    }
+    response
+}
+
+/// The recorded response for a request. The same method, URL and body is
+/// preferred; failing that, the same path and operation, since bodies and
+/// query strings carry times that move between runs. Each exchange answers
+/// one request, in recorded order.
+fn take(
+    exchanges: &Mutex<Vec<Option<Exchange>>>,
+    method: &str,
+    url: &str,
+    operation: &str,
+    body: &str,
+) -> Exchange {
+    let Ok(mut exchanges) = exchanges.lock() else {
+        return not_recorded(method, url);
+    };
+    let path = url_path(url);
+
+    let exact = exchanges.iter().position(|e| {
+        e.as_ref()
+            .is_some_and(|e| e.method == method && e.url == url && e.request_body == body)
+    });
+    let similar = || {
+        exchanges.iter().position(|e| {
+            e.as_ref().is_some_and(|e| {
+                e.method == method && url_path(&e.url) == path && e.operation == operation
+            })
+        })
+    };
+    match exact.or_else(similar) {
+        Some(index) => exchanges[index]
+            .take()
+            .unwrap_or_else(|| not_recorded(method, url)),
+        None => not_recorded(method, url),
+    }
+}
+
+/// A 400 response for a request the session has no answer for, so the
+/// command fails the way it would on a provider error.
+fn not_recorded(method: &str, url: &str) -> Exchange {
+    log::debug!("no recorded response for {} {}", method, url);
+    let message = format!("{} {} was not recorded in this session", method, url);
+    Exchange {
+        method: method.to_string(),
+        url: url.to_string(),
+        operation: String::new(),
+        request_body: String::new(),
+        status: 400,
+        headers: BTreeMap::from([("content-type".to_string(), "application/json".to_string())]),
+        response_body: serde_json::json!({
+            "__type": "NotRecorded",
+            "message": message,
+            "error": { "code": "NotRecorded", "status": "NOT_RECORDED", "message": message },
+        })
+        .to_string(),
+    }
+}
+
+/// Keep `exchange` for the session file. The caller still gets the real
+/// response; only the recorded copy is redacted.
+fn push(exchanges: &Mutex<Vec<Exchange>>, exchange: &Exchange) {
+    let recorded = Exchange {
+        response_body: redact_body(&exchange.response_body),
+        ..exchange.clone()
+    };
+    if let Ok(mut exchanges) = exchanges.lock() {
+        exchanges.push(recorded);
+    }
+}
+
+fn url_path(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|_| url.split('?').next().unwrap_or(url).to_string())
+}
+
+fn kept_headers<'a>(headers: impl Iterator<Item = (&'a str, &'a str)>) -> BTreeMap<String, String> {
+    headers
+        .filter(|(name, _)| !DROPPED_HEADERS.contains(&name.to_lowercase().as_str()))
+        .map(|(name, value)| (name.to_lowercase(), value.to_string()))
+        .collect()
+}
+
+/// The command line to record, with `--record` dropped and secret flag
+/// values redacted.
+fn recorded_args(mut args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut recorded = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--record" {
+            args.next();
+            continue;
+        }
+        if arg.starts_with("--record=") {
+            continue;
+        }
+        if let Some((flag, _)) = arg.split_once('=') {
+            if SECRET_FLAGS.contains(&flag) {
+                recorded.push(format!("{}={}", flag, REDACTED));
+                continue;
+            }
+        }
+        let secret = SECRET_FLAGS.contains(&arg.as_str());
+        recorded.push(arg);
+        if secret && args.next().is_some() {
+            recorded.push(REDACTED.to_string());
+        }
+    }
+    recorded
+}
+
+fn is_secret(name: &str) -> bool {
+    let normalized: String = name
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_lowercase();
+    SECRET_NAMES.contains(&normalized.as_str())
+}
+
+fn redact_url(url: &str) -> String {
+    let Ok(mut parsed) = reqwest::Url::parse(url) else {
+        return url.to_string();
+    };
+    if !parsed.query_pairs().any(|(name, _)| is_secret(&name)) {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if is_secret(&name) {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+/// `body` with the values of secret fields replaced, for JSON, XML and
+/// form-encoded bodies.
+fn redact_body(body: &str) -> String {
+    if let Ok(mut json) = serde_json::from_str::<Value>(body) {
+        redact_json(&mut json);
+        return json.to_string();
+    }
+    if body.trim_start().starts_with('<') {
+        return redact_xml(body);
+    }
+    if body.contains('=') && !body.contains(char::is_whitespace) {
+        return body
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if is_secret(name) => format!("{}={}", name, REDACTED),
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+    }
+    body.to_string()
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                if is_secret(name) && !field.is_object() && !field.is_array() {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Blank the text of secret elements such as `<SessionToken>...</SessionToken>`.
+fn redact_xml(body: &str) -> String {
+    let mut redacted = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(open) = rest.find('<') {
+        let Some(close) = rest[open..].find('>').map(|i| open + i) else {
+            break;
+        };
+        let tag = &rest[open + 1..close];
+        redacted.push_str(&rest[..=close]);
+        rest = &rest[close + 1..];
+
+        let closing = format!("</{}>", tag);
+        if !tag.starts_with('/') && is_secret(tag) {
+            if let Some(end) = rest.find(&closing) {
+                redacted.push_str(REDACTED);
+                rest = &rest[end..];
+            }
+        }
+    }
+    redacted.push_str(rest);
+    redacted
+}
@@ -0,0 +1,221 @@
+use crate::error::AppError;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Tool-wide settings read from `actlog.toml` in the actlog config directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+
+    #[serde(default)]
+    pub sinks: Vec<SinkConfig>,
+
+    #[serde(default)]
+    pub executor: ExecutorSettings,
+
+    #[serde(default)]
+    pub retry: RetrySettings,
+
+    #[serde(default)]
+    pub cache: ResponseCacheSettings,
+
+    #[serde(default)]
+    pub timeouts: TimeoutSettings,
+
+    #[serde(default)]
+    pub locks: LockSettings,
+
+    #[serde(default)]
+    pub credentials: CredentialSettings,
+
+    /// Per-profile guardrails, keyed by profile name
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileSettings>,
+}
+
+/// Limits for commands that fan out across regions and accounts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExecutorSettings {
+    /// Regions or accounts scanned at once
+    pub parallelism: Option<usize>,
+    /// Requests per second per service, e.g. `ec2 = 20`
+    #[serde(default)]
+    pub rate_limits: BTreeMap<String, f64>,
+}
+
+/// Backoff for throttled or transiently failing provider calls.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RetrySettings {
+    /// Attempts per request, including the first
+    pub max_attempts: Option<u32>,
+    /// Upper bound of the first retry delay
+    pub initial_backoff_ms: Option<u64>,
+    /// Longest delay between two attempts
+    pub max_backoff_ms: Option<u64>,
+}
+
+/// Opt-in cache for read-only describe, list and metric responses.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResponseCacheSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long a response is served before it is fetched again
+    pub ttl_seconds: Option<u64>,
+}
+
+/// Bounds on single API calls and whole commands.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TimeoutSettings {
+    /// Longest one cloud API call may take, retries included
+    pub call_seconds: Option<u64>,
+    /// Longest a whole command may run; unbounded when unset
+    pub command_seconds: Option<u64>,
+}
+
+/// Where the resource lock registry lives.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LockSettings {
+    /// Registry shared with other operators, e.g. on a network drive;
+    /// `locks.yaml` in the config directory when unset
+    pub path: Option<String>,
+}
+
+/// Where `authenticate` keeps secrets.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CredentialSettings {
+    #[serde(default)]
+    pub storage: CredentialStorage,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialStorage {
+    /// The OS keychain, falling back to the file when none is available
+    #[default]
+    Keyring,
+    /// Plaintext in credentials.json
+    File,
+}
+
+/// Guardrails for one credentials profile.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProfileSettings {
+    /// Block every call that changes cloud resources
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub provider: String,
+    pub metric: AlertMetric,
+    #[serde(default)]
+    pub operator: AlertOperator,
+    pub threshold: f64,
+    #[serde(default = "default_severity")]
+    pub severity: String,
+    /// Service name for `service_cost` rules
+    pub service: Option<String>,
+    /// Resource type for `resource_count` rules (e.g. "ec2")
+    pub resource_type: Option<String>,
+    /// Sink names to notify; every sink when empty
+    #[serde(default)]
+    pub sinks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    TotalCost,
+    ServiceCost,
+    ResourceCount,
+    IdleCount,
+    IdleCost,
+    ScalingActions,
+    /// Month-end spend projected from the burn rate so far
+    ProjectedCost,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertOperator {
+    #[default]
+    #[serde(rename = ">")]
+    GreaterThan,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+    #[serde(rename = "<")]
+    LessThan,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+    #[serde(rename = "==")]
+    Equal,
+}
+
+impl AlertOperator {
+    pub fn matches(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertOperator::GreaterThan => value > threshold,
+            AlertOperator::GreaterOrEqual => value >= threshold,
+            AlertOperator::LessThan => value < threshold,
+            AlertOperator::LessOrEqual => value <= threshold,
+            AlertOperator::Equal => (value - threshold).abs() < f64::EPSILON,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            AlertOperator::GreaterThan => ">",
+            AlertOperator::GreaterOrEqual => ">=",
+            AlertOperator::LessThan => "<",
+            AlertOperator::LessOrEqual => "<=",
+            AlertOperator::Equal => "==",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinkConfig {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: SinkKind,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkKind {
+    Stdout,
+    Webhook,
+    Slack,
+}
+
+fn default_severity() -> String {
+    "medium".to_string()
+}
+
+pub fn settings_path() -> Result<PathBuf, AppError> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| {
+            AppError::ConfigurationError("Could not determine config directory".to_string())
+        })?
+        .join("actlog");
+
+    Ok(config_dir.join("actlog.toml"))
+}
+
+pub fn load_settings() -> Result<Settings, AppError> {
+    let path = settings_path()?;
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    toml::from_str(&content).map_err(|e| {
+        AppError::ConfigurationError(format!("Invalid settings in {}: {}", path.display(), e))
+    })
+}
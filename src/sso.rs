@@ -0,0 +1,346 @@
+use crate::error::AppError;
+use crate::executor;
+use crate::retry;
+use crate::token_cache;
+use aws_config::{Region, SdkConfig};
+use aws_credential_types::Credentials;
+use aws_sdk_ssooidc::error::ProvideErrorMetadata;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// Grant type of the OAuth device authorization flow (RFC 8628)
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Scope that makes Identity Center issue a refresh token with the access
+/// token, so a session outlives the first hour without another browser login
+const ACCOUNT_ACCESS_SCOPE: &str = "sso:account:access";
+
+/// Extra wait IAM Identity Center asks for with a SlowDownException
+const SLOW_DOWN_SECONDS: u64 = 5;
+
+/// An IAM Identity Center portal and the account role chosen in it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SsoRole {
+    pub start_url: String,
+    /// Region the Identity Center instance lives in
+    pub region: String,
+    pub account_id: String,
+    pub role_name: String,
+}
+
+/// An Identity Center access token with the client registration that
+/// obtained it, cached per start URL.
+#[derive(Serialize, Deserialize)]
+struct SsoToken {
+    start_url: String,
+    region: String,
+    client_id: String,
+    client_secret: String,
+    client_expires_at: DateTime<Utc>,
+    access_token: String,
+    expires_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
+}
+
+/// An AWS account the token can reach.
+pub struct SsoAccount {
+    pub id: String,
+    pub name: String,
+}
+
+/// An access token for `start_url`, from the cache while it is valid,
+/// refreshed when it has expired, or from a new browser login otherwise.
+pub async fn access_token(start_url: &str, region: &str) -> Result<String, AppError> {
+    let config = oidc_config(region).await;
+    let oidc = aws_sdk_ssooidc::Client::new(&config);
+
+    if let Some(token) = cached_token(start_url)?.filter(|t| t.region == region) {
+        if token.expires_at > Utc::now() {
+            return Ok(token.access_token);
+        }
+        if let Some(refreshed) = refresh(&oidc, token).await {
+            save_token(&refreshed)?;
+            return Ok(refreshed.access_token);
+        }
+    }
+
+    let token = device_login(&oidc, start_url, region).await?;
+    save_token(&token)?;
+    Ok(token.access_token)
+}
+
+/// The cached access token for `start_url`, refreshed if needed, without
+/// ever starting a browser login. Used to renew role credentials in the
+/// background of other commands.
+async fn cached_access_token(start_url: &str, region: &str) -> Result<String, AppError> {
+    let expired = || {
+        AppError::AuthenticationError(format!(
+            "IAM Identity Center session for {} expired; run `actlog authenticate aws --sso` again",
+            start_url
+        ))
+    };
+
+    let token = cached_token(start_url)?.ok_or_else(expired)?;
+    if token.expires_at > Utc::now() {
+        return Ok(token.access_token);
+    }
+
+    let config = oidc_config(region).await;
+    let oidc = aws_sdk_ssooidc::Client::new(&config);
+    let refreshed = refresh(&oidc, token).await.ok_or_else(expired)?;
+    save_token(&refreshed)?;
+    Ok(refreshed.access_token)
+}
+
+/// Register actlog as a public OIDC client and walk the operator through the
+/// device authorization: they confirm the code in a browser while this polls
+/// for the token.
+async fn device_login(
+    oidc: &aws_sdk_ssooidc::Client,
+    start_url: &str,
+    region: &str,
+) -> Result<SsoToken, AppError> {
+    executor::throttle("ssooidc").await;
+    let registration = oidc
+        .register_client()
+        .client_name("actlog")
+        .client_type("public")
+        .scopes(ACCOUNT_ACCESS_SCOPE)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("sso-oidc:RegisterClient", e).in_region(region))?;
+    let client_id = registration.client_id().unwrap_or_default().to_string();
+    let client_secret = registration.client_secret().unwrap_or_default().to_string();
+
+    executor::throttle("ssooidc").await;
+    let authorization = oidc
+        .start_device_authorization()
+        .client_id(&client_id)
+        .client_secret(&client_secret)
+        .start_url(start_url)
+        .send()
+        .await
+        .map_err(|e| AppError::aws("sso-oidc:StartDeviceAuthorization", e).in_region(region))?;
+
+    let verification_url = authorization
+        .verification_uri_complete()
+        .or(authorization.verification_uri())
+        .unwrap_or(start_url);
+    println!("🌐 Open this URL in a browser to sign in:");
+    println!("   {}", verification_url.cyan());
+    println!(
+        "   and confirm the code {}",
+        authorization.user_code().unwrap_or_default().bold()
+    );
+
+    let device_code = authorization.device_code().unwrap_or_default();
+    let deadline = Utc::now() + Duration::seconds(authorization.expires_in() as i64);
+    let mut interval = authorization.interval().max(1) as u64;
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+
+        executor::throttle("ssooidc").await;
+        let created = oidc
+            .create_token()
+            .client_id(&client_id)
+            .client_secret(&client_secret)
+            .grant_type(DEVICE_CODE_GRANT)
+            .device_code(device_code)
+            .send()
+            .await;
+        match created {
+            Ok(token) => {
+                return Ok(SsoToken {
+                    start_url: start_url.to_string(),
+                    region: region.to_string(),
+                    client_id,
+                    client_secret,
+                    client_expires_at: timestamp(registration.client_secret_expires_at()),
+                    access_token: token.access_token().unwrap_or_default().to_string(),
+                    expires_at: Utc::now() + Duration::seconds(token.expires_in() as i64),
+                    refresh_token: token.refresh_token().map(str::to_string),
+                });
+            }
+            Err(e) => match e.code() {
+                Some("AuthorizationPendingException") if Utc::now() < deadline => {}
+                Some("SlowDownException") if Utc::now() < deadline => interval += SLOW_DOWN_SECONDS,
+                Some("AuthorizationPendingException" | "SlowDownException") => {
+                    return Err(AppError::AuthenticationError(
+                        "The sign-in code expired before it was confirmed".to_string(),
+                    ))
+                }
+                _ => return Err(AppError::aws("sso-oidc:CreateToken", e).in_region(region)),
+            },
+        }
+    }
+}
+
+/// Trade the refresh token of `token` for a new access token. `None` when
+/// there is no refresh token, the client registration has expired or the
+/// portal refused, leaving a new login as the way out.
+async fn refresh(oidc: &aws_sdk_ssooidc::Client, token: SsoToken) -> Option<SsoToken> {
+    let refresh_token = token.refresh_token.as_deref()?;
+    if token.client_expires_at <= Utc::now() {
+        return None;
+    }
+
+    executor::throttle("ssooidc").await;
+    let refreshed = oidc
+        .create_token()
+        .client_id(&token.client_id)
+        .client_secret(&token.client_secret)
+        .grant_type("refresh_token")
+        .refresh_token(refresh_token)
+        .send()
+        .await
+        .ok()?;
+
+    Some(SsoToken {
+        access_token: refreshed.access_token()?.to_string(),
+        expires_at: Utc::now() + Duration::seconds(refreshed.expires_in() as i64),
+        refresh_token: refreshed
+            .refresh_token()
+            .map(str::to_string)
+            .or(token.refresh_token.clone()),
+        ..token
+    })
+}
+
+/// Every account the signed-in user has access to, by name.
+pub async fn accounts(access_token: &str, region: &str) -> Result<Vec<SsoAccount>, AppError> {
+    let client = aws_sdk_sso::Client::new(&oidc_config(region).await);
+    let mut accounts = Vec::new();
+    let mut next_token = None;
+    loop {
+        executor::throttle("sso").await;
+        let page = client
+            .list_accounts()
+            .access_token(access_token)
+            .set_next_token(next_token)
+            .send()
+            .await
+            .map_err(|e| AppError::aws("sso:ListAccounts", e).in_region(region))?;
+        accounts.extend(page.account_list().iter().map(|account| SsoAccount {
+            id: account.account_id().unwrap_or_default().to_string(),
+            name: account.account_name().unwrap_or_default().to_string(),
+        }));
+        next_token = page.next_token().map(str::to_string);
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(accounts)
+}
+
+/// Roles the signed-in user may assume in `account_id`.
+pub async fn roles(
+    access_token: &str,
+    region: &str,
+    account_id: &str,
+) -> Result<Vec<String>, AppError> {
+    let client = aws_sdk_sso::Client::new(&oidc_config(region).await);
+    let mut roles = Vec::new();
+    let mut next_token = None;
+    loop {
+        executor::throttle("sso").await;
+        let page = client
+            .list_account_roles()
+            .access_token(access_token)
+            .account_id(account_id)
+            .set_next_token(next_token)
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::aws("sso:ListAccountRoles", e)
+                    .for_resource(account_id)
+                    .in_region(region)
+            })?;
+        roles.extend(
+            page.role_list()
+                .iter()
+                .filter_map(|role| role.role_name().map(str::to_string)),
+        );
+        next_token = page.next_token().map(str::to_string);
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    roles.sort();
+    Ok(roles)
+}
+
+/// Temporary credentials for `role` with `access_token`.
+pub async fn role_credentials(access_token: &str, role: &SsoRole) -> Result<Credentials, AppError> {
+    let client = aws_sdk_sso::Client::new(&oidc_config(&role.region).await);
+    executor::throttle("sso").await;
+    let output = client
+        .get_role_credentials()
+        .access_token(access_token)
+        .account_id(&role.account_id)
+        .role_name(&role.role_name)
+        .send()
+        .await
+        .map_err(|e| {
+            AppError::aws("sso:GetRoleCredentials", e)
+                .for_resource(&format!("{}/{}", role.account_id, role.role_name))
+                .in_region(&role.region)
+        })?;
+
+    let credentials = output.role_credentials().ok_or_else(|| {
+        AppError::AuthenticationError(format!(
+            "IAM Identity Center returned no credentials for {}",
+            role.role_name
+        ))
+    })?;
+    let expires_at = Utc
+        .timestamp_millis_opt(credentials.expiration())
+        .single()
+        .map(SystemTime::from);
+    Ok(Credentials::new(
+        credentials.access_key_id().unwrap_or_default(),
+        credentials.secret_access_key().unwrap_or_default(),
+        credentials.session_token().map(str::to_string),
+        expires_at,
+        "actlog-sso",
+    ))
+}
+
+/// New credentials for `role` from the cached Identity Center session.
+pub async fn renew(role: &SsoRole) -> Result<Credentials, AppError> {
+    let access_token = cached_access_token(&role.start_url, &role.region).await?;
+    role_credentials(&access_token, role).await
+}
+
+/// Config for the Identity Center APIs, which take the access token rather
+/// than signed requests.
+async fn oidc_config(region: &str) -> SdkConfig {
+    retry::aws_loader()
+        .region(Region::new(region.to_string()))
+        .no_credentials()
+        .load()
+        .await
+}
+
+fn timestamp(seconds: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(seconds, 0)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
+
+fn cache_key(start_url: &str) -> String {
+    format!("sso_{}", start_url)
+}
+
+fn cached_token(start_url: &str) -> Result<Option<SsoToken>, AppError> {
+    token_cache::load(&cache_key(start_url))
+}
+
+fn save_token(token: &SsoToken) -> Result<(), AppError> {
+    token_cache::store(&cache_key(&token.start_url), token)
+}
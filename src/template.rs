@@ -0,0 +1,24 @@
+use crate::error::AppError;
+use handlebars::{handlebars_helper, Handlebars};
+use serde::Serialize;
+use std::fs;
+
+// `{{money total_cost}}` prints 1234.5 as 1234.50
+handlebars_helper!(money: |value: f64| format!("{:.2}", value));
+
+/// Render the Handlebars template at `path` with `data` as its context, so
+/// teams can lay out a report however their stakeholders expect. The
+/// context is the same structure `--format json` prints.
+pub fn render<T: Serialize>(path: &str, data: &T) -> Result<String, AppError> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| AppError::TemplateError(format!("Could not read {}: {}", path, e)))?;
+
+    let mut handlebars = Handlebars::new();
+    handlebars.register_helper("money", Box::new(money));
+    // Reports are plain text as often as HTML
+    handlebars.register_escape_fn(handlebars::no_escape);
+
+    handlebars
+        .render_template(&source, data)
+        .map_err(|e| AppError::TemplateError(format!("{}: {}", path, e)))
+}
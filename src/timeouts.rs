@@ -0,0 +1,67 @@
+use crate::executor;
+use crate::settings::TimeoutSettings;
+use aws_config::timeout::TimeoutConfig;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Longest a single API call may take, retries included, when nothing is configured
+const DEFAULT_CALL_TIMEOUT: Duration = Duration::from_secs(60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Timeouts {
+    call: Duration,
+    command: Option<Duration>,
+}
+
+static TIMEOUTS: OnceLock<Timeouts> = OnceLock::new();
+
+/// Set the call and command timeouts from `actlog.toml`; `command_seconds`
+/// (from `--timeout`) overrides the configured command timeout.
+pub fn configure(command_seconds: Option<u64>, settings: &TimeoutSettings) {
+    let _ = TIMEOUTS.set(Timeouts {
+        call: settings
+            .call_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_CALL_TIMEOUT),
+        command: command_seconds
+            .or(settings.command_seconds)
+            .filter(|s| *s > 0)
+            .map(Duration::from_secs),
+    });
+}
+
+pub fn call_timeout() -> Duration {
+    TIMEOUTS
+        .get()
+        .map(|t| t.call)
+        .unwrap_or(DEFAULT_CALL_TIMEOUT)
+}
+
+/// Bound on the whole command, if any.
+pub fn command_timeout() -> Option<Duration> {
+    TIMEOUTS.get().and_then(|t| t.command)
+}
+
+/// SDK timeouts bounding each AWS call.
+pub fn aws_timeout_config() -> TimeoutConfig {
+    TimeoutConfig::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .operation_timeout(call_timeout())
+        .build()
+}
+
+/// Explain a command timeout: what was still running and the last service
+/// called, so the stalled provider, region or call can be found.
+pub fn stalled_message(limit: Duration) -> String {
+    let mut message = format!("command did not finish within {}s", limit.as_secs());
+
+    let pending = executor::in_flight();
+    if !pending.is_empty() {
+        message.push_str(&format!("; still waiting on {}", pending.join(", ")));
+    }
+    if let Some(service) = executor::last_service() {
+        message.push_str(&format!("; last request went to {}", service));
+    }
+
+    message
+}
@@ -0,0 +1,69 @@
+use crate::error::AppError;
+use crate::secrets;
+use crate::settings::{self, CredentialStorage};
+use colored::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Where tokens go when the keyring is off or unusable, next to actlog.toml
+const CACHE_FILE: &str = "token_cache.json";
+
+/// The token cached under `key`, if any.
+pub fn load<T: DeserializeOwned>(key: &str) -> Result<Option<T>, AppError> {
+    if let Ok(Some(stored)) = secrets::load(key) {
+        return Ok(Some(serde_json::from_str(&stored)?));
+    }
+    match load_file()?.remove(key) {
+        Some(value) => Ok(Some(serde_json::from_value(value)?)),
+        None => Ok(None),
+    }
+}
+
+/// Cache `token` under `key` where `credentials.storage` says, falling back
+/// to token_cache.json without a usable keyring.
+pub fn store<T: Serialize>(key: &str, token: &T) -> Result<(), AppError> {
+    let mut cache = load_file()?;
+
+    if settings::load_settings()?.credentials.storage == CredentialStorage::Keyring {
+        match secrets::store(key, &serde_json::to_string(token)?) {
+            Ok(()) => {
+                // Don't leave an older copy behind in the file
+                if cache.remove(key).is_some() {
+                    save_file(&cache)?;
+                }
+                return Ok(());
+            }
+            Err(e) => eprintln!(
+                "   ⚠️  {}; caching the token in {} instead",
+                e.to_string().yellow(),
+                CACHE_FILE
+            ),
+        }
+    } else {
+        let _ = secrets::delete(key);
+    }
+
+    cache.insert(key.to_string(), serde_json::to_value(token)?);
+    save_file(&cache)
+}
+
+fn path() -> Result<PathBuf, AppError> {
+    Ok(settings::settings_path()?.with_file_name(CACHE_FILE))
+}
+
+fn load_file() -> Result<HashMap<String, Value>, AppError> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_file(cache: &HashMap<String, Value>) -> Result<(), AppError> {
+    fs::write(path()?, serde_json::to_string_pretty(cache)?)?;
+    Ok(())
+}